@@ -17,16 +17,19 @@ use crate::{
         report_writer::{ReportWriteBatcher, WritableReport},
     },
     cache::{
-        HpkeKeypairCache, PeerAggregatorCache, TASK_AGGREGATOR_CACHE_DEFAULT_CAPACITY,
-        TASK_AGGREGATOR_CACHE_DEFAULT_TTL, TaskAggregatorCache,
+        HpkeKeypairCache, OhttpGatewayKeyCache, PeerAggregatorCache,
+        TASK_AGGREGATOR_CACHE_DEFAULT_CAPACITY, TASK_AGGREGATOR_CACHE_DEFAULT_TTL,
+        TaskAggregatorCache,
     },
     config::TaskprovConfig,
     diagnostic::AggregationJobInitForbiddenMutationEvent,
     metrics::{
-        aggregate_step_failure_counter, aggregated_report_share_dimension_histogram,
-        early_report_clock_skew_histogram, past_report_clock_skew_histogram,
+        TaskMetricLabels, aggregate_step_failure_counter,
+        aggregated_report_share_dimension_histogram, early_report_clock_skew_histogram,
+        past_report_clock_skew_histogram, report_aggregation_failure_counter,
         report_aggregation_success_counter,
     },
+    trace::trace_context_headers,
 };
 use aggregation_job_continue::compute_helper_aggregate_continue;
 use aws_lc_rs::{
@@ -54,7 +57,7 @@ use janus_aggregator_core::{
             TaskAggregationCounter,
         },
     },
-    task::{self, AggregationMode, AggregatorTask, BatchMode},
+    task::{self, AggregationMode, AggregatorTask, BatchMode, TaskState},
     taskprov::PeerAggregator,
 };
 #[cfg(feature = "fpvec_bounded_l2")]
@@ -124,6 +127,8 @@ mod error;
 pub mod garbage_collector;
 pub mod http_handlers;
 pub mod key_rotator;
+pub mod ohttp_gateway;
+pub mod partition_manager;
 pub mod problem_details;
 mod queue;
 pub mod report_writer;
@@ -151,6 +156,9 @@ pub struct Aggregator<C: Clock> {
     /// Cache of HPKE keypairs and configs.
     hpke_keypairs: Arc<HpkeKeypairCache>,
 
+    /// Cache of OHTTP gateway keys, if the OHTTP gateway is enabled.
+    ohttp_gateway_keys: Option<Arc<OhttpGatewayKeyCache>>,
+
     /// Cache of taskprov peer aggregators.
     peer_aggregators: PeerAggregatorCache,
 }
@@ -168,12 +176,16 @@ pub struct AggregatorMetrics {
     /// Counters tracking the number of failures to step client reports through the aggregation
     /// process.
     aggregate_step_failure_counter: Counter<u64>,
+    /// Counter tracking the number of failed report aggregations, by report error.
+    report_aggregation_failure_counter: Counter<u64>,
     /// Histogram tracking the VDAF type and dimension of successfully-aggregated reports.
     aggregated_report_share_dimension_histogram: Histogram<u64>,
     /// Histogram tracking the clock skew of early reports.
     early_report_clock_skew_histogram: Histogram<u64>,
     /// Histogram tracking the clock skew of reports with timestamps in the past.
     past_report_clock_skew_histogram: Histogram<u64>,
+    /// Determines which tasks' metrics are labeled with their task ID.
+    task_metric_labels: TaskMetricLabels,
 }
 
 impl AggregatorMetrics {
@@ -181,9 +193,11 @@ impl AggregatorMetrics {
         AggregationJobWriterMetrics {
             report_aggregation_success_counter: self.report_aggregation_success_counter.clone(),
             aggregate_step_failure_counter: self.aggregate_step_failure_counter.clone(),
+            report_aggregation_failure_counter: self.report_aggregation_failure_counter.clone(),
             aggregated_report_share_dimension_histogram: self
                 .aggregated_report_share_dimension_histogram
                 .clone(),
+            task_metric_labels: self.task_metric_labels.clone(),
         }
     }
 }
@@ -237,6 +251,17 @@ pub struct Config {
     ///
     /// This option is not stable, and not subject to Janus' typical API/config stability promises.
     pub log_forbidden_mutations: Option<PathBuf>,
+
+    /// If set, the aggregator serves an OHTTP gateway for its upload endpoint, and refreshes the
+    /// gateway keys cache at this interval. If unset, the gateway is not served.
+    pub ohttp_gateway_keys_refresh_interval: Option<StdDuration>,
+
+    /// Determines which tasks' metrics are labeled with their task ID.
+    pub task_metric_labels: TaskMetricLabels,
+
+    /// If set, W3C trace context is extracted from requests by clients and collectors, as well as
+    /// from requests by the peer aggregator.
+    pub honor_untrusted_trace_context: bool,
 }
 
 impl Default for Config {
@@ -253,6 +278,9 @@ impl Default for Config {
             task_cache_ttl: TASK_AGGREGATOR_CACHE_DEFAULT_TTL,
             task_cache_capacity: TASK_AGGREGATOR_CACHE_DEFAULT_CAPACITY,
             log_forbidden_mutations: None,
+            ohttp_gateway_keys_refresh_interval: None,
+            task_metric_labels: TaskMetricLabels::default(),
+            honor_untrusted_trace_context: false,
         }
     }
 }
@@ -281,6 +309,8 @@ impl<C: Clock> Aggregator<C> {
                 cfg.task_counter_shard_count,
                 cfg.max_upload_batch_size,
                 cfg.max_upload_batch_write_delay,
+                meter,
+                cfg.task_metric_labels.clone(),
             ),
             // If we're in taskprov mode, we can never cache None entries for tasks, since
             // aggregators could insert tasks at any time and expect them to be available across all
@@ -310,16 +340,25 @@ impl<C: Clock> Aggregator<C> {
 
         let report_aggregation_success_counter = report_aggregation_success_counter(meter);
         let aggregate_step_failure_counter = aggregate_step_failure_counter(meter);
+        let report_aggregation_failure_counter = report_aggregation_failure_counter(meter);
         let aggregated_report_share_dimension_histogram =
             aggregated_report_share_dimension_histogram(meter);
         let early_report_clock_skew_histogram = early_report_clock_skew_histogram(meter);
         let past_report_clock_skew_histogram = past_report_clock_skew_histogram(meter);
+        let task_metric_labels = cfg.task_metric_labels.clone();
 
         let hpke_keypairs = Arc::new(
             HpkeKeypairCache::new(Arc::clone(&datastore), cfg.hpke_configs_refresh_interval)
                 .await?,
         );
 
+        let ohttp_gateway_keys = match cfg.ohttp_gateway_keys_refresh_interval {
+            Some(refresh_interval) => Some(Arc::new(
+                OhttpGatewayKeyCache::new(Arc::clone(&datastore), refresh_interval).await?,
+            )),
+            None => None,
+        };
+
         let peer_aggregators = PeerAggregatorCache::new(&datastore).await?;
 
         Ok(Self {
@@ -332,11 +371,14 @@ impl<C: Clock> Aggregator<C> {
                 upload_decode_failure_counter,
                 report_aggregation_success_counter,
                 aggregate_step_failure_counter,
+                report_aggregation_failure_counter,
                 aggregated_report_share_dimension_histogram,
                 early_report_clock_skew_histogram,
                 past_report_clock_skew_histogram,
+                task_metric_labels,
             },
             hpke_keypairs,
+            ohttp_gateway_keys,
             peer_aggregators,
         })
     }
@@ -433,6 +475,9 @@ impl<C: Clock> Aggregator<C> {
                 return Err(Error::UnrecognizedTask(*task_id));
             }
         };
+        if task_aggregator.task.state() == &TaskState::Archived {
+            return Err(Error::TaskArchived(*task_id));
+        }
 
         task_aggregator
             .handle_aggregate_init(
@@ -600,6 +645,9 @@ impl<C: Clock> Aggregator<C> {
         {
             return Err(Error::UnauthorizedRequest(*task_id));
         }
+        if task_aggregator.task.state() == &TaskState::Archived {
+            return Err(Error::TaskArchived(*task_id));
+        }
 
         task_aggregator
             .handle_create_collection_job(&self.datastore, collection_job_id, req_bytes)
@@ -712,6 +760,10 @@ impl<C: Clock> Aggregator<C> {
                 .ok_or_else(|| Error::Internal("task is missing collector_hpke_config".into()))?
         };
 
+        if task_aggregator.task.state() == &TaskState::Archived {
+            return Err(Error::TaskArchived(*task_id));
+        }
+
         task_aggregator
             .handle_aggregate_share(
                 &self.datastore,
@@ -853,6 +905,16 @@ impl<C: Clock> Aggregator<C> {
         ))
     }
 
+    /// Applies the tunable values of a reloaded configuration: the upload batch size and write
+    /// delay, and the task cache's capacity and TTL.
+    fn apply_tunables(&self, cfg: &Config) {
+        self.task_aggregators
+            .report_writer()
+            .reconfigure(cfg.max_upload_batch_size, cfg.max_upload_batch_write_delay);
+        self.task_aggregators
+            .reconfigure(cfg.task_cache_capacity, cfg.task_cache_ttl);
+    }
+
     #[cfg(feature = "test-util")]
     pub async fn refresh_caches(&self) -> Result<(), Error> {
         self.hpke_keypairs.refresh(&self.datastore).await
@@ -880,14 +942,23 @@ impl<C: Clock> TaskAggregator<C> {
         report_writer: Arc<ReportWriteBatcher<C>>,
     ) -> Result<Self, Error> {
         let vdaf_ops = match task.vdaf() {
-            VdafInstance::Prio3Count => {
+            VdafInstance::Prio3Count { dp_strategy } => {
                 let vdaf = Prio3::new_count(2)?;
-                VdafOps::Prio3Count(Arc::new(vdaf))
+                VdafOps::Prio3Count(
+                    Arc::new(vdaf),
+                    vdaf_ops_strategies::Prio3Count::from_vdaf_dp_strategy(dp_strategy.clone()),
+                )
             }
 
-            VdafInstance::Prio3Sum { max_measurement } => {
+            VdafInstance::Prio3Sum {
+                max_measurement,
+                dp_strategy,
+            } => {
                 let vdaf = Prio3::new_sum(2, *max_measurement)?;
-                VdafOps::Prio3Sum(Arc::new(vdaf))
+                VdafOps::Prio3Sum(
+                    Arc::new(vdaf),
+                    vdaf_ops_strategies::Prio3Sum::from_vdaf_dp_strategy(dp_strategy.clone()),
+                )
             }
 
             VdafInstance::Prio3SumVec {
@@ -1146,15 +1217,60 @@ impl<C: Clock> TaskAggregator<C> {
 mod vdaf_ops_strategies {
     use std::sync::Arc;
 
-    use janus_core::vdaf::vdaf_dp_strategies;
-    use prio::dp::distributions::PureDpDiscreteLaplace;
-    #[cfg(feature = "fpvec_bounded_l2")]
-    use prio::dp::distributions::ZCdpDiscreteGaussian;
+    use janus_core::{dp::JanusStrategy, vdaf::vdaf_dp_strategies};
+    use prio::dp::distributions::{PureDpDiscreteLaplace, ZCdpDiscreteGaussian};
+
+    #[derive(Debug)]
+    pub enum Prio3Count {
+        NoDifferentialPrivacy,
+        PureDpDiscreteLaplace(Arc<JanusStrategy<PureDpDiscreteLaplace>>),
+        ZCdpDiscreteGaussian(Arc<JanusStrategy<ZCdpDiscreteGaussian>>),
+    }
+
+    impl Prio3Count {
+        pub fn from_vdaf_dp_strategy(dp_strategy: vdaf_dp_strategies::Prio3Count) -> Self {
+            match dp_strategy {
+                vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy => {
+                    Prio3Count::NoDifferentialPrivacy
+                }
+                vdaf_dp_strategies::Prio3Count::PureDpDiscreteLaplace(s) => {
+                    Prio3Count::PureDpDiscreteLaplace(Arc::new(JanusStrategy(s.into_strategy())))
+                }
+                vdaf_dp_strategies::Prio3Count::ZCdpDiscreteGaussian(s) => {
+                    Prio3Count::ZCdpDiscreteGaussian(Arc::new(JanusStrategy(s.into_strategy())))
+                }
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    pub enum Prio3Sum {
+        NoDifferentialPrivacy,
+        PureDpDiscreteLaplace(Arc<JanusStrategy<PureDpDiscreteLaplace>>),
+        ZCdpDiscreteGaussian(Arc<JanusStrategy<ZCdpDiscreteGaussian>>),
+    }
+
+    impl Prio3Sum {
+        pub fn from_vdaf_dp_strategy(dp_strategy: vdaf_dp_strategies::Prio3Sum) -> Self {
+            match dp_strategy {
+                vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy => {
+                    Prio3Sum::NoDifferentialPrivacy
+                }
+                vdaf_dp_strategies::Prio3Sum::PureDpDiscreteLaplace(s) => {
+                    Prio3Sum::PureDpDiscreteLaplace(Arc::new(JanusStrategy(s.into_strategy())))
+                }
+                vdaf_dp_strategies::Prio3Sum::ZCdpDiscreteGaussian(s) => {
+                    Prio3Sum::ZCdpDiscreteGaussian(Arc::new(JanusStrategy(s.into_strategy())))
+                }
+            }
+        }
+    }
 
     #[derive(Debug)]
     pub enum Prio3Histogram {
         NoDifferentialPrivacy,
         PureDpDiscreteLaplace(Arc<PureDpDiscreteLaplace>),
+        ZCdpDiscreteGaussian(Arc<JanusStrategy<ZCdpDiscreteGaussian>>),
     }
 
     impl Prio3Histogram {
@@ -1164,7 +1280,10 @@ mod vdaf_ops_strategies {
                     Prio3Histogram::NoDifferentialPrivacy
                 }
                 vdaf_dp_strategies::Prio3Histogram::PureDpDiscreteLaplace(s) => {
-                    Prio3Histogram::PureDpDiscreteLaplace(Arc::new(s))
+                    Prio3Histogram::PureDpDiscreteLaplace(Arc::new(s.into_strategy()))
+                }
+                vdaf_dp_strategies::Prio3Histogram::ZCdpDiscreteGaussian(s) => {
+                    Prio3Histogram::ZCdpDiscreteGaussian(Arc::new(JanusStrategy(s.into_strategy())))
                 }
             }
         }
@@ -1174,6 +1293,7 @@ mod vdaf_ops_strategies {
     pub enum Prio3SumVec {
         NoDifferentialPrivacy,
         PureDpDiscreteLaplace(Arc<PureDpDiscreteLaplace>),
+        ZCdpDiscreteGaussian(Arc<JanusStrategy<ZCdpDiscreteGaussian>>),
     }
 
     impl Prio3SumVec {
@@ -1183,7 +1303,10 @@ mod vdaf_ops_strategies {
                     Prio3SumVec::NoDifferentialPrivacy
                 }
                 vdaf_dp_strategies::Prio3SumVec::PureDpDiscreteLaplace(s) => {
-                    Prio3SumVec::PureDpDiscreteLaplace(Arc::new(s))
+                    Prio3SumVec::PureDpDiscreteLaplace(Arc::new(s.into_strategy()))
+                }
+                vdaf_dp_strategies::Prio3SumVec::ZCdpDiscreteGaussian(s) => {
+                    Prio3SumVec::ZCdpDiscreteGaussian(Arc::new(JanusStrategy(s.into_strategy())))
                 }
             }
         }
@@ -1206,7 +1329,9 @@ mod vdaf_ops_strategies {
                     Prio3FixedPointBoundedL2VecSum::NoDifferentialPrivacy
                 }
                 vdaf_dp_strategies::Prio3FixedPointBoundedL2VecSum::ZCdpDiscreteGaussian(s) => {
-                    Prio3FixedPointBoundedL2VecSum::ZCdpDiscreteGaussian(Arc::new(s))
+                    Prio3FixedPointBoundedL2VecSum::ZCdpDiscreteGaussian(Arc::new(
+                        s.into_strategy(),
+                    ))
                 }
             }
         }
@@ -1217,8 +1342,8 @@ mod vdaf_ops_strategies {
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug)]
 enum VdafOps {
-    Prio3Count(Arc<Prio3Count>),
-    Prio3Sum(Arc<Prio3Sum>),
+    Prio3Count(Arc<Prio3Count>, vdaf_ops_strategies::Prio3Count),
+    Prio3Sum(Arc<Prio3Sum>, vdaf_ops_strategies::Prio3Sum),
     Prio3SumVec(Arc<Prio3SumVec>, vdaf_ops_strategies::Prio3SumVec),
     Prio3SumVecField64MultiproofHmacSha256Aes128(
         Arc<Prio3SumVecField64MultiproofHmacSha256Aes128<ParallelSum<Field64, Mul<Field64>>>>,
@@ -1247,24 +1372,60 @@ enum VdafOps {
 macro_rules! vdaf_ops_dispatch {
     ($vdaf_ops:expr, ($vdaf:pat_param, $Vdaf:ident, $VERIFY_KEY_LENGTH:ident, $dp_strategy:ident, $DpStrategy:ident) => $body:tt) => {
         match $vdaf_ops {
-            crate::aggregator::VdafOps::Prio3Count(vdaf) => {
+            crate::aggregator::VdafOps::Prio3Count(vdaf, _dp_strategy) => {
                 let $vdaf = vdaf;
                 type $Vdaf = ::prio::vdaf::prio3::Prio3Count;
                 const $VERIFY_KEY_LENGTH: usize = ::janus_core::vdaf::VERIFY_KEY_LENGTH_PRIO3;
-                type $DpStrategy = janus_core::dp::NoDifferentialPrivacy;
-                let $dp_strategy = &Arc::new(janus_core::dp::NoDifferentialPrivacy);
-                let body = $body;
-                body
+                match _dp_strategy {
+                    vdaf_ops_strategies::Prio3Count::NoDifferentialPrivacy => {
+                        type $DpStrategy = janus_core::dp::NoDifferentialPrivacy;
+                        let $dp_strategy = &Arc::new(janus_core::dp::NoDifferentialPrivacy);
+                        let body = $body;
+                        body
+                    }
+                    vdaf_ops_strategies::Prio3Count::PureDpDiscreteLaplace(_strategy) => {
+                        type $DpStrategy =
+                            janus_core::dp::JanusStrategy<::prio::dp::distributions::PureDpDiscreteLaplace>;
+                        let $dp_strategy = &_strategy;
+                        let body = $body;
+                        body
+                    }
+                    vdaf_ops_strategies::Prio3Count::ZCdpDiscreteGaussian(_strategy) => {
+                        type $DpStrategy =
+                            janus_core::dp::JanusStrategy<::prio::dp::distributions::ZCdpDiscreteGaussian>;
+                        let $dp_strategy = &_strategy;
+                        let body = $body;
+                        body
+                    }
+                }
             }
 
-            crate::aggregator::VdafOps::Prio3Sum(vdaf) => {
+            crate::aggregator::VdafOps::Prio3Sum(vdaf, _dp_strategy) => {
                 let $vdaf = vdaf;
                 type $Vdaf = ::prio::vdaf::prio3::Prio3Sum;
                 const $VERIFY_KEY_LENGTH: usize = ::janus_core::vdaf::VERIFY_KEY_LENGTH_PRIO3;
-                type $DpStrategy = janus_core::dp::NoDifferentialPrivacy;
-                let $dp_strategy = &Arc::new(janus_core::dp::NoDifferentialPrivacy);
-                let body = $body;
-                body
+                match _dp_strategy {
+                    vdaf_ops_strategies::Prio3Sum::NoDifferentialPrivacy => {
+                        type $DpStrategy = janus_core::dp::NoDifferentialPrivacy;
+                        let $dp_strategy = &Arc::new(janus_core::dp::NoDifferentialPrivacy);
+                        let body = $body;
+                        body
+                    }
+                    vdaf_ops_strategies::Prio3Sum::PureDpDiscreteLaplace(_strategy) => {
+                        type $DpStrategy =
+                            janus_core::dp::JanusStrategy<::prio::dp::distributions::PureDpDiscreteLaplace>;
+                        let $dp_strategy = &_strategy;
+                        let body = $body;
+                        body
+                    }
+                    vdaf_ops_strategies::Prio3Sum::ZCdpDiscreteGaussian(_strategy) => {
+                        type $DpStrategy =
+                            janus_core::dp::JanusStrategy<::prio::dp::distributions::ZCdpDiscreteGaussian>;
+                        let $dp_strategy = &_strategy;
+                        let body = $body;
+                        body
+                    }
+                }
             }
 
             crate::aggregator::VdafOps::Prio3SumVec(vdaf, _dp_strategy) => {
@@ -1284,6 +1445,13 @@ macro_rules! vdaf_ops_dispatch {
                         let body = $body;
                         body
                     }
+                    vdaf_ops_strategies::Prio3SumVec::ZCdpDiscreteGaussian(_strategy) => {
+                        type $DpStrategy =
+                            janus_core::dp::JanusStrategy<::prio::dp::distributions::ZCdpDiscreteGaussian>;
+                        let $dp_strategy = &_strategy;
+                        let body = $body;
+                        body
+                    }
                 }
             }
 
@@ -1309,6 +1477,13 @@ macro_rules! vdaf_ops_dispatch {
                         let body = $body;
                         body
                     }
+                    vdaf_ops_strategies::Prio3SumVec::ZCdpDiscreteGaussian(_strategy) => {
+                        type $DpStrategy =
+                            janus_core::dp::JanusStrategy<::prio::dp::distributions::ZCdpDiscreteGaussian>;
+                        let $dp_strategy = &_strategy;
+                        let body = $body;
+                        body
+                    }
                 }
             }
 
@@ -1329,6 +1504,13 @@ macro_rules! vdaf_ops_dispatch {
                         let body = $body;
                         body
                     }
+                    vdaf_ops_strategies::Prio3Histogram::ZCdpDiscreteGaussian(_strategy) => {
+                        type $DpStrategy =
+                            janus_core::dp::JanusStrategy<::prio::dp::distributions::ZCdpDiscreteGaussian>;
+                        let $dp_strategy = &_strategy;
+                        let body = $body;
+                        body
+                    }
                 }
             }
 
@@ -1667,6 +1849,11 @@ impl VdafOps {
             return Err(reject_report(ReportRejectionReason::TooEarly).await?);
         }
 
+        // Reject reports for tasks that are paused, draining or archived.
+        if !task.state().is_active() {
+            return Err(reject_report(ReportRejectionReason::TaskNotAcceptingReports).await?);
+        }
+
         // Reject reports before a task has started.
         if let Some(task_start) = task.task_start() {
             if report.metadata().time().is_before(task_start) {
@@ -1709,7 +1896,9 @@ impl VdafOps {
                         ?err,
                         "public share decoding failed",
                     );
-                    metrics.upload_decode_failure_counter.add(1, &[]);
+                    metrics
+                        .upload_decode_failure_counter
+                        .add(1, &metrics.task_metric_labels.attributes(task.id(), []));
                     return Err(reject_report(ReportRejectionReason::DecodeFailure).await?);
                 }
             };
@@ -1751,7 +1940,9 @@ impl VdafOps {
                     ?error,
                     "Report decryption failed",
                 );
-                metrics.upload_decrypt_failure_counter.add(1, &[]);
+                metrics
+                    .upload_decrypt_failure_counter
+                    .add(1, &metrics.task_metric_labels.attributes(task.id(), []));
                 return Err(reject_report(ReportRejectionReason::DecryptFailure).await?);
             }
         };
@@ -1778,7 +1969,9 @@ impl VdafOps {
                     ?err,
                     "Leader input share decoding failed",
                 );
-                metrics.upload_decode_failure_counter.add(1, &[]);
+                metrics
+                    .upload_decode_failure_counter
+                    .add(1, &metrics.task_metric_labels.attributes(task.id(), []));
                 return Err(reject_report(ReportRejectionReason::DecodeFailure).await?);
             }
         };
@@ -2212,6 +2405,7 @@ impl VdafOps {
                     // Write report shares, and ensure this isn't a repeated report aggregation.
                     let report_aggregations = try_join_all(report_aggregations.iter().map(|ra| {
                         let task = Arc::clone(&task);
+                        let aggregation_job = Arc::clone(&aggregation_job);
 
                         async move {
                             let mut report_aggregation = Cow::Borrowed(ra);
@@ -2224,12 +2418,27 @@ impl VdafOps {
                                 .await
                             {
                                 Ok(()) => (),
+                                // A report may be aggregated once per aggregation parameter, so
+                                // having seen it before only makes it a replay if it was already
+                                // aggregated with this aggregation job's parameter.
                                 Err(datastore::Error::MutationTargetAlreadyExists) => {
-                                    report_aggregation = Cow::Owned(
-                                        report_aggregation
-                                            .into_owned()
-                                            .with_failure(ReportError::ReportReplayed),
-                                    )
+                                    if tx
+                                        .check_report_aggregated_with_aggregation_param::<
+                                            SEED_SIZE,
+                                            A,
+                                        >(
+                                            task.id(),
+                                            ra.report_aggregation().report_id(),
+                                            aggregation_job.aggregation_parameter(),
+                                        )
+                                        .await?
+                                    {
+                                        report_aggregation = Cow::Owned(
+                                            report_aggregation
+                                                .into_owned()
+                                                .with_failure(ReportError::ReportReplayed),
+                                        )
+                                    }
                                 }
                                 Err(err) => return Err(err),
                             };
@@ -2878,6 +3087,23 @@ impl VdafOps {
                         ));
                     }
 
+                    // Charge the collection against the task's differential privacy budget. This
+                    // happens only when the collection job is created, so that retried requests
+                    // for an existing job are not charged again.
+                    if !tx
+                        .spend_dp_budget::<SEED_SIZE, B, A>(
+                            task.id(),
+                            &collection_identifier,
+                            aggregation_param.as_ref(),
+                            task.vdaf().privacy_loss(),
+                        )
+                        .await?
+                    {
+                        return Err(datastore::Error::User(
+                            Error::DpBudgetExhausted(*task.id()).into(),
+                        ));
+                    }
+
                     tx.put_collection_job(&CollectionJob::<SEED_SIZE, B, A>::new(
                         *task.id(),
                         collection_job_id,
@@ -3287,6 +3513,22 @@ impl VdafOps {
                         )
                     )?;
 
+                    // Charge the aggregate share against the task's differential privacy budget.
+                    // Cached aggregate share jobs were served above, so each is charged once.
+                    if !tx
+                        .spend_dp_budget::<SEED_SIZE, B, A>(
+                            task.id(),
+                            aggregate_share_req.batch_selector().batch_identifier(),
+                            &aggregation_param,
+                            task.vdaf().privacy_loss(),
+                        )
+                        .await?
+                    {
+                        return Err(datastore::Error::User(
+                            Error::DpBudgetExhausted(*task.id()).into(),
+                        ));
+                    }
+
                     // Empty batch aggregations cannot contribute to the aggregate share so don't
                     // bother including them.
                     let mut aggregate_share = AggregateShareComputer::new(&task)
@@ -3517,6 +3759,8 @@ async fn send_request_to_helper(
         method_str,
     );
 
+    let trace_context_headers = trace_context_headers();
+
     let result = retry_http_request_notify(
         backoff.build(),
         |_, _| timer.finish_attempt("error"),
@@ -3524,7 +3768,8 @@ async fn send_request_to_helper(
             timer.start_attempt();
             let mut request = http_client
                 .request(method.clone(), url.clone())
-                .header(auth_header, auth_value.as_str());
+                .header(auth_header, auth_value.as_str())
+                .headers(trace_context_headers.clone());
             if let Some(request_body) = request_body.clone() {
                 request = request
                     .header(CONTENT_TYPE, request_body.content_type)
@@ -333,7 +333,7 @@ mod tests {
     use janus_core::{
         test_util::{install_test_trace_subscriber, runtime::TestRuntime},
         time::MockClock,
-        vdaf::VdafInstance,
+        vdaf::{VdafInstance, vdaf_dp_strategies},
     };
     use janus_messages::{
         AggregationJobContinueReq, AggregationJobId, AggregationJobInitializeReq,
@@ -531,7 +531,9 @@ mod tests {
         let task = TaskBuilder::new(
             BatchMode::TimeInterval,
             AggregationMode::Synchronous,
-            VdafInstance::Prio3Count,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
         )
         .build();
         datastore
@@ -568,7 +570,9 @@ mod tests {
         let task = TaskBuilder::new(
             BatchMode::TimeInterval,
             AggregationMode::Synchronous,
-            VdafInstance::Prio3Count,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
         )
         .build();
         datastore
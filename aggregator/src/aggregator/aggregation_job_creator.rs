@@ -3,7 +3,10 @@ use crate::{
         aggregation_job_writer::{AggregationJobWriter, InitialWrite},
         batch_creator::BatchCreator,
     },
-    metrics::AGGREGATION_JOB_SIZE_HISTOGRAM_BOUNDARIES,
+    metrics::{
+        AGGREGATION_JOB_SIZE_HISTOGRAM_BOUNDARIES, TaskMetricLabels, batch_mode_label,
+        report_aggregation_job_creation_latency_histogram,
+    },
 };
 #[cfg(feature = "fpvec_bounded_l2")]
 use fixed::{
@@ -12,10 +15,8 @@ use fixed::{
 };
 use futures::future::try_join_all;
 use itertools::Itertools as _;
-#[cfg(feature = "test-util")]
-use janus_aggregator_core::VdafHasAggregationParameter;
 use janus_aggregator_core::{
-    AsyncAggregator, TIME_HISTOGRAM_BOUNDARIES,
+    AsyncAggregator, TIME_HISTOGRAM_BOUNDARIES, VdafHasAggregationParameter,
     datastore::{
         self, Datastore,
         models::{
@@ -36,7 +37,8 @@ use janus_core::{
     },
 };
 use janus_messages::{
-    AggregationJobStep, Duration as DurationMsg, Interval, Role, TaskId, batch_mode::TimeInterval,
+    AggregationJobStep, Duration as DurationMsg, Interval, Role, TaskId, Time,
+    batch_mode::{LeaderSelected, TimeInterval},
 };
 use opentelemetry::{
     KeyValue,
@@ -56,7 +58,7 @@ use rand::{Rng, random, rng};
 use std::{
     cmp::{max, min},
     collections::{HashMap, HashSet},
-    sync::Arc,
+    sync::{Arc, Mutex},
     time::Duration,
 };
 use tokio::{
@@ -97,8 +99,48 @@ pub struct AggregationJobCreator<C: Clock> {
     task_update_time_histogram: Histogram<f64>,
     /// Time spent creating aggregation jobs.
     job_creation_time_histogram: Histogram<f64>,
+    /// Metrics recorded for each aggregation job created.
+    aggregation_job_creation_metrics: AggregationJobCreationMetrics,
+    /// The time at which the oldest unaggregated report of each task was stored, as last observed
+    /// by the task's job creation worker.
+    oldest_unaggregated_reports: Arc<Mutex<HashMap<TaskId, (Vec<KeyValue>, Time)>>>,
+}
+
+/// Metrics instruments recorded for each aggregation job created.
+#[derive(Clone)]
+pub(crate) struct AggregationJobCreationMetrics {
     /// Number of reports in aggregation jobs.
     aggregation_job_size_histogram: Histogram<u64>,
+    /// Time from each report's timestamp to the creation of its aggregation job.
+    report_latency_histogram: Histogram<f64>,
+    task_metric_labels: TaskMetricLabels,
+}
+
+impl AggregationJobCreationMetrics {
+    /// Records that an aggregation job with the given report aggregations was created at `now`.
+    pub(crate) fn record_aggregation_job(
+        &self,
+        task_id: &TaskId,
+        batch_mode: &task::BatchMode,
+        now: &Time,
+        report_aggregations: &[ReportAggregationMetadata],
+    ) {
+        self.aggregation_job_size_histogram.record(
+            u64::try_from(report_aggregations.len()).unwrap_or(u64::MAX),
+            &[],
+        );
+        let attributes = self.task_metric_labels.attributes(
+            task_id,
+            [KeyValue::new("batch_mode", batch_mode_label(batch_mode))],
+        );
+        for report_aggregation in report_aggregations {
+            self.report_latency_histogram.record(
+                now.saturating_difference(report_aggregation.time())
+                    .as_seconds() as f64,
+                &attributes,
+            );
+        }
+    }
 }
 
 impl<C: Clock + 'static> AggregationJobCreator<C> {
@@ -142,6 +184,38 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
             .with_unit("{report}")
             .build();
 
+        let oldest_unaggregated_reports: Arc<Mutex<HashMap<TaskId, (Vec<KeyValue>, Time)>>> =
+            Arc::default();
+        let _oldest_unaggregated_report_age_gauge = meter
+            .u64_observable_gauge("janus_oldest_unaggregated_report_age")
+            .with_description(
+                "Time since the oldest report not yet included in an aggregation job was stored.",
+            )
+            .with_unit("s")
+            .with_callback({
+                let clock = datastore.clock().clone();
+                let oldest_unaggregated_reports = Arc::clone(&oldest_unaggregated_reports);
+                move |observer| {
+                    // Tasks that aren't labeled with their own task ID share attributes, so report
+                    // the greatest age among them.
+                    let now = clock.now();
+                    let mut ages: Vec<(&Vec<KeyValue>, u64)> = Vec::new();
+                    // Unwrap safety: panic on mutex poisoning.
+                    let oldest_unaggregated_reports = oldest_unaggregated_reports.lock().unwrap();
+                    for (attributes, stored_at) in oldest_unaggregated_reports.values() {
+                        let age = now.saturating_difference(stored_at).as_seconds();
+                        match ages.iter_mut().find(|(other, _)| *other == attributes) {
+                            Some((_, max_age)) => *max_age = max(*max_age, age),
+                            None => ages.push((attributes, age)),
+                        }
+                    }
+                    for (attributes, age) in ages {
+                        observer.observe(age, attributes);
+                    }
+                }
+            })
+            .build();
+
         AggregationJobCreator {
             datastore,
             batch_aggregation_shard_count,
@@ -153,10 +227,21 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
             late_report_grace_period,
             task_update_time_histogram,
             job_creation_time_histogram,
-            aggregation_job_size_histogram,
+            aggregation_job_creation_metrics: AggregationJobCreationMetrics {
+                aggregation_job_size_histogram,
+                report_latency_histogram: report_aggregation_job_creation_latency_histogram(&meter),
+                task_metric_labels: TaskMetricLabels::default(),
+            },
+            oldest_unaggregated_reports,
         }
     }
 
+    /// Labels per-task metrics with task IDs, as determined by `task_metric_labels`.
+    pub fn with_task_metric_labels(mut self, task_metric_labels: TaskMetricLabels) -> Self {
+        self.aggregation_job_creation_metrics.task_metric_labels = task_metric_labels;
+        self
+    }
+
     pub async fn run(self: Arc<Self>, stopper: Stopper) {
         // TODO(#1393): add support for handling only a subset of tasks in a single job (i.e. sharding).
 
@@ -221,7 +306,8 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
         let tasks = tasks
             .into_iter()
             .filter_map(|task| match task.role() {
-                Role::Leader => Some((*task.id(), task)),
+                // Archived tasks no longer have report data to aggregate.
+                Role::Leader if task.state().creates_aggregation_jobs() => Some((*task.id(), task)),
                 _ => None,
             })
             .collect::<HashMap<_, _>>();
@@ -234,6 +320,11 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
 
             info!(%task_id, "Stopping job creation worker");
             task_stopper.stop();
+            // Unwrap safety: panic on mutex poisoning.
+            self.oldest_unaggregated_reports
+                .lock()
+                .unwrap()
+                .remove(task_id);
             false
         });
 
@@ -299,7 +390,45 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
                 start.elapsed().as_secs_f64(),
                 &[KeyValue::new("status", status)],
             );
+
+            if let Err(err) = self.update_oldest_unaggregated_report(&task).await {
+                error!(task_id = %task.id(), %err, "Couldn't find oldest unaggregated report");
+            }
+        }
+    }
+
+    /// Looks up the oldest report of the task that is not yet included in an aggregation job, to
+    /// be reported by the oldest unaggregated report age gauge.
+    async fn update_oldest_unaggregated_report(
+        &self,
+        task: &AggregatorTask,
+    ) -> Result<(), datastore::Error> {
+        let task_id = *task.id();
+        let stored_at = self
+            .datastore
+            .run_tx("aggregation_job_creator_oldest_unaggregated_report", |tx| {
+                Box::pin(async move {
+                    tx.get_oldest_unaggregated_client_report_time(&task_id)
+                        .await
+                })
+            })
+            .await?;
+
+        // Unwrap safety: panic on mutex poisoning.
+        let mut oldest_unaggregated_reports = self.oldest_unaggregated_reports.lock().unwrap();
+        match stored_at {
+            Some(stored_at) => {
+                let attributes = self
+                    .aggregation_job_creation_metrics
+                    .task_metric_labels
+                    .attributes(&task_id, []);
+                oldest_unaggregated_reports.insert(task_id, (attributes, stored_at));
+            }
+            None => {
+                oldest_unaggregated_reports.remove(&task_id);
+            }
         }
+        Ok(())
     }
 
     /// This returns `true` if at least one aggregation job at or above the
@@ -316,13 +445,18 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
         task: Arc<AggregatorTask>,
     ) -> anyhow::Result<bool> {
         match (task.batch_mode(), task.vdaf()) {
-            (task::BatchMode::TimeInterval, VdafInstance::Prio3Count) => {
+            (task::BatchMode::TimeInterval, VdafInstance::Prio3Count { .. }) => {
                 let vdaf = Arc::new(Prio3::new_count(2)?);
                 self.create_aggregation_jobs_for_time_interval_task_no_param::<VERIFY_KEY_LENGTH_PRIO3, Prio3Count>(task, vdaf)
                     .await
             }
 
-            (task::BatchMode::TimeInterval, VdafInstance::Prio3Sum { max_measurement }) => {
+            (
+                task::BatchMode::TimeInterval,
+                VdafInstance::Prio3Sum {
+                    max_measurement, ..
+                },
+            ) => {
                 let vdaf = Arc::new(Prio3::new_sum(2, *max_measurement)?);
                 self.create_aggregation_jobs_for_time_interval_task_no_param::<VERIFY_KEY_LENGTH_PRIO3, Prio3Sum>(task, vdaf)
                     .await
@@ -410,7 +544,7 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
                 task::BatchMode::LeaderSelected {
                     batch_time_window_size,
                 },
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count { .. },
             ) => {
                 let vdaf: Arc<
                     Prio3<
@@ -430,7 +564,9 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
                 task::BatchMode::LeaderSelected {
                     batch_time_window_size,
                 },
-                VdafInstance::Prio3Sum { max_measurement },
+                VdafInstance::Prio3Sum {
+                    max_measurement, ..
+                },
             ) => {
                 let vdaf = Arc::new(Prio3::new_sum(2, *max_measurement)?);
                 let batch_time_window_size = *batch_time_window_size;
@@ -533,16 +669,12 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
             }
 
             #[cfg(feature = "test-util")]
-            (
-                task::BatchMode::LeaderSelected {
-                    batch_time_window_size: _batch_time_window_size,
-                },
-                VdafInstance::Fake { rounds },
-            ) => {
-                let _vdaf = prio::vdaf::dummy::Vdaf::new(*rounds);
-                todo!(
-                    "wire up call to self.create_aggregation_jobs_for_leader_selected_task_with_param"
-                )
+            (task::BatchMode::LeaderSelected { .. }, VdafInstance::Fake { rounds }) => {
+                let vdaf = Arc::new(prio::vdaf::dummy::Vdaf::new(*rounds));
+                self.create_aggregation_jobs_for_leader_selected_task_with_param::<
+                    0,
+                    prio::vdaf::dummy::Vdaf,
+                >(task, vdaf).await
             }
 
             _ => {
@@ -726,10 +858,13 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
                             report_ids_to_scrub
                                 .extend(agg_job_reports.iter().map(UnaggregatedReport::report_id));
 
-                            this.aggregation_job_size_histogram.record(
-                                u64::try_from(report_aggregations.len()).unwrap_or(u64::MAX),
-                                &[],
-                            );
+                            this.aggregation_job_creation_metrics
+                                .record_aggregation_job(
+                                    task.id(),
+                                    task.batch_mode(),
+                                    &tx.clock().now(),
+                                    &report_aggregations,
+                                );
                             aggregation_job_writer.put(aggregation_job, report_aggregations)?;
                         }
                     }
@@ -738,10 +873,13 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
                     aggregation_job_writer.write(tx, vdaf).await?;
                     // Report scrubbing must wait until after report aggregations have been created,
                     // because they have a write-after-read antidependency on the report shares.
+                    // Reports of tasks with a report retention window are instead scrubbed by the
+                    // garbage collector once the window has elapsed.
                     try_join!(
                         try_join_all(
                             report_ids_to_scrub
                                 .iter()
+                                .filter(|_| task.report_retention_window().is_none())
                                 .map(|report_id| tx.scrub_client_report(task.id(), report_id))
                         ),
                         try_join_all(outstanding_reports.iter().map(|report| {
@@ -758,8 +896,13 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
     /// Look for combinations of client reports and collection job aggregation parameters that do not
     /// yet have a report aggregation, and batch them into new aggregation jobs. This should only
     /// be used with VDAFs that have non-unit type aggregation parameters.
-    // This is only used in tests thus far.
-    #[cfg(feature = "test-util")]
+    ///
+    /// Reports are not scrubbed once aggregated, so that the same batch may be aggregated again
+    /// for later collection jobs with different aggregation parameters, for as long as the reports
+    /// are retained.
+    // The only VDAF with a non-unit aggregation parameter that is currently wired up is the fake
+    // VDAF used in tests.
+    #[cfg_attr(not(feature = "test-util"), allow(dead_code))]
     async fn create_aggregation_jobs_for_time_interval_task_with_param<const SEED_SIZE: usize, A>(
         self: Arc<Self>,
         task: Arc<AggregatorTask>,
@@ -798,7 +941,7 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
                         .into_iter()
                         .into_group_map();
 
-                    let mut writers_are_empty = false;
+                    let mut writers_are_empty = true;
                     // Generate aggregation jobs and report aggregations.
                     for (aggregation_param, report_ids_and_times) in result_map {
                         let mut aggregation_job_writer =
@@ -864,16 +1007,19 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
                                     ))
                                 })
                                 .collect::<Result<_, datastore::Error>>()?;
-                            this.aggregation_job_size_histogram.record(
-                                u64::try_from(report_aggregations.len()).unwrap_or(u64::MAX),
-                                &[],
-                            );
+                            this.aggregation_job_creation_metrics
+                                .record_aggregation_job(
+                                    task.id(),
+                                    task.batch_mode(),
+                                    &tx.clock().now(),
+                                    &report_aggregations,
+                                );
                             aggregation_job_writer.put(aggregation_job, report_aggregations)?;
                         }
 
                         // Write the aggregation jobs and report aggregations we created
-                        aggregation_job_writer.write(tx, Arc::clone(&vdaf)).await?;
                         writers_are_empty = writers_are_empty && aggregation_job_writer.is_empty();
+                        aggregation_job_writer.write(tx, Arc::clone(&vdaf)).await?;
                     }
                     Ok(!writers_are_empty)
                 })
@@ -922,8 +1068,10 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
                         task_min_batch_size,
                         task_batch_time_window_size,
                         task.time_precision().to_owned(),
+                        task.report_retention_window().copied(),
                         &mut aggregation_job_writer,
-                        this.aggregation_job_size_histogram.clone(),
+                        this.aggregation_job_creation_metrics.clone(),
+                        tx.clock().now(),
                     );
 
                     for report in unaggregated_reports {
@@ -936,6 +1084,142 @@ impl<C: Clock + 'static> AggregationJobCreator<C> {
             })
             .await?)
     }
+
+    /// Look for client reports belonging to batches with pending collection jobs that have not
+    /// yet been aggregated with the collection job's aggregation parameter, and batch them into
+    /// new aggregation jobs assigned to the same batch. This should only be used with VDAFs that
+    /// have non-unit type aggregation parameters.
+    // The only VDAF with a non-unit aggregation parameter that is currently wired up is the fake
+    // VDAF used in tests.
+    #[cfg_attr(not(feature = "test-util"), allow(dead_code))]
+    async fn create_aggregation_jobs_for_leader_selected_task_with_param<
+        const SEED_SIZE: usize,
+        A,
+    >(
+        self: Arc<Self>,
+        task: Arc<AggregatorTask>,
+        vdaf: Arc<A>,
+    ) -> anyhow::Result<bool>
+    where
+        A: AsyncAggregator<SEED_SIZE> + VdafHasAggregationParameter,
+    {
+        let max_aggregation_job_size = self.max_aggregation_job_size;
+
+        Ok(self
+            .datastore
+            .run_tx("aggregation_job_creator_fixed_with_param", |tx| {
+                let (
+                    this,
+                    task,
+                    vdaf,
+                    aggregation_job_creation_report_window,
+                    batch_aggregation_shard_count,
+                ) = (
+                    Arc::clone(&self),
+                    Arc::clone(&task),
+                    Arc::clone(&vdaf),
+                    self.aggregation_job_creation_report_window,
+                    self.batch_aggregation_shard_count,
+                );
+                Box::pin(async move {
+                    // Find the reports of batches with pending collection jobs that haven't been
+                    // aggregated with the collection job's parameter yet, and group them by batch
+                    // and aggregation parameter.
+                    let result_map = tx
+                        .get_unaggregated_client_report_ids_by_collect_for_leader_selected_task::<
+                            SEED_SIZE,
+                            A,
+                        >(task.id(), aggregation_job_creation_report_window)
+                        .await?
+                        .into_iter()
+                        .map(|(batch_id, aggregation_param, report)| {
+                            ((batch_id, aggregation_param), report)
+                        })
+                        .into_group_map();
+
+                    let mut aggregation_job_writer =
+                        AggregationJobWriter::<SEED_SIZE, _, _, InitialWrite, _>::new(
+                            Arc::clone(&task),
+                            batch_aggregation_shard_count,
+                            None,
+                        );
+                    // Generate aggregation jobs and report aggregations. The batch's membership
+                    // was fixed when it was first aggregated, so every remaining report is
+                    // aggregated, regardless of the minimum aggregation job size.
+                    for ((batch_id, aggregation_param), reports) in result_map {
+                        for agg_job_reports in reports.chunks(max_aggregation_job_size) {
+                            let aggregation_job_id = random();
+                            debug!(
+                                task_id = %task.id(),
+                                %batch_id,
+                                %aggregation_job_id,
+                                report_count = %agg_job_reports.len(),
+                                "Creating aggregation job"
+                            );
+
+                            // unwrap safety: agg_job_reports is non-empty
+                            let min_client_timestamp = agg_job_reports
+                                .iter()
+                                .map(UnaggregatedReport::client_timestamp)
+                                .min()
+                                .unwrap();
+                            // unwrap safety: agg_job_reports is non-empty
+                            let max_client_timestamp = agg_job_reports
+                                .iter()
+                                .map(UnaggregatedReport::client_timestamp)
+                                .max()
+                                .unwrap();
+                            let client_timestamp_interval = Interval::new(
+                                *min_client_timestamp,
+                                max_client_timestamp
+                                    .difference(min_client_timestamp)?
+                                    .add(&DurationMsg::from_seconds(1))?
+                                    .round_up(task.time_precision())?,
+                            )?;
+
+                            let aggregation_job =
+                                AggregationJob::<SEED_SIZE, LeaderSelected, A>::new(
+                                    *task.id(),
+                                    aggregation_job_id,
+                                    aggregation_param.clone(),
+                                    batch_id,
+                                    client_timestamp_interval,
+                                    AggregationJobState::Active,
+                                    AggregationJobStep::from(0),
+                                );
+                            let report_aggregations: Vec<_> = agg_job_reports
+                                .iter()
+                                .enumerate()
+                                .map(|(ord, report)| {
+                                    Ok(ReportAggregationMetadata::new(
+                                        *task.id(),
+                                        aggregation_job_id,
+                                        *report.report_id(),
+                                        *report.client_timestamp(),
+                                        ord.try_into()?,
+                                        ReportAggregationMetadataState::Init,
+                                    ))
+                                })
+                                .collect::<Result<_, datastore::Error>>()?;
+                            this.aggregation_job_creation_metrics
+                                .record_aggregation_job(
+                                    task.id(),
+                                    task.batch_mode(),
+                                    &tx.clock().now(),
+                                    &report_aggregations,
+                                );
+                            aggregation_job_writer.put(aggregation_job, report_aggregations)?;
+                        }
+                    }
+
+                    // Write the aggregation jobs and report aggregations we created
+                    let wrote_aggregation_jobs = !aggregation_job_writer.is_empty();
+                    aggregation_job_writer.write(tx, vdaf).await?;
+                    Ok(wrote_aggregation_jobs)
+                })
+            })
+            .await?)
+    }
 }
 
 #[cfg(test)]
@@ -963,7 +1247,7 @@ mod tests {
         hpke::HpkeKeypair,
         test_util::{install_test_trace_subscriber, run_vdaf},
         time::{Clock, DurationExt, MockClock, TimeExt},
-        vdaf::{VERIFY_KEY_LENGTH_PRIO3, VdafInstance},
+        vdaf::{VERIFY_KEY_LENGTH_PRIO3, VdafInstance, vdaf_dp_strategies},
     };
     use janus_messages::{
         AggregationJobStep, Duration as JanusDuration, Interval, Query, ReportError, ReportId,
@@ -1008,7 +1292,9 @@ mod tests {
             TaskBuilder::new(
                 TaskBatchMode::TimeInterval,
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
             )
             .build()
             .leader_view()
@@ -1039,7 +1325,9 @@ mod tests {
             TaskBuilder::new(
                 TaskBatchMode::TimeInterval,
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
             )
             .build()
             .helper_view()
@@ -1196,7 +1484,9 @@ mod tests {
             TaskBuilder::new(
                 TaskBatchMode::TimeInterval,
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
             )
             .build()
             .leader_view()
@@ -1392,7 +1682,9 @@ mod tests {
             TaskBuilder::new(
                 TaskBatchMode::TimeInterval,
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
             )
             .build()
             .leader_view()
@@ -1593,7 +1885,9 @@ mod tests {
             TaskBuilder::new(
                 TaskBatchMode::TimeInterval,
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
             )
             .build()
             .leader_view()
@@ -1758,7 +2052,9 @@ mod tests {
             TaskBuilder::new(
                 TaskBatchMode::TimeInterval,
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
             )
             .build()
             .leader_view()
@@ -1944,7 +2240,9 @@ mod tests {
                     batch_time_window_size: None,
                 },
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
             )
             .with_min_batch_size(MIN_BATCH_SIZE as u64)
             .with_time_precision(JanusDuration::from_seconds(10))
@@ -2145,7 +2443,9 @@ mod tests {
                     batch_time_window_size: None,
                 },
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
             )
             .with_min_batch_size(MIN_BATCH_SIZE as u64)
             .build()
@@ -2309,7 +2609,9 @@ mod tests {
                     batch_time_window_size: None,
                 },
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
             )
             .with_min_batch_size(MIN_BATCH_SIZE as u64)
             .with_time_precision(JanusDuration::from_seconds(10))
@@ -2574,7 +2876,9 @@ mod tests {
                     batch_time_window_size: None,
                 },
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
             )
             .with_min_batch_size(MIN_BATCH_SIZE as u64)
             .build()
@@ -2847,7 +3151,9 @@ mod tests {
                     batch_time_window_size: Some(batch_time_window_size),
                 },
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
             )
             .with_min_batch_size(MIN_BATCH_SIZE as u64)
             .build()
@@ -3367,6 +3673,243 @@ mod tests {
         assert_eq!(agg_jobs, quiescent_check_agg_jobs);
     }
 
+    #[tokio::test]
+    async fn create_aggregation_jobs_for_leader_selected_task_with_param() {
+        install_test_trace_subscriber();
+        let clock = MockClock::default();
+        let ephemeral_datastore = ephemeral_datastore().await;
+        let ds = ephemeral_datastore.datastore(clock.clone()).await;
+
+        const MAX_AGGREGATION_JOB_SIZE: usize = 10;
+
+        let vdaf = Arc::new(dummy::Vdaf::new(1));
+        let task = Arc::new(
+            TaskBuilder::new(
+                TaskBatchMode::LeaderSelected {
+                    batch_time_window_size: None,
+                },
+                AggregationMode::Synchronous,
+                VdafInstance::Fake { rounds: 1 },
+            )
+            .build()
+            .leader_view()
+            .unwrap(),
+        );
+
+        let first_aggregation_param = dummy::AggregationParam(11);
+        let second_aggregation_param = dummy::AggregationParam(7);
+        let batch_id = random();
+        let first_aggregation_job_id = random();
+        let report_time = clock.now_aligned_to_precision(task.time_precision());
+        let mut expected_report_aggregations = HashMap::new();
+
+        // Create more than MAX_AGGREGATION_JOB_SIZE reports, which have already been aggregated
+        // into the batch under the first aggregation parameter. These should be aggregated again
+        // under the second aggregation parameter.
+        let batch_reports: Vec<LeaderStoredReport<0, dummy::Vdaf>> =
+            iter::repeat_with(|| LeaderStoredReport::new_dummy(*task.id(), report_time))
+                .take(MAX_AGGREGATION_JOB_SIZE + 1)
+                .collect();
+        for report in &batch_reports {
+            expected_report_aggregations.insert(
+                (*report.metadata().id(), first_aggregation_param),
+                ReportAggregationState::Finished,
+            );
+            expected_report_aggregations.insert(
+                (*report.metadata().id(), second_aggregation_param),
+                report
+                    .as_leader_init_report_aggregation(random(), 0)
+                    .state()
+                    .clone(),
+            );
+        }
+
+        // This report failed to aggregate under the first aggregation parameter, so it is not part
+        // of the batch, and should not be aggregated again.
+        let failed_report = LeaderStoredReport::new_dummy(*task.id(), report_time);
+        expected_report_aggregations.insert(
+            (*failed_report.metadata().id(), first_aggregation_param),
+            ReportAggregationState::Failed {
+                report_error: ReportError::VdafPrepError,
+            },
+        );
+
+        // This report has never been aggregated, so it is not part of the batch either.
+        let unaggregated_report = LeaderStoredReport::new_dummy(*task.id(), report_time);
+
+        ds.run_unnamed_tx(|tx| {
+            let (task, batch_reports, failed_report, unaggregated_report) = (
+                Arc::clone(&task),
+                batch_reports.clone(),
+                failed_report.clone(),
+                unaggregated_report.clone(),
+            );
+            Box::pin(async move {
+                tx.put_aggregator_task(&task).await.unwrap();
+                tx.put_aggregation_job(&AggregationJob::<0, LeaderSelected, dummy::Vdaf>::new(
+                    *task.id(),
+                    first_aggregation_job_id,
+                    first_aggregation_param,
+                    batch_id,
+                    Interval::new(report_time, *task.time_precision()).unwrap(),
+                    AggregationJobState::Finished,
+                    AggregationJobStep::from(1),
+                ))
+                .await
+                .unwrap();
+
+                for (ord, report) in batch_reports.iter().enumerate() {
+                    tx.put_client_report(report).await.unwrap();
+                    tx.put_report_aggregation::<0, dummy::Vdaf>(&ReportAggregation::new(
+                        *task.id(),
+                        first_aggregation_job_id,
+                        *report.metadata().id(),
+                        *report.metadata().time(),
+                        ord.try_into().unwrap(),
+                        None,
+                        ReportAggregationState::Finished,
+                    ))
+                    .await
+                    .unwrap();
+                }
+                tx.put_client_report(&failed_report).await.unwrap();
+                tx.put_report_aggregation::<0, dummy::Vdaf>(&ReportAggregation::new(
+                    *task.id(),
+                    first_aggregation_job_id,
+                    *failed_report.metadata().id(),
+                    *failed_report.metadata().time(),
+                    batch_reports.len().try_into().unwrap(),
+                    None,
+                    ReportAggregationState::Failed {
+                        report_error: ReportError::VdafPrepError,
+                    },
+                ))
+                .await
+                .unwrap();
+                tx.put_client_report(&unaggregated_report).await.unwrap();
+
+                tx.put_collection_job::<0, LeaderSelected, dummy::Vdaf>(&CollectionJob::new(
+                    *task.id(),
+                    random(),
+                    Query::new_leader_selected(),
+                    second_aggregation_param,
+                    batch_id,
+                    CollectionJobState::Start,
+                ))
+                .await
+                .unwrap();
+                Ok(())
+            })
+        })
+        .await
+        .unwrap();
+
+        let job_creator = Arc::new(AggregationJobCreator::new(
+            Arc::new(ds),
+            noop_meter(),
+            BATCH_AGGREGATION_SHARD_COUNT,
+            Duration::from_secs(3600),
+            Duration::from_secs(1),
+            MAX_AGGREGATION_JOB_SIZE,
+            MAX_AGGREGATION_JOB_SIZE,
+            5000,
+            janus_messages::Duration::from_seconds(3600),
+        ));
+        assert!(
+            Arc::clone(&job_creator)
+                .create_aggregation_jobs_for_leader_selected_task_with_param::<0, dummy::Vdaf>(
+                    Arc::clone(&task),
+                    Arc::clone(&vdaf),
+                )
+                .await
+                .unwrap()
+        );
+
+        // Verify. The batch's reports should have been split across two aggregation jobs, despite
+        // the minimum aggregation job size, and both jobs should be assigned to the same batch.
+        let (mut agg_jobs, _) = job_creator
+            .datastore
+            .run_unnamed_tx(|tx| {
+                let (task, vdaf, expected_report_aggregations) = (
+                    Arc::clone(&task),
+                    Arc::clone(&vdaf),
+                    expected_report_aggregations.clone(),
+                );
+                Box::pin(async move {
+                    Ok(read_and_verify_aggregate_info_for_task::<
+                        0,
+                        LeaderSelected,
+                        dummy::Vdaf,
+                        _,
+                    >(
+                        tx, &vdaf, task.id(), &expected_report_aggregations
+                    )
+                    .await)
+                })
+            })
+            .await
+            .unwrap();
+        assert_eq!(agg_jobs.len(), 3);
+
+        let mut seen_report_ids = Vec::new();
+        for (aggregation_job, report_aggregations) in &agg_jobs {
+            assert_eq!(aggregation_job.batch_id(), &batch_id);
+            if aggregation_job.id() == &first_aggregation_job_id {
+                continue;
+            }
+            assert_eq!(
+                aggregation_job.aggregation_parameter(),
+                &second_aggregation_param
+            );
+            assert!(report_aggregations.len() <= MAX_AGGREGATION_JOB_SIZE);
+            seen_report_ids.extend(report_aggregations.iter().map(|ra| *ra.report_id()));
+        }
+        let mut expected_report_ids: Vec<_> = batch_reports
+            .iter()
+            .map(|report| *report.metadata().id())
+            .collect();
+        seen_report_ids.sort();
+        expected_report_ids.sort();
+        assert_eq!(seen_report_ids, expected_report_ids);
+
+        // Run once more, and confirm that no further aggregation jobs are created.
+        assert!(
+            !Arc::clone(&job_creator)
+                .create_aggregation_jobs_for_leader_selected_task_with_param::<0, dummy::Vdaf>(
+                    Arc::clone(&task),
+                    Arc::clone(&vdaf),
+                )
+                .await
+                .unwrap()
+        );
+
+        let (mut quiescent_check_agg_jobs, _) = job_creator
+            .datastore
+            .run_unnamed_tx(|tx| {
+                let (task, vdaf, expected_report_aggregations) = (
+                    Arc::clone(&task),
+                    Arc::clone(&vdaf),
+                    expected_report_aggregations.clone(),
+                );
+                Box::pin(async move {
+                    Ok(read_and_verify_aggregate_info_for_task::<
+                        0,
+                        LeaderSelected,
+                        dummy::Vdaf,
+                        _,
+                    >(
+                        tx, &vdaf, task.id(), &expected_report_aggregations
+                    )
+                    .await)
+                })
+            })
+            .await
+            .unwrap();
+        agg_jobs.sort_by_key(|(agg_job, _)| *agg_job.id());
+        quiescent_check_agg_jobs.sort_by_key(|(agg_job, _)| *agg_job.id());
+        assert_eq!(agg_jobs, quiescent_check_agg_jobs);
+    }
+
     /// Test helper function that reads all aggregation jobs & batch aggregations for a given task
     /// ID, returning the aggregation jobs, the report IDs included in the aggregation job, and the
     /// batch aggregations. Report IDs are returned in the order they are included in the
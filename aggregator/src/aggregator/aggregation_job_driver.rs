@@ -14,8 +14,9 @@ use crate::{
     },
     cache::HpkeKeypairCache,
     metrics::{
-        aggregated_report_share_dimension_histogram, early_report_clock_skew_histogram,
-        past_report_clock_skew_histogram,
+        TaskMetricLabels, aggregated_report_share_dimension_histogram, batch_mode_label,
+        early_report_clock_skew_histogram, past_report_clock_skew_histogram,
+        report_aggregation_failure_counter, report_aggregation_latency_histogram,
     },
 };
 use anyhow::{Context, Result, anyhow};
@@ -29,22 +30,22 @@ use janus_aggregator_core::{
     datastore::{
         self, Datastore,
         models::{
-            AcquiredAggregationJob, AggregationJob, AggregationJobState, Lease, ReportAggregation,
-            ReportAggregationState,
+            AcquiredAggregationJob, AggregationJob, AggregationJobState, JobStepOutcome, Lease,
+            ReportAggregation, ReportAggregationState, TaskAggregationCounter,
         },
     },
     task::{self, AggregatorTask},
 };
 use janus_core::{
     retries::{is_retryable_http_client_error, is_retryable_http_status},
-    time::Clock,
+    time::{Clock, TimeExt},
     vdaf::vdaf_application_context,
     vdaf_dispatch,
 };
 use janus_messages::{
     AggregationJobContinueReq, AggregationJobInitializeReq, AggregationJobResp, MediaType,
     PartialBatchSelector, PrepareContinue, PrepareInit, PrepareResp, PrepareStepResult,
-    ReportError, ReportMetadata, ReportShare, Role,
+    ReportError, ReportMetadata, ReportShare, Role, Time,
     batch_mode::{LeaderSelected, TimeInterval},
 };
 use opentelemetry::{
@@ -93,6 +94,8 @@ pub struct AggregationJobDriver<B> {
     #[educe(Debug(ignore))]
     aggregate_step_failure_counter: Counter<u64>,
     #[educe(Debug(ignore))]
+    report_aggregation_failure_counter: Counter<u64>,
+    #[educe(Debug(ignore))]
     aggregated_report_share_dimension_histogram: Histogram<u64>,
     #[educe(Debug(ignore))]
     job_cancel_counter: Counter<u64>,
@@ -104,6 +107,9 @@ pub struct AggregationJobDriver<B> {
     early_report_clock_skew_histogram: Histogram<u64>,
     #[educe(Debug(ignore))]
     past_report_clock_skew_histogram: Histogram<u64>,
+    #[educe(Debug(ignore))]
+    report_aggregation_latency_histogram: Histogram<f64>,
+    task_metric_labels: TaskMetricLabels,
 }
 
 impl<R> AggregationJobDriver<R>
@@ -121,6 +127,7 @@ where
     ) -> Self {
         let aggregation_success_counter = report_aggregation_success_counter(meter);
         let aggregate_step_failure_counter = aggregate_step_failure_counter(meter);
+        let report_aggregation_failure_counter = report_aggregation_failure_counter(meter);
         let aggregated_report_share_dimension_histogram =
             aggregated_report_share_dimension_histogram(meter);
 
@@ -149,6 +156,7 @@ where
 
         let early_report_clock_skew_histogram = early_report_clock_skew_histogram(meter);
         let past_report_clock_skew_histogram = past_report_clock_skew_histogram(meter);
+        let report_aggregation_latency_histogram = report_aggregation_latency_histogram(meter);
 
         Self {
             batch_aggregation_shard_count,
@@ -159,12 +167,59 @@ where
             backoff,
             aggregation_success_counter,
             aggregate_step_failure_counter,
+            report_aggregation_failure_counter,
             aggregated_report_share_dimension_histogram,
             job_cancel_counter,
             job_retry_counter,
             http_request_duration_histogram,
             early_report_clock_skew_histogram,
             past_report_clock_skew_histogram,
+            report_aggregation_latency_histogram,
+            task_metric_labels: TaskMetricLabels::default(),
+        }
+    }
+
+    /// Labels per-task metrics with task IDs, as determined by `task_metric_labels`.
+    pub fn with_task_metric_labels(self, task_metric_labels: TaskMetricLabels) -> Self {
+        Self {
+            task_metric_labels,
+            ..self
+        }
+    }
+
+    fn aggregation_job_writer_metrics(&self) -> AggregationJobWriterMetrics {
+        AggregationJobWriterMetrics {
+            report_aggregation_success_counter: self.aggregation_success_counter.clone(),
+            aggregate_step_failure_counter: self.aggregate_step_failure_counter.clone(),
+            report_aggregation_failure_counter: self.report_aggregation_failure_counter.clone(),
+            aggregated_report_share_dimension_histogram: self
+                .aggregated_report_share_dimension_histogram
+                .clone(),
+            task_metric_labels: self.task_metric_labels.clone(),
+        }
+    }
+
+    /// Records the time from the creation of an aggregation job until `now` for each report in it
+    /// that `counters` counts as successfully aggregated.
+    fn record_report_aggregation_latency(
+        &self,
+        acquired_job: &AcquiredAggregationJob,
+        counters: &TaskAggregationCounter,
+        now: &Time,
+    ) {
+        let latency = now
+            .saturating_difference(acquired_job.created_at())
+            .as_seconds() as f64;
+        let attributes = self.task_metric_labels.attributes(
+            acquired_job.task_id(),
+            [KeyValue::new(
+                "batch_mode",
+                batch_mode_label(acquired_job.batch_mode()),
+            )],
+        );
+        for _ in 0..counters.success() {
+            self.report_aggregation_latency_histogram
+                .record(latency, &attributes);
         }
     }
 
@@ -1071,13 +1126,7 @@ where
             AggregationJobWriter::<SEED_SIZE, _, _, UpdateWrite, _>::new(
                 Arc::new(task),
                 self.batch_aggregation_shard_count,
-                Some(AggregationJobWriterMetrics {
-                    report_aggregation_success_counter: self.aggregation_success_counter.clone(),
-                    aggregate_step_failure_counter: self.aggregate_step_failure_counter.clone(),
-                    aggregated_report_share_dimension_histogram: self
-                        .aggregated_report_share_dimension_histogram
-                        .clone(),
-                }),
+                Some(self.aggregation_job_writer_metrics()),
             );
         aggregation_job_writer.put(aggregation_job, report_aggregations_to_write)?;
         let aggregation_job_writer = Arc::new(aggregation_job_writer);
@@ -1102,6 +1151,8 @@ where
             })
             .await?;
 
+        self.record_report_aggregation_latency(lease.leased(), &counters, &datastore.clock().now());
+
         write_task_aggregation_counter(datastore, self.task_counter_shard_count, task_id, counters);
 
         Ok(())
@@ -1322,13 +1373,7 @@ where
             AggregationJobWriter::<SEED_SIZE, _, _, UpdateWrite, _>::new(
                 Arc::new(task),
                 self.batch_aggregation_shard_count,
-                Some(AggregationJobWriterMetrics {
-                    report_aggregation_success_counter: self.aggregation_success_counter.clone(),
-                    aggregate_step_failure_counter: self.aggregate_step_failure_counter.clone(),
-                    aggregated_report_share_dimension_histogram: self
-                        .aggregated_report_share_dimension_histogram
-                        .clone(),
-                }),
+                Some(self.aggregation_job_writer_metrics()),
             );
         let new_step = aggregation_job.step().increment();
         aggregation_job_writer.put(
@@ -1353,6 +1398,8 @@ where
             })
             .await?;
 
+        self.record_report_aggregation_latency(lease.leased(), &counters, &datastore.clock().now());
+
         write_task_aggregation_counter(datastore, self.task_counter_shard_count, task_id, counters);
 
         Ok(())
@@ -1482,13 +1529,7 @@ where
         );
 
         // Write results back to datastore.
-        let metrics = AggregationJobWriterMetrics {
-            report_aggregation_success_counter: self.aggregation_success_counter.clone(),
-            aggregate_step_failure_counter: self.aggregate_step_failure_counter.clone(),
-            aggregated_report_share_dimension_histogram: self
-                .aggregated_report_share_dimension_histogram
-                .clone(),
-        };
+        let metrics = self.aggregation_job_writer_metrics();
 
         let counters = datastore
             .run_tx("aggregate_init_driver_write", |tx| {
@@ -1522,6 +1563,8 @@ where
             })
             .await?;
 
+        self.record_report_aggregation_latency(lease.leased(), &counters, &datastore.clock().now());
+
         write_task_aggregation_counter(
             datastore,
             self.task_counter_shard_count,
@@ -1573,13 +1616,7 @@ where
         );
 
         // Write results back to datastore.
-        let metrics = AggregationJobWriterMetrics {
-            report_aggregation_success_counter: self.aggregation_success_counter.clone(),
-            aggregate_step_failure_counter: self.aggregate_step_failure_counter.clone(),
-            aggregated_report_share_dimension_histogram: self
-                .aggregated_report_share_dimension_histogram
-                .clone(),
-        };
+        let metrics = self.aggregation_job_writer_metrics();
 
         let counters = datastore
             .run_tx("aggregate_continue_driver_write", |tx| {
@@ -1613,6 +1650,8 @@ where
             })
             .await?;
 
+        self.record_report_aggregation_latency(lease.leased(), &counters, &datastore.clock().now());
+
         write_task_aggregation_counter(
             datastore,
             self.task_counter_shard_count,
@@ -1848,7 +1887,9 @@ where
                 {
                     Ok(_) => Ok(()),
                     Err(error) => {
-                        if !Self::is_retryable_error(&error) {
+                        let retryable = Self::is_retryable_error(&error);
+                        Self::record_step_failure(&datastore, &lease, &error, retryable).await;
+                        if !retryable {
                             // Make a best-effort attempt to immediately cancel the aggregation job.
                             // on fatal errors. This protects the helper from performing wasted
                             // work.
@@ -1878,6 +1919,40 @@ where
         }
     }
 
+    /// Makes a best-effort attempt to record the given error against the leased aggregation job,
+    /// both as the job's last error and in the job's history, so that operators can see why the job
+    /// is failing or was abandoned.
+    async fn record_step_failure<C: Clock>(
+        datastore: &Datastore<C>,
+        lease: &Lease<AcquiredAggregationJob>,
+        error: &Error,
+        retryable: bool,
+    ) {
+        let task_id = *lease.leased().task_id();
+        let aggregation_job_id = *lease.leased().aggregation_job_id();
+        let last_error = error.to_string();
+        let record = Arc::new(error.job_step_record(if retryable {
+            JobStepOutcome::RetryableError
+        } else {
+            JobStepOutcome::FatalError
+        }));
+        if let Err(error) = datastore
+            .run_tx("record_aggregation_job_step_failure", |tx| {
+                let last_error = last_error.clone();
+                let record = Arc::clone(&record);
+                Box::pin(async move {
+                    tx.set_aggregation_job_last_error(&task_id, &aggregation_job_id, &last_error)
+                        .await?;
+                    tx.put_aggregation_job_history_entry(&task_id, &aggregation_job_id, &record)
+                        .await
+                })
+            })
+            .await
+        {
+            warn!(?error, "Couldn't record aggregation job error");
+        }
+    }
+
     /// Determines whether the given [`Error`] is retryable in the context of aggregation job
     /// processing.
     fn is_retryable_error(error: &Error) -> bool {
@@ -37,7 +37,7 @@ use janus_core::{
     retries::test_util::LimitedRetryer,
     test_util::{install_test_trace_subscriber, run_vdaf, runtime::TestRuntimeManager},
     time::{Clock, DurationExt, MockClock, TimeExt},
-    vdaf::{VERIFY_KEY_LENGTH_PRIO3, VdafInstance},
+    vdaf::{VERIFY_KEY_LENGTH_PRIO3, VdafInstance, vdaf_dp_strategies},
 };
 use janus_messages::{
     AggregationJobContinueReq, AggregationJobInitializeReq, AggregationJobResp, AggregationJobStep,
@@ -377,7 +377,9 @@ async fn leader_sync_time_interval_aggregation_job_init_single_step() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_helper_aggregator_endpoint(server.url().parse().unwrap())
     .build();
@@ -1101,7 +1103,9 @@ async fn leader_sync_time_interval_aggregation_job_init_partially_garbage_collec
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_helper_aggregator_endpoint(server.url().parse().unwrap())
     .with_report_expiry_age(Some(REPORT_EXPIRY_AGE))
@@ -1497,7 +1501,9 @@ async fn leader_sync_leader_selected_aggregation_job_init_single_step() {
             batch_time_window_size: None,
         },
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_helper_aggregator_endpoint(server.url().parse().unwrap())
     .build();
@@ -5795,7 +5801,9 @@ async fn setup_cancel_aggregation_job_test() -> CancelAggregationJobTestCase {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_helper_aggregator_endpoint(mock_helper.url().parse().unwrap())
     .build()
@@ -6073,7 +6081,9 @@ async fn abandon_failing_aggregation_job_with_retryable_error() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_helper_aggregator_endpoint(server.url().parse().unwrap())
     .build();
@@ -6325,7 +6335,9 @@ async fn abandon_failing_aggregation_job_with_fatal_error() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_helper_aggregator_endpoint(server.url().parse().unwrap())
     .build();
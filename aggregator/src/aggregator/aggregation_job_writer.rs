@@ -1,7 +1,10 @@
 //! In-memory accumulation of aggregation job (& report aggregation) writes, along with related
 //! batch aggregation writes.
 
-use crate::Operation;
+use crate::{
+    Operation,
+    metrics::{TaskMetricLabels, report_error_label},
+};
 use async_trait::async_trait;
 use futures::future::try_join_all;
 use janus_aggregator_core::{
@@ -54,7 +57,9 @@ where
 pub struct AggregationJobWriterMetrics {
     pub report_aggregation_success_counter: Counter<u64>,
     pub aggregate_step_failure_counter: Counter<u64>,
+    pub report_aggregation_failure_counter: Counter<u64>,
     pub aggregated_report_share_dimension_histogram: Histogram<u64>,
+    pub task_metric_labels: TaskMetricLabels,
 }
 
 #[allow(private_bounds)]
@@ -197,6 +202,7 @@ where
         state.update_batch_aggregations_from_report_aggregations()?;
         state.update_aggregation_job_state_from_report_aggregations();
         state.update_batch_aggregations_from_aggregation_jobs()?;
+        state.record_report_aggregation_failures();
 
         // Write aggregation jobs, report aggregations, and batch aggregations back to the
         // datastore.
@@ -688,11 +694,14 @@ where
 
                                     use VdafInstance::*;
                                     match self.writer.task.vdaf() {
-                                        Prio3Count => metrics
+                                        Prio3Count { dp_strategy: _ } => metrics
                                             .aggregated_report_share_dimension_histogram
                                             .record(1, &[KeyValue::new("type", "Prio3Count")]),
 
-                                        Prio3Sum { max_measurement } => metrics
+                                        Prio3Sum {
+                                            max_measurement,
+                                            dp_strategy: _,
+                                        } => metrics
                                             .aggregated_report_share_dimension_histogram
                                             .record(
                                                 *max_measurement,
@@ -819,6 +828,27 @@ where
         Ok(())
     }
 
+    /// Record the failed report aggregations that will be written in metrics.
+    fn record_report_aggregation_failures(&self) {
+        self.writer.update_metrics(|metrics| {
+            for report_aggregation in self
+                .by_aggregation_job
+                .values()
+                .flat_map(|info| info.report_aggregations.iter())
+            {
+                if let Some(report_error) = report_aggregation.report_error() {
+                    metrics.report_aggregation_failure_counter.add(
+                        1,
+                        &metrics.task_metric_labels.attributes(
+                            self.writer.task.id(),
+                            [KeyValue::new("error", report_error_label(&report_error))],
+                        ),
+                    );
+                }
+            }
+        });
+    }
+
     /// Update aggregation job states if all their report aggregations have reached a terminal
     /// state.
     fn update_aggregation_job_state_from_report_aggregations(&mut self) {
@@ -959,8 +989,13 @@ pub trait ReportAggregationUpdate<const SEED_SIZE: usize, A: AsyncAggregator<SEE
     /// reference to the output share.
     fn is_finished(&self) -> Option<&A::OutputShare>;
 
+    /// Returns the report error this report aggregation failed with, if it is failed.
+    fn report_error(&self) -> Option<ReportError>;
+
     /// Returns whether this report aggregation is failed.
-    fn is_failed(&self) -> bool;
+    fn is_failed(&self) -> bool {
+        self.report_error().is_some()
+    }
 
     /// Returns a new report aggregation corresponding to this report aggregation updated to have
     /// the "Failed" state, with the given [`ReportError`].
@@ -1008,11 +1043,11 @@ impl<const SEED_SIZE: usize, A: AsyncAggregator<SEED_SIZE>> ReportAggregationUpd
         self.output_share.as_ref()
     }
 
-    fn is_failed(&self) -> bool {
-        matches!(
-            self.report_aggregation.state(),
-            ReportAggregationState::Failed { .. }
-        )
+    fn report_error(&self) -> Option<ReportError> {
+        match self.report_aggregation.state() {
+            ReportAggregationState::Failed { report_error } => Some(*report_error),
+            _ => None,
+        }
     }
 
     fn with_failure(self, report_error: ReportError) -> Self {
@@ -1078,8 +1113,11 @@ where
         None
     }
 
-    fn is_failed(&self) -> bool {
-        matches!(self.state(), ReportAggregationMetadataState::Failed { .. })
+    fn report_error(&self) -> Option<ReportError> {
+        match self.state() {
+            ReportAggregationMetadataState::Failed { report_error } => Some(*report_error),
+            _ => None,
+        }
     }
 
     fn with_failure(self, report_error: ReportError) -> Self {
@@ -1129,8 +1167,8 @@ where
         self.as_ref().is_finished()
     }
 
-    fn is_failed(&self) -> bool {
-        self.as_ref().is_failed()
+    fn report_error(&self) -> Option<ReportError> {
+        self.as_ref().report_error()
     }
 
     fn with_failure(self, report_error: ReportError) -> Self {
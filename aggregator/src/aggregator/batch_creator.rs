@@ -1,6 +1,9 @@
 //! In-memory data structure to incrementally build leader-selected batches.
 
-use crate::aggregator::aggregation_job_writer::{AggregationJobWriter, InitialWrite};
+use crate::aggregator::{
+    aggregation_job_creator::AggregationJobCreationMetrics,
+    aggregation_job_writer::{AggregationJobWriter, InitialWrite},
+};
 use futures::future::try_join_all;
 use janus_aggregator_core::{
     AsyncAggregator,
@@ -11,13 +14,13 @@ use janus_aggregator_core::{
             ReportAggregationMetadataState, UnaggregatedReport,
         },
     },
+    task,
 };
 use janus_core::time::{Clock, DurationExt, TimeExt};
 use janus_messages::{
     AggregationJobStep, BatchId, Duration, Interval, ReportId, TaskId, Time,
     batch_mode::LeaderSelected,
 };
-use opentelemetry::metrics::Histogram;
 use prio::codec::Encode;
 use rand::random;
 use std::{
@@ -62,7 +65,9 @@ struct Properties {
     task_min_batch_size: usize,
     task_batch_time_window_size: Option<Duration>,
     task_time_precision: Duration,
-    aggregation_job_size_histogram: Histogram<u64>,
+    task_report_retention_window: Option<Duration>,
+    metrics: AggregationJobCreationMetrics,
+    creation_time: Time,
 }
 
 impl<'a, const SEED_SIZE: usize, A> BatchCreator<'a, SEED_SIZE, A>
@@ -77,6 +82,7 @@ where
         task_min_batch_size: usize,
         task_batch_time_window_size: Option<Duration>,
         task_time_precision: Duration,
+        task_report_retention_window: Option<Duration>,
         aggregation_job_writer: &'a mut AggregationJobWriter<
             SEED_SIZE,
             LeaderSelected,
@@ -84,7 +90,8 @@ where
             InitialWrite,
             ReportAggregationMetadata,
         >,
-        aggregation_job_size_histogram: Histogram<u64>,
+        metrics: AggregationJobCreationMetrics,
+        creation_time: Time,
     ) -> Self {
         Self {
             properties: Properties {
@@ -94,7 +101,9 @@ where
                 task_min_batch_size,
                 task_batch_time_window_size,
                 task_time_precision,
-                aggregation_job_size_histogram,
+                task_report_retention_window,
+                metrics,
+                creation_time,
             },
             aggregation_job_writer,
             buckets: HashMap::new(),
@@ -235,8 +244,7 @@ where
                             &mut bucket.unaggregated_reports,
                             aggregation_job_writer,
                             report_ids_to_scrub,
-                            properties.task_time_precision,
-                            &properties.aggregation_job_size_histogram,
+                            properties,
                         )?;
                         largest_outstanding_batch.add_reports(desired_aggregation_job_size);
                     } else {
@@ -265,8 +273,7 @@ where
                             &mut bucket.unaggregated_reports,
                             aggregation_job_writer,
                             report_ids_to_scrub,
-                            properties.task_time_precision,
-                            &properties.aggregation_job_size_histogram,
+                            properties,
                         )?;
                         largest_outstanding_batch.add_reports(desired_aggregation_job_size);
                     } else {
@@ -308,8 +315,7 @@ where
                     &mut bucket.unaggregated_reports,
                     aggregation_job_writer,
                     report_ids_to_scrub,
-                    properties.task_time_precision,
-                    &properties.aggregation_job_size_histogram,
+                    properties,
                 )?;
 
                 // Loop to the top of this method to create more aggregation jobs in this newly
@@ -336,8 +342,7 @@ where
             ReportAggregationMetadata,
         >,
         report_ids_to_scrub: &mut HashSet<ReportId>,
-        time_precision: Duration,
-        aggregation_job_size_histogram: &Histogram<u64>,
+        properties: &Properties,
     ) -> Result<(), Error> {
         let aggregation_job_id = random();
         debug!(
@@ -376,11 +381,11 @@ where
         let min_client_timestamp = min_client_timestamp.unwrap(); // unwrap safety: aggregation_job_size > 0
         let max_client_timestamp = max_client_timestamp.unwrap(); // unwrap safety: aggregation_job_size > 0
         let client_timestamp_interval = Interval::new(
-            min_client_timestamp.to_batch_interval_start(&time_precision)?,
+            min_client_timestamp.to_batch_interval_start(&properties.task_time_precision)?,
             max_client_timestamp
                 .difference(&min_client_timestamp)?
                 .add(&Duration::from_seconds(1))?
-                .round_up(&time_precision)?,
+                .round_up(&properties.task_time_precision)?,
         )?;
         let aggregation_job = AggregationJob::<SEED_SIZE, LeaderSelected, A>::new(
             task_id,
@@ -391,9 +396,13 @@ where
             AggregationJobState::Active,
             AggregationJobStep::from(0),
         );
-        aggregation_job_size_histogram.record(
-            u64::try_from(report_aggregations.len()).unwrap_or(u64::MAX),
-            &[],
+        properties.metrics.record_aggregation_job(
+            &task_id,
+            &task::BatchMode::LeaderSelected {
+                batch_time_window_size: properties.task_batch_time_window_size,
+            },
+            &properties.creation_time,
+            &report_aggregations,
         );
         aggregation_job_writer.put(aggregation_job, report_aggregations)?;
 
@@ -433,7 +442,9 @@ where
         self.aggregation_job_writer.write(tx, vdaf).await?;
 
         // Report scrubbing must wait until after report aggregations have been created,
-        // because they have a write-after-read antidependency on the report shares.
+        // because they have a write-after-read antidependency on the report shares. Reports of
+        // tasks with a report retention window are instead scrubbed by the garbage collector
+        // once the window has elapsed.
         try_join!(
             try_join_all(self.newly_filled_batches.iter().map(|batch_id| {
                 tx.mark_outstanding_batch_filled(&self.properties.task_id, batch_id)
@@ -441,6 +452,7 @@ where
             try_join_all(
                 self.report_ids_to_scrub
                     .iter()
+                    .filter(|_| self.properties.task_report_retention_window.is_none())
                     .map(|report_id| tx.scrub_client_report(&self.properties.task_id, report_id))
             ),
             try_join_all(
@@ -3,6 +3,7 @@ use super::{
     error::{ReportRejection, ReportRejectionReason},
 };
 use async_trait::async_trait;
+use itertools::Itertools as _;
 use janus_aggregator_core::{
     AsyncAggregator,
     batch_mode::{AccumulableBatchMode, CollectableBatchMode as CoreCollectableBatchMode},
@@ -107,7 +108,8 @@ impl CollectableBatchMode for TimeInterval {
         collect_interval: &Self::BatchIdentifier,
         aggregation_param: &A::AggregationParam,
     ) -> Result<(), datastore::Error> {
-        // Compute the aggregation parameters that have already been collected for.
+        // Compute the aggregation parameters that have already been collected for, in the order
+        // they were first used.
         let mut found_overlapping_nonequal_interval = false;
         let agg_params: Vec<_> = match task.role() {
             Role::Leader => tx
@@ -152,20 +154,12 @@ impl CollectableBatchMode for TimeInterval {
             ));
         }
 
-        // Check that the batch has not already been queried with a distinct aggregation parameter.
-        if agg_params
-            .iter()
-            .any(|agg_param| agg_param != aggregation_param)
-        {
-            return Err(datastore::Error::User(
-                Error::InvalidMessage(
-                    Some(*task.id()),
-                    "batch has already been collected with another aggregation parameter",
-                )
-                .into(),
-            ));
-        }
-        Ok(())
+        validate_aggregation_parameter::<SEED_SIZE, A>(
+            task,
+            &collect_interval.to_string(),
+            aggregation_param,
+            agg_params,
+        )
     }
 }
 
@@ -182,7 +176,8 @@ impl CollectableBatchMode for LeaderSelected {
         batch_id: &Self::BatchIdentifier,
         aggregation_param: &A::AggregationParam,
     ) -> Result<(), datastore::Error> {
-        // Compute the aggregation parameters that have already been collected for.
+        // Compute the aggregation parameters that have already been collected for, in the order
+        // they were first used.
         let agg_params: Vec<_> = match task.role() {
             Role::Leader => tx
                 .get_collection_jobs_by_batch_id::<SEED_SIZE, A>(vdaf, task.id(), batch_id)
@@ -201,19 +196,38 @@ impl CollectableBatchMode for LeaderSelected {
             _ => panic!("Unexpected task role {:?}", task.role()),
         };
 
-        // Check that the batch has not already been queried with a distinct aggregation parameter.
-        if agg_params
-            .iter()
-            .any(|agg_param| agg_param != aggregation_param)
-        {
-            return Err(datastore::Error::User(
-                Error::InvalidMessage(
-                    Some(*task.id()),
-                    "batch has already been collected with another aggregation parameter",
-                )
-                .into(),
-            ));
-        }
-        Ok(())
+        validate_aggregation_parameter::<SEED_SIZE, A>(
+            task,
+            &batch_id.to_string(),
+            aggregation_param,
+            agg_params,
+        )
+    }
+}
+
+/// Checks that a batch may be collected with the given aggregation parameter, per the VDAF's
+/// rules for which sequences of aggregation parameters a batch may be aggregated with.
+/// `previous_aggregation_params` are the aggregation parameters of earlier queries against the
+/// batch, in the order those queries were made.
+///
+/// Repeating a query with an aggregation parameter that has already been used for the batch is
+/// always permitted.
+fn validate_aggregation_parameter<const SEED_SIZE: usize, A: AsyncAggregator<SEED_SIZE>>(
+    task: &AggregatorTask,
+    batch_identifier: &str,
+    aggregation_param: &A::AggregationParam,
+    previous_aggregation_params: Vec<A::AggregationParam>,
+) -> Result<(), datastore::Error> {
+    if previous_aggregation_params.contains(aggregation_param) {
+        return Ok(());
+    }
+
+    let previous_aggregation_params: Vec<_> =
+        previous_aggregation_params.into_iter().unique().collect();
+    if !A::is_agg_param_valid(aggregation_param, &previous_aggregation_params) {
+        return Err(datastore::Error::User(
+            Error::InvalidAggregationParameter(*task.id(), batch_identifier.to_string()).into(),
+        ));
     }
+    Ok(())
 }
@@ -1,9 +1,15 @@
 //! Implements portions of collect sub-protocol for DAP leader and helper.
 
-use crate::aggregator::{
-    BatchAggregationsIterator, Error, RequestBody, aggregate_share::AggregateShareComputer,
-    batch_mode::CollectableBatchMode, http_handlers::AGGREGATE_SHARES_ROUTE,
-    send_request_to_helper,
+use crate::{
+    aggregator::{
+        BatchAggregationsIterator, Error, RequestBody, aggregate_share::AggregateShareComputer,
+        batch_mode::CollectableBatchMode, http_handlers::AGGREGATE_SHARES_ROUTE,
+        send_request_to_helper,
+    },
+    metrics::{
+        TaskMetricLabels, batch_collection_latency_histogram, batch_mode_label,
+        collection_job_latency_histogram,
+    },
 };
 use anyhow::bail;
 use backon::BackoffBuilder;
@@ -14,17 +20,20 @@ use janus_aggregator_core::{
     AsyncAggregator, AsyncAggregatorWithNoise, TIME_HISTOGRAM_BOUNDARIES,
     datastore::{
         self, Datastore,
-        models::{AcquiredCollectionJob, BatchAggregation, CollectionJobState, Lease},
+        models::{
+            AcquiredCollectionJob, BatchAggregation, CollectionJob, CollectionJobState,
+            JobStepOutcome, JobStepRecord, Lease,
+        },
     },
     task,
 };
 use janus_core::{
     retries::{is_retryable_http_client_error, is_retryable_http_status},
-    time::Clock,
+    time::{Clock, IntervalExt, TimeExt},
     vdaf_dispatch,
 };
 use janus_messages::{
-    AggregateShare, AggregateShareReq, BatchSelector, MediaType,
+    AggregateShare, AggregateShareReq, BatchSelector, MediaType, Time,
     batch_mode::{BatchMode, LeaderSelected, TimeInterval},
 };
 use opentelemetry::{
@@ -82,6 +91,12 @@ where
         }
     }
 
+    /// Labels collection job metrics with task IDs, as determined by `task_metric_labels`.
+    pub fn with_task_metric_labels(mut self, task_metric_labels: TaskMetricLabels) -> Self {
+        self.metrics.task_metric_labels = task_metric_labels;
+        self
+    }
+
     /// Step the provided collection job, for which a lease should have been acquired (though this
     /// should be idempotent). If the collection job runs to completion, the leader share, helper
     /// share, report count and report ID checksum will be written to the `collection_jobs` table,
@@ -247,7 +262,11 @@ where
                             tx.update_collection_job::<SEED_SIZE, B, A>(&collection_job),
                             tx.release_collection_job(&lease, None),
                         )?;
-                        metrics.jobs_finished_counter.add(1, &[]);
+                        metrics.record_job_finished(
+                            lease.leased(),
+                            &collection_job,
+                            &tx.clock().now(),
+                        );
                         return Ok(None);
                     }
 
@@ -259,6 +278,13 @@ where
                     {
                         let retry_delay = collection_retry_strategy
                             .compute_retry_delay(lease.leased().step_attempts());
+                        tx.put_collection_job_history_entry(
+                            lease.leased().task_id(),
+                            lease.leased().collection_job_id(),
+                            &JobStepRecord::new(JobStepOutcome::NotReady)
+                                .with_retry_delay(retry_delay),
+                        )
+                        .await?;
                         tx.release_collection_job(&lease, Some(&retry_delay))
                             .await?;
                         return Ok(None);
@@ -454,7 +480,11 @@ where
                             }).await?;
 
                             tx.release_collection_job(&lease, None).await?;
-                            metrics.jobs_finished_counter.add(1, &[]);
+                            metrics.record_job_finished(
+                                lease.leased(),
+                                collection_job.as_ref(),
+                                &tx.clock().now(),
+                            );
                         }
 
                         CollectionJobState::Deleted => {
@@ -621,7 +651,9 @@ where
                 {
                     Ok(_) => Ok(()),
                     Err(error) => {
-                        if !Self::is_retryable_error(&error) {
+                        let retryable = Self::is_retryable_error(&error);
+                        Self::record_step_failure(&datastore, &lease, &error, retryable).await;
+                        if !retryable {
                             // Make a best-effort attempt to immediately cancel the collection job.
                             // on fatal errors. This protects the helper from performing wasted
                             // work.
@@ -651,6 +683,40 @@ where
         }
     }
 
+    /// Makes a best-effort attempt to record the given error against the leased collection job, both
+    /// as the job's last error and in the job's history, so that operators can see why the job is
+    /// failing or was abandoned.
+    async fn record_step_failure<C: Clock>(
+        datastore: &Datastore<C>,
+        lease: &Lease<AcquiredCollectionJob>,
+        error: &Error,
+        retryable: bool,
+    ) {
+        let task_id = *lease.leased().task_id();
+        let collection_job_id = *lease.leased().collection_job_id();
+        let last_error = error.to_string();
+        let record = Arc::new(error.job_step_record(if retryable {
+            JobStepOutcome::RetryableError
+        } else {
+            JobStepOutcome::FatalError
+        }));
+        if let Err(error) = datastore
+            .run_tx("record_collection_job_step_failure", |tx| {
+                let last_error = last_error.clone();
+                let record = Arc::clone(&record);
+                Box::pin(async move {
+                    tx.set_collection_job_last_error(&task_id, &collection_job_id, &last_error)
+                        .await?;
+                    tx.put_collection_job_history_entry(&task_id, &collection_job_id, &record)
+                        .await
+                })
+            })
+            .await
+        {
+            warn!(?error, "Couldn't record collection job error");
+        }
+    }
+
     /// Determines whether the given [`Error`] is retryable in the context of collection job
     /// processing.
     fn is_retryable_error(error: &Error) -> bool {
@@ -676,11 +742,14 @@ where
 #[derive(Clone)]
 struct CollectionJobDriverMetrics {
     jobs_finished_counter: Counter<u64>,
+    job_latency_histogram: Histogram<f64>,
+    batch_collection_latency_histogram: Histogram<f64>,
     http_request_duration_histogram: Histogram<f64>,
     jobs_abandoned_counter: Counter<u64>,
     deleted_jobs_encountered_counter: Counter<u64>,
     unexpected_job_state_counter: Counter<u64>,
     job_steps_retried_counter: Counter<u64>,
+    task_metric_labels: TaskMetricLabels,
 }
 
 impl CollectionJobDriverMetrics {
@@ -737,11 +806,59 @@ impl CollectionJobDriverMetrics {
 
         Self {
             jobs_finished_counter,
+            job_latency_histogram: collection_job_latency_histogram(meter),
+            batch_collection_latency_histogram: batch_collection_latency_histogram(meter),
             http_request_duration_histogram,
             jobs_abandoned_counter,
             deleted_jobs_encountered_counter,
             unexpected_job_state_counter,
             job_steps_retried_counter,
+            task_metric_labels: TaskMetricLabels::default(),
+        }
+    }
+
+    /// Records that a collection job finished at time `now`.
+    fn record_job_finished<const SEED_SIZE: usize, B, A>(
+        &self,
+        acquired_job: &AcquiredCollectionJob,
+        collection_job: &CollectionJob<SEED_SIZE, B, A>,
+        now: &Time,
+    ) where
+        B: CollectableBatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    {
+        self.jobs_finished_counter.add(1, &[]);
+        let attributes = self.task_metric_labels.attributes(
+            acquired_job.task_id(),
+            [KeyValue::new(
+                "batch_mode",
+                batch_mode_label(acquired_job.batch_mode()),
+            )],
+        );
+        self.job_latency_histogram.record(
+            now.saturating_difference(acquired_job.created_at())
+                .as_seconds() as f64,
+            &attributes,
+        );
+
+        // A time-interval batch closes at the end of its batch interval. A leader-selected batch
+        // has no such interval, so it is taken to close at the end of the interval spanned by its
+        // reports' timestamps.
+        let batch_closed_at = match B::to_batch_interval(collection_job.batch_identifier()) {
+            Some(batch_interval) => Some(batch_interval.end()),
+            None => match collection_job.state() {
+                CollectionJobState::Finished {
+                    client_timestamp_interval,
+                    ..
+                } => Some(client_timestamp_interval.end()),
+                _ => None,
+            },
+        };
+        if let Some(batch_closed_at) = batch_closed_at {
+            self.batch_collection_latency_histogram.record(
+                now.saturating_difference(&batch_closed_at).as_seconds() as f64,
+                &attributes,
+            );
         }
     }
 }
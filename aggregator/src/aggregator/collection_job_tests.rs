@@ -256,15 +256,25 @@ impl CollectionJobTestCase {
 pub(crate) async fn setup_collection_job_test_case(
     role: Role,
     batch_mode: BatchMode,
+) -> CollectionJobTestCase {
+    setup_collection_job_test_case_for_task(
+        role,
+        TaskBuilder::new(
+            batch_mode,
+            AggregationMode::Synchronous,
+            VdafInstance::Fake { rounds: 1 },
+        )
+        .build(),
+    )
+    .await
+}
+
+pub(crate) async fn setup_collection_job_test_case_for_task(
+    role: Role,
+    task: Task,
 ) -> CollectionJobTestCase {
     install_test_trace_subscriber();
 
-    let task = TaskBuilder::new(
-        batch_mode,
-        AggregationMode::Synchronous,
-        VdafInstance::Fake { rounds: 1 },
-    )
-    .build();
     let role_task = task.view_for_role(role).unwrap();
     let clock = MockClock::default();
     let ephemeral_datastore = ephemeral_datastore().await;
@@ -1,4 +1,10 @@
-use janus_aggregator_core::{datastore, task};
+use janus_aggregator_core::{
+    datastore::{
+        self,
+        models::{JobStepOutcome, JobStepRecord},
+    },
+    task,
+};
 use janus_core::http::HttpErrorResponse;
 use janus_messages::{
     AggregationJobId, AggregationJobStep, CollectionJobId, HpkeConfigId, Interval, ReportError,
@@ -100,6 +106,11 @@ pub enum Error {
     /// previously collected one.
     #[error("task {0}: queried batch {1} overlaps with previously collected batch(es)")]
     BatchOverlap(TaskId, Interval),
+    /// Corresponds to `invalidAggregationParameter` in DAP. A collect or aggregate share request
+    /// was rejected because the VDAF does not permit its aggregation parameter to follow the
+    /// aggregation parameters the batch has previously been collected with.
+    #[error("task {0}: aggregation parameter is invalid for batch {1}")]
+    InvalidAggregationParameter(TaskId, String),
     /// HPKE failure.
     #[error("HPKE error: {0}")]
     Hpke(#[from] janus_core::hpke::Error),
@@ -143,6 +154,14 @@ pub enum Error {
     ClientDisconnected,
     #[error("too many requests")]
     TooManyRequests,
+    /// A collect or aggregate share request was rejected because collecting the batch would spend
+    /// more than the task's differential privacy budget.
+    #[error("task {0}: differential privacy budget exhausted")]
+    DpBudgetExhausted(TaskId),
+    /// An aggregation, collection or aggregate share request was rejected because the task is
+    /// archived, and its report data has been garbage collected.
+    #[error("task {0}: task is archived")]
+    TaskArchived(TaskId),
 }
 
 /// A newtype around `Arc<Error>`. This is needed to host a customized implementation of
@@ -226,6 +245,7 @@ pub enum ReportRejectionReason {
     TooEarly,
     OutdatedHpkeConfig(HpkeConfigId),
     TaskNotStarted,
+    TaskNotAcceptingReports,
 }
 
 impl ReportRejectionReason {
@@ -243,6 +263,7 @@ impl ReportRejectionReason {
                 "Report is using an outdated HPKE configuration."
             }
             ReportRejectionReason::TaskNotStarted => "Task has not started.",
+            ReportRejectionReason::TaskNotAcceptingReports => "Task is not accepting reports.",
         }
     }
 }
@@ -299,6 +320,7 @@ impl Error {
             Error::Url(_) => "url",
             Error::BatchMismatch { .. } => "batch_mismatch",
             Error::BatchOverlap(_, _) => "batch_overlap",
+            Error::InvalidAggregationParameter(_, _) => "invalid_aggregation_parameter",
             Error::Hpke(_) => "hpke",
             Error::TaskParameters(_) => "task_parameters",
             Error::HttpClient(_) => "http_client",
@@ -312,6 +334,33 @@ impl Error {
             Error::DifferentialPrivacy(_) => "differential_privacy",
             Error::ClientDisconnected => "client_disconnected",
             Error::TooManyRequests => "too_many_requests",
+            Error::DpBudgetExhausted(_) => "dp_budget_exhausted",
+            Error::TaskArchived(_) => "task_archived",
+        }
+    }
+
+    /// Builds a [`JobStepRecord`] describing an unsuccessful job step which failed with this
+    /// error, including the HTTP status and problem type returned by the peer aggregator, if any.
+    pub(crate) fn job_step_record(&self, outcome: JobStepOutcome) -> JobStepRecord {
+        let mut record = JobStepRecord::new(outcome).with_error(self.to_string());
+        if let Some(http_error_response) = self.http_error_response() {
+            record = record.with_http_status(http_error_response.status().as_u16());
+            if let Some(problem_type) = http_error_response.type_uri() {
+                record = record.with_problem_type(problem_type.to_string());
+            }
+        }
+        record
+    }
+
+    /// Returns the HTTP error response received from the peer aggregator which caused this error,
+    /// if any.
+    fn http_error_response(&self) -> Option<&HttpErrorResponse> {
+        match self {
+            Error::Http(http_error_response) => Some(http_error_response),
+            Error::Datastore(datastore::Error::User(error)) => error
+                .downcast_ref::<Error>()
+                .and_then(Error::http_error_response),
+            _ => None,
         }
     }
 }
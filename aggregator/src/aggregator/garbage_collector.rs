@@ -1,19 +1,33 @@
 use anyhow::{Context, Error, Result};
 use futures::future::{OptionFuture, join_all, try_join_all};
-use janus_aggregator_core::datastore::{self, Datastore};
+use janus_aggregator_core::{
+    datastore::{self, Datastore, storage::Storage},
+    task::TaskState,
+};
 use janus_core::time::Clock;
 use janus_messages::TaskId;
 use opentelemetry::metrics::{Counter, Meter};
-use std::sync::{
-    Arc,
-    atomic::{AtomicU64, Ordering},
+use std::{
+    marker::PhantomData,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
 };
 use tokio::{sync::Semaphore, try_join};
 use tracing::error;
 
-pub struct GarbageCollector<C: Clock> {
+/// Deletes & scrubs expired client reports, aggregation artifacts and collection artifacts.
+///
+/// # Storage
+///
+/// Each GC operation is available on
+/// [`StorageTransaction`](janus_aggregator_core::datastore::storage::StorageTransaction), so tests
+/// may run the garbage collector against an in-memory [`Storage`] backend rather than the
+/// [`Datastore`].
+pub struct GarbageCollector<C: Clock, S: Storage<C> = Datastore<C>> {
     // Dependencies.
-    datastore: Arc<Datastore<C>>,
+    datastore: Arc<S>,
 
     // Configuration.
     report_limit: u64,
@@ -24,13 +38,16 @@ pub struct GarbageCollector<C: Clock> {
 
     // Metrics.
     deleted_report_counter: Counter<u64>,
+    scrubbed_report_counter: Counter<u64>,
     deleted_aggregation_job_counter: Counter<u64>,
     deleted_batch_counter: Counter<u64>,
+
+    _clock: PhantomData<C>,
 }
 
-impl<C: Clock> GarbageCollector<C> {
+impl<C: Clock, S: Storage<C>> GarbageCollector<C, S> {
     pub fn new(
-        datastore: Arc<Datastore<C>>,
+        datastore: Arc<S>,
         meter: &Meter,
         report_limit: u64,
         aggregation_limit: u64,
@@ -43,6 +60,14 @@ impl<C: Clock> GarbageCollector<C> {
             .with_description("Count of client reports deleted by the garbage collector.")
             .with_unit("{report}")
             .build();
+        let scrubbed_report_counter = meter
+            .u64_counter("janus_gc_scrubbed_reports")
+            .with_description(
+                "Count of client reports scrubbed by the garbage collector after their task's \
+                 report retention window elapsed.",
+            )
+            .with_unit("{report}")
+            .build();
         let deleted_aggregation_job_counter = meter
             .u64_counter("janus_gc_deleted_aggregation_jobs")
             .with_description("Count of aggregation jobs deleted by the garbage collector.")
@@ -55,6 +80,7 @@ impl<C: Clock> GarbageCollector<C> {
             .build();
 
         deleted_report_counter.add(0, &[]);
+        scrubbed_report_counter.add(0, &[]);
         deleted_aggregation_job_counter.add(0, &[]);
         deleted_batch_counter.add(0, &[]);
 
@@ -66,10 +92,12 @@ impl<C: Clock> GarbageCollector<C> {
             aggregation_limit,
             collection_limit,
             deleted_report_counter,
+            scrubbed_report_counter,
             deleted_aggregation_job_counter,
             deleted_batch_counter,
             tasks_per_tx,
             concurrent_tx_semaphore,
+            _clock: PhantomData,
         }
     }
 
@@ -77,8 +105,8 @@ impl<C: Clock> GarbageCollector<C> {
     pub async fn run(&self) -> Result<()> {
         // TODO(#224): add support for handling only a subset of tasks in a single job (i.e. sharding).
 
-        // Retrieve tasks.
-        let task_ids: Vec<_> = self
+        // Retrieve tasks. All report data of archived tasks is deleted, regardless of its age.
+        let tasks: Vec<_> = self
             .datastore
             .run_tx("garbage_collector_get_tasks", |tx| {
                 Box::pin(async move { tx.get_aggregator_tasks().await })
@@ -86,63 +114,95 @@ impl<C: Clock> GarbageCollector<C> {
             .await
             .context("couldn't retrieve tasks")?
             .into_iter()
-            .map(|task| *task.id())
+            .map(|task| (*task.id(), task.state() == &TaskState::Archived))
             .collect();
 
         // Run GC for each task.
-        join_all(
-            task_ids
-                .chunks(self.tasks_per_tx)
-                .map(|task_ids| async move {
-                    // unwrap safety: we never close concurrent_tx_semaphore.
-                    let _permit = OptionFuture::from(
-                        self.concurrent_tx_semaphore
-                            .as_ref()
-                            .map(Semaphore::acquire),
-                    )
-                    .await
-                    .transpose()
-                    .expect("concurrent_tx_semaphore has been closed");
+        join_all(tasks.chunks(self.tasks_per_tx).map(|tasks| async move {
+            // unwrap safety: we never close concurrent_tx_semaphore.
+            let _permit = OptionFuture::from(
+                self.concurrent_tx_semaphore
+                    .as_ref()
+                    .map(Semaphore::acquire),
+            )
+            .await
+            .transpose()
+            .expect("concurrent_tx_semaphore has been closed");
 
-                    if let Err(err) = self.gc_tasks(task_ids.to_vec()).await {
-                        error!(?err, "GC failure")
-                    }
-                }),
-        )
+            if let Err(err) = self.gc_tasks(tasks.to_vec()).await {
+                error!(?err, "GC failure")
+            }
+        }))
         .await;
         Ok(())
     }
 
+    /// Runs GC for the given tasks, each paired with whether the task is archived.
     #[tracing::instrument(name = "GarbageCollector::gc_tasks", skip(self))]
-    async fn gc_tasks(&self, task_ids: Vec<TaskId>) -> Result<()> {
-        let task_ids = Arc::new(task_ids);
-        let (client_reports_deleted, aggregation_jobs_deleted, batches_deleted) = self
+    async fn gc_tasks(&self, tasks: Vec<(TaskId, bool)>) -> Result<()> {
+        let tasks = Arc::new(tasks);
+        let (
+            client_reports_deleted,
+            client_reports_scrubbed,
+            aggregation_jobs_deleted,
+            batches_deleted,
+        ) = self
             .datastore
             .run_tx("garbage_collector", |tx| {
-                let task_ids = Arc::clone(&task_ids);
+                let tasks = Arc::clone(&tasks);
                 let report_limit = self.report_limit;
                 let aggregation_limit = self.aggregation_limit;
                 let collection_limit = self.collection_limit;
 
                 Box::pin(async move {
                     let client_reports_deleted = Arc::new(AtomicU64::new(0));
+                    let client_reports_scrubbed = Arc::new(AtomicU64::new(0));
                     let aggregation_jobs_deleted = Arc::new(AtomicU64::new(0));
                     let batches_deleted = Arc::new(AtomicU64::new(0));
 
-                    try_join_all(task_ids.iter().map(|task_id| {
+                    try_join_all(tasks.iter().map(|(task_id, archived)| {
                         let client_reports_deleted = Arc::clone(&client_reports_deleted);
+                        let client_reports_scrubbed = Arc::clone(&client_reports_scrubbed);
                         let aggregation_jobs_deleted = Arc::clone(&aggregation_jobs_deleted);
                         let batches_deleted = Arc::clone(&batches_deleted);
 
                         async move {
-                            let (report_count, agg_job_count, batch_count) = try_join!(
-                                tx.delete_expired_client_reports(task_id, report_limit),
-                                tx.delete_expired_aggregation_artifacts(task_id, aggregation_limit),
-                                tx.delete_expired_collection_artifacts(task_id, collection_limit),
-                            )
-                            .with_context(|| format!("Couldn't GC {task_id}"))?;
+                            let (report_count, scrubbed_count, agg_job_count, batch_count) =
+                                try_join!(
+                                    async {
+                                        if *archived {
+                                            tx.delete_all_client_reports(task_id, report_limit)
+                                                .await
+                                        } else {
+                                            tx.delete_expired_client_reports(task_id, report_limit)
+                                                .await
+                                        }
+                                    },
+                                    tx.scrub_retained_client_reports(task_id, report_limit),
+                                    async {
+                                        if *archived {
+                                            tx.delete_all_aggregation_artifacts(
+                                                task_id,
+                                                aggregation_limit,
+                                            )
+                                            .await
+                                        } else {
+                                            tx.delete_expired_aggregation_artifacts(
+                                                task_id,
+                                                aggregation_limit,
+                                            )
+                                            .await
+                                        }
+                                    },
+                                    tx.delete_expired_collection_artifacts(
+                                        task_id,
+                                        collection_limit
+                                    ),
+                                )
+                                .with_context(|| format!("Couldn't GC {task_id}"))?;
 
                             client_reports_deleted.fetch_add(report_count, Ordering::Relaxed);
+                            client_reports_scrubbed.fetch_add(scrubbed_count, Ordering::Relaxed);
                             aggregation_jobs_deleted.fetch_add(agg_job_count, Ordering::Relaxed);
                             batches_deleted.fetch_add(batch_count, Ordering::Relaxed);
 
@@ -154,6 +214,7 @@ impl<C: Clock> GarbageCollector<C> {
 
                     Ok((
                         client_reports_deleted.load(Ordering::Relaxed),
+                        client_reports_scrubbed.load(Ordering::Relaxed),
                         aggregation_jobs_deleted.load(Ordering::Relaxed),
                         batches_deleted.load(Ordering::Relaxed),
                     ))
@@ -162,6 +223,8 @@ impl<C: Clock> GarbageCollector<C> {
             .await?;
 
         self.deleted_report_counter.add(client_reports_deleted, &[]);
+        self.scrubbed_report_counter
+            .add(client_reports_scrubbed, &[]);
         self.deleted_aggregation_job_counter
             .add(aggregation_jobs_deleted, &[]);
         self.deleted_batch_counter.add(batches_deleted, &[]);
@@ -180,9 +243,13 @@ mod tests {
                 BatchAggregationState, CollectionJob, CollectionJobState, LeaderStoredReport,
                 ReportAggregation, ReportAggregationState,
             },
+            storage::{
+                AggregationJobRecord, BatchAggregationRecord, CollectionJobRecord, InMemoryStorage,
+                Storage,
+            },
             test_util::ephemeral_datastore,
         },
-        task::{self, AggregationMode, test_util::TaskBuilder},
+        task::{self, AggregationMode, TaskState, test_util::TaskBuilder},
         test_util::noop_meter,
     };
     use janus_core::{
@@ -197,7 +264,7 @@ mod tests {
     };
     use prio::vdaf::dummy;
     use rand::random;
-    use std::sync::Arc;
+    use std::{sync::Arc, time::Duration as StdDuration};
 
     const OLDEST_ALLOWED_REPORT_TIMESTAMP: Time = Time::from_seconds_since_epoch(1000);
     const REPORT_EXPIRY_AGE: Duration = Duration::from_seconds(500);
@@ -308,7 +375,7 @@ mod tests {
             1,
             Some(1),
         )
-        .gc_tasks(Vec::from([*task.id()]))
+        .gc_tasks(Vec::from([(*task.id(), false)]))
         .await
         .unwrap();
 
@@ -366,6 +433,144 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn gc_task_leader_time_interval_in_memory_storage() {
+        install_test_trace_subscriber();
+
+        let clock = MockClock::new(OLDEST_ALLOWED_REPORT_TIMESTAMP);
+        let storage = Arc::new(InMemoryStorage::new(clock.clone()));
+        let task = TaskBuilder::new(
+            task::BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Fake { rounds: 1 },
+        )
+        .with_report_expiry_age(Some(REPORT_EXPIRY_AGE))
+        .with_time_precision(Duration::from_seconds(10))
+        .build()
+        .leader_view()
+        .unwrap();
+        let client_timestamp = clock.now().sub(&Duration::from_seconds(10)).unwrap();
+        let client_timestamp_interval =
+            Interval::new(client_timestamp, *task.time_precision()).unwrap();
+        let batch_aggregation =
+            BatchAggregationRecord::try_from(
+                &BatchAggregation::<0, TimeInterval, dummy::Vdaf>::new(
+                    *task.id(),
+                    client_timestamp_interval, // unrealistic, but induces GC
+                    dummy::AggregationParam(0),
+                    0,
+                    client_timestamp_interval,
+                    BatchAggregationState::Collected {
+                        aggregate_share: Some(dummy::AggregateShare(11)),
+                        report_count: 1,
+                        checksum: random(),
+                        aggregation_jobs_created: 3,
+                        aggregation_jobs_terminated: 3,
+                    },
+                ),
+            )
+            .unwrap();
+
+        // Setup.
+        storage
+            .run_tx("test", |tx| {
+                let (task, batch_aggregation) = (task.clone(), batch_aggregation.clone());
+                Box::pin(async move {
+                    tx.put_aggregator_task(&task).await?;
+
+                    // Client report artifacts.
+                    tx.put_scrubbed_report(task.id(), &random(), &client_timestamp)
+                        .await?;
+
+                    // Aggregation artifacts.
+                    tx.put_aggregation_job_record(&AggregationJobRecord::try_from(
+                        &AggregationJob::<0, TimeInterval, dummy::Vdaf>::new(
+                            *task.id(),
+                            random(),
+                            dummy::AggregationParam(0),
+                            (),
+                            client_timestamp_interval,
+                            AggregationJobState::Active,
+                            AggregationJobStep::from(0),
+                        ),
+                    )?)
+                    .await?;
+
+                    // Collection artifacts.
+                    tx.put_batch_aggregation_record(&batch_aggregation).await?;
+                    tx.put_collection_job_record(&CollectionJobRecord::try_from(&CollectionJob::<
+                        0,
+                        TimeInterval,
+                        dummy::Vdaf,
+                    >::new(
+                        *task.id(),
+                        random(),
+                        Query::new_time_interval(client_timestamp_interval),
+                        dummy::AggregationParam(0),
+                        client_timestamp_interval,
+                        CollectionJobState::Start,
+                    ))?)
+                    .await
+                })
+            })
+            .await
+            .unwrap();
+
+        // Advance the clock by the expiry age and a time precision interval to "enable" report expiry.
+        clock.advance(&REPORT_EXPIRY_AGE);
+        clock.advance(task.time_precision());
+
+        // Run.
+        GarbageCollector::new(
+            Arc::clone(&storage),
+            &noop_meter(),
+            u64::try_from(i64::MAX).unwrap(),
+            u64::try_from(i64::MAX).unwrap(),
+            u64::try_from(i64::MAX).unwrap(),
+            1,
+            Some(1),
+        )
+        .gc_tasks(Vec::from([(*task.id(), false)]))
+        .await
+        .unwrap();
+
+        // Reset the clock to "undo" read-based expiry.
+        clock.set(OLDEST_ALLOWED_REPORT_TIMESTAMP);
+
+        // Verify. None of the artifacts can be read back, and the batch aggregation can be written
+        // again, which would fail had it not been deleted.
+        storage
+            .run_tx("test", |tx| {
+                let (task, batch_aggregation) = (task.clone(), batch_aggregation.clone());
+                Box::pin(async move {
+                    assert_eq!(
+                        tx.count_client_reports_for_interval(task.id(), &client_timestamp_interval)
+                            .await
+                            .unwrap(),
+                        0
+                    );
+                    assert!(
+                        tx.acquire_incomplete_aggregation_jobs(&StdDuration::from_secs(60), 10)
+                            .await
+                            .unwrap()
+                            .is_empty()
+                    );
+                    assert!(
+                        tx.acquire_incomplete_collection_jobs(&StdDuration::from_secs(60), 10)
+                            .await
+                            .unwrap()
+                            .is_empty()
+                    );
+                    tx.put_batch_aggregation_record(&batch_aggregation)
+                        .await
+                        .unwrap();
+                    Ok(())
+                })
+            })
+            .await
+            .unwrap();
+    }
+
     #[tokio::test]
     async fn gc_task_helper_time_interval() {
         install_test_trace_subscriber();
@@ -498,7 +703,7 @@ mod tests {
             1,
             Some(1),
         )
-        .gc_tasks(Vec::from([*task.id()]))
+        .gc_tasks(Vec::from([(*task.id(), false)]))
         .await
         .unwrap();
 
@@ -670,7 +875,7 @@ mod tests {
             1,
             Some(1),
         )
-        .gc_tasks(Vec::from([*task.id()]))
+        .gc_tasks(Vec::from([(*task.id(), false)]))
         .await
         .unwrap();
 
@@ -872,7 +1077,7 @@ mod tests {
             1,
             Some(1),
         )
-        .gc_tasks(Vec::from([*task.id()]))
+        .gc_tasks(Vec::from([(*task.id(), false)]))
         .await
         .unwrap();
 
@@ -932,4 +1137,211 @@ mod tests {
         .await
         .unwrap();
     }
+
+    #[tokio::test]
+    async fn gc_task_leader_report_retention() {
+        install_test_trace_subscriber();
+
+        let clock = MockClock::new(OLDEST_ALLOWED_REPORT_TIMESTAMP);
+        let ephemeral_datastore = ephemeral_datastore().await;
+        let ds = Arc::new(ephemeral_datastore.datastore(clock.clone()).await);
+        let report_retention_window = Duration::from_seconds(100);
+
+        // Setup.
+        let (task, report) = ds
+            .run_unnamed_tx(|tx| {
+                let clock = clock.clone();
+                Box::pin(async move {
+                    let task = TaskBuilder::new(
+                        task::BatchMode::TimeInterval,
+                        AggregationMode::Synchronous,
+                        VdafInstance::Fake { rounds: 1 },
+                    )
+                    .with_report_expiry_age(Some(REPORT_EXPIRY_AGE))
+                    .with_report_retention_window(Some(report_retention_window))
+                    .with_time_precision(Duration::from_seconds(10))
+                    .build()
+                    .leader_view()
+                    .unwrap();
+                    tx.put_aggregator_task(&task).await?;
+
+                    let report = LeaderStoredReport::new_dummy(*task.id(), clock.now());
+                    tx.put_client_report(&report).await.unwrap();
+                    tx.mark_report_aggregated(task.id(), report.metadata().id())
+                        .await
+                        .unwrap();
+
+                    Ok((task, report))
+                })
+            })
+            .await
+            .unwrap();
+        let gc = GarbageCollector::new(
+            Arc::clone(&ds),
+            &noop_meter(),
+            u64::try_from(i64::MAX).unwrap(),
+            u64::try_from(i64::MAX).unwrap(),
+            u64::try_from(i64::MAX).unwrap(),
+            1,
+            Some(1),
+        );
+
+        // Run within the retention window, and verify the report is retained.
+        clock.advance(&Duration::from_seconds(50));
+        gc.gc_tasks(Vec::from([(*task.id(), false)])).await.unwrap();
+        let retrieved_report = ds
+            .run_unnamed_tx(|tx| {
+                let (task_id, report_id) = (*task.id(), *report.metadata().id());
+                Box::pin(async move {
+                    tx.get_client_report::<0, dummy::Vdaf>(
+                        &dummy::Vdaf::new(1),
+                        &task_id,
+                        &report_id,
+                    )
+                    .await
+                })
+            })
+            .await
+            .unwrap();
+        assert_eq!(retrieved_report, Some(report.clone()));
+
+        // Run once the retention window has elapsed, and verify the report is scrubbed but not
+        // yet deleted.
+        clock.advance(&Duration::from_seconds(51));
+        gc.gc_tasks(Vec::from([(*task.id(), false)])).await.unwrap();
+        ds.run_unnamed_tx(|tx| {
+            let (task_id, report_id) = (*task.id(), *report.metadata().id());
+            Box::pin(async move {
+                tx.verify_client_report_scrubbed(&task_id, &report_id).await;
+                Ok(())
+            })
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn gc_task_archived() {
+        install_test_trace_subscriber();
+
+        let clock = MockClock::new(OLDEST_ALLOWED_REPORT_TIMESTAMP);
+        let ephemeral_datastore = ephemeral_datastore().await;
+        let ds = Arc::new(ephemeral_datastore.datastore(clock.clone()).await);
+        let vdaf = dummy::Vdaf::new(1);
+
+        // Setup: an archived task with unexpired report & aggregation artifacts, and a collection
+        // job.
+        let task = ds
+            .run_unnamed_tx(|tx| {
+                let clock = clock.clone();
+                Box::pin(async move {
+                    let task = TaskBuilder::new(
+                        task::BatchMode::TimeInterval,
+                        AggregationMode::Synchronous,
+                        VdafInstance::Fake { rounds: 1 },
+                    )
+                    .with_report_expiry_age(Some(REPORT_EXPIRY_AGE))
+                    .with_time_precision(Duration::from_seconds(10))
+                    .with_state(TaskState::Archived)
+                    .build()
+                    .leader_view()
+                    .unwrap();
+                    tx.put_aggregator_task(&task).await?;
+
+                    let client_timestamp = clock.now().sub(&Duration::from_seconds(10)).unwrap();
+                    let report = LeaderStoredReport::new_dummy(*task.id(), client_timestamp);
+                    tx.put_client_report(&report).await.unwrap();
+
+                    let aggregation_job_id = random();
+                    tx.put_aggregation_job(&AggregationJob::<0, TimeInterval, dummy::Vdaf>::new(
+                        *task.id(),
+                        aggregation_job_id,
+                        dummy::AggregationParam(0),
+                        (),
+                        Interval::new(client_timestamp, *task.time_precision()).unwrap(),
+                        AggregationJobState::Finished,
+                        AggregationJobStep::from(1),
+                    ))
+                    .await
+                    .unwrap();
+                    tx.put_report_aggregation(
+                        &report.as_leader_init_report_aggregation(aggregation_job_id, 0),
+                    )
+                    .await
+                    .unwrap();
+
+                    let batch_identifier =
+                        Interval::new(client_timestamp, *task.time_precision()).unwrap();
+                    tx.put_collection_job(&CollectionJob::<0, TimeInterval, dummy::Vdaf>::new(
+                        *task.id(),
+                        random(),
+                        Query::new_time_interval(batch_identifier),
+                        dummy::AggregationParam(0),
+                        batch_identifier,
+                        CollectionJobState::Start,
+                    ))
+                    .await
+                    .unwrap();
+
+                    Ok(task)
+                })
+            })
+            .await
+            .unwrap();
+
+        // Run, without advancing the clock.
+        GarbageCollector::new(
+            Arc::clone(&ds),
+            &noop_meter(),
+            u64::try_from(i64::MAX).unwrap(),
+            u64::try_from(i64::MAX).unwrap(),
+            u64::try_from(i64::MAX).unwrap(),
+            1,
+            None,
+        )
+        .run()
+        .await
+        .unwrap();
+
+        // Verify: the report & aggregation artifacts were deleted, but the collection job was not.
+        ds.run_unnamed_tx(|tx| {
+            let (vdaf, task_id) = (vdaf.clone(), *task.id());
+            Box::pin(async move {
+                assert!(
+                    tx.get_client_reports_for_task::<0, dummy::Vdaf>(&vdaf, &task_id)
+                        .await
+                        .unwrap()
+                        .is_empty()
+                );
+                assert!(
+                    tx.get_aggregation_jobs_for_task::<0, TimeInterval, dummy::Vdaf>(&task_id)
+                        .await
+                        .unwrap()
+                        .is_empty()
+                );
+                assert!(
+                    tx.get_report_aggregations_for_task::<0, dummy::Vdaf>(
+                        &vdaf,
+                        &Role::Leader,
+                        &task_id,
+                    )
+                    .await
+                    .unwrap()
+                    .is_empty()
+                );
+                assert_eq!(
+                    tx.get_collection_jobs_for_task::<0, TimeInterval, dummy::Vdaf>(
+                        &vdaf, &task_id,
+                    )
+                    .await
+                    .unwrap()
+                    .len(),
+                    1
+                );
+                Ok(())
+            })
+        })
+        .await
+        .unwrap();
+    }
 }
@@ -1,16 +1,23 @@
 use super::{
     Aggregator, Config, Error,
     error::{ArcError, ReportRejectionReason},
+    ohttp_gateway::{
+        InnerRequestError, InnerUploadRequest, OHTTP_KEYS_MEDIA_TYPE, OHTTP_REQUEST_MEDIA_TYPE,
+        OHTTP_RESPONSE_MEDIA_TYPE, encode_inner_response, parse_inner_request,
+    },
     queue::{LIFORequestQueue, queued_lifo},
 };
-use crate::aggregator::problem_details::{ProblemDetailsConnExt, ProblemDocument};
+use crate::{
+    aggregator::problem_details::{ProblemDetailsConnExt, ProblemDocument},
+    cache::HpkeKeypairCache,
+};
 use anyhow::Context;
 use async_trait::async_trait;
 use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use janus_aggregator_api::BYTES_HISTOGRAM_BOUNDARIES;
 use janus_aggregator_core::{
     TIME_HISTOGRAM_BOUNDARIES, datastore::Datastore, datastore::Error as datastoreError,
-    instrumented, taskprov::taskprov_task_id,
+    instrumented_with_trace_context, taskprov::taskprov_task_id,
 };
 use janus_core::{
     Runtime,
@@ -22,7 +29,7 @@ use janus_core::{
 use janus_messages::{
     AggregateShare, AggregateShareReq, AggregationJobContinueReq, AggregationJobId,
     AggregationJobInitializeReq, AggregationJobResp, AggregationJobStep, CollectionJobId,
-    CollectionJobReq, CollectionJobResp, HpkeConfigList, MediaType, Report, TaskId,
+    CollectionJobReq, CollectionJobResp, HpkeConfigId, HpkeConfigList, MediaType, Report, TaskId,
     batch_mode::TimeInterval, codec::Decode, problem_type::DapProblemType, taskprov::TaskConfig,
 };
 use mime::Mime;
@@ -35,6 +42,7 @@ use querystring::querify;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::{borrow::Cow, time::Duration as StdDuration};
+use tokio::sync::watch;
 use tracing::warn;
 use trillium::{Conn, Handler, KnownHeaderName, Status};
 use trillium_api::{State, TryFromConn, api};
@@ -138,6 +146,10 @@ async fn run_error_handler(error: &Error, mut conn: Conn) -> Conn {
         Error::BatchOverlap(task_id, _) => conn.with_problem_document(
             &ProblemDocument::new_dap(DapProblemType::BatchOverlap).with_task_id(task_id),
         ),
+        Error::InvalidAggregationParameter(task_id, _) => conn.with_problem_document(
+            &ProblemDocument::new_dap(DapProblemType::InvalidAggregationParameter)
+                .with_task_id(task_id),
+        ),
         Error::BatchMismatch(inner) => conn.with_problem_document(
             &ProblemDocument::new_dap(DapProblemType::BatchMismatch)
                 .with_task_id(&inner.task_id)
@@ -191,6 +203,30 @@ async fn run_error_handler(error: &Error, mut conn: Conn) -> Conn {
                 "again later."
             )),
         ),
+        Error::DpBudgetExhausted(task_id) => conn.with_problem_document(
+            &ProblemDocument::new(
+                "https://docs.divviup.org/references/janus-errors#dp-budget-exhausted",
+                "The task's differential privacy budget has been exhausted.",
+                Status::BadRequest,
+            )
+            .with_task_id(task_id)
+            .with_detail(concat!(
+                "Collecting this batch would spend more than the task's configured differential ",
+                "privacy budget, so no further collections are permitted."
+            )),
+        ),
+        Error::TaskArchived(task_id) => conn.with_problem_document(
+            &ProblemDocument::new(
+                "https://docs.divviup.org/references/janus-errors#task-archived",
+                "The task is archived.",
+                Status::BadRequest,
+            )
+            .with_task_id(task_id)
+            .with_detail(concat!(
+                "The task's report data has been garbage collected, so no new aggregations or ",
+                "collections are permitted. Existing collection results may still be retrieved."
+            )),
+        ),
     };
 
     if matches!(conn.status(), Some(status) if status.is_server_error()) {
@@ -327,6 +363,8 @@ pub(crate) static AGGREGATION_JOB_ROUTE: &str =
     "tasks/:task_id/aggregation_jobs/:aggregation_job_id";
 pub(crate) static COLLECTION_JOB_ROUTE: &str = "tasks/:task_id/collection_jobs/:collection_job_id";
 pub(crate) static AGGREGATE_SHARES_ROUTE: &str = "tasks/:task_id/aggregate_shares";
+pub(crate) static OHTTP_KEYS_ROUTE: &str = "ohttp_keys";
+pub(crate) static OHTTP_GATEWAY_ROUTE: &str = "ohttp_gateway";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -380,6 +418,23 @@ where
         }
     }
 
+    /// Applies the tunable values of each configuration received from `config_updates` to the
+    /// aggregator.
+    pub fn with_config_updates(self, mut config_updates: watch::Receiver<Config>) -> Self {
+        let aggregator = Arc::clone(&self.aggregator);
+        tokio::spawn(async move {
+            while config_updates.changed().await.is_ok() {
+                aggregator.apply_tunables(&config_updates.borrow_and_update());
+            }
+        });
+        self
+    }
+
+    /// The aggregator's cache of HPKE keypairs, e.g. for readiness checks.
+    pub fn hpke_keypairs(&self) -> &Arc<HpkeKeypairCache> {
+        &self.aggregator.hpke_keypairs
+    }
+
     pub fn build(self) -> Result<impl Handler, Error> {
         let helper_queue = self
             .helper_aggregation_request_queue
@@ -389,15 +444,27 @@ where
             .transpose()?
             .map(Arc::new);
 
+        // Trace context is always honored on routes that serve the peer aggregator, but only
+        // honored on routes that serve clients and collectors if so configured.
+        let honor_untrusted_trace_context = self.aggregator.cfg.honor_untrusted_trace_context;
         let router = Router::new()
             .without_options_handling()
-            .get("hpke_config", instrumented(api(hpke_config::<C>)))
+            .get(
+                "hpke_config",
+                instrumented_with_trace_context(
+                    api(hpke_config::<C>),
+                    honor_untrusted_trace_context,
+                ),
+            )
             .with_route(
                 trillium::Method::Options,
                 "hpke_config",
                 hpke_config_cors_preflight,
             )
-            .post("tasks/:task_id/reports", instrumented(api(upload::<C>)))
+            .post(
+                "tasks/:task_id/reports",
+                instrumented_with_trace_context(api(upload::<C>), honor_untrusted_trace_context),
+            )
             .with_route(
                 trillium::Method::Options,
                 "tasks/:task_id/reports",
@@ -405,50 +472,84 @@ where
             )
             .put(
                 AGGREGATION_JOB_ROUTE,
-                instrumented(if let Some(ref queue) = helper_queue {
-                    Box::new(queued_lifo(
-                        Arc::clone(queue),
-                        api(aggregation_jobs_put::<C>),
-                    )) as Box<dyn Handler>
-                } else {
-                    Box::new(api(aggregation_jobs_put::<C>)) as Box<dyn Handler>
-                }),
+                instrumented_with_trace_context(
+                    if let Some(ref queue) = helper_queue {
+                        Box::new(queued_lifo(
+                            Arc::clone(queue),
+                            api(aggregation_jobs_put::<C>),
+                        )) as Box<dyn Handler>
+                    } else {
+                        Box::new(api(aggregation_jobs_put::<C>)) as Box<dyn Handler>
+                    },
+                    true,
+                ),
             )
             .post(
                 AGGREGATION_JOB_ROUTE,
-                instrumented(if let Some(ref queue) = helper_queue {
-                    Box::new(queued_lifo(
-                        Arc::clone(queue),
-                        api(aggregation_jobs_post::<C>),
-                    )) as Box<dyn Handler>
-                } else {
-                    Box::new(api(aggregation_jobs_post::<C>)) as Box<dyn Handler>
-                }),
+                instrumented_with_trace_context(
+                    if let Some(ref queue) = helper_queue {
+                        Box::new(queued_lifo(
+                            Arc::clone(queue),
+                            api(aggregation_jobs_post::<C>),
+                        )) as Box<dyn Handler>
+                    } else {
+                        Box::new(api(aggregation_jobs_post::<C>)) as Box<dyn Handler>
+                    },
+                    true,
+                ),
             )
             .get(
                 AGGREGATION_JOB_ROUTE,
-                instrumented(api(aggregation_jobs_get::<C>)),
+                instrumented_with_trace_context(api(aggregation_jobs_get::<C>), true),
             )
             .delete(
                 AGGREGATION_JOB_ROUTE,
-                instrumented(api(aggregation_jobs_delete::<C>)),
+                instrumented_with_trace_context(api(aggregation_jobs_delete::<C>), true),
             )
             .put(
                 COLLECTION_JOB_ROUTE,
-                instrumented(api(collection_jobs_put::<C>)),
+                instrumented_with_trace_context(
+                    api(collection_jobs_put::<C>),
+                    honor_untrusted_trace_context,
+                ),
             )
             .get(
                 COLLECTION_JOB_ROUTE,
-                instrumented(api(collection_jobs_get::<C>)),
+                instrumented_with_trace_context(
+                    api(collection_jobs_get::<C>),
+                    honor_untrusted_trace_context,
+                ),
             )
             .delete(
                 COLLECTION_JOB_ROUTE,
-                instrumented(api(collection_jobs_delete::<C>)),
+                instrumented_with_trace_context(
+                    api(collection_jobs_delete::<C>),
+                    honor_untrusted_trace_context,
+                ),
             )
             .post(
                 AGGREGATE_SHARES_ROUTE,
-                instrumented(api(aggregate_shares::<C>)),
+                instrumented_with_trace_context(api(aggregate_shares::<C>), true),
             );
+        let router = if self.aggregator.ohttp_gateway_keys.is_some() {
+            router
+                .get(
+                    OHTTP_KEYS_ROUTE,
+                    instrumented_with_trace_context(
+                        api(ohttp_keys::<C>),
+                        honor_untrusted_trace_context,
+                    ),
+                )
+                .post(
+                    OHTTP_GATEWAY_ROUTE,
+                    instrumented_with_trace_context(
+                        ohttp_gateway::<C>,
+                        honor_untrusted_trace_context,
+                    ),
+                )
+        } else {
+            router
+        };
 
         let metrics = Metrics::new(self.meter.clone())
             .with_route(|conn| {
@@ -566,6 +667,147 @@ async fn upload_cors_preflight(mut conn: Conn) -> Conn {
     conn
 }
 
+/// API handler for the "/ohttp_keys" GET endpoint.
+async fn ohttp_keys<C: Clock>(
+    conn: &mut Conn,
+    State(aggregator): State<Arc<Aggregator<C>>>,
+) -> Result<(), Error> {
+    let ohttp_gateway_keys = aggregator
+        .ohttp_gateway_keys
+        .as_ref()
+        .ok_or_else(|| Error::Internal("OHTTP gateway is not enabled".into()))?;
+
+    conn.set_cache_control(CacheControlDirective::MaxAge(StdDuration::from_secs(86400)));
+    conn.response_headers_mut()
+        .insert(KnownHeaderName::ContentType, OHTTP_KEYS_MEDIA_TYPE);
+    conn.set_status(Status::Ok);
+    conn.set_body(ohttp_gateway_keys.encoded_key_configs().to_vec());
+    Ok(())
+}
+
+/// Handler for the "/ohttp_gateway" POST endpoint. Decapsulates an OHTTP request, dispatches it to
+/// the upload endpoint, and encapsulates the response. Inner requests for any other endpoint are
+/// answered with 404 Not Found.
+async fn ohttp_gateway<C: Clock>(mut conn: Conn) -> Conn {
+    let Some(aggregator) = conn.state::<Arc<Aggregator<C>>>().cloned() else {
+        return Error::Internal("aggregator missing from connection state".into())
+            .run(conn)
+            .await;
+    };
+    let Some(ohttp_gateway_keys) = aggregator.ohttp_gateway_keys.clone() else {
+        return Error::Internal("OHTTP gateway is not enabled".into())
+            .run(conn)
+            .await;
+    };
+
+    if let Err(err) = validate_content_type(&conn, OHTTP_REQUEST_MEDIA_TYPE) {
+        return err.run(conn).await;
+    }
+    let body = match BodyBytes::try_from_conn(&mut conn).await {
+        Ok(BodyBytes(body)) => body,
+        Err(err) => return err.run(conn).await,
+    };
+
+    // The first byte of an encapsulated request is the ID of the key it is encapsulated to.
+    let Some(server) = body
+        .first()
+        .and_then(|key_id| ohttp_gateway_keys.server(&HpkeConfigId::from(*key_id)))
+    else {
+        // https://datatracker.ietf.org/doc/html/rfc9458#section-5.3
+        conn.insert_state(ErrorCode("ohttp_key"));
+        return conn.with_problem_document(&ProblemDocument::new(
+            "https://iana.org/assignments/http-problem-types#ohttp-key",
+            "The OHTTP key configuration used to encapsulate the request is not recognized.",
+            Status::BadRequest,
+        ));
+    };
+    let (request, server_response) = match server.decapsulate(&body) {
+        Ok(decapsulated) => decapsulated,
+        Err(err) => {
+            return Error::BadRequest(
+                anyhow::Error::new(err)
+                    .context("couldn't decapsulate OHTTP request")
+                    .into(),
+            )
+            .run(conn)
+            .await;
+        }
+    };
+
+    let (status, content_type, body) = match parse_inner_request(&request) {
+        Ok(InnerUploadRequest { task_id, report }) => {
+            match conn
+                .cancel_on_disconnect(aggregator.handle_upload(&task_id, &report))
+                .await
+            {
+                Some(Ok(())) => (Status::Created, None, Vec::new()),
+                Some(Err(err)) => {
+                    let response;
+                    (conn, response) = take_error_response(&err, conn).await;
+                    response
+                }
+                None => return Error::ClientDisconnected.run(conn).await,
+            }
+        }
+        Err(InnerRequestError::Malformed(detail)) => {
+            return Error::BadRequest(format!("malformed OHTTP inner request: {detail}").into())
+                .run(conn)
+                .await;
+        }
+        Err(InnerRequestError::NotFound) => (Status::NotFound, None, Vec::new()),
+        Err(InnerRequestError::MethodNotAllowed) => (Status::MethodNotAllowed, None, Vec::new()),
+        Err(InnerRequestError::UnsupportedMediaType) => {
+            let response;
+            (conn, response) = take_error_response(
+                &Error::BadRequest("unexpected Content-Type header".into()),
+                conn,
+            )
+            .await;
+            response
+        }
+    };
+
+    match encode_inner_response(status as u16, content_type.as_deref(), &body).and_then(
+        |response| {
+            server_response
+                .encapsulate(&response)
+                .context("couldn't encapsulate OHTTP response")
+        },
+    ) {
+        Ok(encapsulated) => conn
+            .with_status(Status::Ok)
+            .with_response_header(KnownHeaderName::ContentType, OHTTP_RESPONSE_MEDIA_TYPE)
+            .with_body(encapsulated)
+            .halt(),
+        Err(err) => Error::Internal(err.into()).run(conn).await,
+    }
+}
+
+/// Runs the error handler for an error arising from an OHTTP inner request, then moves the
+/// resulting status, Content-Type and body out of the connection so that they can be encapsulated.
+/// The error code is left in the connection's state, so that the error is still reflected in
+/// metrics.
+async fn take_error_response(
+    error: &Error,
+    conn: Conn,
+) -> (Conn, (Status, Option<Vec<u8>>, Vec<u8>)) {
+    let mut conn = run_error_handler(error, conn).await;
+    let status = conn.status().unwrap_or(Status::InternalServerError);
+    let content_type = conn
+        .response_headers_mut()
+        .remove(KnownHeaderName::ContentType)
+        .and_then(|content_type| content_type.as_str().map(|s| s.as_bytes().to_vec()));
+    let body = match conn.take_response_body() {
+        Some(body) => body
+            .into_bytes()
+            .await
+            .map(Cow::into_owned)
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    (conn, (status, content_type, body))
+}
+
 /// API handler for the "/tasks/.../aggregation_jobs/..." PUT endpoint.
 async fn aggregation_jobs_put<C: Clock>(
     conn: &mut Conn,
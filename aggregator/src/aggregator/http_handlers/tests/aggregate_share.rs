@@ -560,9 +560,10 @@ async fn aggregate_share_request() {
         }),
     );
 
-    // Previous sequence of aggregate share requests should have consumed the available queries
-    // for all the batches. Further requests for any batches will cause query count violations.
-    for query_count_violation_request in [
+    // The batches may be queried again with a different aggregation parameter, since the VDAF
+    // permits it. Nothing has been aggregated with that parameter, so the requests fail the batch
+    // size check rather than a query count check.
+    for other_aggregation_param_request in [
         AggregateShareReq::new(
             BatchSelector::new_time_interval(
                 Interval::new(
@@ -589,15 +590,14 @@ async fn aggregate_share_request() {
         ),
     ] {
         let mut test_conn =
-            post_aggregate_share_request(&task, &query_count_violation_request, &handler).await;
+            post_aggregate_share_request(&task, &other_aggregation_param_request, &handler).await;
         assert_eq!(test_conn.status(), Some(Status::BadRequest));
         assert_eq!(
             take_problem_details(&mut test_conn).await,
             json!({
                 "status": Status::BadRequest as u16,
-                "type": "urn:ietf:params:ppm:dap:error:invalidMessage",
-                "title": "The message type for a response was incorrect or the payload was malformed.",
-                "detail": "batch has already been collected with another aggregation parameter",
+                "type": "urn:ietf:params:ppm:dap:error:invalidBatchSize",
+                "title": "The number of reports included in the batch is invalid.",
                 "taskid": format!("{}", task.id()),
             })
         );
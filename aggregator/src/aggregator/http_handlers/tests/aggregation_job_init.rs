@@ -12,11 +12,16 @@ use crate::aggregator::{
 use assert_matches::assert_matches;
 use futures::future::try_join_all;
 use janus_aggregator_core::{
-    datastore::models::{
-        AggregationJobState, BatchAggregation, BatchAggregationState, ReportAggregationState,
-        TaskAggregationCounter,
+    datastore::{
+        Transaction,
+        models::{
+            AggregationJob, AggregationJobState, BatchAggregation, BatchAggregationState,
+            ReportAggregation, ReportAggregationState, TaskAggregationCounter,
+        },
+    },
+    task::{
+        AggregationMode, AggregatorTask, BatchMode, TaskState, VerifyKey, test_util::TaskBuilder,
     },
-    task::{AggregationMode, BatchMode, VerifyKey, test_util::TaskBuilder},
 };
 use janus_core::{
     auth_tokens::AuthenticationToken,
@@ -24,13 +29,13 @@ use janus_core::{
     report_id::ReportIdChecksumExt,
     test_util::run_vdaf,
     time::{Clock, MockClock, TimeExt},
-    vdaf::VdafInstance,
+    vdaf::{VdafInstance, vdaf_dp_strategies},
 };
 use janus_messages::{
-    AggregationJobId, AggregationJobInitializeReq, AggregationJobResp, Extension, ExtensionType,
-    HpkeCiphertext, HpkeConfigId, InputShareAad, Interval, MediaType, PartialBatchSelector,
-    PrepareInit, PrepareStepResult, ReportError, ReportIdChecksum, ReportMetadata, ReportShare,
-    Role, Time,
+    AggregationJobId, AggregationJobInitializeReq, AggregationJobResp, AggregationJobStep,
+    Extension, ExtensionType, HpkeCiphertext, HpkeConfigId, InputShareAad, Interval, MediaType,
+    PartialBatchSelector, PrepareInit, PrepareStepResult, ReportError, ReportIdChecksum,
+    ReportMetadata, ReportShare, Role, Time,
     batch_mode::{LeaderSelected, TimeInterval},
 };
 use prio::{codec::Encode, vdaf::dummy};
@@ -51,7 +56,9 @@ async fn aggregate_leader() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .build();
     datastore
@@ -126,7 +133,9 @@ async fn aggregate_wrong_agg_auth_token() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_aggregator_auth_token(dap_auth_token.clone())
     .build();
@@ -469,7 +478,7 @@ async fn aggregate_init_sync() {
                 tx.put_aggregator_task(&helper_task).await.unwrap();
 
                 // report_share_4 is already in the datastore as it was referenced by an existing
-                // aggregation job.
+                // aggregation job with the same aggregation parameter.
                 tx.put_scrubbed_report(
                     helper_task.id(),
                     report_share_4.metadata().id(),
@@ -477,6 +486,13 @@ async fn aggregate_init_sync() {
                 )
                 .await
                 .unwrap();
+                put_finished_report_aggregation(
+                    tx,
+                    &helper_task,
+                    report_share_4.metadata(),
+                    dummy::AggregationParam(0),
+                )
+                .await;
 
                 // Write collected batch aggregations for the interval that report_share_5 falls
                 // into, which will cause it to fail to prepare.
@@ -660,7 +676,7 @@ async fn aggregate_init_sync() {
             .await
             .unwrap();
 
-        assert_eq!(aggregation_jobs.len(), 1);
+        assert_eq!(aggregation_jobs.len(), 2);
 
         let mut saw_new_aggregation_job = false;
         for aggregation_job in &aggregation_jobs {
@@ -726,23 +742,40 @@ async fn aggregate_init_async() {
     // aggregation parameter.
     let (prepare_init_1, _) = prep_init_generator.next(&measurement);
 
+    // prepare_init_2 has already been aggregated in another aggregation job, with a different
+    // aggregation parameter.
+    let (prepare_init_2, _) = prep_init_generator.next(&measurement);
+
     datastore
         .run_unnamed_tx(|tx| {
             let helper_task = helper_task.clone();
             let report_share_1 = prepare_init_1.report_share().clone();
+            let report_share_2 = prepare_init_2.report_share().clone();
 
             Box::pin(async move {
                 tx.put_aggregator_task(&helper_task).await.unwrap();
 
-                // report_share_1 is already in the datastore as it was referenced by an existing
-                // aggregation job.
-                tx.put_scrubbed_report(
-                    helper_task.id(),
-                    report_share_1.metadata().id(),
-                    report_share_1.metadata().time(),
-                )
-                .await
-                .unwrap();
+                // report_share_1 and report_share_2 are already in the datastore as they were
+                // referenced by existing aggregation jobs.
+                for (report_share, aggregation_param) in [
+                    (&report_share_1, dummy::AggregationParam(0)),
+                    (&report_share_2, dummy::AggregationParam(1)),
+                ] {
+                    tx.put_scrubbed_report(
+                        helper_task.id(),
+                        report_share.metadata().id(),
+                        report_share.metadata().time(),
+                    )
+                    .await
+                    .unwrap();
+                    put_finished_report_aggregation(
+                        tx,
+                        &helper_task,
+                        report_share.metadata(),
+                        aggregation_param,
+                    )
+                    .await;
+                }
 
                 Ok(())
             })
@@ -754,7 +787,11 @@ async fn aggregate_init_async() {
     let request = AggregationJobInitializeReq::new(
         aggregation_param.get_encoded().unwrap(),
         PartialBatchSelector::new_time_interval(),
-        Vec::from([prepare_init_0.clone(), prepare_init_1.clone()]),
+        Vec::from([
+            prepare_init_0.clone(),
+            prepare_init_1.clone(),
+            prepare_init_2.clone(),
+        ]),
     );
 
     // Send request, parse response. Do this twice to prove that the request is idempotent.
@@ -800,14 +837,16 @@ async fn aggregate_init_async() {
             .await
             .unwrap();
 
-        assert_eq!(aggregation_jobs.len(), 1);
-
-        assert_eq!(aggregation_jobs[0].task_id(), task.id());
-        assert_eq!(aggregation_jobs[0].id(), &aggregation_job_id);
-        assert_eq!(aggregation_jobs[0].partial_batch_identifier(), &());
-        assert_eq!(aggregation_jobs[0].state(), &AggregationJobState::Active);
+        assert_eq!(aggregation_jobs.len(), 3);
+        let aggregation_job = aggregation_jobs
+            .iter()
+            .find(|aggregation_job| aggregation_job.id() == &aggregation_job_id)
+            .unwrap();
+        assert_eq!(aggregation_job.task_id(), task.id());
+        assert_eq!(aggregation_job.partial_batch_identifier(), &());
+        assert_eq!(aggregation_job.state(), &AggregationJobState::Active);
 
-        assert_eq!(report_aggregations.len(), 2);
+        assert_eq!(report_aggregations.len(), 3);
 
         assert_eq!(
             report_aggregations[0].report_id(),
@@ -832,6 +871,18 @@ async fn aggregate_init_async() {
             }
         );
 
+        assert_eq!(
+            report_aggregations[2].report_id(),
+            prepare_init_2.report_share().metadata().id()
+        );
+        assert_eq!(
+            report_aggregations[2].state(),
+            &ReportAggregationState::HelperInitProcessing {
+                prepare_init: prepare_init_2.clone(),
+                require_taskbind_extension: false
+            }
+        );
+
         aggregation_jobs_results.push(aggregation_jobs);
         report_aggregations_results.push(report_aggregations);
         batch_aggregations_results.push(batch_aggregations);
@@ -966,6 +1017,76 @@ async fn aggregate_init_batch_already_collected() {
     .await;
 }
 
+#[tokio::test]
+async fn aggregate_init_task_archived() {
+    let HttpHandlerTest {
+        clock,
+        ephemeral_datastore: _ephemeral_datastore,
+        datastore,
+        handler,
+        hpke_keypair,
+        ..
+    } = HttpHandlerTest::new().await;
+
+    let task = TaskBuilder::new(
+        BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .with_state(TaskState::Archived)
+    .build();
+
+    let helper_task = task.helper_view().unwrap();
+    datastore.put_aggregator_task(&helper_task).await.unwrap();
+
+    let prep_init_generator = PrepareInitGenerator::new(
+        clock.clone(),
+        helper_task.clone(),
+        hpke_keypair.config().clone(),
+        dummy::Vdaf::new(1),
+        dummy::AggregationParam(0),
+    );
+    let (prepare_init, _) = prep_init_generator.next(&0);
+    let request = AggregationJobInitializeReq::new(
+        dummy::AggregationParam(0).get_encoded().unwrap(),
+        PartialBatchSelector::new_time_interval(),
+        Vec::from([prepare_init]),
+    );
+
+    let aggregation_job_id: AggregationJobId = random();
+    let mut test_conn = put_aggregation_job(&task, &aggregation_job_id, &request, &handler).await;
+    assert_eq!(test_conn.status(), Some(Status::BadRequest));
+    assert_eq!(
+        take_problem_details(&mut test_conn).await,
+        json!({
+            "status": Status::BadRequest as u16,
+            "type": "https://docs.divviup.org/references/janus-errors#task-archived",
+            "title": "The task is archived.",
+            "detail": concat!(
+                "The task's report data has been garbage collected, so no new aggregations or ",
+                "collections are permitted. Existing collection results may still be retrieved."
+            ),
+            "taskid": format!("{}", task.id()),
+        })
+    );
+
+    // No aggregation job was created.
+    let aggregation_job = datastore
+        .run_unnamed_tx(|tx| {
+            let task_id = *task.id();
+            Box::pin(async move {
+                tx.get_aggregation_job::<0, TimeInterval, dummy::Vdaf>(
+                    &task_id,
+                    &aggregation_job_id,
+                )
+                .await
+            })
+        })
+        .await
+        .unwrap();
+    assert!(aggregation_job.is_none());
+}
+
 #[tokio::test]
 async fn aggregate_init_prep_init_failed() {
     let HttpHandlerTest {
@@ -1165,3 +1286,36 @@ async fn aggregate_init_duplicated_report_id() {
     )
     .await;
 }
+
+/// Writes a finished aggregation job with the given aggregation parameter, which has successfully
+/// aggregated the given report.
+async fn put_finished_report_aggregation<C: Clock>(
+    tx: &Transaction<'_, C>,
+    task: &AggregatorTask,
+    report_metadata: &ReportMetadata,
+    aggregation_param: dummy::AggregationParam,
+) {
+    let aggregation_job_id = random();
+    tx.put_aggregation_job(&AggregationJob::<0, TimeInterval, dummy::Vdaf>::new(
+        *task.id(),
+        aggregation_job_id,
+        aggregation_param,
+        (),
+        Interval::new(*report_metadata.time(), *task.time_precision()).unwrap(),
+        AggregationJobState::Finished,
+        AggregationJobStep::from(1),
+    ))
+    .await
+    .unwrap();
+    tx.put_report_aggregation::<0, dummy::Vdaf>(&ReportAggregation::new(
+        *task.id(),
+        aggregation_job_id,
+        *report_metadata.id(),
+        *report_metadata.time(),
+        0,
+        None,
+        ReportAggregationState::Finished,
+    ))
+    .await
+    .unwrap();
+}
@@ -1,16 +1,19 @@
 use crate::aggregator::{
-    collection_job_tests::setup_collection_job_test_case,
+    collection_job_tests::{
+        setup_collection_job_test_case, setup_collection_job_test_case_for_task,
+    },
     http_handlers::test_util::{HttpHandlerTest, decode_response_body, take_problem_details},
 };
 use assert_matches::assert_matches;
 use janus_aggregator_core::{
     batch_mode::AccumulableBatchMode,
-    datastore::models::{CollectionJob, CollectionJobState},
-    task::{AggregationMode, BatchMode, test_util::TaskBuilder},
+    datastore::models::{CollectionJob, CollectionJobState, DpBudgetUsage},
+    task::{AggregationMode, BatchMode, DpBudget, TaskState, test_util::TaskBuilder},
 };
 use janus_core::{
+    dp::DpStrategyConfig,
     hpke::{self, HpkeApplicationInfo, Label},
-    vdaf::VdafInstance,
+    vdaf::{VdafInstance, vdaf_dp_strategies},
 };
 use janus_messages::{
     AggregateShareAad, BatchSelector, CollectionJobId, CollectionJobReq, CollectionJobResp,
@@ -519,26 +522,15 @@ async fn collection_job_put_request_batch_queried_multiple_times() {
 
     assert_eq!(test_conn.status(), Some(Status::Created));
 
-    // This request will not be allowed due to the query count already being consumed.
-    let invalid_request = CollectionJobReq::new(
+    // The batch may be queried again with a different aggregation parameter, since the VDAF
+    // permits it.
+    let request = CollectionJobReq::new(
         Query::new_time_interval(interval),
         dummy::AggregationParam(1).get_encoded().unwrap(),
     );
 
-    let mut test_conn = test_case
-        .put_collection_job(&random(), &invalid_request)
-        .await;
-    assert_eq!(test_conn.status(), Some(Status::BadRequest));
-    assert_eq!(
-        take_problem_details(&mut test_conn).await,
-        json!({
-            "status": Status::BadRequest as u16,
-            "type": "urn:ietf:params:ppm:dap:error:invalidMessage",
-            "title": "The message type for a response was incorrect or the payload was malformed.",
-            "detail": "batch has already been collected with another aggregation parameter",
-            "taskid": format!("{}", test_case.task.id()),
-        })
-    );
+    let test_conn = test_case.put_collection_job(&random(), &request).await;
+    assert_eq!(test_conn.status(), Some(Status::Created));
 }
 
 #[tokio::test]
@@ -585,6 +577,116 @@ async fn collection_job_put_request_batch_overlap() {
     );
 }
 
+#[tokio::test]
+async fn collection_job_put_request_dp_budget_exhausted() {
+    // Each collection of this task spends an epsilon of 1, out of a total budget of 1.5.
+    let test_case = setup_collection_job_test_case_for_task(
+        Role::Leader,
+        TaskBuilder::new(
+            BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::PureDpDiscreteLaplace(
+                    DpStrategyConfig::from_epsilon(1, 1).unwrap(),
+                ),
+            },
+        )
+        .with_min_batch_size(0)
+        .with_dp_budget(Some(DpBudget::new(1.5).unwrap()))
+        .build(),
+    )
+    .await;
+    let time_precision = *test_case.task.time_precision();
+
+    let request = CollectionJobReq::new(
+        Query::new_time_interval(
+            Interval::new(Time::from_seconds_since_epoch(0), time_precision).unwrap(),
+        ),
+        Vec::new(),
+    );
+    let test_conn = test_case.put_collection_job(&random(), &request).await;
+    assert_eq!(test_conn.status(), Some(Status::Created));
+
+    // Collecting another batch would spend more than the remaining budget.
+    let request = CollectionJobReq::new(
+        Query::new_time_interval(
+            Interval::new(
+                Time::from_seconds_since_epoch(time_precision.as_seconds()),
+                time_precision,
+            )
+            .unwrap(),
+        ),
+        Vec::new(),
+    );
+    let mut test_conn = test_case.put_collection_job(&random(), &request).await;
+    assert_eq!(test_conn.status(), Some(Status::BadRequest));
+    assert_eq!(
+        take_problem_details(&mut test_conn).await,
+        json!({
+            "status": Status::BadRequest as u16,
+            "type": "https://docs.divviup.org/references/janus-errors#dp-budget-exhausted",
+            "title": "The task's differential privacy budget has been exhausted.",
+            "detail": concat!(
+                "Collecting this batch would spend more than the task's configured differential ",
+                "privacy budget, so no further collections are permitted."
+            ),
+            "taskid": format!("{}", test_case.task.id()),
+        })
+    );
+
+    let usage = test_case
+        .datastore
+        .run_unnamed_tx(|tx| {
+            let task_id = *test_case.task.id();
+            Box::pin(async move { tx.get_dp_budget_usage(&task_id).await })
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(usage, DpBudgetUsage::new(1.0, 1));
+}
+
+#[tokio::test]
+async fn collection_job_put_request_task_archived() {
+    let test_case = setup_collection_job_test_case_for_task(
+        Role::Leader,
+        TaskBuilder::new(
+            BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Fake { rounds: 1 },
+        )
+        .with_state(TaskState::Archived)
+        .build(),
+    )
+    .await;
+
+    let request = CollectionJobReq::new(
+        Query::new_time_interval(
+            Interval::new(
+                Time::from_seconds_since_epoch(0),
+                *test_case.task.time_precision(),
+            )
+            .unwrap(),
+        ),
+        dummy::AggregationParam::default().get_encoded().unwrap(),
+    );
+    let mut test_conn = test_case.put_collection_job(&random(), &request).await;
+    assert_eq!(test_conn.status(), Some(Status::BadRequest));
+    assert_eq!(
+        take_problem_details(&mut test_conn).await,
+        json!({
+            "status": Status::BadRequest as u16,
+            "type": "https://docs.divviup.org/references/janus-errors#task-archived",
+            "title": "The task is archived.",
+            "detail": concat!(
+                "The task's report data has been garbage collected, so no new aggregations or ",
+                "collections are permitted. Existing collection results may still be retrieved."
+            ),
+            "taskid": format!("{}", test_case.task.id()),
+        })
+    );
+}
+
 #[tokio::test]
 async fn delete_collection_job() {
     let test_case = setup_collection_job_test_case(Role::Leader, BatchMode::TimeInterval).await;
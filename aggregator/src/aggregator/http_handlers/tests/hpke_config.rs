@@ -18,7 +18,7 @@ use janus_aggregator_core::{
 use janus_core::{
     hpke::{self, HpkeApplicationInfo, HpkeKeypair, Label},
     test_util::runtime::TestRuntime,
-    vdaf::VdafInstance,
+    vdaf::{VdafInstance, vdaf_dp_strategies},
 };
 use janus_messages::{HpkeConfigId, HpkeConfigList, MediaType, Role};
 use prio::codec::Decode as _;
@@ -159,7 +159,9 @@ async fn hpke_config_with_taskprov() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .build();
     let taskprov_helper_task = task.taskprov_helper_view().unwrap();
@@ -234,7 +236,9 @@ async fn hpke_config_cors_headers() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .build()
     .leader_view()
@@ -5,4 +5,5 @@ mod aggregation_job_init;
 mod collection_job;
 mod helper_e2e;
 mod hpke_config;
+mod ohttp_gateway;
 mod report;
@@ -0,0 +1,279 @@
+use crate::aggregator::{
+    Aggregator, Config,
+    http_handlers::{
+        AggregatorHandlerBuilder,
+        test_util::{HttpHandlerTest, take_problem_details, take_response_body},
+    },
+    ohttp_gateway::{
+        OHTTP_KEYS_MEDIA_TYPE, OHTTP_REQUEST_MEDIA_TYPE, OHTTP_RESPONSE_MEDIA_TYPE,
+        generate_keypair,
+    },
+    test_util::{create_report, default_aggregator_config},
+};
+use bhttp::{ControlData, Message, Mode};
+use janus_aggregator_core::{
+    datastore::models::HpkeKeyState,
+    task::{AggregationMode, BatchMode, test_util::TaskBuilder},
+    test_util::noop_meter,
+};
+use janus_core::{
+    hpke::HpkeCiphersuite,
+    test_util::runtime::TestRuntime,
+    time::Clock,
+    vdaf::{VdafInstance, vdaf_dp_strategies},
+};
+use janus_messages::{HpkeAeadId, HpkeConfigId, HpkeKdfId, HpkeKemId, MediaType, Report, TaskId};
+use ohttp::{ClientRequest, KeyConfig};
+use prio::codec::Encode;
+use rand::random;
+use serde_json::json;
+use std::{io::Cursor, sync::Arc, time::Duration as StdDuration};
+use trillium::{Handler, KnownHeaderName, Status};
+use trillium_testing::{
+    assert_headers,
+    prelude::{get, post},
+};
+
+/// Builds a handler with the OHTTP gateway enabled, and returns it along with the gateway's
+/// advertised key configs.
+async fn gateway_handler(test: &HttpHandlerTest) -> (impl Handler, Vec<KeyConfig>) {
+    let gateway_keypair = generate_keypair(
+        HpkeConfigId::from(random::<u8>()),
+        &HpkeCiphersuite::new(
+            HpkeKemId::X25519HkdfSha256,
+            HpkeKdfId::HkdfSha256,
+            HpkeAeadId::Aes128Gcm,
+        ),
+    )
+    .unwrap();
+    test.datastore
+        .run_unnamed_tx(|tx| {
+            let gateway_keypair = gateway_keypair.clone();
+            Box::pin(async move {
+                tx.put_ohttp_gateway_keypair(&gateway_keypair).await?;
+                tx.set_ohttp_gateway_keypair_state(
+                    gateway_keypair.config().id(),
+                    &HpkeKeyState::Active,
+                )
+                .await
+            })
+        })
+        .await
+        .unwrap();
+
+    let aggregator = Arc::new(
+        Aggregator::new(
+            Arc::clone(&test.datastore),
+            test.clock.clone(),
+            TestRuntime::default(),
+            &noop_meter(),
+            Config {
+                ohttp_gateway_keys_refresh_interval: Some(StdDuration::from_secs(60)),
+                ..default_aggregator_config()
+            },
+        )
+        .await
+        .unwrap(),
+    );
+    let handler = AggregatorHandlerBuilder::from_aggregator(aggregator, &noop_meter())
+        .build()
+        .unwrap();
+
+    let mut test_conn = get("/ohttp_keys").run_async(&handler).await;
+    assert_eq!(test_conn.status(), Some(Status::Ok));
+    assert_headers!(
+        &test_conn,
+        "cache-control" => "max-age=86400",
+        "content-type" => (OHTTP_KEYS_MEDIA_TYPE),
+    );
+    let key_configs = KeyConfig::decode_list(&take_response_body(&mut test_conn).await).unwrap();
+    assert_eq!(key_configs.len(), 1);
+
+    (handler, key_configs)
+}
+
+/// Encapsulates a binary HTTP request to the gateway, sends it, and returns the decapsulated
+/// response.
+async fn send_through_gateway(
+    handler: &impl Handler,
+    key_config: &KeyConfig,
+    method: &str,
+    path: &str,
+    content_type: &str,
+    body: &[u8],
+) -> Message {
+    let mut message = Message::request(
+        method.into(),
+        "https".into(),
+        "leader.example.com".into(),
+        path.into(),
+    );
+    message.put_header("content-type", content_type);
+    message.write_content(body);
+    let mut request = Vec::new();
+    message
+        .write_bhttp(Mode::KnownLength, &mut request)
+        .unwrap();
+
+    let (encapsulated_request, client_response) =
+        ClientRequest::from_config(&mut key_config.clone())
+            .unwrap()
+            .encapsulate(&request)
+            .unwrap();
+
+    let mut test_conn = post("/ohttp_gateway")
+        .with_request_header(KnownHeaderName::ContentType, OHTTP_REQUEST_MEDIA_TYPE)
+        .with_request_body(encapsulated_request)
+        .run_async(handler)
+        .await;
+    assert_eq!(test_conn.status(), Some(Status::Ok));
+    assert_headers!(&test_conn, "content-type" => (OHTTP_RESPONSE_MEDIA_TYPE));
+
+    let response = client_response
+        .decapsulate(&take_response_body(&mut test_conn).await)
+        .unwrap();
+    Message::read_bhttp(&mut Cursor::new(&response)).unwrap()
+}
+
+fn status(message: &Message) -> u16 {
+    match message.control() {
+        ControlData::Response(status) => u16::from(*status),
+        _ => panic!("not a response"),
+    }
+}
+
+#[tokio::test]
+async fn ohttp_gateway_upload() {
+    let test = HttpHandlerTest::new().await;
+    let (handler, key_configs) = gateway_handler(&test).await;
+
+    let task = TaskBuilder::new(
+        BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
+    )
+    .build();
+    let leader_task = task.leader_view().unwrap();
+    test.datastore
+        .put_aggregator_task(&leader_task)
+        .await
+        .unwrap();
+    let report = create_report(
+        &leader_task,
+        &test.hpke_keypair,
+        test.clock.now_aligned_to_precision(task.time_precision()),
+    );
+
+    // A report uploaded through the gateway is accepted.
+    let response = send_through_gateway(
+        &handler,
+        &key_configs[0],
+        "POST",
+        task.report_upload_uri().unwrap().path(),
+        Report::MEDIA_TYPE,
+        &report.get_encoded().unwrap(),
+    )
+    .await;
+    assert_eq!(status(&response), 201);
+    assert!(response.content().is_empty());
+
+    // Errors from the upload endpoint are encapsulated back to the client.
+    let unknown_task_id: TaskId = random();
+    let response = send_through_gateway(
+        &handler,
+        &key_configs[0],
+        "POST",
+        &format!("/tasks/{unknown_task_id}/reports"),
+        Report::MEDIA_TYPE,
+        &report.get_encoded().unwrap(),
+    )
+    .await;
+    assert_eq!(status(&response), 400);
+    assert_eq!(
+        response.header().get(b"content-type"),
+        Some(&b"application/problem+json"[..])
+    );
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(response.content()).unwrap(),
+        json!({
+            "status": 400,
+            "type": "urn:ietf:params:ppm:dap:error:unrecognizedTask",
+            "title": "An endpoint received a message with an unknown task ID.",
+            "taskid": format!("{unknown_task_id}"),
+        })
+    );
+
+    // Only the upload endpoint is reachable through the gateway.
+    let response = send_through_gateway(
+        &handler,
+        &key_configs[0],
+        "GET",
+        "/hpke_config",
+        Report::MEDIA_TYPE,
+        &[],
+    )
+    .await;
+    assert_eq!(status(&response), 404);
+    let response = send_through_gateway(
+        &handler,
+        &key_configs[0],
+        "PUT",
+        task.report_upload_uri().unwrap().path(),
+        Report::MEDIA_TYPE,
+        &report.get_encoded().unwrap(),
+    )
+    .await;
+    assert_eq!(status(&response), 405);
+}
+
+#[tokio::test]
+async fn ohttp_gateway_bad_requests() {
+    let test = HttpHandlerTest::new().await;
+    let (handler, key_configs) = gateway_handler(&test).await;
+
+    // Requests encapsulated to an unknown key are rejected.
+    let mut encoded_key_config = key_configs[0].encode().unwrap();
+    encoded_key_config[0] = encoded_key_config[0].wrapping_add(1);
+    let (encapsulated_request, _) = ClientRequest::from_encoded_config(&encoded_key_config)
+        .unwrap()
+        .encapsulate(b"request")
+        .unwrap();
+    let mut test_conn = post("/ohttp_gateway")
+        .with_request_header(KnownHeaderName::ContentType, OHTTP_REQUEST_MEDIA_TYPE)
+        .with_request_body(encapsulated_request)
+        .run_async(&handler)
+        .await;
+    assert_eq!(test_conn.status(), Some(Status::BadRequest));
+    assert_eq!(
+        take_problem_details(&mut test_conn).await["type"],
+        "https://iana.org/assignments/http-problem-types#ohttp-key"
+    );
+
+    // Requests that aren't encapsulated are rejected.
+    let test_conn = post("/ohttp_gateway")
+        .with_request_header(KnownHeaderName::ContentType, Report::MEDIA_TYPE)
+        .with_request_body(b"request".to_vec())
+        .run_async(&handler)
+        .await;
+    assert_eq!(test_conn.status(), Some(Status::BadRequest));
+}
+
+#[tokio::test]
+async fn ohttp_gateway_disabled() {
+    let HttpHandlerTest {
+        ephemeral_datastore: _ephemeral_datastore,
+        handler,
+        ..
+    } = HttpHandlerTest::new().await;
+
+    // Neither route is registered, so no status is set and the server falls back to 404.
+    let test_conn = get("/ohttp_keys").run_async(&handler).await;
+    assert_eq!(test_conn.status(), None);
+    let test_conn = post("/ohttp_gateway")
+        .with_request_header(KnownHeaderName::ContentType, OHTTP_REQUEST_MEDIA_TYPE)
+        .run_async(&handler)
+        .await;
+    assert_eq!(test_conn.status(), None);
+}
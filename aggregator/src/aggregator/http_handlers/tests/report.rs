@@ -19,7 +19,7 @@ use janus_core::{
     initialize_rustls,
     test_util::{install_test_trace_subscriber, runtime::TestRuntime},
     time::{Clock, MockClock, TimeExt},
-    vdaf::VdafInstance,
+    vdaf::{VdafInstance, vdaf_dp_strategies},
 };
 use janus_messages::{
     Duration, HpkeCiphertext, HpkeConfigId, InputShareAad, MediaType, PlaintextInputShare, Report,
@@ -79,7 +79,9 @@ async fn upload_handler() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_time_precision(Duration::from_seconds(1000))
     .with_report_expiry_age(Some(Duration::from_seconds(REPORT_EXPIRY_AGE)))
@@ -233,7 +235,9 @@ async fn upload_handler() {
     let task_end_soon = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     // Since HttpHandlerTest's clock instance is a copy of ours, we can't simply
     // advance it, and we have to instead tolerate skew.
@@ -443,7 +447,9 @@ async fn upload_handler_helper() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_time_precision(Duration::from_seconds(100))
     .build();
@@ -513,7 +519,9 @@ async fn upload_handler_error_fanout() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_time_precision(Duration::from_seconds(100))
     .with_report_expiry_age(Some(Duration::from_seconds(REPORT_EXPIRY_AGE)))
@@ -634,7 +642,9 @@ async fn upload_client_early_disconnect() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_time_precision(Duration::from_seconds(100))
     .build();
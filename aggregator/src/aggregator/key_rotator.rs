@@ -1,12 +1,17 @@
 #[allow(unused_imports)]
 use crate::aggregator::Config as AggregatorConfig; // used in doccomment.
-use crate::cache::HpkeKeypairCache;
+use crate::{
+    aggregator::ohttp_gateway,
+    binary_utils::readiness::{RecentSuccessCheck, SuccessTracker},
+    cache::HpkeKeypairCache,
+};
 use anyhow::{Error, anyhow};
 use educe::Educe;
 use futures::{FutureExt, future::try_join_all};
 use janus_aggregator_core::datastore::{
-    Datastore, Error as DatastoreError, Transaction,
+    Datastore, Error as DatastoreError,
     models::{HpkeKeyState, HpkeKeypair},
+    storage::{Storage, StorageTransaction},
 };
 use janus_core::{
     hpke::{self, HpkeCiphersuite},
@@ -18,7 +23,9 @@ use quickcheck::{Arbitrary, Gen};
 use serde::{Deserialize, Deserializer, Serialize, de};
 use std::{
     collections::{HashMap, HashSet, VecDeque},
+    marker::PhantomData,
     sync::Arc,
+    time::Duration as StdDuration,
 };
 use tokio::try_join;
 use tracing::{debug, info};
@@ -50,10 +57,25 @@ use tracing::{debug, info};
 /// Keypairs that are manually inserted are adopted by the key rotator and will have their lifecycle
 /// managed. The key rotator keeps only one key per ciphersuite around, preferring to use the latest
 /// inserted key.
+///
+/// # OHTTP Gateway Keys
+///
+/// If configured with [`KeyRotator::with_ohttp_gateway`], the key rotator also manages the keys of
+/// the aggregator's [OHTTP gateway][ohttp_gateway], stored in the `ohttp_gateway_keys` table. These
+/// move through the same states and follow the same policy as HPKE keys, but are rotated in a
+/// separate transaction and according to their own configuration.
+///
+/// # Storage
+///
+/// The key rotator only uses the operations of [`StorageTransaction`], so it can run against any
+/// [`Storage`] backend, though it is normally backed by the [`Datastore`].
 #[derive(Debug)]
-pub struct KeyRotator<C: Clock> {
-    datastore: Arc<Datastore<C>>,
+pub struct KeyRotator<C: Clock, S: Storage<C> = Datastore<C>> {
+    datastore: Arc<S>,
     hpke: HpkeKeyRotatorConfig,
+    ohttp_gateway: Option<HpkeKeyRotatorConfig>,
+    rotations: SuccessTracker,
+    _clock: PhantomData<C>,
 }
 
 /// Defines the ciphersuite and rotation policy of an HPKE key.
@@ -93,9 +115,29 @@ impl Default for HpkeKeyRotatorConfig {
     }
 }
 
-impl<C: Clock> KeyRotator<C> {
-    pub fn new(datastore: Arc<Datastore<C>>, hpke: HpkeKeyRotatorConfig) -> Self {
-        Self { datastore, hpke }
+impl<C: Clock, S: Storage<C>> KeyRotator<C, S> {
+    pub fn new(datastore: Arc<S>, hpke: HpkeKeyRotatorConfig) -> Self {
+        Self {
+            datastore,
+            hpke,
+            ohttp_gateway: None,
+            rotations: SuccessTracker::default(),
+            _clock: PhantomData,
+        }
+    }
+
+    /// Returns a readiness check that passes if the key rotator has run successfully, within
+    /// `max_age` if set.
+    pub fn readiness_check(&self, max_age: Option<StdDuration>) -> RecentSuccessCheck {
+        self.rotations.check("key rotation", max_age)
+    }
+
+    /// Also rotate OHTTP gateway keys, according to the given configuration.
+    pub fn with_ohttp_gateway(self, ohttp_gateway: HpkeKeyRotatorConfig) -> Self {
+        Self {
+            ohttp_gateway: Some(ohttp_gateway),
+            ..self
+        }
     }
 
     /// Runs the key rotator.
@@ -112,15 +154,29 @@ impl<C: Clock> KeyRotator<C> {
         self.datastore
             .run_tx("hpke_key_rotator", |tx| {
                 let config = Arc::new(self.hpke.clone());
-                Box::pin(async move { Self::run_hpke(tx, &config).await })
+                Box::pin(async move { Self::run_table(tx, KeyTable::Hpke, &config).await })
             })
-            .await
-            .map_err(|err| err.into())
+            .await?;
+
+        if let Some(ohttp_gateway) = &self.ohttp_gateway {
+            self.datastore
+                .run_tx("ohttp_gateway_key_rotator", |tx| {
+                    let config = Arc::new(ohttp_gateway.clone());
+                    Box::pin(
+                        async move { Self::run_table(tx, KeyTable::OhttpGateway, &config).await },
+                    )
+                })
+                .await?;
+        }
+
+        self.rotations.record_success();
+        Ok(())
     }
 
     #[tracing::instrument(err, skip(tx))]
-    async fn run_hpke(
-        tx: &Transaction<'_, C>,
+    async fn run_table(
+        tx: &dyn StorageTransaction<C>,
+        table: KeyTable,
         config: &HpkeKeyRotatorConfig,
     ) -> Result<(), DatastoreError> {
         // Take an ExclusiveLock on the table. This ensures that only one key rotator replica
@@ -128,23 +184,114 @@ impl<C: Clock> KeyRotator<C> {
         //
         // ExclusiveLock does not conflict with the AccessShare lock taken by table reads, i.e.
         // other aggregator replicas can continue to refresh their key caches without being blocked.
-        tx.lock_hpke_keypairs().await?;
+        table.lock(tx).await?;
 
-        let keypairs = tx
-            .get_hpke_keypairs()
+        let keypairs = table
+            .get_keypairs(tx)
             .inspect(|keypairs| debug!(?keypairs, "table state before running key rotator"))
             .await?
             .into_iter()
             .map(|keypair| (*keypair.id(), keypair))
             .collect();
 
-        HpkeKeyRotator::new(tx.clock().clone(), keypairs, config)?
+        HpkeKeyRotator::new(tx.clock().clone(), table, keypairs, config)?
             .sweep()?
             .write(tx)
             .await
     }
 }
 
+/// A table of keys managed by the key rotator. Each table is rotated independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyTable {
+    /// HPKE keys used by clients to encrypt report shares, in the `hpke_keys` table.
+    Hpke,
+    /// Keys used by the OHTTP gateway to decapsulate requests, in the `ohttp_gateway_keys` table.
+    OhttpGateway,
+}
+
+impl KeyTable {
+    fn generate(
+        self,
+        id: HpkeConfigId,
+        ciphersuite: &HpkeCiphersuite,
+    ) -> Result<hpke::HpkeKeypair, DatastoreError> {
+        match self {
+            Self::Hpke => hpke::HpkeKeypair::generate(
+                id,
+                ciphersuite.kem_id(),
+                ciphersuite.kdf_id(),
+                ciphersuite.aead_id(),
+            )
+            .map_err(|e| DatastoreError::User(e.into())),
+            Self::OhttpGateway => ohttp_gateway::generate_keypair(id, ciphersuite)
+                .map_err(|e| DatastoreError::User(e.into())),
+        }
+    }
+
+    async fn lock<C: Clock>(self, tx: &dyn StorageTransaction<C>) -> Result<(), DatastoreError> {
+        match self {
+            Self::Hpke => tx.lock_hpke_keypairs().await,
+            Self::OhttpGateway => tx.lock_ohttp_gateway_keypairs().await,
+        }
+    }
+
+    async fn get_keypairs<C: Clock>(
+        self,
+        tx: &dyn StorageTransaction<C>,
+    ) -> Result<Vec<HpkeKeypair>, DatastoreError> {
+        match self {
+            Self::Hpke => tx.get_hpke_keypairs().await,
+            Self::OhttpGateway => tx.get_ohttp_gateway_keypairs().await,
+        }
+    }
+
+    async fn get_keypair<C: Clock>(
+        self,
+        tx: &dyn StorageTransaction<C>,
+        id: &HpkeConfigId,
+    ) -> Result<Option<HpkeKeypair>, DatastoreError> {
+        match self {
+            Self::Hpke => tx.get_hpke_keypair(id).await,
+            Self::OhttpGateway => tx.get_ohttp_gateway_keypair(id).await,
+        }
+    }
+
+    async fn put_keypair<C: Clock>(
+        self,
+        tx: &dyn StorageTransaction<C>,
+        keypair: &hpke::HpkeKeypair,
+    ) -> Result<(), DatastoreError> {
+        match self {
+            Self::Hpke => tx.put_hpke_keypair(keypair).await,
+            Self::OhttpGateway => tx.put_ohttp_gateway_keypair(keypair).await,
+        }
+    }
+
+    async fn set_keypair_state<C: Clock>(
+        self,
+        tx: &dyn StorageTransaction<C>,
+        id: &HpkeConfigId,
+        state: &HpkeKeyState,
+    ) -> Result<(), DatastoreError> {
+        match self {
+            Self::Hpke => tx.set_hpke_keypair_state(id, state).await,
+            Self::OhttpGateway => tx.set_ohttp_gateway_keypair_state(id, state).await,
+        }
+    }
+
+    async fn delete_keypair<C: Clock>(
+        self,
+        tx: &dyn StorageTransaction<C>,
+        id: &HpkeConfigId,
+    ) -> Result<(), DatastoreError> {
+        match self {
+            Self::Hpke => tx.delete_hpke_keypair(id).await,
+            Self::OhttpGateway => tx.delete_ohttp_gateway_keypair(id).await,
+        }
+    }
+}
+
 fn duration_since<C: Clock>(clock: &C, time: &Time) -> Duration {
     // Use saturating difference to account for time skew between key rotator runners. Since
     // key rotators are synchronized by an exclusive lock on the table, it's possible that
@@ -152,11 +299,12 @@ fn duration_since<C: Clock>(clock: &C, time: &Time) -> Duration {
     clock.now().saturating_difference(time)
 }
 
-/// In-memory representation of the `hpke_keys` table.
+/// In-memory representation of a table of keys, i.e. `hpke_keys` or `ohttp_gateway_keys`.
 #[derive(Educe)]
 #[educe(Debug)]
 struct HpkeKeyRotator<'a, C: Clock> {
     clock: C,
+    table: KeyTable,
     config: &'a HpkeKeyRotatorConfig,
 
     // Data structures for intermediate state.
@@ -169,6 +317,7 @@ struct HpkeKeyRotator<'a, C: Clock> {
 impl<'a, C: Clock> HpkeKeyRotator<'a, C> {
     fn new(
         clock: C,
+        table: KeyTable,
         keypairs: HashMap<HpkeConfigId, HpkeKeypair>,
         config: &'a HpkeKeyRotatorConfig,
     ) -> Result<Self, DatastoreError> {
@@ -193,6 +342,7 @@ impl<'a, C: Clock> HpkeKeyRotator<'a, C> {
 
         Ok(Self {
             clock,
+            table,
             initially_empty: keypairs.is_empty(),
             keypairs,
             available_ids,
@@ -204,7 +354,7 @@ impl<'a, C: Clock> HpkeKeyRotator<'a, C> {
     ///
     /// Key bootstrap policy:
     ///   - If this is the very first run of Janus, insert keypairs in [`HpkeKeyState::Active`] for
-    ///     each configured ciphersuite. Detect first run by whether the `hpke_keys` table is empty.
+    ///     each configured ciphersuite. Detect first run by whether the table is empty.
     ///   - For each configured ciphersuite, if there is no pending or active key, insert a key in
     ///     [`HpkeKeyState::Pending`].
     ///
@@ -334,18 +484,14 @@ impl<'a, C: Clock> HpkeKeyRotator<'a, C> {
         for op in ops {
             match op {
                 HpkeOp::Create(ciphersuite, state, reason) => {
-                    info!(?ciphersuite, ?state, reason, "creating new key");
+                    info!(table = ?self.table, ?ciphersuite, ?state, reason, "creating new key");
 
                     let id = self.available_ids.next().ok_or_else(|| {
-                        DatastoreError::User(anyhow!("HPKE key ID space exhausted").into())
+                        DatastoreError::User(
+                            anyhow!("{:?} key ID space exhausted", self.table).into(),
+                        )
                     })?;
-                    let keypair = hpke::HpkeKeypair::generate(
-                        id,
-                        ciphersuite.kem_id(),
-                        ciphersuite.kdf_id(),
-                        ciphersuite.aead_id(),
-                    )
-                    .map_err(|e| DatastoreError::User(e.into()))?;
+                    let keypair = self.table.generate(id, &ciphersuite)?;
 
                     let result = self
                         .keypairs
@@ -356,12 +502,12 @@ impl<'a, C: Clock> HpkeKeyRotator<'a, C> {
                 HpkeOp::Update(id, state, reason) => {
                     // Unwrap safety: it is a bug to attempt to mutate a non-existent key.
                     let keypair = self.keypairs.get_mut(&id).unwrap();
-                    info!(?id, old_state = ?keypair.state(), new_state = ?state, reason, "changing key state");
+                    info!(table = ?self.table, ?id, old_state = ?keypair.state(), new_state = ?state, reason, "changing key state");
                     keypair.set_state(state, self.clock.now());
                 }
                 HpkeOp::Delete(id, reason) => {
                     // Unwrap safety: it is a bug to delete a non-existent key.
-                    info!(?id, reason, "deleting key");
+                    info!(table = ?self.table, ?id, reason, "deleting key");
                     self.keypairs.remove(&id).unwrap();
                 }
             }
@@ -370,9 +516,10 @@ impl<'a, C: Clock> HpkeKeyRotator<'a, C> {
         Ok(self)
     }
 
-    async fn write(&self, tx: &Transaction<'_, C>) -> Result<(), DatastoreError> {
-        let current_keypairs_ids: HashSet<_> = tx
-            .get_hpke_keypairs()
+    async fn write(&self, tx: &dyn StorageTransaction<C>) -> Result<(), DatastoreError> {
+        let table = self.table;
+        let current_keypairs_ids: HashSet<_> = table
+            .get_keypairs(tx)
             .await?
             .into_iter()
             .map(|keypair| *keypair.id())
@@ -386,30 +533,36 @@ impl<'a, C: Clock> HpkeKeyRotator<'a, C> {
             try_join_all(
                 to_delete
                     .iter()
-                    .map(|id| async move { tx.delete_hpke_keypair(id).await })
+                    .map(|id| async move { table.delete_keypair(tx, id).await })
             ),
             try_join_all(
                 self.keypairs
                     .iter()
                     .map(|(id, updated_keypair)| async move {
-                        match tx.get_hpke_keypair(id).await? {
+                        match table.get_keypair(tx, id).await? {
                             Some(current_keypair) => {
                                 if current_keypair.state() != updated_keypair.state() {
-                                    tx.set_hpke_keypair_state(
-                                        updated_keypair.id(),
-                                        updated_keypair.state(),
-                                    )
-                                    .await?;
+                                    table
+                                        .set_keypair_state(
+                                            tx,
+                                            updated_keypair.id(),
+                                            updated_keypair.state(),
+                                        )
+                                        .await?;
                                 }
                                 Ok(())
                             }
                             None => {
-                                tx.put_hpke_keypair(updated_keypair.hpke_keypair()).await?;
-                                tx.set_hpke_keypair_state(
-                                    updated_keypair.id(),
-                                    updated_keypair.state(),
-                                )
-                                .await?;
+                                table
+                                    .put_keypair(tx, updated_keypair.hpke_keypair())
+                                    .await?;
+                                table
+                                    .set_keypair_state(
+                                        tx,
+                                        updated_keypair.id(),
+                                        updated_keypair.state(),
+                                    )
+                                    .await?;
                                 Ok(())
                             }
                         }
@@ -420,7 +573,7 @@ impl<'a, C: Clock> HpkeKeyRotator<'a, C> {
         // Defensive assertion: Check our transaction snapshot for at least one active keypair in
         // the table. If one is absent, committing the transaction would leave Janus unstartable, so
         // we should rollback.
-        let keypairs = tx.get_hpke_keypairs().await?;
+        let keypairs = table.get_keypairs(tx).await?;
         debug!(?keypairs, "table state after running key rotator");
         if !keypairs
             .iter()
@@ -456,6 +609,32 @@ where
     }
 }
 
+/// Enforces that there's at least one [`HpkeCiphersuite`], and that every ciphersuite is usable
+/// by the OHTTP gateway.
+pub fn deserialize_ohttp_gateway_key_rotator_config<'de, D>(
+    deserializer: D,
+) -> Result<Option<HpkeKeyRotatorConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let config: Option<HpkeKeyRotatorConfig> = Deserialize::deserialize(deserializer)?;
+    if let Some(config) = &config {
+        if config.ciphersuites.is_empty() {
+            return Err(de::Error::custom("must provide at least one ciphersuite"));
+        }
+        if let Some(ciphersuite) = config
+            .ciphersuites
+            .iter()
+            .find(|ciphersuite| !ohttp_gateway::is_supported_ciphersuite(ciphersuite))
+        {
+            return Err(de::Error::custom(format!(
+                "ciphersuite {ciphersuite:?} is not supported by the OHTTP gateway"
+            )));
+        }
+    }
+    Ok(config)
+}
+
 /// Returns [`HpkeKeypairCache::DEFAULT_REFRESH_INTERVAL`] times 2, for safety margin.
 pub fn default_pending_duration() -> Duration {
     Duration::from_seconds(HpkeKeypairCache::DEFAULT_REFRESH_INTERVAL.as_secs() * 2)
@@ -508,6 +687,7 @@ mod tests {
     use janus_aggregator_core::datastore::{
         Datastore,
         models::{HpkeKeyState, HpkeKeypair},
+        storage::{InMemoryStorage, Storage},
         test_util::ephemeral_datastore,
     };
     use janus_core::{
@@ -519,9 +699,12 @@ mod tests {
     use quickcheck::{Arbitrary, Gen, TestResult};
     use quickcheck_macros::quickcheck;
 
-    use crate::aggregator::key_rotator::{HpkeKeyRotatorConfig, KeyRotator, duration_since};
+    use crate::aggregator::{
+        key_rotator::{HpkeKeyRotatorConfig, KeyRotator, duration_since},
+        ohttp_gateway,
+    };
 
-    use super::HpkeKeyRotator;
+    use super::{HpkeKeyRotator, KeyTable};
 
     async fn get_hpke_keypairs<C: Clock>(ds: &Datastore<C>) -> Vec<HpkeKeypair> {
         ds.run_unnamed_tx(|tx| Box::pin(async move { tx.get_hpke_keypairs().await }))
@@ -529,6 +712,12 @@ mod tests {
             .unwrap()
     }
 
+    async fn get_ohttp_gateway_keypairs<C: Clock>(ds: &Datastore<C>) -> Vec<HpkeKeypair> {
+        ds.run_unnamed_tx(|tx| Box::pin(async move { tx.get_ohttp_gateway_keypairs().await }))
+            .await
+            .unwrap()
+    }
+
     // Exercises the happy path and database interaction of the key rotator.
     #[tokio::test]
     async fn hpke_key_rotator() {
@@ -628,6 +817,79 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn ohttp_gateway_key_rotator() {
+        install_test_trace_subscriber();
+        let clock = MockClock::default();
+        let ephemeral_datastore = ephemeral_datastore().await;
+        let ds = Arc::new(ephemeral_datastore.datastore(clock.clone()).await);
+
+        let active_duration = Duration::from_seconds(300);
+        let ciphersuite = HpkeCiphersuite::new(
+            HpkeKemId::X25519HkdfSha256,
+            HpkeKdfId::HkdfSha256,
+            HpkeAeadId::ChaCha20Poly1305,
+        );
+        let key_rotator = KeyRotator::new(ds.clone(), HpkeKeyRotatorConfig::default())
+            .with_ohttp_gateway(HpkeKeyRotatorConfig {
+                active_duration,
+                ciphersuites: HashSet::from([ciphersuite]),
+                ..Default::default()
+            });
+
+        // Both tables are bootstrapped independently.
+        key_rotator.run().await.unwrap();
+        let hpke_keypairs = get_hpke_keypairs(&ds).await;
+        assert_eq!(hpke_keypairs.len(), 1);
+        let gateway_keypairs = get_ohttp_gateway_keypairs(&ds).await;
+        assert_eq!(gateway_keypairs.len(), 1);
+        assert_eq!(gateway_keypairs[0].state(), &HpkeKeyState::Active);
+        assert_eq!(gateway_keypairs[0].ciphersuite(), ciphersuite);
+        ohttp_gateway::key_config(gateway_keypairs[0].hpke_keypair()).unwrap();
+
+        // Only the gateway key has aged out, so only it gets a pending replacement.
+        clock.advance(&active_duration.add(&Duration::from_seconds(1)).unwrap());
+        key_rotator.run().await.unwrap();
+        assert_eq!(get_hpke_keypairs(&ds).await, hpke_keypairs);
+        let gateway_keypairs = get_ohttp_gateway_keypairs(&ds).await;
+        assert_eq!(gateway_keypairs.len(), 2);
+        assert!(gateway_keypairs.iter().any(HpkeKeypair::is_pending));
+    }
+
+    // The key rotator manages keys stored in memory just as it does keys in the datastore.
+    #[tokio::test]
+    async fn hpke_key_rotator_in_memory_storage() {
+        install_test_trace_subscriber();
+        let clock = MockClock::default();
+        let storage = Arc::new(InMemoryStorage::new(clock.clone()));
+
+        let active_duration = Duration::from_seconds(300);
+        let key_rotator = KeyRotator::new(
+            Arc::clone(&storage),
+            HpkeKeyRotatorConfig {
+                active_duration,
+                ..Default::default()
+            },
+        );
+        let get_keypairs = || {
+            storage.run_tx("test", |tx| {
+                Box::pin(async move { tx.get_hpke_keypairs().await })
+            })
+        };
+
+        key_rotator.run().await.unwrap();
+        let keypairs = get_keypairs().await.unwrap();
+        assert_eq!(keypairs.len(), 1);
+        assert!(keypairs[0].is_active());
+
+        clock.advance(&active_duration.add(&Duration::from_seconds(1)).unwrap());
+        key_rotator.run().await.unwrap();
+        let keypairs = get_keypairs().await.unwrap();
+        assert_eq!(keypairs.len(), 2);
+        assert!(keypairs.iter().any(HpkeKeypair::is_active));
+        assert!(keypairs.iter().any(HpkeKeypair::is_pending));
+    }
+
     #[derive(Debug, Clone)]
     struct InitialHpkeKeysState {
         /// Where the clock should start.
@@ -695,7 +957,7 @@ mod tests {
             })
             .collect();
 
-        let key_rotator = HpkeKeyRotator::new(clock, state.keypairs, &config)
+        let key_rotator = HpkeKeyRotator::new(clock, KeyTable::Hpke, state.keypairs, &config)
             .unwrap()
             .sweep()
             .unwrap();
@@ -742,7 +1004,7 @@ mod tests {
             return TestResult::discard();
         }
 
-        let key_rotator = HpkeKeyRotator::new(clock, state.keypairs, &config)
+        let key_rotator = HpkeKeyRotator::new(clock, KeyTable::Hpke, state.keypairs, &config)
             .unwrap()
             .sweep()
             .unwrap();
@@ -783,7 +1045,7 @@ mod tests {
             })
             .collect();
 
-        let key_rotator = HpkeKeyRotator::new(clock, state.keypairs, &config)
+        let key_rotator = HpkeKeyRotator::new(clock, KeyTable::Hpke, state.keypairs, &config)
             .unwrap()
             .sweep()
             .unwrap();
@@ -809,10 +1071,11 @@ mod tests {
         state: InitialHpkeKeysState,
     ) -> TestResult {
         let clock = MockClock::new(state.start);
-        let key_rotator = HpkeKeyRotator::new(clock.clone(), state.keypairs, &config)
-            .unwrap()
-            .sweep()
-            .unwrap();
+        let key_rotator =
+            HpkeKeyRotator::new(clock.clone(), KeyTable::Hpke, state.keypairs, &config)
+                .unwrap()
+                .sweep()
+                .unwrap();
 
         for ciphersuite in &config.ciphersuites {
             let active_keys: Vec<_> = key_rotator
@@ -847,10 +1110,11 @@ mod tests {
         state: InitialHpkeKeysState,
     ) -> TestResult {
         let clock = MockClock::new(state.start);
-        let key_rotator = HpkeKeyRotator::new(clock, state.keypairs.clone(), &config)
-            .unwrap()
-            .sweep()
-            .unwrap();
+        let key_rotator =
+            HpkeKeyRotator::new(clock, KeyTable::Hpke, state.keypairs.clone(), &config)
+                .unwrap()
+                .sweep()
+                .unwrap();
 
         for ciphersuite in &config.ciphersuites {
             let newest_pending_keys_ids: HashSet<_> = state
@@ -908,7 +1172,7 @@ mod tests {
             return TestResult::discard();
         }
 
-        let key_rotator = HpkeKeyRotator::new(clock, state.keypairs, &config)
+        let key_rotator = HpkeKeyRotator::new(clock, KeyTable::Hpke, state.keypairs, &config)
             .unwrap()
             .sweep()
             .unwrap();
@@ -0,0 +1,351 @@
+//! An [Oblivious HTTP][1] gateway for the leader's upload endpoint.
+//!
+//! The gateway serves its key configurations and decapsulates binary HTTP ([RFC 9292][2]) requests
+//! relayed on behalf of clients, such as those sent by `janus_client` when configured with an
+//! `OhttpConfig`. Only requests to the upload endpoint are dispatched; all other inner requests
+//! are refused.
+//!
+//! Gateway keys are stored in the `ohttp_gateway_keys` table, and are rotated by the
+//! [`KeyRotator`][crate::aggregator::key_rotator::KeyRotator] through the same lifecycle as HPKE
+//! keys. Rather than storing an HPKE private key directly, each row stores a random seed from
+//! which the OHTTP key configuration is deterministically derived.
+//!
+//! [1]: https://datatracker.ietf.org/doc/html/rfc9458
+//! [2]: https://datatracker.ietf.org/doc/html/rfc9292
+
+use anyhow::{Context, anyhow, bail};
+use bhttp::{ControlData, Message, Mode, StatusCode};
+use janus_core::hpke::{self, HpkeCiphersuite, HpkePrivateKey};
+use janus_messages::{
+    HpkeAeadId, HpkeConfig, HpkeConfigId, HpkeKdfId, HpkeKemId, HpkePublicKey, MediaType, Report,
+    TaskId,
+};
+use ohttp::{
+    KeyConfig, SymmetricSuite,
+    hpke::{Aead, Kdf, Kem},
+};
+use rand::random;
+use std::io::Cursor;
+
+/// Media type of the gateway's key configurations.
+pub const OHTTP_KEYS_MEDIA_TYPE: &str = "application/ohttp-keys";
+
+/// Media type of encapsulated requests.
+pub const OHTTP_REQUEST_MEDIA_TYPE: &str = "message/ohttp-req";
+
+/// Media type of encapsulated responses.
+pub const OHTTP_RESPONSE_MEDIA_TYPE: &str = "message/ohttp-res";
+
+/// Length of the seed from which a gateway key configuration is derived.
+const KEY_SEED_LEN: usize = 32;
+
+/// Returns whether the gateway can use keys with the given ciphersuite.
+pub fn is_supported_ciphersuite(ciphersuite: &HpkeCiphersuite) -> bool {
+    ohttp_ciphersuite(ciphersuite).is_ok()
+}
+
+fn ohttp_ciphersuite(
+    ciphersuite: &HpkeCiphersuite,
+) -> Result<(Kem, SymmetricSuite), anyhow::Error> {
+    let kem = match ciphersuite.kem_id() {
+        HpkeKemId::X25519HkdfSha256 => Kem::X25519Sha256,
+        kem_id => bail!("unsupported OHTTP gateway KEM {kem_id:?}"),
+    };
+    let kdf = match ciphersuite.kdf_id() {
+        HpkeKdfId::HkdfSha256 => Kdf::HkdfSha256,
+        HpkeKdfId::HkdfSha384 => Kdf::HkdfSha384,
+        HpkeKdfId::HkdfSha512 => Kdf::HkdfSha512,
+        kdf_id => bail!("unsupported OHTTP gateway KDF {kdf_id:?}"),
+    };
+    let aead = match ciphersuite.aead_id() {
+        HpkeAeadId::Aes128Gcm => Aead::Aes128Gcm,
+        HpkeAeadId::Aes256Gcm => Aead::Aes256Gcm,
+        HpkeAeadId::ChaCha20Poly1305 => Aead::ChaCha20Poly1305,
+        aead_id => bail!("unsupported OHTTP gateway AEAD {aead_id:?}"),
+    };
+    Ok((kem, SymmetricSuite::new(kdf, aead)))
+}
+
+/// Derives the OHTTP key configuration for a gateway keypair, as stored in the datastore.
+pub(crate) fn key_config(keypair: &hpke::HpkeKeypair) -> Result<KeyConfig, anyhow::Error> {
+    let config = keypair.config();
+    let (kem, symmetric_suite) = ohttp_ciphersuite(&HpkeCiphersuite::new(
+        *config.kem_id(),
+        *config.kdf_id(),
+        *config.aead_id(),
+    ))?;
+    KeyConfig::derive(
+        u8::from(*config.id()),
+        kem,
+        Vec::from([symmetric_suite]),
+        keypair.private_key().as_ref(),
+    )
+    .context("couldn't derive OHTTP key config")
+}
+
+/// Generates a new gateway keypair. The keypair's private key is the seed from which its OHTTP key
+/// configuration is derived, and its public key is that of the derived key configuration.
+pub fn generate_keypair(
+    id: HpkeConfigId,
+    ciphersuite: &HpkeCiphersuite,
+) -> Result<hpke::HpkeKeypair, anyhow::Error> {
+    let seed: [u8; KEY_SEED_LEN] = random();
+    let (kem, symmetric_suite) = ohttp_ciphersuite(ciphersuite)?;
+    let encoded_key_config =
+        KeyConfig::derive(u8::from(id), kem, Vec::from([symmetric_suite]), &seed)
+            .context("couldn't derive OHTTP key config")?
+            .encode()
+            .context("couldn't encode OHTTP key config")?;
+
+    // An encoded key config with a single symmetric suite is the key ID (1 byte), the KEM ID (2
+    // bytes), the public key, the length of the symmetric suites (2 bytes) and the suite's KDF and
+    // AEAD IDs (4 bytes).
+    let public_key = encoded_key_config
+        .get(3..encoded_key_config.len().saturating_sub(6))
+        .filter(|public_key| !public_key.is_empty())
+        .ok_or_else(|| anyhow!("malformed OHTTP key config"))?;
+
+    Ok(hpke::HpkeKeypair::new(
+        HpkeConfig::new(
+            id,
+            ciphersuite.kem_id(),
+            ciphersuite.kdf_id(),
+            ciphersuite.aead_id(),
+            HpkePublicKey::from(public_key.to_vec()),
+        ),
+        HpkePrivateKey::new(seed.to_vec()),
+    ))
+}
+
+/// An upload request decapsulated by the gateway.
+#[derive(Debug)]
+pub(crate) struct InnerUploadRequest {
+    pub(crate) task_id: TaskId,
+    pub(crate) report: Vec<u8>,
+}
+
+/// The reason an inner request could not be dispatched to the upload endpoint.
+#[derive(Debug)]
+pub(crate) enum InnerRequestError {
+    /// The inner request could not be parsed as a binary HTTP request.
+    Malformed(String),
+    /// The inner request is not for the upload endpoint.
+    NotFound,
+    /// The inner request is for the upload endpoint, but uses the wrong method.
+    MethodNotAllowed,
+    /// The inner request's content type is not that of a report.
+    UnsupportedMediaType,
+}
+
+/// Parses a decapsulated binary HTTP request, which must be a `POST` to
+/// `tasks/{task-id}/reports`. Any path prefix in front of the upload route is ignored, so that the
+/// gateway accepts requests for the aggregator's public URL regardless of how it is mounted.
+pub(crate) fn parse_inner_request(request: &[u8]) -> Result<InnerUploadRequest, InnerRequestError> {
+    let message = Message::read_bhttp(&mut Cursor::new(request))
+        .map_err(|err| InnerRequestError::Malformed(err.to_string()))?;
+    let control = message.control();
+    if matches!(control, ControlData::Response(_)) {
+        return Err(InnerRequestError::Malformed(
+            "inner message is not a request".to_string(),
+        ));
+    }
+
+    let path = control
+        .path()
+        .and_then(|path| std::str::from_utf8(path).ok())
+        .ok_or(InnerRequestError::NotFound)?;
+    let path = path.split_once('?').map_or(path, |(path, _)| path);
+    let task_id = match path.rsplit('/').take(3).collect::<Vec<_>>()[..] {
+        ["reports", task_id, "tasks"] => task_id
+            .parse::<TaskId>()
+            .map_err(|_| InnerRequestError::NotFound)?,
+        _ => return Err(InnerRequestError::NotFound),
+    };
+
+    if control.method() != Some(&b"POST"[..]) {
+        return Err(InnerRequestError::MethodNotAllowed);
+    }
+
+    let content_type = message
+        .header()
+        .get(b"content-type")
+        .and_then(|content_type| std::str::from_utf8(content_type).ok())
+        .and_then(|content_type| content_type.parse::<mime::Mime>().ok());
+    if content_type.as_ref().map(mime::Mime::essence_str) != Some(Report::MEDIA_TYPE) {
+        return Err(InnerRequestError::UnsupportedMediaType);
+    }
+
+    Ok(InnerUploadRequest {
+        task_id,
+        report: message.content().to_vec(),
+    })
+}
+
+/// Encodes a binary HTTP response to be encapsulated back to the client.
+pub(crate) fn encode_inner_response(
+    status: u16,
+    content_type: Option<&[u8]>,
+    body: &[u8],
+) -> Result<Vec<u8>, anyhow::Error> {
+    let status = StatusCode::try_from(status)
+        .map_err(|_| anyhow!("invalid HTTP status {status} for inner response"))?;
+    let mut message = Message::response(status);
+    if let Some(content_type) = content_type {
+        message.put_header("content-type", content_type);
+    }
+    message.write_content(body);
+
+    let mut encoded = Vec::new();
+    message
+        .write_bhttp(Mode::KnownLength, &mut encoded)
+        .context("couldn't encode inner response")?;
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        InnerRequestError, encode_inner_response, generate_keypair, is_supported_ciphersuite,
+        key_config, parse_inner_request,
+    };
+    use assert_matches::assert_matches;
+    use bhttp::{ControlData, Message, Mode};
+    use janus_core::hpke::HpkeCiphersuite;
+    use janus_messages::{
+        HpkeAeadId, HpkeConfigId, HpkeKdfId, HpkeKemId, MediaType, Report, TaskId,
+    };
+    use ohttp::ClientRequest;
+    use rand::random;
+    use std::io::Cursor;
+
+    fn bhttp_request(method: &str, path: &str, content_type: &str, body: &[u8]) -> Vec<u8> {
+        let mut message = Message::request(
+            method.into(),
+            "https".into(),
+            "leader.example.com".into(),
+            path.into(),
+        );
+        message.put_header("content-type", content_type);
+        message.write_content(body);
+        let mut encoded = Vec::new();
+        message
+            .write_bhttp(Mode::KnownLength, &mut encoded)
+            .unwrap();
+        encoded
+    }
+
+    #[test]
+    fn supported_ciphersuites() {
+        assert!(is_supported_ciphersuite(&HpkeCiphersuite::new(
+            HpkeKemId::X25519HkdfSha256,
+            HpkeKdfId::HkdfSha256,
+            HpkeAeadId::Aes128Gcm,
+        )));
+        assert!(!is_supported_ciphersuite(&HpkeCiphersuite::new(
+            HpkeKemId::P256HkdfSha256,
+            HpkeKdfId::HkdfSha256,
+            HpkeAeadId::Aes128Gcm,
+        )));
+    }
+
+    #[test]
+    fn generated_keypair_matches_derived_key_config() {
+        let ciphersuite = HpkeCiphersuite::new(
+            HpkeKemId::X25519HkdfSha256,
+            HpkeKdfId::HkdfSha256,
+            HpkeAeadId::ChaCha20Poly1305,
+        );
+        let keypair = generate_keypair(HpkeConfigId::from(7), &ciphersuite).unwrap();
+        let mut key_config = key_config(&keypair).unwrap();
+
+        // The advertised key config embeds the stored public key.
+        let encoded = key_config.encode().unwrap();
+        assert_eq!(encoded[0], 7);
+        assert_eq!(
+            &encoded[3..encoded.len() - 6],
+            keypair.config().public_key().as_ref()
+        );
+
+        // A request encapsulated to the advertised config can be decapsulated with the stored seed.
+        let (encapsulated, _) = ClientRequest::from_config(&mut key_config)
+            .unwrap()
+            .encapsulate(b"hello")
+            .unwrap();
+        let server = ohttp::Server::new(key_config(&keypair).unwrap()).unwrap();
+        assert_eq!(server.decapsulate(&encapsulated).unwrap().0, b"hello");
+    }
+
+    #[test]
+    fn parse_upload_request() {
+        let task_id: TaskId = random();
+        for path in [
+            format!("/tasks/{task_id}/reports"),
+            format!("/prefix/tasks/{task_id}/reports?foo=bar"),
+        ] {
+            let request =
+                parse_inner_request(&bhttp_request("POST", &path, Report::MEDIA_TYPE, b"report"))
+                    .unwrap();
+            assert_eq!(request.task_id, task_id);
+            assert_eq!(request.report, b"report");
+        }
+    }
+
+    #[test]
+    fn reject_other_requests() {
+        let task_id: TaskId = random();
+        assert_matches!(
+            parse_inner_request(&bhttp_request(
+                "POST",
+                &format!("/tasks/{task_id}/collection_jobs/foo"),
+                Report::MEDIA_TYPE,
+                b"",
+            )),
+            Err(InnerRequestError::NotFound)
+        );
+        assert_matches!(
+            parse_inner_request(&bhttp_request(
+                "POST",
+                "/tasks/not-a-task-id/reports",
+                Report::MEDIA_TYPE,
+                b"",
+            )),
+            Err(InnerRequestError::NotFound)
+        );
+        assert_matches!(
+            parse_inner_request(&bhttp_request(
+                "GET",
+                &format!("/tasks/{task_id}/reports"),
+                Report::MEDIA_TYPE,
+                b"",
+            )),
+            Err(InnerRequestError::MethodNotAllowed)
+        );
+        assert_matches!(
+            parse_inner_request(&bhttp_request(
+                "POST",
+                &format!("/tasks/{task_id}/reports"),
+                "application/json",
+                b"",
+            )),
+            Err(InnerRequestError::UnsupportedMediaType)
+        );
+        assert_matches!(
+            parse_inner_request(b"garbage"),
+            Err(InnerRequestError::Malformed(_))
+        );
+    }
+
+    #[test]
+    fn inner_response() {
+        let encoded = encode_inner_response(400, Some(b"application/problem+json"), b"{}").unwrap();
+        let message = Message::read_bhttp(&mut Cursor::new(&encoded)).unwrap();
+        assert_matches!(message.control(), ControlData::Response(status) => {
+            assert_eq!(u16::from(*status), 400);
+        });
+        assert_eq!(
+            message.header().get(b"content-type"),
+            Some(&b"application/problem+json"[..])
+        );
+        assert_eq!(message.content(), b"{}");
+    }
+}
@@ -0,0 +1,254 @@
+use anyhow::{Context, Result};
+use janus_aggregator_core::datastore::Datastore;
+use janus_core::time::Clock;
+use janus_messages::{Duration, Interval, TaskId, Time};
+use opentelemetry::metrics::{Counter, Meter};
+use std::sync::Arc;
+use tracing::error;
+
+/// Maintains the partitions of the client_reports & report_aggregations tables. Partitions are
+/// created ahead of the client timestamps of incoming reports, and dropped once all of their rows
+/// have expired, which is much cheaper than deleting the rows individually.
+pub struct PartitionManager<C: Clock> {
+    // Dependencies.
+    datastore: Arc<Datastore<C>>,
+
+    // Configuration.
+    partition_width: Duration,
+    lookahead: Duration,
+
+    // Metrics.
+    created_partition_counter: Counter<u64>,
+    dropped_partition_counter: Counter<u64>,
+}
+
+impl<C: Clock> PartitionManager<C> {
+    pub fn new(
+        datastore: Arc<Datastore<C>>,
+        meter: &Meter,
+        partition_width: Duration,
+        lookahead: Duration,
+    ) -> Self {
+        let created_partition_counter = meter
+            .u64_counter("janus_report_partitions_created")
+            .with_description("Count of report table partitions created by the partition manager.")
+            .with_unit("{partition}")
+            .build();
+        let dropped_partition_counter = meter
+            .u64_counter("janus_report_partitions_dropped")
+            .with_description("Count of report table partitions dropped by the partition manager.")
+            .with_unit("{partition}")
+            .build();
+
+        created_partition_counter.add(0, &[]);
+        dropped_partition_counter.add(0, &[]);
+
+        Self {
+            datastore,
+            partition_width,
+            lookahead,
+            created_partition_counter,
+            dropped_partition_counter,
+        }
+    }
+
+    #[tracing::instrument(name = "PartitionManager::run", skip(self))]
+    pub async fn run(&self) -> Result<()> {
+        let dropped = self
+            .datastore
+            .run_tx("partition_manager_deleted_tasks", |tx| {
+                Box::pin(async move { tx.drop_report_partitions_for_deleted_tasks().await })
+            })
+            .await
+            .context("couldn't drop partitions of deleted tasks")?;
+        self.dropped_partition_counter.add(dropped, &[]);
+
+        // Retrieve tasks.
+        let task_ids: Vec<_> = self
+            .datastore
+            .run_tx("partition_manager_get_tasks", |tx| {
+                Box::pin(async move { tx.get_aggregator_tasks().await })
+            })
+            .await
+            .context("couldn't retrieve tasks")?
+            .into_iter()
+            .map(|task| *task.id())
+            .collect();
+
+        // Partitions cover the previous partition width, in case reports are still arriving for
+        // it, through the lookahead period.
+        let now = self.datastore.clock().now().as_seconds_since_epoch();
+        let start = now.saturating_sub(self.partition_width.as_seconds());
+        let end = now.saturating_add(self.lookahead.as_seconds());
+        let interval = Interval::new(
+            Time::from_seconds_since_epoch(start),
+            Duration::from_seconds(end - start),
+        )?;
+
+        // Creating & dropping partitions briefly locks the partitioned tables, so each task is
+        // handled in its own transaction, one at a time.
+        for task_id in task_ids {
+            if let Err(err) = self.manage_task(task_id, interval).await {
+                error!(%task_id, ?err, "Couldn't manage report partitions")
+            }
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "PartitionManager::manage_task", skip(self))]
+    async fn manage_task(&self, task_id: TaskId, interval: Interval) -> Result<()> {
+        let (dropped, created) = self
+            .datastore
+            .run_tx("partition_manager", |tx| {
+                let partition_width = self.partition_width;
+
+                Box::pin(async move {
+                    let dropped = tx.drop_expired_report_partitions(&task_id).await?;
+                    let created = tx
+                        .create_report_partitions(&task_id, &interval, &partition_width)
+                        .await?;
+                    Ok((dropped, created))
+                })
+            })
+            .await?;
+
+        self.dropped_partition_counter.add(dropped, &[]);
+        self.created_partition_counter.add(created, &[]);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::aggregator::{
+        garbage_collector::GarbageCollector, partition_manager::PartitionManager,
+    };
+    use janus_aggregator_core::{
+        datastore::{models::LeaderStoredReport, test_util::ephemeral_datastore},
+        task::{self, AggregationMode, test_util::TaskBuilder},
+        test_util::noop_meter,
+    };
+    use janus_core::{
+        test_util::install_test_trace_subscriber,
+        time::{Clock, DurationExt, MockClock, TimeExt},
+        vdaf::VdafInstance,
+    };
+    use janus_messages::{Duration, Interval};
+    use prio::vdaf::dummy;
+    use std::sync::Arc;
+
+    const PARTITION_WIDTH: Duration = Duration::from_seconds(3600);
+    const LOOKAHEAD: Duration = Duration::from_seconds(3 * 3600);
+    const REPORT_EXPIRY_AGE: Duration = Duration::from_seconds(24 * 3600);
+
+    #[tokio::test]
+    async fn partitions_created_and_dropped() {
+        install_test_trace_subscriber();
+
+        let clock = MockClock::default();
+        let ephemeral_datastore = ephemeral_datastore().await;
+        let ds = Arc::new(ephemeral_datastore.datastore(clock.clone()).await);
+        let vdaf = dummy::Vdaf::new(1);
+
+        // Setup.
+        let task = TaskBuilder::new(
+            task::BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Fake { rounds: 1 },
+        )
+        .with_report_expiry_age(Some(REPORT_EXPIRY_AGE))
+        .with_time_precision(Duration::from_seconds(10))
+        .build()
+        .leader_view()
+        .unwrap();
+        let task_id = *task.id();
+        let report = LeaderStoredReport::new_dummy(task_id, clock.now());
+        let report_id = *report.metadata().id();
+        ds.run_unnamed_tx(|tx| {
+            let task = task.clone();
+            let report = report.clone();
+            Box::pin(async move {
+                tx.put_aggregator_task(&task).await.unwrap();
+                tx.put_client_report(&report).await.unwrap();
+                Ok(())
+            })
+        })
+        .await
+        .unwrap();
+
+        let partition_manager =
+            PartitionManager::new(Arc::clone(&ds), &noop_meter(), PARTITION_WIDTH, LOOKAHEAD);
+
+        // Run.
+        partition_manager.run().await.unwrap();
+
+        // Verify: the partitions covering the previous hour through the lookahead period exist,
+        // and the report is still readable.
+        let now = clock.now();
+        ds.run_unnamed_tx(|tx| {
+            let vdaf = vdaf.clone();
+            Box::pin(async move {
+                assert_eq!(
+                    tx.create_report_partitions(
+                        &task_id,
+                        &Interval::new(
+                            now.sub(&PARTITION_WIDTH).unwrap(),
+                            PARTITION_WIDTH.add(&LOOKAHEAD).unwrap(),
+                        )
+                        .unwrap(),
+                        &PARTITION_WIDTH,
+                    )
+                    .await
+                    .unwrap(),
+                    0
+                );
+                assert!(
+                    tx.get_client_report(&vdaf, &task_id, &report_id)
+                        .await
+                        .unwrap()
+                        .is_some()
+                );
+                Ok(())
+            })
+        })
+        .await
+        .unwrap();
+
+        // Advance the clock past the expiry of every partition created so far, and run again.
+        clock.advance(&REPORT_EXPIRY_AGE.add(&LOOKAHEAD).unwrap());
+        clock.advance(&PARTITION_WIDTH);
+        partition_manager.run().await.unwrap();
+
+        // Verify: the expired partitions were dropped, along with the report, so garbage
+        // collection has no rows left to delete.
+        let got_deleted_reports = ds
+            .run_unnamed_tx(|tx| {
+                Box::pin(async move {
+                    assert_eq!(
+                        tx.drop_expired_report_partitions(&task_id).await.unwrap(),
+                        0
+                    );
+                    tx.delete_expired_client_reports(&task_id, u64::try_from(i64::MAX).unwrap())
+                        .await
+                })
+            })
+            .await
+            .unwrap();
+        assert_eq!(got_deleted_reports, 0);
+
+        // Garbage collection still runs normally alongside partitioning.
+        GarbageCollector::new(
+            Arc::clone(&ds),
+            &noop_meter(),
+            u64::try_from(i64::MAX).unwrap(),
+            u64::try_from(i64::MAX).unwrap(),
+            u64::try_from(i64::MAX).unwrap(),
+            1,
+            None,
+        )
+        .run()
+        .await
+        .unwrap();
+    }
+}
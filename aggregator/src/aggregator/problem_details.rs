@@ -144,6 +144,7 @@ mod tests {
             DapProblemType::InvalidBatchSize,
             DapProblemType::BatchMismatch,
             DapProblemType::BatchOverlap,
+            DapProblemType::InvalidAggregationParameter,
         ] {
             let uri = problem_type.type_uri();
             assert_eq!(uri.parse::<DapProblemType>().unwrap(), problem_type);
@@ -260,6 +261,10 @@ mod tests {
                     }),
                     Some(DapProblemType::BatchOverlap),
                 ),
+                TestCase::new(
+                    Box::new(|| Error::InvalidAggregationParameter(random(), "batch".to_string())),
+                    Some(DapProblemType::InvalidAggregationParameter),
+                ),
                 TestCase::new(
                     Box::new(|| {
                         Error::BatchMismatch(Box::new(BatchMismatch {
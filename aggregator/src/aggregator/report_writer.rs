@@ -1,7 +1,10 @@
-use crate::aggregator::{
-    Error,
-    batch_mode::UploadableBatchMode,
-    error::{ReportRejection, ReportRejectionReason},
+use crate::{
+    aggregator::{
+        Error,
+        batch_mode::UploadableBatchMode,
+        error::{ReportRejection, ReportRejectionReason},
+    },
+    metrics::{TaskMetricLabels, uploaded_reports_counter},
 };
 use async_trait::async_trait;
 use futures::future::{join_all, try_join_all};
@@ -14,6 +17,10 @@ use janus_aggregator_core::{
 };
 use janus_core::{Runtime, time::Clock};
 use janus_messages::TaskId;
+use opentelemetry::{
+    KeyValue,
+    metrics::{Counter, Meter},
+};
 use rand::{Rng, rng};
 use std::{
     collections::BTreeMap,
@@ -25,7 +32,7 @@ use std::{
 };
 use tokio::{
     select,
-    sync::{mpsc, oneshot},
+    sync::{mpsc, oneshot, watch},
     time::{Instant, sleep_until},
 };
 use tracing::{debug, error};
@@ -40,6 +47,21 @@ type ReportWriteBatcherReceiver<C> = mpsc::Receiver<(ReportResult<C>, Option<Res
 #[derive(Debug)]
 pub struct ReportWriteBatcher<C> {
     report_tx: ReportWriteBatcherSender<C>,
+    batch_config_tx: watch::Sender<BatchConfig>,
+}
+
+/// Metrics recorded for each batch of uploaded reports.
+#[derive(Clone, Debug)]
+struct UploadMetrics {
+    uploaded_reports_counter: Counter<u64>,
+    task_metric_labels: TaskMetricLabels,
+}
+
+/// Determines when a batch of reports is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct BatchConfig {
+    max_batch_size: usize,
+    max_batch_write_delay: Duration,
 }
 
 impl<C: Clock> ReportWriteBatcher<C> {
@@ -49,8 +71,18 @@ impl<C: Clock> ReportWriteBatcher<C> {
         counter_shard_count: u64,
         max_batch_size: usize,
         max_batch_write_delay: Duration,
+        meter: &Meter,
+        task_metric_labels: TaskMetricLabels,
     ) -> Self {
         let (report_tx, report_rx) = mpsc::channel(1);
+        let metrics = UploadMetrics {
+            uploaded_reports_counter: uploaded_reports_counter(meter),
+            task_metric_labels,
+        };
+        let (batch_config_tx, batch_config_rx) = watch::channel(BatchConfig {
+            max_batch_size,
+            max_batch_write_delay,
+        });
 
         let runtime = Arc::new(runtime);
         let runtime_clone = Arc::clone(&runtime);
@@ -60,13 +92,25 @@ impl<C: Clock> ReportWriteBatcher<C> {
                 runtime_clone,
                 report_rx,
                 counter_shard_count,
-                max_batch_size,
-                max_batch_write_delay,
+                batch_config_rx,
+                metrics,
             )
             .await
         });
 
-        Self { report_tx }
+        Self {
+            report_tx,
+            batch_config_tx,
+        }
+    }
+
+    /// Changes the maximum batch size and write delay. Batches started before the change are
+    /// written according to the previous values.
+    pub fn reconfigure(&self, max_batch_size: usize, max_batch_write_delay: Duration) {
+        self.batch_config_tx.send_replace(BatchConfig {
+            max_batch_size,
+            max_batch_write_delay,
+        });
     }
 
     /// Save a report rejection to the database.
@@ -103,19 +147,20 @@ impl<C: Clock> ReportWriteBatcher<C> {
 
     #[tracing::instrument(
         name = "ReportWriteBatcher::run_upload_batcher",
-        skip(ds, runtime, report_rx)
+        skip(ds, runtime, report_rx, batch_config_rx, metrics)
     )]
     async fn run_upload_batcher<R: Runtime + Send + Sync>(
         ds: Arc<Datastore<C>>,
         runtime: Arc<R>,
         mut report_rx: ReportWriteBatcherReceiver<C>,
         counter_shard_count: u64,
-        max_batch_size: usize,
-        max_batch_write_delay: Duration,
+        batch_config_rx: watch::Receiver<BatchConfig>,
+        metrics: UploadMetrics,
     ) {
         let mut is_done = false;
         let mut batch_expiry = Instant::now();
-        let mut report_results = Vec::with_capacity(max_batch_size);
+        let mut batch_config = *batch_config_rx.borrow();
+        let mut report_results = Vec::with_capacity(batch_config.max_batch_size);
         while !is_done {
             // Wait for an event of interest.
             let write_batch = select! {
@@ -126,10 +171,12 @@ impl<C: Clock> ReportWriteBatcher<C> {
                         // We got an item. Add it to the current batch of reports to be written.
                         Some(report) => {
                             if report_results.is_empty() {
-                                batch_expiry = Instant::now() + max_batch_write_delay;
+                                batch_config = *batch_config_rx.borrow();
+                                batch_expiry =
+                                    Instant::now() + batch_config.max_batch_write_delay;
                             }
                             report_results.push(report);
-                            report_results.len() >= max_batch_size
+                            report_results.len() >= batch_config.max_batch_size
                         }
 
                         // The channel is closed. Note this, and write any final reports that may be
@@ -148,10 +195,13 @@ impl<C: Clock> ReportWriteBatcher<C> {
             // If the event made us want to write the current batch to storage, do so.
             if write_batch {
                 let ds = Arc::clone(&ds);
-                let report_results =
-                    replace(&mut report_results, Vec::with_capacity(max_batch_size));
+                let metrics = metrics.clone();
+                let report_results = replace(
+                    &mut report_results,
+                    Vec::with_capacity(batch_config.max_batch_size),
+                );
                 runtime.spawn(async move {
-                    Self::write_batch(ds, counter_shard_count, report_results).await;
+                    Self::write_batch(ds, counter_shard_count, report_results, &metrics).await;
                 });
             }
         }
@@ -162,6 +212,7 @@ impl<C: Clock> ReportWriteBatcher<C> {
         ds: Arc<Datastore<C>>,
         counter_shard_count: u64,
         report_results: Vec<(ReportResult<C>, Option<ResultSender>)>,
+        metrics: &UploadMetrics,
     ) {
         // Run all report writes concurrently.
         let (report_results, result_senders): (Vec<ReportResult<C>>, Vec<Option<ResultSender>>) =
@@ -194,6 +245,8 @@ impl<C: Clock> ReportWriteBatcher<C> {
 
         match results {
             Ok((results, task_upload_counters)) => {
+                task_upload_counters.record_metrics(metrics);
+
                 // Write the task upload counters in a separate transaction from uploads. This is
                 // to prevent seralization conflicts causing excess repeated work when INSERTing.
                 //
@@ -338,6 +391,41 @@ impl TaskUploadCounters {
             ReportRejectionReason::TooEarly => entry.increment_report_too_early(),
             ReportRejectionReason::OutdatedHpkeConfig(_) => entry.increment_report_outdated_key(),
             ReportRejectionReason::TaskNotStarted => entry.increment_task_not_started(),
+            ReportRejectionReason::TaskNotAcceptingReports => {
+                entry.increment_task_not_accepting_reports()
+            }
+        }
+    }
+
+    /// Adds the stored [`TaskUploadCounter`]s to the uploaded reports counter metric, by outcome.
+    fn record_metrics(&self, metrics: &UploadMetrics) {
+        // Unwrap safety: panic on mutex poisoning.
+        let map = self.0.lock().unwrap();
+        for (task_id, counter) in map.iter() {
+            for (outcome, count) in [
+                ("success", counter.report_success()),
+                ("interval_collected", counter.interval_collected()),
+                ("decode_failure", counter.report_decode_failure()),
+                ("decrypt_failure", counter.report_decrypt_failure()),
+                ("expired", counter.report_expired()),
+                ("outdated_key", counter.report_outdated_key()),
+                ("too_early", counter.report_too_early()),
+                ("task_not_started", counter.task_not_started()),
+                ("task_ended", counter.task_ended()),
+                (
+                    "task_not_accepting_reports",
+                    counter.task_not_accepting_reports(),
+                ),
+            ] {
+                if count > 0 {
+                    metrics.uploaded_reports_counter.add(
+                        count,
+                        &metrics
+                            .task_metric_labels
+                            .attributes(task_id, [KeyValue::new("outcome", outcome)]),
+                    );
+                }
+            }
         }
     }
 
@@ -7,7 +7,7 @@ use janus_aggregator_core::{
 use janus_core::{
     hpke::{self, HpkeApplicationInfo, HpkeKeypair, Label},
     time::MockClock,
-    vdaf::{VdafInstance, vdaf_application_context},
+    vdaf::{VdafInstance, vdaf_application_context, vdaf_dp_strategies},
 };
 use janus_messages::{
     Extension, HpkeConfig, InputShareAad, PlaintextInputShare, Report, ReportId, ReportMetadata,
@@ -69,7 +69,12 @@ pub fn create_report_custom(
     id: ReportId,
     hpke_keypair: &HpkeKeypair,
 ) -> Report {
-    assert_eq!(task.vdaf(), &VdafInstance::Prio3Count);
+    assert_eq!(
+        task.vdaf(),
+        &VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy
+        }
+    );
 
     let vdaf = Prio3Count::new_count(2).unwrap();
     let report_metadata = ReportMetadata::new(id, report_timestamp, Vec::new());
@@ -12,7 +12,7 @@ use janus_aggregator_core::{
         test_util::{EphemeralDatastore, ephemeral_datastore},
     },
     task::{
-        AggregationMode, BatchMode,
+        AggregationMode, BatchMode, TaskState,
         test_util::{Task, TaskBuilder},
     },
     test_util::noop_meter,
@@ -26,7 +26,7 @@ use janus_core::{
         runtime::{TestRuntime, TestRuntimeManager},
     },
     time::{Clock, MockClock, TimeExt},
-    vdaf::{VERIFY_KEY_LENGTH_PRIO3, VdafInstance},
+    vdaf::{VERIFY_KEY_LENGTH_PRIO3, VdafInstance, vdaf_dp_strategies},
 };
 use janus_messages::{
     Duration, HpkeCiphertext, HpkeConfigId, InputShareAad, Interval, PlaintextInputShare, Query,
@@ -63,7 +63,9 @@ impl UploadTest {
         let task = TaskBuilder::new(
             BatchMode::TimeInterval,
             AggregationMode::Synchronous,
-            VdafInstance::Prio3Count,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
         )
         .with_time_precision(Duration::from_seconds(100))
         .build();
@@ -180,7 +182,7 @@ async fn upload() {
     assert_eq!(
         got_counter,
         Some(TaskUploadCounter::new_with_values(
-            0, 0, 0, 0, 0, 1, 0, 0, 0
+            0, 0, 0, 0, 0, 1, 0, 0, 0, 0
         ))
     )
 }
@@ -252,7 +254,7 @@ async fn upload_batch() {
     assert_eq!(
         got_counters,
         Some(TaskUploadCounter::new_with_values(
-            0, 0, 0, 0, 0, 100, 0, 0, 0
+            0, 0, 0, 0, 0, 100, 0, 0, 0, 0
         ))
     );
 }
@@ -325,7 +327,7 @@ async fn upload_wrong_hpke_config_id() {
     assert_eq!(
         got_counters,
         Some(TaskUploadCounter::new_with_values(
-            0, 0, 0, 0, 1, 0, 0, 0, 0
+            0, 0, 0, 0, 1, 0, 0, 0, 0, 0
         ))
     )
 }
@@ -379,7 +381,7 @@ async fn upload_report_in_the_future_boundary_condition() {
     assert_eq!(
         got_counters,
         Some(TaskUploadCounter::new_with_values(
-            0, 0, 0, 0, 0, 1, 0, 0, 0
+            0, 0, 0, 0, 0, 1, 0, 0, 0, 0
         ))
     )
 }
@@ -437,7 +439,7 @@ async fn upload_report_in_the_future_past_clock_skew() {
     assert_eq!(
         got_counters,
         Some(TaskUploadCounter::new_with_values(
-            0, 0, 0, 0, 0, 0, 1, 0, 0
+            0, 0, 0, 0, 0, 0, 1, 0, 0, 0
         ))
     )
 }
@@ -526,7 +528,7 @@ async fn upload_report_for_collected_batch() {
     assert_eq!(
         got_counters,
         Some(TaskUploadCounter::new_with_values(
-            1, 0, 0, 0, 0, 0, 0, 0, 0
+            1, 0, 0, 0, 0, 0, 0, 0, 0, 0
         ))
     )
 }
@@ -551,7 +553,9 @@ async fn upload_report_task_not_started() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_time_precision(Duration::from_seconds(100))
     .with_task_start(Some(
@@ -598,7 +602,7 @@ async fn upload_report_task_not_started() {
     assert_eq!(
         got_counters,
         Some(TaskUploadCounter::new_with_values(
-            0, 0, 0, 0, 0, 0, 0, 1, 0
+            0, 0, 0, 0, 0, 0, 0, 1, 0, 0
         ))
     )
 }
@@ -622,7 +626,9 @@ async fn upload_report_task_ended() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_time_precision(Duration::from_seconds(100))
     .with_task_end(Some(
@@ -671,11 +677,87 @@ async fn upload_report_task_ended() {
     assert_eq!(
         got_counters,
         Some(TaskUploadCounter::new_with_values(
-            0, 0, 0, 0, 0, 0, 0, 0, 1
+            0, 0, 0, 0, 0, 0, 0, 0, 1, 0
         ))
     )
 }
 
+#[tokio::test]
+async fn upload_report_task_not_accepting_reports() {
+    for state in [TaskState::Paused, TaskState::Draining, TaskState::Archived] {
+        let mut runtime_manager = TestRuntimeManager::new();
+        let UploadTest {
+            aggregator,
+            clock,
+            datastore,
+            ephemeral_datastore: _ephemeral_datastore,
+            hpke_keypair,
+            ..
+        } = UploadTest::new_with_runtime(
+            default_aggregator_config(),
+            runtime_manager.with_label("aggregator"),
+        )
+        .await;
+
+        let task = TaskBuilder::new(
+            BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
+        )
+        .with_time_precision(Duration::from_seconds(100))
+        .with_state(state)
+        .build()
+        .leader_view()
+        .unwrap();
+        datastore.put_aggregator_task(&task).await.unwrap();
+
+        let report = create_report(
+            &task,
+            &hpke_keypair,
+            clock.now_aligned_to_precision(task.time_precision()),
+        );
+
+        // Try to upload the report, verify that we get the expected error.
+        let error = aggregator
+            .handle_upload(task.id(), &report.get_encoded().unwrap())
+            .await
+            .unwrap_err();
+        assert_matches!(
+            error.as_ref(),
+            Error::ReportRejected(rejection) => {
+                assert_eq!(task.id(), rejection.task_id());
+                assert_eq!(report.metadata().id(), rejection.report_id());
+                assert_eq!(report.metadata().time(), rejection.time());
+                assert_matches!(
+                    rejection.reason(),
+                    ReportRejectionReason::TaskNotAcceptingReports
+                );
+            }
+        );
+
+        // Wait for the report writer to have completed one write task.
+        runtime_manager
+            .wait_for_completed_tasks("aggregator", 1)
+            .await;
+
+        let got_counters = datastore
+            .run_unnamed_tx(|tx| {
+                let task_id = *task.id();
+                Box::pin(async move { tx.get_task_upload_counter(&task_id).await })
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            got_counters,
+            Some(TaskUploadCounter::new_with_values(
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 1
+            ))
+        )
+    }
+}
+
 #[tokio::test]
 async fn upload_report_unaligned_time() {
     let mut runtime_manager = TestRuntimeManager::new();
@@ -695,7 +777,9 @@ async fn upload_report_unaligned_time() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_time_precision(Duration::from_seconds(42))
     .build()
@@ -740,7 +824,9 @@ async fn upload_report_report_expired() {
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_time_precision(Duration::from_seconds(100))
     .with_report_expiry_age(Some(Duration::from_seconds(60)))
@@ -788,7 +874,7 @@ async fn upload_report_report_expired() {
     assert_eq!(
         got_counters,
         Some(TaskUploadCounter::new_with_values(
-            0, 0, 0, 1, 0, 0, 0, 0, 0
+            0, 0, 0, 1, 0, 0, 0, 0, 0, 0
         ))
     )
 }
@@ -850,7 +936,7 @@ async fn upload_report_faulty_encryption() {
     assert_eq!(
         got_counters,
         Some(TaskUploadCounter::new_with_values(
-            0, 0, 1, 0, 0, 0, 0, 0, 0
+            0, 0, 1, 0, 0, 0, 0, 0, 0, 0
         ))
     )
 }
@@ -913,7 +999,7 @@ async fn upload_report_public_share_decode_failure() {
     assert_eq!(
         got_counters,
         Some(TaskUploadCounter::new_with_values(
-            0, 1, 0, 0, 0, 0, 0, 0, 0
+            0, 1, 0, 0, 0, 0, 0, 0, 0, 0
         ))
     )
 }
@@ -990,7 +1076,7 @@ async fn upload_report_leader_input_share_decode_failure() {
     assert_eq!(
         got_counters,
         Some(TaskUploadCounter::new_with_values(
-            0, 1, 0, 0, 0, 0, 0, 0, 0
+            0, 1, 0, 0, 0, 0, 0, 0, 0, 0
         ))
     )
 }
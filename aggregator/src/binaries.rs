@@ -1,6 +1,7 @@
 pub mod aggregation_job_creator;
 pub mod aggregation_job_driver;
 pub mod aggregator;
+pub mod all_in_one;
 pub mod collection_job_driver;
 pub mod garbage_collector;
 pub mod janus_cli;
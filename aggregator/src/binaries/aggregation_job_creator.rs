@@ -2,34 +2,58 @@ use crate::{
     aggregator::aggregation_job_creator::AggregationJobCreator,
     binary_utils::{BinaryContext, BinaryOptions, CommonBinaryOptions},
     config::{BinaryConfig, CommonConfig},
+    metrics::TaskMetricLabels,
 };
 use anyhow::Result;
 use clap::Parser;
+use janus_aggregator_core::datastore::Datastore;
 use janus_core::time::RealClock;
+use opentelemetry::metrics::Meter;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::info;
+use trillium_tokio::Stopper;
 
 pub async fn main_callback(ctx: BinaryContext<RealClock, Options, Config>) -> Result<()> {
-    // Start creating aggregation jobs.
-    let aggregation_job_creator = Arc::new(AggregationJobCreator::new(
+    run_aggregation_job_creator(
         Arc::new(ctx.datastore),
         ctx.meter,
-        ctx.config.batch_aggregation_shard_count,
-        Duration::from_secs(ctx.config.tasks_update_frequency_s),
-        Duration::from_secs(ctx.config.aggregation_job_creation_interval_s),
-        ctx.config.min_aggregation_job_size,
-        ctx.config.max_aggregation_job_size,
-        ctx.config.aggregation_job_creation_report_window,
-        janus_messages::Duration::from_seconds(ctx.config.late_report_grace_period_s),
-    ));
-    info!("Running aggregation job creator");
-    aggregation_job_creator.run(ctx.stopper).await;
+        ctx.stopper,
+        &ctx.config.aggregation_job_creator,
+        TaskMetricLabels::new(ctx.config.common_config.metrics_config.task_labels.as_ref()),
+    )
+    .await;
 
     Ok(())
 }
 
+pub(super) async fn run_aggregation_job_creator(
+    datastore: Arc<Datastore<RealClock>>,
+    meter: Meter,
+    stopper: Stopper,
+    config: &AggregationJobCreatorConfig,
+    task_metric_labels: TaskMetricLabels,
+) {
+    // Start creating aggregation jobs.
+    let aggregation_job_creator = Arc::new(
+        AggregationJobCreator::new(
+            datastore,
+            meter,
+            config.batch_aggregation_shard_count,
+            Duration::from_secs(config.tasks_update_frequency_s),
+            Duration::from_secs(config.aggregation_job_creation_interval_s),
+            config.min_aggregation_job_size,
+            config.max_aggregation_job_size,
+            config.aggregation_job_creation_report_window,
+            janus_messages::Duration::from_seconds(config.late_report_grace_period_s),
+        )
+        .with_task_metric_labels(task_metric_labels),
+    );
+    info!("Running aggregation job creator");
+    aggregation_job_creator.run(stopper).await;
+}
+
 #[derive(Debug, Parser)]
 #[clap(
     name = "janus-aggregation-job-creator",
@@ -69,13 +93,22 @@ impl BinaryOptions for Options {
 ///
 /// let _decoded: Config = serde_yaml::from_str(yaml_config).unwrap();
 /// ```
-// TODO(#3293): remove aliases during next breaking changes window.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     #[serde(flatten)]
     pub common_config: CommonConfig,
 
+    #[serde(flatten)]
+    pub aggregation_job_creator: AggregationJobCreatorConfig,
+}
+
+/// Configuration options for creating aggregation jobs, shared by the Aggregation Job Creator binary
+/// and the all-in-one binary.
+// TODO(#3293): remove aliases during next breaking changes window.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AggregationJobCreatorConfig {
     /// Defines the number of shards to break each batch aggregation into. Increasing this value
     /// will reduce the amount of database contention during leader aggregation, while increasing
     /// the cost of collection.
@@ -122,7 +155,7 @@ impl BinaryConfig for Config {
 
 #[cfg(test)]
 mod tests {
-    use super::{Config, Options};
+    use super::{AggregationJobCreatorConfig, Config, Options};
     use crate::config::{
         CommonConfig, default_max_transaction_retries,
         test_util::{generate_db_config, generate_metrics_config, generate_trace_config},
@@ -147,13 +180,15 @@ mod tests {
                 max_transaction_retries: default_max_transaction_retries(),
                 thread_pool_stack_size: None,
             },
-            batch_aggregation_shard_count: 32,
-            tasks_update_frequency_s: 3600,
-            aggregation_job_creation_interval_s: 60,
-            min_aggregation_job_size: 100,
-            max_aggregation_job_size: 500,
-            aggregation_job_creation_report_window: 5000,
-            late_report_grace_period_s: 3600,
+            aggregation_job_creator: AggregationJobCreatorConfig {
+                batch_aggregation_shard_count: 32,
+                tasks_update_frequency_s: 3600,
+                aggregation_job_creation_interval_s: 60,
+                min_aggregation_job_size: 100,
+                max_aggregation_job_size: 500,
+                aggregation_job_creation_report_window: 5000,
+                late_report_grace_period_s: 3600,
+            },
         })
     }
 
@@ -1,17 +1,57 @@
 use crate::{
     aggregator::aggregation_job_driver::AggregationJobDriver,
-    binary_utils::{BinaryContext, BinaryOptions, CommonBinaryOptions, job_driver::JobDriver},
+    binary_utils::{
+        BinaryContext, BinaryOptions, CommonBinaryOptions, job_driver::JobDriver,
+        readiness::ReadinessChecks, reload::map_config_updates,
+    },
     cache::HpkeKeypairCache,
-    config::{BinaryConfig, CommonConfig, JobDriverConfig, TaskprovConfig},
+    config::{BinaryConfig, CommonConfig, ConfigChanges, JobDriverConfig, TaskprovConfig},
+    metrics::TaskMetricLabels,
 };
 use anyhow::{Context, Result};
 use clap::Parser;
+use janus_aggregator_core::datastore::Datastore;
 use janus_core::{TokioRuntime, time::RealClock};
+use opentelemetry::metrics::Meter;
 use serde::{Deserialize, Serialize};
 use std::{fmt::Debug, sync::Arc, time::Duration};
+use tokio::sync::watch;
 use tracing::info;
+use trillium_tokio::Stopper;
 
 pub async fn main_callback(ctx: BinaryContext<RealClock, Options, Config>) -> Result<()> {
+    let job_driver_config_updates = map_config_updates(ctx.config_updates, |config: &Config| {
+        Ok(config.job_driver_config.clone())
+    })?;
+    run_aggregation_job_driver(
+        ctx.clock,
+        Arc::new(ctx.datastore),
+        ctx.meter,
+        ctx.stopper,
+        &ctx.readiness_checks,
+        "job_driver",
+        &ctx.config.job_driver_config,
+        job_driver_config_updates,
+        &ctx.config.aggregation_job_driver,
+        TaskMetricLabels::new(ctx.config.common_config.metrics_config.task_labels.as_ref()),
+    )
+    .await
+}
+
+/// Runs an aggregation job driver until `stopper` is stopped. Its readiness check is registered
+/// under `readiness_check_name`.
+pub(super) async fn run_aggregation_job_driver(
+    clock: RealClock,
+    datastore: Arc<Datastore<RealClock>>,
+    meter: Meter,
+    stopper: Stopper,
+    readiness_checks: &ReadinessChecks,
+    readiness_check_name: &'static str,
+    job_driver_config: &JobDriverConfig,
+    job_driver_config_updates: watch::Receiver<JobDriverConfig>,
+    config: &AggregationJobDriverConfig,
+    task_metric_labels: TaskMetricLabels,
+) -> Result<()> {
     const CLIENT_USER_AGENT: &str = concat!(
         env!("CARGO_PKG_NAME"),
         "/",
@@ -19,54 +59,59 @@ pub async fn main_callback(ctx: BinaryContext<RealClock, Options, Config>) -> Re
         "/aggregation_job_driver",
     );
 
-    let hpke_configs_refresh_interval = match ctx.config.hpke_configs_refresh_interval {
+    let hpke_configs_refresh_interval = match config.hpke_configs_refresh_interval {
         Some(duration) => Duration::from_millis(duration),
         None => HpkeKeypairCache::DEFAULT_REFRESH_INTERVAL,
     };
 
-    let datastore = Arc::new(ctx.datastore);
-    let aggregation_job_driver = Arc::new(AggregationJobDriver::new(
-        reqwest::Client::builder()
-            .user_agent(CLIENT_USER_AGENT)
-            .timeout(Duration::from_secs(
-                ctx.config.job_driver_config.http_request_timeout_s,
-            ))
-            .connect_timeout(Duration::from_secs(
-                ctx.config
-                    .job_driver_config
-                    .http_request_connection_timeout_s,
-            ))
-            .build()
-            .context("couldn't create HTTP client")?,
-        ctx.config.job_driver_config.retry_config(),
-        &ctx.meter,
-        ctx.config.batch_aggregation_shard_count,
-        ctx.config.task_counter_shard_count,
-        hpke_configs_refresh_interval,
-        Duration::from_millis(ctx.config.default_async_poll_interval),
-    ));
-    let lease_duration = Duration::from_secs(ctx.config.job_driver_config.worker_lease_duration_s);
+    let aggregation_job_driver = Arc::new(
+        AggregationJobDriver::new(
+            reqwest::Client::builder()
+                .user_agent(CLIENT_USER_AGENT)
+                .timeout(Duration::from_secs(
+                    job_driver_config.http_request_timeout_s,
+                ))
+                .connect_timeout(Duration::from_secs(
+                    job_driver_config.http_request_connection_timeout_s,
+                ))
+                .build()
+                .context("couldn't create HTTP client")?,
+            job_driver_config.retry_config(),
+            &meter,
+            config.batch_aggregation_shard_count,
+            config.task_counter_shard_count,
+            hpke_configs_refresh_interval,
+            Duration::from_millis(config.default_async_poll_interval),
+        )
+        .with_task_metric_labels(task_metric_labels.clone()),
+    );
+    let lease_duration = Duration::from_secs(job_driver_config.worker_lease_duration_s);
 
     // Start running.
-    let job_driver = Arc::new(JobDriver::new(
-        ctx.clock,
+    let job_driver = JobDriver::new(
+        clock,
         TokioRuntime,
-        ctx.meter,
-        ctx.stopper,
-        Duration::from_secs(ctx.config.job_driver_config.job_discovery_interval_s),
-        ctx.config.job_driver_config.max_concurrent_job_workers,
-        Duration::from_secs(
-            ctx.config
-                .job_driver_config
-                .worker_lease_clock_skew_allowance_s,
-        ),
+        meter,
+        stopper,
+        Duration::from_secs(job_driver_config.job_discovery_interval_s),
+        job_driver_config.max_concurrent_job_workers,
+        Duration::from_secs(job_driver_config.worker_lease_clock_skew_allowance_s),
         aggregation_job_driver
             .make_incomplete_job_acquirer_callback(Arc::clone(&datastore), lease_duration),
         aggregation_job_driver.make_job_stepper_callback(
             Arc::clone(&datastore),
-            ctx.config.job_driver_config.maximum_attempts_before_failure,
+            job_driver_config.maximum_attempts_before_failure,
         ),
-    )?);
+    )?;
+    let job_driver = Arc::new(
+        job_driver
+            .with_config_updates(job_driver_config_updates)
+            .with_task_metric_labels(task_metric_labels),
+    );
+    readiness_checks.register(
+        readiness_check_name,
+        job_driver.readiness_check(lease_duration),
+    );
 
     info!("Running aggregation job driver");
     job_driver.run().await;
@@ -127,6 +172,15 @@ pub struct Config {
     pub common_config: CommonConfig,
     #[serde(flatten)]
     pub job_driver_config: JobDriverConfig,
+    #[serde(flatten)]
+    pub aggregation_job_driver: AggregationJobDriverConfig,
+}
+
+/// Configuration options for driving aggregation jobs, other than the [`JobDriverConfig`] common to
+/// all job drivers. Shared by the Aggregation Job Driver binary and the all-in-one binary.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AggregationJobDriverConfig {
     #[serde(default)]
     pub taskprov_config: TaskprovConfig,
 
@@ -163,6 +217,15 @@ impl BinaryConfig for Config {
     fn common_config_mut(&mut self) -> &mut CommonConfig {
         &mut self.common_config
     }
+
+    fn apply_tunables(&mut self, new: &Self, changes: &mut ConfigChanges) {
+        self.job_driver_config
+            .apply_tunables(&new.job_driver_config, changes);
+    }
+
+    fn validate_tunables(&self) -> Result<()> {
+        self.job_driver_config.validate_tunables()
+    }
 }
 
 fn default_task_counter_shard_count() -> u64 {
@@ -175,7 +238,7 @@ fn default_default_async_poll_interval() -> u64 {
 
 #[cfg(test)]
 mod tests {
-    use super::{Config, Options};
+    use super::{AggregationJobDriverConfig, Config, Options};
     use crate::config::{
         CommonConfig, JobDriverConfig, TaskprovConfig, default_max_transaction_retries,
         test_util::{generate_db_config, generate_metrics_config, generate_trace_config},
@@ -212,11 +275,13 @@ mod tests {
                 retry_max_interval_ms: 30_000,
                 retry_max_elapsed_time_ms: 300_000,
             },
-            batch_aggregation_shard_count: 32,
-            task_counter_shard_count: 64,
-            hpke_configs_refresh_interval: Some(180000),
-            default_async_poll_interval: 5_000,
-            taskprov_config: TaskprovConfig::default(),
+            aggregation_job_driver: AggregationJobDriverConfig {
+                batch_aggregation_shard_count: 32,
+                task_counter_shard_count: 64,
+                hpke_configs_refresh_interval: Some(180000),
+                default_async_poll_interval: 5_000,
+                taskprov_config: TaskprovConfig::default(),
+            },
         })
     }
 
@@ -1,16 +1,56 @@
 use crate::{
     aggregator::collection_job_driver::{CollectionJobDriver, RetryStrategy},
-    binary_utils::{BinaryContext, BinaryOptions, CommonBinaryOptions, job_driver::JobDriver},
-    config::{BinaryConfig, CommonConfig, JobDriverConfig},
+    binary_utils::{
+        BinaryContext, BinaryOptions, CommonBinaryOptions, job_driver::JobDriver,
+        readiness::ReadinessChecks, reload::map_config_updates,
+    },
+    config::{BinaryConfig, CommonConfig, ConfigChanges, JobDriverConfig},
+    metrics::TaskMetricLabels,
 };
 use anyhow::{Context, Result};
 use clap::Parser;
+use janus_aggregator_core::datastore::Datastore;
 use janus_core::{TokioRuntime, time::RealClock};
+use opentelemetry::metrics::Meter;
 use serde::{Deserialize, Serialize};
 use std::{fmt::Debug, sync::Arc, time::Duration};
+use tokio::sync::watch;
 use tracing::info;
+use trillium_tokio::Stopper;
 
 pub async fn main_callback(ctx: BinaryContext<RealClock, Options, Config>) -> Result<()> {
+    let job_driver_config_updates = map_config_updates(ctx.config_updates, |config: &Config| {
+        Ok(config.job_driver_config.clone())
+    })?;
+    run_collection_job_driver(
+        ctx.clock,
+        Arc::new(ctx.datastore),
+        ctx.meter,
+        ctx.stopper,
+        &ctx.readiness_checks,
+        "job_driver",
+        &ctx.config.job_driver_config,
+        job_driver_config_updates,
+        &ctx.config.collection_job_driver,
+        TaskMetricLabels::new(ctx.config.common_config.metrics_config.task_labels.as_ref()),
+    )
+    .await
+}
+
+/// Runs a collection job driver until `stopper` is stopped. Its readiness check is registered
+/// under `readiness_check_name`.
+pub(super) async fn run_collection_job_driver(
+    clock: RealClock,
+    datastore: Arc<Datastore<RealClock>>,
+    meter: Meter,
+    stopper: Stopper,
+    readiness_checks: &ReadinessChecks,
+    readiness_check_name: &'static str,
+    job_driver_config: &JobDriverConfig,
+    job_driver_config_updates: watch::Receiver<JobDriverConfig>,
+    config: &CollectionJobDriverConfig,
+    task_metric_labels: TaskMetricLabels,
+) -> Result<()> {
     const CLIENT_USER_AGENT: &str = concat!(
         env!("CARGO_PKG_NAME"),
         "/",
@@ -18,53 +58,58 @@ pub async fn main_callback(ctx: BinaryContext<RealClock, Options, Config>) -> Re
         "/collection_job_driver"
     );
 
-    let datastore = Arc::new(ctx.datastore);
-    let collection_job_driver = Arc::new(CollectionJobDriver::new(
-        reqwest::Client::builder()
-            .user_agent(CLIENT_USER_AGENT)
-            .timeout(Duration::from_secs(
-                ctx.config.job_driver_config.http_request_timeout_s,
-            ))
-            .connect_timeout(Duration::from_secs(
-                ctx.config
-                    .job_driver_config
-                    .http_request_connection_timeout_s,
-            ))
-            .build()
-            .context("couldn't create HTTP client")?,
-        ctx.config.job_driver_config.retry_config(),
-        &ctx.meter,
-        ctx.config.batch_aggregation_shard_count,
-        RetryStrategy::new(
-            Duration::from_secs(ctx.config.min_collection_job_retry_delay_s),
-            Duration::from_secs(ctx.config.max_collection_job_retry_delay_s),
-            ctx.config.collection_job_retry_delay_exponential_factor,
+    let collection_job_driver = Arc::new(
+        CollectionJobDriver::new(
+            reqwest::Client::builder()
+                .user_agent(CLIENT_USER_AGENT)
+                .timeout(Duration::from_secs(
+                    job_driver_config.http_request_timeout_s,
+                ))
+                .connect_timeout(Duration::from_secs(
+                    job_driver_config.http_request_connection_timeout_s,
+                ))
+                .build()
+                .context("couldn't create HTTP client")?,
+            job_driver_config.retry_config(),
+            &meter,
+            config.batch_aggregation_shard_count,
+            RetryStrategy::new(
+                Duration::from_secs(config.min_collection_job_retry_delay_s),
+                Duration::from_secs(config.max_collection_job_retry_delay_s),
+                config.collection_job_retry_delay_exponential_factor,
+            )
+            .context("Couldn't create collection retry strategy")?,
+            config.max_future_concurrency,
         )
-        .context("Couldn't create collection retry strategy")?,
-        ctx.config.max_future_concurrency,
-    ));
-    let lease_duration = Duration::from_secs(ctx.config.job_driver_config.worker_lease_duration_s);
+        .with_task_metric_labels(task_metric_labels.clone()),
+    );
+    let lease_duration = Duration::from_secs(job_driver_config.worker_lease_duration_s);
 
     // Start running.
-    let job_driver = Arc::new(JobDriver::new(
-        ctx.clock,
+    let job_driver = JobDriver::new(
+        clock,
         TokioRuntime,
-        ctx.meter,
-        ctx.stopper,
-        Duration::from_secs(ctx.config.job_driver_config.job_discovery_interval_s),
-        ctx.config.job_driver_config.max_concurrent_job_workers,
-        Duration::from_secs(
-            ctx.config
-                .job_driver_config
-                .worker_lease_clock_skew_allowance_s,
-        ),
+        meter,
+        stopper,
+        Duration::from_secs(job_driver_config.job_discovery_interval_s),
+        job_driver_config.max_concurrent_job_workers,
+        Duration::from_secs(job_driver_config.worker_lease_clock_skew_allowance_s),
         collection_job_driver
             .make_incomplete_job_acquirer_callback(Arc::clone(&datastore), lease_duration),
         collection_job_driver.make_job_stepper_callback(
             Arc::clone(&datastore),
-            ctx.config.job_driver_config.maximum_attempts_before_failure,
+            job_driver_config.maximum_attempts_before_failure,
         ),
-    )?);
+    )?;
+    let job_driver = Arc::new(
+        job_driver
+            .with_config_updates(job_driver_config_updates)
+            .with_task_metric_labels(task_metric_labels),
+    );
+    readiness_checks.register(
+        readiness_check_name,
+        job_driver.readiness_check(lease_duration),
+    );
 
     info!("Running collection job driver");
     job_driver.run().await;
@@ -119,7 +164,6 @@ impl BinaryOptions for Options {
 ///
 /// let _decoded: Config = serde_yaml::from_str(yaml_config).unwrap();
 /// ```
-// TODO(#3293): remove aliases during next breaking changes window.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
@@ -127,7 +171,16 @@ pub struct Config {
     pub common_config: CommonConfig,
     #[serde(flatten)]
     pub job_driver_config: JobDriverConfig,
+    #[serde(flatten)]
+    pub collection_job_driver: CollectionJobDriverConfig,
+}
 
+/// Configuration options for driving collection jobs, other than the [`JobDriverConfig`] common to
+/// all job drivers. Shared by the Collection Job Driver binary and the all-in-one binary.
+// TODO(#3293): remove aliases during next breaking changes window.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CollectionJobDriverConfig {
     /// Defines the number of shards to break each batch aggregation into. Increasing this value
     /// will reduce the amount of database contention during leader aggregation, while increasing
     /// the cost of collection.
@@ -136,7 +189,7 @@ pub struct Config {
     /// The minimum duration to wait, in seconds, before retrying a collection job that has been
     /// stepped but was not ready yet because not all included reports had finished aggregation.
     #[serde(
-        default = "Config::default_min_collection_job_retry_delay_s",
+        default = "CollectionJobDriverConfig::default_min_collection_job_retry_delay_s",
         alias = "min_collection_job_retry_delay_secs"
     )]
     pub min_collection_job_retry_delay_s: u64,
@@ -144,7 +197,7 @@ pub struct Config {
     /// The maximum duration to wait, in seconds, before retrying a collection job that has been
     /// stepped but was not ready yet because not all included reports had finished aggregation.
     #[serde(
-        default = "Config::default_max_collection_job_retry_delay_s",
+        default = "CollectionJobDriverConfig::default_max_collection_job_retry_delay_s",
         alias = "max_collection_job_retry_delay_secs"
     )]
     pub max_collection_job_retry_delay_s: u64,
@@ -152,16 +205,18 @@ pub struct Config {
     /// The exponential factor to use when computing a retry delay when retrying a collection job
     /// that has been stepped but was not ready yet because not all included reports had finished
     /// aggregation.
-    #[serde(default = "Config::default_collection_job_retry_delay_exponential_factor")]
+    #[serde(
+        default = "CollectionJobDriverConfig::default_collection_job_retry_delay_exponential_factor"
+    )]
     pub collection_job_retry_delay_exponential_factor: f64,
 
     /// The maximum number of futures to await concurrenctly while servicing jobs. Higher values
     /// will cause higher peak memory usage.
-    #[serde(default = "Config::default_max_future_concurrency")]
+    #[serde(default = "CollectionJobDriverConfig::default_max_future_concurrency")]
     pub max_future_concurrency: usize,
 }
 
-impl Config {
+impl CollectionJobDriverConfig {
     fn default_min_collection_job_retry_delay_s() -> u64 {
         600
     }
@@ -187,11 +242,20 @@ impl BinaryConfig for Config {
     fn common_config_mut(&mut self) -> &mut CommonConfig {
         &mut self.common_config
     }
+
+    fn apply_tunables(&mut self, new: &Self, changes: &mut ConfigChanges) {
+        self.job_driver_config
+            .apply_tunables(&new.job_driver_config, changes);
+    }
+
+    fn validate_tunables(&self) -> Result<()> {
+        self.job_driver_config.validate_tunables()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Config, Options};
+    use super::{CollectionJobDriverConfig, Config, Options};
     use crate::config::{
         CommonConfig, JobDriverConfig, default_max_transaction_retries,
         test_util::{generate_db_config, generate_metrics_config, generate_trace_config},
@@ -228,11 +292,13 @@ mod tests {
                 retry_max_interval_ms: 30_000,
                 retry_max_elapsed_time_ms: 300_000,
             },
-            batch_aggregation_shard_count: 32,
-            min_collection_job_retry_delay_s: 600,
-            max_collection_job_retry_delay_s: 3600,
-            collection_job_retry_delay_exponential_factor: 1.25,
-            max_future_concurrency: 10000,
+            collection_job_driver: CollectionJobDriverConfig {
+                batch_aggregation_shard_count: 32,
+                min_collection_job_retry_delay_s: 600,
+                max_collection_job_retry_delay_s: 3600,
+                collection_job_retry_delay_exponential_factor: 1.25,
+                max_future_concurrency: 10000,
+            },
         })
     }
 
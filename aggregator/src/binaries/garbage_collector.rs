@@ -4,16 +4,21 @@ use anyhow::Result;
 use clap::Parser;
 use janus_aggregator_core::datastore::Datastore;
 use janus_core::time::RealClock;
+use janus_messages::Duration as JanusDuration;
 use opentelemetry::metrics::Meter;
 use serde::{Deserialize, Serialize};
-use tokio::time::interval;
+use tokio::{
+    select,
+    sync::watch,
+    time::{self, Instant, interval},
+};
 use tracing::{error, info};
 use trillium_tokio::Stopper;
 
 use crate::{
-    aggregator::garbage_collector::GarbageCollector,
-    binary_utils::{BinaryContext, BinaryOptions, CommonBinaryOptions},
-    config::{BinaryConfig, CommonConfig},
+    aggregator::{garbage_collector::GarbageCollector, partition_manager::PartitionManager},
+    binary_utils::{BinaryContext, BinaryOptions, CommonBinaryOptions, reload::map_config_updates},
+    config::{BinaryConfig, CommonConfig, ConfigChanges},
 };
 
 use super::aggregator::GarbageCollectorConfig;
@@ -24,12 +29,23 @@ pub async fn main_callback(ctx: BinaryContext<RealClock, Options, Config>) -> Re
         datastore,
         meter,
         stopper,
+        config_updates,
         ..
     } = ctx;
 
     let datastore = Arc::new(datastore);
+    let gc_config_updates = map_config_updates(config_updates, |config: &Config| {
+        Ok(config.garbage_collection.clone())
+    })?;
 
-    run_garbage_collector(datastore, config.garbage_collection, meter, stopper).await;
+    run_garbage_collector(
+        datastore,
+        config.garbage_collection,
+        meter,
+        stopper,
+        gc_config_updates,
+    )
+    .await;
 
     Ok(())
 }
@@ -39,21 +55,73 @@ pub(super) async fn run_garbage_collector(
     gc_config: GarbageCollectorConfig,
     meter: Meter,
     stopper: Stopper,
+    mut gc_config_updates: watch::Receiver<GarbageCollectorConfig>,
 ) {
-    let gc = GarbageCollector::new(
-        datastore,
-        &meter,
-        gc_config.report_limit,
-        gc_config.aggregation_limit,
-        gc_config.collection_limit,
-        gc_config.tasks_per_tx,
-        gc_config.concurrent_tx_limit,
-    );
+    let new_gc = |gc_config: &GarbageCollectorConfig| {
+        GarbageCollector::new(
+            Arc::clone(&datastore),
+            &meter,
+            gc_config.report_limit,
+            gc_config.aggregation_limit,
+            gc_config.collection_limit,
+            gc_config.tasks_per_tx,
+            gc_config.concurrent_tx_limit,
+        )
+    };
+    let new_partition_manager = |gc_config: &GarbageCollectorConfig| {
+        gc_config.report_partitioning.map(|report_partitioning| {
+            PartitionManager::new(
+                Arc::clone(&datastore),
+                &meter,
+                JanusDuration::from_seconds(report_partitioning.partition_width_s),
+                JanusDuration::from_seconds(report_partitioning.lookahead_s),
+            )
+        })
+    };
+    let mut gc = new_gc(&gc_config);
+    let mut partition_manager = new_partition_manager(&gc_config);
+    let mut gc_frequency = Duration::from_secs(gc_config.gc_frequency_s);
     info!("Running garbage collector");
-    let mut interval = interval(Duration::from_secs(gc_config.gc_frequency_s));
-    while stopper.stop_future(interval.tick()).await.is_some() {
-        if let Err(err) = gc.run().await {
-            error!(?err, "GC error");
+    let mut interval = interval(gc_frequency);
+    loop {
+        let event = stopper
+            .stop_future(async {
+                select! {
+                    _ = interval.tick() => None,
+                    Ok(()) = gc_config_updates.changed() => {
+                        Some(gc_config_updates.borrow_and_update().clone())
+                    }
+                }
+            })
+            .await;
+        match event {
+            // Stopped.
+            None => break,
+
+            Some(None) => {
+                // Expired partitions are dropped first, leaving GC only the expired rows stored
+                // outside of them.
+                if let Some(partition_manager) = &partition_manager {
+                    if let Err(err) = partition_manager.run().await {
+                        error!(?err, "Partition manager error");
+                    }
+                }
+                if let Err(err) = gc.run().await {
+                    error!(?err, "GC error");
+                }
+            }
+
+            // The configuration was reloaded. Subsequent runs use the new limits, and if the
+            // frequency changed, the next run is scheduled one new period from now.
+            Some(Some(gc_config)) => {
+                gc = new_gc(&gc_config);
+                partition_manager = new_partition_manager(&gc_config);
+                let new_gc_frequency = Duration::from_secs(gc_config.gc_frequency_s);
+                if new_gc_frequency != gc_frequency {
+                    gc_frequency = new_gc_frequency;
+                    interval = time::interval_at(Instant::now() + gc_frequency, gc_frequency);
+                }
+            }
         }
     }
 }
@@ -114,6 +182,15 @@ impl BinaryConfig for Config {
     fn common_config_mut(&mut self) -> &mut CommonConfig {
         &mut self.common_config
     }
+
+    fn apply_tunables(&mut self, new: &Self, changes: &mut ConfigChanges) {
+        self.garbage_collection
+            .apply_tunables(&new.garbage_collection, changes);
+    }
+
+    fn validate_tunables(&self) -> Result<()> {
+        self.garbage_collection.validate_tunables()
+    }
 }
 
 #[cfg(test)]
@@ -156,6 +233,7 @@ mod tests {
                 collection_limit: 50,
                 tasks_per_tx: 1,
                 concurrent_tx_limit: None,
+                report_partitioning: None,
             },
         });
     }
@@ -1,5 +1,7 @@
 use crate::{
-    binary_utils::{CommonBinaryOptions, database_pool, datastore, read_config},
+    binary_utils::{
+        CommonBinaryOptions, database_pool, datastore, parse_aes_128_gcm_key, read_config,
+    },
     config::{BinaryConfig, CommonConfig},
     metrics::{MetricsExporterHandle, install_metrics_exporter},
     trace::{TraceGuards, install_trace_subscriber},
@@ -7,22 +9,30 @@ use crate::{
 use anyhow::{Context, Result, anyhow};
 use aws_lc_rs::aead::AES_128_GCM;
 use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use janus_aggregator_api::git_revision;
 use janus_aggregator_core::{
-    datastore::{self, Datastore, models::HpkeKeyState},
-    task::{AggregationMode, AggregatorTask, SerializedAggregatorTask},
+    audit::{self, emit_audit_log_event},
+    datastore::{
+        self, Crypter, Datastore, Transaction,
+        models::{
+            ApiToken, ApiTokenScope, AuditLogEntry, AuditLogOutcome, HpkeKeyState, JobStepOutcome,
+        },
+        task_archive::{SealedTaskArchive, TaskArchive},
+    },
+    task::{AggregationMode, AggregatorTask, AggregatorTaskParameters, SerializedAggregatorTask},
     taskprov::{PeerAggregator, VerifyKeyInit},
 };
 use janus_core::{
-    auth_tokens::AuthenticationToken,
+    auth_tokens::{AuthenticationToken, AuthenticationTokenHash},
     cli::{AeadAlgorithm, KdfAlgorithm, KemAlgorithm},
     hpke::HpkeKeypair,
     initialize_rustls,
     time::{Clock, RealClock},
 };
 use janus_messages::{
-    Duration, HpkeAeadId, HpkeConfig, HpkeConfigId, HpkeKdfId, HpkeKemId, Role, codec::Encode as _,
+    AggregationJobId, CollectionJobId, Duration, HpkeAeadId, HpkeConfig, HpkeConfigId, HpkeKdfId,
+    HpkeKemId, Role, TaskId, Time, codec::Encode as _,
 };
 use k8s_openapi::api::core::v1::Secret;
 use kube::api::{ObjectMeta, PostParams};
@@ -31,16 +41,22 @@ use prio::codec::Decode as _;
 use rand::{Rng, distr::StandardUniform, rng};
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap, HashSet},
+    future::Future,
     path::{Path, PathBuf},
+    pin::Pin,
+    str::FromStr,
     sync::Arc,
+    time::Duration as StdDuration,
 };
 use tokio::{
     fs,
     runtime::{self, Runtime},
     sync::Mutex,
+    time::{self, MissedTickBehavior},
+    try_join,
 };
-use tracing::{debug, info};
+use tracing::{debug, error, info, warn};
 use url::Url;
 
 pub fn run(command_line_options: CommandLineOptions) -> Result<()> {
@@ -177,11 +193,271 @@ enum Command {
         echo_tasks: bool,
     },
 
+    /// Reconcile the tasks in the datastore with a directory of task definitions
+    ///
+    /// Declared tasks missing from the datastore are created, and changes to the end time or
+    /// authentication tokens of existing tasks are applied. Other changes to existing tasks are
+    /// reported as conflicts and not applied. The reconciliation plan is written to stdout.
+    ReconcileTasks {
+        #[clap(flatten)]
+        kubernetes_secret_options: KubernetesSecretOptions,
+
+        /// A directory of YAML files, each containing a list of tasks
+        tasks_dir: PathBuf,
+
+        /// If true, tasks in the datastore which are not declared are deleted
+        ///
+        /// Tasks provisioned via taskprov are never deleted.
+        #[clap(long, default_value = "false")]
+        delete_undeclared_tasks: bool,
+
+        /// If set, reconcile repeatedly, waiting this many seconds between reconciliations
+        #[arg(long)]
+        interval_secs: Option<u64>,
+    },
+
     /// Create a datastore key and write it to a Kubernetes secret
     CreateDatastoreKey {
         #[clap(flatten)]
         kubernetes_secret_options: KubernetesSecretOptions,
     },
+
+    /// List abandoned aggregation and collection jobs, along with the last error recorded for each
+    ListAbandonedJobs {
+        #[clap(flatten)]
+        kubernetes_secret_options: KubernetesSecretOptions,
+
+        /// Only list jobs belonging to this task
+        #[arg(long)]
+        task_id: Option<TaskId>,
+    },
+
+    /// Reset the attempt counters of an abandoned job and return it to the queue
+    ///
+    /// The leader asks the helper to delete its view of an aggregation job when abandoning it, so a
+    /// requeued aggregation job which had already reached the helper may fail again.
+    RequeueJob {
+        #[clap(flatten)]
+        kubernetes_secret_options: KubernetesSecretOptions,
+
+        #[clap(flatten)]
+        job: JobOptions,
+    },
+
+    /// Show the recorded history of unsuccessful attempts to step a job, most recent first
+    JobHistory {
+        #[clap(flatten)]
+        kubernetes_secret_options: KubernetesSecretOptions,
+
+        #[clap(flatten)]
+        job: JobOptions,
+    },
+
+    /// Forcibly abandon a job
+    ///
+    /// Reports in a failed aggregation job which had not yet been sent to the helper are returned
+    /// to the pool of unaggregated reports, to be picked up by a new aggregation job.
+    FailJob {
+        #[clap(flatten)]
+        kubernetes_secret_options: KubernetesSecretOptions,
+
+        #[clap(flatten)]
+        job: JobOptions,
+    },
+
+    /// Export a task to an encrypted archive, for import into another datastore
+    ///
+    /// The archive holds the task, every HPKE keypair, and the task's outstanding batches, batch
+    /// aggregations, collection jobs & aggregate share jobs. Each unexpired client report is
+    /// included so that its report ID remains protected against replay. Export fails while any of
+    /// the task's aggregation jobs are in progress. Tasks provisioned via taskprov cannot be
+    /// exported.
+    ExportTask {
+        #[clap(flatten)]
+        kubernetes_secret_options: KubernetesSecretOptions,
+
+        /// The ID of the task to export
+        #[arg(long)]
+        task_id: TaskId,
+
+        /// Include the contents of client reports which have not yet been aggregated, so that they
+        /// can be aggregated after import
+        #[clap(long, default_value = "false")]
+        include_unaggregated_reports: bool,
+
+        /// The location to write the archive
+        #[arg(long)]
+        archive_file: PathBuf,
+
+        /// The AES-128-GCM key used to encrypt the archive, in unpadded base64url
+        #[arg(long, env = "TASK_ARCHIVE_KEY", hide_env_values = true)]
+        archive_key: String,
+    },
+
+    /// Import a task from an archive written by export-task
+    ///
+    /// The task keeps its task ID, and must not already exist. Secret values are re-encrypted
+    /// under this datastore's keys.
+    ImportTask {
+        #[clap(flatten)]
+        kubernetes_secret_options: KubernetesSecretOptions,
+
+        /// The location of the archive
+        archive_file: PathBuf,
+
+        /// The AES-128-GCM key the archive was encrypted with, in unpadded base64url
+        #[arg(long, env = "TASK_ARCHIVE_KEY", hide_env_values = true)]
+        archive_key: String,
+    },
+    /// Create a named token for the aggregator API, and write it to stdout
+    ///
+    /// Only a hash of the token is stored, so it cannot be shown again. Named tokens are accepted
+    /// in addition to the aggregator API's configured `auth_tokens`, which retain full access.
+    CreateApiToken {
+        #[clap(flatten)]
+        kubernetes_secret_options: KubernetesSecretOptions,
+
+        /// A unique name for the token, which is recorded as the actor in the audit log
+        #[arg(long)]
+        name: String,
+
+        /// A scope to grant the token. May be repeated.
+        #[arg(long = "scope", value_enum, required = true)]
+        scopes: Vec<ApiTokenScope>,
+
+        /// Restrict the token to this task. May be repeated. A restricted token may only use
+        /// routes naming one of its tasks. If omitted, the token may access any task.
+        #[arg(long = "task-id")]
+        task_ids: Vec<TaskId>,
+    },
+
+    /// Revoke a named aggregator API token, so that it can no longer be used
+    RevokeApiToken {
+        #[clap(flatten)]
+        kubernetes_secret_options: KubernetesSecretOptions,
+
+        /// The name of the token to revoke
+        #[arg(long)]
+        name: String,
+    },
+}
+
+/// Identifies a single aggregation or collection job.
+#[derive(Debug, Parser)]
+struct JobOptions {
+    /// The kind of job
+    #[arg(long, value_enum)]
+    job_kind: JobKind,
+
+    /// The ID of the task the job belongs to
+    #[arg(long)]
+    task_id: TaskId,
+
+    /// The ID of the job, in unpadded base64url
+    #[arg(long)]
+    job_id: String,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum JobKind {
+    Aggregation,
+    Collection,
+}
+
+impl JobOptions {
+    fn job_id(&self) -> Result<JobId> {
+        Ok(match self.job_kind {
+            JobKind::Aggregation => JobId::Aggregation(
+                AggregationJobId::from_str(&self.job_id).context("invalid aggregation job ID")?,
+            ),
+            JobKind::Collection => JobId::Collection(
+                CollectionJobId::from_str(&self.job_id).context("invalid collection job ID")?,
+            ),
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum JobId {
+    Aggregation(AggregationJobId),
+    Collection(CollectionJobId),
+}
+
+/// The identity recorded in the audit log for actions taken via this tool.
+const AUDIT_LOG_ACTOR: &str = "janus_cli";
+
+/// Returns a new audit log entry for an action taken by the given subcommand.
+fn audit_log_entry<C: Clock>(
+    datastore: &Datastore<C>,
+    command: &str,
+    action: &str,
+) -> AuditLogEntry {
+    AuditLogEntry::new(
+        AUDIT_LOG_ACTOR.to_string(),
+        action.to_string(),
+        datastore.clock().now(),
+    )
+    .with_route(Some(format!("janus_cli {command}")))
+}
+
+/// Runs a transaction making an audited change. `f` is given the audit log entry describing the
+/// change, and returns its result along with that entry, updated with e.g. the state of the
+/// affected resource before & after the change. The entry is written as part of the same
+/// transaction. If the transaction fails, the entry is instead written as a failure.
+async fn run_audited_tx<C, T, F>(
+    datastore: &Datastore<C>,
+    name: &'static str,
+    entry: AuditLogEntry,
+    f: F,
+) -> Result<T>
+where
+    C: Clock,
+    T: Send + 'static,
+    F: for<'a> Fn(
+            &'a Transaction<C>,
+            AuditLogEntry,
+        ) -> Pin<
+            Box<dyn Future<Output = Result<(T, AuditLogEntry), datastore::Error>> + Send + 'a>,
+        > + Send
+        + Sync
+        + 'static,
+{
+    let f = Arc::new(f);
+    let result = datastore
+        .run_tx(name, |tx| {
+            let (f, entry) = (Arc::clone(&f), entry.clone());
+            Box::pin(async move {
+                let (value, entry) = f(tx, entry).await?;
+                tx.put_audit_log_entry(&entry).await?;
+                Ok((value, entry))
+            })
+        })
+        .await;
+
+    match result {
+        Ok((value, entry)) => {
+            emit_audit_log_event(&entry);
+            Ok(value)
+        }
+        Err(err) => {
+            let entry = Arc::new(
+                entry
+                    .with_outcome(AuditLogOutcome::Failure)
+                    .with_details(serde_json::json!({ "error": err.to_string() })),
+            );
+            match datastore
+                .run_tx("put_audit_log_failure", |tx| {
+                    let entry = Arc::clone(&entry);
+                    Box::pin(async move { tx.put_audit_log_entry(&entry).await })
+                })
+                .await
+            {
+                Ok(()) => emit_audit_log_event(&entry),
+                Err(err) => warn!(?err, "couldn't record failure in audit log"),
+            }
+            Err(err.into())
+        }
+    }
 }
 
 impl Command {
@@ -313,6 +589,72 @@ impl Command {
                 Ok(())
             }
 
+            Command::ReconcileTasks {
+                kubernetes_secret_options,
+                tasks_dir,
+                delete_undeclared_tasks,
+                interval_secs,
+            } => {
+                let datastore = datastore_from_opts(
+                    kubernetes_secret_options,
+                    command_line_options,
+                    config_file,
+                    &kube_client,
+                )
+                .await?;
+
+                let Some(interval_secs) = interval_secs else {
+                    let plan = reconcile_tasks(
+                        &datastore,
+                        tasks_dir,
+                        *delete_undeclared_tasks,
+                        command_line_options.dry_run,
+                    )
+                    .await?;
+                    println!(
+                        "{}",
+                        serde_yaml::to_string(&plan)
+                            .context("couldn't serialize reconciliation plan to YAML")?
+                    );
+                    if !plan.conflicts.is_empty() {
+                        return Err(anyhow!(
+                            "{} task(s) have changes which can't be applied",
+                            plan.conflicts.len()
+                        ));
+                    }
+                    return Ok(());
+                };
+
+                let mut interval = time::interval(StdDuration::from_secs(*interval_secs));
+                interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                loop {
+                    interval.tick().await;
+                    match reconcile_tasks(
+                        &datastore,
+                        tasks_dir,
+                        *delete_undeclared_tasks,
+                        command_line_options.dry_run,
+                    )
+                    .await
+                    {
+                        Ok(plan) if plan.is_empty() => debug!("Tasks already reconciled"),
+                        Ok(plan) => {
+                            if !plan.conflicts.is_empty() {
+                                warn!(
+                                    conflict_count = %plan.conflicts.len(),
+                                    "Some tasks have changes which can't be applied"
+                                );
+                            }
+                            match serde_yaml::to_string(&plan) {
+                                Ok(plan_yaml) => println!("{plan_yaml}"),
+                                Err(err) => warn!(?err, "couldn't serialize reconciliation plan"),
+                            }
+                        }
+                        Err(err) => error!(?err, "Couldn't reconcile tasks"),
+                    }
+                }
+            }
+
             Command::CreateDatastoreKey {
                 kubernetes_secret_options,
             } => {
@@ -329,6 +671,173 @@ impl Command {
                 )
                 .await
             }
+
+            Command::ListAbandonedJobs {
+                kubernetes_secret_options,
+                task_id,
+            } => {
+                let datastore = datastore_from_opts(
+                    kubernetes_secret_options,
+                    command_line_options,
+                    config_file,
+                    &kube_client,
+                )
+                .await?;
+
+                let abandoned_jobs = list_abandoned_jobs(&datastore, task_id.as_ref()).await?;
+                println!(
+                    "{}",
+                    serde_yaml::to_string(&abandoned_jobs)
+                        .context("couldn't serialize abandoned jobs to YAML")?
+                );
+                Ok(())
+            }
+
+            Command::JobHistory {
+                kubernetes_secret_options,
+                job,
+            } => {
+                let datastore = datastore_from_opts(
+                    kubernetes_secret_options,
+                    command_line_options,
+                    config_file,
+                    &kube_client,
+                )
+                .await?;
+
+                let history = job_history(&datastore, job).await?;
+                println!(
+                    "{}",
+                    serde_yaml::to_string(&history)
+                        .context("couldn't serialize job history to YAML")?
+                );
+                Ok(())
+            }
+
+            Command::RequeueJob {
+                kubernetes_secret_options,
+                job,
+            } => {
+                let datastore = datastore_from_opts(
+                    kubernetes_secret_options,
+                    command_line_options,
+                    config_file,
+                    &kube_client,
+                )
+                .await?;
+
+                requeue_job(&datastore, command_line_options.dry_run, job).await
+            }
+
+            Command::FailJob {
+                kubernetes_secret_options,
+                job,
+            } => {
+                let datastore = datastore_from_opts(
+                    kubernetes_secret_options,
+                    command_line_options,
+                    config_file,
+                    &kube_client,
+                )
+                .await?;
+
+                fail_job(&datastore, command_line_options.dry_run, job).await
+            }
+
+            Command::ExportTask {
+                kubernetes_secret_options,
+                task_id,
+                include_unaggregated_reports,
+                archive_file,
+                archive_key,
+            } => {
+                let datastore = datastore_from_opts(
+                    kubernetes_secret_options,
+                    command_line_options,
+                    config_file,
+                    &kube_client,
+                )
+                .await?;
+                let archive_crypter = Crypter::new(Vec::from([
+                    parse_aes_128_gcm_key(archive_key).context("couldn't parse archive key")?
+                ]));
+
+                export_task(
+                    &datastore,
+                    command_line_options.dry_run,
+                    task_id,
+                    *include_unaggregated_reports,
+                    &archive_crypter,
+                    archive_file,
+                )
+                .await
+            }
+
+            Command::ImportTask {
+                kubernetes_secret_options,
+                archive_file,
+                archive_key,
+            } => {
+                let datastore = datastore_from_opts(
+                    kubernetes_secret_options,
+                    command_line_options,
+                    config_file,
+                    &kube_client,
+                )
+                .await?;
+                let archive_crypter = Crypter::new(Vec::from([
+                    parse_aes_128_gcm_key(archive_key).context("couldn't parse archive key")?
+                ]));
+
+                import_task(
+                    &datastore,
+                    command_line_options.dry_run,
+                    &archive_crypter,
+                    archive_file,
+                )
+                .await
+            }
+
+            Command::CreateApiToken {
+                kubernetes_secret_options,
+                name,
+                scopes,
+                task_ids,
+            } => {
+                let datastore = datastore_from_opts(
+                    kubernetes_secret_options,
+                    command_line_options,
+                    config_file,
+                    &kube_client,
+                )
+                .await?;
+
+                let token = create_api_token(
+                    &datastore,
+                    command_line_options.dry_run,
+                    name,
+                    scopes,
+                    task_ids,
+                )
+                .await?;
+                println!("{}", token.as_str());
+                Ok(())
+            }
+
+            Command::RevokeApiToken {
+                kubernetes_secret_options,
+                name,
+            } => {
+                let datastore = datastore_from_opts(
+                    kubernetes_secret_options,
+                    command_line_options,
+                    config_file,
+                    &kube_client,
+                )
+                .await?;
+
+                revoke_api_token(&datastore, command_line_options.dry_run, name).await
+            }
         }
     }
 }
@@ -359,13 +868,24 @@ async fn generate_hpke_key<C: Clock>(
     let hpke_keypair = Arc::new(HpkeKeypair::generate(id, kem, kdf, aead)?);
 
     if !dry_run {
-        datastore
-            .run_tx("generate_hpke_key", |tx| {
+        let entry = audit_log_entry(datastore, "generate-hpke-key", "create_hpke_keypair")
+            .with_target(Some(u8::from(id).to_string()));
+        run_audited_tx(datastore, "generate_hpke_key", entry, {
+            let hpke_keypair = Arc::clone(&hpke_keypair);
+            move |tx, entry| {
                 let hpke_keypair = Arc::clone(&hpke_keypair);
 
-                Box::pin(async move { tx.put_hpke_keypair(&hpke_keypair).await })
-            })
-            .await?;
+                Box::pin(async move {
+                    tx.put_hpke_keypair(&hpke_keypair).await?;
+                    let after = tx.get_hpke_keypair(&id).await?;
+                    Ok((
+                        (),
+                        entry.with_after(after.as_ref().map(audit::hpke_keypair_state)),
+                    ))
+                })
+            }
+        })
+        .await?;
     }
 
     if let Some(hpke_config_out_file) = hpke_config_out_file {
@@ -382,11 +902,22 @@ async fn set_hpke_key_state<C: Clock>(
     state: HpkeKeyState,
 ) -> Result<()> {
     if !dry_run {
-        datastore
-            .run_tx("set_hpke_key_state", |tx| {
-                Box::pin(async move { tx.set_hpke_keypair_state(&id, &state).await })
+        let entry = audit_log_entry(datastore, "set-hpke-key-state", "set_hpke_keypair_state")
+            .with_target(Some(u8::from(id).to_string()));
+        run_audited_tx(datastore, "set_hpke_key_state", entry, move |tx, entry| {
+            Box::pin(async move {
+                let before = tx.get_hpke_keypair(&id).await?;
+                tx.set_hpke_keypair_state(&id, &state).await?;
+                let after = tx.get_hpke_keypair(&id).await?;
+                Ok((
+                    (),
+                    entry
+                        .with_before(before.as_ref().map(audit::hpke_keypair_state))
+                        .with_after(after.as_ref().map(audit::hpke_keypair_state)),
+                ))
             })
-            .await?;
+        })
+        .await?;
     }
 
     Ok(())
@@ -424,13 +955,27 @@ async fn add_taskprov_peer_aggregator<C: Clock>(
     )?);
 
     if !dry_run {
-        datastore
-            .run_tx("add_taskprov_peer_aggregator", |tx| {
+        let entry = audit_log_entry(
+            datastore,
+            "add-taskprov-peer-aggregator",
+            "create_taskprov_peer_aggregator",
+        )
+        .with_target(Some(format!("{role} {peer_endpoint}")));
+        run_audited_tx(datastore, "add_taskprov_peer_aggregator", entry, {
+            let peer_aggregator = Arc::clone(&peer_aggregator);
+            move |tx, entry| {
                 let peer_aggregator = Arc::clone(&peer_aggregator);
 
-                Box::pin(async move { tx.put_taskprov_peer_aggregator(&peer_aggregator).await })
-            })
-            .await?;
+                Box::pin(async move {
+                    tx.put_taskprov_peer_aggregator(&peer_aggregator).await?;
+                    Ok((
+                        (),
+                        entry.with_after(Some(audit::peer_aggregator_state(&peer_aggregator))),
+                    ))
+                })
+            }
+        })
+        .await?;
     }
 
     Ok(())
@@ -469,35 +1014,781 @@ async fn provision_tasks<C: Clock>(
 
     let tasks = Arc::new(tasks);
 
-    // Write all tasks requested.
-    info!(task_count = %tasks.len(), "Writing tasks");
-    let written_tasks = datastore
-        .run_tx("provision-tasks", |tx| {
-            let tasks = Arc::clone(&tasks);
+    // Write all tasks requested.
+    info!(task_count = %tasks.len(), "Writing tasks");
+    // Each task written gets its own audit log entry, alongside the entry for the command as a
+    // whole.
+    let entry = audit_log_entry(datastore, "provision-tasks", "provision_tasks")
+        .with_target(Some(tasks_file.display().to_string()));
+    let (written_tasks, entries) = run_audited_tx(datastore, "provision-tasks", entry, {
+        let tasks = Arc::clone(&tasks);
+        move |tx, entry| {
+            let tasks = Arc::clone(&tasks);
+            Box::pin(async move {
+                let mut written_tasks = Vec::new();
+                let mut entries = Vec::new();
+                for task in tasks.iter() {
+                    // We attempt to delete the task, but ignore "task not found" errors since
+                    // the task not existing is an OK outcome too.
+                    let before = tx.get_aggregator_task(task.id()).await?;
+                    match tx.delete_task(task.id()).await {
+                        Ok(()) => {
+                            info!(task_id = %task.id(), "replacing existing task");
+                        }
+                        Err(datastore::Error::MutationTargetNotFound) => (),
+                        err => err?,
+                    }
+
+                    tx.put_aggregator_task(task).await?;
+
+                    let task_entry = task_audit_log_entry(&entry, "create_task", task.id())
+                        .with_before(before.as_ref().map(audit::task_state))
+                        .with_after(Some(audit::task_state(task)));
+                    tx.put_audit_log_entry(&task_entry).await?;
+                    entries.push(task_entry);
+
+                    written_tasks.push(task.clone());
+                }
+                Ok((
+                    (written_tasks, entries),
+                    entry.with_details(serde_json::json!({ "task_count": tasks.len() })),
+                ))
+            })
+        }
+    })
+    .await
+    .context("couldn't write tasks")?;
+    entries.iter().for_each(emit_audit_log_event);
+
+    Ok(written_tasks)
+}
+
+/// Returns a new audit log entry for an action taken on a single task, as part of the command
+/// described by `entry`.
+fn task_audit_log_entry(entry: &AuditLogEntry, action: &str, task_id: &TaskId) -> AuditLogEntry {
+    AuditLogEntry::new(
+        entry.actor().to_string(),
+        action.to_string(),
+        *entry.created_at(),
+    )
+    .with_route(entry.route().map(ToString::to_string))
+    .with_task_id(Some(*task_id))
+}
+
+/// The changes made (or, in a dry run, which would be made) by `reconcile-tasks`.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+struct TaskReconciliationPlan {
+    /// Declared tasks which are missing from the datastore.
+    created: Vec<TaskId>,
+    /// Existing tasks whose end time or authentication tokens have changed.
+    updated: Vec<TaskChange>,
+    /// Existing tasks which are not declared, and are deleted.
+    deleted: Vec<TaskId>,
+    /// Existing tasks which are not declared, and are left in place.
+    undeclared: Vec<TaskId>,
+    /// Existing tasks with changes which can't be applied in place. These tasks are left
+    /// unchanged.
+    conflicts: Vec<TaskChange>,
+}
+
+impl TaskReconciliationPlan {
+    /// Returns true if the plan makes no changes and reports no conflicts.
+    fn is_empty(&self) -> bool {
+        self.created.is_empty()
+            && self.updated.is_empty()
+            && self.deleted.is_empty()
+            && self.conflicts.is_empty()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct TaskChange {
+    task_id: TaskId,
+    /// The names of the task parameters which differ from the declared task.
+    fields: Vec<&'static str>,
+}
+
+/// Compares a declared task with the stored task of the same ID. Returns the names of the
+/// parameters which differ and can be updated in place, and the names of those which can't.
+fn diff_task(
+    stored: &AggregatorTask,
+    declared: &AggregatorTask,
+) -> (Vec<&'static str>, Vec<&'static str>) {
+    // A state change is only applied if the task may make that transition; archived tasks cannot
+    // be brought back.
+    let state_changed = stored.state() != declared.state();
+    let state_allowed = stored.state().can_transition_to(declared.state());
+    let mutable = [
+        ("state", state_changed && state_allowed),
+        ("task_end", stored.task_end() != declared.task_end()),
+        (
+            "aggregator_auth_token",
+            stored.aggregator_auth_token() != declared.aggregator_auth_token(),
+        ),
+        (
+            "aggregator_auth_token_hash",
+            stored.aggregator_auth_token_hash() != declared.aggregator_auth_token_hash(),
+        ),
+        (
+            "collector_auth_token_hash",
+            stored.collector_auth_token_hash() != declared.collector_auth_token_hash(),
+        ),
+    ];
+    let immutable = [
+        ("state", state_changed && !state_allowed),
+        ("role", stored.role() != declared.role()),
+        (
+            "peer_aggregator_endpoint",
+            stored.peer_aggregator_endpoint() != declared.peer_aggregator_endpoint(),
+        ),
+        ("batch_mode", stored.batch_mode() != declared.batch_mode()),
+        (
+            "aggregation_mode",
+            stored.aggregation_mode() != declared.aggregation_mode(),
+        ),
+        ("vdaf", stored.vdaf() != declared.vdaf()),
+        (
+            "vdaf_verify_key",
+            stored.opaque_vdaf_verify_key() != declared.opaque_vdaf_verify_key(),
+        ),
+        ("task_start", stored.task_start() != declared.task_start()),
+        (
+            "report_expiry_age",
+            stored.report_expiry_age() != declared.report_expiry_age(),
+        ),
+        (
+            "report_retention_window",
+            stored.report_retention_window() != declared.report_retention_window(),
+        ),
+        ("dp_budget", stored.dp_budget() != declared.dp_budget()),
+        (
+            "min_batch_size",
+            stored.min_batch_size() != declared.min_batch_size(),
+        ),
+        (
+            "time_precision",
+            stored.time_precision() != declared.time_precision(),
+        ),
+        (
+            "tolerable_clock_skew",
+            stored.tolerable_clock_skew() != declared.tolerable_clock_skew(),
+        ),
+        (
+            "collector_hpke_config",
+            stored.collector_hpke_config() != declared.collector_hpke_config(),
+        ),
+    ];
+
+    let changed = |fields: &[(&'static str, bool)]| -> Vec<&'static str> {
+        fields
+            .iter()
+            .filter(|(_, changed)| *changed)
+            .map(|(field, _)| *field)
+            .collect()
+    };
+    (changed(&mutable), changed(&immutable))
+}
+
+/// Plans the reconciliation of the stored tasks with the declared tasks.
+fn plan_task_reconciliation(
+    stored_tasks: &[AggregatorTask],
+    declared_tasks: &[AggregatorTask],
+    delete_undeclared_tasks: bool,
+) -> TaskReconciliationPlan {
+    let stored_tasks_by_id: HashMap<_, _> =
+        stored_tasks.iter().map(|task| (*task.id(), task)).collect();
+    let declared_task_ids: HashSet<_> = declared_tasks.iter().map(|task| *task.id()).collect();
+
+    let mut plan = TaskReconciliationPlan::default();
+    for declared in declared_tasks {
+        let task_id = *declared.id();
+        let Some(stored) = stored_tasks_by_id.get(&task_id) else {
+            plan.created.push(task_id);
+            continue;
+        };
+
+        let (mutable, immutable) = diff_task(stored, declared);
+        if !immutable.is_empty() {
+            plan.conflicts.push(TaskChange {
+                task_id,
+                fields: immutable,
+            });
+        } else if !mutable.is_empty() {
+            plan.updated.push(TaskChange {
+                task_id,
+                fields: mutable,
+            });
+        }
+    }
+
+    // Tasks provisioned via taskprov are never declared, so they are left alone.
+    let mut undeclared_task_ids: Vec<_> = stored_tasks
+        .iter()
+        .filter(|task| {
+            !declared_task_ids.contains(task.id())
+                && !matches!(
+                    task.aggregator_parameters(),
+                    AggregatorTaskParameters::TaskprovHelper { .. }
+                )
+        })
+        .map(|task| *task.id())
+        .collect();
+    undeclared_task_ids.sort();
+    if delete_undeclared_tasks {
+        plan.deleted = undeclared_task_ids;
+    } else {
+        plan.undeclared = undeclared_task_ids;
+    }
+
+    plan
+}
+
+/// Reads task definitions from each YAML file in the given directory.
+async fn read_task_definitions(tasks_dir: &Path) -> Result<Vec<AggregatorTask>> {
+    let mut paths = Vec::new();
+    let mut entries = fs::read_dir(tasks_dir)
+        .await
+        .with_context(|| format!("couldn't read tasks directory {tasks_dir:?}"))?;
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .with_context(|| format!("couldn't read tasks directory {tasks_dir:?}"))?
+    {
+        let path = entry.path();
+        if matches!(
+            path.extension().and_then(|extension| extension.to_str()),
+            Some("yaml" | "yml")
+        ) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut tasks = Vec::new();
+    let mut task_ids = HashSet::new();
+    for path in paths {
+        let task_file_contents = fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("couldn't read tasks file {path:?}"))?;
+        let serialized_tasks: Vec<SerializedAggregatorTask> =
+            serde_yaml::from_str(&task_file_contents)
+                .with_context(|| format!("couldn't parse tasks file {path:?}"))?;
+        for serialized_task in serialized_tasks {
+            let task = AggregatorTask::try_from(serialized_task)
+                .with_context(|| format!("invalid task in tasks file {path:?}"))?;
+            if !task_ids.insert(*task.id()) {
+                return Err(anyhow!(
+                    "task {} is declared more than once, in tasks file {path:?}",
+                    task.id()
+                ));
+            }
+            tasks.push(task);
+        }
+    }
+    Ok(tasks)
+}
+
+async fn reconcile_tasks<C: Clock>(
+    datastore: &Datastore<C>,
+    tasks_dir: &Path,
+    delete_undeclared_tasks: bool,
+    dry_run: bool,
+) -> Result<TaskReconciliationPlan> {
+    let declared_tasks = Arc::new(read_task_definitions(tasks_dir).await?);
+
+    if dry_run {
+        let plan = datastore
+            .run_tx("reconcile-tasks-plan", |tx| {
+                let declared_tasks = Arc::clone(&declared_tasks);
+                Box::pin(async move {
+                    let stored_tasks = tx.get_aggregator_tasks().await?;
+                    Ok(plan_task_reconciliation(
+                        &stored_tasks,
+                        &declared_tasks,
+                        delete_undeclared_tasks,
+                    ))
+                })
+            })
+            .await
+            .context("couldn't plan task reconciliation")?;
+        info!("DRY RUN: Not reconciling tasks");
+        return Ok(plan);
+    }
+
+    // The plan is made in the same transaction that applies it, so that it can't be invalidated by
+    // concurrent changes. Each task changed gets its own audit log entry, alongside the entry for
+    // the command as a whole.
+    let entry = audit_log_entry(datastore, "reconcile-tasks", "reconcile_tasks")
+        .with_target(Some(tasks_dir.display().to_string()));
+    let (plan, entries) = run_audited_tx(datastore, "reconcile-tasks", entry, move |tx, entry| {
+        let declared_tasks = Arc::clone(&declared_tasks);
+        Box::pin(async move {
+            let stored_tasks = tx.get_aggregator_tasks().await?;
+            let plan =
+                plan_task_reconciliation(&stored_tasks, &declared_tasks, delete_undeclared_tasks);
+            let stored_tasks_by_id: HashMap<_, _> =
+                stored_tasks.iter().map(|task| (*task.id(), task)).collect();
+            let declared_tasks_by_id: HashMap<_, _> = declared_tasks
+                .iter()
+                .map(|task| (*task.id(), task))
+                .collect();
+
+            let mut entries = Vec::new();
+            for task_id in &plan.created {
+                let task = declared_tasks_by_id[task_id];
+                tx.put_aggregator_task(task).await?;
+                entries.push(
+                    task_audit_log_entry(&entry, "create_task", task_id)
+                        .with_after(Some(audit::task_state(task))),
+                );
+            }
+            for change in &plan.updated {
+                let (stored, declared) = (
+                    stored_tasks_by_id[&change.task_id],
+                    declared_tasks_by_id[&change.task_id],
+                );
+                if stored.task_end() != declared.task_end() {
+                    tx.update_task_end(&change.task_id, declared.task_end())
+                        .await?;
+                }
+                if stored.state() != declared.state() {
+                    tx.update_task_state(&change.task_id, declared.state())
+                        .await?;
+                }
+                if change
+                    .fields
+                    .iter()
+                    .any(|field| !["task_end", "state"].contains(field))
+                {
+                    tx.update_task_auth_tokens(declared).await?;
+                }
+                entries.push(
+                    task_audit_log_entry(&entry, "update_task", &change.task_id)
+                        .with_before(Some(audit::task_state(stored)))
+                        .with_after(Some(audit::task_state(declared))),
+                );
+            }
+            for task_id in &plan.deleted {
+                tx.delete_task(task_id).await?;
+                entries.push(
+                    task_audit_log_entry(&entry, "delete_task", task_id)
+                        .with_before(Some(audit::task_state(stored_tasks_by_id[task_id]))),
+                );
+            }
+            for task_entry in &entries {
+                tx.put_audit_log_entry(task_entry).await?;
+            }
+
+            let details = serde_json::json!({
+                "created_count": plan.created.len(),
+                "updated_count": plan.updated.len(),
+                "deleted_count": plan.deleted.len(),
+                "conflict_count": plan.conflicts.len(),
+            });
+            Ok(((plan, entries), entry.with_details(details)))
+        })
+    })
+    .await
+    .context("couldn't reconcile tasks")?;
+    entries.iter().for_each(emit_audit_log_event);
+
+    Ok(plan)
+}
+
+/// Abandoned jobs, as listed by `list-abandoned-jobs`.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+struct AbandonedJobs {
+    aggregation_jobs: Vec<AbandonedJob>,
+    collection_jobs: Vec<AbandonedJob>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct AbandonedJob {
+    task_id: TaskId,
+    job_id: String,
+    last_error: Option<String>,
+    updated_at: Time,
+}
+
+async fn list_abandoned_jobs<C: Clock>(
+    datastore: &Datastore<C>,
+    task_id: Option<&TaskId>,
+) -> Result<AbandonedJobs> {
+    let task_id = task_id.copied();
+    let (aggregation_jobs, collection_jobs) = datastore
+        .run_tx("list_abandoned_jobs", |tx| {
+            Box::pin(async move {
+                try_join!(
+                    tx.get_abandoned_aggregation_jobs(task_id.as_ref()),
+                    tx.get_abandoned_collection_jobs(task_id.as_ref()),
+                )
+            })
+        })
+        .await?;
+
+    Ok(AbandonedJobs {
+        aggregation_jobs: aggregation_jobs
+            .into_iter()
+            .map(|job| AbandonedJob {
+                task_id: *job.task_id(),
+                job_id: job.aggregation_job_id().to_string(),
+                last_error: job.last_error().map(str::to_string),
+                updated_at: *job.updated_at(),
+            })
+            .collect(),
+        collection_jobs: collection_jobs
+            .into_iter()
+            .map(|job| AbandonedJob {
+                task_id: *job.task_id(),
+                job_id: job.collection_job_id().to_string(),
+                last_error: job.last_error().map(str::to_string),
+                updated_at: *job.updated_at(),
+            })
+            .collect(),
+    })
+}
+
+/// A single entry in a job's history, as shown by `job-history`.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct JobHistoryEntry {
+    step: u64,
+    lease_attempts: u64,
+    outcome: JobStepOutcome,
+    error: Option<String>,
+    http_status: Option<u16>,
+    problem_type: Option<String>,
+    retry_delay_ms: Option<u64>,
+    created_at: Time,
+}
+
+async fn job_history<C: Clock>(
+    datastore: &Datastore<C>,
+    job: &JobOptions,
+) -> Result<Vec<JobHistoryEntry>> {
+    let task_id = job.task_id;
+    let job_id = job.job_id()?;
+
+    let entries = datastore
+        .run_tx("job_history", |tx| {
+            Box::pin(async move {
+                match job_id {
+                    JobId::Aggregation(aggregation_job_id) => {
+                        tx.get_aggregation_job_history(&task_id, &aggregation_job_id)
+                            .await
+                    }
+                    JobId::Collection(collection_job_id) => {
+                        tx.get_collection_job_history(&task_id, &collection_job_id)
+                            .await
+                    }
+                }
+            })
+        })
+        .await?
+        .ok_or_else(|| anyhow!("job not found"))?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let record = entry.record();
+            JobHistoryEntry {
+                step: entry.step(),
+                lease_attempts: entry.lease_attempts(),
+                outcome: *record.outcome(),
+                error: record.error().map(str::to_string),
+                http_status: record.http_status(),
+                problem_type: record.problem_type().map(str::to_string),
+                retry_delay_ms: record
+                    .retry_delay()
+                    .map(|retry_delay| u64::try_from(retry_delay.as_millis()).unwrap_or(u64::MAX)),
+                created_at: *entry.created_at(),
+            }
+        })
+        .collect())
+}
+
+async fn requeue_job<C: Clock>(
+    datastore: &Datastore<C>,
+    dry_run: bool,
+    job: &JobOptions,
+) -> Result<()> {
+    let task_id = job.task_id;
+    let job_id = job.job_id()?;
+
+    if dry_run {
+        info!(%task_id, ?job_id, "DRY RUN: Not requeueing job");
+        return Ok(());
+    }
+
+    let (action, target) = match job_id {
+        JobId::Aggregation(aggregation_job_id) => {
+            ("requeue_aggregation_job", aggregation_job_id.to_string())
+        }
+        JobId::Collection(collection_job_id) => {
+            ("requeue_collection_job", collection_job_id.to_string())
+        }
+    };
+    let entry = audit_log_entry(datastore, "requeue-job", action)
+        .with_task_id(Some(task_id))
+        .with_target(Some(target));
+    run_audited_tx(datastore, "requeue_job", entry, move |tx, entry| {
+        Box::pin(async move {
+            match job_id {
+                JobId::Aggregation(aggregation_job_id) => {
+                    tx.requeue_abandoned_aggregation_job(&task_id, &aggregation_job_id)
+                        .await?
+                }
+                JobId::Collection(collection_job_id) => {
+                    tx.requeue_abandoned_collection_job(&task_id, &collection_job_id)
+                        .await?
+                }
+            }
+            Ok(((), entry))
+        })
+    })
+    .await
+    .context("couldn't requeue job")?;
+    info!(%task_id, ?job_id, "Requeued job");
+
+    Ok(())
+}
+
+async fn fail_job<C: Clock>(
+    datastore: &Datastore<C>,
+    dry_run: bool,
+    job: &JobOptions,
+) -> Result<()> {
+    let task_id = job.task_id;
+    let job_id = job.job_id()?;
+
+    if dry_run {
+        info!(%task_id, ?job_id, "DRY RUN: Not failing job");
+        return Ok(());
+    }
+
+    let (action, target) = match job_id {
+        JobId::Aggregation(aggregation_job_id) => {
+            ("fail_aggregation_job", aggregation_job_id.to_string())
+        }
+        JobId::Collection(collection_job_id) => {
+            ("fail_collection_job", collection_job_id.to_string())
+        }
+    };
+    let entry = audit_log_entry(datastore, "fail-job", action)
+        .with_task_id(Some(task_id))
+        .with_target(Some(target));
+    let reports_unaggregated = run_audited_tx(datastore, "fail_job", entry, move |tx, entry| {
+        Box::pin(async move {
+            let reports_unaggregated = match job_id {
+                JobId::Aggregation(aggregation_job_id) => tx
+                    .fail_aggregation_job(&task_id, &aggregation_job_id)
+                    .await?
+                    .len(),
+                JobId::Collection(collection_job_id) => {
+                    tx.fail_collection_job(&task_id, &collection_job_id).await?;
+                    0
+                }
+            };
+            Ok((
+                reports_unaggregated,
+                entry.with_details(
+                    serde_json::json!({ "reports_unaggregated": reports_unaggregated }),
+                ),
+            ))
+        })
+    })
+    .await
+    .context("couldn't fail job")?;
+    info!(%task_id, ?job_id, %reports_unaggregated, "Failed job");
+
+    Ok(())
+}
+
+/// Summarizes the contents of a task archive, for logging & for the audit log.
+fn task_archive_summary(archive: &TaskArchive) -> serde_json::Value {
+    serde_json::json!({
+        "hpke_keypairs": archive.hpke_keypair_count(),
+        "client_reports": archive.client_report_count(),
+        "unaggregated_client_reports": archive.unaggregated_client_report_count(),
+        "outstanding_batches": archive.outstanding_batch_count(),
+        "batch_aggregations": archive.batch_aggregation_count(),
+        "collection_jobs": archive.collection_job_count(),
+        "aggregate_share_jobs": archive.aggregate_share_job_count(),
+    })
+}
+
+async fn export_task<C: Clock>(
+    datastore: &Datastore<C>,
+    dry_run: bool,
+    task_id: &TaskId,
+    include_unaggregated_reports: bool,
+    archive_crypter: &Crypter,
+    archive_file: &Path,
+) -> Result<()> {
+    let task_id = *task_id;
+    let entry =
+        audit_log_entry(datastore, "export-task", "export_task").with_task_id(Some(task_id));
+    let (archive, entry) = datastore
+        .run_tx("export_task", |tx| {
+            let entry = entry.clone();
+            Box::pin(async move {
+                let archive = tx
+                    .export_task(&task_id, include_unaggregated_reports)
+                    .await?;
+                // Reads are not otherwise audited, but an archive holds the task's secrets.
+                let entry = match archive.as_ref().filter(|_| !dry_run) {
+                    Some(archive) => {
+                        let entry = entry.with_details(task_archive_summary(archive));
+                        tx.put_audit_log_entry(&entry).await?;
+                        Some(entry)
+                    }
+                    None => None,
+                };
+                Ok((archive, entry))
+            })
+        })
+        .await
+        .context("couldn't export task")?;
+    let archive = archive.ok_or_else(|| anyhow!("task not found"))?;
+    if let Some(entry) = entry {
+        emit_audit_log_event(&entry);
+    }
+    let summary = task_archive_summary(&archive);
+
+    if dry_run {
+        info!(%task_id, %summary, "DRY RUN: Not writing task archive");
+        return Ok(());
+    }
+
+    let sealed_archive = archive
+        .seal(archive_crypter)
+        .context("couldn't encrypt task archive")?;
+    fs::write(
+        archive_file,
+        serde_json::to_vec(&sealed_archive).context("couldn't serialize task archive")?,
+    )
+    .await
+    .with_context(|| format!("couldn't write task archive {archive_file:?}"))?;
+    info!(%task_id, %summary, "Exported task");
+
+    Ok(())
+}
+
+async fn import_task<C: Clock>(
+    datastore: &Datastore<C>,
+    dry_run: bool,
+    archive_crypter: &Crypter,
+    archive_file: &Path,
+) -> Result<()> {
+    let sealed_archive: SealedTaskArchive = {
+        let archive_file_contents = fs::read(archive_file)
+            .await
+            .with_context(|| format!("couldn't read task archive {archive_file:?}"))?;
+        serde_json::from_slice(&archive_file_contents)
+            .with_context(|| format!("couldn't parse task archive {archive_file:?}"))?
+    };
+    let archive = Arc::new(
+        sealed_archive
+            .open(archive_crypter)
+            .context("couldn't decrypt task archive")?,
+    );
+    let task_id = *archive.task().id();
+    let summary = task_archive_summary(&archive);
+
+    if dry_run {
+        info!(%task_id, %summary, "DRY RUN: Not importing task");
+        return Ok(());
+    }
+
+    let entry = audit_log_entry(datastore, "import-task", "import_task")
+        .with_task_id(Some(task_id))
+        .with_details(summary.clone());
+    run_audited_tx(datastore, "import_task", entry, {
+        let archive = Arc::clone(&archive);
+        move |tx, entry| {
+            let archive = Arc::clone(&archive);
+            Box::pin(async move {
+                tx.import_task(&archive).await?;
+                let after = Some(audit::task_state(archive.task()));
+                Ok(((), entry.with_after(after)))
+            })
+        }
+    })
+    .await
+    .context("couldn't import task")?;
+    info!(%task_id, %summary, "Imported task");
+
+    Ok(())
+}
+
+async fn create_api_token<C: Clock>(
+    datastore: &Datastore<C>,
+    dry_run: bool,
+    name: &str,
+    scopes: &[ApiTokenScope],
+    task_ids: &[TaskId],
+) -> Result<AuthenticationToken> {
+    let token = AuthenticationToken::Bearer(rng().random());
+    let api_token = Arc::new(ApiToken::new(
+        name.to_string(),
+        AuthenticationTokenHash::from(&token),
+        scopes.to_vec(),
+        (!task_ids.is_empty()).then(|| task_ids.to_vec()),
+        datastore.clock().now(),
+    ));
+
+    if dry_run {
+        info!(?api_token, "DRY RUN: Not creating API token");
+        return Ok(token);
+    }
+
+    let entry = audit_log_entry(datastore, "create-api-token", "create_api_token")
+        .with_target(Some(name.to_string()));
+    run_audited_tx(datastore, "create_api_token", entry, {
+        let api_token = Arc::clone(&api_token);
+        move |tx, entry| {
+            let api_token = Arc::clone(&api_token);
             Box::pin(async move {
-                let mut written_tasks = Vec::new();
-                for task in tasks.iter() {
-                    // We attempt to delete the task, but ignore "task not found" errors since
-                    // the task not existing is an OK outcome too.
-                    match tx.delete_task(task.id()).await {
-                        Ok(()) => {
-                            info!(task_id = %task.id(), "replacing existing task");
-                        }
-                        Err(datastore::Error::MutationTargetNotFound) => (),
-                        err => err?,
-                    }
+                tx.put_api_token(&api_token).await?;
+                let after = Some(audit::api_token_state(&api_token));
+                Ok(((), entry.with_after(after)))
+            })
+        }
+    })
+    .await
+    .context("couldn't create API token")?;
 
-                    tx.put_aggregator_task(task).await?;
+    Ok(token)
+}
 
-                    written_tasks.push(task.clone());
-                }
-                Ok(written_tasks)
+async fn revoke_api_token<C: Clock>(
+    datastore: &Datastore<C>,
+    dry_run: bool,
+    name: &str,
+) -> Result<()> {
+    if !dry_run {
+        let entry = audit_log_entry(datastore, "revoke-api-token", "revoke_api_token")
+            .with_target(Some(name.to_string()));
+        let name = Arc::new(name.to_string());
+        let revoked_at = datastore.clock().now();
+        run_audited_tx(datastore, "revoke_api_token", entry, move |tx, entry| {
+            let name = Arc::clone(&name);
+            Box::pin(async move {
+                let before = tx.get_api_token(&name).await?;
+                tx.revoke_api_token(&name, &revoked_at).await?;
+                let after = tx.get_api_token(&name).await?;
+                Ok((
+                    (),
+                    entry
+                        .with_before(before.as_ref().map(audit::api_token_state))
+                        .with_after(after.as_ref().map(audit::api_token_state)),
+                ))
             })
         })
         .await
-        .context("couldn't write tasks")?;
+        .context("couldn't revoke API token")?;
+    }
 
-    Ok(written_tasks)
+    Ok(())
 }
 
 async fn fetch_datastore_keys(
@@ -740,8 +2031,8 @@ impl From<kube::Client> for LazyKubeClient {
 mod tests {
     use crate::{
         binaries::janus_cli::{
-            CommandLineOptions, ConfigFile, KubernetesSecretOptions, LazyKubeClient,
-            fetch_datastore_keys,
+            CommandLineOptions, ConfigFile, KubernetesSecretOptions, LazyKubeClient, TaskChange,
+            TaskReconciliationPlan, fetch_datastore_keys,
         },
         binary_utils::CommonBinaryOptions,
         config::{
@@ -753,12 +2044,17 @@ mod tests {
     use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
     use clap::CommandFactory;
     use janus_aggregator_core::{
-        datastore::{Datastore, models::HpkeKeyState, test_util::ephemeral_datastore},
-        task::{AggregationMode, AggregatorTask, BatchMode, test_util::TaskBuilder},
+        datastore::{
+            Crypter, Datastore,
+            models::{ApiTokenScope, AuditLogFilter, HpkeKeyState},
+            test_util::{ephemeral_datastore, generate_aead_key},
+        },
+        task::{AggregationMode, AggregatorTask, BatchMode, TaskState, test_util::TaskBuilder},
         taskprov::{PeerAggregator, VerifyKeyInit},
     };
     use janus_core::{
-        auth_tokens::AuthenticationToken,
+        auth_tokens::{AuthenticationToken, AuthenticationTokenHash},
+        dp::DpStrategyConfig,
         hpke::HpkeKeypair,
         initialize_rustls,
         test_util::{kubernetes, roundtrip_encoding},
@@ -766,7 +2062,7 @@ mod tests {
         vdaf::{VdafInstance, vdaf_dp_strategies},
     };
     use janus_messages::{
-        Duration, HpkeAeadId, HpkeConfig, HpkeConfigId, HpkeKdfId, HpkeKemId, Role, TaskId,
+        Duration, HpkeAeadId, HpkeConfig, HpkeConfigId, HpkeKdfId, HpkeKemId, Role, TaskId, Time,
         codec::Encode,
     };
     use prio::codec::Decode;
@@ -976,17 +2272,141 @@ mod tests {
             .await
             .unwrap();
 
-        // Verify the HPKE key was updated appropriately.
+        // Verify the HPKE key was updated appropriately, and the change was audit-logged.
         ds.run_unnamed_tx(|tx| {
             Box::pin(async move {
                 let hpke_keypair = tx.get_hpke_keypair(&id).await.unwrap().unwrap();
                 assert_eq!(hpke_keypair.state(), &HpkeKeyState::Active);
 
+                let entries = tx
+                    .get_audit_log_entries(&AuditLogFilter::default())
+                    .await
+                    .unwrap();
+                assert_eq!(entries.len(), 1);
+                assert_eq!(entries[0].actor(), "janus_cli");
+                assert_eq!(entries[0].action(), "set_hpke_keypair_state");
+                assert_eq!(entries[0].target(), Some("26"));
+                assert_eq!(entries[0].route(), Some("janus_cli set-hpke-key-state"));
+                assert_eq!(
+                    entries[0].before().unwrap()["state"],
+                    serde_json::json!("pending")
+                );
+                assert_eq!(
+                    entries[0].after().unwrap()["state"],
+                    serde_json::json!("active")
+                );
+
+                Ok(())
+            })
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn create_and_revoke_api_token() {
+        let ephemeral_datastore = ephemeral_datastore().await;
+        let ds = ephemeral_datastore.datastore(RealClock::default()).await;
+        let task_id = random();
+
+        // Run command.
+        let token = super::create_api_token(
+            &ds,
+            /* dry_run */ false,
+            "dashboard",
+            &[ApiTokenScope::ReadOnly],
+            &[task_id],
+        )
+        .await
+        .unwrap();
+
+        // Verify the token was written, hashed, and the change was audit-logged.
+        let token_hash = AuthenticationTokenHash::from(&token);
+        ds.run_unnamed_tx(|tx| {
+            let token_hash = token_hash.clone();
+            Box::pin(async move {
+                let api_token = tx
+                    .get_api_token_by_hash(&token_hash)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(api_token.name(), "dashboard");
+                assert_eq!(api_token.scopes(), &[ApiTokenScope::ReadOnly]);
+                assert_eq!(api_token.task_ids(), Some([task_id].as_slice()));
+
+                let entries = tx
+                    .get_audit_log_entries(&AuditLogFilter::default())
+                    .await
+                    .unwrap();
+                assert_eq!(entries.len(), 1);
+                assert_eq!(entries[0].action(), "create_api_token");
+                assert_eq!(entries[0].target(), Some("dashboard"));
+                assert_eq!(
+                    entries[0].after().unwrap()["scopes"],
+                    serde_json::json!(["read_only"])
+                );
+
+                Ok(())
+            })
+        })
+        .await
+        .unwrap();
+
+        // Names must be unique.
+        super::create_api_token(
+            &ds,
+            /* dry_run */ false,
+            "dashboard",
+            &[ApiTokenScope::TaskAdmin],
+            &[],
+        )
+        .await
+        .unwrap_err();
+
+        // Revoke the token, then verify that it can no longer be used.
+        super::revoke_api_token(&ds, /* dry_run */ false, "dashboard")
+            .await
+            .unwrap();
+        ds.run_unnamed_tx(|tx| {
+            let token_hash = token_hash.clone();
+            Box::pin(async move {
+                assert_eq!(tx.get_api_token_by_hash(&token_hash).await.unwrap(), None);
+                assert!(
+                    tx.get_api_token("dashboard")
+                        .await
+                        .unwrap()
+                        .unwrap()
+                        .revoked_at()
+                        .is_some()
+                );
+
+                let entries = tx
+                    .get_audit_log_entries(
+                        &AuditLogFilter::default()
+                            .with_action(Some("revoke_api_token".to_string())),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(entries.len(), 1);
+                assert_eq!(
+                    entries[0].before().unwrap()["revoked_at"],
+                    serde_json::Value::Null
+                );
+                assert_ne!(
+                    entries[0].after().unwrap()["revoked_at"],
+                    serde_json::Value::Null
+                );
+
                 Ok(())
             })
         })
         .await
         .unwrap();
+
+        // Revoking a token twice fails.
+        super::revoke_api_token(&ds, /* dry_run */ false, "dashboard")
+            .await
+            .unwrap_err();
     }
 
     #[tokio::test]
@@ -1201,7 +2621,32 @@ mod tests {
             TaskBuilder::new(
                 BatchMode::TimeInterval,
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
+            )
+            .build()
+            .leader_view()
+            .unwrap(),
+            TaskBuilder::new(
+                BatchMode::TimeInterval,
+                AggregationMode::Synchronous,
+                VdafInstance::Prio3Sum {
+                    max_measurement: 4096,
+                    dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
+                },
+            )
+            .build()
+            .helper_view()
+            .unwrap(),
+            TaskBuilder::new(
+                BatchMode::TimeInterval,
+                AggregationMode::Synchronous,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::PureDpDiscreteLaplace(
+                        DpStrategyConfig::from_epsilon(1, 2).unwrap(),
+                    ),
+                },
             )
             .build()
             .leader_view()
@@ -1211,6 +2656,9 @@ mod tests {
                 AggregationMode::Synchronous,
                 VdafInstance::Prio3Sum {
                     max_measurement: 4096,
+                    dp_strategy: vdaf_dp_strategies::Prio3Sum::ZCdpDiscreteGaussian(
+                        DpStrategyConfig::from_epsilon(1, 4).unwrap(),
+                    ),
                 },
             )
             .build()
@@ -1240,7 +2688,9 @@ mod tests {
         let tasks = Vec::from([TaskBuilder::new(
             BatchMode::TimeInterval,
             AggregationMode::Synchronous,
-            VdafInstance::Prio3Count,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
         )
         .build()
         .leader_view()
@@ -1265,7 +2715,9 @@ mod tests {
             TaskBuilder::new(
                 BatchMode::TimeInterval,
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
             )
             .build()
             .leader_view()
@@ -1275,6 +2727,7 @@ mod tests {
                 AggregationMode::Synchronous,
                 VdafInstance::Prio3Sum {
                     max_measurement: 4096,
+                    dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
                 },
             )
             .build()
@@ -1375,6 +2828,10 @@ mod tests {
   aggregation_mode: Asynchronous
   vdaf: !Prio3Sum
     max_measurement: 4096
+    dp_strategy:
+      dp_strategy: PureDpDiscreteLaplace
+      budget:
+        epsilon: [[1], [1]]
   role: Helper
   vdaf_verify_key:
   task_end: 9000000000
@@ -1436,8 +2893,28 @@ mod tests {
 
         for task in &got_tasks {
             match task.role() {
-                Role::Leader => assert!(task.collector_auth_token_hash().is_some()),
-                Role::Helper => assert!(task.collector_auth_token_hash().is_none()),
+                Role::Leader => {
+                    assert!(task.collector_auth_token_hash().is_some());
+                    assert_eq!(
+                        task.vdaf(),
+                        &VdafInstance::Prio3Sum {
+                            max_measurement: 4096,
+                            dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
+                        }
+                    );
+                }
+                Role::Helper => {
+                    assert!(task.collector_auth_token_hash().is_none());
+                    assert_eq!(
+                        task.vdaf(),
+                        &VdafInstance::Prio3Sum {
+                            max_measurement: 4096,
+                            dp_strategy: vdaf_dp_strategies::Prio3Sum::PureDpDiscreteLaplace(
+                                DpStrategyConfig::from_epsilon(1, 1).unwrap(),
+                            ),
+                        }
+                    );
+                }
                 role => panic!("unexpected role {role}"),
             }
         }
@@ -1448,6 +2925,284 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn reconcile_tasks() {
+        let ephemeral_datastore = ephemeral_datastore().await;
+        let ds = ephemeral_datastore.datastore(RealClock::default()).await;
+
+        // Existing tasks: one whose state, end time & auth tokens will change, one whose VDAF will
+        // change, and one which won't be declared.
+        let updated_task_builder = TaskBuilder::new(
+            BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
+        );
+        let updated_task = updated_task_builder.clone().build().leader_view().unwrap();
+        let conflicting_task = TaskBuilder::new(
+            BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
+        )
+        .build()
+        .helper_view()
+        .unwrap();
+        let undeclared_task = TaskBuilder::new(
+            BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
+        )
+        .build()
+        .leader_view()
+        .unwrap();
+        for task in [&updated_task, &conflicting_task, &undeclared_task] {
+            ds.put_aggregator_task(task).await.unwrap();
+        }
+
+        // Declared tasks: the changed existing tasks, and a new task.
+        let declared_updated_task = updated_task_builder
+            .with_state(TaskState::Paused)
+            .with_task_end(Some(Time::from_seconds_since_epoch(100 * 28800)))
+            .with_aggregator_auth_token(random())
+            .with_collector_auth_token(random())
+            .build()
+            .leader_view()
+            .unwrap();
+        let mut declared_conflicting_task = serde_yaml::to_value(&conflicting_task).unwrap();
+        declared_conflicting_task["vdaf"] = serde_yaml::to_value(VdafInstance::Prio3Sum {
+            max_measurement: 4096,
+            dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
+        })
+        .unwrap();
+        let created_task = TaskBuilder::new(
+            BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
+        )
+        .build()
+        .helper_view()
+        .unwrap();
+
+        let tasks_dir = tempdir().unwrap();
+        fs::write(
+            tasks_dir.path().join("existing.yaml"),
+            serde_yaml::to_string(&[
+                serde_yaml::to_value(&declared_updated_task).unwrap(),
+                declared_conflicting_task,
+            ])
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+        fs::write(
+            tasks_dir.path().join("new.yml"),
+            serde_yaml::to_string(&[&created_task]).unwrap(),
+        )
+        .await
+        .unwrap();
+        fs::write(tasks_dir.path().join("README.md"), "Not a task file")
+            .await
+            .unwrap();
+
+        // A dry run leaves the datastore unchanged.
+        let plan = super::reconcile_tasks(&ds, tasks_dir.path(), false, true)
+            .await
+            .unwrap();
+        assert_eq!(
+            plan,
+            TaskReconciliationPlan {
+                created: Vec::from([*created_task.id()]),
+                updated: Vec::from([TaskChange {
+                    task_id: *updated_task.id(),
+                    fields: Vec::from([
+                        "state",
+                        "task_end",
+                        "aggregator_auth_token",
+                        "collector_auth_token_hash"
+                    ]),
+                }]),
+                deleted: Vec::new(),
+                undeclared: Vec::from([*undeclared_task.id()]),
+                conflicts: Vec::from([TaskChange {
+                    task_id: *conflicting_task.id(),
+                    fields: Vec::from(["vdaf"]),
+                }]),
+            }
+        );
+        let got_tasks = task_hashmap_from_slice(
+            ds.run_unnamed_tx(|tx| Box::pin(async move { tx.get_aggregator_tasks().await }))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(
+            got_tasks,
+            task_hashmap_from_slice(Vec::from([
+                updated_task.clone(),
+                conflicting_task.clone(),
+                undeclared_task.clone(),
+            ]))
+        );
+
+        // Reconcile, deleting the undeclared task.
+        let plan = super::reconcile_tasks(&ds, tasks_dir.path(), true, false)
+            .await
+            .unwrap();
+        assert_eq!(plan.deleted, Vec::from([*undeclared_task.id()]));
+        assert!(plan.undeclared.is_empty());
+
+        let got_tasks = task_hashmap_from_slice(
+            ds.run_unnamed_tx(|tx| Box::pin(async move { tx.get_aggregator_tasks().await }))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(
+            got_tasks,
+            task_hashmap_from_slice(Vec::from([
+                declared_updated_task,
+                conflicting_task,
+                created_task,
+            ]))
+        );
+
+        // Reconciling again changes nothing, but still reports the conflict.
+        let plan = super::reconcile_tasks(&ds, tasks_dir.path(), true, false)
+            .await
+            .unwrap();
+        assert!(plan.created.is_empty());
+        assert!(plan.updated.is_empty());
+        assert!(plan.deleted.is_empty());
+        assert_eq!(plan.conflicts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn reconcile_tasks_duplicate_task() {
+        let ephemeral_datastore = ephemeral_datastore().await;
+        let ds = ephemeral_datastore.datastore(RealClock::default()).await;
+
+        let task = TaskBuilder::new(
+            BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
+        )
+        .build()
+        .leader_view()
+        .unwrap();
+
+        let tasks_dir = tempdir().unwrap();
+        for file_name in ["a.yaml", "b.yaml"] {
+            fs::write(
+                tasks_dir.path().join(file_name),
+                serde_yaml::to_string(&[&task]).unwrap(),
+            )
+            .await
+            .unwrap();
+        }
+
+        super::reconcile_tasks(&ds, tasks_dir.path(), false, false)
+            .await
+            .unwrap_err();
+        let got_tasks = ds
+            .run_unnamed_tx(|tx| Box::pin(async move { tx.get_aggregator_tasks().await }))
+            .await
+            .unwrap();
+        assert!(got_tasks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn export_import_task() {
+        let ephemeral_datastore = ephemeral_datastore().await;
+        let ds = ephemeral_datastore.datastore(RealClock::default()).await;
+        let archive_crypter = Crypter::new(Vec::from([generate_aead_key()]));
+        let temp_dir = tempdir().unwrap();
+        let archive_file = temp_dir.path().join("task_archive");
+
+        let task = TaskBuilder::new(
+            BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
+        )
+        .build()
+        .leader_view()
+        .unwrap();
+        ds.put_aggregator_task(&task).await.unwrap();
+
+        // Verify that a dry run does not write the archive.
+        super::export_task(&ds, true, task.id(), false, &archive_crypter, &archive_file)
+            .await
+            .unwrap();
+        assert!(!fs::try_exists(&archive_file).await.unwrap());
+
+        super::export_task(
+            &ds,
+            false,
+            task.id(),
+            false,
+            &archive_crypter,
+            &archive_file,
+        )
+        .await
+        .unwrap();
+
+        // Importing fails while the task still exists.
+        super::import_task(&ds, false, &archive_crypter, &archive_file)
+            .await
+            .unwrap_err();
+
+        let task_id = *task.id();
+        ds.run_unnamed_tx(|tx| Box::pin(async move { tx.delete_task(&task_id).await }))
+            .await
+            .unwrap();
+
+        // Verify that a dry run does not import the task.
+        super::import_task(&ds, true, &archive_crypter, &archive_file)
+            .await
+            .unwrap();
+        assert_eq!(
+            ds.run_unnamed_tx(|tx| Box::pin(async move { tx.get_aggregator_task(&task_id).await }))
+                .await
+                .unwrap(),
+            None
+        );
+
+        super::import_task(&ds, false, &archive_crypter, &archive_file)
+            .await
+            .unwrap();
+        let (got_task, audit_log_entries) = ds
+            .run_unnamed_tx(|tx| {
+                Box::pin(async move {
+                    Ok((
+                        tx.get_aggregator_task(&task_id).await.unwrap(),
+                        tx.get_audit_log_entries(
+                            &AuditLogFilter::default().with_task_id(Some(task_id)),
+                        )
+                        .await
+                        .unwrap(),
+                    ))
+                })
+            })
+            .await
+            .unwrap();
+        assert_eq!(got_task, Some(task));
+        assert_eq!(
+            audit_log_entries
+                .iter()
+                .map(|entry| entry.action())
+                .collect::<Vec<_>>(),
+            Vec::from(["import_task", "export_task"])
+        );
+    }
+
     #[tokio::test]
     async fn create_datastore_key() {
         initialize_rustls();
@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     aggregator::key_rotator::{
         HpkeKeyRotatorConfig, KeyRotator, deserialize_hpke_key_rotator_config,
+        deserialize_ohttp_gateway_key_rotator_config,
     },
     binary_utils::{BinaryContext, BinaryOptions, CommonBinaryOptions},
     config::{BinaryConfig, CommonConfig},
@@ -15,12 +16,18 @@ use crate::{
 
 pub async fn main_callback(ctx: BinaryContext<RealClock, Options, Config>) -> Result<()> {
     let BinaryContext {
-        config, datastore, ..
+        config,
+        datastore,
+        readiness_checks,
+        ..
     } = ctx;
 
-    KeyRotator::new(Arc::new(datastore), config.key_rotator.hpke)
-        .run()
-        .await
+    let mut key_rotator = KeyRotator::new(Arc::new(datastore), config.key_rotator.hpke);
+    if let Some(ohttp_gateway) = config.key_rotator.ohttp_gateway {
+        key_rotator = key_rotator.with_ohttp_gateway(ohttp_gateway);
+    }
+    readiness_checks.register("key_rotator", key_rotator.readiness_check(None));
+    key_rotator.run().await
 }
 
 #[derive(Debug, Default, Parser)]
@@ -82,6 +89,13 @@ impl BinaryConfig for Config {
 pub struct KeyRotatorConfig {
     #[serde(deserialize_with = "deserialize_hpke_key_rotator_config")]
     pub hpke: HpkeKeyRotatorConfig,
+
+    /// How to rotate OHTTP gateway keys. If not set, OHTTP gateway keys are not rotated.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_ohttp_gateway_key_rotator_config"
+    )]
+    pub ohttp_gateway: Option<HpkeKeyRotatorConfig>,
 }
 
 #[cfg(test)]
@@ -143,6 +157,7 @@ mod tests {
                         ),
                     ]),
                 },
+                ohttp_gateway: Some(HpkeKeyRotatorConfig::default()),
             },
         });
     }
@@ -163,7 +178,8 @@ hpke: {}
                     active_duration: default_active_duration(),
                     expired_duration: default_expired_duration(),
                     ciphersuites: default_hpke_ciphersuites(),
-                }
+                },
+                ohttp_gateway: None,
             }
         )
     }
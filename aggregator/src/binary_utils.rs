@@ -1,9 +1,15 @@
 //! Utilities for Janus binaries.
 
 pub mod job_driver;
+pub mod readiness;
+pub mod reload;
 
 use crate::{
-    config::{BinaryConfig, DbConfig},
+    binary_utils::{
+        readiness::{ReadinessChecks, ReadinessReport, datastore_check},
+        reload::{ConfigReload, ConfigReloader, setup_reload_signal_handler},
+    },
+    config::{BinaryConfig, ConfigChanges, DbConfig, DbReplicaConfig},
     metrics::install_metrics_exporter,
     trace::{TraceReloadHandle, install_trace_subscriber},
 };
@@ -34,16 +40,20 @@ use std::{
     sync::Arc,
     time::Duration,
 };
-use tokio::{runtime, sync::oneshot};
+use tokio::{
+    runtime,
+    sync::{oneshot, watch},
+};
 use tokio_postgres::NoTls;
 use tokio_postgres_rustls::MakeRustlsConnect;
 use tracing::{debug, info};
 use tracing_subscriber::EnvFilter;
 use trillium::{Handler, Info, Init, Status};
-use trillium_api::{State, api};
+use trillium_api::{Json, State, api};
 use trillium_head::Head;
 use trillium_router::Router;
 use trillium_tokio::Stopper;
+use url::Url;
 
 /// Reads, parses, and returns the config referenced by the given options, or None if no config file
 /// path was set.
@@ -57,13 +67,70 @@ pub fn read_config<Config: BinaryConfig>(options: &CommonBinaryOptions) -> Resul
 /// Connects to a database, given a config. `db_password` is mutually exclusive with the database
 /// password specified in the connection URL in `db_config`.
 pub async fn database_pool(db_config: &DbConfig, db_password: Option<&str>) -> Result<Pool> {
-    let mut database_config = tokio_postgres::Config::from_str(db_config.url.as_str())
-        .with_context(|| {
-            format!(
-                "couldn't parse database connect string: {:?}",
-                db_config.url
-            )
-        })?;
+    let pool = build_database_pool(
+        db_config,
+        &db_config.url,
+        db_config.connection_pool_max_size,
+        db_password,
+    )?;
+    let connection_pool_timeout = Duration::from_secs(db_config.connection_pool_timeouts_s);
+
+    // Attempt to fetch a connection from the connection pool, to check that the database is
+    // accessible. This will either create a new database connection or recycle an existing one, and
+    // then return the connection back to the pool.
+    //
+    // Retrying if we encounter timeouts when creating a connection or connection refused errors
+    // (which occur if the Cloud SQL Proxy hasn't started yet and manifest as `PoolError::Backend`)
+    let backoff = ExponentialBuilder::default()
+        .with_min_delay(Duration::from_secs(1))
+        .with_max_delay(connection_pool_timeout)
+        .with_factor(2.0)
+        .with_jitter()
+        .with_max_times(10)
+        .build();
+
+    let _ = (|| async { pool.get().await })
+        .retry(backoff)
+        .when(|error| match error {
+            PoolError::Timeout(TimeoutType::Create) | PoolError::Backend(_) => {
+                debug!(?error, "transient error connecting to database");
+                true
+            }
+            _ => false,
+        })
+        .await
+        .context("couldn't make connection to database")?;
+
+    Ok(pool)
+}
+
+/// Connects to a read replica of the database, given its config. Unlike [`database_pool`], this
+/// doesn't check that the replica is accessible, since the datastore falls back to the primary
+/// database while it isn't.
+pub fn replica_database_pool(
+    db_config: &DbConfig,
+    replica_config: &DbReplicaConfig,
+    db_password: Option<&str>,
+) -> Result<Pool> {
+    build_database_pool(
+        db_config,
+        &replica_config.url,
+        replica_config.connection_pool_max_size,
+        db_password,
+    )
+    .context("couldn't create database replica connection pool")
+}
+
+/// Builds a connection pool for the database at `url`, using the timeouts & TLS configuration from
+/// `db_config`.
+fn build_database_pool(
+    db_config: &DbConfig,
+    url: &Url,
+    connection_pool_max_size: Option<usize>,
+    db_password: Option<&str>,
+) -> Result<Pool> {
+    let mut database_config = tokio_postgres::Config::from_str(url.as_str())
+        .with_context(|| format!("couldn't parse database connect string: {url:?}"))?;
     if database_config.get_password().is_some() && db_password.is_some() {
         return Err(anyhow!(
             "database config & password override are both specified"
@@ -92,42 +159,32 @@ pub async fn database_pool(db_config: &DbConfig, db_password: Option<&str>) -> R
             create: Some(connection_pool_timeout),
             recycle: Some(connection_pool_timeout),
         });
-    if let Some(max_size) = db_config.connection_pool_max_size {
+    if let Some(max_size) = connection_pool_max_size {
         pool = pool.max_size(max_size)
     }
     let pool = pool
         .build()
         .context("failed to create database connection pool")?;
 
-    // Attempt to fetch a connection from the connection pool, to check that the database is
-    // accessible. This will either create a new database connection or recycle an existing one, and
-    // then return the connection back to the pool.
-    //
-    // Retrying if we encounter timeouts when creating a connection or connection refused errors
-    // (which occur if the Cloud SQL Proxy hasn't started yet and manifest as `PoolError::Backend`)
-    let backoff = ExponentialBuilder::default()
-        .with_min_delay(Duration::from_secs(1))
-        .with_max_delay(connection_pool_timeout)
-        .with_factor(2.0)
-        .with_jitter()
-        .with_max_times(10)
-        .build();
-
-    let _ = (|| async { pool.get().await })
-        .retry(backoff)
-        .when(|error| match error {
-            PoolError::Timeout(TimeoutType::Create) | PoolError::Backend(_) => {
-                debug!(?error, "transient error connecting to database");
-                true
-            }
-            _ => false,
-        })
-        .await
-        .context("couldn't make connection to database")?;
-
     Ok(pool)
 }
 
+/// Parses an AES-128-GCM key, encoded in base64 with no padding.
+pub fn parse_aes_128_gcm_key(key: &str) -> Result<LessSafeKey> {
+    let key = URL_SAFE_NO_PAD
+        .decode(key)
+        .context("couldn't base64-decode key")?;
+    Ok(LessSafeKey::new(
+        UnboundKey::new(&AES_128_GCM, &key).map_err(|_| {
+            anyhow!(
+                "expected {}-byte key, got {} bytes",
+                AES_128_GCM.key_len(),
+                key.len()
+            )
+        })?,
+    ))
+}
+
 /// Connects to a datastore, given a connection pool to the underlying database.
 ///
 /// `datastore_keys` is a list of AES-128-GCM keys, encoded in base64 with no padding, used to
@@ -143,22 +200,7 @@ pub async fn datastore<C: Clock>(
     let datastore_keys = datastore_keys
         .iter()
         .filter(|k| !k.is_empty())
-        .map(|k| {
-            URL_SAFE_NO_PAD
-                .decode(k)
-                .context("couldn't base64-decode datastore keys")
-                .and_then(|k| {
-                    Ok(LessSafeKey::new(
-                        UnboundKey::new(&AES_128_GCM, k.as_ref()).map_err(|_| {
-                            anyhow!(
-                                "couldn't parse datastore keys, expected {} bytes, got {}",
-                                AES_128_GCM.key_len(),
-                                k.len()
-                            )
-                        })?,
-                    ))
-                })
-        })
+        .map(|k| parse_aes_128_gcm_key(k).context("couldn't parse datastore keys"))
         .collect::<Result<Vec<LessSafeKey>>>()?;
     if datastore_keys.is_empty() {
         return Err(anyhow!("datastore_keys is empty"));
@@ -245,6 +287,12 @@ pub struct BinaryContext<C: Clock, Options: BinaryOptions, Config: BinaryConfig>
     pub datastore: Datastore<C>,
     pub meter: Meter,
     pub stopper: Stopper,
+    /// Checks served by the z-pages server at `/readyz`. A check of the datastore is registered
+    /// before the binary is started.
+    pub readiness_checks: ReadinessChecks,
+    /// Notified with the new configuration whenever a reload changes its tunable values. See
+    /// [`reload`] for details.
+    pub config_updates: watch::Receiver<Config>,
 }
 
 pub fn janus_main<C, Options, Config, F, Fut>(
@@ -303,7 +351,7 @@ where
         )
         .await
         .context("couldn't create database connection pool")?;
-        let datastore = datastore(
+        let mut datastore = datastore(
             pool.clone(),
             clock.clone(),
             &meter,
@@ -313,6 +361,24 @@ where
         )
         .await
         .context("couldn't create datastore")?;
+        if let Some(replica) = &config.common_config().database.replica {
+            let replica_pool = replica_database_pool(
+                &config.common_config().database,
+                replica,
+                options.common_options().database_password.as_deref(),
+            )?;
+            datastore =
+                datastore.with_replica(replica_pool, Duration::from_secs(replica.max_staleness_s));
+        }
+
+        let readiness_checks = ReadinessChecks::default();
+        readiness_checks.register(
+            "datastore",
+            datastore_check(
+                pool.clone(),
+                config.common_config().database.check_schema_version,
+            ),
+        );
 
         register_database_pool_status_metrics(pool, &meter)?;
 
@@ -320,9 +386,26 @@ where
             initialize_rayon(config.common_config().thread_pool_stack_size)?;
         }
 
+        let config_reloader = Arc::new(ConfigReloader::new(
+            options.common_options().clone(),
+            config.clone(),
+        ));
+        let config_updates = config_reloader.subscribe();
+        setup_reload_signal_handler(Arc::clone(&config_reloader) as Arc<dyn ConfigReload>)
+            .context("failed to register SIGHUP signal handler")?;
+
         let health_check_listen_address = config.common_config().health_check_listen_address;
-        let zpages_task_handle = tokio::task::spawn(async move {
-            zpages_server(health_check_listen_address, trace_reload_handle).await
+        let zpages_task_handle = tokio::task::spawn({
+            let readiness_checks = readiness_checks.clone();
+            async move {
+                zpages_server(
+                    health_check_listen_address,
+                    trace_reload_handle,
+                    readiness_checks,
+                    config_reloader,
+                )
+                .await
+            }
         });
 
         let result = f(BinaryContext {
@@ -332,6 +415,8 @@ where
             datastore,
             meter,
             stopper,
+            readiness_checks,
+            config_updates,
         })
         .await;
 
@@ -406,10 +491,21 @@ fn register_database_pool_status_metrics(pool: Pool, meter: &Meter) -> Result<()
 /// `/healthz` responds with an empty body and status code 200, which serves as a healthcheck to
 /// indicate when Janus has started up.
 ///
+/// `/readyz` runs the given readiness checks, and responds with status code 200 if they all pass
+/// or 503 otherwise, along with a JSON breakdown of each check. See [`readiness`] for details.
+///
 /// `/traceconfigz` responds with the tracing_subscriber configuration, or allows configuring it
 /// with a PUT request.
-async fn zpages_server(address: SocketAddr, trace_reload_handle: TraceReloadHandle) {
-    let handler = zpages_handler(trace_reload_handle);
+///
+/// `/reloadz` reloads the binary's configuration on a POST request, responding with the applied
+/// changes. See [`reload`] for details.
+async fn zpages_server(
+    address: SocketAddr,
+    trace_reload_handle: TraceReloadHandle,
+    readiness_checks: ReadinessChecks,
+    config_reload: Arc<dyn ConfigReload>,
+) {
+    let handler = zpages_handler(trace_reload_handle, readiness_checks, config_reload);
     trillium_tokio::config()
         .with_port(address.port())
         .with_host(&address.ip().to_string())
@@ -418,20 +514,41 @@ async fn zpages_server(address: SocketAddr, trace_reload_handle: TraceReloadHand
         .await;
 }
 
-fn zpages_handler(trace_reload_handle: TraceReloadHandle) -> impl Handler {
+fn zpages_handler(
+    trace_reload_handle: TraceReloadHandle,
+    readiness_checks: ReadinessChecks,
+    config_reload: Arc<dyn ConfigReload>,
+) -> impl Handler {
     (
         Head::new(),
         State(Arc::new(trace_reload_handle)),
+        State(readiness_checks),
+        State(config_reload),
         Router::new()
             .get(
                 "/healthz",
                 |conn: trillium::Conn| async move { conn.ok("") },
             )
+            .get("/readyz", api(get_readyz))
             .get("/traceconfigz", api(get_traceconfigz))
-            .put("/traceconfigz", api(put_traceconfigz)),
+            .put("/traceconfigz", api(put_traceconfigz))
+            .post("/reloadz", api(post_reloadz)),
     )
 }
 
+async fn get_readyz(
+    _: &mut trillium::Conn,
+    State(readiness_checks): State<ReadinessChecks>,
+) -> (Status, Json<ReadinessReport>) {
+    let report = readiness_checks.run().await;
+    let status = if report.ready {
+        Status::Ok
+    } else {
+        Status::ServiceUnavailable
+    };
+    (status, Json(report))
+}
+
 async fn get_traceconfigz(
     conn: &mut trillium::Conn,
     State(trace_reload_handle): State<Arc<TraceReloadHandle>>,
@@ -466,6 +583,17 @@ async fn put_traceconfigz(
         })
 }
 
+/// Reloads the binary's configuration, and responds with the applied changes.
+async fn post_reloadz(
+    conn: &mut trillium::Conn,
+    State(config_reload): State<Arc<dyn ConfigReload>>,
+) -> Result<Json<ConfigChanges>, Status> {
+    config_reload.reload().map(Json).map_err(|err| {
+        conn.set_body(format!("couldn't reload configuration: {err:#}"));
+        Status::BadRequest
+    })
+}
+
 /// Register a signal handler for SIGTERM, and stop the [`Stopper`] when a SIGTERM signal is
 /// received.
 pub fn setup_signal_handler(stopper: Stopper) -> Result<(), std::io::Error> {
@@ -539,10 +667,10 @@ mod tests {
     use crate::{
         aggregator::http_handlers::test_util::take_response_body,
         binary_utils::{
-            CommonBinaryOptions, database_pool, initialize_rustls,
-            register_database_pool_status_metrics, zpages_handler,
+            CommonBinaryOptions, database_pool, initialize_rustls, readiness::ReadinessChecks,
+            register_database_pool_status_metrics, reload::ConfigReload, zpages_handler,
         },
-        config::DbConfig,
+        config::{ConfigChanges, DbConfig},
         metrics::test_util::InMemoryMetricInfrastructure,
     };
     use clap::CommandFactory;
@@ -552,12 +680,29 @@ mod tests {
         testcontainers::{Postgres, Volume},
     };
     use opentelemetry_sdk::metrics::data::Gauge;
-    use std::fs;
+    use std::{fs, sync::Arc};
     use testcontainers::{ContainerRequest, ImageExt, core::Mount, runners::AsyncRunner};
     use tracing_subscriber::{EnvFilter, reload};
     use trillium::Status;
     use trillium_testing::prelude::*;
 
+    /// Reports a change to a single value on every reload, or fails if `fail` is set.
+    #[derive(Default)]
+    struct FakeConfigReload {
+        fail: bool,
+    }
+
+    impl ConfigReload for FakeConfigReload {
+        fn reload(&self) -> anyhow::Result<ConfigChanges> {
+            if self.fail {
+                return Err(anyhow::anyhow!("bad config"));
+            }
+            let mut changes = ConfigChanges::default();
+            changes.apply("report_limit", &mut 1, &2);
+            Ok(changes)
+        }
+    }
+
     #[test]
     fn verify_app() {
         CommonBinaryOptions::command().debug_assert()
@@ -566,16 +711,60 @@ mod tests {
     #[tokio::test]
     async fn healthz() {
         let (_, filter_handle) = reload::Layer::new(EnvFilter::new("info"));
-        let handler = zpages_handler(filter_handle);
+        let handler = zpages_handler(
+            filter_handle,
+            ReadinessChecks::default(),
+            Arc::new(FakeConfigReload::default()),
+        );
 
         let test_conn = get("/healthz").run_async(&handler).await;
         assert_eq!(test_conn.status(), Some(Status::Ok));
     }
 
+    #[tokio::test]
+    async fn readyz() {
+        let (_, filter_handle) = reload::Layer::new(EnvFilter::new("info"));
+        let readiness_checks = ReadinessChecks::default();
+        let handler = zpages_handler(
+            filter_handle,
+            readiness_checks.clone(),
+            Arc::new(FakeConfigReload::default()),
+        );
+
+        readiness_checks.register("passing", || async { Ok(None) });
+        let mut test_conn = get("/readyz").run_async(&handler).await;
+        assert_eq!(test_conn.status(), Some(Status::Ok));
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&take_response_body(&mut test_conn).await)
+                .unwrap(),
+            serde_json::json!({"ready": true, "checks": {"passing": {"ready": true}}}),
+        );
+
+        // Checks registered after the handler is built are run too.
+        readiness_checks.register("failing", || async { Err(anyhow::anyhow!("broken")) });
+        let mut test_conn = get("/readyz").run_async(&handler).await;
+        assert_eq!(test_conn.status(), Some(Status::ServiceUnavailable));
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&take_response_body(&mut test_conn).await)
+                .unwrap(),
+            serde_json::json!({
+                "ready": false,
+                "checks": {
+                    "failing": {"ready": false, "error": "broken"},
+                    "passing": {"ready": true},
+                },
+            }),
+        );
+    }
+
     #[tokio::test]
     async fn traceconfigz() {
         let (_filter, filter_handle) = reload::Layer::new(EnvFilter::new("info"));
-        let handler = zpages_handler(filter_handle);
+        let handler = zpages_handler(
+            filter_handle,
+            ReadinessChecks::default(),
+            Arc::new(FakeConfigReload::default()),
+        );
 
         let mut test_conn = get("/traceconfigz").run_async(&handler).await;
         assert_eq!(test_conn.status(), Some(Status::Ok));
@@ -605,11 +794,47 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn reloadz() {
+        let (_, filter_handle) = reload::Layer::new(EnvFilter::new("info"));
+        let handler = zpages_handler(
+            filter_handle,
+            ReadinessChecks::default(),
+            Arc::new(FakeConfigReload::default()),
+        );
+
+        let mut test_conn = post("/reloadz").run_async(&handler).await;
+        assert_eq!(test_conn.status(), Some(Status::Ok));
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&take_response_body(&mut test_conn).await)
+                .unwrap(),
+            serde_json::json!([{"field": "report_limit", "old": "1", "new": "2"}]),
+        );
+
+        let (_, filter_handle) = reload::Layer::new(EnvFilter::new("info"));
+        let handler = zpages_handler(
+            filter_handle,
+            ReadinessChecks::default(),
+            Arc::new(FakeConfigReload { fail: true }),
+        );
+
+        let mut test_conn = post("/reloadz").run_async(&handler).await;
+        assert_eq!(test_conn.status(), Some(Status::BadRequest));
+        assert_eq!(
+            String::from_utf8_lossy(&take_response_body(&mut test_conn).await),
+            "couldn't reload configuration: bad config",
+        );
+    }
+
     #[tokio::test]
     async fn traceconfigz_dropped_filter() {
         // Drop the filter immediately but leave the handle open.
         let (_, filter_handle) = reload::Layer::new(EnvFilter::new("info"));
-        let handler = zpages_handler(filter_handle);
+        let handler = zpages_handler(
+            filter_handle,
+            ReadinessChecks::default(),
+            Arc::new(FakeConfigReload::default()),
+        );
 
         let mut test_conn = get("/traceconfigz").run_async(&handler).await;
         assert_eq!(test_conn.status(), Some(Status::InternalServerError));
@@ -693,6 +918,7 @@ mod tests {
             connection_pool_max_size: None,
             check_schema_version: false,
             tls_trust_store_path: Some("tests/tls_files/rootCA.pem".into()),
+            replica: None,
         };
         let pool = database_pool(&db_config, None).await.unwrap();
         let conn = pool.get().await.unwrap();
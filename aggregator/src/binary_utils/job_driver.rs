@@ -1,12 +1,21 @@
 //! Discovery and driving of jobs scheduled elsewhere.
 
+use crate::{
+    binary_utils::readiness::{RecentSuccessCheck, SuccessTracker},
+    config::JobDriverConfig,
+    metrics::TaskMetricLabels,
+};
 use anyhow::Context as _;
 use chrono::NaiveDateTime;
 use janus_aggregator_core::{
     TIME_HISTOGRAM_BOUNDARIES,
-    datastore::{self, models::Lease},
+    datastore::{
+        self,
+        models::{AcquiredAggregationJob, AcquiredCollectionJob, Lease},
+    },
 };
 use janus_core::{Runtime, time::Clock};
+use janus_messages::TaskId;
 use opentelemetry::{KeyValue, metrics::Meter};
 use rand::{Rng, rng};
 use std::{
@@ -16,12 +25,29 @@ use std::{
     time::Duration,
 };
 use tokio::{
-    sync::{Semaphore, SemaphorePermit},
+    sync::{Semaphore, SemaphorePermit, watch},
     time::{self, Instant},
 };
-use tracing::{Instrument, debug, error, info_span};
+use tracing::{Instrument, debug, error, info, info_span};
 use trillium_tokio::Stopper;
 
+/// A job acquired by a [`JobDriver`], which belongs to some task.
+pub trait AcquiredTaskJob {
+    fn task_id(&self) -> &TaskId;
+}
+
+impl AcquiredTaskJob for AcquiredAggregationJob {
+    fn task_id(&self) -> &TaskId {
+        self.task_id()
+    }
+}
+
+impl AcquiredTaskJob for AcquiredCollectionJob {
+    fn task_id(&self) -> &TaskId {
+        self.task_id()
+    }
+}
+
 /// Periodically seeks incomplete jobs in the datastore and drives them concurrently.
 pub struct JobDriver<C: Clock, R, JobAcquirer, JobStepper> {
     /// Clock used to determine when to schedule jobs.
@@ -47,6 +73,13 @@ pub struct JobDriver<C: Clock, R, JobAcquirer, JobStepper> {
     incomplete_job_acquirer: JobAcquirer,
     /// Steps an incomplete job.
     job_stepper: JobStepper,
+
+    /// Records when jobs were last successfully acquired, for readiness checks.
+    job_acquisitions: SuccessTracker,
+    /// Updates to the job discovery interval and maximum number of concurrent job workers.
+    config_updates: Option<watch::Receiver<JobDriverConfig>>,
+    /// Determines which tasks' job step metrics are labeled with their task ID.
+    task_metric_labels: TaskMetricLabels,
 }
 
 impl<
@@ -67,7 +100,7 @@ where
     JobAcquirerFuture: Future<Output = Result<Vec<Lease<AcquiredJob>>, datastore::Error>> + Send,
     JobStepper: Fn(Lease<AcquiredJob>) -> JobStepperFuture + Send + Sync + 'static,
     JobStepperFuture: Future<Output = Result<(), JobStepperError>> + Send,
-    AcquiredJob: Clone + Debug + Send + Sync + 'static,
+    AcquiredJob: AcquiredTaskJob + Clone + Debug + Send + Sync + 'static,
 {
     /// Create a new [`JobDriver`].
     pub fn new(
@@ -93,9 +126,40 @@ where
             worker_lease_clock_skew_allowance,
             incomplete_job_acquirer,
             job_stepper,
+            job_acquisitions: SuccessTracker::default(),
+            config_updates: None,
+            task_metric_labels: TaskMetricLabels::default(),
         })
     }
 
+    /// Labels job step metrics with task IDs, as determined by `task_metric_labels`.
+    pub fn with_task_metric_labels(self, task_metric_labels: TaskMetricLabels) -> Self {
+        Self {
+            task_metric_labels,
+            ..self
+        }
+    }
+
+    /// Applies the job discovery interval and maximum number of concurrent job workers of each
+    /// configuration received from `config_updates` while running. Other values are ignored.
+    pub fn with_config_updates(self, config_updates: watch::Receiver<JobDriverConfig>) -> Self {
+        Self {
+            config_updates: Some(config_updates),
+            ..self
+        }
+    }
+
+    /// Returns a readiness check that passes if this job driver has recently succeeded in acquiring
+    /// jobs, even if there were none to acquire. Between acquisition attempts, the job driver
+    /// waits up to one discovery interval, and then up to one lease duration for a worker to become
+    /// available, so an acquisition is considered recent if it happened within twice that time.
+    pub fn readiness_check(&self, worker_lease_duration: Duration) -> RecentSuccessCheck {
+        self.job_acquisitions.check(
+            "job acquisition",
+            Some((self.job_discovery_interval + worker_lease_duration) * 2),
+        )
+    }
+
     /// Run this job driver, periodically seeking incomplete jobs and stepping them.
     pub async fn run(self: Arc<Self>) {
         // Create metric recorders.
@@ -114,12 +178,20 @@ where
             .with_boundaries(TIME_HISTOGRAM_BOUNDARIES.to_vec())
             .build();
 
-        // Set up state for the job driver run.
-        let sem = Arc::new(Semaphore::new(self.max_concurrent_job_workers));
+        // Set up state for the job driver run. The job discovery interval and the maximum number
+        // of concurrent job workers may change while running, due to configuration updates.
+        let mut config_updates = self.config_updates.clone();
+        let mut job_discovery_interval = self.job_discovery_interval;
+        let mut max_concurrent_job_workers = self.max_concurrent_job_workers;
+        // When the maximum number of workers decreases, the excess permits are removed from the
+        // semaphore as they are released by running workers. The semaphore always holds
+        // `max_concurrent_job_workers + excess_permits` permits in total.
+        let mut excess_permits = 0;
+        let sem = Arc::new(Semaphore::new(max_concurrent_job_workers));
 
         let mut next_run_instant = Instant::now();
-        if !self.job_discovery_interval.is_zero() {
-            next_run_instant += rng().random_range(Duration::ZERO..self.job_discovery_interval);
+        if !job_discovery_interval.is_zero() {
+            next_run_instant += rng().random_range(Duration::ZERO..job_discovery_interval);
         }
 
         loop {
@@ -133,12 +205,15 @@ where
                 // Shut down when signalled via the stopper. Wait for all in-flight jobs to
                 // complete by acquiring all semaphore permits.
                 //
-                // Unwrap safety: The constructor checks that max_concurrent_job_workers can be
-                // converted to a u32.
+                // Unwrap safety: The constructor and configuration validation check that
+                // max_concurrent_job_workers can be converted to a u32, and the total number of
+                // permits never exceeds the largest value it has taken.
                 // Unwrap safety: Semaphore::acquire is documented as only returning an error if the
                 // semaphore is closed, and we never close this semaphore.
                 let _: SemaphorePermit<'_> = sem
-                    .acquire_many(u32::try_from(self.max_concurrent_job_workers).unwrap())
+                    .acquire_many(
+                        u32::try_from(max_concurrent_job_workers + excess_permits).unwrap(),
+                    )
                     .await
                     .unwrap();
                 break;
@@ -150,6 +225,38 @@ where
             // semaphore is closed, and we never close this semaphore.
             drop(sem.acquire().await.unwrap());
 
+            // Apply the latest configuration update, if any.
+            let new_config = config_updates.as_mut().and_then(|config_updates| {
+                config_updates
+                    .has_changed()
+                    .unwrap_or(false)
+                    .then(|| config_updates.borrow_and_update().clone())
+            });
+            if let Some(config) = new_config {
+                job_discovery_interval = Duration::from_secs(config.job_discovery_interval_s);
+                if config.max_concurrent_job_workers > max_concurrent_job_workers {
+                    let added = config.max_concurrent_job_workers - max_concurrent_job_workers;
+                    let reclaimed = added.min(excess_permits);
+                    excess_permits -= reclaimed;
+                    sem.add_permits(added - reclaimed);
+                } else {
+                    excess_permits +=
+                        max_concurrent_job_workers - config.max_concurrent_job_workers;
+                }
+                max_concurrent_job_workers = config.max_concurrent_job_workers;
+                info!(
+                    ?job_discovery_interval,
+                    max_concurrent_job_workers, "Applied job driver configuration update"
+                );
+            }
+
+            // Remove any excess permits released by workers since the last iteration. If that
+            // leaves no worker available, wait for another to be released.
+            excess_permits -= sem.forget_permits(excess_permits);
+            if sem.available_permits() == 0 {
+                continue;
+            }
+
             // Acquire some jobs which are ready to be stepped.
             //
             // We determine the maximum number of jobs to acquire based on the number of semaphore
@@ -167,6 +274,7 @@ where
             debug!(%max_acquire_count, "Acquiring jobs");
             let leases = match (self.incomplete_job_acquirer)(max_acquire_count).await {
                 Ok(leases) => {
+                    self.job_acquisitions.record_success();
                     job_acquire_time_histogram.record(
                         start.elapsed().as_secs_f64(),
                         &[KeyValue::new("status", "success")],
@@ -174,7 +282,7 @@ where
 
                     if leases.is_empty() {
                         debug!("No jobs available");
-                        next_run_instant += self.job_discovery_interval;
+                        next_run_instant += job_discovery_interval;
                         continue;
                     } else {
                         assert!(
@@ -196,7 +304,7 @@ where
 
                     // Go ahead and provide a delay in this error case to ensure we don't tightly loop
                     // running transactions that will fail without any delay.
-                    next_run_instant += self.job_discovery_interval;
+                    next_run_instant += job_discovery_interval;
                     error!(?error, "Couldn't acquire jobs");
                     continue;
                 }
@@ -223,6 +331,7 @@ where
 
                     async move {
                         debug!(lease_expiry = %lease.lease_expiry_time(), "Stepping job");
+                        let task_id = *lease.leased().task_id();
                         let (start, mut status) = (Instant::now(), "success");
                         match time::timeout(
                             this.effective_lease_duration(lease.lease_expiry_time()),
@@ -242,7 +351,9 @@ where
                         }
                         job_step_time_histogram.record(
                             start.elapsed().as_secs_f64(),
-                            &[KeyValue::new("status", status)],
+                            &this
+                                .task_metric_labels
+                                .attributes(&task_id, [KeyValue::new("status", status)]),
                         );
                         drop(permit);
                     }
@@ -269,7 +380,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::JobDriver;
+    use super::{AcquiredTaskJob, JobDriver};
     use chrono::{DateTime, NaiveDateTime, Utc};
     use janus_aggregator_core::{
         datastore::{self, models::Lease},
@@ -309,6 +420,12 @@ mod tests {
             lease_expiry: NaiveDateTime,
         }
 
+        impl AcquiredTaskJob for IncompleteJob {
+            fn task_id(&self) -> &TaskId {
+                &self.task_id
+            }
+        }
+
         /// Records a job observed by the job stepper closure.
         #[derive(Clone, Debug, PartialEq, Eq)]
         struct SteppedJob {
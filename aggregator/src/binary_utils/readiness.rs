@@ -0,0 +1,288 @@
+//! Readiness checks, served by the z-pages server at `/readyz`.
+//!
+//! Each Janus binary registers the checks relevant to it in its [`ReadinessChecks`]. A request to
+//! `/readyz` runs every registered check concurrently, and responds with status code 200 if all of
+//! them pass, or 503 otherwise. In either case, the response body is a JSON object breaking down
+//! the result of each check, e.g.:
+//!
+//! ```json
+//! {
+//!   "ready": false,
+//!   "checks": {
+//!     "datastore": {"ready": true, "detail": "schema version 5"},
+//!     "hpke_keypair_cache": {"ready": false, "error": "no active HPKE configs in cache"}
+//!   }
+//! }
+//! ```
+
+use anyhow::{Context as _, anyhow};
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use futures::future::join_all;
+use janus_aggregator_core::datastore::SUPPORTED_SCHEMA_VERSIONS;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug, Formatter},
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::{Instant, timeout};
+
+/// How long a single check may run before it is considered to have failed.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A check of whether some component of a Janus binary is ready to do its work.
+#[async_trait]
+pub trait ReadinessCheck: Send + Sync {
+    /// Runs the check. On success, optionally returns a short description of the observed state;
+    /// on failure, returns an error describing why the component is not ready.
+    async fn check(&self) -> anyhow::Result<Option<String>>;
+}
+
+#[async_trait]
+impl<F, Fut> ReadinessCheck for F
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = anyhow::Result<Option<String>>> + Send,
+{
+    async fn check(&self) -> anyhow::Result<Option<String>> {
+        self().await
+    }
+}
+
+/// The set of readiness checks registered by a Janus binary. Clones share the same set of checks,
+/// so checks may be registered after the z-pages server has started.
+#[derive(Clone, Default)]
+pub struct ReadinessChecks {
+    checks: Arc<Mutex<Vec<(&'static str, Arc<dyn ReadinessCheck>)>>>,
+}
+
+impl Debug for ReadinessChecks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let checks = self.checks.lock().unwrap();
+        f.debug_list()
+            .entries(checks.iter().map(|(name, _)| name))
+            .finish()
+    }
+}
+
+impl ReadinessChecks {
+    /// Registers a check under the given name, which identifies it in the `/readyz` response.
+    pub fn register(&self, name: &'static str, check: impl ReadinessCheck + 'static) {
+        self.checks.lock().unwrap().push((name, Arc::new(check)));
+    }
+
+    /// Runs all registered checks concurrently.
+    pub async fn run(&self) -> ReadinessReport {
+        let checks = self.checks.lock().unwrap().clone();
+        let results = join_all(checks.into_iter().map(|(name, check)| async move {
+            let result = match timeout(CHECK_TIMEOUT, check.check()).await {
+                Ok(Ok(detail)) => CheckResult {
+                    ready: true,
+                    detail,
+                    error: None,
+                },
+                Ok(Err(error)) => CheckResult {
+                    ready: false,
+                    detail: None,
+                    error: Some(format!("{error:#}")),
+                },
+                Err(_) => CheckResult {
+                    ready: false,
+                    detail: None,
+                    error: Some(format!("timed out after {CHECK_TIMEOUT:?}")),
+                },
+            };
+            (name, result)
+        }))
+        .await;
+
+        ReadinessReport {
+            ready: results.iter().all(|(_, result)| result.ready),
+            checks: results.into_iter().collect(),
+        }
+    }
+}
+
+/// The outcome of running all registered readiness checks.
+#[derive(Debug, Serialize)]
+pub struct ReadinessReport {
+    /// Whether all checks passed.
+    pub ready: bool,
+    /// The result of each check, by name.
+    pub checks: BTreeMap<&'static str, CheckResult>,
+}
+
+/// The outcome of a single readiness check.
+#[derive(Debug, Serialize)]
+pub struct CheckResult {
+    pub ready: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Checks that the database is reachable and, if `check_schema_version` is set, that its schema
+/// version is supported by this version of Janus.
+pub fn datastore_check(pool: Pool, check_schema_version: bool) -> impl ReadinessCheck {
+    move || {
+        let pool = pool.clone();
+        async move {
+            let client = pool
+                .get()
+                .await
+                .context("couldn't get database connection")?;
+            let row = client
+                .query_one(
+                    "SELECT version FROM _sqlx_migrations
+WHERE success = TRUE ORDER BY version DESC LIMIT(1)",
+                    &[],
+                )
+                .await
+                .context("couldn't query schema version")?;
+            let version: i64 = row.get("version");
+            if check_schema_version && !SUPPORTED_SCHEMA_VERSIONS.contains(&version) {
+                return Err(anyhow!("unsupported schema version {version}"));
+            }
+            Ok(Some(format!("schema version {version}")))
+        }
+    }
+}
+
+/// Records when some recurring operation, such as job acquisition or key rotation, last succeeded.
+/// Clones share the same record.
+#[derive(Clone, Debug, Default)]
+pub struct SuccessTracker {
+    last_success: Arc<Mutex<Option<Instant>>>,
+}
+
+impl SuccessTracker {
+    /// Records that the operation succeeded just now.
+    pub fn record_success(&self) {
+        *self.last_success.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Returns a check that passes if the operation has succeeded at least once and, if `max_age`
+    /// is set, most recently within `max_age`.
+    pub fn check(&self, operation: &'static str, max_age: Option<Duration>) -> RecentSuccessCheck {
+        RecentSuccessCheck {
+            tracker: self.clone(),
+            operation,
+            max_age,
+        }
+    }
+}
+
+/// A check that some recurring operation has succeeded recently. See [`SuccessTracker::check`].
+#[derive(Debug)]
+pub struct RecentSuccessCheck {
+    tracker: SuccessTracker,
+    operation: &'static str,
+    max_age: Option<Duration>,
+}
+
+#[async_trait]
+impl ReadinessCheck for RecentSuccessCheck {
+    async fn check(&self) -> anyhow::Result<Option<String>> {
+        let operation = self.operation;
+        let last_success = *self.tracker.last_success.lock().unwrap();
+        let age = last_success
+            .ok_or_else(|| anyhow!("no successful {operation} yet"))?
+            .elapsed();
+        if let Some(max_age) = self.max_age.filter(|max_age| age > *max_age) {
+            return Err(anyhow!(
+                "last successful {operation} was {}s ago, exceeding {}s",
+                age.as_secs(),
+                max_age.as_secs(),
+            ));
+        }
+        Ok(Some(format!(
+            "last successful {operation} {}s ago",
+            age.as_secs()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::binary_utils::readiness::{ReadinessChecks, SuccessTracker, datastore_check};
+    use anyhow::anyhow;
+    use janus_aggregator_core::datastore::{
+        SUPPORTED_SCHEMA_VERSIONS, test_util::ephemeral_datastore,
+    };
+    use serde_json::json;
+    use std::time::Duration;
+    use tokio::time::advance;
+
+    #[tokio::test]
+    async fn report() {
+        let checks = ReadinessChecks::default();
+        let report = checks.run().await;
+        assert!(report.ready);
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({"ready": true, "checks": {}})
+        );
+
+        checks.register("passing", || async { Ok(Some("fine".to_string())) });
+        checks.register("failing", || async { Err(anyhow!("broken")) });
+        let report = checks.run().await;
+        assert!(!report.ready);
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({
+                "ready": false,
+                "checks": {
+                    "failing": {"ready": false, "error": "broken"},
+                    "passing": {"ready": true, "detail": "fine"},
+                },
+            })
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn success_tracker() {
+        let tracker = SuccessTracker::default();
+        let checks = ReadinessChecks::default();
+        checks.register(
+            "recent",
+            tracker.check("job acquisition", Some(Duration::from_secs(60))),
+        );
+        checks.register("ever", tracker.check("job acquisition", None));
+
+        // Nothing has succeeded yet.
+        let report = checks.run().await;
+        assert!(!report.checks["recent"].ready);
+        assert!(!report.checks["ever"].ready);
+
+        tracker.record_success();
+        let report = checks.run().await;
+        assert!(report.ready);
+
+        // The last success is now too old for the check with a maximum age.
+        advance(Duration::from_secs(61)).await;
+        let report = checks.run().await;
+        assert!(!report.checks["recent"].ready);
+        assert!(report.checks["ever"].ready);
+    }
+
+    #[tokio::test]
+    async fn datastore() {
+        let ephemeral_datastore = ephemeral_datastore().await;
+        let checks = ReadinessChecks::default();
+        checks.register(
+            "datastore",
+            datastore_check(ephemeral_datastore.pool(), true),
+        );
+
+        let report = checks.run().await;
+        assert!(report.ready);
+        assert_eq!(
+            report.checks["datastore"].detail,
+            Some(format!("schema version {}", SUPPORTED_SCHEMA_VERSIONS[0]))
+        );
+    }
+}
@@ -1,6 +1,13 @@
 //! Various in-memory caches that can be used by an aggregator.
 
-use crate::aggregator::{Error, TaskAggregator, report_writer::ReportWriteBatcher};
+use crate::{
+    aggregator::{
+        Error, TaskAggregator, ohttp_gateway::key_config, report_writer::ReportWriteBatcher,
+    },
+    binary_utils::readiness::ReadinessCheck,
+};
+use anyhow::anyhow;
+use educe::Educe;
 use janus_aggregator_core::{
     datastore::{
         Datastore,
@@ -27,6 +34,7 @@ use url::Url;
 
 type HpkeConfigs = Arc<Vec<HpkeConfig>>;
 type HpkeKeypairs = HashMap<HpkeConfigId, Arc<hpke::HpkeKeypair>>;
+type OhttpGatewayServers = HashMap<HpkeConfigId, Arc<ohttp::Server>>;
 
 #[derive(Debug)]
 pub struct HpkeKeypairCache {
@@ -98,7 +106,7 @@ impl HpkeKeypairCache {
         // proceeding.
         for _ in 0..Self::WAIT_MAX_RETRIES {
             let keypairs = datastore
-                .run_tx("refresh_hpke_keypairs_cache", |tx| {
+                .run_read_only_tx("refresh_hpke_keypairs_cache", |tx| {
                     Box::pin(async move { tx.get_hpke_keypairs().await })
                 })
                 .await?;
@@ -165,6 +173,20 @@ impl HpkeKeypairCache {
         let state = self.state.lock().unwrap();
         state.keypairs.get(id).cloned()
     }
+
+    /// Returns a readiness check that passes if the cache holds at least one active config.
+    pub fn readiness_check(self: &Arc<Self>) -> impl ReadinessCheck + use<> {
+        let cache = Arc::clone(self);
+        move || {
+            let configs = cache.configs();
+            async move {
+                if configs.is_empty() {
+                    return Err(anyhow!("no active HPKE configs in cache"));
+                }
+                Ok(Some(format!("{} active HPKE configs", configs.len())))
+            }
+        }
+    }
 }
 
 impl Drop for HpkeKeypairCache {
@@ -173,6 +195,150 @@ impl Drop for HpkeKeypairCache {
     }
 }
 
+/// Caches the keys of the aggregator's OHTTP gateway. See
+/// [`ohttp_gateway`][crate::aggregator::ohttp_gateway] for details.
+#[derive(Debug)]
+pub struct OhttpGatewayKeyCache {
+    /// The cache state: encoded key configs for advertisement, and servers for decapsulation.
+    state: Arc<StdMutex<OhttpGatewayKeyCacheState>>,
+
+    /// Handle for task responsible for periodically refreshing the cache.
+    refresh_handle: JoinHandle<()>,
+}
+
+#[derive(Educe, Default)]
+#[educe(Debug)]
+struct OhttpGatewayKeyCacheState {
+    /// Encoded list of active key configs, in `application/ohttp-keys` format.
+    encoded_key_configs: Arc<Vec<u8>>,
+
+    /// OHTTP servers for request decapsulation, by key ID.
+    #[educe(Debug(ignore))]
+    servers: OhttpGatewayServers,
+}
+
+impl OhttpGatewayKeyCache {
+    const WAIT_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+    const WAIT_MAX_RETRIES: u32 = 10;
+
+    pub async fn new<C: Clock>(
+        datastore: Arc<Datastore<C>>,
+        refresh_interval: Duration,
+    ) -> Result<Self, Error> {
+        let state = Arc::new(Default::default());
+
+        // Initial cache load.
+        Self::refresh_inner(&datastore, &state).await?;
+
+        // Start refresh task.
+        let refresh_handle = spawn({
+            let datastore = Arc::clone(&datastore);
+            let state = Arc::clone(&state);
+
+            async move {
+                loop {
+                    sleep(refresh_interval).await;
+
+                    let now = Instant::now();
+                    let result = Self::refresh_inner(&datastore, &state).await;
+                    let elapsed = now.elapsed();
+
+                    match result {
+                        Ok(_) => debug!(?elapsed, "successfully refreshed OHTTP gateway key cache"),
+                        Err(err) => {
+                            error!(?err, ?elapsed, "failed to refresh OHTTP gateway key cache")
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            state,
+            refresh_handle,
+        })
+    }
+
+    async fn get_keypairs<C: Clock>(datastore: &Datastore<C>) -> Result<Vec<HpkeKeypair>, Error> {
+        // As with HPKE keys, wait for a concurrently running key rotator to bootstrap an active
+        // gateway key before failing the process.
+        for _ in 0..Self::WAIT_MAX_RETRIES {
+            let keypairs = datastore
+                .run_tx("refresh_ohttp_gateway_keys_cache", |tx| {
+                    Box::pin(async move { tx.get_ohttp_gateway_keypairs().await })
+                })
+                .await?;
+
+            if keypairs.iter().any(|keypair| keypair.is_active()) {
+                return Ok(keypairs);
+            }
+
+            debug!("no active OHTTP gateway keys present in database, retrying");
+            sleep(Self::WAIT_RETRY_INTERVAL).await;
+        }
+        Err(Error::Internal(
+            "no active OHTTP gateway keys present in database".into(),
+        ))
+    }
+
+    #[tracing::instrument(skip_all, err)]
+    async fn refresh_inner<C: Clock>(
+        datastore: &Datastore<C>,
+        state: &StdMutex<OhttpGatewayKeyCacheState>,
+    ) -> Result<(), Error> {
+        let keypairs = Self::get_keypairs(datastore).await?;
+
+        let mut active_key_configs = Vec::new();
+        let mut servers = HashMap::new();
+        for keypair in &keypairs {
+            let key_config =
+                key_config(keypair.hpke_keypair()).map_err(|err| Error::Internal(err.into()))?;
+            if keypair.is_active() {
+                active_key_configs.push(key_config.clone());
+            }
+            let server =
+                ohttp::Server::new(key_config).map_err(|err| Error::Internal(err.into()))?;
+            servers.insert(*keypair.id(), Arc::new(server));
+        }
+        let encoded_key_configs = Arc::new(
+            ohttp::KeyConfig::encode_list(&active_key_configs.iter().collect::<Vec<_>>())
+                .map_err(|err| Error::Internal(err.into()))?,
+        );
+
+        let mut state = state.lock().unwrap();
+        *state = OhttpGatewayKeyCacheState {
+            encoded_key_configs,
+            servers,
+        };
+        Ok(())
+    }
+
+    #[cfg(feature = "test-util")]
+    pub async fn refresh<C: Clock>(&self, datastore: &Datastore<C>) -> Result<(), Error> {
+        Self::refresh_inner(datastore, &self.state).await
+    }
+
+    /// Retrieve the encoded list of key configs for advertisement. This only includes keys that
+    /// are in the `[HpkeKeyState::Active]` state.
+    pub fn encoded_key_configs(&self) -> Arc<Vec<u8>> {
+        let state = self.state.lock().unwrap();
+        Arc::clone(&state.encoded_key_configs)
+    }
+
+    /// Retrieve the server for a key ID, for request decapsulation. This retrieves servers for
+    /// keys in any state.
+    pub fn server(&self, id: &HpkeConfigId) -> Option<Arc<ohttp::Server>> {
+        let state = self.state.lock().unwrap();
+        state.servers.get(id).cloned()
+    }
+}
+
+impl Drop for OhttpGatewayKeyCache {
+    fn drop(&mut self) {
+        self.refresh_handle.abort()
+    }
+}
+
 /// Caches taskprov [`PeerAggregator`]'s. This cache is never invalidated, so the process needs to
 /// be restarted if there are any changes to peer aggregators.
 #[derive(Debug)]
@@ -206,7 +372,7 @@ impl PeerAggregatorCache {
 pub struct TaskAggregatorCache<C: Clock> {
     datastore: Arc<Datastore<C>>,
     report_writer: Arc<ReportWriteBatcher<C>>,
-    cache: Cache<TaskId, TaskAggregatorRef<C>>,
+    cache: StdMutex<Cache<TaskId, TaskAggregatorRef<C>>>,
     cache_none: bool,
 }
 
@@ -228,26 +394,54 @@ impl<C: Clock> TaskAggregatorCache<C> {
         Self {
             datastore,
             report_writer: Arc::new(report_writer),
-            cache: CacheBuilder::new(capacity).time_to_live(ttl).build(),
+            cache: StdMutex::new(CacheBuilder::new(capacity).time_to_live(ttl).build()),
             cache_none,
         }
     }
 
+    /// The report writer shared by all cached task aggregators.
+    pub fn report_writer(&self) -> &ReportWriteBatcher<C> {
+        &self.report_writer
+    }
+
+    /// Changes the capacity and TTL of the cache. If either changes, cached task aggregators are
+    /// discarded, and are fetched again from the datastore as they are needed.
+    pub fn reconfigure(&self, capacity: u64, ttl: Duration) {
+        let mut cache = self.cache.lock().unwrap();
+        let policy = cache.policy();
+        if policy.max_capacity() != Some(capacity) || policy.time_to_live() != Some(ttl) {
+            *cache = CacheBuilder::new(capacity).time_to_live(ttl).build();
+        }
+    }
+
     pub async fn get(&self, task_id: &TaskId) -> Result<TaskAggregatorRef<C>, Error> {
-        Ok(self
-            .cache
+        // Cloning the cache is cheap, and avoids holding the lock across await points.
+        let cache = self.cache.lock().unwrap().clone();
+        Ok(cache
             .entry(*task_id)
             .and_try_compute_with(|entry| async move {
                 match entry {
                     Some(_) => Ok::<_, Error>(Op::Nop),
                     None => {
-                        let task = self
+                        let mut task = self
                             .datastore
-                            .run_tx("task_aggregator_get_task", |tx| {
+                            .run_read_only_tx("task_aggregator_get_task", |tx| {
                                 let task_id = *task_id;
                                 Box::pin(async move { tx.get_aggregator_task(&task_id).await })
                             })
-                            .await?
+                            .await?;
+                        // A task which was just provisioned may not have reached the read replica
+                        // yet, so check the primary before concluding that the task doesn't exist.
+                        if task.is_none() && self.datastore.has_replica() {
+                            task = self
+                                .datastore
+                                .run_tx("task_aggregator_get_task", |tx| {
+                                    let task_id = *task_id;
+                                    Box::pin(async move { tx.get_aggregator_task(&task_id).await })
+                                })
+                                .await?;
+                        }
+                        let task = task
                             .map(|task| TaskAggregator::new(task, Arc::clone(&self.report_writer)))
                             .transpose()?
                             .map(Arc::new);
@@ -277,12 +471,13 @@ mod tests {
     use janus_aggregator_core::{
         datastore::{models::HpkeKeyState, test_util::ephemeral_datastore},
         task::{AggregationMode, BatchMode, test_util::TaskBuilder},
+        test_util::noop_meter,
     };
     use janus_core::{
         hpke::HpkeKeypair,
         test_util::{install_test_trace_subscriber, runtime::TestRuntime},
         time::MockClock,
-        vdaf::VdafInstance,
+        vdaf::{VdafInstance, vdaf_dp_strategies},
     };
     use janus_messages::{Duration as janusDuration, Time};
     use tokio::time::sleep;
@@ -290,6 +485,7 @@ mod tests {
     use crate::{
         aggregator::report_writer::ReportWriteBatcher,
         cache::{HpkeKeypairCache, TaskAggregatorCache},
+        metrics::TaskMetricLabels,
     };
 
     #[tokio::test]
@@ -346,6 +542,8 @@ mod tests {
                 100,                      // doesn't matter
                 100,                      // doesn't matter
                 Duration::from_secs(100), // doesn't matter
+                &noop_meter(),
+                TaskMetricLabels::default(),
             ),
             false,
             10000,
@@ -355,7 +553,9 @@ mod tests {
         let task = TaskBuilder::new(
             BatchMode::TimeInterval,
             AggregationMode::Synchronous,
-            VdafInstance::Prio3Count,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
         )
         .with_time_precision(janusDuration::from_seconds(100))
         .build()
@@ -364,7 +564,7 @@ mod tests {
 
         assert!(task_aggregators.get(task.id()).await.unwrap().is_none());
         // We shouldn't have cached that last call.
-        assert_eq!(task_aggregators.cache.entry_count(), 0);
+        assert_eq!(task_aggregators.cache.lock().unwrap().entry_count(), 0);
 
         // A wild task appears!
         datastore.put_aggregator_task(&task).await.unwrap();
@@ -416,6 +616,8 @@ mod tests {
                 100,                      // doesn't matter
                 100,                      // doesn't matter
                 Duration::from_secs(100), // doesn't matter
+                &noop_meter(),
+                TaskMetricLabels::default(),
             ),
             true,
             10000,
@@ -425,7 +627,9 @@ mod tests {
         let task = TaskBuilder::new(
             BatchMode::TimeInterval,
             AggregationMode::Synchronous,
-            VdafInstance::Prio3Count,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
         )
         .with_time_precision(janusDuration::from_seconds(100))
         .build()
@@ -471,4 +675,66 @@ mod tests {
         let task_aggregator = task_aggregators.get(task.id()).await.unwrap().unwrap();
         assert_eq!(task_aggregator.task.task_end(), Some(&new_end));
     }
+
+    #[tokio::test]
+    async fn task_aggregator_cache_reconfigure() {
+        install_test_trace_subscriber();
+        let clock = MockClock::default();
+        let ephemeral_datastore = ephemeral_datastore().await;
+        let datastore = Arc::new(ephemeral_datastore.datastore(clock.clone()).await);
+
+        let ttl = Duration::from_secs(600);
+        let task_aggregators = TaskAggregatorCache::new(
+            Arc::clone(&datastore),
+            ReportWriteBatcher::new(
+                Arc::clone(&datastore),
+                TestRuntime::default(),
+                100,                      // doesn't matter
+                100,                      // doesn't matter
+                Duration::from_secs(100), // doesn't matter
+                &noop_meter(),
+                TaskMetricLabels::default(),
+            ),
+            false,
+            10000,
+            ttl,
+        );
+
+        let task = TaskBuilder::new(
+            BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
+        )
+        .with_time_precision(janusDuration::from_seconds(100))
+        .build()
+        .leader_view()
+        .unwrap();
+        datastore.put_aggregator_task(&task).await.unwrap();
+        task_aggregators.get(task.id()).await.unwrap().unwrap();
+
+        // Modify the task.
+        let new_end = Time::from_seconds_since_epoch(100);
+        datastore
+            .run_unnamed_tx(|tx| {
+                let task_id = *task.id();
+                Box::pin(async move {
+                    tx.update_task_end(&task_id, Some(&new_end)).await.unwrap();
+                    Ok(())
+                })
+            })
+            .await
+            .unwrap();
+
+        // Reconfiguring with the same values keeps the cached task.
+        task_aggregators.reconfigure(10000, ttl);
+        let task_aggregator = task_aggregators.get(task.id()).await.unwrap().unwrap();
+        assert_eq!(task_aggregator.task.task_end(), task.task_end());
+
+        // Changing the TTL discards the cached task.
+        task_aggregators.reconfigure(10000, Duration::from_secs(60));
+        let task_aggregator = task_aggregators.get(task.id()).await.unwrap().unwrap();
+        assert_eq!(task_aggregator.task.task_end(), Some(&new_end));
+    }
 }
@@ -8,6 +8,7 @@ pub mod cache;
 pub mod config;
 pub mod diagnostic;
 pub mod metrics;
+pub mod otlp;
 pub mod trace;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
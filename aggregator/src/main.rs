@@ -1,8 +1,8 @@
 use clap::{Parser, Subcommand};
 use janus_aggregator::{
     binaries::{
-        aggregation_job_creator, aggregation_job_driver, aggregator, collection_job_driver,
-        garbage_collector, janus_cli, key_rotator,
+        aggregation_job_creator, aggregation_job_driver, aggregator, all_in_one,
+        collection_job_driver, garbage_collector, janus_cli, key_rotator,
     },
     binary_utils::janus_main,
 };
@@ -25,6 +25,8 @@ enum Options {
     JanusCli(janus_cli::CommandLineOptions),
     #[clap(name = "key_rotator")]
     KeyRotator(key_rotator::Options),
+    #[clap(name = "all-in-one")]
+    AllInOne(all_in_one::Options),
     #[clap(name = "janus_aggregator", subcommand)]
     Default(Nested),
 }
@@ -50,6 +52,8 @@ enum Nested {
     JanusCli(janus_cli::CommandLineOptions),
     #[clap(name = "key_rotator")]
     KeyRotator(key_rotator::Options),
+    #[clap(name = "all-in-one")]
+    AllInOne(all_in_one::Options),
 }
 
 fn main() -> anyhow::Result<()> {
@@ -104,5 +108,12 @@ fn main() -> anyhow::Result<()> {
             false,
             key_rotator::main_callback,
         ),
+        Options::AllInOne(options) | Options::Default(Nested::AllInOne(options)) => janus_main(
+            "all-in-one",
+            options,
+            clock,
+            true,
+            all_in_one::main_callback,
+        ),
     }
 }
@@ -1,12 +1,21 @@
 //! Collection and exporting of application-level metrics for Janus.
 
+use crate::otlp::{OtlpProtocol, fmt_redacted_headers};
 use anyhow::anyhow;
+use educe::Educe;
+use janus_aggregator_core::task;
+use janus_messages::{ReportError, TaskId};
 use opentelemetry::{
     KeyValue,
     metrics::{Counter, Histogram, Meter},
 };
 use serde::{Deserialize, Serialize};
-use std::net::AddrParseError;
+use std::{
+    collections::{BTreeMap, HashSet},
+    net::AddrParseError,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
 use tokio::runtime::Runtime;
 
 #[cfg(feature = "prometheus")]
@@ -23,8 +32,9 @@ use {
 
 #[cfg(feature = "otlp")]
 use {
-    opentelemetry_otlp::WithExportConfig,
+    crate::otlp::{OtlpExporterOptions, otlp_resource},
     opentelemetry_sdk::{metrics::PeriodicReader, runtime::Tokio},
+    std::time::Duration,
 };
 
 #[cfg(any(feature = "otlp", feature = "prometheus"))]
@@ -67,6 +77,11 @@ pub struct MetricsConfiguration {
     /// Configuration to expose metrics from the Tokio asynchronous runtime.
     #[serde(default)]
     pub tokio: Option<TokioMetricsConfiguration>,
+
+    /// Configuration for labeling per-task metrics with task IDs. If not set, per-task metrics are
+    /// not labeled with task IDs.
+    #[serde(default, with = "serde_yaml::with::singleton_map")]
+    pub task_labels: Option<TaskLabelsConfiguration>,
 }
 
 /// Selection of an exporter for OpenTelemetry metrics.
@@ -81,11 +96,45 @@ pub enum MetricsExporterConfiguration {
 }
 
 /// Configuration options specific to the OpenTelemetry OTLP metrics exporter.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Default, Educe, PartialEq, Eq, Serialize, Deserialize)]
+#[educe(Debug)]
 #[serde(deny_unknown_fields)]
 pub struct OtlpExporterConfiguration {
-    /// gRPC endpoint for OTLP exporter.
+    /// Endpoint for OTLP exporter. See [`OtlpProtocol`] for its form.
     pub endpoint: String,
+    /// Transport used to reach the endpoint. Defaults to gRPC.
+    #[serde(default)]
+    pub protocol: OtlpProtocol,
+    /// Headers sent with each export request, e.g. for authentication.
+    #[serde(default)]
+    #[educe(Debug(method(fmt_redacted_headers)))]
+    pub headers: BTreeMap<String, String>,
+    /// Path to a PEM file of CA certificates to trust when connecting to the endpoint.
+    #[serde(default)]
+    pub ca_bundle_path: Option<PathBuf>,
+    /// Timeout for each export request, in milliseconds.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Interval between exports, in milliseconds. Defaults to 60 seconds.
+    #[serde(default)]
+    pub export_interval_ms: Option<u64>,
+    /// Attributes added to the resource describing this process, e.g. `service.name` or
+    /// `deployment.environment`.
+    #[serde(default)]
+    pub resource_attributes: BTreeMap<String, String>,
+}
+
+#[cfg(feature = "otlp")]
+impl OtlpExporterConfiguration {
+    fn exporter_options(&self) -> OtlpExporterOptions<'_> {
+        OtlpExporterOptions {
+            endpoint: &self.endpoint,
+            protocol: self.protocol,
+            headers: &self.headers,
+            ca_bundle_path: self.ca_bundle_path.as_deref(),
+            timeout: self.timeout_ms.map(Duration::from_millis),
+        }
+    }
 }
 
 /// Configuration options for Tokio's (unstable) metrics feature.
@@ -98,6 +147,88 @@ pub struct TokioMetricsConfiguration {
     pub enabled: bool,
 }
 
+/// Selection of the tasks whose metrics are labeled with their task ID. Metrics for any other task
+/// are labeled with the task ID `other`.
+///
+/// Since taskprov peers can create any number of tasks, labeling every task's metrics could produce
+/// an unbounded number of time series, so the set of labeled tasks is always bounded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum TaskLabelsConfiguration {
+    /// Label metrics for the listed tasks only.
+    Allowlist(Vec<TaskId>),
+    /// Label metrics for at most this many tasks: the first tasks to be observed since the process
+    /// started.
+    MaxTasks(usize),
+}
+
+/// Decides which task ID, if any, per-task metrics are labeled with. Clones share the same set of
+/// labeled tasks.
+#[derive(Clone, Debug, Default)]
+pub struct TaskMetricLabels {
+    selection: Option<Arc<TaskSelection>>,
+}
+
+#[derive(Debug)]
+enum TaskSelection {
+    Allowlist(HashSet<TaskId>),
+    MaxTasks {
+        max_tasks: usize,
+        labeled: Mutex<HashSet<TaskId>>,
+    },
+}
+
+impl TaskMetricLabels {
+    /// The task ID label value used for tasks that aren't selected for labeling.
+    pub const OTHER: &'static str = "other";
+
+    pub fn new(config: Option<&TaskLabelsConfiguration>) -> Self {
+        Self {
+            selection: config.map(|config| {
+                Arc::new(match config {
+                    TaskLabelsConfiguration::Allowlist(task_ids) => {
+                        TaskSelection::Allowlist(task_ids.iter().copied().collect())
+                    }
+                    TaskLabelsConfiguration::MaxTasks(max_tasks) => TaskSelection::MaxTasks {
+                        max_tasks: *max_tasks,
+                        labeled: Mutex::default(),
+                    },
+                })
+            }),
+        }
+    }
+
+    /// Returns `attributes`, followed by a `task_id` attribute if per-task labels are enabled.
+    pub fn attributes<const N: usize>(
+        &self,
+        task_id: &TaskId,
+        attributes: [KeyValue; N],
+    ) -> Vec<KeyValue> {
+        let mut attributes = Vec::from(attributes);
+        if let Some(label) = self.label(task_id) {
+            attributes.push(KeyValue::new("task_id", label));
+        }
+        attributes
+    }
+
+    fn label(&self, task_id: &TaskId) -> Option<String> {
+        let selected = match self.selection.as_deref()? {
+            TaskSelection::Allowlist(task_ids) => task_ids.contains(task_id),
+            TaskSelection::MaxTasks { max_tasks, labeled } => {
+                // Unwrap safety: panic on mutex poisoning.
+                let mut labeled = labeled.lock().unwrap();
+                labeled.contains(task_id)
+                    || (labeled.len() < *max_tasks && labeled.insert(*task_id))
+            }
+        };
+        Some(if selected {
+            task_id.to_string()
+        } else {
+            Self::OTHER.to_string()
+        })
+    }
+}
+
 /// Choice of OpenTelemetry metrics exporter implementation.
 pub enum MetricsExporterHandle {
     #[cfg(feature = "prometheus")]
@@ -222,14 +353,14 @@ pub async fn install_metrics_exporter(
                 )));
             }
 
-            let exporter = opentelemetry_otlp::MetricExporter::builder()
-                .with_tonic()
-                .with_endpoint(otlp_config.endpoint.clone())
-                .build()?;
-            let reader = PeriodicReader::builder(exporter, Tokio).build();
+            let exporter = otlp_config.exporter_options().metric_exporter()?;
+            let mut reader = PeriodicReader::builder(exporter, Tokio);
+            if let Some(export_interval_ms) = otlp_config.export_interval_ms {
+                reader = reader.with_interval(Duration::from_millis(export_interval_ms));
+            }
             let meter_provider = SdkMeterProvider::builder()
-                .with_reader(reader)
-                .with_resource(resource())
+                .with_reader(reader.build())
+                .with_resource(otlp_resource(&otlp_config.resource_attributes))
                 .build();
             set_meter_provider(meter_provider.clone());
             // We can't drop the PushController, as that would stop pushes, so return it to the
@@ -249,7 +380,7 @@ pub async fn install_metrics_exporter(
 
 /// Produces a [`opentelemetry::sdk::Resource`] representing this process.
 #[cfg(any(feature = "otlp", feature = "prometheus"))]
-fn resource() -> Resource {
+pub(crate) fn resource() -> Resource {
     // Note that the implementation of `Default` pulls in attributes set via environment variables.
     let default_resource = Resource::default();
 
@@ -342,6 +473,98 @@ pub(crate) fn aggregate_step_failure_counter(meter: &Meter) -> Counter<u64> {
     aggregate_step_failure_counter
 }
 
+pub(crate) fn report_aggregation_failure_counter(meter: &Meter) -> Counter<u64> {
+    meter
+        .u64_counter("janus_report_aggregation_failures")
+        .with_description("Report aggregations that failed, by the resulting report error")
+        .with_unit("{report}")
+        .build()
+}
+
+/// Returns the label value for a [`ReportError`] in metrics.
+pub(crate) fn report_error_label(report_error: &ReportError) -> &'static str {
+    match report_error {
+        ReportError::Reserved => "reserved",
+        ReportError::BatchCollected => "batch_collected",
+        ReportError::ReportReplayed => "report_replayed",
+        ReportError::ReportDropped => "report_dropped",
+        ReportError::HpkeUnknownConfigId => "hpke_unknown_config_id",
+        ReportError::HpkeDecryptError => "hpke_decrypt_error",
+        ReportError::VdafPrepError => "vdaf_prep_error",
+        ReportError::TaskExpired => "task_expired",
+        ReportError::InvalidMessage => "invalid_message",
+        ReportError::ReportTooEarly => "report_too_early",
+        ReportError::TaskNotStarted => "task_not_started",
+    }
+}
+
+pub(crate) fn uploaded_reports_counter(meter: &Meter) -> Counter<u64> {
+    meter
+        .u64_counter("janus_uploaded_reports")
+        .with_description(concat!(
+            "Reports uploaded to the leader, by whether they were accepted or why they were ",
+            "rejected."
+        ))
+        .with_unit("{report}")
+        .build()
+}
+
+/// These boundaries are intended to be used with measurements having the unit of "s", for
+/// operations that may take up to days.
+pub(crate) const LONG_LATENCY_HISTOGRAM_BOUNDARIES: &[f64] = &[
+    1.0, 10.0, 60.0, 300.0, 900.0, 1800.0, 3600.0, 7200.0, 14400.0, 43200.0, 86400.0, 172800.0,
+    604800.0,
+];
+
+pub(crate) fn collection_job_latency_histogram(meter: &Meter) -> Histogram<f64> {
+    meter
+        .f64_histogram("janus_collection_job_latency")
+        .with_description("Time from the creation of a collection job to its completion")
+        .with_unit("s")
+        .with_boundaries(LONG_LATENCY_HISTOGRAM_BOUNDARIES.to_vec())
+        .build()
+}
+
+pub(crate) fn report_aggregation_job_creation_latency_histogram(meter: &Meter) -> Histogram<f64> {
+    meter
+        .f64_histogram("janus_report_aggregation_job_creation_latency")
+        .with_description(
+            "Time from a report's timestamp to the creation of an aggregation job including it",
+        )
+        .with_unit("s")
+        .with_boundaries(LONG_LATENCY_HISTOGRAM_BOUNDARIES.to_vec())
+        .build()
+}
+
+pub(crate) fn report_aggregation_latency_histogram(meter: &Meter) -> Histogram<f64> {
+    meter
+        .f64_histogram("janus_report_aggregation_latency")
+        .with_description(
+            "Time from the creation of an aggregation job to the successful aggregation of a \
+             report in it",
+        )
+        .with_unit("s")
+        .with_boundaries(LONG_LATENCY_HISTOGRAM_BOUNDARIES.to_vec())
+        .build()
+}
+
+pub(crate) fn batch_collection_latency_histogram(meter: &Meter) -> Histogram<f64> {
+    meter
+        .f64_histogram("janus_batch_collection_latency")
+        .with_description("Time from the close of a batch to the completion of its collection")
+        .with_unit("s")
+        .with_boundaries(LONG_LATENCY_HISTOGRAM_BOUNDARIES.to_vec())
+        .build()
+}
+
+/// Returns the label value for a task's batch mode in metrics.
+pub(crate) fn batch_mode_label(batch_mode: &task::BatchMode) -> &'static str {
+    match batch_mode {
+        task::BatchMode::TimeInterval => "time_interval",
+        task::BatchMode::LeaderSelected { .. } => "leader_selected",
+    }
+}
+
 const TIME_SKEW_HISTOGRAM_VALUES: &[f64] = &[
     30.0, 60.0, 120.0, 300.0, 600.0, 1800.0, 3600.0, 7200.0, 14400.0, 28800.0, 86400.0,
 ];
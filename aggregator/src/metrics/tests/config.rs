@@ -1,6 +1,8 @@
 use crate::metrics::{
     MetricsConfiguration, MetricsExporterConfiguration, OtlpExporterConfiguration,
+    TaskLabelsConfiguration,
 };
+use janus_messages::TaskId;
 
 #[test]
 fn metrics_configuration_serde() {
@@ -9,7 +11,8 @@ fn metrics_configuration_serde() {
         config,
         MetricsConfiguration {
             exporter: None,
-            tokio: None
+            tokio: None,
+            task_labels: None,
         }
     );
 
@@ -29,6 +32,7 @@ exporter:
                 port: Some(9464),
             }),
             tokio: None,
+            task_labels: None,
         }
     );
 
@@ -44,10 +48,47 @@ exporter:
         MetricsConfiguration {
             exporter: Some(MetricsExporterConfiguration::Otlp(
                 OtlpExporterConfiguration {
-                    endpoint: "https://example.com/".into()
+                    endpoint: "https://example.com/".into(),
+                    ..Default::default()
                 }
             )),
-            tokio: None
+            tokio: None,
+            task_labels: None,
         }
     );
 }
+
+#[test]
+fn task_labels_configuration_serde() {
+    let config = serde_yaml::from_str::<MetricsConfiguration>(
+        "---
+task_labels:
+  allowlist:
+    - AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE",
+    )
+    .unwrap();
+    assert_eq!(
+        config.task_labels,
+        Some(TaskLabelsConfiguration::Allowlist(Vec::from([
+            TaskId::from([1; 32])
+        ])))
+    );
+
+    let config = serde_yaml::from_str::<MetricsConfiguration>(
+        "---
+task_labels:
+  max_tasks: 10",
+    )
+    .unwrap();
+    assert_eq!(
+        config.task_labels,
+        Some(TaskLabelsConfiguration::MaxTasks(10))
+    );
+
+    serde_yaml::from_str::<MetricsConfiguration>(
+        "---
+task_labels:
+  all_tasks: true",
+    )
+    .unwrap_err();
+}
@@ -1,3 +1,4 @@
 mod config;
 #[cfg(feature = "prometheus")]
 mod prometheus;
+mod task_labels;
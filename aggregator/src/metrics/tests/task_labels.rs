@@ -0,0 +1,56 @@
+use crate::metrics::{TaskLabelsConfiguration, TaskMetricLabels};
+use janus_messages::TaskId;
+use opentelemetry::KeyValue;
+
+#[test]
+fn no_task_labels() {
+    let labels = TaskMetricLabels::default();
+    assert_eq!(
+        labels.attributes(&TaskId::from([1; 32]), [KeyValue::new("status", "success")]),
+        Vec::from([KeyValue::new("status", "success")])
+    );
+}
+
+#[test]
+fn allowlist_task_labels() {
+    let (task_id_1, task_id_2) = (TaskId::from([1; 32]), TaskId::from([2; 32]));
+    let labels = TaskMetricLabels::new(Some(&TaskLabelsConfiguration::Allowlist(Vec::from([
+        task_id_1,
+    ]))));
+
+    assert_eq!(
+        labels.attributes(&task_id_1, []),
+        Vec::from([KeyValue::new("task_id", task_id_1.to_string())])
+    );
+    assert_eq!(
+        labels.attributes(&task_id_2, []),
+        Vec::from([KeyValue::new("task_id", TaskMetricLabels::OTHER)])
+    );
+}
+
+#[test]
+fn max_tasks_task_labels() {
+    let task_ids: Vec<_> = (1..=3).map(|i| TaskId::from([i; 32])).collect();
+    let labels = TaskMetricLabels::new(Some(&TaskLabelsConfiguration::MaxTasks(2)));
+    let clone = labels.clone();
+
+    assert_eq!(
+        labels.attributes(&task_ids[0], []),
+        Vec::from([KeyValue::new("task_id", task_ids[0].to_string())])
+    );
+    assert_eq!(
+        clone.attributes(&task_ids[1], []),
+        Vec::from([KeyValue::new("task_id", task_ids[1].to_string())])
+    );
+
+    // The limit has been reached, so further tasks are not labeled, but tasks that were already
+    // labeled still are.
+    assert_eq!(
+        labels.attributes(&task_ids[2], []),
+        Vec::from([KeyValue::new("task_id", TaskMetricLabels::OTHER)])
+    );
+    assert_eq!(
+        labels.attributes(&task_ids[0], []),
+        Vec::from([KeyValue::new("task_id", task_ids[0].to_string())])
+    );
+}
@@ -0,0 +1,145 @@
+//! Configuration shared by the OpenTelemetry OTLP exporters for traces and metrics.
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+
+/// The transport used by an OTLP exporter to reach its collector.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OtlpProtocol {
+    /// Protobuf over gRPC. The endpoint is the collector's gRPC address, e.g.
+    /// `https://collector.example.com:4317`.
+    #[default]
+    Grpc,
+    /// Protobuf over HTTP. The endpoint is the full URL of the signal's export path, e.g.
+    /// `https://collector.example.com:4318/v1/traces`.
+    HttpProtobuf,
+}
+
+/// Formats OTLP exporter headers with their values redacted, since they typically carry
+/// credentials.
+pub(crate) fn fmt_redacted_headers(
+    headers: &BTreeMap<String, String>,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    f.debug_map()
+        .entries(headers.keys().map(|name| (name, "[redacted]")))
+        .finish()
+}
+
+#[cfg(feature = "otlp")]
+pub(crate) use exporter::{OtlpExporterOptions, otlp_resource};
+
+#[cfg(feature = "otlp")]
+mod exporter {
+    use super::OtlpProtocol;
+    use crate::metrics::resource;
+    use anyhow::{Context as _, Result};
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::{
+        MetricExporter, Protocol, SpanExporter, WithExportConfig, WithHttpConfig, WithTonicConfig,
+    };
+    use opentelemetry_sdk::Resource;
+    use std::{collections::BTreeMap, fs, path::Path, time::Duration};
+    use tonic::{
+        metadata::{MetadataKey, MetadataMap, MetadataValue},
+        transport::{Certificate, ClientTlsConfig},
+    };
+
+    /// Options for connecting an OTLP exporter to its collector.
+    pub(crate) struct OtlpExporterOptions<'a> {
+        pub(crate) endpoint: &'a str,
+        pub(crate) protocol: OtlpProtocol,
+        pub(crate) headers: &'a BTreeMap<String, String>,
+        pub(crate) ca_bundle_path: Option<&'a Path>,
+        pub(crate) timeout: Option<Duration>,
+    }
+
+    impl OtlpExporterOptions<'_> {
+        pub(crate) fn span_exporter(&self) -> Result<SpanExporter> {
+            match self.protocol {
+                OtlpProtocol::Grpc => self
+                    .configure_tonic(SpanExporter::builder().with_tonic())?
+                    .build(),
+                OtlpProtocol::HttpProtobuf => self
+                    .configure_http(SpanExporter::builder().with_http())?
+                    .build(),
+            }
+            .context("couldn't build OTLP span exporter")
+        }
+
+        pub(crate) fn metric_exporter(&self) -> Result<MetricExporter> {
+            match self.protocol {
+                OtlpProtocol::Grpc => self
+                    .configure_tonic(MetricExporter::builder().with_tonic())?
+                    .build(),
+                OtlpProtocol::HttpProtobuf => self
+                    .configure_http(MetricExporter::builder().with_http())?
+                    .build(),
+            }
+            .context("couldn't build OTLP metric exporter")
+        }
+
+        fn configure_tonic<B: WithExportConfig + WithTonicConfig>(&self, builder: B) -> Result<B> {
+            let mut metadata = MetadataMap::new();
+            for (name, value) in self.headers {
+                metadata.insert(
+                    MetadataKey::from_bytes(name.as_bytes())
+                        .with_context(|| format!("invalid OTLP header name {name:?}"))?,
+                    MetadataValue::try_from(value.as_str())
+                        .with_context(|| format!("invalid value for OTLP header {name:?}"))?,
+                );
+            }
+
+            let mut builder = builder.with_endpoint(self.endpoint).with_metadata(metadata);
+            if let Some(path) = self.ca_bundle_path {
+                builder = builder.with_tls_config(
+                    ClientTlsConfig::new().ca_certificate(Certificate::from_pem(read_pem(path)?)),
+                );
+            }
+            if let Some(timeout) = self.timeout {
+                builder = builder.with_timeout(timeout);
+            }
+            Ok(builder)
+        }
+
+        fn configure_http<B: WithExportConfig + WithHttpConfig>(&self, builder: B) -> Result<B> {
+            let mut client = reqwest::Client::builder();
+            if let Some(path) = self.ca_bundle_path {
+                for certificate in reqwest::Certificate::from_pem_bundle(&read_pem(path)?)
+                    .with_context(|| format!("couldn't parse CA bundle {}", path.display()))?
+                {
+                    client = client.add_root_certificate(certificate);
+                }
+            }
+            if let Some(timeout) = self.timeout {
+                client = client.timeout(timeout);
+            }
+            let client = client.build().context("couldn't build OTLP HTTP client")?;
+
+            let mut builder = builder
+                .with_protocol(Protocol::HttpBinary)
+                .with_endpoint(self.endpoint)
+                .with_headers(self.headers.clone().into_iter().collect())
+                .with_http_client(client);
+            if let Some(timeout) = self.timeout {
+                builder = builder.with_timeout(timeout);
+            }
+            Ok(builder)
+        }
+    }
+
+    fn read_pem(path: &Path) -> Result<Vec<u8>> {
+        fs::read(path).with_context(|| format!("couldn't read CA bundle {}", path.display()))
+    }
+
+    /// Produces the resource describing this process, with the configured attributes added. Where
+    /// a configured attribute has the same key as a detected one, the configured value is used.
+    pub(crate) fn otlp_resource(attributes: &BTreeMap<String, String>) -> Resource {
+        resource().merge(&Resource::new(
+            attributes
+                .iter()
+                .map(|(key, value)| KeyValue::new(key.clone(), value.clone())),
+        ))
+    }
+}
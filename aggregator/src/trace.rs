@@ -1,9 +1,14 @@
 //! Configures a tracing subscriber for Janus.
 
+use crate::otlp::{OtlpProtocol, fmt_redacted_headers};
+use educe::Educe;
+use http::HeaderMap;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     io::{IsTerminal, stdout},
     net::SocketAddr,
+    path::PathBuf,
 };
 use tracing::Level;
 use tracing_chrome::{ChromeLayerBuilder, TraceStyle};
@@ -14,9 +19,19 @@ use tracing_subscriber::{
 
 #[cfg(feature = "otlp")]
 use {
-    opentelemetry::{global::set_tracer_provider, trace::TracerProvider as _},
-    opentelemetry_otlp::WithExportConfig,
-    opentelemetry_sdk::trace::TracerProvider,
+    crate::otlp::{OtlpExporterOptions, otlp_resource},
+    http::{HeaderName, HeaderValue},
+    opentelemetry::{
+        global::{get_text_map_propagator, set_text_map_propagator, set_tracer_provider},
+        trace::TracerProvider as _,
+    },
+    opentelemetry_sdk::{
+        propagation::TraceContextPropagator,
+        runtime::Tokio,
+        trace::{BatchConfigBuilder, BatchSpanProcessor, Sampler, TracerProvider},
+    },
+    std::{collections::HashMap, time::Duration},
+    tracing_opentelemetry::OpenTelemetrySpanExt,
 };
 
 /// Errors from initializing trace subscriber.
@@ -29,6 +44,9 @@ pub enum Error {
     #[cfg(feature = "otlp")]
     #[error(transparent)]
     OpenTelemetry(#[from] opentelemetry::trace::TraceError),
+    #[cfg(feature = "otlp")]
+    #[error("OTLP exporter error: {0:#}")]
+    Otlp(anyhow::Error),
     #[error("bad log/trace filter: {0}")]
     FromEnv(#[from] FromEnvError),
     #[error("{0}")]
@@ -36,7 +54,7 @@ pub enum Error {
 }
 
 /// Configuration for the tracing subscriber.
-#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct TraceConfiguration {
     /// If true, uses a [`tracing_subscriber::fmt::TestWriter`] to capture trace
@@ -84,18 +102,92 @@ pub struct TokioConsoleConfiguration {
 }
 
 /// Selection of an exporter for OpenTelemetry spans.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields, rename_all = "lowercase")]
 pub enum OpenTelemetryTraceConfiguration {
     Otlp(OtlpTraceConfiguration),
 }
 
 /// Configuration options specific to the OpenTelemetry OTLP exporter.
-#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Default, Educe, PartialEq, Serialize, Deserialize)]
+#[educe(Debug)]
 #[serde(deny_unknown_fields)]
 pub struct OtlpTraceConfiguration {
-    /// gRPC endpoint for OTLP exporter.
+    /// Endpoint for OTLP exporter. See [`OtlpProtocol`] for its form.
     pub endpoint: String,
+    /// Transport used to reach the endpoint. Defaults to gRPC.
+    #[serde(default)]
+    pub protocol: OtlpProtocol,
+    /// Headers sent with each export request, e.g. for authentication.
+    #[serde(default)]
+    #[educe(Debug(method(fmt_redacted_headers)))]
+    pub headers: BTreeMap<String, String>,
+    /// Path to a PEM file of CA certificates to trust when connecting to the endpoint.
+    #[serde(default)]
+    pub ca_bundle_path: Option<PathBuf>,
+    /// Timeout for each export request, in milliseconds.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Attributes added to the resource describing this process, e.g. `service.name` or
+    /// `deployment.environment`.
+    #[serde(default)]
+    pub resource_attributes: BTreeMap<String, String>,
+    /// Fraction of traces to sample, between 0 and 1, for traces not started by a sampled parent.
+    /// Defaults to sampling all traces.
+    #[serde(default)]
+    pub sampling_ratio: Option<f64>,
+    /// Maximum number of spans buffered for export. Further spans are dropped.
+    #[serde(default)]
+    pub max_queue_size: Option<usize>,
+    /// Maximum number of spans sent in a single export request.
+    #[serde(default)]
+    pub max_export_batch_size: Option<usize>,
+    /// Delay between exports of buffered spans, in milliseconds.
+    #[serde(default)]
+    pub scheduled_delay_ms: Option<u64>,
+}
+
+#[cfg(feature = "otlp")]
+impl OtlpTraceConfiguration {
+    fn exporter_options(&self) -> OtlpExporterOptions<'_> {
+        OtlpExporterOptions {
+            endpoint: &self.endpoint,
+            protocol: self.protocol,
+            headers: &self.headers,
+            ca_bundle_path: self.ca_bundle_path.as_deref(),
+            timeout: self.timeout_ms.map(Duration::from_millis),
+        }
+    }
+
+    fn batch_span_processor(&self) -> Result<BatchSpanProcessor<Tokio>, Error> {
+        let exporter = self
+            .exporter_options()
+            .span_exporter()
+            .map_err(Error::Otlp)?;
+        let mut batch_config = BatchConfigBuilder::default();
+        if let Some(max_queue_size) = self.max_queue_size {
+            batch_config = batch_config.with_max_queue_size(max_queue_size);
+        }
+        if let Some(max_export_batch_size) = self.max_export_batch_size {
+            batch_config = batch_config.with_max_export_batch_size(max_export_batch_size);
+        }
+        if let Some(scheduled_delay_ms) = self.scheduled_delay_ms {
+            batch_config =
+                batch_config.with_scheduled_delay(Duration::from_millis(scheduled_delay_ms));
+        }
+        Ok(BatchSpanProcessor::builder(exporter, Tokio)
+            .with_batch_config(batch_config.build())
+            .build())
+    }
+
+    fn sampler(&self) -> Sampler {
+        match self.sampling_ratio {
+            Some(sampling_ratio) => {
+                Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(sampling_ratio)))
+            }
+            None => Sampler::ParentBased(Box::new(Sampler::AlwaysOn)),
+        }
+    }
 }
 
 /// Create a base tracing layer with configuration used in all subscribers
@@ -193,14 +285,13 @@ pub fn install_trace_subscriber(
     #[cfg(feature = "otlp")]
     if let Some(OpenTelemetryTraceConfiguration::Otlp(otlp_config)) = &config.open_telemetry_config
     {
-        let exporter = opentelemetry_otlp::SpanExporter::builder()
-            .with_tonic()
-            .with_endpoint(otlp_config.endpoint.clone())
-            .build()?;
         let tracer_provider = TracerProvider::builder()
-            .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+            .with_span_processor(otlp_config.batch_span_processor()?)
+            .with_sampler(otlp_config.sampler())
+            .with_resource(otlp_resource(&otlp_config.resource_attributes))
             .build();
         set_tracer_provider(tracer_provider.clone());
+        set_text_map_propagator(TraceContextPropagator::new());
         let tracer = tracer_provider.tracer("janus_aggregator");
 
         let telemetry = tracing_opentelemetry::layer()
@@ -244,6 +335,30 @@ pub fn install_trace_subscriber(
     ))
 }
 
+/// Returns headers carrying the W3C trace context of the current span, to be sent with requests to
+/// the peer aggregator so that the spans handling them join this trace. The headers are empty
+/// unless OpenTelemetry traces are configured.
+#[cfg(feature = "otlp")]
+pub(crate) fn trace_context_headers() -> HeaderMap {
+    let context = tracing::Span::current().context();
+    let mut carrier = HashMap::new();
+    get_text_map_propagator(|propagator| propagator.inject_context(&context, &mut carrier));
+    carrier
+        .into_iter()
+        .filter_map(|(name, value)| {
+            Some((
+                HeaderName::try_from(name).ok()?,
+                HeaderValue::try_from(value).ok()?,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "otlp"))]
+pub(crate) fn trace_context_headers() -> HeaderMap {
+    HeaderMap::new()
+}
+
 pub struct TraceGuards {
     uses_otel_tracer: bool,
     _chrome_guard: Option<tracing_chrome::FlushGuard>,
@@ -257,3 +372,40 @@ impl Drop for TraceGuards {
         }
     }
 }
+
+#[cfg(all(test, feature = "otlp"))]
+mod tests {
+    use crate::trace::trace_context_headers;
+    use opentelemetry::{
+        global::set_text_map_propagator,
+        trace::{TraceContextExt, TracerProvider as _},
+    };
+    use opentelemetry_sdk::{propagation::TraceContextPropagator, trace::TracerProvider};
+    use tracing::info_span;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::{Registry, layer::SubscriberExt};
+
+    #[test]
+    fn trace_context_headers_carry_current_span() {
+        set_text_map_propagator(TraceContextPropagator::new());
+        let tracer = TracerProvider::builder().build().tracer("test");
+        let subscriber =
+            Registry::default().with(tracing_opentelemetry::layer().with_tracer(tracer));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = info_span!("test");
+            let _entered = span.enter();
+            let span_context = span.context().span().span_context().clone();
+
+            let headers = trace_context_headers();
+            assert_eq!(
+                headers.get("traceparent").unwrap().to_str().unwrap(),
+                format!(
+                    "00-{}-{}-01",
+                    span_context.trace_id(),
+                    span_context.span_id()
+                )
+            );
+        });
+    }
+}
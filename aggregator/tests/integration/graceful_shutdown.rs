@@ -7,12 +7,19 @@ use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use janus_aggregator::{
     aggregator::key_rotator::HpkeKeyRotatorConfig,
     binaries::{
-        aggregation_job_creator::Config as AggregationJobCreatorConfig,
-        aggregation_job_driver::Config as AggregationJobDriverConfig,
+        aggregation_job_creator::{
+            AggregationJobCreatorConfig, Config as AggregationJobCreatorBinaryConfig,
+        },
+        aggregation_job_driver::{
+            AggregationJobDriverConfig, Config as AggregationJobDriverBinaryConfig,
+        },
         aggregator::{
             AggregatorApi, Config as AggregatorConfig, GarbageCollectorConfig, KeyRotatorConfig,
         },
-        collection_job_driver::Config as CollectionJobDriverConfig,
+        all_in_one::{Config as AllInOneConfig, JobDriverSection},
+        collection_job_driver::{
+            CollectionJobDriverConfig, Config as CollectionJobDriverBinaryConfig,
+        },
         garbage_collector::Config as GarbageCollectorBinaryConfig,
     },
     config::{
@@ -27,8 +34,11 @@ use janus_aggregator_core::{
     task::{AggregationMode, BatchMode, test_util::TaskBuilder},
 };
 use janus_core::{
-    hpke::HpkeCiphersuite, initialize_rustls, test_util::install_test_trace_subscriber,
-    time::RealClock, vdaf::VdafInstance,
+    hpke::HpkeCiphersuite,
+    initialize_rustls,
+    test_util::install_test_trace_subscriber,
+    time::RealClock,
+    vdaf::{VdafInstance, vdaf_dp_strategies},
 };
 use janus_messages::{Duration, HpkeAeadId, HpkeKdfId, HpkeKemId};
 use reqwest::Url;
@@ -144,7 +154,9 @@ async fn graceful_shutdown<C: BinaryConfig + Serialize>(binary_name: &str, mut c
     let task = TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .build()
     .leader_view()
@@ -253,9 +265,7 @@ async fn graceful_shutdown<C: BinaryConfig + Serialize>(binary_name: &str, mut c
     }
 }
 
-#[tokio::test(flavor = "multi_thread")]
-#[cfg_attr(not(target_os = "linux"), ignore)]
-async fn aggregator_shutdown() {
+async fn aggregator_config() -> AggregatorConfig {
     let aggregator_port = select_open_port().await.unwrap();
     let aggregator_listen_address = SocketAddr::from((Ipv4Addr::LOCALHOST, aggregator_port));
 
@@ -263,7 +273,7 @@ async fn aggregator_shutdown() {
     let aggregator_api_listen_address =
         SocketAddr::from((Ipv4Addr::LOCALHOST, aggregator_api_port));
 
-    let config = AggregatorConfig {
+    AggregatorConfig {
         common_config: CommonConfig {
             database: DbConfig {
                 url: "postgres://localhost".parse().unwrap(),
@@ -271,6 +281,7 @@ async fn aggregator_shutdown() {
                 connection_pool_max_size: None,
                 check_schema_version: true,
                 tls_trust_store_path: None,
+                replica: None,
             },
             logging_config: TraceConfiguration::default(),
             metrics_config: MetricsConfiguration::default(),
@@ -286,6 +297,7 @@ async fn aggregator_shutdown() {
             collection_limit: 50,
             tasks_per_tx: 1,
             concurrent_tx_limit: None,
+            report_partitioning: None,
         }),
         key_rotator: Some(KeyRotatorConfig {
             frequency_s: 60 * 60 * 6,
@@ -306,12 +318,15 @@ async fn aggregator_shutdown() {
                     ),
                 ]),
             },
+            ohttp_gateway: None,
         }),
         listen_address: aggregator_listen_address,
         aggregator_api: Some(AggregatorApi {
             listen_address: Some(aggregator_api_listen_address),
             path_prefix: None,
             public_dap_url: "https://public.dap.url".parse().unwrap(),
+            log_audit_events: false,
+            report_count_mode: Default::default(),
         }),
         max_upload_batch_size: 100,
         max_upload_batch_write_delay_ms: 250,
@@ -323,9 +338,30 @@ async fn aggregator_shutdown() {
         task_cache_capacity: None,
         log_forbidden_mutations: None,
         helper_aggregation_request_queue: None,
-    };
+        ohttp_gateway: None,
+        honor_untrusted_trace_context: false,
+    }
+}
 
-    graceful_shutdown("aggregator", config).await;
+fn job_driver_config() -> JobDriverConfig {
+    JobDriverConfig {
+        job_discovery_interval_s: 10,
+        max_concurrent_job_workers: 10,
+        worker_lease_duration_s: 600,
+        worker_lease_clock_skew_allowance_s: 60,
+        maximum_attempts_before_failure: 5,
+        http_request_timeout_s: 10,
+        http_request_connection_timeout_s: 30,
+        retry_initial_interval_ms: 1000,
+        retry_max_interval_ms: 30_000,
+        retry_max_elapsed_time_ms: 300_000,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(not(target_os = "linux"), ignore)]
+async fn aggregator_shutdown() {
+    graceful_shutdown("aggregator", aggregator_config().await).await;
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -339,6 +375,7 @@ async fn garbage_collector_shutdown() {
                 connection_pool_max_size: None,
                 check_schema_version: true,
                 tls_trust_store_path: None,
+                replica: None,
             },
             logging_config: TraceConfiguration::default(),
             metrics_config: MetricsConfiguration::default(),
@@ -353,16 +390,49 @@ async fn garbage_collector_shutdown() {
             collection_limit: 50,
             tasks_per_tx: 1,
             concurrent_tx_limit: None,
+            report_partitioning: None,
         },
     };
 
     graceful_shutdown("garbage_collector", config).await;
 }
 
+fn aggregation_job_creator_config() -> AggregationJobCreatorConfig {
+    AggregationJobCreatorConfig {
+        batch_aggregation_shard_count: 32,
+        tasks_update_frequency_s: 3600,
+        aggregation_job_creation_interval_s: 60,
+        min_aggregation_job_size: 100,
+        max_aggregation_job_size: 100,
+        aggregation_job_creation_report_window: 5000,
+        late_report_grace_period_s: 3600,
+    }
+}
+
+fn aggregation_job_driver_config() -> AggregationJobDriverConfig {
+    AggregationJobDriverConfig {
+        taskprov_config: TaskprovConfig::default(),
+        batch_aggregation_shard_count: 32,
+        task_counter_shard_count: 32,
+        hpke_configs_refresh_interval: None,
+        default_async_poll_interval: 1000,
+    }
+}
+
+fn collection_job_driver_config() -> CollectionJobDriverConfig {
+    CollectionJobDriverConfig {
+        batch_aggregation_shard_count: 32,
+        min_collection_job_retry_delay_s: 1,
+        max_collection_job_retry_delay_s: 1,
+        collection_job_retry_delay_exponential_factor: 1.0,
+        max_future_concurrency: 10000,
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[cfg_attr(not(target_os = "linux"), ignore)]
 async fn aggregation_job_creator_shutdown() {
-    let config = AggregationJobCreatorConfig {
+    let config = AggregationJobCreatorBinaryConfig {
         common_config: CommonConfig {
             database: DbConfig {
                 url: "postgres://localhost".parse().unwrap(),
@@ -370,6 +440,7 @@ async fn aggregation_job_creator_shutdown() {
                 connection_pool_max_size: None,
                 check_schema_version: true,
                 tls_trust_store_path: None,
+                replica: None,
             },
             logging_config: TraceConfiguration::default(),
             metrics_config: MetricsConfiguration::default(),
@@ -377,13 +448,7 @@ async fn aggregation_job_creator_shutdown() {
             max_transaction_retries: default_max_transaction_retries(),
             thread_pool_stack_size: None,
         },
-        batch_aggregation_shard_count: 32,
-        tasks_update_frequency_s: 3600,
-        aggregation_job_creation_interval_s: 60,
-        min_aggregation_job_size: 100,
-        max_aggregation_job_size: 100,
-        aggregation_job_creation_report_window: 5000,
-        late_report_grace_period_s: 3600,
+        aggregation_job_creator: aggregation_job_creator_config(),
     };
 
     graceful_shutdown("aggregation_job_creator", config).await;
@@ -392,7 +457,7 @@ async fn aggregation_job_creator_shutdown() {
 #[tokio::test(flavor = "multi_thread")]
 #[cfg_attr(not(target_os = "linux"), ignore)]
 async fn aggregation_job_driver_shutdown() {
-    let config = AggregationJobDriverConfig {
+    let config = AggregationJobDriverBinaryConfig {
         common_config: CommonConfig {
             database: DbConfig {
                 url: "postgres://localhost".parse().unwrap(),
@@ -400,6 +465,7 @@ async fn aggregation_job_driver_shutdown() {
                 connection_pool_max_size: None,
                 check_schema_version: true,
                 tls_trust_store_path: None,
+                replica: None,
             },
             logging_config: TraceConfiguration::default(),
             metrics_config: MetricsConfiguration::default(),
@@ -407,23 +473,8 @@ async fn aggregation_job_driver_shutdown() {
             max_transaction_retries: default_max_transaction_retries(),
             thread_pool_stack_size: None,
         },
-        job_driver_config: JobDriverConfig {
-            job_discovery_interval_s: 10,
-            max_concurrent_job_workers: 10,
-            worker_lease_duration_s: 600,
-            worker_lease_clock_skew_allowance_s: 60,
-            maximum_attempts_before_failure: 5,
-            http_request_timeout_s: 10,
-            http_request_connection_timeout_s: 30,
-            retry_initial_interval_ms: 1000,
-            retry_max_interval_ms: 30_000,
-            retry_max_elapsed_time_ms: 300_000,
-        },
-        taskprov_config: TaskprovConfig::default(),
-        batch_aggregation_shard_count: 32,
-        task_counter_shard_count: 32,
-        hpke_configs_refresh_interval: None,
-        default_async_poll_interval: 1000,
+        job_driver_config: job_driver_config(),
+        aggregation_job_driver: aggregation_job_driver_config(),
     };
 
     graceful_shutdown("aggregation_job_driver", config).await;
@@ -432,7 +483,7 @@ async fn aggregation_job_driver_shutdown() {
 #[tokio::test(flavor = "multi_thread")]
 #[cfg_attr(not(target_os = "linux"), ignore)]
 async fn collection_job_driver_shutdown() {
-    let config = CollectionJobDriverConfig {
+    let config = CollectionJobDriverBinaryConfig {
         common_config: CommonConfig {
             database: DbConfig {
                 url: "postgres://localhost".parse().unwrap(),
@@ -440,6 +491,7 @@ async fn collection_job_driver_shutdown() {
                 connection_pool_max_size: None,
                 check_schema_version: true,
                 tls_trust_store_path: None,
+                replica: None,
             },
             logging_config: TraceConfiguration::default(),
             metrics_config: MetricsConfiguration::default(),
@@ -447,24 +499,28 @@ async fn collection_job_driver_shutdown() {
             max_transaction_retries: default_max_transaction_retries(),
             thread_pool_stack_size: None,
         },
-        job_driver_config: JobDriverConfig {
-            job_discovery_interval_s: 10,
-            max_concurrent_job_workers: 10,
-            worker_lease_duration_s: 600,
-            worker_lease_clock_skew_allowance_s: 60,
-            maximum_attempts_before_failure: 5,
-            http_request_timeout_s: 10,
-            http_request_connection_timeout_s: 30,
-            retry_initial_interval_ms: 1000,
-            retry_max_interval_ms: 30_000,
-            retry_max_elapsed_time_ms: 300_000,
-        },
-        batch_aggregation_shard_count: 32,
-        min_collection_job_retry_delay_s: 1,
-        max_collection_job_retry_delay_s: 1,
-        collection_job_retry_delay_exponential_factor: 1.0,
-        max_future_concurrency: 10000,
+        job_driver_config: job_driver_config(),
+        collection_job_driver: collection_job_driver_config(),
     };
 
     graceful_shutdown("collection_job_driver", config).await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(not(target_os = "linux"), ignore)]
+async fn all_in_one_shutdown() {
+    let config = AllInOneConfig {
+        aggregator: aggregator_config().await,
+        aggregation_job_creator: aggregation_job_creator_config(),
+        aggregation_job_driver: JobDriverSection {
+            job_driver_config: job_driver_config(),
+            config: aggregation_job_driver_config(),
+        },
+        collection_job_driver: JobDriverSection {
+            job_driver_config: job_driver_config(),
+            config: collection_job_driver_config(),
+        },
+    };
+
+    graceful_shutdown("all-in-one", config).await;
+}
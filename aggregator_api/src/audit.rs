@@ -0,0 +1,203 @@
+//! Records mutations made via the aggregator API in the audit log.
+
+use crate::{Config, Error};
+use aws_lc_rs::digest::{SHA256, digest};
+use janus_aggregator_core::{
+    audit::emit_audit_log_event,
+    datastore::{
+        self, Datastore, Transaction,
+        models::{ApiToken, AuditLogEntry, AuditLogOutcome},
+    },
+};
+use janus_core::{auth_tokens::AuthenticationToken, time::Clock};
+use janus_messages::TaskId;
+use serde_json::Value;
+use std::{future::Future, pin::Pin, sync::Arc};
+use tracing::warn;
+use trillium::Conn;
+use trillium_router::RouterConnExt;
+
+/// The identity of an authenticated caller, as recorded in the audit log. This is placed in the
+/// connection's state once the caller's bearer token has been checked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct AuditActor(String);
+
+impl AuditActor {
+    /// Identifies a caller by a fingerprint of their bearer token, so that the audit log shows
+    /// which token was used without revealing it.
+    pub(crate) fn from_token(token: &AuthenticationToken) -> Self {
+        let digest = digest(&SHA256, token.as_ref());
+        let fingerprint: String = digest.as_ref()[..8]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        Self(format!("token:{fingerprint}"))
+    }
+
+    /// Identifies a caller by the name of the API token they presented.
+    pub(crate) fn from_api_token(api_token: &ApiToken) -> Self {
+        Self(format!("api_token:{}", api_token.name()))
+    }
+}
+
+/// The result of an audited mutation, along with what should be recorded about it in the audit
+/// log.
+pub(crate) struct Audited<T> {
+    value: T,
+    before: Option<Value>,
+    after: Option<Value>,
+    details: Value,
+}
+
+impl<T> Audited<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Self {
+            value,
+            before: None,
+            after: None,
+            details: Value::Null,
+        }
+    }
+
+    /// Sets the redacted state of the affected resource before the mutation.
+    pub(crate) fn with_before(self, before: Option<Value>) -> Self {
+        Self { before, ..self }
+    }
+
+    /// Sets the redacted state of the affected resource after the mutation.
+    pub(crate) fn with_after(self, after: Option<Value>) -> Self {
+        Self { after, ..self }
+    }
+
+    /// Sets additional, action-specific details.
+    pub(crate) fn with_details(self, details: Value) -> Self {
+        Self { details, ..self }
+    }
+}
+
+/// Writes audit log entries on behalf of a single request.
+pub(crate) struct Auditor {
+    actor: String,
+    route: String,
+    emit_events: bool,
+}
+
+impl Auditor {
+    pub(crate) fn from_conn(conn: &Conn) -> Result<Self, Error> {
+        let actor = conn
+            .state::<AuditActor>()
+            .ok_or_else(|| Error::Internal("Missing audit actor".into()))?
+            .0
+            .clone();
+        let route = format!(
+            "{} {}",
+            conn.method(),
+            conn.route()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| conn.path().to_string()),
+        );
+        let emit_events = conn
+            .state::<Arc<Config>>()
+            .is_some_and(|cfg| cfg.log_audit_events);
+        Ok(Self {
+            actor,
+            route,
+            emit_events,
+        })
+    }
+
+    /// Returns a new entry for the given action, with the actor and route filled in.
+    fn entry<C: Clock>(
+        &self,
+        ds: &Datastore<C>,
+        action: &str,
+        task_id: Option<TaskId>,
+        target: Option<String>,
+    ) -> AuditLogEntry {
+        AuditLogEntry::new(self.actor.clone(), action.to_string(), ds.clock().now())
+            .with_task_id(task_id)
+            .with_target(target)
+            .with_route(Some(self.route.clone()))
+    }
+
+    /// Runs a transaction carrying out an audited mutation, writing a successful audit log entry
+    /// as part of the same transaction. If the transaction fails, a failed entry is written
+    /// instead.
+    pub(crate) async fn run_tx<C, T, F>(
+        &self,
+        ds: &Datastore<C>,
+        name: &'static str,
+        action: &str,
+        task_id: Option<TaskId>,
+        target: Option<String>,
+        f: F,
+    ) -> Result<T, Error>
+    where
+        C: Clock,
+        T: Send + 'static,
+        F: for<'a> Fn(
+                &'a Transaction<C>,
+            ) -> Pin<
+                Box<dyn Future<Output = Result<Audited<T>, datastore::Error>> + Send + 'a>,
+            > + Send
+            + Sync
+            + 'static,
+    {
+        let template = self.entry(ds, action, task_id, target);
+        let f = Arc::new(f);
+        let result = ds
+            .run_tx(name, |tx| {
+                let (f, template) = (Arc::clone(&f), template.clone());
+                Box::pin(async move {
+                    let audited = f(tx).await?;
+                    let entry = template
+                        .with_before(audited.before)
+                        .with_after(audited.after)
+                        .with_details(audited.details);
+                    tx.put_audit_log_entry(&entry).await?;
+                    Ok((audited.value, entry))
+                })
+            })
+            .await;
+
+        match result {
+            Ok((value, entry)) => {
+                if self.emit_events {
+                    emit_audit_log_event(&entry);
+                }
+                Ok(value)
+            }
+            Err(err) => {
+                let err = Error::from(err);
+                self.put_failure(ds, template, &err).await;
+                Err(err)
+            }
+        }
+    }
+
+    async fn put_failure<C: Clock>(&self, ds: &Datastore<C>, template: AuditLogEntry, err: &Error) {
+        let entry = Arc::new(
+            template
+                .with_outcome(AuditLogOutcome::Failure)
+                .with_details(serde_json::json!({ "error": err.to_string() })),
+        );
+        // The action has already failed, so a failure to audit it is logged rather than returned.
+        if let Err(err) = ds
+            .run_tx("put_audit_log_failure", |tx| {
+                let entry = Arc::clone(&entry);
+                Box::pin(async move { tx.put_audit_log_entry(&entry).await })
+            })
+            .await
+        {
+            warn!(
+                ?err,
+                action = entry.action(),
+                "Couldn't record failure in audit log"
+            );
+            return;
+        }
+        if self.emit_events {
+            emit_audit_log_event(&entry);
+        }
+    }
+}
@@ -1,20 +1,32 @@
 //! This crate implements the Janus Aggregator API.
+mod audit;
 mod models;
 mod routes;
 #[cfg(test)]
 mod tests;
 
 use async_trait::async_trait;
+use audit::AuditActor;
+use aws_lc_rs::hmac;
 use git_version::git_version;
 use janus_aggregator_core::{
     TIME_HISTOGRAM_BOUNDARIES,
-    datastore::{self, Datastore},
+    datastore::{
+        self, Datastore,
+        models::{ApiToken, ApiTokenScope},
+    },
     instrumented,
 };
-use janus_core::{auth_tokens::AuthenticationToken, hpke, http::extract_bearer_token, time::Clock};
-use janus_messages::{HpkeConfigId, RoleParseError, TaskId};
+use janus_core::{
+    auth_tokens::{AuthenticationToken, AuthenticationTokenHash},
+    hpke,
+    http::extract_bearer_token,
+    time::Clock,
+};
+use janus_messages::{AggregationJobId, CollectionJobId, HpkeConfigId, RoleParseError, TaskId};
 use opentelemetry::metrics::Meter;
 use routes::*;
+use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, str::FromStr, sync::Arc};
 use tracing::error;
 use trillium::{
@@ -33,6 +45,71 @@ use url::Url;
 pub struct Config {
     pub auth_tokens: Vec<AuthenticationToken>,
     pub public_dap_url: Url,
+    /// Whether to emit each audit log entry as a structured log event, in addition to writing it
+    /// to the datastore.
+    pub log_audit_events: bool,
+    /// How report counts are disclosed by the batch status route.
+    pub report_count_mode: ReportCountMode,
+    /// Secret keying the noise added to report counts in the [`ReportCountMode::Noised`] mode. It
+    /// must be dedicated to this purpose, and the same across replicas and restarts, so that
+    /// repeated queries of a batch can't be averaged to remove the noise.
+    pub report_count_noise_key: hmac::Key,
+}
+
+/// How report counts are disclosed by the batch status route. Exact counts may leak information
+/// about individual clients' participation, so deployments exposing batch status to collectors may
+/// prefer to coarsen or perturb them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "mode")]
+pub enum ReportCountMode {
+    /// Report counts are disclosed exactly.
+    #[default]
+    Exact,
+    /// Report counts are rounded down to a multiple of `bucket_size`.
+    Bucketed { bucket_size: u64 },
+    /// Report counts are perturbed with two-sided geometric (discrete Laplace) noise, scaled for
+    /// `epsilon` with respect to a single report, then clamped at zero.
+    ///
+    /// The noise is derived pseudorandomly from a server secret, the batch and its exact count, so
+    /// querying an unchanged batch repeatedly returns the same value rather than fresh samples
+    /// which could be averaged away. Each distinct count of a batch observed over its lifetime is
+    /// a separate release, and their privacy loss composes. The noise is computed using
+    /// floating-point arithmetic, so this is a mitigation rather than a formal guarantee.
+    Noised { epsilon: f64 },
+}
+
+impl ReportCountMode {
+    /// Returns the given exact report count of a batch, as it should be disclosed. `noise_key` is
+    /// [`Config::report_count_noise_key`], which, with `batch` (identifying the task & batch) and
+    /// the count, determines the noise added in the `Noised` mode.
+    fn disclose(&self, noise_key: &hmac::Key, batch: &[u8], count: u64) -> u64 {
+        match self {
+            Self::Exact => count,
+            Self::Bucketed { bucket_size } => {
+                let bucket_size = (*bucket_size).max(1);
+                count - count % bucket_size
+            }
+            Self::Noised { epsilon } => {
+                let mut ctx = hmac::Context::with_key(noise_key);
+                ctx.update(b"janus report count noise");
+                ctx.update(batch);
+                ctx.update(&count.to_be_bytes());
+                let tag = ctx.sign();
+                let noise = sample_geometric(*epsilon, &tag.as_ref()[..8])
+                    - sample_geometric(*epsilon, &tag.as_ref()[8..16]);
+                u64::try_from((i128::from(count) + noise).max(0)).unwrap_or(u64::MAX)
+            }
+        }
+    }
+}
+
+/// Samples from a geometric distribution with success probability `1 - e^(-epsilon)`, counting the
+/// failures before the first success, using 8 pseudorandom bytes.
+fn sample_geometric(epsilon: f64, random_bytes: &[u8]) -> i128 {
+    // Map the top 53 bits to a float uniform on (0, 1], so its logarithm is finite.
+    let bits = u64::from_be_bytes(random_bytes.try_into().unwrap()) >> 11;
+    let uniform = (bits + 1) as f64 / (1u64 << 53) as f64;
+    (-uniform.ln() / epsilon).floor() as i128
 }
 
 /// Content type
@@ -95,67 +172,266 @@ pub fn aggregator_api_handler<C: Clock>(
             .with_request_size_histogram_boundaries(BYTES_HISTOGRAM_BOUNDARIES.to_vec())
             .with_response_size_histogram_boundaries(BYTES_HISTOGRAM_BOUNDARIES.to_vec()),
         // Authorization check.
-        api(auth_check),
+        api(auth_check::<C>),
         // Check content type and accept headers
         ReplaceMimeTypes,
-        // Main functionality router.
+        // Main functionality router. Each route also checks that the caller holds the scope it
+        // requires.
         Router::new()
-            .get("/", instrumented(api(get_config)))
-            .get("/task_ids", instrumented(api(get_task_ids::<C>)))
-            .post("/tasks", instrumented(api(post_task::<C>)))
-            .get("/tasks/:task_id", instrumented(api(get_task::<C>)))
-            .patch("/tasks/:task_id", instrumented(api(patch_task::<C>)))
-            .delete("/tasks/:task_id", instrumented(api(delete_task::<C>)))
+            .get(
+                "/",
+                instrumented((requires(ApiTokenScope::ReadOnly), api(get_config))),
+            )
+            .get(
+                "/task_ids",
+                instrumented((requires(ApiTokenScope::ReadOnly), api(get_task_ids::<C>))),
+            )
+            .post(
+                "/tasks",
+                instrumented((requires(ApiTokenScope::TaskAdmin), api(post_task::<C>))),
+            )
+            .get(
+                "/tasks/:task_id",
+                instrumented((requires(ApiTokenScope::ReadOnly), api(get_task::<C>))),
+            )
+            .patch(
+                "/tasks/:task_id",
+                instrumented((requires(ApiTokenScope::TaskAdmin), api(patch_task::<C>))),
+            )
+            .delete(
+                "/tasks/:task_id",
+                instrumented((requires(ApiTokenScope::TaskAdmin), api(delete_task::<C>))),
+            )
             .get(
                 "/tasks/:task_id/metrics/uploads",
-                instrumented(api(get_task_upload_metrics::<C>)),
+                instrumented((
+                    requires(ApiTokenScope::ReadOnly),
+                    api(get_task_upload_metrics::<C>),
+                )),
             )
             .get(
                 "/tasks/:task_id/metrics/aggregations",
-                instrumented(api(get_task_aggregation_metrics::<C>)),
+                instrumented((
+                    requires(ApiTokenScope::ReadOnly),
+                    api(get_task_aggregation_metrics::<C>),
+                )),
+            )
+            .get(
+                "/tasks/:task_id/dp_budget",
+                instrumented((
+                    requires(ApiTokenScope::ReadOnly),
+                    api(get_task_dp_budget::<C>),
+                )),
+            )
+            .get(
+                "/tasks/:task_id/batch_status",
+                instrumented((
+                    requires(ApiTokenScope::ReadOnly),
+                    api(get_batch_status::<C>),
+                )),
+            )
+            .get(
+                "/audit_log",
+                instrumented((requires(ApiTokenScope::ReadOnly), api(get_audit_log::<C>))),
+            )
+            .get(
+                "/abandoned_jobs",
+                instrumented((
+                    requires(ApiTokenScope::ReadOnly),
+                    api(get_abandoned_jobs::<C>),
+                )),
+            )
+            .get(
+                "/tasks/:task_id/aggregation_jobs/:aggregation_job_id/history",
+                instrumented((
+                    requires(ApiTokenScope::ReadOnly),
+                    api(get_aggregation_job_history::<C>),
+                )),
+            )
+            .post(
+                "/tasks/:task_id/aggregation_jobs/:aggregation_job_id/requeue",
+                instrumented((
+                    requires(ApiTokenScope::TaskAdmin),
+                    api(post_aggregation_job_requeue::<C>),
+                )),
+            )
+            .post(
+                "/tasks/:task_id/aggregation_jobs/:aggregation_job_id/fail",
+                instrumented((
+                    requires(ApiTokenScope::TaskAdmin),
+                    api(post_aggregation_job_fail::<C>),
+                )),
+            )
+            .get(
+                "/tasks/:task_id/collection_jobs/:collection_job_id/history",
+                instrumented((
+                    requires(ApiTokenScope::ReadOnly),
+                    api(get_collection_job_history::<C>),
+                )),
+            )
+            .post(
+                "/tasks/:task_id/collection_jobs/:collection_job_id/requeue",
+                instrumented((
+                    requires(ApiTokenScope::TaskAdmin),
+                    api(post_collection_job_requeue::<C>),
+                )),
+            )
+            .post(
+                "/tasks/:task_id/collection_jobs/:collection_job_id/fail",
+                instrumented((
+                    requires(ApiTokenScope::TaskAdmin),
+                    api(post_collection_job_fail::<C>),
+                )),
+            )
+            .get(
+                "/hpke_configs",
+                instrumented((
+                    requires(ApiTokenScope::ReadOnly),
+                    api(get_hpke_configs::<C>),
+                )),
             )
-            .get("/hpke_configs", instrumented(api(get_hpke_configs::<C>)))
             .get(
                 "/hpke_configs/:config_id",
-                instrumented(api(get_hpke_config::<C>)),
+                instrumented((requires(ApiTokenScope::ReadOnly), api(get_hpke_config::<C>))),
+            )
+            .put(
+                "/hpke_configs",
+                instrumented((requires(ApiTokenScope::KeyAdmin), api(put_hpke_config::<C>))),
             )
-            .put("/hpke_configs", instrumented(api(put_hpke_config::<C>)))
             .patch(
                 "/hpke_configs/:config_id",
-                instrumented(api(patch_hpke_config::<C>)),
+                instrumented((
+                    requires(ApiTokenScope::KeyAdmin),
+                    api(patch_hpke_config::<C>),
+                )),
             )
             .delete(
                 "/hpke_configs/:config_id",
-                instrumented(api(delete_hpke_config::<C>)),
+                instrumented((
+                    requires(ApiTokenScope::KeyAdmin),
+                    api(delete_hpke_config::<C>),
+                )),
             )
             .get(
                 "/taskprov/peer_aggregators",
-                instrumented(api(get_taskprov_peer_aggregators::<C>)),
+                instrumented((
+                    requires(ApiTokenScope::ReadOnly),
+                    api(get_taskprov_peer_aggregators::<C>),
+                )),
             )
             .post(
                 "/taskprov/peer_aggregators",
-                instrumented(api(post_taskprov_peer_aggregator::<C>)),
+                instrumented((
+                    requires(ApiTokenScope::TaskprovAdmin),
+                    api(post_taskprov_peer_aggregator::<C>),
+                )),
             )
             .delete(
                 "/taskprov/peer_aggregators",
-                instrumented(api(delete_taskprov_peer_aggregator::<C>)),
+                instrumented((
+                    requires(ApiTokenScope::TaskprovAdmin),
+                    api(delete_taskprov_peer_aggregator::<C>),
+                )),
             ),
     )
 }
 
-async fn auth_check(conn: &mut Conn, (): ()) -> Option<(Status, Halt)> {
-    let (Some(cfg), Ok(Some(bearer_token))) =
-        (conn.state::<Arc<Config>>(), extract_bearer_token(conn))
-    else {
+/// The permissions of an authenticated caller. This is placed in the connection's state by
+/// [`auth_check`], and checked against each route by [`Authorize`].
+#[derive(Clone, Debug)]
+enum Permissions {
+    /// The caller presented one of the tokens in [`Config::auth_tokens`], which may use any route.
+    Unrestricted,
+    /// The caller presented a named API token from the datastore, which may only use the routes
+    /// permitted by its scopes and task restrictions.
+    Scoped(ApiToken),
+}
+
+async fn auth_check<C: Clock>(conn: &mut Conn, (): ()) -> Option<(Status, Halt)> {
+    let (Some(cfg), Some(ds), Ok(Some(bearer_token))) = (
+        conn.state::<Arc<Config>>().cloned(),
+        conn.state::<Arc<Datastore<C>>>().cloned(),
+        extract_bearer_token(conn),
+    ) else {
         return Some((Status::Unauthorized, Halt));
     };
 
     if cfg.auth_tokens.contains(&bearer_token) {
-        // Authorization succeeds.
-        None
-    } else {
+        // Authorization succeeds. Remember who the caller is, for the audit log.
+        conn.insert_state(AuditActor::from_token(&bearer_token));
+        conn.insert_state(Permissions::Unrestricted);
+        return None;
+    }
+
+    // Tokens are looked up on the read replica, if any, so a newly created or deleted token may
+    // take up to the replica's maximum staleness to take effect.
+    let token_hash = AuthenticationTokenHash::from(&bearer_token);
+    match ds
+        .run_read_only_tx("get_api_token_by_hash", |tx| {
+            let token_hash = token_hash.clone();
+            Box::pin(async move { tx.get_api_token_by_hash(&token_hash).await })
+        })
+        .await
+    {
+        Ok(Some(api_token)) => {
+            // Authorization succeeds, subject to the checks made by each route.
+            conn.insert_state(AuditActor::from_api_token(&api_token));
+            conn.insert_state(Permissions::Scoped(api_token));
+            None
+        }
         // Authorization fails.
-        Some((Status::Unauthorized, Halt))
+        Ok(None) => Some((Status::Unauthorized, Halt)),
+        Err(err) => {
+            error!(?err, "Couldn't look up API token");
+            Some((Status::InternalServerError, Halt))
+        }
+    }
+}
+
+/// Checks that the caller is permitted to use a route, responding with 403 Forbidden if not.
+#[derive(Clone, Copy, Debug)]
+struct Authorize {
+    scope: ApiTokenScope,
+}
+
+/// Returns a handler which only permits callers holding a scope permitting `scope`. Callers
+/// restricted to particular tasks may only use routes naming one of those tasks; routes without a
+/// task ID in their path, such as listing task IDs or managing HPKE keys, are forbidden to them.
+fn requires(scope: ApiTokenScope) -> Authorize {
+    Authorize { scope }
+}
+
+impl Authorize {
+    fn permits(&self, conn: &Conn) -> bool {
+        let api_token = match conn.state::<Permissions>() {
+            Some(Permissions::Unrestricted) => return true,
+            Some(Permissions::Scoped(api_token)) => api_token,
+            None => return false,
+        };
+        if !api_token.permits(&self.scope) {
+            return false;
+        }
+        if api_token.task_ids().is_none() {
+            return true;
+        }
+        // Leave malformed task IDs to be rejected by the route itself. Routes which don't name a
+        // task act on global state, or on all tasks.
+        match conn.param("task_id").map(TaskId::from_str) {
+            Some(Ok(task_id)) => api_token.permits_task(&task_id),
+            Some(Err(_)) => true,
+            None => false,
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for Authorize {
+    async fn run(&self, conn: Conn) -> Conn {
+        if self.permits(&conn) {
+            conn
+        } else {
+            conn.with_status(Status::Forbidden).halt()
+        }
     }
 }
 
@@ -234,6 +510,8 @@ impl Handler for Error {
 trait ConnExt {
     fn task_id_param(&self) -> Result<TaskId, Error>;
     fn hpke_config_id_param(&self) -> Result<HpkeConfigId, Error>;
+    fn aggregation_job_id_param(&self) -> Result<AggregationJobId, Error>;
+    fn collection_job_id_param(&self) -> Result<CollectionJobId, Error>;
 }
 
 impl ConnExt for Conn {
@@ -253,6 +531,22 @@ impl ConnExt for Conn {
                 .map_err(|_| Error::BadRequest("Invalid config_id parameter".into()))?,
         ))
     }
+
+    fn aggregation_job_id_param(&self) -> Result<AggregationJobId, Error> {
+        AggregationJobId::from_str(
+            self.param("aggregation_job_id")
+                .ok_or_else(|| Error::Internal("Missing aggregation_job_id parameter".into()))?,
+        )
+        .map_err(|err| Error::BadRequest(err.into()))
+    }
+
+    fn collection_job_id_param(&self) -> Result<CollectionJobId, Error> {
+        CollectionJobId::from_str(
+            self.param("collection_job_id")
+                .ok_or_else(|| Error::Internal("Missing collection_job_id parameter".into()))?,
+        )
+        .map_err(|err| Error::BadRequest(err.into()))
+    }
 }
 
 /// Returns the git revision used to build this crate, using `git describe` if available, or the
@@ -1,8 +1,13 @@
+use crate::ReportCountMode;
 use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use educe::Educe;
 use janus_aggregator_core::{
-    datastore::models::{HpkeKeyState, HpkeKeypair, TaskAggregationCounter, TaskUploadCounter},
-    task::{AggregationMode, AggregatorTask, BatchMode},
+    datastore::models::{
+        AbandonedAggregationJob, AbandonedCollectionJob, AuditLogEntry, AuditLogOutcome,
+        HpkeKeyState, HpkeKeypair, JobHistoryEntry, JobStepOutcome, TaskAggregationCounter,
+        TaskUploadCounter,
+    },
+    task::{AggregationMode, AggregatorTask, BatchMode, DpBudget, TaskState},
     taskprov::{PeerAggregator, VerifyKeyInit},
 };
 use janus_core::{
@@ -10,7 +15,7 @@ use janus_core::{
     vdaf::VdafInstance,
 };
 use janus_messages::{
-    Duration, HpkeAeadId, HpkeConfig, HpkeKdfId, HpkeKemId, Role, TaskId, Time,
+    AggregationJobStep, Duration, HpkeAeadId, HpkeConfig, HpkeKdfId, HpkeKemId, Role, TaskId, Time,
     batch_mode::Code as SupportedBatchMode,
 };
 use serde::{Deserialize, Deserializer, Serialize};
@@ -93,12 +98,23 @@ pub(crate) struct PostTaskReq {
     /// sub-protocol requests received from the helper. If this aggregator is the helper, the value
     /// is `None`.
     pub(crate) collector_auth_token_hash: Option<AuthenticationTokenHash>,
+    /// How long the payloads of client reports are retained after the reports are aggregated, to
+    /// allow re-aggregation. If omitted, report payloads are scrubbed as soon as they are
+    /// aggregated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) report_retention_window: Option<Duration>,
+    /// The total privacy loss that collections of this task may spend, in the units of the VDAF's
+    /// differential privacy strategy. If omitted, collections are not limited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) dp_budget: Option<DpBudget>,
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct PatchTaskReq {
     #[serde(default, deserialize_with = "deserialize_some")]
     pub(crate) task_end: Option<Option<Time>>,
+    #[serde(default)]
+    pub(crate) state: Option<TaskState>,
 }
 
 #[derive(Clone, Educe, PartialEq, Eq, Serialize, Deserialize)]
@@ -126,6 +142,14 @@ pub(crate) struct TaskResp {
     /// The age after which a report is considered to be "expired" and will be considered a
     /// candidate for garbage collection.
     pub(crate) report_expiry_age: Option<Duration>,
+    /// How long the payloads of client reports are retained after the reports are aggregated. If
+    /// `None`, report payloads are scrubbed as soon as they are aggregated.
+    #[serde(default)]
+    pub(crate) report_retention_window: Option<Duration>,
+    /// The total privacy loss that collections of this task may spend. If `None`, collections are
+    /// not limited.
+    #[serde(default)]
+    pub(crate) dp_budget: Option<DpBudget>,
     /// The minimum number of reports in a batch to allow it to be collected.
     pub(crate) min_batch_size: u64,
     /// The duration to which clients should round their reported timestamps.
@@ -139,6 +163,9 @@ pub(crate) struct TaskResp {
     pub(crate) aggregator_auth_token: Option<AuthenticationToken>,
     /// HPKE configuration used by the collector to decrypt aggregate shares.
     pub(crate) collector_hpke_config: HpkeConfig,
+    /// The lifecycle state of the task.
+    #[serde(default)]
+    pub(crate) state: TaskState,
 }
 
 impl TryFrom<&AggregatorTask> for TaskResp {
@@ -155,6 +182,8 @@ impl TryFrom<&AggregatorTask> for TaskResp {
             task_start: task.task_start().copied(),
             task_end: task.task_end().copied(),
             report_expiry_age: task.report_expiry_age().cloned(),
+            report_retention_window: task.report_retention_window().cloned(),
+            dp_budget: task.dp_budget().copied(),
             min_batch_size: task.min_batch_size(),
             time_precision: *task.time_precision(),
             tolerable_clock_skew: *task.tolerable_clock_skew(),
@@ -163,6 +192,7 @@ impl TryFrom<&AggregatorTask> for TaskResp {
                 .collector_hpke_config()
                 .ok_or("collector_hpke_config is required")?
                 .clone(),
+            state: *task.state(),
         })
     }
 }
@@ -173,6 +203,22 @@ pub(crate) struct GetTaskUploadMetricsResp(pub(crate) TaskUploadCounter);
 #[derive(Serialize)]
 pub(crate) struct GetTaskAggregationMetricsResp(pub(crate) TaskAggregationCounter);
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct GetDpBudgetResp {
+    /// The total privacy loss that collections of this task may spend, or `None` if collections
+    /// are not limited.
+    pub(crate) dp_budget: Option<DpBudget>,
+    /// The privacy loss spent by each collection, as determined by the VDAF's differential privacy
+    /// strategy.
+    pub(crate) privacy_loss_per_collection: f64,
+    /// The privacy loss spent by collections so far.
+    pub(crate) spent: f64,
+    /// The privacy loss which remains to be spent, or `None` if collections are not limited.
+    pub(crate) remaining: Option<f64>,
+    /// The number of collections which have been charged against the budget.
+    pub(crate) collection_count: u64,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct HpkeConfigResp {
     pub(crate) config: HpkeConfig,
@@ -240,6 +286,172 @@ pub(crate) struct DeleteTaskprovPeerAggregatorReq {
     pub(crate) peer_role: Role,
 }
 
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct AbandonedAggregationJobResp {
+    pub(crate) task_id: TaskId,
+    pub(crate) aggregation_job_id: String,
+    /// The step the aggregation job had reached when it was abandoned.
+    pub(crate) step: AggregationJobStep,
+    /// The most recent error encountered while stepping the aggregation job, if known.
+    pub(crate) last_error: Option<String>,
+    pub(crate) updated_at: Time,
+}
+
+impl From<AbandonedAggregationJob> for AbandonedAggregationJobResp {
+    fn from(value: AbandonedAggregationJob) -> Self {
+        Self {
+            task_id: *value.task_id(),
+            aggregation_job_id: value.aggregation_job_id().to_string(),
+            step: value.step(),
+            last_error: value.last_error().map(ToString::to_string),
+            updated_at: *value.updated_at(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct AbandonedCollectionJobResp {
+    pub(crate) task_id: TaskId,
+    pub(crate) collection_job_id: String,
+    /// The number of times the collection job was stepped without making progress.
+    pub(crate) step_attempts: u64,
+    /// The most recent error encountered while stepping the collection job, if known.
+    pub(crate) last_error: Option<String>,
+    pub(crate) updated_at: Time,
+}
+
+impl From<AbandonedCollectionJob> for AbandonedCollectionJobResp {
+    fn from(value: AbandonedCollectionJob) -> Self {
+        Self {
+            task_id: *value.task_id(),
+            collection_job_id: value.collection_job_id().to_string(),
+            step_attempts: value.step_attempts(),
+            last_error: value.last_error().map(ToString::to_string),
+            updated_at: *value.updated_at(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct GetAbandonedJobsResp {
+    pub(crate) aggregation_jobs: Vec<AbandonedAggregationJobResp>,
+    pub(crate) collection_jobs: Vec<AbandonedCollectionJobResp>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct FailAggregationJobResp {
+    /// The number of reports returned to the pool of unaggregated reports.
+    pub(crate) reports_unaggregated: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct JobHistoryEntryResp {
+    /// The aggregation job step, or the number of collection job step attempts, at the time.
+    pub(crate) step: u64,
+    /// The number of times the job's lease had been acquired at the time.
+    pub(crate) lease_attempts: u64,
+    pub(crate) outcome: JobStepOutcome,
+    pub(crate) error: Option<String>,
+    /// The HTTP status returned by the peer aggregator, if any.
+    pub(crate) http_status: Option<u16>,
+    /// The problem type URI returned by the peer aggregator, if any.
+    pub(crate) problem_type: Option<String>,
+    /// The delay chosen before the job is retried, in milliseconds, if one was chosen.
+    pub(crate) retry_delay_ms: Option<u64>,
+    pub(crate) created_at: Time,
+}
+
+impl From<JobHistoryEntry> for JobHistoryEntryResp {
+    fn from(value: JobHistoryEntry) -> Self {
+        let record = value.record();
+        Self {
+            step: value.step(),
+            lease_attempts: value.lease_attempts(),
+            outcome: *record.outcome(),
+            error: record.error().map(ToString::to_string),
+            http_status: record.http_status(),
+            problem_type: record.problem_type().map(ToString::to_string),
+            retry_delay_ms: record
+                .retry_delay()
+                .map(|retry_delay| u64::try_from(retry_delay.as_millis()).unwrap_or(u64::MAX)),
+            created_at: *value.created_at(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct GetJobHistoryResp {
+    /// The job's history, most recent first.
+    pub(crate) entries: Vec<JobHistoryEntryResp>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct AuditLogEntryResp {
+    /// The identity of the operator or component which took the action.
+    pub(crate) actor: String,
+    pub(crate) action: String,
+    pub(crate) task_id: Option<TaskId>,
+    pub(crate) target: Option<String>,
+    /// The API route or CLI command through which the action was taken, if known.
+    pub(crate) route: Option<String>,
+    /// The state of the affected resource before the action, with secrets redacted.
+    pub(crate) before: Option<serde_json::Value>,
+    /// The state of the affected resource after the action, with secrets redacted.
+    pub(crate) after: Option<serde_json::Value>,
+    pub(crate) outcome: AuditLogOutcome,
+    pub(crate) details: serde_json::Value,
+    pub(crate) created_at: Time,
+}
+
+impl From<AuditLogEntry> for AuditLogEntryResp {
+    fn from(value: AuditLogEntry) -> Self {
+        Self {
+            actor: value.actor().to_string(),
+            action: value.action().to_string(),
+            task_id: value.task_id().copied(),
+            target: value.target().map(ToString::to_string),
+            route: value.route().map(ToString::to_string),
+            before: value.before().cloned(),
+            after: value.after().cloned(),
+            outcome: *value.outcome(),
+            details: value.details().clone(),
+            created_at: *value.created_at(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct GetAuditLogResp {
+    /// The matching audit log entries, most recent first.
+    pub(crate) entries: Vec<AuditLogEntryResp>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct BatchStatusResp {
+    /// The start of the batch interval, for time-interval tasks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) batch_interval_start: Option<Time>,
+    /// The duration of the batch interval, for time-interval tasks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) batch_interval_duration: Option<Duration>,
+    /// The batch ID, in unpadded base64url, for leader-selected tasks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) batch_id: Option<String>,
+    /// The number of reports in the batch, disclosed according to the response's
+    /// `report_count_mode`.
+    pub(crate) report_count: u64,
+    /// Whether the batch holds at least the task's minimum batch size of reports. This is
+    /// determined from the exact report count.
+    pub(crate) min_batch_size_met: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct GetBatchStatusResp {
+    pub(crate) min_batch_size: u64,
+    pub(crate) report_count_mode: ReportCountMode,
+    pub(crate) batches: Vec<BatchStatusResp>,
+}
+
 // Any value that is present is considered Some value, including null. See
 // https://github.com/serde-rs/serde/issues/984#issuecomment-314143738
 fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
@@ -1,25 +1,30 @@
 use crate::{
-    Config, ConnExt, Error, git_revision,
+    Config, ConnExt, Error,
+    audit::{Audited, Auditor},
+    git_revision,
     models::{
-        AggregatorApiConfig, AggregatorRole, DeleteTaskprovPeerAggregatorReq,
-        GetTaskAggregationMetricsResp, GetTaskIdsResp, GetTaskUploadMetricsResp, HpkeConfigResp,
-        PatchHpkeConfigReq, PatchTaskReq, PostTaskReq, PostTaskprovPeerAggregatorReq,
-        PutHpkeConfigReq, SupportedVdaf, TaskResp, TaskprovPeerAggregatorResp,
+        AbandonedAggregationJobResp, AbandonedCollectionJobResp, AggregatorApiConfig,
+        AggregatorRole, AuditLogEntryResp, BatchStatusResp, DeleteTaskprovPeerAggregatorReq,
+        FailAggregationJobResp, GetAbandonedJobsResp, GetAuditLogResp, GetBatchStatusResp,
+        GetDpBudgetResp, GetJobHistoryResp, GetTaskAggregationMetricsResp, GetTaskIdsResp,
+        GetTaskUploadMetricsResp, HpkeConfigResp, JobHistoryEntryResp, PatchHpkeConfigReq,
+        PatchTaskReq, PostTaskReq, PostTaskprovPeerAggregatorReq, PutHpkeConfigReq, SupportedVdaf,
+        TaskResp, TaskprovPeerAggregatorResp,
     },
 };
 use anyhow::Context;
 use aws_lc_rs::digest::{SHA256, digest};
 use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use janus_aggregator_core::{
-    SecretBytes,
-    datastore::{self, Datastore},
-    task::{AggregatorTask, AggregatorTaskParameters},
+    SecretBytes, audit,
+    datastore::{self, Datastore, models::AuditLogFilter},
+    task::{AggregatorTask, AggregatorTaskParameters, BatchMode},
     taskprov::PeerAggregator,
 };
 use janus_core::{auth_tokens::AuthenticationTokenHash, hpke::HpkeKeypair, time::Clock};
 use janus_messages::HpkeConfigId;
 use janus_messages::{
-    Duration, HpkeAeadId, HpkeKdfId, HpkeKemId, Role, TaskId,
+    BatchId, Duration, HpkeAeadId, HpkeKdfId, HpkeKemId, Interval, Role, TaskId, Time,
     batch_mode::Code as SupportedBatchMode,
 };
 use querystring::querify;
@@ -31,6 +36,7 @@ use std::{
 };
 use trillium::{Conn, Status};
 use trillium_api::{Json, State};
+use url::{Url, form_urlencoded};
 
 pub(super) async fn get_config(
     _: &mut Conn,
@@ -58,6 +64,7 @@ pub(super) async fn get_config(
             "UploadMetrics",
             "TimeBucketedLeaderSelected",
             "PureDpDiscreteLaplace",
+            "ZCdpDiscreteGaussian",
         ],
         software_name: "Janus",
         software_version: &VERSION,
@@ -78,7 +85,7 @@ pub(super) async fn get_task_ids<C: Clock>(
         .map_err(|err| Error::BadRequest(err.into()))?;
 
     let task_ids = ds
-        .run_tx("get_task_ids", |tx| {
+        .run_read_only_tx("get_task_ids", |tx| {
             Box::pin(async move { tx.get_task_ids(lower_bound).await })
         })
         .await?;
@@ -91,9 +98,10 @@ pub(super) async fn get_task_ids<C: Clock>(
 }
 
 pub(super) async fn post_task<C: Clock>(
-    _: &mut Conn,
+    conn: &mut Conn,
     (State(ds), Json(req)): (State<Arc<Datastore<C>>>, Json<PostTaskReq>),
 ) -> Result<Json<TaskResp>, Error> {
+    let auditor = Auditor::from_conn(conn)?;
     if !matches!(req.role, Role::Leader | Role::Helper) {
         return Err(Error::BadRequest(
             format!("invalid role {}", req.role).into(),
@@ -192,40 +200,55 @@ pub(super) async fn post_task<C: Clock>(
             req.time_precision, // Must be a multiple of the precision
             aggregator_parameters,
         )
+        .and_then(|task| task.with_report_retention_window(req.report_retention_window))
+        .map(|task| task.with_dp_budget(req.dp_budget))
         .context("Error constructing task")
         .map_err(|err| Error::BadRequest(err.into()))?,
     );
 
-    ds.run_tx("post_task", |tx| {
-        let task = Arc::clone(&task);
-        Box::pin(async move {
-            if let Some(existing_task) = tx.get_aggregator_task(task.id()).await? {
-            // Check whether the existing task in the DB corresponds to the incoming task, ignoring
-            // those fields that are randomly generated.
-            if existing_task.peer_aggregator_endpoint() == task.peer_aggregator_endpoint()
-                && existing_task.batch_mode() == task.batch_mode()
-                && existing_task.vdaf() == task.vdaf()
-                && existing_task.opaque_vdaf_verify_key() == task.opaque_vdaf_verify_key()
-                && existing_task.role() == task.role()
-                && existing_task.task_start() == task.task_start()
-                && existing_task.task_end() == task.task_end()
-                && existing_task.min_batch_size() == task.min_batch_size()
-                && existing_task.time_precision() == task.time_precision()
-                && existing_task.tolerable_clock_skew() == task.tolerable_clock_skew()
-                && existing_task.collector_hpke_config() == task.collector_hpke_config() {
-                    return Ok(())
-                }
+    let audited_task = Arc::clone(&task);
+    auditor
+        .run_tx(
+            &ds,
+            "post_task",
+            "create_task",
+            Some(*task.id()),
+            None,
+            move |tx| {
+                let task = Arc::clone(&audited_task);
+                Box::pin(async move {
+                    if let Some(existing_task) = tx.get_aggregator_task(task.id()).await? {
+                    // Check whether the existing task in the DB corresponds to the incoming task,
+                    // ignoring those fields that are randomly generated.
+                    if existing_task.peer_aggregator_endpoint() == task.peer_aggregator_endpoint()
+                        && existing_task.batch_mode() == task.batch_mode()
+                        && existing_task.vdaf() == task.vdaf()
+                        && existing_task.opaque_vdaf_verify_key() == task.opaque_vdaf_verify_key()
+                        && existing_task.role() == task.role()
+                        && existing_task.task_start() == task.task_start()
+                        && existing_task.task_end() == task.task_end()
+                        && existing_task.report_retention_window() == task.report_retention_window()
+                        && existing_task.dp_budget() == task.dp_budget()
+                        && existing_task.min_batch_size() == task.min_batch_size()
+                        && existing_task.time_precision() == task.time_precision()
+                        && existing_task.tolerable_clock_skew() == task.tolerable_clock_skew()
+                        && existing_task.collector_hpke_config() == task.collector_hpke_config() {
+                            let state = Some(audit::task_state(&existing_task));
+                            return Ok(Audited::new(()).with_before(state.clone()).with_after(state))
+                        }
 
-                let err = Error::Conflict(
-                    "task with same VDAF verify key and task ID already exists with different parameters".to_string(),
-                );
-                return Err(datastore::Error::User(err.into()));
-            }
+                        let err = Error::Conflict(
+                            "task with same VDAF verify key and task ID already exists with different parameters".to_string(),
+                        );
+                        return Err(datastore::Error::User(err.into()));
+                    }
 
-            tx.put_aggregator_task(&task).await
-        })
-    })
-    .await?;
+                    tx.put_aggregator_task(&task).await?;
+                    Ok(Audited::new(()).with_after(Some(audit::task_state(&task))))
+                })
+            },
+        )
+        .await?;
 
     let mut task_resp =
         TaskResp::try_from(task.as_ref()).map_err(|err| Error::Internal(err.into()))?;
@@ -261,15 +284,26 @@ pub(super) async fn delete_task<C: Clock>(
     State(ds): State<Arc<Datastore<C>>>,
 ) -> Result<Status, Error> {
     let task_id = conn.task_id_param()?;
-    match ds
-        .run_tx("delete_task", |tx| {
-            Box::pin(async move { tx.delete_task(&task_id).await })
-        })
-        .await
-    {
-        Ok(_) | Err(datastore::Error::MutationTargetNotFound) => Ok(Status::NoContent),
-        Err(err) => Err(err.into()),
-    }
+    Auditor::from_conn(conn)?
+        .run_tx(
+            &ds,
+            "delete_task",
+            "delete_task",
+            Some(task_id),
+            None,
+            move |tx| {
+                Box::pin(async move {
+                    let before = tx.get_aggregator_task(&task_id).await?;
+                    match tx.delete_task(&task_id).await {
+                        Ok(_) | Err(datastore::Error::MutationTargetNotFound) => (),
+                        Err(err) => return Err(err),
+                    }
+                    Ok(Audited::new(()).with_before(before.as_ref().map(audit::task_state)))
+                })
+            },
+        )
+        .await?;
+    Ok(Status::NoContent)
 }
 
 pub(super) async fn patch_task<C: Clock>(
@@ -277,17 +311,46 @@ pub(super) async fn patch_task<C: Clock>(
     (State(ds), Json(req)): (State<Arc<Datastore<C>>>, Json<PatchTaskReq>),
 ) -> Result<Json<TaskResp>, Error> {
     let task_id = conn.task_id_param()?;
-    let task = ds
-        .run_tx("patch_task", |tx| {
-            Box::pin(async move {
-                if let Some(task_end) = req.task_end {
-                    tx.update_task_end(&task_id, task_end.as_ref()).await?;
-                }
-                tx.get_aggregator_task(&task_id).await
-            })
-        })
-        .await?
-        .ok_or(Error::NotFound)?;
+    let (task_end, state) = (req.task_end, req.state);
+    let task = Auditor::from_conn(conn)?
+        .run_tx(
+            &ds,
+            "patch_task",
+            "update_task",
+            Some(task_id),
+            None,
+            move |tx| {
+                Box::pin(async move {
+                    let before = tx
+                        .get_aggregator_task(&task_id)
+                        .await?
+                        .ok_or(datastore::Error::MutationTargetNotFound)?;
+                    if let Some(task_end) = task_end {
+                        tx.update_task_end(&task_id, task_end.as_ref()).await?;
+                    }
+                    if let Some(state) = state {
+                        if !before.state().can_transition_to(&state) {
+                            return Err(bad_request_in_tx(
+                                format!(
+                                    "task cannot transition from {:?} to {state:?}",
+                                    before.state()
+                                )
+                                .into(),
+                            ));
+                        }
+                        tx.update_task_state(&task_id, &state).await?;
+                    }
+                    let after = tx
+                        .get_aggregator_task(&task_id)
+                        .await?
+                        .ok_or(datastore::Error::MutationTargetNotFound)?;
+                    Ok(Audited::new(after.clone())
+                        .with_before(Some(audit::task_state(&before)))
+                        .with_after(Some(audit::task_state(&after))))
+                })
+            },
+        )
+        .await?;
 
     Ok(Json(
         TaskResp::try_from(&task).map_err(|err| Error::Internal(err.into()))?,
@@ -300,7 +363,7 @@ pub(super) async fn get_task_upload_metrics<C: Clock>(
 ) -> Result<Json<GetTaskUploadMetricsResp>, Error> {
     let task_id = conn.task_id_param()?;
     Ok(Json(GetTaskUploadMetricsResp(
-        ds.run_tx("get_task_upload_metrics", |tx| {
+        ds.run_read_only_tx("get_task_upload_metrics", |tx| {
             Box::pin(async move { tx.get_task_upload_counter(&task_id).await })
         })
         .await?
@@ -314,7 +377,7 @@ pub(super) async fn get_task_aggregation_metrics<C: Clock>(
 ) -> Result<Json<GetTaskAggregationMetricsResp>, Error> {
     let task_id = conn.task_id_param()?;
     Ok(Json(GetTaskAggregationMetricsResp(
-        ds.run_tx("get_task_aggregation_metrics", |tx| {
+        ds.run_read_only_tx("get_task_aggregation_metrics", |tx| {
             Box::pin(async move { tx.get_task_aggregation_counter(&task_id).await })
         })
         .await?
@@ -322,12 +385,218 @@ pub(super) async fn get_task_aggregation_metrics<C: Clock>(
     )))
 }
 
+pub(super) async fn get_task_dp_budget<C: Clock>(
+    conn: &mut Conn,
+    State(ds): State<Arc<Datastore<C>>>,
+) -> Result<Json<GetDpBudgetResp>, Error> {
+    let task_id = conn.task_id_param()?;
+    let (task, usage) = ds
+        .run_tx("get_task_dp_budget", |tx| {
+            Box::pin(async move {
+                Ok((
+                    tx.get_aggregator_task(&task_id).await?,
+                    tx.get_dp_budget_usage(&task_id).await?,
+                ))
+            })
+        })
+        .await?;
+    let (task, usage) = task.zip(usage).ok_or(Error::NotFound)?;
+
+    let dp_budget = task.dp_budget().copied();
+    Ok(Json(GetDpBudgetResp {
+        dp_budget,
+        privacy_loss_per_collection: task.vdaf().privacy_loss(),
+        spent: usage.spent(),
+        remaining: dp_budget.map(|dp_budget| (dp_budget.get() - usage.spent()).max(0.0)),
+        collection_count: usage.collection_count(),
+    }))
+}
+
+pub(super) async fn get_batch_status<C: Clock>(
+    conn: &mut Conn,
+    (State(ds), State(config)): (State<Arc<Datastore<C>>>, State<Arc<Config>>),
+) -> Result<Json<GetBatchStatusResp>, Error> {
+    let task_id = conn.task_id_param()?;
+
+    let (mut batch_interval_start, mut batch_interval_duration) = (None, None);
+    let (mut batch_id, mut time_bucket_start) = (None, None);
+    for (key, value) in form_urlencoded::parse(conn.querystring().as_bytes()) {
+        match key.as_ref() {
+            "batch_interval_start" => {
+                batch_interval_start = Some(Time::from_seconds_since_epoch(parse_query_param(
+                    &key, &value,
+                )?))
+            }
+            "batch_interval_duration" => {
+                batch_interval_duration =
+                    Some(Duration::from_seconds(parse_query_param(&key, &value)?))
+            }
+            "batch_id" => batch_id = Some(parse_query_param::<BatchId>(&key, &value)?),
+            "time_bucket_start" => {
+                time_bucket_start = Some(Time::from_seconds_since_epoch(parse_query_param(
+                    &key, &value,
+                )?))
+            }
+            key => {
+                return Err(Error::BadRequest(
+                    format!("Unknown query parameter {key}").into(),
+                ));
+            }
+        }
+    }
+
+    let (min_batch_size, counts) = ds
+        .run_tx("get_batch_status", |tx| {
+            Box::pin(async move {
+                let task = tx
+                    .get_aggregator_task(&task_id)
+                    .await?
+                    .ok_or_else(|| datastore::Error::User(Error::NotFound.into()))?;
+                if task.role() != &Role::Leader {
+                    return Err(bad_request_in_tx(
+                        "Batch status is only available for leader tasks".into(),
+                    ));
+                }
+
+                let counts = match (
+                    task.batch_mode(),
+                    batch_interval_start,
+                    batch_interval_duration,
+                    batch_id,
+                ) {
+                    (BatchMode::TimeInterval, Some(start), Some(duration), None) => {
+                        let batch_interval = Interval::new(start, duration)
+                            .map_err(|err| bad_request_in_tx(err.into()))?;
+                        let count = tx
+                            .count_client_reports_for_interval(&task_id, &batch_interval)
+                            .await?;
+                        Vec::from([(BatchIdentifier::Interval(batch_interval), count)])
+                    }
+                    (BatchMode::TimeInterval, ..) => {
+                        return Err(bad_request_in_tx(
+                            "Time-interval tasks require batch_interval_start and \
+                             batch_interval_duration, and no batch_id"
+                                .into(),
+                        ));
+                    }
+                    (BatchMode::LeaderSelected { .. }, None, None, Some(batch_id)) => {
+                        let count = tx
+                            .count_client_reports_for_batch_id(&task_id, &batch_id)
+                            .await?;
+                        Vec::from([(BatchIdentifier::BatchId(batch_id), count)])
+                    }
+                    (
+                        BatchMode::LeaderSelected {
+                            batch_time_window_size,
+                        },
+                        None,
+                        None,
+                        None,
+                    ) => {
+                        if batch_time_window_size.is_some() != time_bucket_start.is_some() {
+                            return Err(bad_request_in_tx(
+                                "time_bucket_start is required exactly when the task has a \
+                                 batch_time_window_size"
+                                    .into(),
+                            ));
+                        }
+                        // Report the batches which are still being filled, i.e. those which aren't
+                        // yet known to be collectable.
+                        let outstanding_batches = tx
+                            .get_unfilled_outstanding_batches(&task_id, &time_bucket_start)
+                            .await?;
+                        let mut counts = Vec::with_capacity(outstanding_batches.len());
+                        for outstanding_batch in outstanding_batches {
+                            let batch_id = *outstanding_batch.id();
+                            let count = tx
+                                .count_client_reports_for_batch_id(&task_id, &batch_id)
+                                .await?;
+                            counts.push((BatchIdentifier::BatchId(batch_id), count));
+                        }
+                        counts
+                    }
+                    (BatchMode::LeaderSelected { .. }, ..) => {
+                        return Err(bad_request_in_tx(
+                            "Leader-selected tasks accept a batch_id, but no batch interval".into(),
+                        ));
+                    }
+                };
+                Ok((task.min_batch_size(), counts))
+            })
+        })
+        .await?;
+
+    Ok(Json(GetBatchStatusResp {
+        min_batch_size,
+        report_count_mode: config.report_count_mode,
+        batches: counts
+            .into_iter()
+            .map(|(batch_identifier, count)| {
+                let report_count = config.report_count_mode.disclose(
+                    &config.report_count_noise_key,
+                    &[task_id.as_ref().as_slice(), &batch_identifier.to_bytes()].concat(),
+                    count,
+                );
+                let (batch_interval, batch_id) = match batch_identifier {
+                    BatchIdentifier::Interval(interval) => (Some(interval), None),
+                    BatchIdentifier::BatchId(batch_id) => (None, Some(batch_id)),
+                };
+                BatchStatusResp {
+                    batch_interval_start: batch_interval.map(|interval| *interval.start()),
+                    batch_interval_duration: batch_interval.map(|interval| *interval.duration()),
+                    batch_id: batch_id.as_ref().map(ToString::to_string),
+                    report_count,
+                    // Computed from the disclosed count: computing it from the exact count would
+                    // reveal the exact count at the moment the threshold is crossed.
+                    min_batch_size_met: report_count >= min_batch_size,
+                }
+            })
+            .collect(),
+    }))
+}
+
+/// Identifies a batch whose status is reported by [`get_batch_status`].
+enum BatchIdentifier {
+    Interval(Interval),
+    BatchId(BatchId),
+}
+
+impl BatchIdentifier {
+    /// Returns a byte string uniquely identifying the batch within its task.
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Interval(interval) => [
+                interval.start().as_seconds_since_epoch().to_be_bytes(),
+                interval.duration().as_seconds().to_be_bytes(),
+            ]
+            .concat(),
+            Self::BatchId(batch_id) => batch_id.as_ref().to_vec(),
+        }
+    }
+}
+
+/// Wraps a [`Error::BadRequest`] for return from within a datastore transaction.
+fn bad_request_in_tx(err: Box<dyn std::error::Error + Send + Sync>) -> datastore::Error {
+    datastore::Error::User(Error::BadRequest(err).into())
+}
+
+fn parse_query_param<T>(key: &str, value: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    value
+        .parse()
+        .with_context(|| format!("Couldn't parse {key}"))
+        .map_err(|err| Error::BadRequest(err.into()))
+}
+
 pub(super) async fn get_hpke_configs<C: Clock>(
     _: &mut Conn,
     State(ds): State<Arc<Datastore<C>>>,
 ) -> Result<Json<Vec<HpkeConfigResp>>, Error> {
     Ok(Json(
-        ds.run_tx("get_hpke_configs", |tx| {
+        ds.run_read_only_tx("get_hpke_configs", |tx| {
             Box::pin(async move { tx.get_hpke_keypairs().await })
         })
         .await?
@@ -352,7 +621,7 @@ pub(super) async fn get_hpke_config<C: Clock>(
 }
 
 pub(super) async fn put_hpke_config<C: Clock>(
-    _: &mut Conn,
+    conn: &mut Conn,
     (State(ds), Json(req)): (State<Arc<Datastore<C>>>, Json<PutHpkeConfigReq>),
 ) -> Result<(Status, Json<HpkeConfigResp>), Error> {
     let existing_keypairs = ds
@@ -378,14 +647,23 @@ pub(super) async fn put_hpke_config<C: Clock>(
         req.aead_id.unwrap_or(HpkeAeadId::Aes128Gcm),
     )?;
 
-    let inserted_keypair = ds
-        .run_tx("put_hpke_config", |tx| {
-            let keypair = keypair.clone();
-            Box::pin(async move {
-                tx.put_hpke_keypair(&keypair).await?;
-                tx.get_hpke_keypair(&config_id).await
-            })
-        })
+    let inserted_keypair = Auditor::from_conn(conn)?
+        .run_tx(
+            &ds,
+            "put_hpke_config",
+            "create_hpke_keypair",
+            None,
+            Some(u8::from(config_id).to_string()),
+            move |tx| {
+                let keypair = keypair.clone();
+                Box::pin(async move {
+                    tx.put_hpke_keypair(&keypair).await?;
+                    let inserted = tx.get_hpke_keypair(&config_id).await?;
+                    let after = inserted.as_ref().map(audit::hpke_keypair_state);
+                    Ok(Audited::new(inserted).with_after(after))
+                })
+            },
+        )
         .await?
         .ok_or_else(|| Error::Internal("Newly inserted key disappeared".into()))?;
 
@@ -401,10 +679,26 @@ pub(super) async fn patch_hpke_config<C: Clock>(
 ) -> Result<Status, Error> {
     let config_id = conn.hpke_config_id_param()?;
 
-    ds.run_tx("patch_hpke_keypair", |tx| {
-        Box::pin(async move { tx.set_hpke_keypair_state(&config_id, &req.state).await })
-    })
-    .await?;
+    Auditor::from_conn(conn)?
+        .run_tx(
+            &ds,
+            "patch_hpke_keypair",
+            "set_hpke_keypair_state",
+            None,
+            Some(u8::from(config_id).to_string()),
+            move |tx| {
+                let state = req.state;
+                Box::pin(async move {
+                    let before = tx.get_hpke_keypair(&config_id).await?;
+                    tx.set_hpke_keypair_state(&config_id, &state).await?;
+                    let after = tx.get_hpke_keypair(&config_id).await?;
+                    Ok(Audited::new(())
+                        .with_before(before.as_ref().map(audit::hpke_keypair_state))
+                        .with_after(after.as_ref().map(audit::hpke_keypair_state)))
+                })
+            },
+        )
+        .await?;
 
     Ok(Status::Ok)
 }
@@ -414,15 +708,29 @@ pub(super) async fn delete_hpke_config<C: Clock>(
     State(ds): State<Arc<Datastore<C>>>,
 ) -> Result<Status, Error> {
     let config_id = conn.hpke_config_id_param()?;
-    match ds
-        .run_tx("delete_hpke_config", |tx| {
-            Box::pin(async move { tx.delete_hpke_keypair(&config_id).await })
-        })
-        .await
-    {
-        Ok(_) | Err(datastore::Error::MutationTargetNotFound) => Ok(Status::NoContent),
-        Err(err) => Err(err.into()),
-    }
+    Auditor::from_conn(conn)?
+        .run_tx(
+            &ds,
+            "delete_hpke_config",
+            "delete_hpke_keypair",
+            None,
+            Some(u8::from(config_id).to_string()),
+            move |tx| {
+                Box::pin(async move {
+                    let before = tx.get_hpke_keypair(&config_id).await?;
+                    match tx.delete_hpke_keypair(&config_id).await {
+                        Ok(_) | Err(datastore::Error::MutationTargetNotFound) => (),
+                        Err(err) => return Err(err),
+                    }
+                    Ok(
+                        Audited::new(())
+                            .with_before(before.as_ref().map(audit::hpke_keypair_state)),
+                    )
+                })
+            },
+        )
+        .await?;
+    Ok(Status::NoContent)
 }
 
 pub(super) async fn get_taskprov_peer_aggregators<C: Clock>(
@@ -430,7 +738,7 @@ pub(super) async fn get_taskprov_peer_aggregators<C: Clock>(
     State(ds): State<Arc<Datastore<C>>>,
 ) -> Result<Json<Vec<TaskprovPeerAggregatorResp>>, Error> {
     Ok(Json(
-        ds.run_tx("get_taskprov_peer_aggregators", |tx| {
+        ds.run_read_only_tx("get_taskprov_peer_aggregators", |tx| {
             Box::pin(async move { tx.get_taskprov_peer_aggregators().await })
         })
         .await?
@@ -447,7 +755,7 @@ pub(super) async fn get_taskprov_peer_aggregators<C: Clock>(
 /// token rotation cumbersome and fragile. Since token rotation is the main use case for updating
 /// an existing peer aggregator, we will resolve peer aggregator updates in that issue.
 pub(super) async fn post_taskprov_peer_aggregator<C: Clock>(
-    _: &mut Conn,
+    conn: &mut Conn,
     (State(ds), Json(req)): (
         State<Arc<Datastore<C>>>,
         Json<PostTaskprovPeerAggregatorReq>,
@@ -466,15 +774,26 @@ pub(super) async fn post_taskprov_peer_aggregator<C: Clock>(
     .context("Invalid request")
     .map_err(|e| Error::BadRequest(e.into()))?;
 
-    let inserted = ds
-        .run_tx("post_taskprov_peer_aggregator", |tx| {
-            let to_insert = to_insert.clone();
-            Box::pin(async move {
-                tx.put_taskprov_peer_aggregator(&to_insert).await?;
-                tx.get_taskprov_peer_aggregator(to_insert.endpoint(), to_insert.peer_role())
-                    .await
-            })
-        })
+    let target = peer_aggregator_target(to_insert.endpoint(), to_insert.peer_role());
+    let inserted = Auditor::from_conn(conn)?
+        .run_tx(
+            &ds,
+            "post_taskprov_peer_aggregator",
+            "create_taskprov_peer_aggregator",
+            None,
+            Some(target),
+            move |tx| {
+                let to_insert = to_insert.clone();
+                Box::pin(async move {
+                    tx.put_taskprov_peer_aggregator(&to_insert).await?;
+                    let inserted = tx
+                        .get_taskprov_peer_aggregator(to_insert.endpoint(), to_insert.peer_role())
+                        .await?;
+                    let after = inserted.as_ref().map(audit::peer_aggregator_state);
+                    Ok(Audited::new(inserted).with_after(after))
+                })
+            },
+        )
         .await?
         .map(TaskprovPeerAggregatorResp::from)
         .ok_or_else(|| Error::Internal("Newly inserted peer aggregator disappeared".into()))?;
@@ -483,23 +802,293 @@ pub(super) async fn post_taskprov_peer_aggregator<C: Clock>(
 }
 
 pub(super) async fn delete_taskprov_peer_aggregator<C: Clock>(
-    _: &mut Conn,
+    conn: &mut Conn,
     (State(ds), Json(req)): (
         State<Arc<Datastore<C>>>,
         Json<DeleteTaskprovPeerAggregatorReq>,
     ),
 ) -> Result<Status, Error> {
-    let res = ds
-        .run_tx("delete_taskprov_peer_aggregator", |tx| {
-            let req = req.clone();
+    let target = peer_aggregator_target(&req.endpoint, &req.peer_role);
+    Auditor::from_conn(conn)?
+        .run_tx(
+            &ds,
+            "delete_taskprov_peer_aggregator",
+            "delete_taskprov_peer_aggregator",
+            None,
+            Some(target),
+            move |tx| {
+                let req = req.clone();
+                Box::pin(async move {
+                    let before = tx
+                        .get_taskprov_peer_aggregator(&req.endpoint, &req.peer_role)
+                        .await?;
+                    match tx
+                        .delete_taskprov_peer_aggregator(&req.endpoint, &req.peer_role)
+                        .await
+                    {
+                        Ok(_) | Err(datastore::Error::MutationTargetNotFound) => (),
+                        Err(err) => return Err(err),
+                    }
+                    Ok(Audited::new(())
+                        .with_before(before.as_ref().map(audit::peer_aggregator_state)))
+                })
+            },
+        )
+        .await?;
+    Ok(Status::NoContent)
+}
+
+/// Describes a taskprov peer aggregator in the audit log.
+fn peer_aggregator_target(endpoint: &Url, peer_role: &Role) -> String {
+    format!("{peer_role} {endpoint}")
+}
+
+pub(super) async fn get_abandoned_jobs<C: Clock>(
+    conn: &mut Conn,
+    State(ds): State<Arc<Datastore<C>>>,
+) -> Result<Json<GetAbandonedJobsResp>, Error> {
+    const TASK_ID_KEY: &str = "task_id";
+    let task_id = querify(conn.querystring())
+        .into_iter()
+        .find(|&(k, _)| k == TASK_ID_KEY)
+        .map(|(_, v)| TaskId::from_str(v))
+        .transpose()
+        .context("Couldn't parse task_id")
+        .map_err(|err| Error::BadRequest(err.into()))?;
+
+    let (aggregation_jobs, collection_jobs) = ds
+        .run_tx("get_abandoned_jobs", |tx| {
+            Box::pin(async move {
+                Ok((
+                    tx.get_abandoned_aggregation_jobs(task_id.as_ref()).await?,
+                    tx.get_abandoned_collection_jobs(task_id.as_ref()).await?,
+                ))
+            })
+        })
+        .await?;
+
+    Ok(Json(GetAbandonedJobsResp {
+        aggregation_jobs: aggregation_jobs
+            .into_iter()
+            .map(AbandonedAggregationJobResp::from)
+            .collect(),
+        collection_jobs: collection_jobs
+            .into_iter()
+            .map(AbandonedCollectionJobResp::from)
+            .collect(),
+    }))
+}
+
+pub(super) async fn get_aggregation_job_history<C: Clock>(
+    conn: &mut Conn,
+    State(ds): State<Arc<Datastore<C>>>,
+) -> Result<Json<GetJobHistoryResp>, Error> {
+    let task_id = conn.task_id_param()?;
+    let aggregation_job_id = conn.aggregation_job_id_param()?;
+
+    let entries = ds
+        .run_tx("get_aggregation_job_history", |tx| {
             Box::pin(async move {
-                tx.delete_taskprov_peer_aggregator(&req.endpoint, &req.peer_role)
+                tx.get_aggregation_job_history(&task_id, &aggregation_job_id)
                     .await
             })
         })
-        .await;
-    match res {
-        Ok(_) | Err(datastore::Error::MutationTargetNotFound) => Ok(Status::NoContent),
-        Err(err) => Err(err.into()),
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    Ok(Json(GetJobHistoryResp {
+        entries: entries.into_iter().map(JobHistoryEntryResp::from).collect(),
+    }))
+}
+
+pub(super) async fn get_collection_job_history<C: Clock>(
+    conn: &mut Conn,
+    State(ds): State<Arc<Datastore<C>>>,
+) -> Result<Json<GetJobHistoryResp>, Error> {
+    let task_id = conn.task_id_param()?;
+    let collection_job_id = conn.collection_job_id_param()?;
+
+    let entries = ds
+        .run_tx("get_collection_job_history", |tx| {
+            Box::pin(async move {
+                tx.get_collection_job_history(&task_id, &collection_job_id)
+                    .await
+            })
+        })
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    Ok(Json(GetJobHistoryResp {
+        entries: entries.into_iter().map(JobHistoryEntryResp::from).collect(),
+    }))
+}
+
+pub(super) async fn post_aggregation_job_requeue<C: Clock>(
+    conn: &mut Conn,
+    State(ds): State<Arc<Datastore<C>>>,
+) -> Result<Status, Error> {
+    let task_id = conn.task_id_param()?;
+    let aggregation_job_id = conn.aggregation_job_id_param()?;
+
+    Auditor::from_conn(conn)?
+        .run_tx(
+            &ds,
+            "post_aggregation_job_requeue",
+            "requeue_aggregation_job",
+            Some(task_id),
+            Some(aggregation_job_id.to_string()),
+            move |tx| {
+                Box::pin(async move {
+                    tx.requeue_abandoned_aggregation_job(&task_id, &aggregation_job_id)
+                        .await?;
+                    Ok(Audited::new(()))
+                })
+            },
+        )
+        .await?;
+
+    Ok(Status::NoContent)
+}
+
+pub(super) async fn post_aggregation_job_fail<C: Clock>(
+    conn: &mut Conn,
+    State(ds): State<Arc<Datastore<C>>>,
+) -> Result<Json<FailAggregationJobResp>, Error> {
+    let task_id = conn.task_id_param()?;
+    let aggregation_job_id = conn.aggregation_job_id_param()?;
+
+    let reports_unaggregated = Auditor::from_conn(conn)?
+        .run_tx(
+            &ds,
+            "post_aggregation_job_fail",
+            "fail_aggregation_job",
+            Some(task_id),
+            Some(aggregation_job_id.to_string()),
+            move |tx| {
+                Box::pin(async move {
+                    let reports_unaggregated = tx
+                        .fail_aggregation_job(&task_id, &aggregation_job_id)
+                        .await?
+                        .len();
+                    Ok(Audited::new(reports_unaggregated).with_details(
+                        serde_json::json!({ "reports_unaggregated": reports_unaggregated }),
+                    ))
+                })
+            },
+        )
+        .await?;
+
+    Ok(Json(FailAggregationJobResp {
+        reports_unaggregated,
+    }))
+}
+
+pub(super) async fn post_collection_job_requeue<C: Clock>(
+    conn: &mut Conn,
+    State(ds): State<Arc<Datastore<C>>>,
+) -> Result<Status, Error> {
+    let task_id = conn.task_id_param()?;
+    let collection_job_id = conn.collection_job_id_param()?;
+
+    Auditor::from_conn(conn)?
+        .run_tx(
+            &ds,
+            "post_collection_job_requeue",
+            "requeue_collection_job",
+            Some(task_id),
+            Some(collection_job_id.to_string()),
+            move |tx| {
+                Box::pin(async move {
+                    tx.requeue_abandoned_collection_job(&task_id, &collection_job_id)
+                        .await?;
+                    Ok(Audited::new(()))
+                })
+            },
+        )
+        .await?;
+
+    Ok(Status::NoContent)
+}
+
+pub(super) async fn post_collection_job_fail<C: Clock>(
+    conn: &mut Conn,
+    State(ds): State<Arc<Datastore<C>>>,
+) -> Result<Status, Error> {
+    let task_id = conn.task_id_param()?;
+    let collection_job_id = conn.collection_job_id_param()?;
+
+    Auditor::from_conn(conn)?
+        .run_tx(
+            &ds,
+            "post_collection_job_fail",
+            "fail_collection_job",
+            Some(task_id),
+            Some(collection_job_id.to_string()),
+            move |tx| {
+                Box::pin(async move {
+                    tx.fail_collection_job(&task_id, &collection_job_id).await?;
+                    Ok(Audited::new(()))
+                })
+            },
+        )
+        .await?;
+
+    Ok(Status::NoContent)
+}
+
+pub(super) async fn get_audit_log<C: Clock>(
+    conn: &mut Conn,
+    State(ds): State<Arc<Datastore<C>>>,
+) -> Result<Json<GetAuditLogResp>, Error> {
+    /// The maximum number of entries returned if the request doesn't specify a limit.
+    const DEFAULT_LIMIT: u64 = 100;
+
+    let mut filter = AuditLogFilter::default().with_limit(Some(DEFAULT_LIMIT));
+    // Actors and routes may contain reserved characters, so the query string must be decoded.
+    for (key, value) in form_urlencoded::parse(conn.querystring().as_bytes()) {
+        filter = match key.as_ref() {
+            "task_id" => filter.with_task_id(Some(
+                TaskId::from_str(&value)
+                    .context("Couldn't parse task_id")
+                    .map_err(|err| Error::BadRequest(err.into()))?,
+            )),
+            "actor" => filter.with_actor(Some(value.into_owned())),
+            "action" => filter.with_action(Some(value.into_owned())),
+            "since" => filter.with_since(Some(Time::from_seconds_since_epoch(
+                value
+                    .parse()
+                    .context("Couldn't parse since")
+                    .map_err(|err| Error::BadRequest(err.into()))?,
+            ))),
+            "until" => filter.with_until(Some(Time::from_seconds_since_epoch(
+                value
+                    .parse()
+                    .context("Couldn't parse until")
+                    .map_err(|err| Error::BadRequest(err.into()))?,
+            ))),
+            "limit" => filter.with_limit(Some(
+                value
+                    .parse()
+                    .context("Couldn't parse limit")
+                    .map_err(|err| Error::BadRequest(err.into()))?,
+            )),
+            key => {
+                return Err(Error::BadRequest(
+                    format!("Unknown query parameter {key}").into(),
+                ));
+            }
+        };
     }
+
+    let filter = Arc::new(filter);
+    let entries = ds
+        .run_read_only_tx("get_audit_log", |tx| {
+            let filter = Arc::clone(&filter);
+            Box::pin(async move { tx.get_audit_log_entries(&filter).await })
+        })
+        .await?;
+
+    Ok(Json(GetAuditLogResp {
+        entries: entries.into_iter().map(AuditLogEntryResp::from).collect(),
+    }))
 }
@@ -1,23 +1,28 @@
 use crate::{
-    CONTENT_TYPE, Config, aggregator_api_handler,
+    CONTENT_TYPE, Config, ReportCountMode, aggregator_api_handler,
     models::{
-        DeleteTaskprovPeerAggregatorReq, GetTaskAggregationMetricsResp, GetTaskIdsResp,
-        GetTaskUploadMetricsResp, HpkeConfigResp, PatchHpkeConfigReq, PostTaskReq,
-        PostTaskprovPeerAggregatorReq, PutHpkeConfigReq, TaskResp, TaskprovPeerAggregatorResp,
+        BatchStatusResp, DeleteTaskprovPeerAggregatorReq, GetAuditLogResp, GetBatchStatusResp,
+        GetDpBudgetResp, GetTaskAggregationMetricsResp, GetTaskIdsResp, GetTaskUploadMetricsResp,
+        HpkeConfigResp, PatchHpkeConfigReq, PostTaskReq, PostTaskprovPeerAggregatorReq,
+        PutHpkeConfigReq, TaskResp, TaskprovPeerAggregatorResp,
     },
 };
 use assert_matches::assert_matches;
+use aws_lc_rs::hmac;
 use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use futures::future::try_join_all;
 use janus_aggregator_core::{
     SecretBytes,
     datastore::{
         Datastore,
-        models::{HpkeKeyState, TaskAggregationCounter, TaskUploadCounter},
+        models::{
+            ApiToken, ApiTokenScope, AuditLogFilter, AuditLogOutcome, HpkeKeyState,
+            LeaderStoredReport, TaskAggregationCounter, TaskUploadCounter,
+        },
         test_util::{EphemeralDatastore, ephemeral_datastore},
     },
     task::{
-        AggregationMode, AggregatorTask, AggregatorTaskParameters, BatchMode,
+        AggregationMode, AggregatorTask, AggregatorTaskParameters, BatchMode, DpBudget, TaskState,
         test_util::TaskBuilder,
     },
     taskprov::test_util::PeerAggregatorBuilder,
@@ -25,21 +30,23 @@ use janus_aggregator_core::{
 };
 use janus_core::{
     auth_tokens::{AuthenticationToken, AuthenticationTokenHash},
+    dp::DpStrategyConfig,
     hpke::HpkeKeypair,
     test_util::install_test_trace_subscriber,
-    time::MockClock,
+    time::{Clock, MockClock, TimeExt},
     vdaf::{VERIFY_KEY_LENGTH_PRIO3, VdafInstance, vdaf_dp_strategies},
 };
 use janus_messages::{
-    Duration, HpkeAeadId, HpkeConfig, HpkeConfigId, HpkeKdfId, HpkeKemId, HpkePublicKey, Role,
-    TaskId, Time,
+    AggregationJobId, BatchId, CollectionJobId, Duration, HpkeAeadId, HpkeConfig, HpkeConfigId,
+    HpkeKdfId, HpkeKemId, HpkePublicKey, Interval, Role, TaskId, Time, batch_mode::TimeInterval,
 };
+use prio::vdaf::dummy;
 use rand::{Rng, distr::StandardUniform, random, rng};
 use serde_test::{Token, assert_ser_tokens, assert_tokens};
 use std::{iter, sync::Arc};
 use trillium::{Handler, Status};
 use trillium_testing::{
-    Url, assert_body_contains, assert_response, assert_status,
+    TestConn, Url, assert_body_contains, assert_response, assert_status,
     prelude::{delete, get, patch, post, put},
 };
 
@@ -56,6 +63,9 @@ async fn setup_api_test() -> (impl Handler, EphemeralDatastore, Arc<Datastore<Mo
                 AuthenticationToken::new_bearer_token_from_string(AUTH_TOKEN).unwrap(),
             ]),
             public_dap_url: "https://dap.url".parse().unwrap(),
+            log_audit_events: false,
+            report_count_mode: ReportCountMode::Exact,
+            report_count_noise_key: hmac::Key::new(hmac::HMAC_SHA256, &random::<[u8; 32]>()),
         },
         &noop_meter(),
     );
@@ -78,7 +88,7 @@ async fn get_config() {
             r#""protocol":"DAP-09","dap_url":"https://dap.url/","role":"Either","vdafs":"#,
             r#"["Prio3Count","Prio3Sum","Prio3Histogram","Prio3SumVec"],"#,
             r#""batch_modes":["TimeInterval","LeaderSelected"],"#,
-            r#""features":["TokenHash","UploadMetrics","TimeBucketedLeaderSelected","PureDpDiscreteLaplace"],"#,
+            r#""features":["TokenHash","UploadMetrics","TimeBucketedLeaderSelected","PureDpDiscreteLaplace","ZCdpDiscreteGaussian"],"#,
             r#""software_name":"Janus","software_version":""#,
         )
     );
@@ -200,7 +210,9 @@ async fn post_task_bad_role() {
         peer_aggregator_endpoint: "http://aggregator.endpoint".try_into().unwrap(),
         batch_mode: BatchMode::TimeInterval,
         aggregation_mode: None,
-        vdaf: VdafInstance::Prio3Count,
+        vdaf: VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
         role: Role::Collector,
         vdaf_verify_key: URL_SAFE_NO_PAD.encode(&vdaf_verify_key),
         task_start: Some(Time::from_seconds_since_epoch(12300)),
@@ -210,6 +222,8 @@ async fn post_task_bad_role() {
         collector_hpke_config: HpkeKeypair::test().config().clone(),
         aggregator_auth_token: Some(aggregator_auth_token),
         collector_auth_token_hash: Some(AuthenticationTokenHash::from(&random())),
+        report_retention_window: None,
+        dp_budget: None,
     };
     assert_response!(
         post("/tasks")
@@ -240,7 +254,9 @@ async fn post_task_unauthorized() {
         peer_aggregator_endpoint: "http://aggregator.endpoint".try_into().unwrap(),
         batch_mode: BatchMode::TimeInterval,
         aggregation_mode: Some(AggregationMode::Synchronous),
-        vdaf: VdafInstance::Prio3Count,
+        vdaf: VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
         role: Role::Helper,
         vdaf_verify_key: URL_SAFE_NO_PAD.encode(&vdaf_verify_key),
         task_start: None,
@@ -250,6 +266,8 @@ async fn post_task_unauthorized() {
         collector_hpke_config: HpkeKeypair::test().config().clone(),
         aggregator_auth_token: Some(aggregator_auth_token),
         collector_auth_token_hash: Some(AuthenticationTokenHash::from(&random())),
+        report_retention_window: None,
+        dp_budget: None,
     };
     assert_response!(
         post("/tasks")
@@ -281,7 +299,9 @@ async fn post_task_helper_no_optional_fields() {
         peer_aggregator_endpoint: "http://aggregator.endpoint".try_into().unwrap(),
         batch_mode: BatchMode::TimeInterval,
         aggregation_mode: Some(AggregationMode::Synchronous),
-        vdaf: VdafInstance::Prio3Count,
+        vdaf: VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
         role: Role::Helper,
         vdaf_verify_key: URL_SAFE_NO_PAD.encode(&vdaf_verify_key),
         task_start: None,
@@ -291,6 +311,8 @@ async fn post_task_helper_no_optional_fields() {
         collector_hpke_config: HpkeKeypair::test().config().clone(),
         aggregator_auth_token: None,
         collector_auth_token_hash: None,
+        report_retention_window: None,
+        dp_budget: None,
     };
     let mut conn = post("/tasks")
         .with_request_body(serde_json::to_vec(&req).unwrap())
@@ -350,6 +372,78 @@ async fn post_task_helper_no_optional_fields() {
     assert_eq!(got_task_resp, TaskResp::try_from(&got_task).unwrap());
 }
 
+#[tokio::test]
+async fn post_task_with_dp_strategy() {
+    // Setup: create a datastore & handler.
+    let (handler, _ephemeral_datastore, ds) = setup_api_test().await;
+
+    for vdaf in [
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::PureDpDiscreteLaplace(
+                DpStrategyConfig::from_epsilon(1, 1).unwrap(),
+            ),
+        },
+        VdafInstance::Prio3Sum {
+            max_measurement: 255,
+            dp_strategy: vdaf_dp_strategies::Prio3Sum::ZCdpDiscreteGaussian(
+                DpStrategyConfig::from_epsilon(1, 2).unwrap(),
+            ),
+        },
+    ] {
+        let req = PostTaskReq {
+            peer_aggregator_endpoint: "http://aggregator.endpoint".try_into().unwrap(),
+            batch_mode: BatchMode::TimeInterval,
+            aggregation_mode: Some(AggregationMode::Synchronous),
+            vdaf,
+            role: Role::Helper,
+            vdaf_verify_key: URL_SAFE_NO_PAD.encode(
+                rng()
+                    .sample_iter(StandardUniform)
+                    .take(VERIFY_KEY_LENGTH_PRIO3)
+                    .collect::<Vec<u8>>(),
+            ),
+            task_start: None,
+            task_end: None,
+            min_batch_size: 223,
+            time_precision: Duration::from_seconds(60),
+            collector_hpke_config: HpkeKeypair::test().config().clone(),
+            aggregator_auth_token: None,
+            collector_auth_token_hash: None,
+            report_retention_window: None,
+            dp_budget: None,
+        };
+        let mut conn = post("/tasks")
+            .with_request_body(serde_json::to_vec(&req).unwrap())
+            .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+            .with_request_header("Accept", CONTENT_TYPE)
+            .with_request_header("Content-Type", CONTENT_TYPE)
+            .run_async(&handler)
+            .await;
+        assert_status!(conn, Status::Ok);
+        let got_task_resp: TaskResp = serde_json::from_slice(
+            &conn
+                .take_response_body()
+                .unwrap()
+                .into_bytes()
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(got_task_resp.vdaf, req.vdaf);
+
+        // Verify: the DP strategy is stored with the task.
+        let got_task = ds
+            .run_unnamed_tx(|tx| {
+                let task_id = got_task_resp.task_id;
+                Box::pin(async move { tx.get_aggregator_task(&task_id).await })
+            })
+            .await
+            .unwrap()
+            .expect("task was not created");
+        assert_eq!(&req.vdaf, got_task.vdaf());
+    }
+}
+
 #[tokio::test]
 async fn post_task_helper_with_aggregator_auth_token() {
     // Setup: create a datastore & handler.
@@ -368,7 +462,9 @@ async fn post_task_helper_with_aggregator_auth_token() {
         peer_aggregator_endpoint: "http://aggregator.endpoint".try_into().unwrap(),
         batch_mode: BatchMode::TimeInterval,
         aggregation_mode: Some(AggregationMode::Synchronous),
-        vdaf: VdafInstance::Prio3Count,
+        vdaf: VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
         role: Role::Helper,
         vdaf_verify_key: URL_SAFE_NO_PAD.encode(&vdaf_verify_key),
         task_start: None,
@@ -378,6 +474,8 @@ async fn post_task_helper_with_aggregator_auth_token() {
         collector_hpke_config: HpkeKeypair::test().config().clone(),
         aggregator_auth_token: Some(aggregator_auth_token),
         collector_auth_token_hash: None,
+        report_retention_window: None,
+        dp_budget: None,
     };
     assert_response!(
         post("/tasks")
@@ -410,7 +508,9 @@ async fn post_task_idempotence() {
         peer_aggregator_endpoint: "http://aggregator.endpoint".try_into().unwrap(),
         batch_mode: BatchMode::TimeInterval,
         aggregation_mode: Some(AggregationMode::Synchronous),
-        vdaf: VdafInstance::Prio3Count,
+        vdaf: VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
         role: Role::Leader,
         vdaf_verify_key: URL_SAFE_NO_PAD.encode(&vdaf_verify_key),
         task_start: Some(Time::from_seconds_since_epoch(12300)),
@@ -420,6 +520,8 @@ async fn post_task_idempotence() {
         collector_hpke_config: HpkeKeypair::test().config().clone(),
         aggregator_auth_token: Some(aggregator_auth_token.clone()),
         collector_auth_token_hash: Some(AuthenticationTokenHash::from(&random())),
+        report_retention_window: None,
+        dp_budget: None,
     };
 
     let post_task = || async {
@@ -490,7 +592,9 @@ async fn post_task_leader_all_optional_fields() {
         peer_aggregator_endpoint: "http://aggregator.endpoint".try_into().unwrap(),
         batch_mode: BatchMode::TimeInterval,
         aggregation_mode: Some(AggregationMode::Synchronous),
-        vdaf: VdafInstance::Prio3Count,
+        vdaf: VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
         role: Role::Leader,
         vdaf_verify_key: URL_SAFE_NO_PAD.encode(&vdaf_verify_key),
         task_start: None,
@@ -500,6 +604,8 @@ async fn post_task_leader_all_optional_fields() {
         collector_hpke_config: HpkeKeypair::test().config().clone(),
         aggregator_auth_token: Some(aggregator_auth_token.clone()),
         collector_auth_token_hash: Some(collector_auth_token_hash.clone()),
+        report_retention_window: Some(Duration::from_seconds(3600)),
+        dp_budget: Some(DpBudget::new(2.0).unwrap()),
     };
     let mut conn = post("/tasks")
         .with_request_body(serde_json::to_vec(&req).unwrap())
@@ -540,6 +646,11 @@ async fn post_task_leader_all_optional_fields() {
     assert_eq!(req.task_end.as_ref(), got_task.task_end());
     assert_eq!(req.min_batch_size, got_task.min_batch_size());
     assert_eq!(&req.time_precision, got_task.time_precision());
+    assert_eq!(
+        req.report_retention_window.as_ref(),
+        got_task.report_retention_window()
+    );
+    assert_eq!(req.dp_budget.as_ref(), got_task.dp_budget());
     assert_eq!(
         &req.collector_hpke_config,
         got_task.collector_hpke_config().unwrap()
@@ -575,7 +686,9 @@ async fn post_task_leader_no_aggregator_auth_token() {
         peer_aggregator_endpoint: "http://aggregator.endpoint".try_into().unwrap(),
         batch_mode: BatchMode::TimeInterval,
         aggregation_mode: Some(AggregationMode::Synchronous),
-        vdaf: VdafInstance::Prio3Count,
+        vdaf: VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
         role: Role::Leader,
         vdaf_verify_key: URL_SAFE_NO_PAD.encode(&vdaf_verify_key),
         task_start: Some(Time::from_seconds_since_epoch(12300)),
@@ -585,6 +698,8 @@ async fn post_task_leader_no_aggregator_auth_token() {
         collector_hpke_config: HpkeKeypair::test().config().clone(),
         aggregator_auth_token: None,
         collector_auth_token_hash: Some(AuthenticationTokenHash::from(&random())),
+        report_retention_window: None,
+        dp_budget: None,
     };
 
     assert_response!(
@@ -849,6 +964,48 @@ async fn patch_task(#[case] role: Role) {
         .unwrap();
     assert_eq!(task.unwrap().task_end(), expected_time.as_ref());
 
+    // Verify: patching the task's state returns the expected result.
+    for (body, want_state) in [
+        (r#"{"state": "paused"}"#, TaskState::Paused),
+        (r#"{"state": "active"}"#, TaskState::Active),
+        (r#"{"state": "archived"}"#, TaskState::Archived),
+    ] {
+        let mut conn = patch(format!("/tasks/{task_id}"))
+            .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+            .with_request_header("Accept", CONTENT_TYPE)
+            .with_request_body(body)
+            .run_async(&handler)
+            .await;
+        assert_status!(conn, Status::Ok);
+        let got_task_resp: TaskResp = serde_json::from_slice(
+            &conn
+                .take_response_body()
+                .unwrap()
+                .into_bytes()
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(got_task_resp.state, want_state);
+        let task = ds
+            .run_unnamed_tx(|tx| Box::pin(async move { tx.get_aggregator_task(&task_id).await }))
+            .await
+            .unwrap();
+        assert_eq!(task.unwrap().state(), &want_state);
+    }
+
+    // Verify: an archived task cannot be moved to another state.
+    assert_response!(
+        patch(format!("/tasks/{task_id}"))
+            .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+            .with_request_header("Accept", CONTENT_TYPE)
+            .with_request_body(r#"{"state": "active"}"#)
+            .run_async(&handler)
+            .await,
+        Status::BadRequest,
+        "task cannot transition from Archived to Active"
+    );
+
     // Verify: patching a nonexistent task returns NotFound.
     assert_response!(
         patch(format!("/tasks/{}", random::<TaskId>()))
@@ -873,6 +1030,149 @@ async fn patch_task(#[case] role: Role) {
     );
 }
 
+#[tokio::test]
+async fn abandoned_jobs() {
+    let (handler, _ephemeral_datastore, ds) = setup_api_test().await;
+
+    let task_id = ds
+        .run_unnamed_tx(|tx| {
+            Box::pin(async move {
+                let task = TaskBuilder::new(
+                    BatchMode::TimeInterval,
+                    AggregationMode::Synchronous,
+                    VdafInstance::Fake { rounds: 1 },
+                )
+                .build()
+                .leader_view()
+                .unwrap();
+
+                tx.put_aggregator_task(&task).await?;
+
+                Ok(*task.id())
+            })
+        })
+        .await
+        .unwrap();
+
+    // Verify: listing abandoned jobs succeeds, with or without a task filter.
+    assert_response!(
+        get("/abandoned_jobs")
+            .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+            .with_request_header("Accept", CONTENT_TYPE)
+            .run_async(&handler)
+            .await,
+        Status::Ok,
+        r#"{"aggregation_jobs":[],"collection_jobs":[]}"#,
+    );
+    assert_response!(
+        get(format!("/abandoned_jobs?task_id={task_id}"))
+            .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+            .with_request_header("Accept", CONTENT_TYPE)
+            .run_async(&handler)
+            .await,
+        Status::Ok,
+        r#"{"aggregation_jobs":[],"collection_jobs":[]}"#,
+    );
+    assert_status!(
+        get("/abandoned_jobs?task_id=not-a-task-id")
+            .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+            .with_request_header("Accept", CONTENT_TYPE)
+            .run_async(&handler)
+            .await,
+        Status::BadRequest
+    );
+
+    // Verify: acting on a nonexistent job returns NotFound, and the failure is audit-logged.
+    for path in [
+        format!(
+            "/tasks/{task_id}/aggregation_jobs/{}/requeue",
+            random::<AggregationJobId>()
+        ),
+        format!(
+            "/tasks/{task_id}/aggregation_jobs/{}/fail",
+            random::<AggregationJobId>()
+        ),
+        format!(
+            "/tasks/{task_id}/collection_jobs/{}/requeue",
+            random::<CollectionJobId>()
+        ),
+        format!(
+            "/tasks/{task_id}/collection_jobs/{}/fail",
+            random::<CollectionJobId>()
+        ),
+    ] {
+        assert_response!(
+            post(path)
+                .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+                .with_request_header("Accept", CONTENT_TYPE)
+                .run_async(&handler)
+                .await,
+            Status::NotFound,
+            "",
+        );
+    }
+    let entries = ds
+        .run_unnamed_tx(|tx| {
+            Box::pin(async move { tx.get_audit_log_entries(&AuditLogFilter::default()).await })
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        entries
+            .iter()
+            .map(|entry| (entry.action(), entry.outcome()))
+            .collect::<Vec<_>>(),
+        Vec::from([
+            ("fail_collection_job", &AuditLogOutcome::Failure),
+            ("requeue_collection_job", &AuditLogOutcome::Failure),
+            ("fail_aggregation_job", &AuditLogOutcome::Failure),
+            ("requeue_aggregation_job", &AuditLogOutcome::Failure),
+        ])
+    );
+
+    // Verify: the history of a nonexistent job is not found.
+    for path in [
+        format!(
+            "/tasks/{task_id}/aggregation_jobs/{}/history",
+            random::<AggregationJobId>()
+        ),
+        format!(
+            "/tasks/{task_id}/collection_jobs/{}/history",
+            random::<CollectionJobId>()
+        ),
+    ] {
+        assert_response!(
+            get(path)
+                .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+                .with_request_header("Accept", CONTENT_TYPE)
+                .run_async(&handler)
+                .await,
+            Status::NotFound,
+            "",
+        );
+    }
+
+    // Verify: a malformed job ID is rejected.
+    assert_status!(
+        post(format!("/tasks/{task_id}/aggregation_jobs/bogus/requeue"))
+            .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+            .with_request_header("Accept", CONTENT_TYPE)
+            .run_async(&handler)
+            .await,
+        Status::BadRequest
+    );
+
+    // Verify: unauthorized requests are denied appropriately.
+    assert_response!(
+        get("/abandoned_jobs")
+            .with_request_header("Accept", CONTENT_TYPE)
+            .run_async(&handler)
+            .await,
+        Status::Unauthorized,
+        "",
+    );
+}
+
 #[tokio::test]
 async fn get_task_upload_metrics() {
     let (handler, _ephemeral_datastore, ds) = setup_api_test().await;
@@ -913,7 +1213,7 @@ async fn get_task_upload_metrics() {
             tx.increment_task_upload_counter(
                 &task_id,
                 1,
-                &TaskUploadCounter::new_with_values(0, 0, 2, 4, 6, 100, 25, 22, 12),
+                &TaskUploadCounter::new_with_values(0, 0, 2, 4, 6, 100, 25, 22, 12, 0),
             )
             .await
         })
@@ -928,7 +1228,7 @@ async fn get_task_upload_metrics() {
             .await,
         Status::Ok,
         serde_json::to_string(&GetTaskUploadMetricsResp(
-            TaskUploadCounter::new_with_values(0, 0, 2, 4, 6, 100, 25, 22, 12)
+            TaskUploadCounter::new_with_values(0, 0, 2, 4, 6, 100, 25, 22, 12, 0)
         ))
         .unwrap(),
     );
@@ -1043,6 +1343,105 @@ async fn get_task_aggregation_metrics() {
     );
 }
 
+#[tokio::test]
+async fn get_task_dp_budget() {
+    let (handler, _ephemeral_datastore, ds) = setup_api_test().await;
+    let task_id = ds
+        .run_unnamed_tx(|tx| {
+            Box::pin(async move {
+                let task = TaskBuilder::new(
+                    BatchMode::TimeInterval,
+                    AggregationMode::Synchronous,
+                    VdafInstance::Fake { rounds: 1 },
+                )
+                .with_dp_budget(Some(DpBudget::new(2.0).unwrap()))
+                .build()
+                .leader_view()
+                .unwrap();
+                let task_id = *task.id();
+                tx.put_aggregator_task(&task).await.unwrap();
+
+                Ok(task_id)
+            })
+        })
+        .await
+        .unwrap();
+
+    // Verify: requesting the budget of a fresh task shows nothing has been spent.
+    assert_response!(
+        get(format!("/tasks/{task_id}/dp_budget"))
+            .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+            .with_request_header("Accept", CONTENT_TYPE)
+            .run_async(&handler)
+            .await,
+        Status::Ok,
+        serde_json::to_string(&GetDpBudgetResp {
+            dp_budget: Some(DpBudget::new(2.0).unwrap()),
+            privacy_loss_per_collection: 0.0,
+            spent: 0.0,
+            remaining: Some(2.0),
+            collection_count: 0,
+        })
+        .unwrap(),
+    );
+
+    // Verify: requesting the budget of a task reflects its collections.
+    ds.run_unnamed_tx(|tx| {
+        Box::pin(async move {
+            tx.spend_dp_budget::<0, TimeInterval, dummy::Vdaf>(
+                &task_id,
+                &Interval::new(
+                    Time::from_seconds_since_epoch(0),
+                    Duration::from_seconds(100),
+                )
+                .unwrap(),
+                &dummy::AggregationParam(0),
+                0.75,
+            )
+            .await
+        })
+    })
+    .await
+    .unwrap();
+    assert_response!(
+        get(format!("/tasks/{task_id}/dp_budget"))
+            .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+            .with_request_header("Accept", CONTENT_TYPE)
+            .run_async(&handler)
+            .await,
+        Status::Ok,
+        serde_json::to_string(&GetDpBudgetResp {
+            dp_budget: Some(DpBudget::new(2.0).unwrap()),
+            privacy_loss_per_collection: 0.0,
+            spent: 0.75,
+            remaining: Some(1.25),
+            collection_count: 1,
+        })
+        .unwrap(),
+    );
+
+    // Verify: requesting the budget of a nonexistent task returns NotFound.
+    assert_response!(
+        get(format!("/tasks/{}/dp_budget", &random::<TaskId>()))
+            .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+            .with_request_header("Accept", CONTENT_TYPE)
+            .run_async(&handler)
+            .await,
+        Status::NotFound,
+        "",
+    );
+
+    // Verify: unauthorized requests are denied appropriately.
+    assert_response!(
+        get(format!("/tasks/{task_id}/dp_budget"))
+            .with_request_header("Accept", CONTENT_TYPE)
+            .run_async(&handler)
+            .await,
+        Status::Unauthorized,
+        "",
+    );
+}
+
 #[tokio::test]
 async fn get_hpke_configs() {
     let (handler, _ephemeral_datastore, ds) = setup_api_test().await;
@@ -1684,6 +2083,612 @@ async fn delete_taskprov_peer_aggregator() {
     );
 }
 
+#[tokio::test]
+async fn get_audit_log() {
+    let (handler, _ephemeral_datastore, _ds) = setup_api_test().await;
+
+    let get_entries = {
+        let handler = &handler;
+        move |query: &str| {
+            let conn = get(format!("/audit_log{query}"))
+                .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+                .with_request_header("Accept", CONTENT_TYPE)
+                .run_async(handler);
+            async move {
+                let mut conn = conn.await;
+                assert_status!(conn, Status::Ok);
+                serde_json::from_slice::<GetAuditLogResp>(
+                    &conn
+                        .take_response_body()
+                        .unwrap()
+                        .into_bytes()
+                        .await
+                        .unwrap(),
+                )
+                .unwrap()
+                .entries
+            }
+        }
+    };
+
+    // Create a key, activate it, then try to activate a nonexistent key.
+    let mut conn = put("/hpke_configs")
+        .with_request_body("{}")
+        .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+        .with_request_header("Accept", CONTENT_TYPE)
+        .with_request_header("Content-Type", CONTENT_TYPE)
+        .run_async(&handler)
+        .await;
+    assert_status!(conn, Status::Created);
+    let key: HpkeConfigResp = serde_json::from_slice(
+        &conn
+            .take_response_body()
+            .unwrap()
+            .into_bytes()
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    let config_id = u8::from(*key.config.id());
+
+    let req = PatchHpkeConfigReq {
+        state: HpkeKeyState::Active,
+    };
+    assert_status!(
+        patch(format!("/hpke_configs/{config_id}"))
+            .with_request_body(serde_json::to_vec(&req).unwrap())
+            .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+            .with_request_header("Accept", CONTENT_TYPE)
+            .with_request_header("Content-Type", CONTENT_TYPE)
+            .run_async(&handler)
+            .await,
+        Status::Ok
+    );
+    let missing_config_id = config_id.wrapping_add(1);
+    assert_status!(
+        patch(format!("/hpke_configs/{missing_config_id}"))
+            .with_request_body(serde_json::to_vec(&req).unwrap())
+            .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+            .with_request_header("Accept", CONTENT_TYPE)
+            .with_request_header("Content-Type", CONTENT_TYPE)
+            .run_async(&handler)
+            .await,
+        Status::NotFound
+    );
+
+    // Verify: every attempt is recorded, most recent first, attributed to the caller's token.
+    let entries = get_entries("").await;
+    assert_eq!(entries.len(), 3);
+    let actor = &entries[0].actor;
+    assert!(actor.starts_with("token:"));
+    assert!(!actor.contains(AUTH_TOKEN));
+    assert!(entries.iter().all(|entry| &entry.actor == actor));
+
+    assert_eq!(entries[0].action, "set_hpke_keypair_state");
+    assert_eq!(entries[0].outcome, AuditLogOutcome::Failure);
+    assert_eq!(
+        entries[0].target.as_deref(),
+        Some(missing_config_id.to_string().as_str())
+    );
+    assert!(entries[0].details["error"].is_string());
+
+    assert_eq!(entries[1].action, "set_hpke_keypair_state");
+    assert_eq!(entries[1].outcome, AuditLogOutcome::Success);
+    assert_eq!(
+        entries[1].route.as_deref(),
+        Some("PATCH /hpke_configs/:config_id")
+    );
+    assert_eq!(
+        entries[1].before.as_ref().unwrap()["state"],
+        serde_json::json!("pending")
+    );
+    assert_eq!(
+        entries[1].after.as_ref().unwrap()["state"],
+        serde_json::json!("active")
+    );
+
+    assert_eq!(entries[2].action, "create_hpke_keypair");
+    assert_eq!(entries[2].route.as_deref(), Some("PUT /hpke_configs"));
+    assert_eq!(entries[2].before, None);
+    assert_eq!(
+        entries[2].after.as_ref().unwrap()["config"],
+        serde_json::to_value(&key.config).unwrap()
+    );
+
+    // Verify: entries can be filtered.
+    let entries = get_entries("?action=create_hpke_keypair").await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].action, "create_hpke_keypair");
+
+    let entries = get_entries(&format!("?actor={}", actor.replace(':', "%3A"))).await;
+    assert_eq!(entries.len(), 3);
+    assert!(get_entries("?actor=someone-else").await.is_empty());
+
+    assert_eq!(get_entries("?limit=2").await.len(), 2);
+    assert!(get_entries("?until=0").await.is_empty());
+    assert_eq!(get_entries("?since=0").await.len(), 3);
+
+    // Verify: malformed or unknown filters are rejected.
+    for query in ["?limit=many", "?task_id=not-a-task-id", "?bogus=1"] {
+        assert_status!(
+            get(format!("/audit_log{query}"))
+                .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+                .with_request_header("Accept", CONTENT_TYPE)
+                .run_async(&handler)
+                .await,
+            Status::BadRequest
+        );
+    }
+
+    // Verify: unauthorized requests are denied appropriately.
+    assert_response!(
+        get("/audit_log")
+            .with_request_header("Accept", CONTENT_TYPE)
+            .run_async(&handler)
+            .await,
+        Status::Unauthorized,
+        "",
+    );
+}
+
+#[tokio::test]
+async fn get_batch_status() {
+    let (handler, _ephemeral_datastore, ds) = setup_api_test().await;
+
+    // Setup: write a time-interval task with a few reports, a leader-selected task and a helper
+    // task.
+    let time_precision = Duration::from_seconds(1000);
+    let time_interval_task = TaskBuilder::new(
+        BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .with_time_precision(time_precision)
+    .with_min_batch_size(3)
+    .build()
+    .leader_view()
+    .unwrap();
+    let leader_selected_task = TaskBuilder::new(
+        BatchMode::LeaderSelected {
+            batch_time_window_size: None,
+        },
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .with_min_batch_size(3)
+    .build()
+    .leader_view()
+    .unwrap();
+    let helper_task = TaskBuilder::new(
+        BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .build()
+    .helper_view()
+    .unwrap();
+    let batch_interval_start = ds.clock().now();
+
+    ds.run_unnamed_tx(|tx| {
+        let time_interval_task = time_interval_task.clone();
+        let leader_selected_task = leader_selected_task.clone();
+        let helper_task = helper_task.clone();
+        Box::pin(async move {
+            tx.put_aggregator_task(&time_interval_task).await?;
+            tx.put_aggregator_task(&leader_selected_task).await?;
+            tx.put_aggregator_task(&helper_task).await?;
+            for offset in 0..4 {
+                tx.put_client_report(&LeaderStoredReport::new_dummy(
+                    *time_interval_task.id(),
+                    batch_interval_start
+                        .add(&Duration::from_seconds(offset))
+                        .unwrap(),
+                ))
+                .await?;
+            }
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+
+    let bucketed_handler = aggregator_api_handler(
+        Arc::clone(&ds),
+        Config {
+            auth_tokens: Vec::from([
+                AuthenticationToken::new_bearer_token_from_string(AUTH_TOKEN).unwrap(),
+            ]),
+            public_dap_url: "https://dap.url".parse().unwrap(),
+            log_audit_events: false,
+            report_count_mode: ReportCountMode::Bucketed { bucket_size: 3 },
+            report_count_noise_key: hmac::Key::new(hmac::HMAC_SHA256, &random::<[u8; 32]>()),
+        },
+        &noop_meter(),
+    );
+
+    async fn get_status(handler: &impl Handler, task_id: &TaskId, query: &str) -> TestConn {
+        get(format!("/tasks/{task_id}/batch_status{query}"))
+            .with_request_header("Authorization", format!("Bearer {AUTH_TOKEN}"))
+            .with_request_header("Accept", CONTENT_TYPE)
+            .run_async(handler)
+            .await
+    }
+    let interval_query = format!(
+        "?batch_interval_start={}&batch_interval_duration={}",
+        batch_interval_start.as_seconds_since_epoch(),
+        time_precision.as_seconds(),
+    );
+
+    // Verify: exact counts are reported for a time interval.
+    let mut conn = get_status(&handler, time_interval_task.id(), &interval_query).await;
+    assert_status!(conn, Status::Ok);
+    let resp: GetBatchStatusResp = serde_json::from_slice(
+        &conn
+            .take_response_body()
+            .unwrap()
+            .into_bytes()
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        resp,
+        GetBatchStatusResp {
+            min_batch_size: 3,
+            report_count_mode: ReportCountMode::Exact,
+            batches: Vec::from([BatchStatusResp {
+                batch_interval_start: Some(batch_interval_start),
+                batch_interval_duration: Some(time_precision),
+                batch_id: None,
+                report_count: 4,
+                min_batch_size_met: true,
+            }]),
+        }
+    );
+
+    // Verify: counts are bucketed if so configured, and whether the minimum batch size is met is
+    // determined from the bucketed count.
+    let mut conn = get_status(&bucketed_handler, time_interval_task.id(), &interval_query).await;
+    assert_status!(conn, Status::Ok);
+    let resp: GetBatchStatusResp = serde_json::from_slice(
+        &conn
+            .take_response_body()
+            .unwrap()
+            .into_bytes()
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        resp.report_count_mode,
+        ReportCountMode::Bucketed { bucket_size: 3 }
+    );
+    assert_eq!(resp.batches[0].report_count, 3);
+    assert!(resp.batches[0].min_batch_size_met);
+
+    // Verify: leader-selected batches can be queried by ID, or listed if still being filled.
+    let batch_id = random::<BatchId>();
+    let mut conn = get_status(
+        &handler,
+        leader_selected_task.id(),
+        &format!("?batch_id={batch_id}"),
+    )
+    .await;
+    assert_status!(conn, Status::Ok);
+    let resp: GetBatchStatusResp = serde_json::from_slice(
+        &conn
+            .take_response_body()
+            .unwrap()
+            .into_bytes()
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        resp.batches,
+        Vec::from([BatchStatusResp {
+            batch_interval_start: None,
+            batch_interval_duration: None,
+            batch_id: Some(batch_id.to_string()),
+            report_count: 0,
+            min_batch_size_met: false,
+        }])
+    );
+
+    let mut conn = get_status(&handler, leader_selected_task.id(), "").await;
+    assert_status!(conn, Status::Ok);
+    let resp: GetBatchStatusResp = serde_json::from_slice(
+        &conn
+            .take_response_body()
+            .unwrap()
+            .into_bytes()
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    assert!(resp.batches.is_empty());
+
+    // Verify: requests which don't match the task's batch mode are rejected.
+    for (task_id, query) in [
+        (time_interval_task.id(), ""),
+        (time_interval_task.id(), "?batch_interval_start=1000000000"),
+        (
+            time_interval_task.id(),
+            "?batch_interval_start=1000000001&batch_interval_duration=1000",
+        ),
+        (time_interval_task.id(), "?bogus=1"),
+        (leader_selected_task.id(), interval_query.as_str()),
+        (leader_selected_task.id(), "?batch_id=not-a-batch-id"),
+        (leader_selected_task.id(), "?time_bucket_start=1000000000"),
+        (helper_task.id(), interval_query.as_str()),
+    ] {
+        assert_status!(
+            get_status(&handler, task_id, query).await,
+            Status::BadRequest
+        );
+    }
+    assert_status!(
+        get_status(&handler, &random(), &interval_query).await,
+        Status::NotFound
+    );
+}
+
+#[test]
+fn report_count_mode_disclose() {
+    let noise_key = hmac::Key::new(hmac::HMAC_SHA256, &random::<[u8; 16]>());
+    let batch = random::<[u8; 32]>();
+
+    assert_eq!(ReportCountMode::Exact.disclose(&noise_key, &batch, 17), 17);
+    assert_eq!(
+        ReportCountMode::Bucketed { bucket_size: 5 }.disclose(&noise_key, &batch, 17),
+        15
+    );
+    assert_eq!(
+        ReportCountMode::Bucketed { bucket_size: 5 }.disclose(&noise_key, &batch, 4),
+        0
+    );
+
+    // Noised counts are never negative, and stay close to the exact count for large epsilon.
+    let mode = ReportCountMode::Noised { epsilon: 1.0 };
+    assert!(
+        iter::repeat_with(|| mode.disclose(&noise_key, &random::<[u8; 32]>(), 0))
+            .take(100)
+            .all(|count| count < 100)
+    );
+    let mode = ReportCountMode::Noised { epsilon: 100.0 };
+    assert!(
+        iter::repeat_with(|| mode.disclose(&noise_key, &random::<[u8; 32]>(), 50))
+            .take(100)
+            .all(|count| count == 50)
+    );
+
+    // Repeated queries of an unchanged batch return the same noised count, so the noise can't be
+    // averaged away, but the noise differs between batches.
+    let mode = ReportCountMode::Noised { epsilon: 0.1 };
+    let count = mode.disclose(&noise_key, &batch, 1000);
+    assert!(
+        iter::repeat_with(|| mode.disclose(&noise_key, &batch, 1000))
+            .take(10)
+            .all(|got_count| got_count == count)
+    );
+    assert!(
+        iter::repeat_with(|| mode.disclose(&noise_key, &random::<[u8; 32]>(), 1000))
+            .take(100)
+            .any(|got_count| got_count != count)
+    );
+}
+
+#[tokio::test]
+async fn scoped_api_tokens() {
+    let (handler, _ephemeral_datastore, ds) = setup_api_test().await;
+
+    // Setup: write a couple of tasks, and tokens with various scopes.
+    let task = TaskBuilder::new(
+        BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .build()
+    .leader_view()
+    .unwrap();
+    let other_task = TaskBuilder::new(
+        BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .build()
+    .leader_view()
+    .unwrap();
+
+    let reader_token = AuthenticationToken::Bearer(random());
+    let task_admin_token = AuthenticationToken::Bearer(random());
+    let key_admin_token = AuthenticationToken::Bearer(random());
+    let restricted_admin_token = AuthenticationToken::Bearer(random());
+    let revoked_token = AuthenticationToken::Bearer(random());
+    let now = ds.clock().now();
+    let api_tokens = Vec::from([
+        ApiToken::new(
+            "reader".to_string(),
+            AuthenticationTokenHash::from(&reader_token),
+            Vec::from([ApiTokenScope::ReadOnly]),
+            None,
+            now,
+        ),
+        ApiToken::new(
+            "task-admin".to_string(),
+            AuthenticationTokenHash::from(&task_admin_token),
+            Vec::from([ApiTokenScope::TaskAdmin]),
+            Some(Vec::from([*task.id()])),
+            now,
+        ),
+        ApiToken::new(
+            "key-admin".to_string(),
+            AuthenticationTokenHash::from(&key_admin_token),
+            Vec::from([ApiTokenScope::KeyAdmin]),
+            None,
+            now,
+        ),
+        ApiToken::new(
+            "restricted-admin".to_string(),
+            AuthenticationTokenHash::from(&restricted_admin_token),
+            Vec::from([ApiTokenScope::KeyAdmin, ApiTokenScope::TaskprovAdmin]),
+            Some(Vec::from([*task.id()])),
+            now,
+        ),
+        ApiToken::new(
+            "revoked".to_string(),
+            AuthenticationTokenHash::from(&revoked_token),
+            Vec::from([ApiTokenScope::TaskAdmin]),
+            None,
+            now,
+        )
+        .with_revoked_at(Some(now)),
+    ]);
+    ds.run_unnamed_tx(|tx| {
+        let (task, other_task) = (task.clone(), other_task.clone());
+        let api_tokens = api_tokens.clone();
+        Box::pin(async move {
+            tx.put_aggregator_task(&task).await?;
+            tx.put_aggregator_task(&other_task).await?;
+            for api_token in &api_tokens {
+                tx.put_api_token(api_token).await?;
+            }
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+
+    let handler = &handler;
+    let status = move |method: &'static str, path: String, token: &AuthenticationToken| {
+        let conn = match method {
+            "GET" => get(path),
+            "PUT" => put(path).with_request_body("{}"),
+            "POST" => post(path).with_request_body("{}"),
+            "DELETE" => delete(path),
+            _ => unreachable!(),
+        }
+        .with_request_header("Authorization", format!("Bearer {}", token.as_str()))
+        .with_request_header("Accept", CONTENT_TYPE)
+        .with_request_header("Content-Type", CONTENT_TYPE)
+        .run_async(handler);
+        async move { conn.await.status().unwrap() }
+    };
+    let task_path = format!("/tasks/{}", task.id());
+    let other_task_path = format!("/tasks/{}", other_task.id());
+
+    // Verify: a read-only token may read, but not modify anything.
+    assert_eq!(
+        status("GET", "/task_ids".into(), &reader_token).await,
+        Status::Ok
+    );
+    assert_eq!(
+        status("GET", other_task_path.clone(), &reader_token).await,
+        Status::Ok
+    );
+    assert_eq!(
+        status("GET", "/audit_log".into(), &reader_token).await,
+        Status::Ok
+    );
+    assert_eq!(
+        status("DELETE", task_path.clone(), &reader_token).await,
+        Status::Forbidden
+    );
+    assert_eq!(
+        status("PUT", "/hpke_configs".into(), &reader_token).await,
+        Status::Forbidden
+    );
+
+    // Verify: a token restricted to a task may only use routes naming that task.
+    assert_eq!(
+        status("GET", task_path.clone(), &task_admin_token).await,
+        Status::Ok
+    );
+    assert_eq!(
+        status("GET", other_task_path.clone(), &task_admin_token).await,
+        Status::Forbidden
+    );
+    assert_eq!(
+        status("DELETE", other_task_path.clone(), &task_admin_token).await,
+        Status::Forbidden
+    );
+    assert_eq!(
+        status("GET", "/task_ids".into(), &task_admin_token).await,
+        Status::Forbidden
+    );
+    assert_eq!(
+        status("POST", "/tasks".into(), &task_admin_token).await,
+        Status::Forbidden
+    );
+    assert_eq!(
+        status("PUT", "/hpke_configs".into(), &task_admin_token).await,
+        Status::Forbidden
+    );
+    assert_eq!(
+        status("DELETE", task_path.clone(), &task_admin_token).await,
+        Status::NoContent
+    );
+
+    // Verify: a token restricted to a task may not use global routes, whatever its scopes.
+    assert_eq!(
+        status("GET", other_task_path.clone(), &restricted_admin_token).await,
+        Status::Forbidden
+    );
+    for (method, path) in [
+        ("GET", "/"),
+        ("GET", "/hpke_configs"),
+        ("PUT", "/hpke_configs"),
+        ("DELETE", "/hpke_configs/1"),
+        ("GET", "/taskprov/peer_aggregators"),
+        ("POST", "/taskprov/peer_aggregators"),
+        ("DELETE", "/taskprov/peer_aggregators"),
+    ] {
+        assert_eq!(
+            status(method, path.into(), &restricted_admin_token).await,
+            Status::Forbidden,
+            "{method} {path}"
+        );
+    }
+
+    // Verify: a key admin token may manage keys, but not tasks.
+    assert_eq!(
+        status("PUT", "/hpke_configs".into(), &key_admin_token).await,
+        Status::Created
+    );
+    assert_eq!(
+        status("DELETE", other_task_path.clone(), &key_admin_token).await,
+        Status::Forbidden
+    );
+
+    // Verify: revoked and unknown tokens are rejected.
+    assert_eq!(
+        status("GET", "/task_ids".into(), &revoked_token).await,
+        Status::Unauthorized
+    );
+    assert_eq!(
+        status(
+            "GET",
+            "/task_ids".into(),
+            &AuthenticationToken::Bearer(random())
+        )
+        .await,
+        Status::Unauthorized
+    );
+
+    // Verify: changes are audit-logged under the name of the token used.
+    let entries = ds
+        .run_unnamed_tx(|tx| {
+            Box::pin(async move { tx.get_audit_log_entries(&AuditLogFilter::default()).await })
+        })
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].actor(), "api_token:key-admin");
+    assert_eq!(entries[0].action(), "create_hpke_keypair");
+    assert_eq!(entries[1].actor(), "api_token:task-admin");
+    assert_eq!(entries[1].action(), "delete_task");
+}
+
 #[test]
 fn get_task_ids_resp_serialization() {
     assert_ser_tokens(
@@ -1756,6 +2761,8 @@ fn post_task_req_serialization() {
             ),
             aggregator_auth_token: None,
             collector_auth_token_hash: None,
+            report_retention_window: None,
+            dp_budget: None,
         },
         &[
             Token::Struct {
@@ -1885,11 +2892,13 @@ fn post_task_req_serialization() {
             collector_auth_token_hash: Some(AuthenticationTokenHash::from(
                 &AuthenticationToken::new_dap_auth_token_from_string("ZW5jb2RlZA").unwrap(),
             )),
+            report_retention_window: Some(Duration::from_seconds(86400)),
+            dp_budget: Some(DpBudget::new(1.5).unwrap()),
         },
         &[
             Token::Struct {
                 name: "PostTaskReq",
-                len: 13,
+                len: 15,
             },
             Token::Str("peer_aggregator_endpoint"),
             Token::Str("https://example.com/"),
@@ -2001,6 +3010,13 @@ fn post_task_req_serialization() {
             Token::Str("hash"),
             Token::Str("hT_ixzv_X1CmJmHGT7jYSEBbdB-CN9H8WxAvjgv4rms"),
             Token::StructEnd,
+            Token::Str("report_retention_window"),
+            Token::Some,
+            Token::NewtypeStruct { name: "Duration" },
+            Token::U64(86400),
+            Token::Str("dp_budget"),
+            Token::Some,
+            Token::F64(1.5),
             Token::StructEnd,
         ],
     );
@@ -2051,7 +3067,7 @@ fn task_resp_serialization() {
         &[
             Token::Struct {
                 name: "TaskResp",
-                len: 14,
+                len: 17,
             },
             Token::Str("task_id"),
             Token::Str("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"),
@@ -2100,6 +3116,10 @@ fn task_resp_serialization() {
             Token::None,
             Token::Str("report_expiry_age"),
             Token::None,
+            Token::Str("report_retention_window"),
+            Token::None,
+            Token::Str("dp_budget"),
+            Token::None,
             Token::Str("min_batch_size"),
             Token::U64(100),
             Token::Str("time_precision"),
@@ -2138,6 +3158,11 @@ fn task_resp_serialization() {
             Token::Str("public_key"),
             Token::Str("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"),
             Token::StructEnd,
+            Token::Str("state"),
+            Token::UnitVariant {
+                name: "TaskState",
+                variant: "active",
+            },
             Token::StructEnd,
         ],
     );
@@ -2147,7 +3172,7 @@ fn task_resp_serialization() {
 fn get_task_upload_metrics_serialization() {
     assert_ser_tokens(
         &GetTaskUploadMetricsResp(TaskUploadCounter::new_with_values(
-            0, 1, 2, 3, 4, 5, 6, 7, 8,
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 9,
         )),
         &[
             Token::NewtypeStruct {
@@ -2155,7 +3180,7 @@ fn get_task_upload_metrics_serialization() {
             },
             Token::Struct {
                 name: "TaskUploadCounter",
-                len: 9,
+                len: 10,
             },
             Token::Str("interval_collected"),
             Token::U64(0),
@@ -2175,6 +3200,8 @@ fn get_task_upload_metrics_serialization() {
             Token::U64(7),
             Token::Str("task_ended"),
             Token::U64(8),
+            Token::Str("task_not_accepting_reports"),
+            Token::U64(9),
             Token::StructEnd,
         ],
     )
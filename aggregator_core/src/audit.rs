@@ -0,0 +1,162 @@
+//! Helpers for recording administrative actions in the audit log.
+//!
+//! Audit log entries capture the state of the affected resource before & after each action. Those
+//! states are produced by the functions in this module, which omit or redact any secrets, since
+//! the audit log is not encrypted and may also be emitted as log events.
+
+use crate::{
+    datastore::models::{ApiToken, AuditLogEntry, HpkeKeypair},
+    task::AggregatorTask,
+    taskprov::PeerAggregator,
+};
+use serde_json::{Value, json};
+use tracing::info;
+
+/// The target of the log events emitted by [`emit_audit_log_event`], allowing them to be routed
+/// or filtered separately from other log events.
+pub const AUDIT_LOG_EVENT_TARGET: &str = "janus_audit";
+
+/// Placeholder for a secret value that has been redacted.
+const REDACTED: &str = "[redacted]";
+
+/// Returns the state of a task, as recorded in the audit log. The VDAF verify key and any
+/// aggregator auth token are redacted; token hashes are kept so that rotations can be traced.
+pub fn task_state(task: &AggregatorTask) -> Value {
+    json!({
+        "task_id": task.id(),
+        "peer_aggregator_endpoint": task.peer_aggregator_endpoint(),
+        "batch_mode": task.batch_mode(),
+        "aggregation_mode": task.aggregation_mode(),
+        "vdaf": task.vdaf(),
+        "role": task.role(),
+        "vdaf_verify_key": REDACTED,
+        "task_start": task.task_start(),
+        "task_end": task.task_end(),
+        "report_expiry_age": task.report_expiry_age(),
+        "report_retention_window": task.report_retention_window(),
+        "min_batch_size": task.min_batch_size(),
+        "time_precision": task.time_precision(),
+        "tolerable_clock_skew": task.tolerable_clock_skew(),
+        "collector_hpke_config": task.collector_hpke_config(),
+        "aggregator_auth_token": task.aggregator_auth_token().map(|_| REDACTED),
+        "aggregator_auth_token_hash": task.aggregator_auth_token_hash(),
+        "collector_auth_token_hash": task.collector_auth_token_hash(),
+        "dp_budget": task.dp_budget(),
+        "state": task.state(),
+    })
+}
+
+/// Returns the state of an HPKE keypair, as recorded in the audit log. Only the public
+/// configuration is included.
+pub fn hpke_keypair_state(keypair: &HpkeKeypair) -> Value {
+    json!({
+        "config": keypair.hpke_keypair().config(),
+        "state": keypair.state(),
+    })
+}
+
+/// Returns the state of a taskprov peer aggregator, as recorded in the audit log. The verify key
+/// init and auth tokens are redacted, but the number of each kind of token is kept.
+pub fn peer_aggregator_state(peer_aggregator: &PeerAggregator) -> Value {
+    json!({
+        "endpoint": peer_aggregator.endpoint(),
+        "peer_role": peer_aggregator.peer_role(),
+        "aggregation_mode": peer_aggregator.aggregation_mode(),
+        "verify_key_init": REDACTED,
+        "collector_hpke_config": peer_aggregator.collector_hpke_config(),
+        "report_expiry_age": peer_aggregator.report_expiry_age(),
+        "aggregator_auth_tokens": peer_aggregator.aggregator_auth_tokens().len(),
+        "collector_auth_tokens": peer_aggregator.collector_auth_tokens().len(),
+    })
+}
+
+/// Returns the state of an aggregator API token, as recorded in the audit log. The token hash is
+/// omitted.
+pub fn api_token_state(api_token: &ApiToken) -> Value {
+    json!({
+        "name": api_token.name(),
+        "scopes": api_token.scopes(),
+        "task_ids": api_token.task_ids(),
+        "created_at": api_token.created_at(),
+        "revoked_at": api_token.revoked_at(),
+    })
+}
+
+/// Emits a structured log event describing an audit log entry. This should only be called once
+/// the transaction which wrote the entry has committed, so that retried transactions don't emit
+/// duplicate events.
+pub fn emit_audit_log_event(entry: &AuditLogEntry) {
+    info!(
+        target: AUDIT_LOG_EVENT_TARGET,
+        actor = entry.actor(),
+        action = entry.action(),
+        task_id = entry.task_id().map(ToString::to_string),
+        resource = entry.target(),
+        route = entry.route(),
+        outcome = ?entry.outcome(),
+        before = entry.before().map(ToString::to_string),
+        after = entry.after().map(ToString::to_string),
+        details = %entry.details(),
+        "Audit log entry"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        audit::{peer_aggregator_state, task_state},
+        task::{AggregationMode, BatchMode, DpBudget, TaskState, test_util::TaskBuilder},
+        taskprov::test_util::PeerAggregatorBuilder,
+    };
+    use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+    use janus_core::vdaf::{VdafInstance, vdaf_dp_strategies};
+    use serde_json::json;
+    use std::str;
+
+    #[test]
+    fn task_state_redacts_secrets() {
+        let task = TaskBuilder::new(
+            BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
+        )
+        .with_dp_budget(Some(DpBudget::new(2.0).unwrap()))
+        .with_state(TaskState::Paused)
+        .build();
+
+        for view in [task.leader_view().unwrap(), task.helper_view().unwrap()] {
+            let state = task_state(&view);
+            assert_eq!(state["task_id"], json!(view.id()));
+            assert_eq!(state["vdaf_verify_key"], json!("[redacted]"));
+            assert_eq!(state["dp_budget"], json!(2.0));
+            assert_eq!(state["state"], json!("paused"));
+
+            let state = state.to_string();
+            assert!(!state.contains(&URL_SAFE_NO_PAD.encode(view.opaque_vdaf_verify_key())));
+            if let Some(token) = view.aggregator_auth_token() {
+                assert!(!state.contains(str::from_utf8(token.as_ref()).unwrap()));
+            }
+        }
+    }
+
+    #[test]
+    fn peer_aggregator_state_redacts_secrets() {
+        let peer_aggregator = PeerAggregatorBuilder::new().build().unwrap();
+
+        let state = peer_aggregator_state(&peer_aggregator);
+        assert_eq!(state["verify_key_init"], json!("[redacted]"));
+        assert_eq!(state["aggregator_auth_tokens"], json!(1));
+        assert_eq!(state["collector_auth_tokens"], json!(1));
+
+        let state = state.to_string();
+        for token in peer_aggregator
+            .aggregator_auth_tokens()
+            .iter()
+            .chain(peer_aggregator.collector_auth_tokens())
+        {
+            assert!(!state.contains(str::from_utf8(token.as_ref()).unwrap()));
+        }
+    }
+}
@@ -1,35 +1,40 @@
 //! Janus datastore (durable storage) implementation.
 
 use self::models::{
-    AcquiredAggregationJob, AcquiredCollectionJob, AggregateShareJob, AggregationJob,
-    AggregatorRole, AuthenticationTokenType, BatchAggregation, BatchAggregationState,
-    BatchAggregationStateCode, CollectionJob, CollectionJobState, CollectionJobStateCode,
-    HpkeKeyState, HpkeKeypair, LeaderStoredReport, Lease, LeaseToken, OutstandingBatch,
-    ReportAggregation, ReportAggregationMetadata, ReportAggregationMetadataState,
-    ReportAggregationState, ReportAggregationStateCode, SqlInterval, TaskAggregationCounter,
-    TaskUploadCounter,
+    AbandonedAggregationJob, AbandonedCollectionJob, AcquiredAggregationJob, AcquiredCollectionJob,
+    AggregateShareJob, AggregationJob, AggregationJobState, AggregatorRole, ApiToken,
+    AuditLogEntry, AuditLogFilter, AuthenticationTokenType, BatchAggregation,
+    BatchAggregationState, BatchAggregationStateCode, CollectionJob, CollectionJobState,
+    CollectionJobStateCode, DpBudgetUsage, HpkeKeyState, HpkeKeypair, JobHistoryEntry,
+    JobStepRecord, LeaderStoredReport, Lease, LeaseToken, OutstandingBatch, ReportAggregation,
+    ReportAggregationMetadata, ReportAggregationMetadataState, ReportAggregationState,
+    ReportAggregationStateCode, SqlInterval, TaskAggregationCounter, TaskUploadCounter,
+};
+use self::storage::{AggregationJobRecord, BatchAggregationRecord, CollectionJobRecord};
+use self::task_archive::{
+    ArchivedAggregateShareJob, ArchivedBatchAggregation, ArchivedClientReport,
+    ArchivedClientReportPayload, ArchivedCollectionJob, ArchivedDpBudgetLedgerEntry,
+    ArchivedHpkeKeypair, ArchivedOutstandingBatch, TaskArchive,
 };
-#[cfg(feature = "test-util")]
-use crate::VdafHasAggregationParameter;
 use crate::{
-    AsyncAggregator, SecretBytes, TIME_HISTOGRAM_BOUNDARIES,
+    AsyncAggregator, SecretBytes, TIME_HISTOGRAM_BOUNDARIES, VdafHasAggregationParameter,
     batch_mode::{AccumulableBatchMode, CollectableBatchMode},
-    task::{self, AggregationMode, AggregatorTask, AggregatorTaskParameters},
+    task::{self, AggregationMode, AggregatorTask, AggregatorTaskParameters, DpBudget, TaskState},
     taskprov::PeerAggregator,
 };
 use aws_lc_rs::aead::{self, AES_128_GCM, LessSafeKey};
 use chrono::NaiveDateTime;
 use futures::future::try_join_all;
 use janus_core::{
-    auth_tokens::AuthenticationToken,
+    auth_tokens::{AuthenticationToken, AuthenticationTokenHash},
     hpke::{self, HpkePrivateKey},
     time::{Clock, DurationExt, IntervalExt, TimeExt},
     vdaf::VdafInstance,
 };
 use janus_messages::{
     AggregationJobId, BatchId, CollectionJobId, Duration, Extension, HpkeCiphertext, HpkeConfig,
-    HpkeConfigId, Interval, PrepareContinue, PrepareInit, PrepareResp, Query, ReportId,
-    ReportIdChecksum, ReportMetadata, Role, TaskId, Time,
+    HpkeConfigId, Interval, PrepareContinue, PrepareInit, PrepareResp, Query, ReportError,
+    ReportId, ReportIdChecksum, ReportMetadata, Role, TaskId, Time,
     batch_mode::{BatchMode, LeaderSelected, TimeInterval},
 };
 use models::UnaggregatedReport;
@@ -60,10 +65,12 @@ use std::{
 };
 use tokio::{sync::Barrier, try_join};
 use tokio_postgres::{IsolationLevel, Row, Statement, ToStatement, error::SqlState, row::RowIndex};
-use tracing::{Level, error};
+use tracing::{Level, error, warn};
 use url::Url;
 
 pub mod models;
+pub mod storage;
+pub mod task_archive;
 #[cfg(feature = "test-util")]
 #[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
 pub mod test_util;
@@ -77,7 +84,8 @@ mod tests;
 /// [1]: https://docs.rs/rstest_reuse/latest/rstest_reuse/
 macro_rules! supported_schema_versions {
     ( $i_latest:literal $(,)? $( $i:literal ),* ) => {
-        const SUPPORTED_SCHEMA_VERSIONS: &[i64] = &[$i_latest, $($i),*];
+        /// The database schema versions supported by this version of Janus.
+        pub const SUPPORTED_SCHEMA_VERSIONS: &[i64] = &[$i_latest, $($i),*];
 
         #[cfg(test)]
         #[rstest_reuse::template]
@@ -103,7 +111,18 @@ macro_rules! supported_schema_versions {
 // version is seen, [`Datastore::new`] fails.
 //
 // Note that the latest supported version must be first in the list.
-supported_schema_versions!(1);
+supported_schema_versions!(10);
+
+/// The tables which may be partitioned by task & client timestamp. See
+/// [`Transaction::create_report_partitions`].
+const PARTITIONED_TABLES: [&str; 2] = ["client_reports", "report_aggregations"];
+
+/// The format of timestamp literals in statements creating report partitions.
+const SQL_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// The maximum number of history entries retained for each aggregation or collection job. Older
+/// entries are discarded as new ones are recorded.
+pub const MAX_JOB_HISTORY_ENTRIES: usize = 20;
 
 /// Datastore represents a datastore for Janus, with support for transactional reads and writes.
 /// In practice, Datastore instances are currently backed by a PostgreSQL database.
@@ -117,7 +136,75 @@ pub struct Datastore<C: Clock> {
     rollback_error_counter: Counter<u64>,
     transaction_duration_histogram: Histogram<f64>,
     transaction_pool_wait_histogram: Histogram<f64>,
+    replica_fallback_counter: Counter<u64>,
     max_transaction_retries: u64,
+    replica: Option<Replica>,
+}
+
+/// A read replica of the database, to which read-only transactions are routed while it is healthy.
+struct Replica {
+    pool: deadpool_postgres::Pool,
+    max_staleness: StdDuration,
+    /// The result of the last health check of the replica, which is reused until it expires.
+    health: Mutex<ReplicaHealth>,
+}
+
+/// The cached result of a health check of a read replica.
+#[derive(Clone, Copy)]
+enum ReplicaHealth {
+    /// The replica hasn't been checked yet.
+    Unchecked,
+    /// The replica was found to be healthy; read-only transactions are routed to it without
+    /// checking it again until this instant passes.
+    Healthy { until: Instant },
+    /// The replica was found to be unreachable, disconnected from the primary, or too stale;
+    /// read-only transactions are routed to the primary until this instant passes.
+    Unhealthy { until: Instant },
+}
+
+/// The replication status of a database, as reported by [`REPLICA_STATUS_QUERY`].
+#[derive(Debug)]
+struct ReplicaStatus {
+    /// Whether the database is a replica, i.e. in recovery.
+    in_recovery: bool,
+    /// Whether the database's WAL receiver is streaming from the primary.
+    streaming: bool,
+    /// How far the database lags behind the WAL it has received from the primary.
+    lag: StdDuration,
+}
+
+impl ReplicaStatus {
+    fn from_row(row: &Row) -> Self {
+        Self {
+            in_recovery: row.get("in_recovery"),
+            streaming: row.get("streaming"),
+            lag: StdDuration::try_from_secs_f64(row.get("lag")).unwrap_or_default(),
+        }
+    }
+
+    /// Checks whether read-only transactions which tolerate `max_staleness` may be routed to the
+    /// database. On failure, returns the reason, for metrics, and a description of the problem.
+    ///
+    /// A replica which isn't streaming from the primary can't tell how far it lags behind it, so
+    /// it is never considered healthy, however recently it replayed the WAL it has received.
+    fn check(&self, max_staleness: StdDuration) -> Result<(), (&'static str, String)> {
+        if !self.in_recovery {
+            return Ok(());
+        }
+        if !self.streaming {
+            return Err((
+                "disconnected",
+                "replica is not streaming WAL from the primary".to_string(),
+            ));
+        }
+        if self.lag > max_staleness {
+            return Err((
+                "stale",
+                format!("replica lags {:?} behind the primary", self.lag),
+            ));
+        }
+        Ok(())
+    }
 }
 
 impl<C: Clock> Debug for Datastore<C> {
@@ -224,6 +311,14 @@ impl<C: Clock> Datastore<C> {
             .with_unit("s")
             .with_boundaries(TIME_HISTOGRAM_BOUNDARIES.to_vec())
             .build();
+        let replica_fallback_counter = meter
+            .u64_counter(REPLICA_FALLBACK_METER_NAME)
+            .with_description(concat!(
+                "Count of read-only database transactions routed to the primary database because ",
+                "the read replica was unavailable or too stale, with the reason.",
+            ))
+            .with_unit("{transaction}")
+            .build();
 
         Self {
             pool,
@@ -235,10 +330,29 @@ impl<C: Clock> Datastore<C> {
             rollback_error_counter,
             transaction_duration_histogram,
             transaction_pool_wait_histogram,
+            replica_fallback_counter,
             max_transaction_retries,
+            replica: None,
         }
     }
 
+    /// Routes read-only transactions, run via [`Datastore::run_read_only_tx`], to a read replica
+    /// of the database. While the replica can't be reached, isn't streaming from the primary, or
+    /// lags further than `max_staleness` behind it, read-only transactions fall back to the
+    /// primary.
+    pub fn with_replica(
+        mut self,
+        pool: deadpool_postgres::Pool,
+        max_staleness: StdDuration,
+    ) -> Self {
+        self.replica = Some(Replica {
+            pool,
+            max_staleness,
+            health: Mutex::new(ReplicaHealth::Unchecked),
+        });
+        self
+    }
+
     /// run_tx runs a transaction, whose body is determined by the given function. The transaction
     /// is committed if the body returns a successful value, and rolled back if the body returns an
     /// error value.
@@ -251,13 +365,43 @@ impl<C: Clock> Datastore<C> {
     /// This method requires a transaction name for use in database metrics.
     #[tracing::instrument(level = "trace", skip(self, f))]
     pub async fn run_tx<F, T>(&self, name: &'static str, f: F) -> Result<T, Error>
+    where
+        for<'a> F:
+            Fn(&'a Transaction<C>) -> Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>,
+    {
+        self.run_tx_with_retries(name, false, f).await
+    }
+
+    /// run_read_only_tx runs a read-only transaction, whose body is determined by the given
+    /// function. Any attempt to write from within the transaction fails.
+    ///
+    /// If the datastore was configured with a read replica (see [`Datastore::with_replica`]), the
+    /// transaction runs against the replica, so it may observe state up to the replica's maximum
+    /// staleness old. Callers must be able to tolerate this; transactions which read state that
+    /// they, or a transaction they depend upon, just wrote should use [`Datastore::run_tx`]
+    /// instead. Otherwise, this method behaves as [`Datastore::run_tx`].
+    #[tracing::instrument(level = "trace", skip(self, f))]
+    pub async fn run_read_only_tx<F, T>(&self, name: &'static str, f: F) -> Result<T, Error>
+    where
+        for<'a> F:
+            Fn(&'a Transaction<C>) -> Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>,
+    {
+        self.run_tx_with_retries(name, true, f).await
+    }
+
+    async fn run_tx_with_retries<F, T>(
+        &self,
+        name: &'static str,
+        read_only: bool,
+        f: F,
+    ) -> Result<T, Error>
     where
         for<'a> F:
             Fn(&'a Transaction<C>) -> Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>,
     {
         let mut retry_count = 0;
         loop {
-            let (mut rslt, retry) = self.run_tx_once(name, &f).await;
+            let (mut rslt, retry) = self.run_tx_once(name, read_only, &f).await;
             let retries_exceeded = retry_count + 1 > self.max_transaction_retries;
             let status = match (rslt.as_ref(), retry) {
                 (_, true) => {
@@ -300,27 +444,43 @@ impl<C: Clock> Datastore<C> {
     }
 
     #[tracing::instrument(level = "trace", skip(self, f))]
-    async fn run_tx_once<F, T>(&self, name: &'static str, f: &F) -> (Result<T, Error>, bool)
+    async fn run_tx_once<F, T>(
+        &self,
+        name: &'static str,
+        read_only: bool,
+        f: &F,
+    ) -> (Result<T, Error>, bool)
     where
         for<'a> F:
             Fn(&'a Transaction<C>) -> Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>,
     {
-        // Acquire connection from the connection pooler.
-        let before = Instant::now();
-        let result = self.pool.get().await;
-        let elapsed = before.elapsed();
-        // We don't record the transaction name for this metric, since it's not particularly
-        // interesting. All transactions should get FIFO access to connections.
-        self.transaction_pool_wait_histogram.record(
-            elapsed.as_secs_f64(),
-            &[KeyValue::new(
-                "status",
-                if result.is_err() { "error" } else { "success" },
-            )],
-        );
-        let mut client = match result {
-            Ok(client) => client,
-            Err(err) => return (Err(err.into()), false),
+        // Acquire connection from the replica, if the transaction can use it, or else from the
+        // primary's connection pooler.
+        let replica_client = match (read_only, &self.replica) {
+            (true, Some(replica)) => self.replica_client(replica).await,
+            _ => None,
+        };
+        let mut client = match replica_client {
+            Some(client) => client,
+            None => {
+                let before = Instant::now();
+                let result = self.pool.get().await;
+                let elapsed = before.elapsed();
+                // We don't record the transaction name for this metric, since it's not
+                // particularly interesting. All transactions should get FIFO access to
+                // connections.
+                self.transaction_pool_wait_histogram.record(
+                    elapsed.as_secs_f64(),
+                    &[KeyValue::new(
+                        "status",
+                        if result.is_err() { "error" } else { "success" },
+                    )],
+                );
+                match result {
+                    Ok(client) => client,
+                    Err(err) => return (Err(err.into()), false),
+                }
+            }
         };
 
         // Open transaction.
@@ -328,6 +488,7 @@ impl<C: Clock> Datastore<C> {
         let raw_tx = match client
             .build_transaction()
             .isolation_level(IsolationLevel::RepeatableRead)
+            .read_only(read_only)
             .start()
             .await
         {
@@ -383,6 +544,64 @@ impl<C: Clock> Datastore<C> {
         (rslt, retry.load(Ordering::Relaxed))
     }
 
+    /// Acquires a connection to the replica, if it is healthy. Returns `None` if the transaction
+    /// should instead run against the primary.
+    ///
+    /// The replica's health is checked at most once per [`REPLICA_HEALTH_CHECK_INTERVAL`], rather
+    /// than before every transaction, so transactions may observe state up to that much older
+    /// than the replica's maximum staleness.
+    async fn replica_client(&self, replica: &Replica) -> Option<deadpool_postgres::Object> {
+        let check_health = match *replica.health.lock().unwrap() {
+            ReplicaHealth::Unhealthy { until } if Instant::now() < until => return None,
+            ReplicaHealth::Healthy { until } => until <= Instant::now(),
+            _ => true,
+        };
+
+        let before = Instant::now();
+        let result = replica.pool.get().await;
+        self.transaction_pool_wait_histogram.record(
+            before.elapsed().as_secs_f64(),
+            &[KeyValue::new(
+                "status",
+                if result.is_err() { "error" } else { "success" },
+            )],
+        );
+        let (reason, err) = match result {
+            Ok(client) if !check_health => return Some(client),
+            Ok(client) => match client.query_one(REPLICA_STATUS_QUERY, &[]).await {
+                Ok(row) => match ReplicaStatus::from_row(&row).check(replica.max_staleness) {
+                    Ok(()) => {
+                        *replica.health.lock().unwrap() = ReplicaHealth::Healthy {
+                            until: Instant::now() + REPLICA_HEALTH_CHECK_INTERVAL,
+                        };
+                        return Some(client);
+                    }
+                    Err(reason_and_err) => reason_and_err,
+                },
+                Err(err) => ("unavailable", err.to_string()),
+            },
+            Err(err) => ("unavailable", err.to_string()),
+        };
+
+        warn!(
+            reason,
+            %err,
+            "Read replica is unhealthy, routing read-only transactions to the primary"
+        );
+        self.replica_fallback_counter
+            .add(1, &[KeyValue::new("reason", reason)]);
+        *replica.health.lock().unwrap() = ReplicaHealth::Unhealthy {
+            until: Instant::now() + REPLICA_RETRY_INTERVAL,
+        };
+        None
+    }
+
+    /// Returns true if read-only transactions may be routed to a read replica, in which case they
+    /// may observe stale data.
+    pub fn has_replica(&self) -> bool {
+        self.replica.is_some()
+    }
+
     /// Returns the clock in use by this datastore.
     pub fn clock(&self) -> &C {
         &self.clock
@@ -462,6 +681,33 @@ pub const TRANSACTION_ROLLBACK_METER_NAME: &str = "janus_database_rollback_error
 pub const TRANSACTION_RETRIES_METER_NAME: &str = "janus_database_transaction_retries";
 pub const TRANSACTION_DURATION_METER_NAME: &str = "janus_database_transaction_duration";
 pub const TRANSACTION_POOL_WAIT_METER_NAME: &str = "janus_database_pool_wait_duration";
+pub const REPLICA_FALLBACK_METER_NAME: &str = "janus_database_replica_fallbacks";
+
+/// How long read-only transactions are routed to the primary after the read replica is found to be
+/// unhealthy, before the replica is tried again.
+const REPLICA_RETRY_INTERVAL: StdDuration = StdDuration::from_secs(10);
+
+/// How long a read replica found to be healthy is used without checking its health again.
+const REPLICA_HEALTH_CHECK_INTERVAL: StdDuration = StdDuration::from_secs(1);
+
+/// Reports the replication status of the database; see [`ReplicaStatus`]. The lag is measured in
+/// seconds. A replica which has replayed all of the WAL it has received is considered up to date,
+/// since the timestamp of its last replayed transaction doesn't advance while the primary is idle;
+/// this only holds while the replica is streaming from the primary, which is reported separately.
+/// The status of the WAL receiver is only visible to roles with the privileges of
+/// `pg_read_all_stats`; for other roles, a running WAL receiver is assumed to be streaming. A
+/// database which isn't a replica never lags.
+const REPLICA_STATUS_QUERY: &str = "-- replica_status()
+SELECT
+    pg_is_in_recovery() AS in_recovery,
+    EXISTS(
+        SELECT 1 FROM pg_stat_wal_receiver WHERE COALESCE(status, 'streaming') = 'streaming'
+    ) AS streaming,
+    CASE
+        WHEN NOT pg_is_in_recovery() THEN 0
+        WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0
+        ELSE COALESCE(EXTRACT(EPOCH FROM clock_timestamp() - pg_last_xact_replay_timestamp()), 0)
+    END::DOUBLE PRECISION AS lag";
 
 /// These boundaries are for the number of times a database transaction was retried.
 const RETRIES_HISTOGRAM_BOUNDARIES: &[f64] = &[
@@ -692,15 +938,15 @@ WHERE success = TRUE ORDER BY version DESC LIMIT(1)",
                 "-- put_aggregator_task()
 INSERT INTO tasks (
     task_id, aggregator_role, aggregation_mode, peer_aggregator_endpoint,
-    batch_mode, vdaf, task_start, task_end, report_expiry_age, min_batch_size,
-    time_precision, tolerable_clock_skew, collector_hpke_config,
-    vdaf_verify_key, taskprov_task_info, aggregator_auth_token_type,
-    aggregator_auth_token, aggregator_auth_token_hash,
-    collector_auth_token_type, collector_auth_token_hash, created_at,
-    updated_at, updated_by)
+    batch_mode, vdaf, task_start, task_end, report_expiry_age,
+    report_retention_window, dp_budget, min_batch_size, time_precision,
+    tolerable_clock_skew, collector_hpke_config, vdaf_verify_key,
+    taskprov_task_info, aggregator_auth_token_type, aggregator_auth_token,
+    aggregator_auth_token_hash, collector_auth_token_type,
+    collector_auth_token_hash, state, created_at, updated_at, updated_by)
 VALUES (
     $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18,
-    $19, $20, $21, $22, $23
+    $19, $20, $21, $22, $23, $24, $25, $26
 )
 ON CONFLICT DO NOTHING",
             )
@@ -729,6 +975,13 @@ ON CONFLICT DO NOTHING",
                         .map(Duration::as_seconds)
                         .map(i64::try_from)
                         .transpose()?,
+                    /* report_retention_window */
+                    &task
+                        .report_retention_window()
+                        .map(Duration::as_seconds)
+                        .map(i64::try_from)
+                        .transpose()?,
+                    /* dp_budget */ &task.dp_budget().map(DpBudget::get),
                     /* min_batch_size */ &i64::try_from(task.min_batch_size())?,
                     /* time_precision */
                     &i64::try_from(task.time_precision().as_seconds())?,
@@ -780,6 +1033,7 @@ ON CONFLICT DO NOTHING",
                     &task
                         .collector_auth_token_hash()
                         .map(|token_hash| token_hash.as_ref()),
+                    /* state */ task.state(),
                     /* created_at */ &now,
                     /* updated_at */ &now,
                     /* updated_by */ &self.name,
@@ -849,6 +1103,95 @@ UPDATE tasks SET task_end = $1, updated_at = $2, updated_by = $3
         )
     }
 
+    /// Sets the lifecycle state of a task.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn update_task_state(
+        &self,
+        task_id: &TaskId,
+        state: &TaskState,
+    ) -> Result<(), Error> {
+        let stmt = self
+            .prepare_cached(
+                "-- update_task_state()
+UPDATE tasks SET state = $1, updated_at = $2, updated_by = $3
+   WHERE task_id = $4",
+            )
+            .await?;
+
+        check_single_row_mutation(
+            self.execute(
+                &stmt,
+                &[
+                    /* state */ state,
+                    /* updated_at */ &self.clock.now().as_naive_date_time()?,
+                    /* updated_by */ &self.name,
+                    /* task_id */ &task_id.as_ref(),
+                ],
+            )
+            .await?,
+        )
+    }
+
+    /// Replaces the aggregator & collector authentication tokens of an existing task with those of
+    /// the provided task. No other task parameters are updated.
+    #[tracing::instrument(skip(self, task), fields(task_id = ?task.id()), err(level = Level::DEBUG))]
+    pub async fn update_task_auth_tokens(&self, task: &AggregatorTask) -> Result<(), Error> {
+        let stmt = self
+            .prepare_cached(
+                "-- update_task_auth_tokens()
+UPDATE tasks SET
+    aggregator_auth_token_type = $1, aggregator_auth_token = $2,
+    aggregator_auth_token_hash = $3, collector_auth_token_type = $4,
+    collector_auth_token_hash = $5, updated_at = $6, updated_by = $7
+WHERE task_id = $8",
+            )
+            .await?;
+
+        check_single_row_mutation(
+            self.execute(
+                &stmt,
+                &[
+                    /* aggregator_auth_token_type */
+                    &task
+                        .aggregator_auth_token()
+                        .map(AuthenticationTokenType::from)
+                        .or_else(|| {
+                            task.aggregator_auth_token_hash()
+                                .map(AuthenticationTokenType::from)
+                        }),
+                    /* aggregator_auth_token */
+                    &task
+                        .aggregator_auth_token()
+                        .map(|token| {
+                            self.crypter.encrypt(
+                                "tasks",
+                                task.id().as_ref(),
+                                "aggregator_auth_token",
+                                token.as_ref(),
+                            )
+                        })
+                        .transpose()?,
+                    /* aggregator_auth_token_hash */
+                    &task
+                        .aggregator_auth_token_hash()
+                        .map(|token_hash| token_hash.as_ref()),
+                    /* collector_auth_token_type */
+                    &task
+                        .collector_auth_token_hash()
+                        .map(AuthenticationTokenType::from),
+                    /* collector_auth_token_hash */
+                    &task
+                        .collector_auth_token_hash()
+                        .map(|token_hash| token_hash.as_ref()),
+                    /* updated_at */ &self.clock.now().as_naive_date_time()?,
+                    /* updated_by */ &self.name,
+                    /* task_id */ &task.id().as_ref(),
+                ],
+            )
+            .await?,
+        )
+    }
+
     /// Fetch the task parameters corresponing to the provided `task_id`.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
     pub async fn get_aggregator_task(
@@ -861,11 +1204,12 @@ UPDATE tasks SET task_end = $1, updated_at = $2, updated_by = $3
                 "-- get_aggregator_task()
 SELECT
     aggregator_role, aggregation_mode, peer_aggregator_endpoint, batch_mode,
-    vdaf, task_start, task_end, report_expiry_age, min_batch_size,
-    time_precision, tolerable_clock_skew, collector_hpke_config,
-    vdaf_verify_key, taskprov_task_info, aggregator_auth_token_type,
-    aggregator_auth_token, aggregator_auth_token_hash,
-    collector_auth_token_type, collector_auth_token_hash
+    vdaf, task_start, task_end, report_expiry_age, report_retention_window,
+    dp_budget, min_batch_size, time_precision, tolerable_clock_skew,
+    collector_hpke_config, vdaf_verify_key, taskprov_task_info,
+    aggregator_auth_token_type, aggregator_auth_token,
+    aggregator_auth_token_hash, collector_auth_token_type,
+    collector_auth_token_hash, state
 FROM tasks WHERE task_id = $1",
             )
             .await?;
@@ -884,11 +1228,12 @@ FROM tasks WHERE task_id = $1",
                 "-- get_aggregator_tasks()
 SELECT
     task_id, aggregator_role, aggregation_mode, peer_aggregator_endpoint,
-    batch_mode, vdaf, task_start, task_end, report_expiry_age, min_batch_size,
-    time_precision, tolerable_clock_skew, collector_hpke_config,
-    vdaf_verify_key, taskprov_task_info, aggregator_auth_token_type,
-    aggregator_auth_token, aggregator_auth_token_hash,
-    collector_auth_token_type, collector_auth_token_hash
+    batch_mode, vdaf, task_start, task_end, report_expiry_age,
+    report_retention_window, dp_budget, min_batch_size, time_precision,
+    tolerable_clock_skew, collector_hpke_config, vdaf_verify_key,
+    taskprov_task_info, aggregator_auth_token_type, aggregator_auth_token,
+    aggregator_auth_token_hash, collector_auth_token_type,
+    collector_auth_token_hash, state
 FROM tasks",
             )
             .await?;
@@ -919,6 +1264,14 @@ FROM tasks",
         let report_expiry_age = row
             .get_nullable_bigint_and_convert("report_expiry_age")?
             .map(Duration::from_seconds);
+        let report_retention_window = row
+            .get_nullable_bigint_and_convert("report_retention_window")?
+            .map(Duration::from_seconds);
+        let dp_budget = row
+            .get::<_, Option<f64>>("dp_budget")
+            .map(DpBudget::new)
+            .transpose()?;
+        let state: TaskState = row.get("state");
         let min_batch_size = row.get_bigint_and_convert("min_batch_size")?;
         let time_precision = Duration::from_seconds(row.get_bigint_and_convert("time_precision")?);
         let tolerable_clock_skew =
@@ -1022,7 +1375,10 @@ FROM tasks",
             time_precision,
             tolerable_clock_skew,
             aggregator_parameters,
-        )?;
+        )?
+        .with_report_retention_window(report_retention_window)?
+        .with_dp_budget(dp_budget)
+        .with_state(state);
         if let Some(taskprov_task_info) = taskprov_task_info {
             task = task.with_taskprov_task_info(taskprov_task_info);
         }
@@ -1256,9 +1612,9 @@ RETURNING report_id, client_timestamp",
     }
 
     /// `get_unaggregated_client_report_ids_by_collect_for_task` returns pairs of report IDs and
-    /// aggregation parameters, corresponding to client reports that have not yet been aggregated,
-    /// or not aggregated with a certain aggregation parameter, and for which there are collect
-    /// jobs, for a given task. Returned client reports are marked as aggregation-started, but this
+    /// aggregation parameters, corresponding to client reports that have not yet been aggregated
+    /// with a certain aggregation parameter, and for which there are collection jobs, for a given
+    /// time-interval task. Returned client reports are marked as aggregation-started, but this
     /// will not stop additional aggregation jobs from being created later with different
     /// aggregation parameters.
     ///
@@ -1268,9 +1624,9 @@ RETURNING report_id, client_timestamp",
     /// not necessary to wait for a collection job to arrive before preparing reports.
     ///
     /// This function deliberately ignores the `client_reports.aggregation_started` column, which
-    /// only has meaning for VDAFs without aggregation parameters.
-    #[tracing::instrument(skip(self), err)]
-    #[cfg(feature = "test-util")]
+    /// only has meaning for VDAFs without aggregation parameters. Reports whose shares have been
+    /// scrubbed, or which are older than the task's report expiry age, are not returned.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
     pub async fn get_unaggregated_client_report_ids_by_collect_for_task<const SEED_SIZE: usize, A>(
         &self,
         task_id: &TaskId,
@@ -1279,42 +1635,44 @@ RETURNING report_id, client_timestamp",
     where
         A: AsyncAggregator<SEED_SIZE> + VdafHasAggregationParameter,
     {
-        // TODO(#224): lock retrieved client_reports rows
-        // TODO(#225): use get_task_primary_key_and_expiry_threshold as in
-        // get_unaggregated_client_reports_for_task
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(Vec::new()),
+        };
+        let now = self.clock.now().as_naive_date_time()?;
+
         let stmt = self
             .prepare_cached(
                 "-- get_unaggregated_client_report_ids_by_collect_for_task()
 WITH unaggregated_client_report_ids AS (
-    SELECT DISTINCT report_id, client_timestamp, collection_jobs.aggregation_param
+    SELECT DISTINCT client_reports.report_id, client_reports.client_timestamp,
+        collection_jobs.aggregation_param
     FROM collection_jobs
-    INNER JOIN client_reports
-    ON collection_jobs.task_id = client_reports.task_id
-    AND client_reports.client_timestamp <@ collection_jobs.batch_interval
-    LEFT JOIN (
-        SELECT report_aggregations.id, report_aggregations.client_report_id,
-            aggregation_jobs.aggregation_param
-        FROM report_aggregations
-        INNER JOIN aggregation_jobs
-        ON aggregation_jobs.id = report_aggregations.aggregation_job_id
-        WHERE aggregation_jobs.task_id = (SELECT id FROM tasks WHERE task_id = $1)
-    ) AS report_aggs
-    ON report_aggs.client_report_id = client_reports.report_id
-    -- this join is very inefficient, fix before deploying in non-test scenario
-    AND report_aggs.aggregation_param = collection_jobs.aggregation_param
-    WHERE client_reports.task_id = (SELECT id FROM tasks WHERE task_id = $1)
-    AND collection_jobs.task_id = (SELECT id FROM tasks WHERE task_id = $1)
-    AND collection_jobs.state = 'START'
-    AND report_aggs.id IS NULL
-    LIMIT $2::BIGINT
+    JOIN client_reports
+      ON client_reports.task_id = collection_jobs.task_id
+     AND client_reports.client_timestamp <@ collection_jobs.batch_interval
+    WHERE collection_jobs.task_id = $1
+      AND collection_jobs.state = 'START'
+      AND client_reports.client_timestamp >= $2
+      AND client_reports.leader_input_share IS NOT NULL
+      AND NOT EXISTS (
+        SELECT 1 FROM report_aggregations
+        JOIN aggregation_jobs
+          ON aggregation_jobs.id = report_aggregations.aggregation_job_id
+        WHERE report_aggregations.task_id = $1
+          AND report_aggregations.client_report_id = client_reports.report_id
+          AND report_aggregations.client_timestamp = client_reports.client_timestamp
+          AND aggregation_jobs.aggregation_param = collection_jobs.aggregation_param
+      )
+    LIMIT $5::BIGINT
 ),
 updated_client_reports AS (
-    UPDATE client_reports SET aggregation_started = TRUE
+    UPDATE client_reports SET
+        aggregation_started = TRUE, updated_at = $3, updated_by = $4
     FROM unaggregated_client_report_ids
-    WHERE client_reports.task_id = (SELECT id FROM tasks WHERE task_id = $1)
+    WHERE client_reports.task_id = $1
       AND client_reports.report_id = unaggregated_client_report_ids.report_id
-      AND client_reports.client_timestamp =
-        unaggregated_client_report_ids.client_timestamp
+      AND client_reports.client_timestamp = unaggregated_client_report_ids.client_timestamp
 )
 SELECT report_id, client_timestamp, aggregation_param
 FROM unaggregated_client_report_ids",
@@ -1323,7 +1681,13 @@ FROM unaggregated_client_report_ids",
         let rows = self
             .query(
                 &stmt,
-                &[&task_id.as_ref(), /* limit */ &i64::try_from(limit)?],
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* limit */ &i64::try_from(limit)?,
+                ],
             )
             .await?;
 
@@ -1339,6 +1703,91 @@ FROM unaggregated_client_report_ids",
             .collect::<Result<Vec<_>, Error>>()
     }
 
+    /// `get_unaggregated_client_report_ids_by_collect_for_leader_selected_task` returns triples
+    /// of batch IDs, aggregation parameters, and report IDs, corresponding to client reports that
+    /// belong to a batch for which there is a collection job, but which have not yet been
+    /// aggregated with that collection job's aggregation parameter, for a given leader-selected
+    /// task.
+    ///
+    /// A report belongs to a batch if it was successfully aggregated by an aggregation job
+    /// assigned to that batch, under any aggregation parameter. The membership of a batch is
+    /// therefore fixed by its first aggregation, and re-aggregating it with another parameter only
+    /// ever considers those same reports. Reports whose shares have been scrubbed, or which are
+    /// older than the task's report expiry age, are not returned.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_unaggregated_client_report_ids_by_collect_for_leader_selected_task<
+        const SEED_SIZE: usize,
+        A,
+    >(
+        &self,
+        task_id: &TaskId,
+        limit: usize,
+    ) -> Result<Vec<(BatchId, A::AggregationParam, UnaggregatedReport)>, Error>
+    where
+        A: AsyncAggregator<SEED_SIZE> + VdafHasAggregationParameter,
+    {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(Vec::new()),
+        };
+        let now = self.clock.now().as_naive_date_time()?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_unaggregated_client_report_ids_by_collect_for_leader_selected_task()
+SELECT DISTINCT client_reports.report_id, client_reports.client_timestamp,
+    collection_jobs.batch_identifier, collection_jobs.aggregation_param
+FROM collection_jobs
+JOIN aggregation_jobs AS batch_aggregation_jobs
+  ON batch_aggregation_jobs.task_id = collection_jobs.task_id
+ AND batch_aggregation_jobs.batch_id = collection_jobs.batch_identifier
+JOIN report_aggregations AS batch_report_aggregations
+  ON batch_report_aggregations.aggregation_job_id = batch_aggregation_jobs.id
+ AND batch_report_aggregations.state = 'FINISHED'
+JOIN client_reports
+  ON client_reports.task_id = collection_jobs.task_id
+ AND client_reports.report_id = batch_report_aggregations.client_report_id
+ AND client_reports.client_timestamp = batch_report_aggregations.client_timestamp
+WHERE collection_jobs.task_id = $1
+  AND collection_jobs.state = 'START'
+  AND client_reports.client_timestamp >= $2
+  AND client_reports.leader_input_share IS NOT NULL
+  AND NOT EXISTS (
+    SELECT 1 FROM report_aggregations
+    JOIN aggregation_jobs
+      ON aggregation_jobs.id = report_aggregations.aggregation_job_id
+    WHERE report_aggregations.task_id = $1
+      AND report_aggregations.client_report_id = client_reports.report_id
+      AND report_aggregations.client_timestamp = client_reports.client_timestamp
+      AND aggregation_jobs.aggregation_param = collection_jobs.aggregation_param
+  )
+LIMIT $3::BIGINT",
+            )
+            .await?;
+        let rows = self
+            .query(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                    /* limit */ &i64::try_from(limit)?,
+                ],
+            )
+            .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let batch_id = BatchId::get_decoded(row.get("batch_identifier"))?;
+                let agg_param = A::AggregationParam::get_decoded(row.get("aggregation_param"))?;
+                let unaggregated_report = UnaggregatedReport::new(
+                    row.get_bytea_and_convert::<ReportId>("report_id")?,
+                    Time::from_naive_date_time(&row.get("client_timestamp")),
+                );
+                Ok((batch_id, agg_param, unaggregated_report))
+            })
+            .collect::<Result<Vec<_>, Error>>()
+    }
+
     /// `mark_report_unaggregated` resets the aggregation-started flag on the given client report,
     /// so that it may once again be returned by `get_unaggregated_client_report_ids_for_task`. It
     /// should generally only be called on report IDs returned from
@@ -1461,33 +1910,25 @@ SELECT EXISTS(
         Ok(row.get("unaggregated_report_exists"))
     }
 
-    /// Return the number of reports in the provided task whose timestamp falls within the provided
-    /// interval, regardless of whether the reports have been aggregated or collected. Applies only
-    /// to time-interval queries.
+    /// Returns the time at which the oldest unexpired client report in the given task which has not
+    /// yet started the aggregation process was stored, or `None` if there are no such reports.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn count_client_reports_for_interval(
+    pub async fn get_oldest_unaggregated_client_report_time(
         &self,
         task_id: &TaskId,
-        batch_interval: &Interval,
-    ) -> Result<u64, Error> {
+    ) -> Result<Option<Time>, Error> {
         let task_info = match self.task_info_for(task_id).await? {
             Some(task_info) => task_info,
-            None => return Ok(0),
+            None => return Ok(None),
         };
 
-        batch_interval
-            .validate_precision(&task_info.time_precision)
-            .map_err(|e| Self::unaligned_time_error(task_id, &task_info.time_precision, e))?;
-
         let stmt = self
             .prepare_cached(
-                "-- count_client_reports_for_interval()
-SELECT COUNT(1) AS count
-FROM client_reports
+                "-- get_oldest_unaggregated_client_report_time()
+SELECT MIN(client_reports.created_at) AS created_at FROM client_reports
 WHERE client_reports.task_id = $1
-  AND client_reports.client_timestamp >= LOWER($2::TSRANGE)
-  AND client_reports.client_timestamp < UPPER($2::TSRANGE)
-  AND client_reports.client_timestamp >= $3",
+  AND client_reports.aggregation_started = FALSE
+  AND client_reports.client_timestamp >= $2",
             )
             .await?;
         let row = self
@@ -1495,21 +1936,66 @@ WHERE client_reports.task_id = $1
                 &stmt,
                 &[
                     /* task_id */ &task_info.pkey,
-                    /* batch_interval */ &SqlInterval::from(batch_interval),
                     /* threshold */
                     &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
                 ],
             )
             .await?;
         Ok(row
-            .get::<_, Option<i64>>("count")
-            .unwrap_or_default()
-            .try_into()?)
+            .get::<_, Option<NaiveDateTime>>("created_at")
+            .as_ref()
+            .map(Time::from_naive_date_time))
     }
 
-    /// Return the number of reports in the provided task & batch, regardless of whether the reports
-    /// have been aggregated or collected. Applies only to leader-selected queries.
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    /// Return the number of reports in the provided task whose timestamp falls within the provided
+    /// interval, regardless of whether the reports have been aggregated or collected. Applies only
+    /// to time-interval queries.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn count_client_reports_for_interval(
+        &self,
+        task_id: &TaskId,
+        batch_interval: &Interval,
+    ) -> Result<u64, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(0),
+        };
+
+        batch_interval
+            .validate_precision(&task_info.time_precision)
+            .map_err(|e| Self::unaligned_time_error(task_id, &task_info.time_precision, e))?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- count_client_reports_for_interval()
+SELECT COUNT(1) AS count
+FROM client_reports
+WHERE client_reports.task_id = $1
+  AND client_reports.client_timestamp >= LOWER($2::TSRANGE)
+  AND client_reports.client_timestamp < UPPER($2::TSRANGE)
+  AND client_reports.client_timestamp >= $3",
+            )
+            .await?;
+        let row = self
+            .query_one(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* batch_interval */ &SqlInterval::from(batch_interval),
+                    /* threshold */
+                    &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+                ],
+            )
+            .await?;
+        Ok(row
+            .get::<_, Option<i64>>("count")
+            .unwrap_or_default()
+            .try_into()?)
+    }
+
+    /// Return the number of reports in the provided task & batch, regardless of whether the reports
+    /// have been aggregated or collected. Applies only to leader-selected queries.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
     pub async fn count_client_reports_for_batch_id(
         &self,
         task_id: &TaskId,
@@ -1591,10 +2077,14 @@ WHERE report_aggregations.task_id = $1
                 Self::unaligned_time_error(report.task_id(), &task_info.time_precision, e)
             })?;
 
-        // If there is a conflict, the we upsert the incoming report (excluded) if the existing
-        // report is expired (virtually GCed; would be invisible to other queries that retrieve
-        // report rows).
-        // https://github.com/divviup/janus/pull/2818
+        self.put_client_report_id(
+            &task_info,
+            report.metadata().id(),
+            report.metadata().time(),
+            &now,
+        )
+        .await?;
+
         let stmt = self
             .prepare_cached(
                 "-- put_client_report()
@@ -1603,21 +2093,8 @@ INSERT INTO client_reports (
     leader_private_extensions, leader_input_share,
     helper_encrypted_input_share, created_at, updated_at, updated_by
 )
-VALUES (
-    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11
-)
-ON CONFLICT(task_id, report_id) DO UPDATE
-    SET (
-        client_timestamp, public_extensions, public_share,
-        leader_private_extensions, leader_input_share,
-        helper_encrypted_input_share, created_at, updated_at, updated_by
-    ) = (
-        excluded.client_timestamp, excluded.public_extensions,
-        excluded.public_share, excluded.leader_private_extensions,
-        excluded.leader_input_share, excluded.helper_encrypted_input_share,
-        excluded.created_at, excluded.updated_at, excluded.updated_by
-    )
-    WHERE client_reports.client_timestamp < $12",
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+ON CONFLICT DO NOTHING",
             )
             .await?;
         check_insert(
@@ -1636,7 +2113,6 @@ ON CONFLICT(task_id, report_id) DO UPDATE
                     /* created_at */ &now,
                     /* updated_at */ &now,
                     /* updated_by */ &self.name,
-                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
                 ],
             )
             .await?,
@@ -1754,10 +2230,9 @@ WHERE task_id = $1
             .map_err(|e| Self::unaligned_time_error(task_id, &task_info.time_precision, e))?;
         let now = self.clock.now().as_naive_date_time()?;
 
-        // If there is a conflict, the we upsert the incoming report (excluded) if the existing
-        // report is expired (virtually GCed; would be invisible to other queries that retrieve
-        // report rows).
-        // https://github.com/divviup/janus/pull/2818
+        self.put_client_report_id(&task_info, report_id, client_timestamp, &now)
+            .await?;
+
         let stmt = self
             .prepare_cached(
                 "-- put_scrubbed_report()
@@ -1765,18 +2240,7 @@ INSERT INTO client_reports (
     task_id, report_id, client_timestamp, created_at, updated_at, updated_by
 )
 VALUES ($1, $2, $3, $4, $5, $6)
-ON CONFLICT(task_id, report_id) DO UPDATE
-SET (
-    client_timestamp, public_extensions, public_share,
-    leader_private_extensions, leader_input_share,
-    helper_encrypted_input_share, created_at, updated_at, updated_by
-) = (
-    excluded.client_timestamp, excluded.public_extensions,
-    excluded.public_share, excluded.leader_private_extensions,
-    excluded.leader_input_share, excluded.helper_encrypted_input_share,
-    excluded.created_at, excluded.updated_at, excluded.updated_by
-)
-WHERE client_reports.client_timestamp < $7",
+ON CONFLICT DO NOTHING",
             )
             .await?;
         check_insert(
@@ -1789,13 +2253,130 @@ WHERE client_reports.client_timestamp < $7",
                     /* created_at */ &now,
                     /* updated_at */ &now,
                     /* updated_by */ &self.name,
-                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
                 ],
             )
             .await?,
         )
     }
 
+    /// Claims a report ID for a report about to be written to client_reports, by writing it to
+    /// client_report_ids. Returns [`Error::MutationTargetAlreadyExists`] if the report ID is
+    /// already in use by an unexpired report.
+    ///
+    /// client_reports is partitioned by client timestamp, so the database only enforces uniqueness
+    /// of report IDs per client timestamp there; the primary key of the unpartitioned
+    /// client_report_ids table enforces uniqueness of the report ID within the task. If a
+    /// concurrent transaction claims the same report ID, this transaction either sees its row or
+    /// fails with a serialization failure once it commits, and so is retried.
+    async fn put_client_report_id(
+        &self,
+        task_info: &TaskInfo,
+        report_id: &ReportId,
+        client_timestamp: &Time,
+        now: &NaiveDateTime,
+    ) -> Result<(), Error> {
+        // If there is a conflict, we replace the existing report with the incoming report if the
+        // existing report is expired (virtually GCed; would be invisible to other queries that
+        // retrieve report rows).
+        // https://github.com/divviup/janus/pull/2818
+        //
+        // The expired report is deleted, rather than upserted over, since it may be stored in a
+        // different partition of client_reports than the incoming report.
+        let stmt = self
+            .prepare_cached(
+                "-- put_client_report_id()
+WITH expired_report_ids AS (
+    DELETE FROM client_report_ids
+    WHERE task_id = $1
+      AND report_id = $2
+      AND client_timestamp < $3
+    RETURNING task_id, report_id, client_timestamp
+)
+DELETE FROM client_reports
+USING expired_report_ids
+WHERE client_reports.task_id = expired_report_ids.task_id
+  AND client_reports.report_id = expired_report_ids.report_id
+  AND client_reports.client_timestamp = expired_report_ids.client_timestamp",
+            )
+            .await?;
+        self.execute(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* report_id */ &report_id.as_ref(),
+                /* threshold */ &task_info.report_expiry_threshold(now)?,
+            ],
+        )
+        .await?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- put_client_report_id()
+INSERT INTO client_report_ids (task_id, report_id, client_timestamp)
+VALUES ($1, $2, $3)
+ON CONFLICT DO NOTHING",
+            )
+            .await?;
+        check_insert(
+            self.execute(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* report_id */ &report_id.as_ref(),
+                    /* client_timestamp */ &client_timestamp.as_naive_date_time()?,
+                ],
+            )
+            .await?,
+        )
+    }
+
+    /// Returns whether the given client report has already been included in an aggregation job
+    /// with the given aggregation parameter. Aggregation jobs which are older than the task's
+    /// report expiry age are ignored.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn check_report_aggregated_with_aggregation_param<
+        const SEED_SIZE: usize,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        task_id: &TaskId,
+        report_id: &ReportId,
+        aggregation_param: &A::AggregationParam,
+    ) -> Result<bool, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(false),
+        };
+
+        let stmt = self
+            .prepare_cached(
+                "-- check_report_aggregated_with_aggregation_param()
+SELECT EXISTS(
+    SELECT 1 FROM report_aggregations
+    JOIN aggregation_jobs
+      ON aggregation_jobs.id = report_aggregations.aggregation_job_id
+    WHERE report_aggregations.task_id = $1
+      AND report_aggregations.client_report_id = $2
+      AND aggregation_jobs.aggregation_param = $3
+      AND UPPER(aggregation_jobs.client_timestamp_interval) >= $4
+) AS aggregated",
+            )
+            .await?;
+        Ok(self
+            .query_one(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* report_id */ &report_id.as_ref(),
+                    /* aggregation_param */ &aggregation_param.get_encoded()?,
+                    /* threshold */
+                    &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+                ],
+            )
+            .await?
+            .get("aggregated"))
+    }
+
     /// get_aggregation_job retrieves an aggregation job by ID.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
     pub async fn get_aggregation_job<
@@ -1961,7 +2542,7 @@ WHERE tasks.id = aggregation_jobs.task_id
 AND aggregation_jobs.id IN (SELECT id FROM incomplete_jobs)
 RETURNING tasks.task_id, tasks.batch_mode, tasks.vdaf,
           aggregation_jobs.aggregation_job_id, aggregation_jobs.lease_token,
-          aggregation_jobs.lease_attempts",
+          aggregation_jobs.lease_attempts, aggregation_jobs.created_at",
             )
             .await?;
         self.query(
@@ -1984,9 +2565,16 @@ RETURNING tasks.task_id, tasks.batch_mode, tasks.vdaf,
             let vdaf = row.try_get::<_, Json<VdafInstance>>("vdaf")?.0;
             let lease_token = row.get_bytea_and_convert::<LeaseToken>("lease_token")?;
             let lease_attempts = row.get_bigint_and_convert("lease_attempts")?;
+            let created_at = Time::from_naive_date_time(&row.get("created_at"));
 
             Ok(Lease::new(
-                AcquiredAggregationJob::new(task_id, aggregation_job_id, batch_mode, vdaf),
+                AcquiredAggregationJob::new(
+                    task_id,
+                    aggregation_job_id,
+                    batch_mode,
+                    vdaf,
+                    created_at,
+                ),
                 lease_expiry_time,
                 lease_token,
                 lease_attempts,
@@ -2052,72 +2640,41 @@ WHERE aggregation_jobs.task_id = $4
         )
     }
 
-    /// put_aggregation_job stores an aggregation job.
+    /// set_aggregation_job_last_error records the most recent error encountered while stepping
+    /// the given aggregation job, so that it can be surfaced to operators if the job is later
+    /// abandoned.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn put_aggregation_job<
-        const SEED_SIZE: usize,
-        B: BatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    pub async fn set_aggregation_job_last_error(
         &self,
-        aggregation_job: &AggregationJob<SEED_SIZE, B, A>,
+        task_id: &TaskId,
+        aggregation_job_id: &AggregationJobId,
+        last_error: &str,
     ) -> Result<(), Error> {
         let task_info = self
-            .task_info_for(aggregation_job.task_id())
+            .task_info_for(task_id)
             .await?
             .ok_or(Error::MutationTargetNotFound)?;
         let now = self.clock.now().as_naive_date_time()?;
 
-        aggregation_job
-            .client_timestamp_interval()
-            .validate_precision(&task_info.time_precision)
-            .map_err(|e| {
-                Self::unaligned_time_error(aggregation_job.task_id(), &task_info.time_precision, e)
-            })?;
-
-        // If there is a conflict, the we upsert the incoming aggregation job (excluded) if the
-        // existing aggregation job is expired (virtually GCed; would be invisible to other queries
-        // that retrieve aggregation job rows).
-        // https://github.com/divviup/janus/pull/2818
         let stmt = self
             .prepare_cached(
-                "-- put_aggregation_job()
-INSERT INTO aggregation_jobs
-    (task_id, aggregation_job_id, aggregation_param, batch_id,
-    client_timestamp_interval, state, step, last_request_hash,
-    created_at, updated_at, updated_by)
-VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
-ON CONFLICT(task_id, aggregation_job_id) DO UPDATE
-    SET (
-        aggregation_param, batch_id, client_timestamp_interval, state, step,
-        last_request_hash, created_at, updated_at, updated_by
-    ) = (
-        excluded.aggregation_param, excluded.batch_id,
-        excluded.client_timestamp_interval, excluded.state, excluded.step,
-        excluded.last_request_hash, excluded.created_at, excluded.updated_at,
-        excluded.updated_by
-    )
-    WHERE UPPER(aggregation_jobs.client_timestamp_interval) < $12",
+                "-- set_aggregation_job_last_error()
+UPDATE aggregation_jobs
+SET last_error = $1, updated_at = $2, updated_by = $3
+WHERE aggregation_jobs.task_id = $4
+  AND aggregation_jobs.aggregation_job_id = $5
+  AND UPPER(aggregation_jobs.client_timestamp_interval) >= $6",
             )
             .await?;
-        check_insert(
+        check_single_row_mutation(
             self.execute(
                 &stmt,
                 &[
-                    /* task_id */ &task_info.pkey,
-                    /* aggregation_job_id */ &aggregation_job.id().as_ref(),
-                    /* aggregation_param */
-                    &aggregation_job.aggregation_parameter().get_encoded()?,
-                    /* batch_id */
-                    &aggregation_job.partial_batch_identifier().get_encoded()?,
-                    /* client_timestamp_interval */
-                    &SqlInterval::from(aggregation_job.client_timestamp_interval()),
-                    /* state */ &aggregation_job.state(),
-                    /* step */ &(u16::from(aggregation_job.step()) as i32),
-                    /* last_request_hash */ &aggregation_job.last_request_hash(),
-                    /* created_at */ &now,
+                    /* last_error */ &last_error,
                     /* updated_at */ &now,
                     /* updated_by */ &self.name,
+                    /* task_id */ &task_info.pkey,
+                    /* aggregation_job_id */ &aggregation_job_id.as_ref(),
                     /* threshold */ &task_info.report_expiry_threshold(&now)?,
                 ],
             )
@@ -2125,554 +2682,2886 @@ ON CONFLICT(task_id, aggregation_job_id) DO UPDATE
         )
     }
 
-    /// update_aggregation_job updates a stored aggregation job.
+    /// put_aggregation_job_history_entry records an unsuccessful attempt to step the given leader
+    /// aggregation job. Only the most recent [`MAX_JOB_HISTORY_ENTRIES`] entries for each job are
+    /// retained.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn update_aggregation_job<
-        const SEED_SIZE: usize,
-        B: BatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    pub async fn put_aggregation_job_history_entry(
         &self,
-        aggregation_job: &AggregationJob<SEED_SIZE, B, A>,
+        task_id: &TaskId,
+        aggregation_job_id: &AggregationJobId,
+        record: &JobStepRecord,
     ) -> Result<(), Error> {
         let task_info = self
-            .task_info_for(aggregation_job.task_id())
+            .task_info_for(task_id)
             .await?
             .ok_or(Error::MutationTargetNotFound)?;
         let now = self.clock.now().as_naive_date_time()?;
 
         let stmt = self
             .prepare_cached(
-                "-- update_aggregation_job()
-UPDATE aggregation_jobs SET
-    state = $1,
-    step = $2,
-    last_request_hash = $3,
-    updated_at = $4,
-    updated_by = $5
-WHERE aggregation_jobs.task_id = $6
-  AND aggregation_jobs.aggregation_job_id = $7
-  AND UPPER(aggregation_jobs.client_timestamp_interval) >= $8::TIMESTAMP",
+                "-- put_aggregation_job_history_entry()
+INSERT INTO aggregation_job_history
+    (aggregation_job_id, step, lease_attempts, outcome, error, http_status, problem_type,
+     retry_delay_ms, created_at)
+SELECT id, step, lease_attempts, $1, $2, $3, $4, $5, $6
+FROM aggregation_jobs
+WHERE aggregation_jobs.task_id = $7
+  AND aggregation_jobs.aggregation_job_id = $8
+  AND UPPER(aggregation_jobs.client_timestamp_interval) >= $9
+RETURNING aggregation_job_id",
             )
             .await?;
-        check_single_row_mutation(
-            self.execute(
+        let aggregation_job_pkey: i64 = self
+            .query_opt(
                 &stmt,
                 &[
-                    /* state */ &aggregation_job.state(),
-                    /* step */ &(u16::from(aggregation_job.step()) as i32),
-                    /* last_request_hash */ &aggregation_job.last_request_hash(),
-                    /* updated_at */ &now,
-                    /* updated_by */ &self.name,
+                    /* outcome */ record.outcome(),
+                    /* error */ &record.error(),
+                    /* http_status */ &record.http_status().map(i32::from),
+                    /* problem_type */ &record.problem_type(),
+                    /* retry_delay_ms */
+                    &record
+                        .retry_delay()
+                        .map(|retry_delay| i64::try_from(retry_delay.as_millis()))
+                        .transpose()?,
+                    /* created_at */ &now,
                     /* task_id */ &task_info.pkey,
-                    /* aggregation_job_id */ &aggregation_job.id().as_ref(),
+                    /* aggregation_job_id */ &aggregation_job_id.as_ref(),
                     /* threshold */ &task_info.report_expiry_threshold(&now)?,
                 ],
             )
-            .await?,
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?
+            .get("aggregation_job_id");
+
+        let stmt = self
+            .prepare_cached(
+                "-- put_aggregation_job_history_entry()
+DELETE FROM aggregation_job_history
+WHERE aggregation_job_id = $1
+  AND id NOT IN (
+      SELECT id FROM aggregation_job_history
+      WHERE aggregation_job_id = $1
+      ORDER BY id DESC
+      LIMIT $2)",
+            )
+            .await?;
+        self.execute(
+            &stmt,
+            &[
+                /* aggregation_job_id */ &aggregation_job_pkey,
+                /* limit */ &i64::try_from(MAX_JOB_HISTORY_ENTRIES)?,
+            ],
         )
+        .await?;
+        Ok(())
     }
 
-    /// get_report_aggregations_for_aggregation_job retrieves all report aggregations associated
-    /// with a given aggregation job, ordered by their natural ordering.
+    /// get_aggregation_job_history retrieves the recorded history of unsuccessful attempts to step
+    /// the given leader aggregation job, most recent first. It returns `None` if no such
+    /// aggregation job exists.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn get_report_aggregations_for_aggregation_job<
-        const SEED_SIZE: usize,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    pub async fn get_aggregation_job_history(
         &self,
-        vdaf: &A,
-        role: &Role,
         task_id: &TaskId,
         aggregation_job_id: &AggregationJobId,
-    ) -> Result<Vec<ReportAggregation<SEED_SIZE, A>>, Error> {
+    ) -> Result<Option<Vec<JobHistoryEntry>>, Error> {
         let task_info = match self.task_info_for(task_id).await? {
             Some(task_info) => task_info,
-            None => return Ok(Vec::new()),
+            None => return Ok(None),
         };
+        let now = self.clock.now().as_naive_date_time()?;
 
         let stmt = self
             .prepare_cached(
-                "-- get_report_aggregations_for_aggregation_job()
+                "-- get_aggregation_job_history()
 SELECT
-    ord, client_report_id, client_timestamp, last_prep_resp,
-    report_aggregations.state, public_extensions, public_share,
-    leader_private_extensions, leader_input_share,
-    helper_encrypted_input_share, leader_prep_transition, leader_prep_state,
-    prepare_init, require_taskbind_extension,
-    helper_prep_state, prepare_continue, error_code
-FROM report_aggregations
-JOIN aggregation_jobs ON aggregation_jobs.id = report_aggregations.aggregation_job_id
-WHERE report_aggregations.task_id = $1
-  AND aggregation_jobs.task_id = $1
+    aggregation_job_history.id, aggregation_job_history.step::BIGINT AS step,
+    aggregation_job_history.lease_attempts, aggregation_job_history.outcome,
+    aggregation_job_history.error, aggregation_job_history.http_status,
+    aggregation_job_history.problem_type, aggregation_job_history.retry_delay_ms,
+    aggregation_job_history.created_at
+FROM aggregation_jobs
+LEFT JOIN aggregation_job_history
+    ON aggregation_job_history.aggregation_job_id = aggregation_jobs.id
+WHERE aggregation_jobs.task_id = $1
   AND aggregation_jobs.aggregation_job_id = $2
-  AND UPPER(client_timestamp_interval) >= $3
-ORDER BY ord ASC",
+  AND UPPER(aggregation_jobs.client_timestamp_interval) >= $3
+ORDER BY aggregation_job_history.id DESC",
+            )
+            .await?;
+        let rows = self
+            .query(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* aggregation_job_id */ &aggregation_job_id.as_ref(),
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                ],
+            )
+            .await?;
+        job_history_from_rows(rows)
+    }
+
+    /// get_abandoned_aggregation_jobs retrieves all unexpired aggregation jobs in the abandoned
+    /// state, optionally restricted to a single task.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_abandoned_aggregation_jobs(
+        &self,
+        task_id: Option<&TaskId>,
+    ) -> Result<Vec<AbandonedAggregationJob>, Error> {
+        let now = self.clock.now().as_naive_date_time()?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_abandoned_aggregation_jobs()
+SELECT
+    tasks.task_id, aggregation_jobs.aggregation_job_id,
+    aggregation_jobs.client_timestamp_interval, aggregation_jobs.step,
+    aggregation_jobs.last_error, aggregation_jobs.updated_at
+FROM aggregation_jobs
+JOIN tasks ON tasks.id = aggregation_jobs.task_id
+WHERE aggregation_jobs.state = 'ABANDONED'
+  AND ($1::BYTEA IS NULL OR tasks.task_id = $1)
+  AND UPPER(aggregation_jobs.client_timestamp_interval) >=
+      COALESCE($2::TIMESTAMP - tasks.report_expiry_age * '1 second'::INTERVAL,
+               '-infinity'::TIMESTAMP)
+ORDER BY aggregation_jobs.updated_at DESC",
             )
             .await?;
         self.query(
             &stmt,
             &[
-                /* task_id */ &task_info.pkey,
-                /* aggregation_job_id */ &aggregation_job_id.as_ref(),
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+                /* task_id */ &task_id.map(TaskId::get_encoded).transpose()?,
+                /* now */ &now,
             ],
         )
         .await?
         .into_iter()
         .map(|row| {
-            Self::report_aggregation_from_row(
-                vdaf,
-                role,
-                task_id,
-                aggregation_job_id,
-                &row.get_bytea_and_convert::<ReportId>("client_report_id")?,
-                &row,
-            )
+            Ok(AbandonedAggregationJob::new(
+                TaskId::get_decoded(row.get("task_id"))?,
+                row.get_bytea_and_convert::<AggregationJobId>("aggregation_job_id")?,
+                row.get::<_, SqlInterval>("client_timestamp_interval")
+                    .as_interval(),
+                row.get_postgres_integer_and_convert::<i32, _, _>("step")?,
+                row.get("last_error"),
+                Time::from_naive_date_time(&row.get("updated_at")),
+            ))
         })
         .collect()
     }
 
-    /// get_report_aggregation_by_report_id gets a report aggregation by report ID.
-    #[cfg(feature = "test-util")]
-    pub async fn get_report_aggregation_by_report_id<
-        const SEED_SIZE: usize,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    /// requeue_abandoned_aggregation_job returns an abandoned aggregation job to the active state,
+    /// with a fresh lease attempt counter, so that it will be picked up by the aggregation job
+    /// driver again. It returns `Error::MutationTargetNotFound` if no abandoned aggregation job
+    /// with the given ID exists in a leader task.
+    ///
+    /// Abandoning an aggregation job counts it as terminated in the batch aggregations it
+    /// contributes to; this method reverses that accounting so that the job is counted again once
+    /// it terminates. Note that the leader asks the helper to delete its view of an aggregation job
+    /// when abandoning it, so a requeued aggregation job which had already reached the helper may
+    /// fail again.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn requeue_abandoned_aggregation_job(
         &self,
-        vdaf: &A,
-        role: &Role,
         task_id: &TaskId,
         aggregation_job_id: &AggregationJobId,
-        report_id: &ReportId,
-    ) -> Result<Option<ReportAggregation<SEED_SIZE, A>>, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok(None),
-        };
+    ) -> Result<(), Error> {
+        let task_info = self
+            .task_info_for(task_id)
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?;
+        let now = self.clock.now().as_naive_date_time()?;
 
         let stmt = self
             .prepare_cached(
-                "-- get_report_aggregation_by_report_id()
-SELECT
-    ord, client_timestamp, last_prep_resp, report_aggregations.state,
-    public_extensions, public_share, leader_private_extensions,
-    leader_input_share, helper_encrypted_input_share, leader_prep_transition,
-    leader_prep_state, prepare_init,
-    require_taskbind_extension, helper_prep_state, prepare_continue, error_code
-FROM report_aggregations
-JOIN aggregation_jobs
-    ON aggregation_jobs.id = report_aggregations.aggregation_job_id
-WHERE report_aggregations.task_id = $1
-    AND aggregation_jobs.task_id = $1
-    AND aggregation_jobs.aggregation_job_id = $2
-    AND report_aggregations.client_report_id = $3
-    AND UPPER(client_timestamp_interval) >= $4",
+                "-- requeue_abandoned_aggregation_job()
+UPDATE aggregation_jobs
+SET state = 'ACTIVE',
+    lease_expiry = '-infinity'::TIMESTAMP,
+    lease_token = NULL,
+    lease_attempts = 0,
+    last_error = NULL,
+    updated_at = $1,
+    updated_by = $2
+FROM tasks
+WHERE tasks.id = aggregation_jobs.task_id
+  AND tasks.aggregator_role = 'LEADER'
+  AND aggregation_jobs.task_id = $3
+  AND aggregation_jobs.aggregation_job_id = $4
+  AND aggregation_jobs.state = 'ABANDONED'
+  AND UPPER(aggregation_jobs.client_timestamp_interval) >= $5
+RETURNING aggregation_jobs.id",
             )
             .await?;
-        self.query_opt(
-            &stmt,
-            &[
-                /* task_id */ &task_info.pkey,
-                /* aggregation_job_id */ &aggregation_job_id.as_ref(),
-                /* report_id */ &report_id.as_ref(),
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-            ],
-        )
-        .await?
-        .map(|row| {
-            Self::report_aggregation_from_row(
-                vdaf,
-                role,
-                task_id,
-                aggregation_job_id,
-                report_id,
-                &row,
+        let aggregation_job_pkey: i64 = self
+            .query_opt(
+                &stmt,
+                &[
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* task_id */ &task_info.pkey,
+                    /* aggregation_job_id */ &aggregation_job_id.as_ref(),
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                ],
             )
-        })
-        .transpose()
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?
+            .get("id");
+
+        self.adjust_aggregation_jobs_terminated(task_info.pkey, aggregation_job_pkey, -1)
+            .await
     }
 
-    /// get_report_aggregations_for_task retrieves all report aggregations associated with a given
-    /// task.
-    #[cfg(feature = "test-util")]
-    pub async fn get_report_aggregations_for_task<
-        const SEED_SIZE: usize,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    /// fail_aggregation_job forcibly moves an active or abandoned aggregation job in a leader task
+    /// to the abandoned state, failing all of its report aggregations which have not yet reached a
+    /// terminal state. Reports which had not yet been sent to the helper are restored and returned
+    /// to the pool of unaggregated reports (via `mark_report_unaggregated`), so that they may be
+    /// picked up by a new aggregation job; the IDs of these reports are returned. Reports whose
+    /// aggregation had progressed further can not be recovered, since their input shares are no
+    /// longer stored.
+    ///
+    /// It returns `Error::MutationTargetNotFound` if no such aggregation job exists.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn fail_aggregation_job(
         &self,
-        vdaf: &A,
-        role: &Role,
         task_id: &TaskId,
-    ) -> Result<Vec<ReportAggregation<SEED_SIZE, A>>, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok(Vec::new()),
-        };
+        aggregation_job_id: &AggregationJobId,
+    ) -> Result<Vec<ReportId>, Error> {
+        let task_info = self
+            .task_info_for(task_id)
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?;
+        let now = self.clock.now().as_naive_date_time()?;
+        let threshold = task_info.report_expiry_threshold(&now)?;
 
         let stmt = self
             .prepare_cached(
-                "-- get_report_aggregations_for_task()
-SELECT
-    aggregation_jobs.aggregation_job_id, ord, client_report_id,
-    client_timestamp, last_prep_resp, report_aggregations.state,
-    public_extensions, public_share, leader_private_extensions,
-    leader_input_share, helper_encrypted_input_share, leader_prep_transition,
-    leader_prep_state, prepare_init,
-    require_taskbind_extension, helper_prep_state, prepare_continue, error_code
+                "-- fail_aggregation_job()
+WITH target_job AS (
+    SELECT aggregation_jobs.id, aggregation_jobs.state
+    FROM aggregation_jobs
+    JOIN tasks ON tasks.id = aggregation_jobs.task_id
+    WHERE tasks.aggregator_role = 'LEADER'
+      AND aggregation_jobs.task_id = $3
+      AND aggregation_jobs.aggregation_job_id = $4
+      AND aggregation_jobs.state IN ('ACTIVE', 'ABANDONED')
+      AND UPPER(aggregation_jobs.client_timestamp_interval) >= $5
+    FOR UPDATE OF aggregation_jobs
+)
+UPDATE aggregation_jobs
+SET state = 'ABANDONED',
+    lease_expiry = '-infinity'::TIMESTAMP,
+    lease_token = NULL,
+    lease_attempts = 0,
+    updated_at = $1,
+    updated_by = $2
+FROM target_job
+WHERE aggregation_jobs.id = target_job.id
+RETURNING aggregation_jobs.id, target_job.state AS previous_state",
+            )
+            .await?;
+        let row = self
+            .query_opt(
+                &stmt,
+                &[
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* task_id */ &task_info.pkey,
+                    /* aggregation_job_id */ &aggregation_job_id.as_ref(),
+                    /* threshold */ &threshold,
+                ],
+            )
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?;
+        let aggregation_job_pkey: i64 = row.get("id");
+        let previous_state: AggregationJobState = row.get("previous_state");
+
+        // An active aggregation job has not yet been counted as terminated in its batch
+        // aggregations; count it now, as the aggregation job driver would have on abandonment.
+        if previous_state == AggregationJobState::Active {
+            self.adjust_aggregation_jobs_terminated(task_info.pkey, aggregation_job_pkey, 1)
+                .await?;
+        }
+
+        // Reports in the initial state still have their input shares stored alongside the report
+        // aggregation; copy them back to the (scrubbed) client report so that it can be
+        // aggregated again.
+        let stmt = self
+            .prepare_cached(
+                "-- fail_aggregation_job()
+UPDATE client_reports
+SET public_extensions = report_aggregations.public_extensions,
+    public_share = report_aggregations.public_share,
+    leader_private_extensions = report_aggregations.leader_private_extensions,
+    leader_input_share = report_aggregations.leader_input_share,
+    helper_encrypted_input_share = report_aggregations.helper_encrypted_input_share,
+    updated_at = $1,
+    updated_by = $2
 FROM report_aggregations
-JOIN aggregation_jobs ON aggregation_jobs.id = report_aggregations.aggregation_job_id
-WHERE report_aggregations.task_id = $1
-  AND aggregation_jobs.task_id = $1
-  AND UPPER(aggregation_jobs.client_timestamp_interval) >= $2",
+WHERE report_aggregations.aggregation_job_id = $3
+  AND report_aggregations.state = 'INIT'
+  AND client_reports.task_id = report_aggregations.task_id
+  AND client_reports.report_id = report_aggregations.client_report_id
+  AND client_reports.client_timestamp = report_aggregations.client_timestamp
+  AND client_reports.client_timestamp >= $4
+RETURNING client_reports.report_id",
             )
             .await?;
-        self.query(
+        let report_ids = self
+            .query(
+                &stmt,
+                &[
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* aggregation_job_id */ &aggregation_job_pkey,
+                    /* threshold */ &threshold,
+                ],
+            )
+            .await?
+            .into_iter()
+            .map(|row| row.get_bytea_and_convert::<ReportId>("report_id"))
+            .collect::<Result<Vec<_>, _>>()?;
+        try_join_all(
+            report_ids
+                .iter()
+                .map(|report_id| self.mark_report_unaggregated(task_id, report_id)),
+        )
+        .await?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- fail_aggregation_job()
+UPDATE report_aggregations
+SET state = 'FAILED',
+    public_extensions = NULL,
+    public_share = NULL,
+    leader_private_extensions = NULL,
+    leader_input_share = NULL,
+    helper_encrypted_input_share = NULL,
+    leader_prep_transition = NULL,
+    leader_prep_state = NULL,
+    error_code = $1,
+    updated_at = $2,
+    updated_by = $3
+WHERE aggregation_job_id = $4
+  AND state NOT IN ('FINISHED', 'FAILED')",
+            )
+            .await?;
+        self.execute(
             &stmt,
             &[
-                /* task_id */ &task_info.pkey,
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+                /* error_code */ &(ReportError::ReportDropped as i16),
+                /* updated_at */ &now,
+                /* updated_by */ &self.name,
+                /* aggregation_job_id */ &aggregation_job_pkey,
             ],
         )
-        .await?
-        .into_iter()
-        .map(|row| {
-            Self::report_aggregation_from_row(
-                vdaf,
-                role,
-                task_id,
-                &row.get_bytea_and_convert::<AggregationJobId>("aggregation_job_id")?,
-                &row.get_bytea_and_convert::<ReportId>("client_report_id")?,
-                &row,
-            )
-        })
-        .collect()
-    }
+        .await?;
 
-    fn report_aggregation_from_row<const SEED_SIZE: usize, A: AsyncAggregator<SEED_SIZE>>(
-        vdaf: &A,
-        role: &Role,
-        task_id: &TaskId,
-        aggregation_job_id: &AggregationJobId,
-        report_id: &ReportId,
-        row: &Row,
-    ) -> Result<ReportAggregation<SEED_SIZE, A>, Error> {
-        let ord: u64 = row.get_bigint_and_convert("ord")?;
-        let time = Time::from_naive_date_time(&row.get("client_timestamp"));
-        let state: ReportAggregationStateCode = row.get("state");
-        let error_code: Option<i16> = row.get("error_code");
-        let last_prep_resp_bytes: Option<Vec<u8>> = row.get("last_prep_resp");
+        Ok(report_ids)
+    }
 
-        let last_prep_resp = last_prep_resp_bytes
-            .map(|bytes| PrepareResp::get_decoded(&bytes))
-            .transpose()?;
+    /// adjust_aggregation_jobs_terminated adds `delta` to the count of terminated aggregation jobs
+    /// of each batch aggregation to which the given leader aggregation job contributes. As with the
+    /// aggregation job writer, batch aggregations which are no longer accepting aggregations are
+    /// left alone. Only a single shard of each batch aggregation is updated, since the counts are
+    /// summed across shards when read.
+    async fn adjust_aggregation_jobs_terminated(
+        &self,
+        task_pkey: i64,
+        aggregation_job_pkey: i64,
+        delta: i64,
+    ) -> Result<(), Error> {
+        let stmt = self
+            .prepare_cached(
+                "-- adjust_aggregation_jobs_terminated()
+WITH target_shards AS (
+    SELECT DISTINCT ON (batch_aggregations.batch_identifier) batch_aggregations.id
+    FROM batch_aggregations
+    JOIN aggregation_jobs ON aggregation_jobs.id = $2
+    WHERE batch_aggregations.task_id = $1
+      AND batch_aggregations.aggregation_param = aggregation_jobs.aggregation_param
+      AND batch_aggregations.state = 'AGGREGATING'
+      AND CASE
+              -- Leader-selected batches are identified by the aggregation job's batch ID.
+              WHEN batch_aggregations.batch_interval IS NULL
+                  THEN batch_aggregations.batch_identifier = aggregation_jobs.batch_id
+              -- Time-interval batches are identified by the timestamps of the job's reports.
+              ELSE EXISTS(
+                  SELECT 1 FROM report_aggregations
+                  WHERE report_aggregations.aggregation_job_id = aggregation_jobs.id
+                    AND report_aggregations.client_timestamp <@ batch_aggregations.batch_interval)
+          END
+    ORDER BY batch_aggregations.batch_identifier, batch_aggregations.id
+)
+UPDATE batch_aggregations
+SET aggregation_jobs_terminated = aggregation_jobs_terminated + $3,
+    updated_at = $4,
+    updated_by = $5
+WHERE id IN (SELECT id FROM target_shards)",
+            )
+            .await?;
+        self.execute(
+            &stmt,
+            &[
+                /* task_id */ &task_pkey,
+                /* aggregation_job_id */ &aggregation_job_pkey,
+                /* delta */ &delta,
+                /* updated_at */ &self.clock.now().as_naive_date_time()?,
+                /* updated_by */ &self.name,
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// put_aggregation_job stores an aggregation job.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn put_aggregation_job<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        aggregation_job: &AggregationJob<SEED_SIZE, B, A>,
+    ) -> Result<(), Error> {
+        self.put_aggregation_job_record(&AggregationJobRecord::try_from(aggregation_job)?)
+            .await
+    }
+
+    /// put_aggregation_job_record stores an aggregation job given in its type-erased form, as
+    /// [`Self::put_aggregation_job`] does.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn put_aggregation_job_record(
+        &self,
+        aggregation_job: &AggregationJobRecord,
+    ) -> Result<(), Error> {
+        let task_info = self
+            .task_info_for(aggregation_job.task_id())
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?;
+        let now = self.clock.now().as_naive_date_time()?;
+
+        aggregation_job
+            .client_timestamp_interval()
+            .validate_precision(&task_info.time_precision)
+            .map_err(|e| {
+                Self::unaligned_time_error(aggregation_job.task_id(), &task_info.time_precision, e)
+            })?;
+
+        // If there is a conflict, the we upsert the incoming aggregation job (excluded) if the
+        // existing aggregation job is expired (virtually GCed; would be invisible to other queries
+        // that retrieve aggregation job rows).
+        // https://github.com/divviup/janus/pull/2818
+        let stmt = self
+            .prepare_cached(
+                "-- put_aggregation_job()
+INSERT INTO aggregation_jobs
+    (task_id, aggregation_job_id, aggregation_param, batch_id,
+    client_timestamp_interval, state, step, last_request_hash,
+    created_at, updated_at, updated_by)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+ON CONFLICT(task_id, aggregation_job_id) DO UPDATE
+    SET (
+        aggregation_param, batch_id, client_timestamp_interval, state, step,
+        last_request_hash, created_at, updated_at, updated_by
+    ) = (
+        excluded.aggregation_param, excluded.batch_id,
+        excluded.client_timestamp_interval, excluded.state, excluded.step,
+        excluded.last_request_hash, excluded.created_at, excluded.updated_at,
+        excluded.updated_by
+    )
+    WHERE UPPER(aggregation_jobs.client_timestamp_interval) < $12",
+            )
+            .await?;
+        check_insert(
+            self.execute(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* aggregation_job_id */ &aggregation_job.aggregation_job_id().as_ref(),
+                    /* aggregation_param */ &aggregation_job.encoded_aggregation_param(),
+                    /* batch_id */ &aggregation_job.encoded_batch_id(),
+                    /* client_timestamp_interval */
+                    &SqlInterval::from(aggregation_job.client_timestamp_interval()),
+                    /* state */ &aggregation_job.state(),
+                    /* step */ &(u16::from(aggregation_job.step()) as i32),
+                    /* last_request_hash */ &aggregation_job.last_request_hash(),
+                    /* created_at */ &now,
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                ],
+            )
+            .await?,
+        )
+    }
+
+    /// update_aggregation_job updates a stored aggregation job.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn update_aggregation_job<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        aggregation_job: &AggregationJob<SEED_SIZE, B, A>,
+    ) -> Result<(), Error> {
+        let task_info = self
+            .task_info_for(aggregation_job.task_id())
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?;
+        let now = self.clock.now().as_naive_date_time()?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- update_aggregation_job()
+UPDATE aggregation_jobs SET
+    state = $1,
+    step = $2,
+    last_request_hash = $3,
+    updated_at = $4,
+    updated_by = $5
+WHERE aggregation_jobs.task_id = $6
+  AND aggregation_jobs.aggregation_job_id = $7
+  AND UPPER(aggregation_jobs.client_timestamp_interval) >= $8::TIMESTAMP",
+            )
+            .await?;
+        check_single_row_mutation(
+            self.execute(
+                &stmt,
+                &[
+                    /* state */ &aggregation_job.state(),
+                    /* step */ &(u16::from(aggregation_job.step()) as i32),
+                    /* last_request_hash */ &aggregation_job.last_request_hash(),
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* task_id */ &task_info.pkey,
+                    /* aggregation_job_id */ &aggregation_job.id().as_ref(),
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                ],
+            )
+            .await?,
+        )
+    }
+
+    /// get_report_aggregations_for_aggregation_job retrieves all report aggregations associated
+    /// with a given aggregation job, ordered by their natural ordering.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_report_aggregations_for_aggregation_job<
+        const SEED_SIZE: usize,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        vdaf: &A,
+        role: &Role,
+        task_id: &TaskId,
+        aggregation_job_id: &AggregationJobId,
+    ) -> Result<Vec<ReportAggregation<SEED_SIZE, A>>, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(Vec::new()),
+        };
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_report_aggregations_for_aggregation_job()
+SELECT
+    ord, client_report_id, client_timestamp, last_prep_resp,
+    report_aggregations.state, public_extensions, public_share,
+    leader_private_extensions, leader_input_share,
+    helper_encrypted_input_share, leader_prep_transition, leader_prep_state,
+    prepare_init, require_taskbind_extension,
+    helper_prep_state, prepare_continue, error_code
+FROM report_aggregations
+JOIN aggregation_jobs ON aggregation_jobs.id = report_aggregations.aggregation_job_id
+WHERE report_aggregations.task_id = $1
+  AND aggregation_jobs.task_id = $1
+  AND aggregation_jobs.aggregation_job_id = $2
+  AND UPPER(client_timestamp_interval) >= $3
+ORDER BY ord ASC",
+            )
+            .await?;
+        self.query(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* aggregation_job_id */ &aggregation_job_id.as_ref(),
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
+        )
+        .await?
+        .into_iter()
+        .map(|row| {
+            Self::report_aggregation_from_row(
+                vdaf,
+                role,
+                task_id,
+                aggregation_job_id,
+                &row.get_bytea_and_convert::<ReportId>("client_report_id")?,
+                &row,
+            )
+        })
+        .collect()
+    }
+
+    /// get_report_aggregation_by_report_id gets a report aggregation by report ID.
+    #[cfg(feature = "test-util")]
+    pub async fn get_report_aggregation_by_report_id<
+        const SEED_SIZE: usize,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        vdaf: &A,
+        role: &Role,
+        task_id: &TaskId,
+        aggregation_job_id: &AggregationJobId,
+        report_id: &ReportId,
+    ) -> Result<Option<ReportAggregation<SEED_SIZE, A>>, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(None),
+        };
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_report_aggregation_by_report_id()
+SELECT
+    ord, client_timestamp, last_prep_resp, report_aggregations.state,
+    public_extensions, public_share, leader_private_extensions,
+    leader_input_share, helper_encrypted_input_share, leader_prep_transition,
+    leader_prep_state, prepare_init,
+    require_taskbind_extension, helper_prep_state, prepare_continue, error_code
+FROM report_aggregations
+JOIN aggregation_jobs
+    ON aggregation_jobs.id = report_aggregations.aggregation_job_id
+WHERE report_aggregations.task_id = $1
+    AND aggregation_jobs.task_id = $1
+    AND aggregation_jobs.aggregation_job_id = $2
+    AND report_aggregations.client_report_id = $3
+    AND UPPER(client_timestamp_interval) >= $4",
+            )
+            .await?;
+        self.query_opt(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* aggregation_job_id */ &aggregation_job_id.as_ref(),
+                /* report_id */ &report_id.as_ref(),
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
+        )
+        .await?
+        .map(|row| {
+            Self::report_aggregation_from_row(
+                vdaf,
+                role,
+                task_id,
+                aggregation_job_id,
+                report_id,
+                &row,
+            )
+        })
+        .transpose()
+    }
+
+    /// get_report_aggregations_for_task retrieves all report aggregations associated with a given
+    /// task.
+    #[cfg(feature = "test-util")]
+    pub async fn get_report_aggregations_for_task<
+        const SEED_SIZE: usize,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        vdaf: &A,
+        role: &Role,
+        task_id: &TaskId,
+    ) -> Result<Vec<ReportAggregation<SEED_SIZE, A>>, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(Vec::new()),
+        };
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_report_aggregations_for_task()
+SELECT
+    aggregation_jobs.aggregation_job_id, ord, client_report_id,
+    client_timestamp, last_prep_resp, report_aggregations.state,
+    public_extensions, public_share, leader_private_extensions,
+    leader_input_share, helper_encrypted_input_share, leader_prep_transition,
+    leader_prep_state, prepare_init,
+    require_taskbind_extension, helper_prep_state, prepare_continue, error_code
+FROM report_aggregations
+JOIN aggregation_jobs ON aggregation_jobs.id = report_aggregations.aggregation_job_id
+WHERE report_aggregations.task_id = $1
+  AND aggregation_jobs.task_id = $1
+  AND UPPER(aggregation_jobs.client_timestamp_interval) >= $2",
+            )
+            .await?;
+        self.query(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
+        )
+        .await?
+        .into_iter()
+        .map(|row| {
+            Self::report_aggregation_from_row(
+                vdaf,
+                role,
+                task_id,
+                &row.get_bytea_and_convert::<AggregationJobId>("aggregation_job_id")?,
+                &row.get_bytea_and_convert::<ReportId>("client_report_id")?,
+                &row,
+            )
+        })
+        .collect()
+    }
+
+    fn report_aggregation_from_row<const SEED_SIZE: usize, A: AsyncAggregator<SEED_SIZE>>(
+        vdaf: &A,
+        role: &Role,
+        task_id: &TaskId,
+        aggregation_job_id: &AggregationJobId,
+        report_id: &ReportId,
+        row: &Row,
+    ) -> Result<ReportAggregation<SEED_SIZE, A>, Error> {
+        let ord: u64 = row.get_bigint_and_convert("ord")?;
+        let time = Time::from_naive_date_time(&row.get("client_timestamp"));
+        let state: ReportAggregationStateCode = row.get("state");
+        let error_code: Option<i16> = row.get("error_code");
+        let last_prep_resp_bytes: Option<Vec<u8>> = row.get("last_prep_resp");
+
+        let last_prep_resp = last_prep_resp_bytes
+            .map(|bytes| PrepareResp::get_decoded(&bytes))
+            .transpose()?;
+
+        let agg_state = match state {
+            ReportAggregationStateCode::Init => {
+                let public_extensions_bytes = row
+                    .get::<_, Option<Vec<u8>>>("public_extensions")
+                    .ok_or_else(|| {
+                        Error::DbState(
+                            "report aggregation in state INIT but public_extensions is NULL"
+                                .to_string(),
+                        )
+                    })?;
+                let public_share_bytes =
+                    row.get::<_, Option<Vec<u8>>>("public_share")
+                        .ok_or_else(|| {
+                            Error::DbState(
+                                "report aggregation in state INIT but public_share is NULL"
+                                    .to_string(),
+                            )
+                        })?;
+                let leader_private_extensions_bytes = row
+                    .get::<_, Option<Vec<u8>>>("leader_private_extensions")
+                    .ok_or_else(|| {
+                        Error::DbState(
+                            "report aggregation in state INIT but leader_private_extensions is NULL"
+                                .to_string(),
+                        )
+                    })?;
+                let leader_input_share_bytes = row
+                    .get::<_, Option<Vec<u8>>>("leader_input_share")
+                    .ok_or_else(|| {
+                        Error::DbState(
+                            "report aggregation in state INIT but leader_input_share is NULL"
+                                .to_string(),
+                        )
+                    })?;
+                let helper_encrypted_input_share_bytes =
+                    row.get::<_, Option<Vec<u8>>>("helper_encrypted_input_share")
+                        .ok_or_else(|| {
+                            Error::DbState(
+                            "report aggregation in state INIT but helper_encrypted_input_share is NULL"
+                                .to_string(),
+                        )
+                        })?;
+
+                let public_extensions =
+                    decode_u16_items(&(), &mut Cursor::new(&public_extensions_bytes))?;
+                let public_share =
+                    A::PublicShare::get_decoded_with_param(vdaf, &public_share_bytes)?;
+                let leader_private_extensions =
+                    decode_u16_items(&(), &mut Cursor::new(&leader_private_extensions_bytes))?;
+                let leader_input_share = A::InputShare::get_decoded_with_param(
+                    &(vdaf, Role::Leader.index().unwrap()),
+                    &leader_input_share_bytes,
+                )?;
+                let helper_encrypted_input_share =
+                    HpkeCiphertext::get_decoded(&helper_encrypted_input_share_bytes)?;
+
+                ReportAggregationState::LeaderInit {
+                    public_extensions,
+                    public_share,
+                    leader_private_extensions,
+                    leader_input_share,
+                    helper_encrypted_input_share,
+                }
+            }
+
+            ReportAggregationStateCode::InitProcessing => {
+                let prepare_init_bytes =
+                    row.get::<_, Option<Vec<u8>>>("prepare_init")
+                        .ok_or_else(|| {
+                            Error::DbState(
+                            "report aggregation in state INIT_PROCESSING but prepare_init is NULL"
+                                .to_string(),
+                        )
+                        })?;
+                let require_taskbind_extension = row.get::<_, Option<bool>>("require_taskbind_extension")
+                        .ok_or_else(|| {
+                            Error::DbState(
+                                "report aggregation in state INIT_PROCESSING but require_taskbind_extension is NULL"
+                                    .to_string(),
+                            )
+                        })?;
+
+                let prepare_init = PrepareInit::get_decoded(&prepare_init_bytes)?;
+
+                ReportAggregationState::HelperInitProcessing {
+                    prepare_init,
+                    require_taskbind_extension,
+                }
+            }
+
+            ReportAggregationStateCode::Continue => {
+                match role {
+                    Role::Leader => {
+                        let leader_prep_transition_bytes = row
+                            .get::<_, Option<Vec<u8>>>("leader_prep_transition")
+                            .ok_or_else(|| {
+                                Error::DbState(
+                                    "report aggregation in state CONTINUE but leader_prep_transition is NULL"
+                                        .to_string(),
+                                )
+                            })?;
+                        let ping_pong_transition = PingPongContinuation::get_decoded_with_param(
+                            &(vdaf, 0 /* leader */),
+                            &leader_prep_transition_bytes,
+                        )?;
+
+                        ReportAggregationState::LeaderContinue {
+                            continuation: ping_pong_transition,
+                        }
+                    }
+                    Role::Helper => {
+                        let helper_prep_state_bytes = row
+                            .get::<_, Option<Vec<u8>>>("helper_prep_state")
+                            .ok_or_else(|| {
+                                Error::DbState(
+                                    "report aggregation in state CONTINUE but helper_prep_state is NULL"
+                                        .to_string(),
+                                )
+                            })?;
+                        let prepare_state = A::PrepareState::get_decoded_with_param(
+                            &(vdaf, 1 /* helper */),
+                            &helper_prep_state_bytes,
+                        )?;
+
+                        ReportAggregationState::HelperContinue { prepare_state }
+                    }
+                    _ => panic!("unexpected role"),
+                }
+            }
+
+            ReportAggregationStateCode::ContinueProcessing => {
+                let helper_prep_state_bytes = row
+                    .get::<_, Option<Vec<u8>>>("helper_prep_state")
+                    .ok_or_else(|| {
+                        Error::DbState(
+                            "report aggregation in state CONTINUE_PROCESSING but helper_prep_state is NULL"
+                                .to_string(),
+                        )
+                    })?;
+                let prepare_continue_bytes = row
+                    .get::<_, Option<Vec<u8>>>("prepare_continue")
+                    .ok_or_else(|| {
+                        Error::DbState(
+                            "report aggregation in state CONTINUE_PROCESSING but message is NULL"
+                                .to_string(),
+                        )
+                    })?;
+
+                let prepare_state = A::PrepareState::get_decoded_with_param(
+                    &(vdaf, 1 /* helper */),
+                    &helper_prep_state_bytes,
+                )?;
+                let prepare_continue = PrepareContinue::get_decoded(&prepare_continue_bytes)?;
+
+                ReportAggregationState::HelperContinueProcessing {
+                    prepare_state,
+                    prepare_continue,
+                }
+            }
+
+            ReportAggregationStateCode::PollInit => {
+                row.get::<_, Option<Vec<u8>>>("leader_prep_state")
+                    .ok_or_else(|| {
+                        Error::DbState(
+                            "report aggregation in state POLL_INIT but leader_prep_state is NULL"
+                                .to_string(),
+                        )
+                    })
+                    .and_then(|encoded| {
+                        Ok(ReportAggregationState::LeaderPollInit {
+                            prepare_state: A::PrepareState::get_decoded_with_param(
+                                &(vdaf, 0 /* leader */),
+                                &encoded,
+                            )?,
+                        })
+                    })?
+            }
+
+            ReportAggregationStateCode::PollContinue => {
+                row.get::<_, Option<Vec<u8>>>("leader_prep_transition")
+                    .ok_or_else(|| {
+                        Error::DbState(
+                            "report aggregation in state POLL_CONTINUE but leader_prep_transition is NULL"
+                                .to_string(),
+                        )
+                    })
+                    .and_then(|encoded| {
+                        Ok(ReportAggregationState::LeaderPollContinue {
+                            continuation: PingPongContinuation::get_decoded_with_param(
+                                &(vdaf, 0 /* leader */),
+                                &encoded,
+                            )?,
+                        })
+                    })?
+            }
+
+            ReportAggregationStateCode::Finished => ReportAggregationState::Finished,
+
+            ReportAggregationStateCode::Failed => {
+                let report_error = match error_code {
+                    Some(c) => {
+                        let c: u8 = c.try_into().map_err(|err| {
+                            Error::DbState(format!("couldn't convert error_code value: {err}"))
+                        })?;
+                        Some(c.try_into().map_err(|err| {
+                            Error::DbState(format!("couldn't convert error_code value: {err}"))
+                        })?)
+                    }
+                    None => None,
+                }
+                .ok_or_else(|| {
+                    Error::DbState(
+                        "report aggregation in state FAILED but error_code is NULL".to_string(),
+                    )
+                })?;
+
+                ReportAggregationState::Failed { report_error }
+            }
+        };
+
+        Ok(ReportAggregation::new(
+            *task_id,
+            *aggregation_job_id,
+            *report_id,
+            time,
+            ord,
+            last_prep_resp,
+            agg_state,
+        ))
+    }
+
+    /// delete_expired_report_aggregation deletes the report aggregation at the given position of an
+    /// aggregation job if the aggregation job is expired (virtually GCed; would be invisible to
+    /// other queries that retrieve report aggregation rows), so that it can be replaced.
+    ///
+    /// Expired report aggregations are deleted, rather than upserted over, since
+    /// report_aggregations may be partitioned by client timestamp, in which case the database only
+    /// enforces uniqueness of each position per client timestamp.
+    /// https://github.com/divviup/janus/pull/2818
+    async fn delete_expired_report_aggregation(
+        &self,
+        task_pkey: i64,
+        aggregation_job_id: &AggregationJobId,
+        ord: i64,
+        threshold: &Timestamp<NaiveDateTime>,
+    ) -> Result<(), Error> {
+        let stmt = self
+            .prepare_cached(
+                "-- delete_expired_report_aggregation()
+DELETE FROM report_aggregations
+USING aggregation_jobs
+WHERE report_aggregations.aggregation_job_id = aggregation_jobs.id
+  AND report_aggregations.task_id = $1
+  AND report_aggregations.ord = $3
+  AND aggregation_jobs.task_id = $1
+  AND aggregation_jobs.aggregation_job_id = $2
+  AND UPPER(aggregation_jobs.client_timestamp_interval) < $4",
+            )
+            .await?;
+        self.execute(
+            &stmt,
+            &[
+                /* task_id */ &task_pkey,
+                /* aggregation_job_id */ &aggregation_job_id.as_ref(),
+                /* ord */ &ord,
+                /* threshold */ threshold,
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// put_report_aggregation stores aggregation data for a single report.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn put_report_aggregation<const SEED_SIZE: usize, A: AsyncAggregator<SEED_SIZE>>(
+        &self,
+        report_aggregation: &ReportAggregation<SEED_SIZE, A>,
+    ) -> Result<(), Error> {
+        let task_info = match self.task_info_for(report_aggregation.task_id()).await? {
+            Some(task_info) => task_info,
+            None => return Err(Error::MutationTargetNotFound),
+        };
+        let now = self.clock.now().as_naive_date_time()?;
+
+        let encoded_state_values = report_aggregation.state().encoded_values_from_state()?;
+        let encoded_last_prep_resp: Option<Vec<u8>> = report_aggregation
+            .last_prep_resp()
+            .map(PrepareResp::get_encoded)
+            .transpose()?;
+
+        report_aggregation
+            .time()
+            .validate_precision(&task_info.time_precision)
+            .map_err(|e| {
+                Self::unaligned_time_error(
+                    report_aggregation.task_id(),
+                    &task_info.time_precision,
+                    e,
+                )
+            })?;
+
+        // If there is a conflict, we replace the existing report aggregation with the incoming
+        // report aggregation if the existing report aggregation is expired.
+        //
+        // report_aggregations may be partitioned by client timestamp, so the database only
+        // enforces uniqueness of (aggregation job, ord) per client timestamp, and the insert below
+        // checks the rest. Report aggregations are only written by the transaction which writes
+        // their aggregation job, which conflicts with any concurrent writer of the same job.
+        // Replay protection for reports themselves is provided by put_client_report &
+        // put_scrubbed_report.
+        let ord = TryInto::<i64>::try_into(report_aggregation.ord())?;
+        self.delete_expired_report_aggregation(
+            task_info.pkey,
+            report_aggregation.aggregation_job_id(),
+            ord,
+            &task_info.report_expiry_threshold(&now)?,
+        )
+        .await?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- put_report_aggregation()
+INSERT INTO report_aggregations
+    (task_id, aggregation_job_id, ord, client_report_id, client_timestamp,
+    last_prep_resp, state, public_extensions, public_share,
+    leader_private_extensions, leader_input_share,
+    helper_encrypted_input_share, leader_prep_transition, leader_prep_state,
+    prepare_init, require_taskbind_extension,
+    helper_prep_state, prepare_continue, error_code, created_at, updated_at,
+    updated_by)
+SELECT
+    $1, aggregation_jobs.id, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14,
+    $15, $16, $17, $18, $19, $20, $21, $22
+FROM aggregation_jobs
+WHERE task_id = $1
+  AND aggregation_job_id = $2
+  AND NOT EXISTS (
+    SELECT 1 FROM report_aggregations
+    WHERE report_aggregations.task_id = $1
+      AND report_aggregations.aggregation_job_id = aggregation_jobs.id
+      AND report_aggregations.ord = $3
+  )
+ON CONFLICT DO NOTHING",
+            )
+            .await?;
+        check_insert(
+            self.execute(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* aggregation_job_id */
+                    &report_aggregation.aggregation_job_id().as_ref(),
+                    /* ord */ &ord,
+                    /* client_report_id */ &report_aggregation.report_id().as_ref(),
+                    /* client_timestamp */ &report_aggregation.time().as_naive_date_time()?,
+                    /* last_prep_resp */ &encoded_last_prep_resp,
+                    /* state */ &report_aggregation.state().state_code(),
+                    /* public_extensions */ &encoded_state_values.public_extensions,
+                    /* public_share */ &encoded_state_values.public_share,
+                    /* leader_private_extensions */
+                    &encoded_state_values.leader_private_extensions,
+                    /* leader_input_share */ &encoded_state_values.leader_input_share,
+                    /* helper_encrypted_input_share */
+                    &encoded_state_values.helper_encrypted_input_share,
+                    /* leader_prep_transition */
+                    &encoded_state_values.leader_prep_continuation,
+                    /* leader_prep_state */ &encoded_state_values.leader_prep_state,
+                    /* prepare_init */ &encoded_state_values.prepare_init,
+                    /* require_taskbind_extension */
+                    &encoded_state_values.require_taskbind_extension,
+                    /* helper_prep_state */ &encoded_state_values.helper_prep_state,
+                    /* prepare_continue */ &encoded_state_values.prepare_continue,
+                    /* error_code */ &encoded_state_values.report_error,
+                    /* created_at */ &now,
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                ],
+            )
+            .await?,
+        )
+    }
+
+    /// Creates a report aggregation in the `LeaderInit` state from its metadata.
+    ///
+    /// Report shares are copied directly from the `client_reports` table.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn put_leader_report_aggregation(
+        &self,
+        report_aggregation_metadata: &ReportAggregationMetadata,
+    ) -> Result<(), Error> {
+        let task_info = self
+            .task_info_for(report_aggregation_metadata.task_id())
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?;
+        let now = self.clock.now().as_naive_date_time()?;
+
+        report_aggregation_metadata
+            .time()
+            .validate_precision(&task_info.time_precision)
+            .map_err(|e| {
+                Self::unaligned_time_error(
+                    report_aggregation_metadata.task_id(),
+                    &task_info.time_precision,
+                    e,
+                )
+            })?;
+
+        // If there is a conflict, we replace the existing report aggregation with the incoming
+        // report aggregation if the existing report aggregation is expired.
+        let ord = TryInto::<i64>::try_into(report_aggregation_metadata.ord())?;
+        self.delete_expired_report_aggregation(
+            task_info.pkey,
+            report_aggregation_metadata.aggregation_job_id(),
+            ord,
+            &task_info.report_expiry_threshold(&now)?,
+        )
+        .await?;
+
+        match report_aggregation_metadata.state() {
+            ReportAggregationMetadataState::Init => {
+                let stmt = self
+                    .prepare_cached(
+                        "-- put_leader_report_aggregation()
+INSERT INTO report_aggregations
+    (task_id, aggregation_job_id, ord, client_report_id, client_timestamp,
+    state, public_extensions, public_share, leader_private_extensions,
+    leader_input_share, helper_encrypted_input_share, created_at, updated_at,
+    updated_by)
+SELECT
+    $1, aggregation_jobs.id, $3, $4, $5, 'INIT'::REPORT_AGGREGATION_STATE,
+    client_reports.public_extensions, client_reports.public_share,
+    client_reports.leader_private_extensions,
+    client_reports.leader_input_share,
+    client_reports.helper_encrypted_input_share, $6, $7, $8
+FROM aggregation_jobs
+JOIN client_reports
+    ON aggregation_jobs.task_id = client_reports.task_id
+AND client_reports.report_id = $4
+AND client_reports.client_timestamp = $5
+WHERE aggregation_jobs.task_id = $1
+AND aggregation_job_id = $2
+AND NOT EXISTS (
+    SELECT 1 FROM report_aggregations
+    WHERE report_aggregations.task_id = $1
+      AND report_aggregations.aggregation_job_id = aggregation_jobs.id
+      AND report_aggregations.ord = $3
+)
+ON CONFLICT DO NOTHING",
+                    )
+                    .await?;
+                check_insert(
+                    self.execute(
+                        &stmt,
+                        &[
+                            /* task_id */ &task_info.pkey,
+                            /* aggregation_job_id */
+                            &report_aggregation_metadata.aggregation_job_id().as_ref(),
+                            /* ord */ &ord,
+                            /* client_report_id */
+                            &report_aggregation_metadata.report_id().as_ref(),
+                            /* client_timestamp */
+                            &report_aggregation_metadata.time().as_naive_date_time()?,
+                            /* created_at */ &now,
+                            /* updated_at */ &now,
+                            /* updated_by */ &self.name,
+                        ],
+                    )
+                    .await?,
+                )
+            }
+            ReportAggregationMetadataState::Failed { report_error } => {
+                let stmt = self
+                    .prepare_cached(
+                        "-- put_leader_report_aggregation()
+INSERT INTO report_aggregations
+    (task_id, aggregation_job_id, ord, client_report_id, client_timestamp,
+    state, error_code, created_at, updated_at, updated_by)
+SELECT
+    $1, aggregation_jobs.id, $3, $4, $5, 'FAILED'::REPORT_AGGREGATION_STATE,
+    $6, $7, $8, $9
+FROM aggregation_jobs
+JOIN client_reports
+    ON client_reports.task_id = aggregation_jobs.task_id
+   AND client_reports.report_id = $4
+   AND client_reports.client_timestamp = $5
+WHERE aggregation_jobs.task_id = $1
+AND aggregation_job_id = $2
+AND NOT EXISTS (
+    SELECT 1 FROM report_aggregations
+    WHERE report_aggregations.task_id = $1
+      AND report_aggregations.aggregation_job_id = aggregation_jobs.id
+      AND report_aggregations.ord = $3
+)
+ON CONFLICT DO NOTHING",
+                    )
+                    .await?;
+                check_insert(
+                    self.execute(
+                        &stmt,
+                        &[
+                            /* task_id */ &task_info.pkey,
+                            /* aggregation_job_id */
+                            &report_aggregation_metadata.aggregation_job_id().as_ref(),
+                            /* ord */ &ord,
+                            /* client_report_id */
+                            &report_aggregation_metadata.report_id().as_ref(),
+                            /* client_timestamp */
+                            &report_aggregation_metadata.time().as_naive_date_time()?,
+                            /* error_code */ &(*report_error as i16),
+                            /* created_at */ &now,
+                            /* updated_at */ &now,
+                            /* updated_by */ &self.name,
+                        ],
+                    )
+                    .await?,
+                )
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn update_report_aggregation<
+        const SEED_SIZE: usize,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        report_aggregation: &ReportAggregation<SEED_SIZE, A>,
+    ) -> Result<(), Error> {
+        let task_info = match self.task_info_for(report_aggregation.task_id()).await? {
+            Some(task_info) => task_info,
+            None => return Err(Error::MutationTargetNotFound),
+        };
+        let now = self.clock.now().as_naive_date_time()?;
+
+        let encoded_state_values = report_aggregation.state().encoded_values_from_state()?;
+        let encoded_last_prep_resp: Option<Vec<u8>> = report_aggregation
+            .last_prep_resp()
+            .map(PrepareResp::get_encoded)
+            .transpose()?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- update_report_aggregation()
+UPDATE report_aggregations
+SET
+    last_prep_resp = $1, state = $2, public_extensions = $3, public_share = $4,
+    leader_private_extensions = $5, leader_input_share = $6,
+    helper_encrypted_input_share = $7, leader_prep_transition = $8,
+    leader_prep_state = $9, prepare_init = $10,
+    require_taskbind_extension = $11, helper_prep_state = $12,
+    prepare_continue = $13, error_code = $14, updated_at = $15,
+    updated_by = $16
+FROM aggregation_jobs
+WHERE report_aggregations.aggregation_job_id = aggregation_jobs.id
+  AND aggregation_jobs.aggregation_job_id = $17
+  AND aggregation_jobs.task_id = $18
+  AND report_aggregations.task_id = $18
+  AND report_aggregations.client_report_id = $19
+  AND report_aggregations.client_timestamp = $20
+  AND report_aggregations.ord = $21
+  AND UPPER(aggregation_jobs.client_timestamp_interval) >= $22",
+            )
+            .await?;
+        check_single_row_mutation(
+            self.execute(
+                &stmt,
+                &[
+                    /* last_prep_resp */ &encoded_last_prep_resp,
+                    /* state */ &report_aggregation.state().state_code(),
+                    /* public_extensions */ &encoded_state_values.public_extensions,
+                    /* public_share */ &encoded_state_values.public_share,
+                    /* leader_private_extensions */
+                    &encoded_state_values.leader_private_extensions,
+                    /* leader_input_share */ &encoded_state_values.leader_input_share,
+                    /* helper_encrypted_input_share */
+                    &encoded_state_values.helper_encrypted_input_share,
+                    /* leader_prep_transition */
+                    &encoded_state_values.leader_prep_continuation,
+                    /* leader_prep_state */ &encoded_state_values.leader_prep_state,
+                    /* prepare_init */ &encoded_state_values.prepare_init,
+                    /* require_taskbind_extension */
+                    &encoded_state_values.require_taskbind_extension,
+                    /* helper_prep_state */ &encoded_state_values.helper_prep_state,
+                    /* prepare_continue */ &encoded_state_values.prepare_continue,
+                    /* error_code */ &encoded_state_values.report_error,
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* aggregation_job_id */
+                    &report_aggregation.aggregation_job_id().as_ref(),
+                    /* task_id */ &task_info.pkey,
+                    /* client_report_id */ &report_aggregation.report_id().as_ref(),
+                    /* client_timestamp */ &report_aggregation.time().as_naive_date_time()?,
+                    /* ord */ &TryInto::<i64>::try_into(report_aggregation.ord())?,
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                ],
+            )
+            .await?,
+        )
+    }
+
+    /// Returns the collection job for the provided ID, or `None` if no such collection job exists.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_collection_job<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        vdaf: &A,
+        task_id: &TaskId,
+        collection_job_id: &CollectionJobId,
+    ) -> Result<Option<CollectionJob<SEED_SIZE, B, A>>, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(None),
+        };
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_collection_job()
+SELECT
+    query, aggregation_param, batch_identifier, state, report_count,
+    client_timestamp_interval, helper_aggregate_share, leader_aggregate_share
+FROM collection_jobs
+WHERE collection_jobs.task_id = $1
+  AND collection_jobs.collection_job_id = $2
+  AND COALESCE(
+          LOWER(collection_jobs.batch_interval),
+          (SELECT MAX(UPPER(client_timestamp_interval))
+           FROM batch_aggregations
+           WHERE batch_aggregations.task_id = collection_jobs.task_id
+             AND batch_aggregations.batch_identifier = collection_jobs.batch_identifier
+             AND batch_aggregations.aggregation_param = collection_jobs.aggregation_param),
+          '-infinity'::TIMESTAMP) >= $3",
+            )
+            .await?;
+        self.query_opt(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* collection_job_id */ &collection_job_id.as_ref(),
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
+        )
+        .await?
+        .map(|row| {
+            let batch_identifier = B::BatchIdentifier::get_decoded(row.get("batch_identifier"))?;
+            Self::collection_job_from_row(
+                vdaf,
+                *task_id,
+                batch_identifier,
+                *collection_job_id,
+                &row,
+            )
+        })
+        .transpose()
+    }
+
+    /// Returns a collection job in state FINISHED with the given parameters, or `None` if no such
+    /// collection job exists.
+    pub async fn get_finished_collection_job<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        vdaf: &A,
+        task_id: &TaskId,
+        batch_identifier: &B::BatchIdentifier,
+        aggregation_param: &A::AggregationParam,
+    ) -> Result<Option<CollectionJob<SEED_SIZE, B, A>>, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(None),
+        };
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_finished_collection_job()
+SELECT
+    collection_job_id, query, aggregation_param, state, report_count,
+    client_timestamp_interval, helper_aggregate_share, leader_aggregate_share
+FROM collection_jobs
+WHERE collection_jobs.task_id = $1
+  AND collection_jobs.batch_identifier = $2
+  AND collection_jobs.aggregation_param = $3
+  AND collection_jobs.state = 'FINISHED'
+  AND COALESCE(
+          LOWER(collection_jobs.batch_interval),
+          (SELECT MAX(UPPER(client_timestamp_interval))
+           FROM batch_aggregations
+           WHERE batch_aggregations.task_id = collection_jobs.task_id
+             AND batch_aggregations.batch_identifier = collection_jobs.batch_identifier
+             AND batch_aggregations.aggregation_param = collection_jobs.aggregation_param),
+          '-infinity'::TIMESTAMP) >= $4
+LIMIT 1",
+            )
+            .await?;
+        self.query_opt(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* batch_identifier */ &batch_identifier.get_encoded()?,
+                /* aggregation_param */ &aggregation_param.get_encoded()?,
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
+        )
+        .await?
+        .map(|row| {
+            let collection_job_id =
+                row.get_bytea_and_convert::<CollectionJobId>("collection_job_id")?;
+            Self::collection_job_from_row(
+                vdaf,
+                *task_id,
+                batch_identifier.clone(),
+                collection_job_id,
+                &row,
+            )
+        })
+        .transpose()
+    }
+
+    /// Returns all collection jobs for the given task which include the given timestamp. Applies
+    /// only to time-interval tasks.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_collection_jobs_including_time<
+        const SEED_SIZE: usize,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        vdaf: &A,
+        task_id: &TaskId,
+        timestamp: &Time,
+    ) -> Result<Vec<CollectionJob<SEED_SIZE, TimeInterval, A>>, Error> {
+        // TODO(#1553): write unit test
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(Vec::new()),
+        };
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_collection_jobs_including_time()
+SELECT
+    collection_job_id, query, aggregation_param, batch_identifier, state,
+    report_count, client_timestamp_interval, helper_aggregate_share,
+    leader_aggregate_share
+FROM collection_jobs
+WHERE task_id = $1
+  AND batch_interval @> $2::TIMESTAMP
+  AND LOWER(batch_interval) >= $3",
+            )
+            .await?;
+        self.query(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* timestamp */ &timestamp.as_naive_date_time()?,
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
+        )
+        .await?
+        .into_iter()
+        .map(|row| {
+            let batch_identifier = Interval::get_decoded(row.get("batch_identifier"))?;
+            let collection_job_id =
+                row.get_bytea_and_convert::<CollectionJobId>("collection_job_id")?;
+            Self::collection_job_from_row(vdaf, *task_id, batch_identifier, collection_job_id, &row)
+        })
+        .collect()
+    }
+
+    /// Returns all collection jobs for the given task whose collect intervals intersect with the
+    /// given interval, in the order they were created. Applies only to time-interval tasks.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_collection_jobs_intersecting_interval<
+        const SEED_SIZE: usize,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        vdaf: &A,
+        task_id: &TaskId,
+        batch_interval: &Interval,
+    ) -> Result<Vec<CollectionJob<SEED_SIZE, TimeInterval, A>>, Error> {
+        // TODO(#1553): write unit test
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(Vec::new()),
+        };
+
+        batch_interval
+            .validate_precision(&task_info.time_precision)
+            .map_err(|e| Self::unaligned_time_error(task_id, &task_info.time_precision, e))?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_collection_jobs_intersecting_interval()
+SELECT
+    collection_job_id, query, aggregation_param, batch_identifier, state,
+    report_count, client_timestamp_interval, helper_aggregate_share,
+    leader_aggregate_share
+FROM collection_jobs
+WHERE task_id = $1
+  AND batch_interval && $2
+  AND LOWER(collection_jobs.batch_interval) >= $3
+ORDER BY id",
+            )
+            .await?;
+        self.query(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* batch_interval */ &SqlInterval::from(batch_interval),
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
+        )
+        .await?
+        .into_iter()
+        .map(|row| {
+            let batch_identifier = Interval::get_decoded(row.get("batch_identifier"))?;
+            let collection_job_id =
+                row.get_bytea_and_convert::<CollectionJobId>("collection_job_id")?;
+            Self::collection_job_from_row::<SEED_SIZE, TimeInterval, A>(
+                vdaf,
+                *task_id,
+                batch_identifier,
+                collection_job_id,
+                &row,
+            )
+        })
+        .collect()
+    }
+
+    /// Retrieves all collection jobs for the given batch ID, in the order they were created.
+    /// Multiple collection jobs may be returned with distinct aggregation parameters. Applies only
+    /// to leader-selected tasks.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_collection_jobs_by_batch_id<
+        const SEED_SIZE: usize,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        vdaf: &A,
+        task_id: &TaskId,
+        batch_id: &BatchId,
+    ) -> Result<Vec<CollectionJob<SEED_SIZE, LeaderSelected, A>>, Error> {
+        // TODO(#1553): write unit test
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(Vec::new()),
+        };
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_collection_jobs_by_batch_id()
+SELECT
+    collection_job_id, query, aggregation_param, state, report_count,
+    client_timestamp_interval, helper_aggregate_share, leader_aggregate_share
+FROM collection_jobs
+WHERE task_id = $1
+  AND batch_identifier = $2
+  AND COALESCE(
+          (SELECT MAX(UPPER(client_timestamp_interval))
+           FROM batch_aggregations ba
+           WHERE ba.task_id = collection_jobs.task_id
+             AND ba.batch_identifier = collection_jobs.batch_identifier
+             AND ba.aggregation_param = collection_jobs.aggregation_param),
+          '-infinity'::TIMESTAMP) >= $3
+ORDER BY id",
+            )
+            .await?;
+        self.query(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* batch_id */ &batch_id.get_encoded()?,
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
+        )
+        .await?
+        .into_iter()
+        .map(|row| {
+            let collection_job_id =
+                row.get_bytea_and_convert::<CollectionJobId>("collection_job_id")?;
+            Self::collection_job_from_row(vdaf, *task_id, *batch_id, collection_job_id, &row)
+        })
+        .collect()
+    }
+
+    #[cfg(feature = "test-util")]
+    pub async fn get_collection_jobs_for_task<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        vdaf: &A,
+        task_id: &TaskId,
+    ) -> Result<Vec<CollectionJob<SEED_SIZE, B, A>>, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(Vec::new()),
+        };
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_collection_jobs_for_task()
+SELECT
+    collection_job_id, query, aggregation_param, batch_identifier, state,
+    report_count, client_timestamp_interval, helper_aggregate_share,
+    leader_aggregate_share
+FROM collection_jobs
+WHERE task_id = $1
+  AND COALESCE(
+          LOWER(batch_interval),
+          (SELECT MAX(UPPER(ba.client_timestamp_interval))
+           FROM batch_aggregations ba
+           WHERE ba.task_id = collection_jobs.task_id
+             AND ba.batch_identifier = collection_jobs.batch_identifier
+             AND ba.aggregation_param = collection_jobs.aggregation_param),
+          '-infinity'::TIMESTAMP) >= $2",
+            )
+            .await?;
+        self.query(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
+        )
+        .await?
+        .into_iter()
+        .map(|row| {
+            let collection_job_id =
+                row.get_bytea_and_convert::<CollectionJobId>("collection_job_id")?;
+            let batch_identifier = B::BatchIdentifier::get_decoded(row.get("batch_identifier"))?;
+            Self::collection_job_from_row(vdaf, *task_id, batch_identifier, collection_job_id, &row)
+        })
+        .collect()
+    }
+
+    fn collection_job_from_row<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        vdaf: &A,
+        task_id: TaskId,
+        batch_identifier: B::BatchIdentifier,
+        collection_job_id: CollectionJobId,
+        row: &Row,
+    ) -> Result<CollectionJob<SEED_SIZE, B, A>, Error> {
+        let query = Query::<B>::get_decoded(row.get("query"))?;
+        let aggregation_param = A::AggregationParam::get_decoded(row.get("aggregation_param"))?;
+        let state: CollectionJobStateCode = row.get("state");
+        let report_count: Option<i64> = row.get("report_count");
+        let client_timestamp_interval: Option<SqlInterval> = row.get("client_timestamp_interval");
+        let helper_aggregate_share_bytes: Option<Vec<u8>> = row.get("helper_aggregate_share");
+        let leader_aggregate_share_bytes: Option<Vec<u8>> = row.get("leader_aggregate_share");
+
+        let state = match state {
+            CollectionJobStateCode::Start => CollectionJobState::Start,
+
+            CollectionJobStateCode::Finished => {
+                let report_count = u64::try_from(report_count.ok_or_else(|| {
+                    Error::DbState(
+                        "collection job in state FINISHED but report_count is NULL".to_string(),
+                    )
+                })?)?;
+                let client_timestamp_interval = client_timestamp_interval
+                    .ok_or_else(|| Error::DbState(
+                        "collection job in state FINISHED but client_timestamp_interval is NULL".to_string())
+                    )?.as_interval();
+                let encrypted_helper_aggregate_share = HpkeCiphertext::get_decoded(
+                    &helper_aggregate_share_bytes.ok_or_else(|| {
+                        Error::DbState(
+                            "collection job in state FINISHED but helper_aggregate_share is NULL"
+                                .to_string(),
+                        )
+                    })?,
+                )?;
+                let leader_aggregate_share = A::AggregateShare::get_decoded_with_param(
+                    &(vdaf, &aggregation_param),
+                    &leader_aggregate_share_bytes.ok_or_else(|| {
+                        Error::DbState(
+                            "collection job is in state FINISHED but leader_aggregate_share is \
+                             NULL"
+                                .to_string(),
+                        )
+                    })?,
+                )?;
+                CollectionJobState::Finished {
+                    report_count,
+                    client_timestamp_interval,
+                    encrypted_helper_aggregate_share,
+                    leader_aggregate_share,
+                }
+            }
+
+            CollectionJobStateCode::Abandoned => CollectionJobState::Abandoned,
+
+            CollectionJobStateCode::Deleted => CollectionJobState::Deleted,
+        };
+
+        Ok(CollectionJob::new(
+            task_id,
+            collection_job_id,
+            query,
+            aggregation_param,
+            batch_identifier,
+            state,
+        ))
+    }
+
+    /// Stores a new collection job.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn put_collection_job<
+        const SEED_SIZE: usize,
+        B: CollectableBatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        collection_job: &CollectionJob<SEED_SIZE, B, A>,
+    ) -> Result<(), Error> {
+        self.put_collection_job_record(&CollectionJobRecord::try_from(collection_job)?)
+            .await
+    }
+
+    /// Stores a new collection job given in its type-erased form, as [`Self::put_collection_job`]
+    /// does.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn put_collection_job_record(
+        &self,
+        collection_job: &CollectionJobRecord,
+    ) -> Result<(), Error> {
+        let task_info = match self.task_info_for(collection_job.task_id()).await? {
+            Some(task_info) => task_info,
+            None => return Err(Error::MutationTargetNotFound),
+        };
+        let now = self.clock.now().as_naive_date_time()?;
+
+        let batch_interval = collection_job.batch_interval().map(SqlInterval::from);
+
+        let stmt = self
+            .prepare_cached(
+                "-- put_collection_job()
+INSERT INTO collection_jobs
+    (task_id, collection_job_id, query, aggregation_param, batch_identifier,
+    batch_interval, state, created_at, updated_at, updated_by)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+ON CONFLICT(task_id, collection_job_id) DO UPDATE
+    SET (
+        query, aggregation_param, batch_identifier, batch_interval, state,
+        created_at, updated_at, updated_by
+    ) = (
+        excluded.query, excluded.aggregation_param, excluded.batch_identifier,
+        excluded.batch_interval, excluded.state, excluded.created_at,
+        excluded.updated_at, excluded.updated_by
+    )
+    WHERE COALESCE(
+              LOWER(collection_jobs.batch_interval),
+              (SELECT MAX(UPPER(ba.client_timestamp_interval))
+               FROM batch_aggregations ba
+               WHERE ba.task_id = collection_jobs.task_id
+                 AND ba.batch_identifier = collection_jobs.batch_identifier
+                 AND ba.aggregation_param = collection_jobs.aggregation_param),
+              '-infinity'::TIMESTAMP) < $11",
+            )
+            .await?;
+
+        check_insert(
+            self.execute(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* collection_job_id */ collection_job.collection_job_id().as_ref(),
+                    /* query */ &collection_job.encoded_query(),
+                    /* aggregation_param */ &collection_job.encoded_aggregation_param(),
+                    /* batch_identifier */ &collection_job.encoded_batch_identifier(),
+                    /* batch_interval */ &batch_interval,
+                    /* state */ &collection_job.state(),
+                    /* created_at */ &now,
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                ],
+            )
+            .await?,
+        )
+    }
+
+    /// acquire_incomplete_collection_jobs retrieves & acquires the IDs of unclaimed incomplete
+    /// collection jobs. At most `maximum_acquire_count` jobs are acquired. The job is acquired with
+    /// a "lease" that will time out; the desired duration of the lease is a parameter, and the
+    /// lease expiration time is returned.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn acquire_incomplete_collection_jobs(
+        &self,
+        lease_duration: &StdDuration,
+        maximum_acquire_count: usize,
+    ) -> Result<Vec<Lease<AcquiredCollectionJob>>, Error> {
+        let now = self.clock.now().as_naive_date_time()?;
+        let lease_expiry_time = add_naive_date_time_duration(&now, lease_duration)?;
+        let maximum_acquire_count: i64 = maximum_acquire_count.try_into()?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- acquire_incomplete_collection_jobs()
+WITH incomplete_jobs AS (
+    SELECT
+        collection_jobs.id, collection_jobs.batch_identifier, tasks.task_id,
+        tasks.batch_mode, tasks.vdaf, tasks.time_precision
+    FROM collection_jobs
+    JOIN tasks ON tasks.id = collection_jobs.task_id
+    WHERE tasks.aggregator_role = 'LEADER'
+      AND collection_jobs.state = 'START'
+      AND collection_jobs.lease_expiry <= $4
+      AND COALESCE(
+              LOWER(batch_interval),
+              (SELECT MAX(UPPER(ba.client_timestamp_interval))
+               FROM batch_aggregations ba
+               WHERE ba.task_id = collection_jobs.task_id
+                 AND ba.batch_identifier = collection_jobs.batch_identifier
+                 AND ba.aggregation_param = collection_jobs.aggregation_param),
+              '-infinity'::TIMESTAMP)
+          >= COALESCE(
+                 $4::TIMESTAMP - tasks.report_expiry_age * '1 second'::INTERVAL,
+                 '-infinity'::TIMESTAMP
+             )
+    FOR UPDATE OF collection_jobs SKIP LOCKED LIMIT $5
+)
+UPDATE collection_jobs SET
+    lease_expiry = $1,
+    lease_token = gen_random_bytes(16),
+    lease_attempts = lease_attempts + 1,
+    updated_at = $2,
+    updated_by = $3
+FROM incomplete_jobs
+WHERE collection_jobs.id = incomplete_jobs.id
+RETURNING
+    incomplete_jobs.task_id, incomplete_jobs.batch_mode, incomplete_jobs.vdaf,
+    incomplete_jobs.time_precision, collection_jobs.collection_job_id,
+    collection_jobs.batch_identifier, collection_jobs.aggregation_param,
+    collection_jobs.lease_token, collection_jobs.lease_attempts,
+    collection_jobs.step_attempts, collection_jobs.created_at",
+            )
+            .await?;
+
+        self.query(
+            &stmt,
+            &[
+                /* lease_expiry */ &lease_expiry_time,
+                /* updated_at */ &now,
+                /* updated_by */ &self.name,
+                /* now */ &now,
+                /* limit */ &maximum_acquire_count,
+            ],
+        )
+        .await?
+        .into_iter()
+        .map(|row| {
+            let task_id = TaskId::get_decoded(row.get("task_id"))?;
+            let collection_job_id =
+                row.get_bytea_and_convert::<CollectionJobId>("collection_job_id")?;
+            let batch_mode = row.try_get::<_, Json<task::BatchMode>>("batch_mode")?.0;
+            let vdaf = row.try_get::<_, Json<VdafInstance>>("vdaf")?.0;
+            let time_precision =
+                Duration::from_seconds(row.get_bigint_and_convert("time_precision")?);
+            let encoded_batch_identifier = row.get("batch_identifier");
+            let encoded_aggregation_param = row.get("aggregation_param");
+            let lease_token = row.get_bytea_and_convert::<LeaseToken>("lease_token")?;
+            let lease_attempts = row.get_bigint_and_convert("lease_attempts")?;
+            let step_attempts = row.get_bigint_and_convert("step_attempts")?;
+            let created_at = Time::from_naive_date_time(&row.get("created_at"));
+
+            Ok(Lease::new(
+                AcquiredCollectionJob::new(
+                    task_id,
+                    collection_job_id,
+                    batch_mode,
+                    vdaf,
+                    time_precision,
+                    encoded_batch_identifier,
+                    encoded_aggregation_param,
+                    step_attempts,
+                    created_at,
+                ),
+                lease_expiry_time,
+                lease_token,
+                lease_attempts,
+            ))
+        })
+        .collect()
+    }
+
+    /// release_collection_job releases an acquired (via e.g. acquire_incomplete_collection_jobs)
+    /// collect job. If given, `reacquire_delay` determines the duration of time that must pass
+    /// before the collection job can be reacquired; this method assumes a reacquire delay indicates
+    /// that no progress was made, and will increment `step_attempts` accordingly. It returns an
+    /// error if the collection job has no current lease.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn release_collection_job(
+        &self,
+        lease: &Lease<AcquiredCollectionJob>,
+        reacquire_delay: Option<&StdDuration>,
+    ) -> Result<(), Error> {
+        let task_info = match self.task_info_for(lease.leased().task_id()).await? {
+            Some(task_info) => task_info,
+            None => return Err(Error::MutationTargetNotFound),
+        };
+        let now = self.clock.now().as_naive_date_time()?;
+
+        let lease_expiration = reacquire_delay
+            .map(|rd| add_naive_date_time_duration(&now, rd))
+            .transpose()?
+            .map(Timestamp::Value)
+            .unwrap_or_else(|| Timestamp::NegInfinity);
+
+        let stmt = self
+            .prepare_cached(
+                "-- release_collection_job()
+UPDATE collection_jobs
+SET lease_expiry = $1,
+    lease_token = NULL,
+    lease_attempts = 0,
+    step_attempts = CASE
+            WHEN $6 = '-infinity'::TIMESTAMP THEN 0
+            ELSE step_attempts + 1
+        END,
+    updated_at = $2,
+    updated_by = $3
+WHERE task_id = $4
+  AND collection_job_id = $5
+  AND lease_expiry = $6
+  AND lease_token = $7
+  AND COALESCE(
+          LOWER(batch_interval),
+          (SELECT MAX(UPPER(ba.client_timestamp_interval))
+           FROM batch_aggregations ba
+           WHERE ba.task_id = collection_jobs.task_id
+             AND ba.batch_identifier = collection_jobs.batch_identifier
+             AND ba.aggregation_param = collection_jobs.aggregation_param),
+          '-infinity'::TIMESTAMP) >= $8",
+            )
+            .await?;
+        check_single_row_mutation(
+            self.execute(
+                &stmt,
+                &[
+                    /* lease_expiry */ &lease_expiration,
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* task_id */ &task_info.pkey,
+                    /* collection_job_id */ &lease.leased().collection_job_id().as_ref(),
+                    /* lease_expiry */ &lease.lease_expiry_time(),
+                    /* lease_token */ &lease.lease_token().as_ref(),
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                ],
+            )
+            .await?,
+        )
+    }
+
+    /// set_collection_job_last_error records the most recent error encountered while stepping the
+    /// given collection job, so that it can be surfaced to operators if the job is later
+    /// abandoned.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn set_collection_job_last_error(
+        &self,
+        task_id: &TaskId,
+        collection_job_id: &CollectionJobId,
+        last_error: &str,
+    ) -> Result<(), Error> {
+        let task_info = self
+            .task_info_for(task_id)
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- set_collection_job_last_error()
+UPDATE collection_jobs
+SET last_error = $1, updated_at = $2, updated_by = $3
+WHERE task_id = $4
+  AND collection_job_id = $5",
+            )
+            .await?;
+        check_single_row_mutation(
+            self.execute(
+                &stmt,
+                &[
+                    /* last_error */ &last_error,
+                    /* updated_at */ &self.clock.now().as_naive_date_time()?,
+                    /* updated_by */ &self.name,
+                    /* task_id */ &task_info.pkey,
+                    /* collection_job_id */ &collection_job_id.as_ref(),
+                ],
+            )
+            .await?,
+        )
+    }
+
+    /// put_collection_job_history_entry records an unsuccessful attempt to step the given
+    /// collection job. Only the most recent [`MAX_JOB_HISTORY_ENTRIES`] entries for each job are
+    /// retained.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn put_collection_job_history_entry(
+        &self,
+        task_id: &TaskId,
+        collection_job_id: &CollectionJobId,
+        record: &JobStepRecord,
+    ) -> Result<(), Error> {
+        let task_info = self
+            .task_info_for(task_id)
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- put_collection_job_history_entry()
+INSERT INTO collection_job_history
+    (collection_job_id, step, lease_attempts, outcome, error, http_status, problem_type,
+     retry_delay_ms, created_at)
+SELECT id, step_attempts, lease_attempts, $1, $2, $3, $4, $5, $6
+FROM collection_jobs
+WHERE task_id = $7
+  AND collection_job_id = $8
+RETURNING collection_job_id",
+            )
+            .await?;
+        let collection_job_pkey: i64 = self
+            .query_opt(
+                &stmt,
+                &[
+                    /* outcome */ record.outcome(),
+                    /* error */ &record.error(),
+                    /* http_status */ &record.http_status().map(i32::from),
+                    /* problem_type */ &record.problem_type(),
+                    /* retry_delay_ms */
+                    &record
+                        .retry_delay()
+                        .map(|retry_delay| i64::try_from(retry_delay.as_millis()))
+                        .transpose()?,
+                    /* created_at */ &self.clock.now().as_naive_date_time()?,
+                    /* task_id */ &task_info.pkey,
+                    /* collection_job_id */ &collection_job_id.as_ref(),
+                ],
+            )
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?
+            .get("collection_job_id");
+
+        let stmt = self
+            .prepare_cached(
+                "-- put_collection_job_history_entry()
+DELETE FROM collection_job_history
+WHERE collection_job_id = $1
+  AND id NOT IN (
+      SELECT id FROM collection_job_history
+      WHERE collection_job_id = $1
+      ORDER BY id DESC
+      LIMIT $2)",
+            )
+            .await?;
+        self.execute(
+            &stmt,
+            &[
+                /* collection_job_id */ &collection_job_pkey,
+                /* limit */ &i64::try_from(MAX_JOB_HISTORY_ENTRIES)?,
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// get_collection_job_history retrieves the recorded history of unsuccessful attempts to step
+    /// the given collection job, most recent first. It returns `None` if no such collection job
+    /// exists.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_collection_job_history(
+        &self,
+        task_id: &TaskId,
+        collection_job_id: &CollectionJobId,
+    ) -> Result<Option<Vec<JobHistoryEntry>>, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(None),
+        };
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_collection_job_history()
+SELECT
+    collection_job_history.id, collection_job_history.step,
+    collection_job_history.lease_attempts, collection_job_history.outcome,
+    collection_job_history.error, collection_job_history.http_status,
+    collection_job_history.problem_type, collection_job_history.retry_delay_ms,
+    collection_job_history.created_at
+FROM collection_jobs
+LEFT JOIN collection_job_history
+    ON collection_job_history.collection_job_id = collection_jobs.id
+WHERE collection_jobs.task_id = $1
+  AND collection_jobs.collection_job_id = $2
+ORDER BY collection_job_history.id DESC",
+            )
+            .await?;
+        let rows = self
+            .query(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* collection_job_id */ &collection_job_id.as_ref(),
+                ],
+            )
+            .await?;
+        job_history_from_rows(rows)
+    }
+
+    /// get_abandoned_collection_jobs retrieves all unexpired collection jobs in the abandoned
+    /// state, optionally restricted to a single task.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_abandoned_collection_jobs(
+        &self,
+        task_id: Option<&TaskId>,
+    ) -> Result<Vec<AbandonedCollectionJob>, Error> {
+        let now = self.clock.now().as_naive_date_time()?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_abandoned_collection_jobs()
+SELECT
+    tasks.task_id, collection_jobs.collection_job_id, collection_jobs.step_attempts,
+    collection_jobs.last_error, collection_jobs.updated_at
+FROM collection_jobs
+JOIN tasks ON tasks.id = collection_jobs.task_id
+WHERE collection_jobs.state = 'ABANDONED'
+  AND ($1::BYTEA IS NULL OR tasks.task_id = $1)
+  AND COALESCE(
+          LOWER(collection_jobs.batch_interval),
+          (SELECT MAX(UPPER(ba.client_timestamp_interval))
+           FROM batch_aggregations ba
+           WHERE ba.task_id = collection_jobs.task_id
+             AND ba.batch_identifier = collection_jobs.batch_identifier
+             AND ba.aggregation_param = collection_jobs.aggregation_param),
+          '-infinity'::TIMESTAMP)
+      >= COALESCE(
+             $2::TIMESTAMP - tasks.report_expiry_age * '1 second'::INTERVAL,
+             '-infinity'::TIMESTAMP
+         )
+ORDER BY collection_jobs.updated_at DESC",
+            )
+            .await?;
+        self.query(
+            &stmt,
+            &[
+                /* task_id */ &task_id.map(TaskId::get_encoded).transpose()?,
+                /* now */ &now,
+            ],
+        )
+        .await?
+        .into_iter()
+        .map(|row| {
+            Ok(AbandonedCollectionJob::new(
+                TaskId::get_decoded(row.get("task_id"))?,
+                row.get_bytea_and_convert::<CollectionJobId>("collection_job_id")?,
+                row.get_bigint_and_convert("step_attempts")?,
+                row.get("last_error"),
+                Time::from_naive_date_time(&row.get("updated_at")),
+            ))
+        })
+        .collect()
+    }
+
+    /// requeue_abandoned_collection_job returns an abandoned collection job to the start state,
+    /// with fresh lease and step attempt counters, so that it will be picked up by the collection
+    /// job driver again. It returns `Error::MutationTargetNotFound` if no abandoned collection job
+    /// with the given ID exists, or if the job's batch has expired, since its reports may already
+    /// have been garbage collected and the collection job driver would not pick the job up.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn requeue_abandoned_collection_job(
+        &self,
+        task_id: &TaskId,
+        collection_job_id: &CollectionJobId,
+    ) -> Result<(), Error> {
+        let task_info = self
+            .task_info_for(task_id)
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?;
+        let now = self.clock.now().as_naive_date_time()?;
+
+        // The batch expiry check matches the one in acquire_incomplete_collection_jobs().
+        let stmt = self
+            .prepare_cached(
+                "-- requeue_abandoned_collection_job()
+UPDATE collection_jobs
+SET state = 'START',
+    lease_expiry = '-infinity'::TIMESTAMP,
+    lease_token = NULL,
+    lease_attempts = 0,
+    step_attempts = 0,
+    last_error = NULL,
+    updated_at = $1,
+    updated_by = $2
+WHERE task_id = $3
+  AND collection_job_id = $4
+  AND state = 'ABANDONED'
+  AND COALESCE(
+          LOWER(batch_interval),
+          (SELECT MAX(UPPER(ba.client_timestamp_interval))
+           FROM batch_aggregations ba
+           WHERE ba.task_id = collection_jobs.task_id
+             AND ba.batch_identifier = collection_jobs.batch_identifier
+             AND ba.aggregation_param = collection_jobs.aggregation_param),
+          '-infinity'::TIMESTAMP)
+      >= $5",
+            )
+            .await?;
+        check_single_row_mutation(
+            self.execute(
+                &stmt,
+                &[
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* task_id */ &task_info.pkey,
+                    /* collection_job_id */ &collection_job_id.as_ref(),
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                ],
+            )
+            .await?,
+        )
+    }
+
+    /// fail_collection_job forcibly moves a collection job in the start state to the abandoned
+    /// state. It returns `Error::MutationTargetNotFound` if no such collection job exists.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn fail_collection_job(
+        &self,
+        task_id: &TaskId,
+        collection_job_id: &CollectionJobId,
+    ) -> Result<(), Error> {
+        let task_info = self
+            .task_info_for(task_id)
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- fail_collection_job()
+UPDATE collection_jobs
+SET state = 'ABANDONED',
+    lease_expiry = '-infinity'::TIMESTAMP,
+    lease_token = NULL,
+    lease_attempts = 0,
+    updated_at = $1,
+    updated_by = $2
+WHERE task_id = $3
+  AND collection_job_id = $4
+  AND state = 'START'",
+            )
+            .await?;
+        check_single_row_mutation(
+            self.execute(
+                &stmt,
+                &[
+                    /* updated_at */ &self.clock.now().as_naive_date_time()?,
+                    /* updated_by */ &self.name,
+                    /* task_id */ &task_info.pkey,
+                    /* collection_job_id */ &collection_job_id.as_ref(),
+                ],
+            )
+            .await?,
+        )
+    }
 
-        let agg_state = match state {
-            ReportAggregationStateCode::Init => {
-                let public_extensions_bytes = row
-                    .get::<_, Option<Vec<u8>>>("public_extensions")
-                    .ok_or_else(|| {
-                        Error::DbState(
-                            "report aggregation in state INIT but public_extensions is NULL"
-                                .to_string(),
-                        )
-                    })?;
-                let public_share_bytes =
-                    row.get::<_, Option<Vec<u8>>>("public_share")
-                        .ok_or_else(|| {
-                            Error::DbState(
-                                "report aggregation in state INIT but public_share is NULL"
-                                    .to_string(),
-                            )
-                        })?;
-                let leader_private_extensions_bytes = row
-                    .get::<_, Option<Vec<u8>>>("leader_private_extensions")
-                    .ok_or_else(|| {
-                        Error::DbState(
-                            "report aggregation in state INIT but leader_private_extensions is NULL"
-                                .to_string(),
-                        )
-                    })?;
-                let leader_input_share_bytes = row
-                    .get::<_, Option<Vec<u8>>>("leader_input_share")
-                    .ok_or_else(|| {
-                        Error::DbState(
-                            "report aggregation in state INIT but leader_input_share is NULL"
-                                .to_string(),
-                        )
-                    })?;
-                let helper_encrypted_input_share_bytes =
-                    row.get::<_, Option<Vec<u8>>>("helper_encrypted_input_share")
-                        .ok_or_else(|| {
-                            Error::DbState(
-                            "report aggregation in state INIT but helper_encrypted_input_share is NULL"
-                                .to_string(),
-                        )
-                        })?;
+    /// Updates an existing collection job.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn update_collection_job<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        collection_job: &CollectionJob<SEED_SIZE, B, A>,
+    ) -> Result<(), Error> {
+        let task_info = match self.task_info_for(collection_job.task_id()).await? {
+            Some(task_info) => task_info,
+            None => return Err(Error::MutationTargetNotFound),
+        };
+        let now = self.clock.now().as_naive_date_time()?;
 
-                let public_extensions =
-                    decode_u16_items(&(), &mut Cursor::new(&public_extensions_bytes))?;
-                let public_share =
-                    A::PublicShare::get_decoded_with_param(vdaf, &public_share_bytes)?;
-                let leader_private_extensions =
-                    decode_u16_items(&(), &mut Cursor::new(&leader_private_extensions_bytes))?;
-                let leader_input_share = A::InputShare::get_decoded_with_param(
-                    &(vdaf, Role::Leader.index().unwrap()),
-                    &leader_input_share_bytes,
-                )?;
-                let helper_encrypted_input_share =
-                    HpkeCiphertext::get_decoded(&helper_encrypted_input_share_bytes)?;
+        let (
+            report_count,
+            client_timestamp_interval,
+            leader_aggregate_share,
+            helper_aggregate_share,
+        ) = match collection_job.state() {
+            CollectionJobState::Start => {
+                return Err(Error::InvalidParameter(
+                    "cannot update collection job into START state",
+                ));
+            }
+
+            CollectionJobState::Finished {
+                report_count,
+                client_timestamp_interval,
+                encrypted_helper_aggregate_share,
+                leader_aggregate_share,
+            } => {
+                let report_count = Some(i64::try_from(*report_count)?);
+                let client_timestamp_interval = Some(SqlInterval::from(client_timestamp_interval));
+                let leader_aggregate_share = Some(leader_aggregate_share.get_encoded()?);
+                let helper_aggregate_share = Some(encrypted_helper_aggregate_share.get_encoded()?);
+
+                (
+                    report_count,
+                    client_timestamp_interval,
+                    leader_aggregate_share,
+                    helper_aggregate_share,
+                )
+            }
+
+            CollectionJobState::Abandoned | CollectionJobState::Deleted => (None, None, None, None),
+        };
+
+        let stmt = self
+            .prepare_cached(
+                "-- update_collection_job()
+UPDATE collection_jobs SET
+    state = $1,
+    report_count = $2,
+    client_timestamp_interval = $3,
+    leader_aggregate_share = $4,
+    helper_aggregate_share = $5,
+    updated_at = $6,
+    updated_by = $7
+WHERE task_id = $8
+  AND collection_job_id = $9
+  AND COALESCE(
+          LOWER(batch_interval),
+          (SELECT MAX(UPPER(ba.client_timestamp_interval))
+           FROM batch_aggregations ba
+           WHERE ba.task_id = collection_jobs.task_id
+             AND ba.batch_identifier = collection_jobs.batch_identifier
+             AND ba.aggregation_param = collection_jobs.aggregation_param),
+          '-infinity'::TIMESTAMP) >= $10",
+            )
+            .await?;
+
+        check_single_row_mutation(
+            self.execute(
+                &stmt,
+                &[
+                    /* state */ &collection_job.state().collection_job_state_code(),
+                    /* report_count */ &report_count,
+                    /* client_timestamp_interval */ &client_timestamp_interval,
+                    /* leader_aggregate_share */ &leader_aggregate_share,
+                    /* helper_aggregate_share */ &helper_aggregate_share,
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* task_id */ &task_info.pkey,
+                    /* collection_job_id */ &collection_job.id().as_ref(),
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                ],
+            )
+            .await?,
+        )
+    }
+
+    /// Retrieves an existing batch aggregation.
+    #[tracing::instrument(skip(self, aggregation_parameter), err(level = Level::DEBUG))]
+    pub async fn get_batch_aggregation<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        vdaf: &A,
+        task_id: &TaskId,
+        batch_identifier: &B::BatchIdentifier,
+        aggregation_parameter: &A::AggregationParam,
+        ord: u64,
+    ) -> Result<Option<BatchAggregation<SEED_SIZE, B, A>>, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(None),
+        };
+
+        // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param) which
+        // are _not_ garbage collected. This is used to evaluate whether given batch_aggregations
+        // rows are GC'ed.
+        let stmt = self
+            .prepare_cached(
+                "-- get_batch_aggregation()
+WITH non_gc_batches AS (
+    SELECT batch_identifier, aggregation_param
+    FROM batch_aggregations
+    WHERE task_id = $1
+      AND batch_identifier = $2
+      AND aggregation_param = $3
+    GROUP BY batch_identifier, aggregation_param
+    HAVING MAX(UPPER(COALESCE(batch_interval, client_timestamp_interval))) >= $5
+)
+SELECT
+    client_timestamp_interval, batch_aggregations.state, aggregate_share,
+    report_count, checksum, aggregation_jobs_created, aggregation_jobs_terminated
+FROM batch_aggregations
+WHERE task_id = $1
+  AND batch_identifier = $2
+  AND aggregation_param = $3
+  AND ord = $4
+  AND EXISTS(SELECT 1 FROM non_gc_batches
+             WHERE batch_identifier = $2
+               AND aggregation_param = $3)",
+            )
+            .await?;
+
+        self.query_opt(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* batch_identifier */ &batch_identifier.get_encoded()?,
+                /* aggregation_param */ &aggregation_parameter.get_encoded()?,
+                /* ord */ &TryInto::<i64>::try_into(ord)?,
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
+        )
+        .await?
+        .map(|row| {
+            Self::batch_aggregation_from_row(
+                vdaf,
+                *task_id,
+                batch_identifier.clone(),
+                aggregation_parameter.clone(),
+                ord,
+                row,
+            )
+        })
+        .transpose()
+    }
+
+    /// Retrieves all batch aggregations stored for a given batch, identified by task ID, batch
+    /// identifier, and aggregation parameter.
+    #[tracing::instrument(skip(self, aggregation_parameter), err(level = Level::DEBUG))]
+    pub async fn get_batch_aggregations_for_batch<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        vdaf: &A,
+        task_id: &TaskId,
+        batch_identifier: &B::BatchIdentifier,
+        aggregation_parameter: &A::AggregationParam,
+    ) -> Result<Vec<BatchAggregation<SEED_SIZE, B, A>>, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(Vec::new()),
+        };
 
-                ReportAggregationState::LeaderInit {
-                    public_extensions,
-                    public_share,
-                    leader_private_extensions,
-                    leader_input_share,
-                    helper_encrypted_input_share,
-                }
-            }
+        // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param) which
+        // are _not_ garbage collected. This is used to evaluate whether given batch_aggregations
+        // rows are GC'ed.
+        let stmt = self
+            .prepare_cached(
+                "-- get_batch_aggregations_for_batch()
+WITH non_gc_batches AS (
+    SELECT batch_identifier, aggregation_param
+    FROM batch_aggregations
+    WHERE task_id = $1
+      AND batch_identifier = $2
+      AND aggregation_param = $3
+    GROUP BY batch_identifier, aggregation_param
+    HAVING MAX(UPPER(COALESCE(batch_interval, client_timestamp_interval))) >= $4
+)
+SELECT
+    ord, client_timestamp_interval, batch_aggregations.state, aggregate_share,
+    report_count, checksum, aggregation_jobs_created, aggregation_jobs_terminated
+FROM batch_aggregations
+WHERE task_id = $1
+  AND batch_identifier = $2
+  AND aggregation_param = $3
+  AND EXISTS(SELECT 1 FROM non_gc_batches
+             WHERE batch_identifier = $2
+               AND aggregation_param = $3)",
+            )
+            .await?;
 
-            ReportAggregationStateCode::InitProcessing => {
-                let prepare_init_bytes =
-                    row.get::<_, Option<Vec<u8>>>("prepare_init")
-                        .ok_or_else(|| {
-                            Error::DbState(
-                            "report aggregation in state INIT_PROCESSING but prepare_init is NULL"
-                                .to_string(),
-                        )
-                        })?;
-                let require_taskbind_extension = row.get::<_, Option<bool>>("require_taskbind_extension")
-                        .ok_or_else(|| {
-                            Error::DbState(
-                                "report aggregation in state INIT_PROCESSING but require_taskbind_extension is NULL"
-                                    .to_string(),
-                            )
-                        })?;
+        self.query(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* batch_identifier */ &batch_identifier.get_encoded()?,
+                /* aggregation_param */ &aggregation_parameter.get_encoded()?,
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
+        )
+        .await?
+        .into_iter()
+        .map(|row| {
+            Self::batch_aggregation_from_row(
+                vdaf,
+                *task_id,
+                batch_identifier.clone(),
+                aggregation_parameter.clone(),
+                row.get_bigint_and_convert("ord")?,
+                row,
+            )
+        })
+        .collect()
+    }
 
-                let prepare_init = PrepareInit::get_decoded(&prepare_init_bytes)?;
+    /// Retrieves the number of aggregation jobs created & terminated for the given batch
+    /// identifier.
+    #[tracing::instrument(skip(self, aggregation_parameter), err(level = Level::DEBUG))]
+    pub async fn get_batch_aggregation_job_count_for_batch<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        task_id: &TaskId,
+        batch_identifier: &B::BatchIdentifier,
+        aggregation_parameter: &A::AggregationParam,
+    ) -> Result<(u64, u64), Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok((0, 0)),
+        };
 
-                ReportAggregationState::HelperInitProcessing {
-                    prepare_init,
-                    require_taskbind_extension,
-                }
-            }
+        // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param) which
+        // are _not_ garbage collected. This is used to evaluate whether given batch_aggregations
+        // rows are GC'ed.
+        let stmt = self
+            .prepare_cached(
+                "-- get_batch_aggregation_job_count_for_batch()
+WITH non_gc_batches AS (
+    SELECT batch_identifier, aggregation_param
+    FROM batch_aggregations
+    WHERE task_id = $1
+      AND batch_identifier = $2
+      AND aggregation_param = $3
+    GROUP BY batch_identifier, aggregation_param
+    HAVING MAX(UPPER(COALESCE(batch_interval, client_timestamp_interval))) >= $4
+)
+SELECT
+    SUM(aggregation_jobs_created)::BIGINT AS created,
+    SUM(aggregation_jobs_terminated)::BIGINT AS terminated
+FROM batch_aggregations
+WHERE task_id = $1
+  AND batch_identifier = $2
+  AND aggregation_param = $3
+  AND EXISTS(SELECT 1 FROM non_gc_batches
+             WHERE batch_identifier = $2
+               AND aggregation_param = $3)",
+            )
+            .await?;
 
-            ReportAggregationStateCode::Continue => {
-                match role {
-                    Role::Leader => {
-                        let leader_prep_transition_bytes = row
-                            .get::<_, Option<Vec<u8>>>("leader_prep_transition")
-                            .ok_or_else(|| {
-                                Error::DbState(
-                                    "report aggregation in state CONTINUE but leader_prep_transition is NULL"
-                                        .to_string(),
-                                )
-                            })?;
-                        let ping_pong_transition = PingPongContinuation::get_decoded_with_param(
-                            &(vdaf, 0 /* leader */),
-                            &leader_prep_transition_bytes,
-                        )?;
+        let row = self
+            .query_one(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* batch_identifier */ &batch_identifier.get_encoded()?,
+                    /* aggregation_param */ &aggregation_parameter.get_encoded()?,
+                    /* threshold */
+                    &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+                ],
+            )
+            .await?;
 
-                        ReportAggregationState::LeaderContinue {
-                            continuation: ping_pong_transition,
-                        }
-                    }
-                    Role::Helper => {
-                        let helper_prep_state_bytes = row
-                            .get::<_, Option<Vec<u8>>>("helper_prep_state")
-                            .ok_or_else(|| {
-                                Error::DbState(
-                                    "report aggregation in state CONTINUE but helper_prep_state is NULL"
-                                        .to_string(),
-                                )
-                            })?;
-                        let prepare_state = A::PrepareState::get_decoded_with_param(
-                            &(vdaf, 1 /* helper */),
-                            &helper_prep_state_bytes,
-                        )?;
+        let aggregation_jobs_created = row.get_nullable_bigint_and_convert("created")?;
+        let aggregation_jobs_terminated = row.get_nullable_bigint_and_convert("terminated")?;
 
-                        ReportAggregationState::HelperContinue { prepare_state }
-                    }
-                    _ => panic!("unexpected role"),
-                }
-            }
+        Ok((
+            aggregation_jobs_created.unwrap_or(0),
+            aggregation_jobs_terminated.unwrap_or(0),
+        ))
+    }
 
-            ReportAggregationStateCode::ContinueProcessing => {
-                let helper_prep_state_bytes = row
-                    .get::<_, Option<Vec<u8>>>("helper_prep_state")
-                    .ok_or_else(|| {
-                        Error::DbState(
-                            "report aggregation in state CONTINUE_PROCESSING but helper_prep_state is NULL"
-                                .to_string(),
-                        )
-                    })?;
-                let prepare_continue_bytes = row
-                    .get::<_, Option<Vec<u8>>>("prepare_continue")
-                    .ok_or_else(|| {
-                        Error::DbState(
-                            "report aggregation in state CONTINUE_PROCESSING but message is NULL"
-                                .to_string(),
-                        )
-                    })?;
+    #[cfg(feature = "test-util")]
+    pub async fn get_batch_aggregations_for_task<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        vdaf: &A,
+        task_id: &TaskId,
+    ) -> Result<Vec<BatchAggregation<SEED_SIZE, B, A>>, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(Vec::new()),
+        };
 
-                let prepare_state = A::PrepareState::get_decoded_with_param(
-                    &(vdaf, 1 /* helper */),
-                    &helper_prep_state_bytes,
-                )?;
-                let prepare_continue = PrepareContinue::get_decoded(&prepare_continue_bytes)?;
+        // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param) which
+        // are _not_ garbage collected. This is used to evaluate whether given batch_aggregations
+        // rows are GC'ed.
+        let stmt = self
+            .prepare_cached(
+                "-- get_batch_aggregations_for_task()
+WITH non_gc_batches AS (
+    SELECT batch_identifier, aggregation_param
+    FROM batch_aggregations
+    WHERE task_id = $1
+    GROUP BY batch_identifier, aggregation_param
+    HAVING MAX(UPPER(COALESCE(batch_interval, client_timestamp_interval))) >= $2
+)
+SELECT
+    client_timestamp_interval, batch_aggregations.batch_identifier,
+    batch_aggregations.aggregation_param, ord, batch_aggregations.state,
+    aggregate_share, report_count, checksum, aggregation_jobs_created,
+    aggregation_jobs_terminated
+FROM batch_aggregations
+WHERE task_id = $1
+  AND EXISTS(SELECT 1 FROM non_gc_batches
+             WHERE batch_identifier = batch_aggregations.batch_identifier
+               AND aggregation_param = batch_aggregations.aggregation_param)",
+            )
+            .await?;
 
-                ReportAggregationState::HelperContinueProcessing {
-                    prepare_state,
-                    prepare_continue,
-                }
-            }
+        self.query(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
+        )
+        .await?
+        .into_iter()
+        .map(|row| {
+            let batch_identifier = B::BatchIdentifier::get_decoded(row.get("batch_identifier"))?;
+            let aggregation_param = A::AggregationParam::get_decoded(row.get("aggregation_param"))?;
+            let ord = row.get_bigint_and_convert("ord")?;
 
-            ReportAggregationStateCode::PollInit => {
-                row.get::<_, Option<Vec<u8>>>("leader_prep_state")
-                    .ok_or_else(|| {
-                        Error::DbState(
-                            "report aggregation in state POLL_INIT but leader_prep_state is NULL"
-                                .to_string(),
-                        )
-                    })
-                    .and_then(|encoded| {
-                        Ok(ReportAggregationState::LeaderPollInit {
-                            prepare_state: A::PrepareState::get_decoded_with_param(
-                                &(vdaf, 0 /* leader */),
-                                &encoded,
-                            )?,
-                        })
-                    })?
-            }
+            Self::batch_aggregation_from_row(
+                vdaf,
+                *task_id,
+                batch_identifier,
+                aggregation_param,
+                ord,
+                row,
+            )
+        })
+        .collect()
+    }
 
-            ReportAggregationStateCode::PollContinue => {
-                row.get::<_, Option<Vec<u8>>>("leader_prep_transition")
-                    .ok_or_else(|| {
-                        Error::DbState(
-                            "report aggregation in state POLL_CONTINUE but leader_prep_transition is NULL"
-                                .to_string(),
-                        )
-                    })
-                    .and_then(|encoded| {
-                        Ok(ReportAggregationState::LeaderPollContinue {
-                            continuation: PingPongContinuation::get_decoded_with_param(
-                                &(vdaf, 0 /* leader */),
-                                &encoded,
-                            )?,
-                        })
-                    })?
-            }
+    fn batch_aggregation_from_row<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        vdaf: &A,
+        task_id: TaskId,
+        batch_identifier: B::BatchIdentifier,
+        aggregation_param: A::AggregationParam,
+        ord: u64,
+        row: Row,
+    ) -> Result<BatchAggregation<SEED_SIZE, B, A>, Error> {
+        #[allow(clippy::type_complexity)]
+        fn parse_values_from_row<const SEED_SIZE: usize, A: AsyncAggregator<SEED_SIZE>>(
+            vdaf: &A,
+            aggregation_param: &A::AggregationParam,
+            row: &Row,
+        ) -> Result<(Option<A::AggregateShare>, u64, ReportIdChecksum, u64, u64), Error> {
+            let aggregate_share = row
+                .get::<_, Option<Vec<u8>>>("aggregate_share")
+                .map(|bytes| {
+                    A::AggregateShare::get_decoded_with_param(&(vdaf, aggregation_param), &bytes)
+                })
+                .transpose()
+                .map_err(|_| Error::DbState("aggregate_share couldn't be parsed".to_string()))?;
+            let report_count = row.get_bigint_and_convert("report_count")?;
+            let checksum = ReportIdChecksum::get_decoded(row.get("checksum"))?;
+            let aggregation_jobs_created =
+                row.get_bigint_and_convert("aggregation_jobs_created")?;
+            let aggregation_jobs_terminated =
+                row.get_bigint_and_convert("aggregation_jobs_terminated")?;
 
-            ReportAggregationStateCode::Finished => ReportAggregationState::Finished,
+            Ok((
+                aggregate_share,
+                report_count,
+                checksum,
+                aggregation_jobs_created,
+                aggregation_jobs_terminated,
+            ))
+        }
 
-            ReportAggregationStateCode::Failed => {
-                let report_error = match error_code {
-                    Some(c) => {
-                        let c: u8 = c.try_into().map_err(|err| {
-                            Error::DbState(format!("couldn't convert error_code value: {err}"))
-                        })?;
-                        Some(c.try_into().map_err(|err| {
-                            Error::DbState(format!("couldn't convert error_code value: {err}"))
-                        })?)
-                    }
-                    None => None,
+        let client_timestamp_interval = row
+            .get::<_, SqlInterval>("client_timestamp_interval")
+            .as_interval();
+        let state: BatchAggregationStateCode = row.get("state");
+        let state = match state {
+            BatchAggregationStateCode::Aggregating => {
+                let (
+                    aggregate_share,
+                    report_count,
+                    checksum,
+                    aggregation_jobs_created,
+                    aggregation_jobs_terminated,
+                ) = parse_values_from_row(vdaf, &aggregation_param, &row)?;
+                BatchAggregationState::Aggregating {
+                    aggregate_share,
+                    report_count,
+                    checksum,
+                    aggregation_jobs_created,
+                    aggregation_jobs_terminated,
+                }
+            }
+            BatchAggregationStateCode::Collected => {
+                let (
+                    aggregate_share,
+                    report_count,
+                    checksum,
+                    aggregation_jobs_created,
+                    aggregation_jobs_terminated,
+                ) = parse_values_from_row(vdaf, &aggregation_param, &row)?;
+                BatchAggregationState::Collected {
+                    aggregate_share,
+                    report_count,
+                    checksum,
+                    aggregation_jobs_created,
+                    aggregation_jobs_terminated,
                 }
-                .ok_or_else(|| {
-                    Error::DbState(
-                        "report aggregation in state FAILED but error_code is NULL".to_string(),
-                    )
-                })?;
-
-                ReportAggregationState::Failed { report_error }
             }
+            BatchAggregationStateCode::Scrubbed => BatchAggregationState::Scrubbed,
         };
 
-        Ok(ReportAggregation::new(
-            *task_id,
-            *aggregation_job_id,
-            *report_id,
-            time,
+        Ok(BatchAggregation::new(
+            task_id,
+            batch_identifier,
+            aggregation_param,
             ord,
-            last_prep_resp,
-            agg_state,
+            client_timestamp_interval,
+            state,
         ))
     }
 
-    /// put_report_aggregation stores aggregation data for a single report.
+    /// Store a new `batch_aggregations` row in the datastore.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn put_report_aggregation<const SEED_SIZE: usize, A: AsyncAggregator<SEED_SIZE>>(
+    pub async fn put_batch_aggregation<
+        const SEED_SIZE: usize,
+        B: AccumulableBatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
         &self,
-        report_aggregation: &ReportAggregation<SEED_SIZE, A>,
+        batch_aggregation: &BatchAggregation<SEED_SIZE, B, A>,
     ) -> Result<(), Error> {
-        let task_info = match self.task_info_for(report_aggregation.task_id()).await? {
+        self.put_batch_aggregation_record(&BatchAggregationRecord::try_from(batch_aggregation)?)
+            .await
+    }
+
+    /// Store a new `batch_aggregations` row given in its type-erased form, as
+    /// [`Self::put_batch_aggregation`] does.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn put_batch_aggregation_record(
+        &self,
+        batch_aggregation: &BatchAggregationRecord,
+    ) -> Result<(), Error> {
+        let task_info = match self.task_info_for(batch_aggregation.task_id()).await? {
             Some(task_info) => task_info,
             None => return Err(Error::MutationTargetNotFound),
         };
         let now = self.clock.now().as_naive_date_time()?;
 
-        let encoded_state_values = report_aggregation.state().encoded_values_from_state()?;
-        let encoded_last_prep_resp: Option<Vec<u8>> = report_aggregation
-            .last_prep_resp()
-            .map(PrepareResp::get_encoded)
-            .transpose()?;
+        let batch_interval = batch_aggregation.batch_interval().map(SqlInterval::from);
+        let encoded_state_values = batch_aggregation.encoded_state_values();
 
-        report_aggregation
-            .time()
+        batch_aggregation
+            .client_timestamp_interval()
             .validate_precision(&task_info.time_precision)
             .map_err(|e| {
                 Self::unaligned_time_error(
-                    report_aggregation.task_id(),
+                    batch_aggregation.task_id(),
                     &task_info.time_precision,
                     e,
                 )
             })?;
 
-        // If there is a conflict, the we upsert the incoming report agggregation (excluded) if the
-        // existing report aggregation is expired (virtually GCed; would be invisible to other
-        // queries that retrieve report rows).
-        // https://github.com/divviup/janus/pull/2818
         let stmt = self
             .prepare_cached(
-                "-- put_report_aggregation()
-INSERT INTO report_aggregations
-    (task_id, aggregation_job_id, ord, client_report_id, client_timestamp,
-    last_prep_resp, state, public_extensions, public_share,
-    leader_private_extensions, leader_input_share,
-    helper_encrypted_input_share, leader_prep_transition, leader_prep_state,
-    prepare_init, require_taskbind_extension,
-    helper_prep_state, prepare_continue, error_code, created_at, updated_at,
-    updated_by)
-SELECT
-    $1, aggregation_jobs.id, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14,
-    $15, $16, $17, $18, $19, $20, $21, $22
-FROM aggregation_jobs
-WHERE task_id = $1
-  AND aggregation_job_id = $2
-ON CONFLICT(task_id, aggregation_job_id, ord) DO UPDATE
+                "-- put_batch_aggregation()
+INSERT INTO batch_aggregations (
+    task_id, batch_identifier, batch_interval, aggregation_param, ord,
+    client_timestamp_interval, state, aggregate_share, report_count, checksum,
+    aggregation_jobs_created, aggregation_jobs_terminated, created_at, updated_at,
+    updated_by
+)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+ON CONFLICT(task_id, batch_identifier, aggregation_param, ord) DO UPDATE
     SET (
-        client_report_id, client_timestamp, last_prep_resp, state,
-        public_extensions, public_share, leader_private_extensions,
-        leader_input_share, helper_encrypted_input_share,
-        leader_prep_transition, leader_prep_state,
-        prepare_init, require_taskbind_extension, helper_prep_state,
-        prepare_continue, error_code, created_at, updated_at, updated_by
+        client_timestamp_interval, state, aggregate_share, report_count, checksum,
+        aggregation_jobs_created, aggregation_jobs_terminated, created_at,
+        updated_at, updated_by
     ) = (
-        excluded.client_report_id, excluded.client_timestamp,
-        excluded.last_prep_resp, excluded.state, excluded.public_extensions,
-        excluded.public_share, excluded.leader_private_extensions,
-        excluded.leader_input_share, excluded.helper_encrypted_input_share,
-        excluded.leader_prep_transition, excluded.leader_prep_state,
-        excluded.prepare_init,
-        excluded.require_taskbind_extension, excluded.helper_prep_state,
-        excluded.prepare_continue, excluded.error_code, excluded.created_at,
-        excluded.updated_at, excluded.updated_by
+        excluded.client_timestamp_interval, excluded.state,
+        excluded.aggregate_share, excluded.report_count, excluded.checksum,
+        excluded.aggregation_jobs_created, excluded.aggregation_jobs_terminated,
+        excluded.created_at, excluded.updated_at, excluded.updated_by
     )
-    WHERE (SELECT UPPER(client_timestamp_interval)
-           FROM aggregation_jobs
-           WHERE id = report_aggregations.aggregation_job_id) < $23",
+    WHERE GREATEST(
+              UPPER(COALESCE(batch_aggregations.batch_interval,
+                             batch_aggregations.client_timestamp_interval)),
+              (SELECT MAX(UPPER(COALESCE(ba.batch_interval,
+                                         ba.client_timestamp_interval)))
+               FROM batch_aggregations ba
+               WHERE ba.task_id = batch_aggregations.task_id
+                 AND ba.batch_identifier = batch_aggregations.batch_identifier
+                 AND ba.aggregation_param = batch_aggregations.aggregation_param)) < $16",
             )
             .await?;
         check_insert(
@@ -2680,29 +5569,20 @@ ON CONFLICT(task_id, aggregation_job_id, ord) DO UPDATE
                 &stmt,
                 &[
                     /* task_id */ &task_info.pkey,
-                    /* aggregation_job_id */
-                    &report_aggregation.aggregation_job_id().as_ref(),
-                    /* ord */ &TryInto::<i64>::try_into(report_aggregation.ord())?,
-                    /* client_report_id */ &report_aggregation.report_id().as_ref(),
-                    /* client_timestamp */ &report_aggregation.time().as_naive_date_time()?,
-                    /* last_prep_resp */ &encoded_last_prep_resp,
-                    /* state */ &report_aggregation.state().state_code(),
-                    /* public_extensions */ &encoded_state_values.public_extensions,
-                    /* public_share */ &encoded_state_values.public_share,
-                    /* leader_private_extensions */
-                    &encoded_state_values.leader_private_extensions,
-                    /* leader_input_share */ &encoded_state_values.leader_input_share,
-                    /* helper_encrypted_input_share */
-                    &encoded_state_values.helper_encrypted_input_share,
-                    /* leader_prep_transition */
-                    &encoded_state_values.leader_prep_continuation,
-                    /* leader_prep_state */ &encoded_state_values.leader_prep_state,
-                    /* prepare_init */ &encoded_state_values.prepare_init,
-                    /* require_taskbind_extension */
-                    &encoded_state_values.require_taskbind_extension,
-                    /* helper_prep_state */ &encoded_state_values.helper_prep_state,
-                    /* prepare_continue */ &encoded_state_values.prepare_continue,
-                    /* error_code */ &encoded_state_values.report_error,
+                    /* batch_identifier */ &batch_aggregation.encoded_batch_identifier(),
+                    /* batch_interval */ &batch_interval,
+                    /* aggregation_param */ &batch_aggregation.encoded_aggregation_param(),
+                    /* ord */ &i64::try_from(batch_aggregation.ord())?,
+                    /* client_timestamp_interval */
+                    &SqlInterval::from(batch_aggregation.client_timestamp_interval()),
+                    /* state */ &batch_aggregation.state(),
+                    /* aggregate_share */ &encoded_state_values.aggregate_share,
+                    /* report_count */ &encoded_state_values.report_count,
+                    /* checksum */ &encoded_state_values.checksum,
+                    /* aggregation_jobs_created */
+                    &encoded_state_values.aggregation_jobs_created,
+                    /* aggregation_jobs_terminated */
+                    &encoded_state_values.aggregation_jobs_terminated,
                     /* created_at */ &now,
                     /* updated_at */ &now,
                     /* updated_by */ &self.name,
@@ -2713,252 +5593,267 @@ ON CONFLICT(task_id, aggregation_job_id, ord) DO UPDATE
         )
     }
 
-    /// Creates a report aggregation in the `LeaderInit` state from its metadata.
-    ///
-    /// Report shares are copied directly from the `client_reports` table.
+    /// Update an existing `batch_aggregations` row with the values from the provided batch
+    /// aggregation.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn put_leader_report_aggregation(
+    pub async fn update_batch_aggregation<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
         &self,
-        report_aggregation_metadata: &ReportAggregationMetadata,
+        batch_aggregation: &BatchAggregation<SEED_SIZE, B, A>,
     ) -> Result<(), Error> {
-        let task_info = self
-            .task_info_for(report_aggregation_metadata.task_id())
-            .await?
-            .ok_or(Error::MutationTargetNotFound)?;
+        let task_info = match self.task_info_for(batch_aggregation.task_id()).await? {
+            Some(task_info) => task_info,
+            None => return Err(Error::MutationTargetNotFound),
+        };
         let now = self.clock.now().as_naive_date_time()?;
+        let encoded_state_values = batch_aggregation.state().encoded_values_from_state()?;
 
-        report_aggregation_metadata
-            .time()
+        batch_aggregation
+            .client_timestamp_interval()
             .validate_precision(&task_info.time_precision)
             .map_err(|e| {
                 Self::unaligned_time_error(
-                    report_aggregation_metadata.task_id(),
+                    batch_aggregation.task_id(),
                     &task_info.time_precision,
                     e,
                 )
             })?;
 
-        // If there is a conflict, the we upsert the incoming report aggregation (excluded) if the
-        // existing report aggregation is expired (virtually GCed; would be invisible to other
-        // queries that retrieve report rows).
-        // https://github.com/divviup/janus/pull/2818
-        match report_aggregation_metadata.state() {
-            ReportAggregationMetadataState::Init => {
-                let stmt = self
-                    .prepare_cached(
-                        "-- put_leader_report_aggregation()
-INSERT INTO report_aggregations
-    (task_id, aggregation_job_id, ord, client_report_id, client_timestamp,
-    state, public_extensions, public_share, leader_private_extensions,
-    leader_input_share, helper_encrypted_input_share, created_at, updated_at,
-    updated_by)
-SELECT
-    $1, aggregation_jobs.id, $3, $4, $5, 'INIT'::REPORT_AGGREGATION_STATE,
-    client_reports.public_extensions, client_reports.public_share,
-    client_reports.leader_private_extensions,
-    client_reports.leader_input_share,
-    client_reports.helper_encrypted_input_share, $6, $7, $8
-FROM aggregation_jobs
-JOIN client_reports
-    ON aggregation_jobs.task_id = client_reports.task_id
-AND client_reports.report_id = $4
-WHERE aggregation_jobs.task_id = $1
-AND aggregation_job_id = $2
-ON CONFLICT(task_id, aggregation_job_id, ord) DO UPDATE
-    SET (
-        client_report_id, client_timestamp, last_prep_resp, state,
-        public_extensions, public_share, leader_private_extensions,
-        leader_input_share, helper_encrypted_input_share,
-        leader_prep_transition, leader_prep_state,
-        prepare_init, helper_prep_state, prepare_continue, error_code,
-        created_at, updated_at, updated_by
-    ) = (
-        excluded.client_report_id, excluded.client_timestamp,
-        excluded.last_prep_resp, excluded.state, excluded.public_extensions,
-        excluded.public_share, excluded.leader_private_extensions,
-        excluded.leader_input_share, excluded.helper_encrypted_input_share,
-        excluded.leader_prep_transition, excluded.leader_prep_state,
-        excluded.prepare_init,
-        excluded.helper_prep_state, excluded.prepare_continue,
-        excluded.error_code, excluded.created_at, excluded.updated_at,
-        excluded.updated_by
-    )
-    WHERE (SELECT UPPER(client_timestamp_interval)
-        FROM aggregation_jobs
-        WHERE id = report_aggregations.aggregation_job_id) < $9",
-                    )
-                    .await?;
-                check_insert(
-                    self.execute(
-                        &stmt,
-                        &[
-                            /* task_id */ &task_info.pkey,
-                            /* aggregation_job_id */
-                            &report_aggregation_metadata.aggregation_job_id().as_ref(),
-                            /* ord */
-                            &TryInto::<i64>::try_into(report_aggregation_metadata.ord())?,
-                            /* client_report_id */
-                            &report_aggregation_metadata.report_id().as_ref(),
-                            /* client_timestamp */
-                            &report_aggregation_metadata.time().as_naive_date_time()?,
-                            /* created_at */ &now,
-                            /* updated_at */ &now,
-                            /* updated_by */ &self.name,
-                            /* threshold */ &task_info.report_expiry_threshold(&now)?,
-                        ],
-                    )
-                    .await?,
-                )
-            }
-            ReportAggregationMetadataState::Failed { report_error } => {
-                let stmt = self
-                    .prepare_cached(
-                        "-- put_leader_report_aggregation()
-INSERT INTO report_aggregations
-    (task_id, aggregation_job_id, ord, client_report_id, client_timestamp,
-    state, error_code, created_at, updated_at, updated_by)
-SELECT
-    $1, aggregation_jobs.id, $3, $4, $5, 'FAILED'::REPORT_AGGREGATION_STATE,
-    $6, $7, $8, $9
-FROM aggregation_jobs
-JOIN client_reports
-    ON client_reports.task_id = aggregation_jobs.task_id
-   AND client_reports.report_id = $4
-WHERE aggregation_jobs.task_id = $1
-AND aggregation_job_id = $2
-ON CONFLICT(task_id, aggregation_job_id, ord) DO UPDATE
-    SET (
-        client_report_id, client_timestamp, last_prep_resp, state,
-        public_extensions, public_share, leader_private_extensions,
-        leader_input_share, helper_encrypted_input_share,
-        leader_prep_transition, leader_prep_state,
-        prepare_init, helper_prep_state, prepare_continue, error_code,
-        created_at, updated_at, updated_by
-    ) = (
-        excluded.client_report_id, excluded.client_timestamp,
-        excluded.last_prep_resp, excluded.state, excluded.public_extensions,
-        excluded.public_share, excluded.leader_private_extensions,
-        excluded.leader_input_share, excluded.helper_encrypted_input_share,
-        excluded.leader_prep_transition, excluded.leader_prep_state,
-        excluded.prepare_init,
-        excluded.helper_prep_state, excluded.prepare_continue,
-        excluded.error_code, excluded.created_at, excluded.updated_at,
-        excluded.updated_by
-    )
-    WHERE (SELECT UPPER(client_timestamp_interval)
-           FROM aggregation_jobs
-           WHERE id = report_aggregations.aggregation_job_id) < $10",
-                    )
-                    .await?;
-                check_insert(
-                    self.execute(
-                        &stmt,
-                        &[
-                            /* task_id */ &task_info.pkey,
-                            /* aggregation_job_id */
-                            &report_aggregation_metadata.aggregation_job_id().as_ref(),
-                            /* ord */
-                            &TryInto::<i64>::try_into(report_aggregation_metadata.ord())?,
-                            /* client_report_id */
-                            &report_aggregation_metadata.report_id().as_ref(),
-                            /* client_timestamp */
-                            &report_aggregation_metadata.time().as_naive_date_time()?,
-                            /* error_code */ &(*report_error as i16),
-                            /* created_at */ &now,
-                            /* updated_at */ &now,
-                            /* updated_by */ &self.name,
-                            /* threshold */ &task_info.report_expiry_threshold(&now)?,
-                        ],
-                    )
-                    .await?,
-                )
-            }
-        }
+        let stmt = self
+            .prepare_cached(
+                "-- update_batch_aggregations()
+UPDATE batch_aggregations
+SET
+    client_timestamp_interval = $1,
+    state = $2,
+    aggregate_share = $3,
+    report_count = $4,
+    checksum = $5,
+    aggregation_jobs_created = $6,
+    aggregation_jobs_terminated = $7,
+    updated_at = $8,
+    updated_by = $9
+WHERE task_id = $10
+  AND batch_identifier = $11
+  AND aggregation_param = $12
+  AND ord = $13
+  AND GREATEST(
+          UPPER($1::TSRANGE),
+          (SELECT MAX(UPPER(COALESCE(batch_interval, client_timestamp_interval)))
+           FROM batch_aggregations ba
+           WHERE ba.task_id = batch_aggregations.task_id
+             AND ba.batch_identifier = batch_aggregations.batch_identifier
+             AND ba.aggregation_param = batch_aggregations.aggregation_param)) >= $14",
+            )
+            .await?;
+        check_single_row_mutation(
+            self.execute(
+                &stmt,
+                &[
+                    /* client_timestamp_interval */
+                    &SqlInterval::from(batch_aggregation.client_timestamp_interval()),
+                    /* state */ &batch_aggregation.state().state_code(),
+                    /* aggregate_share */ &encoded_state_values.aggregate_share,
+                    /* report_count */ &encoded_state_values.report_count,
+                    /* checksum */ &encoded_state_values.checksum,
+                    /* aggregation_jobs_created */
+                    &encoded_state_values.aggregation_jobs_created,
+                    /* aggregation_jobs_terminated */
+                    &encoded_state_values.aggregation_jobs_terminated,
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* task_id */ &task_info.pkey,
+                    /* batch_identifier */
+                    &batch_aggregation.batch_identifier().get_encoded()?,
+                    /* aggregation_param */
+                    &batch_aggregation.aggregation_parameter().get_encoded()?,
+                    /* ord */
+                    &TryInto::<i64>::try_into(batch_aggregation.ord())?,
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                ],
+            )
+            .await?,
+        )
+    }
+
+    /// Fetch an [`AggregateShareJob`] from the datastore corresponding to given parameters, or
+    /// `None` if no such job exists.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_aggregate_share_job<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        vdaf: &A,
+        task_id: &TaskId,
+        batch_identifier: &B::BatchIdentifier,
+        aggregation_parameter: &A::AggregationParam,
+    ) -> Result<Option<AggregateShareJob<SEED_SIZE, B, A>>, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(None),
+        };
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_aggregate_share_job()
+SELECT helper_aggregate_share, report_count, checksum
+FROM aggregate_share_jobs
+WHERE task_id = $1
+  AND batch_identifier = $2
+  AND aggregation_param = $3
+  AND COALESCE(
+          LOWER(batch_interval),
+          (SELECT MAX(UPPER(client_timestamp_interval))
+           FROM batch_aggregations ba
+           WHERE ba.task_id = aggregate_share_jobs.task_id
+             AND ba.batch_identifier = aggregate_share_jobs.batch_identifier
+             AND ba.aggregation_param = aggregate_share_jobs.aggregation_param),
+          '-infinity'::TIMESTAMP) >= $4",
+            )
+            .await?;
+        self.query_opt(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* batch_identifier */ &batch_identifier.get_encoded()?,
+                /* aggregation_param */ &aggregation_parameter.get_encoded()?,
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
+        )
+        .await?
+        .map(|row| {
+            Self::aggregate_share_job_from_row(
+                vdaf,
+                task_id,
+                batch_identifier.clone(),
+                aggregation_parameter.clone(),
+                &row,
+            )
+        })
+        .transpose()
+    }
+
+    /// Returns all aggregate share jobs for the given task whose collect intervals intersect with
+    /// the given interval, in the order they were created. Applies only to time-interval tasks.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_aggregate_share_jobs_intersecting_interval<
+        const SEED_SIZE: usize,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        vdaf: &A,
+        task_id: &TaskId,
+        interval: &Interval,
+    ) -> Result<Vec<AggregateShareJob<SEED_SIZE, TimeInterval, A>>, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(Vec::new()),
+        };
+
+        interval
+            .validate_precision(&task_info.time_precision)
+            .map_err(|e| Self::unaligned_time_error(task_id, &task_info.time_precision, e))?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_aggregate_share_jobs_intersecting_interval()
+SELECT
+    batch_identifier, aggregation_param, helper_aggregate_share, report_count,
+    checksum
+FROM aggregate_share_jobs
+WHERE task_id = $1
+  AND batch_interval && $2
+  AND LOWER(aggregate_share_jobs.batch_interval) >= $3
+ORDER BY id",
+            )
+            .await?;
+        self.query(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* interval */ &SqlInterval::from(interval),
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
+        )
+        .await?
+        .into_iter()
+        .map(|row| {
+            let batch_identifier = Interval::get_decoded(row.get("batch_identifier"))?;
+            let aggregation_param = A::AggregationParam::get_decoded(row.get("aggregation_param"))?;
+            Self::aggregate_share_job_from_row(
+                vdaf,
+                task_id,
+                batch_identifier,
+                aggregation_param,
+                &row,
+            )
+        })
+        .collect()
     }
 
+    /// Returns all aggregate share jobs for the given task with the given batch identifier, in the
+    /// order they were created. Multiple aggregate share jobs may be returned with distinct
+    /// aggregation parameters. Applies only to leader-selected tasks.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn update_report_aggregation<
+    pub async fn get_aggregate_share_jobs_by_batch_id<
         const SEED_SIZE: usize,
         A: AsyncAggregator<SEED_SIZE>,
     >(
         &self,
-        report_aggregation: &ReportAggregation<SEED_SIZE, A>,
-    ) -> Result<(), Error> {
-        let task_info = match self.task_info_for(report_aggregation.task_id()).await? {
+        vdaf: &A,
+        task_id: &TaskId,
+        batch_id: &BatchId,
+    ) -> Result<Vec<AggregateShareJob<SEED_SIZE, LeaderSelected, A>>, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
             Some(task_info) => task_info,
-            None => return Err(Error::MutationTargetNotFound),
+            None => return Ok(Vec::new()),
         };
-        let now = self.clock.now().as_naive_date_time()?;
-
-        let encoded_state_values = report_aggregation.state().encoded_values_from_state()?;
-        let encoded_last_prep_resp: Option<Vec<u8>> = report_aggregation
-            .last_prep_resp()
-            .map(PrepareResp::get_encoded)
-            .transpose()?;
 
         let stmt = self
             .prepare_cached(
-                "-- update_report_aggregation()
-UPDATE report_aggregations
-SET
-    last_prep_resp = $1, state = $2, public_extensions = $3, public_share = $4,
-    leader_private_extensions = $5, leader_input_share = $6,
-    helper_encrypted_input_share = $7, leader_prep_transition = $8,
-    leader_prep_state = $9, prepare_init = $10,
-    require_taskbind_extension = $11, helper_prep_state = $12,
-    prepare_continue = $13, error_code = $14, updated_at = $15,
-    updated_by = $16
-FROM aggregation_jobs
-WHERE report_aggregations.aggregation_job_id = aggregation_jobs.id
-  AND aggregation_jobs.aggregation_job_id = $17
-  AND aggregation_jobs.task_id = $18
-  AND report_aggregations.task_id = $18
-  AND report_aggregations.client_report_id = $19
-  AND report_aggregations.client_timestamp = $20
-  AND report_aggregations.ord = $21
-  AND UPPER(aggregation_jobs.client_timestamp_interval) >= $22",
+                "-- get_aggregate_share_jobs_by_batch_id()
+SELECT
+    aggregation_param, helper_aggregate_share, report_count, checksum
+FROM aggregate_share_jobs
+WHERE task_id = $1
+  AND batch_identifier = $2
+  AND COALESCE(
+          (SELECT MAX(UPPER(client_timestamp_interval))
+           FROM batch_aggregations ba
+           WHERE ba.task_id = aggregate_share_jobs.task_id
+             AND ba.batch_identifier = aggregate_share_jobs.batch_identifier
+             AND ba.aggregation_param = aggregate_share_jobs.aggregation_param),
+          '-infinity'::TIMESTAMP) >= $3
+ORDER BY id",
             )
             .await?;
-        check_single_row_mutation(
-            self.execute(
-                &stmt,
-                &[
-                    /* last_prep_resp */ &encoded_last_prep_resp,
-                    /* state */ &report_aggregation.state().state_code(),
-                    /* public_extensions */ &encoded_state_values.public_extensions,
-                    /* public_share */ &encoded_state_values.public_share,
-                    /* leader_private_extensions */
-                    &encoded_state_values.leader_private_extensions,
-                    /* leader_input_share */ &encoded_state_values.leader_input_share,
-                    /* helper_encrypted_input_share */
-                    &encoded_state_values.helper_encrypted_input_share,
-                    /* leader_prep_transition */
-                    &encoded_state_values.leader_prep_continuation,
-                    /* leader_prep_state */ &encoded_state_values.leader_prep_state,
-                    /* prepare_init */ &encoded_state_values.prepare_init,
-                    /* require_taskbind_extension */
-                    &encoded_state_values.require_taskbind_extension,
-                    /* helper_prep_state */ &encoded_state_values.helper_prep_state,
-                    /* prepare_continue */ &encoded_state_values.prepare_continue,
-                    /* error_code */ &encoded_state_values.report_error,
-                    /* updated_at */ &now,
-                    /* updated_by */ &self.name,
-                    /* aggregation_job_id */
-                    &report_aggregation.aggregation_job_id().as_ref(),
-                    /* task_id */ &task_info.pkey,
-                    /* client_report_id */ &report_aggregation.report_id().as_ref(),
-                    /* client_timestamp */ &report_aggregation.time().as_naive_date_time()?,
-                    /* ord */ &TryInto::<i64>::try_into(report_aggregation.ord())?,
-                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
-                ],
-            )
-            .await?,
+        self.query(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* batch_id */ &batch_id.get_encoded()?,
+                /* threshold */
+                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+            ],
         )
+        .await?
+        .into_iter()
+        .map(|row| {
+            let aggregation_param = A::AggregationParam::get_decoded(row.get("aggregation_param"))?;
+            Self::aggregate_share_job_from_row(vdaf, task_id, *batch_id, aggregation_param, &row)
+        })
+        .collect()
     }
 
-    /// Returns the collection job for the provided ID, or `None` if no such collection job exists.
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn get_collection_job<
+    #[cfg(feature = "test-util")]
+    pub async fn get_aggregate_share_jobs_for_task<
         const SEED_SIZE: usize,
         B: BatchMode,
         A: AsyncAggregator<SEED_SIZE>,
@@ -2966,2761 +5861,2853 @@ WHERE report_aggregations.aggregation_job_id = aggregation_jobs.id
         &self,
         vdaf: &A,
         task_id: &TaskId,
-        collection_job_id: &CollectionJobId,
-    ) -> Result<Option<CollectionJob<SEED_SIZE, B, A>>, Error> {
+    ) -> Result<Vec<AggregateShareJob<SEED_SIZE, B, A>>, Error> {
         let task_info = match self.task_info_for(task_id).await? {
             Some(task_info) => task_info,
-            None => return Ok(None),
+            None => return Ok(Vec::new()),
         };
 
         let stmt = self
             .prepare_cached(
-                "-- get_collection_job()
+                "-- get_aggregate_share_jobs_for_task()
 SELECT
-    query, aggregation_param, batch_identifier, state, report_count,
-    client_timestamp_interval, helper_aggregate_share, leader_aggregate_share
-FROM collection_jobs
-WHERE collection_jobs.task_id = $1
-  AND collection_jobs.collection_job_id = $2
+    batch_identifier, aggregation_param, helper_aggregate_share, report_count,
+    checksum
+FROM aggregate_share_jobs
+WHERE task_id = $1
   AND COALESCE(
-          LOWER(collection_jobs.batch_interval),
+          LOWER(aggregate_share_jobs.batch_interval),
           (SELECT MAX(UPPER(client_timestamp_interval))
-           FROM batch_aggregations
-           WHERE batch_aggregations.task_id = collection_jobs.task_id
-             AND batch_aggregations.batch_identifier = collection_jobs.batch_identifier
-             AND batch_aggregations.aggregation_param = collection_jobs.aggregation_param),
-          '-infinity'::TIMESTAMP) >= $3",
+           FROM batch_aggregations ba
+           WHERE ba.task_id = aggregate_share_jobs.task_id
+             AND ba.batch_identifier = aggregate_share_jobs.batch_identifier
+             AND ba.aggregation_param = aggregate_share_jobs.aggregation_param),
+          '-infinity'::TIMESTAMP) >= $2",
             )
             .await?;
-        self.query_opt(
+        self.query(
             &stmt,
             &[
                 /* task_id */ &task_info.pkey,
-                /* collection_job_id */ &collection_job_id.as_ref(),
                 /* threshold */
                 &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
             ],
         )
         .await?
+        .into_iter()
         .map(|row| {
             let batch_identifier = B::BatchIdentifier::get_decoded(row.get("batch_identifier"))?;
-            Self::collection_job_from_row(
+            let aggregation_param = A::AggregationParam::get_decoded(row.get("aggregation_param"))?;
+            Self::aggregate_share_job_from_row(
                 vdaf,
-                *task_id,
+                task_id,
                 batch_identifier,
-                *collection_job_id,
+                aggregation_param,
                 &row,
             )
         })
-        .transpose()
+        .collect()
     }
 
-    /// Returns a collection job in state FINISHED with the given parameters, or `None` if no such
-    /// collection job exists.
-    pub async fn get_finished_collection_job<
+    fn aggregate_share_job_from_row<
         const SEED_SIZE: usize,
         B: BatchMode,
         A: AsyncAggregator<SEED_SIZE>,
     >(
-        &self,
         vdaf: &A,
         task_id: &TaskId,
-        batch_identifier: &B::BatchIdentifier,
-        aggregation_param: &A::AggregationParam,
-    ) -> Result<Option<CollectionJob<SEED_SIZE, B, A>>, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
+        batch_identifier: B::BatchIdentifier,
+        aggregation_param: A::AggregationParam,
+        row: &Row,
+    ) -> Result<AggregateShareJob<SEED_SIZE, B, A>, Error> {
+        let helper_aggregate_share =
+            row.get_bytea_and_decode("helper_aggregate_share", &(vdaf, &aggregation_param))?;
+        Ok(AggregateShareJob::new(
+            *task_id,
+            batch_identifier,
+            aggregation_param,
+            helper_aggregate_share,
+            row.get_bigint_and_convert("report_count")?,
+            ReportIdChecksum::get_decoded(row.get("checksum"))?,
+        ))
+    }
+
+    /// Put an `aggregate_share_job` row into the datastore.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn put_aggregate_share_job<
+        const SEED_SIZE: usize,
+        B: CollectableBatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
+        &self,
+        aggregate_share_job: &AggregateShareJob<SEED_SIZE, B, A>,
+    ) -> Result<(), Error> {
+        let task_info = match self.task_info_for(aggregate_share_job.task_id()).await? {
             Some(task_info) => task_info,
-            None => return Ok(None),
+            None => return Err(Error::MutationTargetNotFound),
         };
+        let now = self.clock.now().as_naive_date_time()?;
+        let batch_interval =
+            B::to_batch_interval(aggregate_share_job.batch_identifier()).map(SqlInterval::from);
 
         let stmt = self
             .prepare_cached(
-                "-- get_finished_collection_job()
-SELECT
-    collection_job_id, query, aggregation_param, state, report_count,
-    client_timestamp_interval, helper_aggregate_share, leader_aggregate_share
-FROM collection_jobs
-WHERE collection_jobs.task_id = $1
-  AND collection_jobs.batch_identifier = $2
-  AND collection_jobs.aggregation_param = $3
-  AND collection_jobs.state = 'FINISHED'
-  AND COALESCE(
-          LOWER(collection_jobs.batch_interval),
-          (SELECT MAX(UPPER(client_timestamp_interval))
-           FROM batch_aggregations
-           WHERE batch_aggregations.task_id = collection_jobs.task_id
-             AND batch_aggregations.batch_identifier = collection_jobs.batch_identifier
-             AND batch_aggregations.aggregation_param = collection_jobs.aggregation_param),
-          '-infinity'::TIMESTAMP) >= $4
-LIMIT 1",
+                "-- put_aggregate_share_job()
+INSERT INTO aggregate_share_jobs (
+    task_id, batch_identifier, batch_interval, aggregation_param,
+    helper_aggregate_share, report_count, checksum, created_at, updated_by
+)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+ON CONFLICT(task_id, batch_identifier, aggregation_param) DO UPDATE
+    SET (
+        helper_aggregate_share, report_count, checksum, created_at, updated_by
+    ) = (
+        excluded.helper_aggregate_share, excluded.report_count, excluded.checksum,
+        excluded.created_at, excluded.updated_by
+    )
+    WHERE COALESCE(
+              LOWER(aggregate_share_jobs.batch_interval),
+              (SELECT MAX(UPPER(ba.client_timestamp_interval))
+               FROM batch_aggregations ba
+               WHERE ba.task_id = aggregate_share_jobs.task_id
+                 AND ba.batch_identifier = aggregate_share_jobs.batch_identifier
+                 AND ba.aggregation_param = aggregate_share_jobs.aggregation_param),
+              '-infinity'::TIMESTAMP) < $10",
             )
             .await?;
-        self.query_opt(
-            &stmt,
-            &[
-                /* task_id */ &task_info.pkey,
-                /* batch_identifier */ &batch_identifier.get_encoded()?,
-                /* aggregation_param */ &aggregation_param.get_encoded()?,
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-            ],
-        )
-        .await?
-        .map(|row| {
-            let collection_job_id =
-                row.get_bytea_and_convert::<CollectionJobId>("collection_job_id")?;
-            Self::collection_job_from_row(
-                vdaf,
-                *task_id,
-                batch_identifier.clone(),
-                collection_job_id,
-                &row,
+        check_insert(
+            self.execute(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* batch_identifier */
+                    &aggregate_share_job.batch_identifier().get_encoded()?,
+                    /* batch_interval */ &batch_interval,
+                    /* aggregation_param */
+                    &aggregate_share_job.aggregation_parameter().get_encoded()?,
+                    /* helper_aggregate_share */
+                    &aggregate_share_job.helper_aggregate_share().get_encoded()?,
+                    /* report_count */ &i64::try_from(aggregate_share_job.report_count())?,
+                    /* checksum */ &aggregate_share_job.checksum().get_encoded()?,
+                    /* created_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                ],
             )
-        })
-        .transpose()
+            .await?,
+        )
     }
 
-    /// Returns all collection jobs for the given task which include the given timestamp. Applies
-    /// only to time-interval tasks.
+    /// Writes an outstanding batch. (This method does not take an [`OutstandingBatch`] as several
+    /// of the included values are read implicitly.)
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn get_collection_jobs_including_time<
-        const SEED_SIZE: usize,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    pub async fn put_outstanding_batch(
         &self,
-        vdaf: &A,
         task_id: &TaskId,
-        timestamp: &Time,
-    ) -> Result<Vec<CollectionJob<SEED_SIZE, TimeInterval, A>>, Error> {
-        // TODO(#1553): write unit test
+        batch_id: &BatchId,
+        time_bucket_start: &Option<Time>,
+    ) -> Result<(), Error> {
         let task_info = match self.task_info_for(task_id).await? {
             Some(task_info) => task_info,
-            None => return Ok(Vec::new()),
+            None => return Err(Error::MutationTargetNotFound),
         };
 
+        if let Some(start) = time_bucket_start {
+            start
+                .validate_precision(&task_info.time_precision)
+                .map_err(|e| Self::unaligned_time_error(task_id, &task_info.time_precision, e))?;
+        }
+
+        let now = self.clock.now().as_naive_date_time()?;
+
+        // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param) which
+        // are _not_ garbage collected. This is used to evaluate whether given batch_aggregations
+        // rows are GC'ed.
+        //
+        // Note that this ignores aggregation parameter, as `outstanding_batches` does not need to
+        // worry about aggregation parameters.
+        //
+        // TODO(#225): reevaluate whether we can ignore aggregation parameter here once we have
+        // experience with VDAFs requiring multiple aggregations per batch.
         let stmt = self
             .prepare_cached(
-                "-- get_collection_jobs_including_time()
-SELECT
-    collection_job_id, query, aggregation_param, batch_identifier, state,
-    report_count, client_timestamp_interval, helper_aggregate_share,
-    leader_aggregate_share
-FROM collection_jobs
-WHERE task_id = $1
-  AND batch_interval @> $2::TIMESTAMP
-  AND LOWER(batch_interval) >= $3",
+                "-- put_outstanding_batch()
+WITH non_gc_batches AS (
+    SELECT batch_identifier
+    FROM batch_aggregations
+    WHERE task_id = $1
+      AND batch_identifier = $2
+    GROUP BY batch_identifier
+    HAVING MAX(UPPER(client_timestamp_interval)) >= $6
+)
+INSERT INTO outstanding_batches (
+    task_id, batch_id, time_bucket_start, created_at, updated_by
+)
+VALUES ($1, $2, $3, $4, $5)
+ON CONFLICT(task_id, batch_id) DO UPDATE
+    SET (
+        time_bucket_start, created_at, updated_by
+    ) = (
+        excluded.time_bucket_start, excluded.created_at, excluded.updated_by
+    )
+    WHERE NOT EXISTS(SELECT 1 FROM non_gc_batches
+                     WHERE batch_identifier = outstanding_batches.batch_id)",
             )
             .await?;
-        self.query(
-            &stmt,
-            &[
-                /* task_id */ &task_info.pkey,
-                /* timestamp */ &timestamp.as_naive_date_time()?,
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-            ],
+        check_insert(
+            self.execute(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* batch_id */ batch_id.as_ref(),
+                    /* time_bucket_start */
+                    &time_bucket_start
+                        .as_ref()
+                        .map(Time::as_naive_date_time)
+                        .transpose()?,
+                    /* created_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                ],
+            )
+            .await?,
         )
-        .await?
-        .into_iter()
-        .map(|row| {
-            let batch_identifier = Interval::get_decoded(row.get("batch_identifier"))?;
-            let collection_job_id =
-                row.get_bytea_and_convert::<CollectionJobId>("collection_job_id")?;
-            Self::collection_job_from_row(vdaf, *task_id, batch_identifier, collection_job_id, &row)
-        })
-        .collect()
     }
 
-    /// Returns all collection jobs for the given task whose collect intervals intersect with the
-    /// given interval. Applies only to time-interval tasks.
+    /// Retrieves all [`OutstandingBatch`]es for a given task and (if applicable) time bucket which
+    /// have not yet been marked filled.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn get_collection_jobs_intersecting_interval<
-        const SEED_SIZE: usize,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    pub async fn get_unfilled_outstanding_batches(
         &self,
-        vdaf: &A,
         task_id: &TaskId,
-        batch_interval: &Interval,
-    ) -> Result<Vec<CollectionJob<SEED_SIZE, TimeInterval, A>>, Error> {
-        // TODO(#1553): write unit test
+        time_bucket_start: &Option<Time>,
+    ) -> Result<Vec<OutstandingBatch>, Error> {
         let task_info = match self.task_info_for(task_id).await? {
             Some(task_info) => task_info,
             None => return Ok(Vec::new()),
         };
 
-        batch_interval
-            .validate_precision(&task_info.time_precision)
-            .map_err(|e| Self::unaligned_time_error(task_id, &task_info.time_precision, e))?;
+        let rows = if let Some(time_bucket_start) = time_bucket_start {
+            // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param)
+            // which are _not_ garbage collected. This is used to evaluate whether given
+            // batch_aggregations rows are GC'ed.
+            let stmt = self
+                .prepare_cached(
+                    "-- get_unfilled_outstanding_batches()
+SELECT batch_id FROM outstanding_batches
+WHERE task_id = $1
+  AND time_bucket_start = $2
+  AND state = 'FILLING'
+  AND (SELECT MAX(UPPER(client_timestamp_interval)) FROM batch_aggregations
+          WHERE batch_identifier = outstanding_batches.batch_id
+            AND task_id = $1
+  ) >= $3",
+                )
+                .await?;
+            self.query(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* time_bucket_start */ &time_bucket_start.as_naive_date_time()?,
+                    /* threshold */
+                    &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+                ],
+            )
+            .await?
+        } else {
+            // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param)
+            // which are _not_ garbage collected. This is used to evaluate whether given
+            // batch_aggregations rows are GC'ed.
+            let stmt = self
+                .prepare_cached(
+                    "-- get_unfilled_outstanding_batches()
+SELECT batch_id FROM outstanding_batches
+WHERE task_id = $1
+  AND time_bucket_start IS NULL
+  AND state = 'FILLING'
+  AND (SELECT MAX(UPPER(client_timestamp_interval)) FROM batch_aggregations
+          WHERE batch_identifier = outstanding_batches.batch_id
+            AND task_id = $1
+  ) >= $2",
+                )
+                .await?;
+            self.query(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* threshold */
+                    &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+                ],
+            )
+            .await?
+        };
+
+        try_join_all(rows.into_iter().map(|row| async move {
+            let batch_id = BatchId::get_decoded(row.get("batch_id"))?;
+            let size = self.read_batch_size(task_info.pkey, &batch_id).await?;
+            Ok(OutstandingBatch::new(*task_id, batch_id, size))
+        }))
+        .await
+    }
 
+    // Return value is an inclusive range [min_size, max_size], where:
+    //  * min_size is the minimum possible number of reports included in the batch, i.e. all report
+    //    aggregations in the batch which have reached the FINISHED state.
+    //  * max_size is the maximum possible number of reports included in the batch, i.e. all report
+    //    aggregations in the batch which are in a non-failure state (INIT/CONTINUE/FINISHED).
+    async fn read_batch_size(
+        &self,
+        task_pkey: i64,
+        batch_id: &BatchId,
+    ) -> Result<RangeInclusive<usize>, Error> {
         let stmt = self
             .prepare_cached(
-                "-- get_collection_jobs_intersecting_interval()
+                "-- read_batch_size()
+WITH report_aggregations_count AS (
+    SELECT COUNT(*) AS count FROM report_aggregations
+    JOIN aggregation_jobs
+        ON report_aggregations.aggregation_job_id = aggregation_jobs.id
+    WHERE aggregation_jobs.task_id = $1
+    AND report_aggregations.task_id = aggregation_jobs.task_id
+    AND aggregation_jobs.batch_id = $2
+    AND report_aggregations.state in ('INIT', 'CONTINUE')
+),
+batch_aggregation_count AS (
+    -- A batch may be aggregated under several aggregation parameters; each of these covers the
+    -- same reports, so only the largest count is meaningful.
+    SELECT MAX(count) AS count FROM (
+        SELECT SUM(report_count) AS count FROM batch_aggregations
+        WHERE batch_aggregations.task_id = $1
+        AND batch_aggregations.batch_identifier = $2
+        GROUP BY aggregation_param
+    ) AS counts_by_aggregation_param
+)
 SELECT
-    collection_job_id, query, aggregation_param, batch_identifier, state,
-    report_count, client_timestamp_interval, helper_aggregate_share,
-    leader_aggregate_share
-FROM collection_jobs
-WHERE task_id = $1
-  AND batch_interval && $2
-  AND LOWER(collection_jobs.batch_interval) >= $3",
+    (SELECT count FROM batch_aggregation_count)::BIGINT AS min_size,
+    (SELECT count FROM report_aggregations_count)::BIGINT
+        + (SELECT count FROM batch_aggregation_count)::BIGINT AS max_size",
             )
             .await?;
-        self.query(
-            &stmt,
-            &[
-                /* task_id */ &task_info.pkey,
-                /* batch_interval */ &SqlInterval::from(batch_interval),
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-            ],
-        )
-        .await?
-        .into_iter()
-        .map(|row| {
-            let batch_identifier = Interval::get_decoded(row.get("batch_identifier"))?;
-            let collection_job_id =
-                row.get_bytea_and_convert::<CollectionJobId>("collection_job_id")?;
-            Self::collection_job_from_row::<SEED_SIZE, TimeInterval, A>(
-                vdaf,
-                *task_id,
-                batch_identifier,
-                collection_job_id,
-                &row,
+
+        let row = self
+            .query_one(
+                &stmt,
+                &[
+                    /* task_id */ &task_pkey,
+                    /* batch_id */ batch_id.as_ref(),
+                ],
             )
-        })
-        .collect()
+            .await?;
+
+        Ok(RangeInclusive::new(
+            row.get::<_, Option<i64>>("min_size")
+                .unwrap_or_default()
+                .try_into()?,
+            row.get::<_, Option<i64>>("max_size")
+                .unwrap_or_default()
+                .try_into()?,
+        ))
     }
 
-    /// Retrieves all collection jobs for the given batch ID. Multiple collection jobs may be
-    /// returned with distinct aggregation parameters. Applies only to leader-selected tasks.
+    /// Retrieves an outstanding batch for the given task with at least the given number of
+    /// successfully-aggregated reports, removing it from the datastore.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn get_collection_jobs_by_batch_id<
-        const SEED_SIZE: usize,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    pub async fn acquire_outstanding_batch_with_report_count(
         &self,
-        vdaf: &A,
         task_id: &TaskId,
-        batch_id: &BatchId,
-    ) -> Result<Vec<CollectionJob<SEED_SIZE, LeaderSelected, A>>, Error> {
-        // TODO(#1553): write unit test
+        min_report_count: u64,
+    ) -> Result<Option<BatchId>, Error> {
         let task_info = match self.task_info_for(task_id).await? {
             Some(task_info) => task_info,
-            None => return Ok(Vec::new()),
+            None => return Ok(None),
         };
 
+        // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param) which
+        // are _not_ garbage collected. This is used to evaluate whether given batch_aggregations
+        // rows are GC'ed.
         let stmt = self
             .prepare_cached(
-                "-- get_collection_jobs_by_batch_id()
-SELECT
-    collection_job_id, query, aggregation_param, state, report_count,
-    client_timestamp_interval, helper_aggregate_share, leader_aggregate_share
-FROM collection_jobs
-WHERE task_id = $1
-  AND batch_identifier = $2
-  AND COALESCE(
-          (SELECT MAX(UPPER(client_timestamp_interval))
-           FROM batch_aggregations ba
-           WHERE ba.task_id = collection_jobs.task_id
-             AND ba.batch_identifier = collection_jobs.batch_identifier
-             AND ba.aggregation_param = collection_jobs.aggregation_param),
-          '-infinity'::TIMESTAMP) >= $3",
+                "-- acquire_outstanding_batch_with_report_count()
+WITH batch_aggregation_counts AS (
+    SELECT batch_identifier, SUM(report_count) AS report_count,
+        MAX(UPPER(client_timestamp_interval)) AS max_client_timestamp
+    FROM batch_aggregations
+    WHERE task_id = $1
+    GROUP BY batch_identifier, aggregation_param
+),
+non_gc_batches AS (
+    SELECT batch_identifier, MAX(report_count) AS report_count
+    FROM batch_aggregation_counts
+    GROUP BY batch_identifier
+    HAVING MAX(max_client_timestamp) >= $3
+),
+selected_outstanding_batch AS (
+    SELECT outstanding_batches.id
+    FROM outstanding_batches
+    WHERE task_id = $1
+    AND (SELECT report_count FROM non_gc_batches
+        WHERE batch_identifier = outstanding_batches.batch_id) >= $2::BIGINT
+    LIMIT 1
+)
+DELETE FROM outstanding_batches WHERE id IN (SELECT id FROM selected_outstanding_batch)
+RETURNING batch_id",
             )
             .await?;
-        self.query(
+
+        self.query_opt(
             &stmt,
             &[
                 /* task_id */ &task_info.pkey,
-                /* batch_id */ &batch_id.get_encoded()?,
+                /* min_report_count */ &i64::try_from(min_report_count)?,
                 /* threshold */
                 &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
             ],
         )
         .await?
-        .into_iter()
-        .map(|row| {
-            let collection_job_id =
-                row.get_bytea_and_convert::<CollectionJobId>("collection_job_id")?;
-            Self::collection_job_from_row(vdaf, *task_id, *batch_id, collection_job_id, &row)
-        })
-        .collect()
+        .map(|row| Ok(BatchId::get_decoded(row.get("batch_id"))?))
+        .transpose()
     }
 
-    #[cfg(feature = "test-util")]
-    pub async fn get_collection_jobs_for_task<
-        const SEED_SIZE: usize,
-        B: BatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    /// Marks a given outstanding batch as filled, such that it will no longer be considered when
+    /// assigning aggregation jobs to batches.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn mark_outstanding_batch_filled(
         &self,
-        vdaf: &A,
         task_id: &TaskId,
-    ) -> Result<Vec<CollectionJob<SEED_SIZE, B, A>>, Error> {
+        batch_id: &BatchId,
+    ) -> Result<(), Error> {
         let task_info = match self.task_info_for(task_id).await? {
             Some(task_info) => task_info,
-            None => return Ok(Vec::new()),
+            None => return Err(Error::MutationTargetNotFound),
         };
+        let now = self.clock.now().as_naive_date_time()?;
 
         let stmt = self
             .prepare_cached(
-                "-- get_collection_jobs_for_task()
-SELECT
-    collection_job_id, query, aggregation_param, batch_identifier, state,
-    report_count, client_timestamp_interval, helper_aggregate_share,
-    leader_aggregate_share
-FROM collection_jobs
+                "-- mark_outstanding_batch_filled()
+WITH non_gc_batches AS (
+    SELECT batch_identifier
+    FROM batch_aggregations
+    WHERE task_id = $1
+    AND batch_identifier = $2
+    GROUP BY batch_identifier
+    HAVING MAX(UPPER(client_timestamp_interval)) >= $3
+)
+UPDATE outstanding_batches
+SET state = 'FILLED'
 WHERE task_id = $1
-  AND COALESCE(
-          LOWER(batch_interval),
-          (SELECT MAX(UPPER(ba.client_timestamp_interval))
-           FROM batch_aggregations ba
-           WHERE ba.task_id = collection_jobs.task_id
-             AND ba.batch_identifier = collection_jobs.batch_identifier
-             AND ba.aggregation_param = collection_jobs.aggregation_param),
-          '-infinity'::TIMESTAMP) >= $2",
+AND batch_id = $2
+AND EXISTS(SELECT 1 FROM non_gc_batches WHERE batch_identifier = $2)",
             )
             .await?;
-        self.query(
-            &stmt,
-            &[
-                /* task_id */ &task_info.pkey,
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-            ],
+        check_single_row_mutation(
+            self.execute(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* batch_id */ batch_id.as_ref(),
+                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                ],
+            )
+            .await?,
         )
-        .await?
-        .into_iter()
-        .map(|row| {
-            let collection_job_id =
-                row.get_bytea_and_convert::<CollectionJobId>("collection_job_id")?;
-            let batch_identifier = B::BatchIdentifier::get_decoded(row.get("batch_identifier"))?;
-            Self::collection_job_from_row(vdaf, *task_id, batch_identifier, collection_job_id, &row)
-        })
-        .collect()
-    }
-
-    fn collection_job_from_row<
-        const SEED_SIZE: usize,
-        B: BatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
-        vdaf: &A,
-        task_id: TaskId,
-        batch_identifier: B::BatchIdentifier,
-        collection_job_id: CollectionJobId,
-        row: &Row,
-    ) -> Result<CollectionJob<SEED_SIZE, B, A>, Error> {
-        let query = Query::<B>::get_decoded(row.get("query"))?;
-        let aggregation_param = A::AggregationParam::get_decoded(row.get("aggregation_param"))?;
-        let state: CollectionJobStateCode = row.get("state");
-        let report_count: Option<i64> = row.get("report_count");
-        let client_timestamp_interval: Option<SqlInterval> = row.get("client_timestamp_interval");
-        let helper_aggregate_share_bytes: Option<Vec<u8>> = row.get("helper_aggregate_share");
-        let leader_aggregate_share_bytes: Option<Vec<u8>> = row.get("leader_aggregate_share");
-
-        let state = match state {
-            CollectionJobStateCode::Start => CollectionJobState::Start,
-
-            CollectionJobStateCode::Finished => {
-                let report_count = u64::try_from(report_count.ok_or_else(|| {
-                    Error::DbState(
-                        "collection job in state FINISHED but report_count is NULL".to_string(),
-                    )
-                })?)?;
-                let client_timestamp_interval = client_timestamp_interval
-                    .ok_or_else(|| Error::DbState(
-                        "collection job in state FINISHED but client_timestamp_interval is NULL".to_string())
-                    )?.as_interval();
-                let encrypted_helper_aggregate_share = HpkeCiphertext::get_decoded(
-                    &helper_aggregate_share_bytes.ok_or_else(|| {
-                        Error::DbState(
-                            "collection job in state FINISHED but helper_aggregate_share is NULL"
-                                .to_string(),
-                        )
-                    })?,
-                )?;
-                let leader_aggregate_share = A::AggregateShare::get_decoded_with_param(
-                    &(vdaf, &aggregation_param),
-                    &leader_aggregate_share_bytes.ok_or_else(|| {
-                        Error::DbState(
-                            "collection job is in state FINISHED but leader_aggregate_share is \
-                             NULL"
-                                .to_string(),
-                        )
-                    })?,
-                )?;
-                CollectionJobState::Finished {
-                    report_count,
-                    client_timestamp_interval,
-                    encrypted_helper_aggregate_share,
-                    leader_aggregate_share,
-                }
-            }
-
-            CollectionJobStateCode::Abandoned => CollectionJobState::Abandoned,
-
-            CollectionJobStateCode::Deleted => CollectionJobState::Deleted,
-        };
-
-        Ok(CollectionJob::new(
-            task_id,
-            collection_job_id,
-            query,
-            aggregation_param,
-            batch_identifier,
-            state,
-        ))
     }
 
-    /// Stores a new collection job.
+    /// Deletes old client reports for a given task, that is, client reports whose timestamp is
+    /// older than the task's report expiry age. Up to `limit` client reports will be deleted.
+    /// Returns the number of client reports deleted.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn put_collection_job<
-        const SEED_SIZE: usize,
-        B: CollectableBatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    pub async fn delete_expired_client_reports(
         &self,
-        collection_job: &CollectionJob<SEED_SIZE, B, A>,
-    ) -> Result<(), Error> {
-        let task_info = match self.task_info_for(collection_job.task_id()).await? {
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
             Some(task_info) => task_info,
-            None => return Err(Error::MutationTargetNotFound),
+            None => return Ok(0),
         };
-        let now = self.clock.now().as_naive_date_time()?;
+        let threshold =
+            task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?;
+        self.delete_client_reports_before(&task_info, threshold, limit)
+            .await
+    }
 
-        let batch_interval =
-            B::to_batch_interval(collection_job.batch_identifier()).map(SqlInterval::from);
+    /// Deletes client reports for a given task regardless of their timestamps, as is done for
+    /// archived tasks. Up to `limit` client reports will be deleted. Returns the number of client
+    /// reports deleted.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn delete_all_client_reports(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(0),
+        };
+        self.delete_client_reports_before(&task_info, Timestamp::PosInfinity, limit)
+            .await
+    }
 
+    async fn delete_client_reports_before(
+        &self,
+        task_info: &TaskInfo,
+        threshold: Timestamp<NaiveDateTime>,
+        limit: u64,
+    ) -> Result<u64, Error> {
         let stmt = self
             .prepare_cached(
-                "-- put_collection_job()
-INSERT INTO collection_jobs
-    (task_id, collection_job_id, query, aggregation_param, batch_identifier,
-    batch_interval, state, created_at, updated_at, updated_by)
-VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-ON CONFLICT(task_id, collection_job_id) DO UPDATE
-    SET (
-        query, aggregation_param, batch_identifier, batch_interval, state,
-        created_at, updated_at, updated_by
-    ) = (
-        excluded.query, excluded.aggregation_param, excluded.batch_identifier,
-        excluded.batch_interval, excluded.state, excluded.created_at,
-        excluded.updated_at, excluded.updated_by
-    )
-    WHERE COALESCE(
-              LOWER(collection_jobs.batch_interval),
-              (SELECT MAX(UPPER(ba.client_timestamp_interval))
-               FROM batch_aggregations ba
-               WHERE ba.task_id = collection_jobs.task_id
-                 AND ba.batch_identifier = collection_jobs.batch_identifier
-                 AND ba.aggregation_param = collection_jobs.aggregation_param),
-              '-infinity'::TIMESTAMP) < $11",
+                "-- delete_client_reports_before()
+WITH client_reports_to_delete AS (
+    SELECT client_reports.id, client_reports.task_id, client_reports.report_id,
+        client_reports.client_timestamp
+    FROM client_reports
+    WHERE client_reports.task_id = $1
+        AND client_reports.client_timestamp < $2::TIMESTAMP
+    LIMIT $3
+),
+deleted_report_ids AS (
+    DELETE FROM client_report_ids
+    USING client_reports_to_delete
+    WHERE client_report_ids.task_id = client_reports_to_delete.task_id
+        AND client_report_ids.report_id = client_reports_to_delete.report_id
+        AND client_report_ids.client_timestamp = client_reports_to_delete.client_timestamp
+)
+DELETE FROM client_reports
+USING client_reports_to_delete
+WHERE client_reports.id = client_reports_to_delete.id",
             )
             .await?;
-
-        check_insert(
-            self.execute(
-                &stmt,
-                &[
-                    /* task_id */ &task_info.pkey,
-                    /* collection_job_id */ collection_job.id().as_ref(),
-                    /* query */ &collection_job.query().get_encoded()?,
-                    /* aggregation_param */
-                    &collection_job.aggregation_parameter().get_encoded()?,
-                    /* batch_identifier */ &collection_job.batch_identifier().get_encoded()?,
-                    /* batch_interval */ &batch_interval,
-                    /* state */ &collection_job.state().collection_job_state_code(),
-                    /* created_at */ &now,
-                    /* updated_at */ &now,
-                    /* updated_by */ &self.name,
-                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
-                ],
-            )
-            .await?,
+        self.execute(
+            &stmt,
+            &[
+                /* id */ &task_info.pkey,
+                /* threshold */ &threshold,
+                /* limit */ &i64::try_from(limit)?,
+            ],
         )
+        .await
+        .map_err(Into::into)
     }
 
-    /// acquire_incomplete_collection_jobs retrieves & acquires the IDs of unclaimed incomplete
-    /// collection jobs. At most `maximum_acquire_count` jobs are acquired. The job is acquired with
-    /// a "lease" that will time out; the desired duration of the lease is a parameter, and the
-    /// lease expiration time is returned.
+    /// Scrubs the payloads of client reports for a given task which were aggregated longer ago
+    /// than the task's report retention window, retaining only the metadata kept by
+    /// [`Self::scrub_client_report`]. Tasks without a report retention window are unaffected,
+    /// since their reports are scrubbed as they are aggregated. Up to `limit` client reports will
+    /// be scrubbed. Returns the number of client reports scrubbed.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn acquire_incomplete_collection_jobs(
+    pub async fn scrub_retained_client_reports(
         &self,
-        lease_duration: &StdDuration,
-        maximum_acquire_count: usize,
-    ) -> Result<Vec<Lease<AcquiredCollectionJob>>, Error> {
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(0),
+        };
         let now = self.clock.now().as_naive_date_time()?;
-        let lease_expiry_time = add_naive_date_time_duration(&now, lease_duration)?;
-        let maximum_acquire_count: i64 = maximum_acquire_count.try_into()?;
 
         let stmt = self
             .prepare_cached(
-                "-- acquire_incomplete_collection_jobs()
-WITH incomplete_jobs AS (
-    SELECT
-        collection_jobs.id, collection_jobs.batch_identifier, tasks.task_id,
-        tasks.batch_mode, tasks.vdaf, tasks.time_precision
-    FROM collection_jobs
-    JOIN tasks ON tasks.id = collection_jobs.task_id
-    WHERE tasks.aggregator_role = 'LEADER'
-      AND collection_jobs.state = 'START'
-      AND collection_jobs.lease_expiry <= $4
-      AND COALESCE(
-              LOWER(batch_interval),
-              (SELECT MAX(UPPER(ba.client_timestamp_interval))
-               FROM batch_aggregations ba
-               WHERE ba.task_id = collection_jobs.task_id
-                 AND ba.batch_identifier = collection_jobs.batch_identifier
-                 AND ba.aggregation_param = collection_jobs.aggregation_param),
-              '-infinity'::TIMESTAMP)
-          >= COALESCE(
-                 $4::TIMESTAMP - tasks.report_expiry_age * '1 second'::INTERVAL,
-                 '-infinity'::TIMESTAMP
-             )
-    FOR UPDATE OF collection_jobs SKIP LOCKED LIMIT $5
+                "-- scrub_retained_client_reports()
+WITH client_reports_to_scrub AS (
+    SELECT client_reports.id FROM client_reports
+    JOIN tasks ON tasks.id = client_reports.task_id
+    WHERE client_reports.task_id = $1
+        AND client_reports.aggregation_started = TRUE
+        AND client_reports.leader_input_share IS NOT NULL
+        AND tasks.report_retention_window IS NOT NULL
+        AND client_reports.updated_at
+            < $2::TIMESTAMP - tasks.report_retention_window * '1 second'::INTERVAL
+    LIMIT $3
 )
-UPDATE collection_jobs SET
-    lease_expiry = $1,
-    lease_token = gen_random_bytes(16),
-    lease_attempts = lease_attempts + 1,
+UPDATE client_reports SET
+    public_extensions = NULL,
+    public_share = NULL,
+    leader_private_extensions = NULL,
+    leader_input_share = NULL,
+    helper_encrypted_input_share = NULL,
     updated_at = $2,
-    updated_by = $3
-FROM incomplete_jobs
-WHERE collection_jobs.id = incomplete_jobs.id
-RETURNING
-    incomplete_jobs.task_id, incomplete_jobs.batch_mode, incomplete_jobs.vdaf,
-    incomplete_jobs.time_precision, collection_jobs.collection_job_id,
-    collection_jobs.batch_identifier, collection_jobs.aggregation_param,
-    collection_jobs.lease_token, collection_jobs.lease_attempts,
-    collection_jobs.step_attempts",
+    updated_by = $4
+FROM client_reports_to_scrub
+WHERE client_reports.id = client_reports_to_scrub.id",
             )
             .await?;
-
-        self.query(
+        self.execute(
             &stmt,
             &[
-                /* lease_expiry */ &lease_expiry_time,
-                /* updated_at */ &now,
-                /* updated_by */ &self.name,
+                /* task_id */ &task_info.pkey,
                 /* now */ &now,
-                /* limit */ &maximum_acquire_count,
+                /* limit */ &i64::try_from(limit)?,
+                /* updated_by */ &self.name,
             ],
         )
-        .await?
-        .into_iter()
-        .map(|row| {
-            let task_id = TaskId::get_decoded(row.get("task_id"))?;
-            let collection_job_id =
-                row.get_bytea_and_convert::<CollectionJobId>("collection_job_id")?;
-            let batch_mode = row.try_get::<_, Json<task::BatchMode>>("batch_mode")?.0;
-            let vdaf = row.try_get::<_, Json<VdafInstance>>("vdaf")?.0;
-            let time_precision =
-                Duration::from_seconds(row.get_bigint_and_convert("time_precision")?);
-            let encoded_batch_identifier = row.get("batch_identifier");
-            let encoded_aggregation_param = row.get("aggregation_param");
-            let lease_token = row.get_bytea_and_convert::<LeaseToken>("lease_token")?;
-            let lease_attempts = row.get_bigint_and_convert("lease_attempts")?;
-            let step_attempts = row.get_bigint_and_convert("step_attempts")?;
+        .await
+        .map_err(Into::into)
+    }
 
-            Ok(Lease::new(
-                AcquiredCollectionJob::new(
-                    task_id,
-                    collection_job_id,
-                    batch_mode,
-                    vdaf,
-                    time_precision,
-                    encoded_batch_identifier,
-                    encoded_aggregation_param,
-                    step_attempts,
-                ),
-                lease_expiry_time,
-                lease_token,
-                lease_attempts,
-            ))
-        })
-        .collect()
+    /// Deletes old aggregation artifacts (aggregation jobs/report aggregations) for a given task,
+    /// that is, aggregation artifacts for which the aggregation job's maximum client timestamp is
+    /// older than the task's report expiry age. Up to `limit` aggregation jobs will be deleted,
+    /// along with all related aggregation artifacts. Returns the number of aggregation jobs
+    /// deleted.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn delete_expired_aggregation_artifacts(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(0),
+        };
+        let threshold =
+            task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?;
+        self.delete_aggregation_artifacts_before(&task_info, threshold, limit)
+            .await
+    }
+
+    /// Deletes aggregation artifacts (aggregation jobs/report aggregations) for a given task
+    /// regardless of their client timestamps, as is done for archived tasks. Up to `limit`
+    /// aggregation jobs will be deleted, along with all related aggregation artifacts. Returns the
+    /// number of aggregation jobs deleted.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn delete_all_aggregation_artifacts(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(0),
+        };
+        self.delete_aggregation_artifacts_before(&task_info, Timestamp::PosInfinity, limit)
+            .await
+    }
+
+    async fn delete_aggregation_artifacts_before(
+        &self,
+        task_info: &TaskInfo,
+        threshold: Timestamp<NaiveDateTime>,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        let stmt = self
+            .prepare_cached(
+                "-- delete_aggregation_artifacts_before()
+WITH aggregation_jobs_to_delete AS (
+    SELECT aggregation_jobs.id FROM aggregation_jobs
+    WHERE task_id = $1
+      AND UPPER(aggregation_jobs.client_timestamp_interval) < $2
+    LIMIT $3
+),
+deleted_report_aggregations AS (
+    DELETE FROM report_aggregations
+    WHERE aggregation_job_id IN (SELECT id FROM aggregation_jobs_to_delete)
+)
+DELETE FROM aggregation_jobs
+WHERE id IN (SELECT id FROM aggregation_jobs_to_delete)",
+            )
+            .await?;
+        self.execute(
+            &stmt,
+            &[
+                /* task_id */ &task_info.pkey,
+                /* threshold */ &threshold,
+                /* limit */ &i64::try_from(limit)?,
+            ],
+        )
+        .await
+        .map_err(Into::into)
     }
 
-    /// release_collection_job releases an acquired (via e.g. acquire_incomplete_collection_jobs)
-    /// collect job. If given, `reacquire_delay` determines the duration of time that must pass
-    /// before the collection job can be reacquired; this method assumes a reacquire delay indicates
-    /// that no progress was made, and will increment `step_attempts` accordingly. It returns an
-    /// error if the collection job has no current lease.
+    /// Deletes old collection artifacts (outstanding batches/batch aggregations/collection jobs/
+    /// aggregate share jobs) for a given task per the following policy:
+    ///
+    /// * batch_aggregations and outstanding_batches will be considered part of the same entity for
+    ///   purposes of GC, and will be considered eligible for GC once the maximum of the
+    ///   batch_interval (for time-interval) or merged client_timestamp_interval (for
+    ///   leader-selected) of the batch_aggregations rows is older than report_expiry_age.
+    /// * collection_jobs and aggregate_share_jobs use a batch mode-specific rule to determine
+    ///   GC-eligiblity.
+    ///   * For time-interval tasks, collection_jobs and aggregate_share_jobs are considered
+    ///     eligible for GC if the minimum of the collection interval is older than
+    ///     report_expiry_age. (The minimum is used instead of the maximum to ensure that collection
+    ///     jobs are not GC'ed after their underlying aggregation information from
+    ///     batch_aggregations.)
+    ///   * For leader-selected tasks, collection_jobs and aggregate_share_jobs are considered
+    ///     eligible for GC if the related batch is eligible for GC, based on the
+    ///     `batch_aggregations` rows.
+    ///
+    /// Up to `limit` batches will be deleted, along with all related collection artifacts.
+    ///
+    /// Returns the number of batches deleted.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn release_collection_job(
+    pub async fn delete_expired_collection_artifacts(
         &self,
-        lease: &Lease<AcquiredCollectionJob>,
-        reacquire_delay: Option<&StdDuration>,
-    ) -> Result<(), Error> {
-        let task_info = match self.task_info_for(lease.leased().task_id()).await? {
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
             Some(task_info) => task_info,
-            None => return Err(Error::MutationTargetNotFound),
+            None => return Ok(0),
         };
-        let now = self.clock.now().as_naive_date_time()?;
-
-        let lease_expiration = reacquire_delay
-            .map(|rd| add_naive_date_time_duration(&now, rd))
-            .transpose()?
-            .map(Timestamp::Value)
-            .unwrap_or_else(|| Timestamp::NegInfinity);
 
         let stmt = self
             .prepare_cached(
-                "-- release_collection_job()
-UPDATE collection_jobs
-SET lease_expiry = $1,
-    lease_token = NULL,
-    lease_attempts = 0,
-    step_attempts = CASE
-            WHEN $6 = '-infinity'::TIMESTAMP THEN 0
-            ELSE step_attempts + 1
-        END,
-    updated_at = $2,
-    updated_by = $3
-WHERE task_id = $4
-  AND collection_job_id = $5
-  AND lease_expiry = $6
-  AND lease_token = $7
-  AND COALESCE(
-          LOWER(batch_interval),
-          (SELECT MAX(UPPER(ba.client_timestamp_interval))
-           FROM batch_aggregations ba
-           WHERE ba.task_id = collection_jobs.task_id
-             AND ba.batch_identifier = collection_jobs.batch_identifier
-             AND ba.aggregation_param = collection_jobs.aggregation_param),
-          '-infinity'::TIMESTAMP) >= $8",
+                "-- delete_expired_collection_artifacts()
+WITH candidate_batches_to_delete AS (
+    SELECT DISTINCT batch_identifier, aggregation_param
+    FROM batch_aggregations
+    WHERE task_id = $1
+      AND UPPER(COALESCE(batch_interval, client_timestamp_interval)) < $2
+),
+batches_to_delete AS (
+    SELECT ba.batch_identifier, ba.aggregation_param
+    FROM batch_aggregations AS ba
+    JOIN candidate_batches_to_delete AS candidate
+      ON ba.batch_identifier = candidate.batch_identifier
+     AND ba.aggregation_param = candidate.aggregation_param
+    WHERE ba.task_id = $1
+    GROUP BY ba.batch_identifier, ba.aggregation_param
+    HAVING MAX(UPPER(COALESCE(ba.batch_interval, ba.client_timestamp_interval))) < $2
+    LIMIT $3
+),
+deleted_outstanding_batches AS (
+    DELETE FROM outstanding_batches
+    USING batches_to_delete
+    WHERE task_id = $1
+      AND outstanding_batches.batch_id = batches_to_delete.batch_identifier
+),
+deleted_collection_jobs AS (
+    DELETE FROM collection_jobs
+    USING batches_to_delete
+    WHERE task_id = $1
+      AND (LOWER(batch_interval) < $2
+        OR (collection_jobs.batch_identifier = batches_to_delete.batch_identifier
+          AND collection_jobs.aggregation_param = batches_to_delete.aggregation_param))
+),
+deleted_aggregate_share_jobs AS (
+    DELETE FROM aggregate_share_jobs
+    USING batches_to_delete
+    WHERE task_id = $1
+      AND (LOWER(batch_interval) < $2
+        OR (aggregate_share_jobs.batch_identifier = batches_to_delete.batch_identifier
+          AND aggregate_share_jobs.aggregation_param = batches_to_delete.aggregation_param))
+),
+deleted_batch_aggregations AS (
+    DELETE FROM batch_aggregations
+    USING batches_to_delete
+    WHERE task_id = $1
+      AND batch_aggregations.batch_identifier = batches_to_delete.batch_identifier
+      AND batch_aggregations.aggregation_param = batches_to_delete.aggregation_param
+)
+SELECT COUNT(1) AS batch_count FROM batches_to_delete",
             )
             .await?;
-        check_single_row_mutation(
-            self.execute(
+        let row = self
+            .query_one(
                 &stmt,
                 &[
-                    /* lease_expiry */ &lease_expiration,
-                    /* updated_at */ &now,
-                    /* updated_by */ &self.name,
                     /* task_id */ &task_info.pkey,
-                    /* collection_job_id */ &lease.leased().collection_job_id().as_ref(),
-                    /* lease_expiry */ &lease.lease_expiry_time(),
-                    /* lease_token */ &lease.lease_token().as_ref(),
-                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                    /* threshold */
+                    &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+                    /* limit */ &i64::try_from(limit)?,
                 ],
             )
-            .await?,
-        )
+            .await?;
+        row.get_bigint_and_convert("batch_count")
     }
 
-    /// Updates an existing collection job.
+    /// Creates partitions of the client_reports & report_aggregations tables for the given task,
+    /// covering each client timestamp range which overlaps `interval`. Ranges are `partition_width`
+    /// wide, and aligned to multiples of `partition_width` since the epoch; ranges which overlap an
+    /// existing partition, e.g. one created with a different width, are skipped. Each table's task
+    /// partition, which is subpartitioned by client timestamp, is created first if necessary. Rows
+    /// belonging in a new partition are moved into it. Returns the number of partitions created.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn update_collection_job<
-        const SEED_SIZE: usize,
-        B: BatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    pub async fn create_report_partitions(
         &self,
-        collection_job: &CollectionJob<SEED_SIZE, B, A>,
-    ) -> Result<(), Error> {
-        let task_info = match self.task_info_for(collection_job.task_id()).await? {
-            Some(task_info) => task_info,
-            None => return Err(Error::MutationTargetNotFound),
-        };
+        task_id: &TaskId,
+        interval: &Interval,
+        partition_width: &Duration,
+    ) -> Result<u64, Error> {
+        let task_info = self
+            .task_info_for(task_id)
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?;
+        let width = partition_width.as_seconds();
+        if width == 0 {
+            return Err(Error::InvalidParameter("partition width must be positive"));
+        }
         let now = self.clock.now().as_naive_date_time()?;
 
-        let (
-            report_count,
-            client_timestamp_interval,
-            leader_aggregate_share,
-            helper_aggregate_share,
-        ) = match collection_job.state() {
-            CollectionJobState::Start => {
-                return Err(Error::InvalidParameter(
-                    "cannot update collection job into START state",
-                ));
+        self.raw_tx
+            .batch_execute("LOCK TABLE report_partitions IN EXCLUSIVE MODE")
+            .await?;
+
+        let stmt = self
+            .prepare_cached(
+                "-- create_report_partitions()
+SELECT partitioned_table, client_timestamp_interval FROM report_partitions
+WHERE task_id = $1",
+            )
+            .await?;
+        let existing_partitions: Vec<(String, Option<Interval>)> = self
+            .query(&stmt, &[/* task_id */ &task_info.pkey])
+            .await?
+            .iter()
+            .map(|row| {
+                (
+                    row.get("partitioned_table"),
+                    row.get::<_, Option<SqlInterval>>("client_timestamp_interval")
+                        .as_ref()
+                        .map(SqlInterval::as_interval),
+                )
+            })
+            .collect();
+
+        let mut ranges = Vec::new();
+        let end = interval.end().as_seconds_since_epoch();
+        let mut start = interval.start().as_seconds_since_epoch() / width * width;
+        while start < end {
+            ranges.push(Interval::new(
+                Time::from_seconds_since_epoch(start),
+                *partition_width,
+            )?);
+            start = start.checked_add(width).ok_or(Error::TimeOverflow(
+                "overflow computing report partition range",
+            ))?;
+        }
+
+        let stmt = self
+            .prepare_cached(
+                "-- create_report_partitions()
+INSERT INTO report_partitions (
+    task_id, partitioned_table, client_timestamp_interval, partition_name,
+    created_at, updated_by
+)
+VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .await?;
+
+        let mut created = 0;
+        for table in PARTITIONED_TABLES {
+            let task_partition = format!("{table}_t{}", task_info.pkey);
+            if !existing_partitions
+                .iter()
+                .any(|(partitioned_table, range)| partitioned_table == table && range.is_none())
+            {
+                self.create_report_partition(table, table, &task_partition, task_info.pkey, None)
+                    .await?;
+                check_insert(
+                    self.execute(
+                        &stmt,
+                        &[
+                            /* task_id */ &task_info.pkey,
+                            /* partitioned_table */ &table,
+                            /* client_timestamp_interval */ &None::<SqlInterval>,
+                            /* partition_name */ &task_partition,
+                            /* created_at */ &now,
+                            /* updated_by */ &self.name,
+                        ],
+                    )
+                    .await?,
+                )?;
+                created += 1;
             }
 
-            CollectionJobState::Finished {
-                report_count,
-                client_timestamp_interval,
-                encrypted_helper_aggregate_share,
-                leader_aggregate_share,
-            } => {
-                let report_count = Some(i64::try_from(*report_count)?);
-                let client_timestamp_interval = Some(SqlInterval::from(client_timestamp_interval));
-                let leader_aggregate_share = Some(leader_aggregate_share.get_encoded()?);
-                let helper_aggregate_share = Some(encrypted_helper_aggregate_share.get_encoded()?);
+            for range in &ranges {
+                let overlaps_existing = existing_partitions.iter().any(|(partitioned_table, r)| {
+                    partitioned_table == table
+                        && r.is_some_and(|r| r.start() < &range.end() && range.start() < &r.end())
+                });
+                if overlaps_existing {
+                    continue;
+                }
+
+                let partition = format!(
+                    "{task_partition}_{}",
+                    range.start().as_seconds_since_epoch()
+                );
+                self.create_report_partition(
+                    table,
+                    &task_partition,
+                    &partition,
+                    task_info.pkey,
+                    Some(range),
+                )
+                .await?;
+                check_insert(
+                    self.execute(
+                        &stmt,
+                        &[
+                            /* task_id */ &task_info.pkey,
+                            /* partitioned_table */ &table,
+                            /* client_timestamp_interval */ &Some(SqlInterval::from(range)),
+                            /* partition_name */ &partition,
+                            /* created_at */ &now,
+                            /* updated_by */ &self.name,
+                        ],
+                    )
+                    .await?,
+                )?;
+                created += 1;
+            }
+        }
+        Ok(created)
+    }
 
+    /// Creates `partition` as a partition of `parent`, which is either `table` itself or the task's
+    /// partition of `table`. If `client_timestamps` is `None`, the partition holds all rows of the
+    /// task, and is itself subpartitioned by client timestamp, starting with a default partition.
+    /// Otherwise, it holds the task's rows with client timestamps in `client_timestamps`.
+    ///
+    /// A partition can't be created while the parent's default partition holds rows belonging in
+    /// it, so those rows are set aside in a temporary table beforehand, and reinserted afterwards.
+    /// Creating the partition takes an `ACCESS EXCLUSIVE` lock on `parent` until the transaction
+    /// commits, blocking all reads & writes of the task's rows (or of every task's rows, for a
+    /// task's partition), and the moved rows are locked by their deletion; both locks are held
+    /// while the rows are copied twice, so the time taken is proportional to the number of rows
+    /// moved. Partitions are normally created ahead of the client timestamps of incoming reports,
+    /// so only the first partitions created for an existing task move a significant number of rows.
+    async fn create_report_partition(
+        &self,
+        table: &str,
+        parent: &str,
+        partition: &str,
+        task_pkey: i64,
+        client_timestamps: Option<&Interval>,
+    ) -> Result<(), Error> {
+        let default_partition = format!("{parent}_default");
+        let partition_default = format!("{partition}_default");
+        let [
+            table,
+            parent,
+            partition,
+            default_partition,
+            partition_default,
+        ] = <[String; 5]>::try_from(
+            self.quote_identifiers(&[
+                table,
+                parent,
+                partition,
+                default_partition.as_str(),
+                partition_default.as_str(),
+            ])
+            .await?,
+        )
+        .map_err(|_| Error::DbState("unexpected number of quoted identifiers".to_string()))?;
+
+        let (lower, upper, create_partition) = match client_timestamps {
+            None => (
+                Timestamp::NegInfinity,
+                Timestamp::PosInfinity,
+                format!(
+                    "CREATE TABLE {partition} PARTITION OF {parent}
+    FOR VALUES IN ({task_pkey}) PARTITION BY RANGE (client_timestamp);
+CREATE TABLE {partition_default} PARTITION OF {partition} DEFAULT"
+                ),
+            ),
+            Some(client_timestamps) => {
+                let lower = client_timestamps.start().as_naive_date_time()?;
+                let upper = client_timestamps.end().as_naive_date_time()?;
                 (
-                    report_count,
-                    client_timestamp_interval,
-                    leader_aggregate_share,
-                    helper_aggregate_share,
+                    Timestamp::Value(lower),
+                    Timestamp::Value(upper),
+                    // Timestamps formatted with SQL_TIMESTAMP_FORMAT consist only of digits, '-',
+                    // ':' & ' ', and so need no escaping.
+                    format!(
+                        "CREATE TABLE {partition} PARTITION OF {parent}
+    FOR VALUES FROM ('{}') TO ('{}')",
+                        lower.format(SQL_TIMESTAMP_FORMAT),
+                        upper.format(SQL_TIMESTAMP_FORMAT),
+                    ),
                 )
             }
+        };
+
+        self.raw_tx
+            .batch_execute(&format!(
+                "CREATE TEMPORARY TABLE report_partition_rows (LIKE {table})"
+            ))
+            .await?;
+        self.execute(
+            &format!(
+                "WITH moved_rows AS (
+    DELETE FROM {default_partition}
+    WHERE task_id = $1
+      AND client_timestamp >= $2::TIMESTAMP
+      AND client_timestamp < $3::TIMESTAMP
+    RETURNING *
+)
+INSERT INTO report_partition_rows SELECT * FROM moved_rows"
+            ),
+            &[
+                /* task_id */ &task_pkey, /* lower */ &lower, /* upper */ &upper,
+            ],
+        )
+        .await?;
+        self.raw_tx
+            .batch_execute(&format!(
+                "{create_partition};
+INSERT INTO {table} SELECT * FROM report_partition_rows;
+DROP TABLE report_partition_rows"
+            ))
+            .await
+            .map_err(Into::into)
+    }
 
-            CollectionJobState::Abandoned | CollectionJobState::Deleted => (None, None, None, None),
+    /// Quotes the given identifiers for use in SQL statements, using PostgreSQL's `quote_ident`.
+    async fn quote_identifiers<T: AsRef<str>>(
+        &self,
+        identifiers: &[T],
+    ) -> Result<Vec<String>, Error> {
+        let identifiers: Vec<&str> = identifiers.iter().map(AsRef::as_ref).collect();
+        let stmt = self
+            .prepare_cached(
+                "-- quote_identifiers()
+SELECT quote_ident(identifier) AS identifier
+FROM UNNEST($1::TEXT[]) WITH ORDINALITY AS identifiers(identifier, ord)
+ORDER BY ord",
+            )
+            .await?;
+        Ok(self
+            .query(&stmt, &[/* identifiers */ &identifiers])
+            .await?
+            .iter()
+            .map(|row| row.get("identifier"))
+            .collect())
+    }
+
+    /// Drops the partitions of the client_reports & report_aggregations tables for the given task
+    /// whose client timestamp ranges are entirely older than the task's report expiry age.
+    /// Partitions of report_aggregations are kept until every aggregation job overlapping their
+    /// range has expired, since report aggregations expire along with their aggregation job.
+    /// Returns the number of partitions dropped.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn drop_expired_report_partitions(&self, task_id: &TaskId) -> Result<u64, Error> {
+        let task_info = match self.task_info_for(task_id).await? {
+            Some(task_info) => task_info,
+            None => return Ok(0),
         };
 
+        self.raw_tx
+            .batch_execute("LOCK TABLE report_partitions IN EXCLUSIVE MODE")
+            .await?;
+
         let stmt = self
             .prepare_cached(
-                "-- update_collection_job()
-UPDATE collection_jobs SET
-    state = $1,
-    report_count = $2,
-    client_timestamp_interval = $3,
-    leader_aggregate_share = $4,
-    helper_aggregate_share = $5,
-    updated_at = $6,
-    updated_by = $7
-WHERE task_id = $8
-  AND collection_job_id = $9
-  AND COALESCE(
-          LOWER(batch_interval),
-          (SELECT MAX(UPPER(ba.client_timestamp_interval))
-           FROM batch_aggregations ba
-           WHERE ba.task_id = collection_jobs.task_id
-             AND ba.batch_identifier = collection_jobs.batch_identifier
-             AND ba.aggregation_param = collection_jobs.aggregation_param),
-          '-infinity'::TIMESTAMP) >= $10",
+                "-- drop_expired_report_partitions()
+WITH dropped_partitions AS (
+    DELETE FROM report_partitions
+    WHERE task_id = $1
+      AND client_timestamp_interval IS NOT NULL
+      AND UPPER(client_timestamp_interval) <= $2
+      AND (partitioned_table = 'client_reports' OR NOT EXISTS (
+          SELECT 1 FROM aggregation_jobs
+          WHERE aggregation_jobs.task_id = $1
+            AND aggregation_jobs.client_timestamp_interval
+                && report_partitions.client_timestamp_interval
+            AND UPPER(aggregation_jobs.client_timestamp_interval) >= $2
+      ))
+    RETURNING partitioned_table, client_timestamp_interval, partition_name
+),
+deleted_report_ids AS (
+    DELETE FROM client_report_ids
+    USING dropped_partitions
+    WHERE client_report_ids.task_id = $1
+      AND dropped_partitions.partitioned_table = 'client_reports'
+      AND client_report_ids.client_timestamp <@ dropped_partitions.client_timestamp_interval
+)
+SELECT partition_name FROM dropped_partitions",
             )
             .await?;
-
-        check_single_row_mutation(
-            self.execute(
+        let partitions: Vec<String> = self
+            .query(
                 &stmt,
                 &[
-                    /* state */ &collection_job.state().collection_job_state_code(),
-                    /* report_count */ &report_count,
-                    /* client_timestamp_interval */ &client_timestamp_interval,
-                    /* leader_aggregate_share */ &leader_aggregate_share,
-                    /* helper_aggregate_share */ &helper_aggregate_share,
-                    /* updated_at */ &now,
-                    /* updated_by */ &self.name,
                     /* task_id */ &task_info.pkey,
-                    /* collection_job_id */ &collection_job.id().as_ref(),
-                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                    /* threshold */
+                    &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
                 ],
             )
-            .await?,
-        )
+            .await?
+            .iter()
+            .map(|row| row.get("partition_name"))
+            .collect();
+        self.drop_report_partitions(&partitions).await
     }
 
-    /// Retrieves an existing batch aggregation.
-    #[tracing::instrument(skip(self, aggregation_parameter), err(level = Level::DEBUG))]
-    pub async fn get_batch_aggregation<
-        const SEED_SIZE: usize,
-        B: BatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
-        &self,
-        vdaf: &A,
-        task_id: &TaskId,
-        batch_identifier: &B::BatchIdentifier,
-        aggregation_parameter: &A::AggregationParam,
-        ord: u64,
-    ) -> Result<Option<BatchAggregation<SEED_SIZE, B, A>>, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok(None),
-        };
+    /// Drops the partitions of the client_reports & report_aggregations tables belonging to tasks
+    /// which have been deleted. Returns the number of partitions dropped.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn drop_report_partitions_for_deleted_tasks(&self) -> Result<u64, Error> {
+        self.raw_tx
+            .batch_execute("LOCK TABLE report_partitions IN EXCLUSIVE MODE")
+            .await?;
 
-        // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param) which
-        // are _not_ garbage collected. This is used to evaluate whether given batch_aggregations
-        // rows are GC'ed.
         let stmt = self
             .prepare_cached(
-                "-- get_batch_aggregation()
-WITH non_gc_batches AS (
-    SELECT batch_identifier, aggregation_param
-    FROM batch_aggregations
-    WHERE task_id = $1
-      AND batch_identifier = $2
-      AND aggregation_param = $3
-    GROUP BY batch_identifier, aggregation_param
-    HAVING MAX(UPPER(COALESCE(batch_interval, client_timestamp_interval))) >= $5
-)
-SELECT
-    client_timestamp_interval, batch_aggregations.state, aggregate_share,
-    report_count, checksum, aggregation_jobs_created, aggregation_jobs_terminated
-FROM batch_aggregations
-WHERE task_id = $1
-  AND batch_identifier = $2
-  AND aggregation_param = $3
-  AND ord = $4
-  AND EXISTS(SELECT 1 FROM non_gc_batches
-             WHERE batch_identifier = $2
-               AND aggregation_param = $3)",
+                "-- drop_report_partitions_for_deleted_tasks()
+DELETE FROM report_partitions
+WHERE NOT EXISTS (SELECT 1 FROM tasks WHERE tasks.id = report_partitions.task_id)
+RETURNING partition_name",
             )
             .await?;
+        let partitions: Vec<String> = self
+            .query(&stmt, &[])
+            .await?
+            .iter()
+            .map(|row| row.get("partition_name"))
+            .collect();
+        self.drop_report_partitions(&partitions).await
+    }
 
-        self.query_opt(
-            &stmt,
-            &[
-                /* task_id */ &task_info.pkey,
-                /* batch_identifier */ &batch_identifier.get_encoded()?,
-                /* aggregation_param */ &aggregation_parameter.get_encoded()?,
-                /* ord */ &TryInto::<i64>::try_into(ord)?,
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-            ],
-        )
-        .await?
-        .map(|row| {
-            Self::batch_aggregation_from_row(
-                vdaf,
-                *task_id,
-                batch_identifier.clone(),
-                aggregation_parameter.clone(),
-                ord,
-                row,
+    /// Drops the given partition tables. Dropping a task partition also drops its subpartitions,
+    /// which may be listed too.
+    async fn drop_report_partitions(&self, partitions: &[String]) -> Result<u64, Error> {
+        if partitions.is_empty() {
+            return Ok(0);
+        }
+        let quoted_partitions = self.quote_identifiers(partitions).await?;
+        self.raw_tx
+            .batch_execute(&format!(
+                "DROP TABLE IF EXISTS {}",
+                quoted_partitions.join(", ")
+            ))
+            .await?;
+        Ok(u64::try_from(partitions.len())?)
+    }
+
+    /// Take an ExclusiveLock on the hpke_keys table.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn lock_hpke_keypairs(&self) -> Result<(), Error> {
+        self.raw_tx
+            .batch_execute("LOCK TABLE hpke_keys IN EXCLUSIVE MODE")
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Retrieve all HPKE keypairs.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_hpke_keypairs(&self) -> Result<Vec<HpkeKeypair>, Error> {
+        let stmt = self
+            .prepare_cached(
+                "-- get_hpke_keypairs()
+SELECT config_id, config, private_key, state, last_state_change_at FROM hpke_keys",
             )
-        })
-        .transpose()
+            .await?;
+        let hpke_key_rows = self.query(&stmt, &[]).await?;
+
+        hpke_key_rows
+            .iter()
+            .map(|row| self.hpke_keypair_from_row("hpke_keys", row))
+            .collect()
     }
 
-    /// Retrieves all batch aggregations stored for a given batch, identified by task ID, batch
-    /// identifier, and aggregation parameter.
-    #[tracing::instrument(skip(self, aggregation_parameter), err(level = Level::DEBUG))]
-    pub async fn get_batch_aggregations_for_batch<
-        const SEED_SIZE: usize,
-        B: BatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    /// Retrieve an HPKE keypair by config ID.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_hpke_keypair(
         &self,
-        vdaf: &A,
-        task_id: &TaskId,
-        batch_identifier: &B::BatchIdentifier,
-        aggregation_parameter: &A::AggregationParam,
-    ) -> Result<Vec<BatchAggregation<SEED_SIZE, B, A>>, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok(Vec::new()),
-        };
-
-        // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param) which
-        // are _not_ garbage collected. This is used to evaluate whether given batch_aggregations
-        // rows are GC'ed.
+        config_id: &HpkeConfigId,
+    ) -> Result<Option<HpkeKeypair>, Error> {
         let stmt = self
             .prepare_cached(
-                "-- get_batch_aggregations_for_batch()
-WITH non_gc_batches AS (
-    SELECT batch_identifier, aggregation_param
-    FROM batch_aggregations
-    WHERE task_id = $1
-      AND batch_identifier = $2
-      AND aggregation_param = $3
-    GROUP BY batch_identifier, aggregation_param
-    HAVING MAX(UPPER(COALESCE(batch_interval, client_timestamp_interval))) >= $4
-)
-SELECT
-    ord, client_timestamp_interval, batch_aggregations.state, aggregate_share,
-    report_count, checksum, aggregation_jobs_created, aggregation_jobs_terminated
-FROM batch_aggregations
-WHERE task_id = $1
-  AND batch_identifier = $2
-  AND aggregation_param = $3
-  AND EXISTS(SELECT 1 FROM non_gc_batches
-             WHERE batch_identifier = $2
-               AND aggregation_param = $3)",
+                "-- get_hpke_keypair()
+SELECT config_id, config, private_key, state, last_state_change_at FROM hpke_keys
+    WHERE config_id = $1",
             )
             .await?;
+        self.query_opt(&stmt, &[&(u8::from(*config_id) as i16)])
+            .await?
+            .map(|row| self.hpke_keypair_from_row("hpke_keys", &row))
+            .transpose()
+    }
 
-        self.query(
-            &stmt,
-            &[
-                /* task_id */ &task_info.pkey,
-                /* batch_identifier */ &batch_identifier.get_encoded()?,
-                /* aggregation_param */ &aggregation_parameter.get_encoded()?,
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-            ],
-        )
-        .await?
-        .into_iter()
-        .map(|row| {
-            Self::batch_aggregation_from_row(
-                vdaf,
-                *task_id,
-                batch_identifier.clone(),
-                aggregation_parameter.clone(),
-                row.get_bigint_and_convert("ord")?,
-                row,
+    /// Decodes a keypair from a row of either `hpke_keys` or `ohttp_gateway_keys`, which share a
+    /// layout. `table` is used as associated data when decrypting the private key.
+    fn hpke_keypair_from_row(&self, table: &str, row: &Row) -> Result<HpkeKeypair, Error> {
+        let config = HpkeConfig::get_decoded(row.get("config"))?;
+        let config_id = u8::try_from(row.get::<_, i16>("config_id"))?;
+
+        let encrypted_private_key: Vec<u8> = row.get("private_key");
+        let private_key = HpkePrivateKey::new(self.crypter.decrypt(
+            table,
+            &config_id.to_be_bytes(),
+            "private_key",
+            &encrypted_private_key,
+        )?);
+        Ok(HpkeKeypair::new(
+            hpke::HpkeKeypair::new(config, private_key),
+            row.get("state"),
+            Time::from_naive_date_time(&row.get("last_state_change_at")),
+        ))
+    }
+
+    /// Unconditionally and fully drop a keypair. This is a dangerous operation, since report shares
+    /// encrypted with this key will no longer be decryptable.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn delete_hpke_keypair(&self, config_id: &HpkeConfigId) -> Result<(), Error> {
+        let stmt = self
+            .prepare_cached(
+                "-- delete_hpke_keypair()
+DELETE FROM hpke_keys WHERE config_id = $1",
             )
-        })
-        .collect()
+            .await?;
+        check_single_row_mutation(
+            self.execute(&stmt, &[&(u8::from(*config_id) as i16)])
+                .await?,
+        )
     }
 
-    /// Retrieves the number of aggregation jobs created & terminated for the given batch
-    /// identifier.
-    #[tracing::instrument(skip(self, aggregation_parameter), err(level = Level::DEBUG))]
-    pub async fn get_batch_aggregation_job_count_for_batch<
-        const SEED_SIZE: usize,
-        B: BatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn set_hpke_keypair_state(
         &self,
-        task_id: &TaskId,
-        batch_identifier: &B::BatchIdentifier,
-        aggregation_parameter: &A::AggregationParam,
-    ) -> Result<(u64, u64), Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok((0, 0)),
-        };
-
-        // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param) which
-        // are _not_ garbage collected. This is used to evaluate whether given batch_aggregations
-        // rows are GC'ed.
+        config_id: &HpkeConfigId,
+        state: &HpkeKeyState,
+    ) -> Result<(), Error> {
         let stmt = self
             .prepare_cached(
-                "-- get_batch_aggregation_job_count_for_batch()
-WITH non_gc_batches AS (
-    SELECT batch_identifier, aggregation_param
-    FROM batch_aggregations
-    WHERE task_id = $1
-      AND batch_identifier = $2
-      AND aggregation_param = $3
-    GROUP BY batch_identifier, aggregation_param
-    HAVING MAX(UPPER(COALESCE(batch_interval, client_timestamp_interval))) >= $4
-)
-SELECT
-    SUM(aggregation_jobs_created)::BIGINT AS created,
-    SUM(aggregation_jobs_terminated)::BIGINT AS terminated
-FROM batch_aggregations
-WHERE task_id = $1
-  AND batch_identifier = $2
-  AND aggregation_param = $3
-  AND EXISTS(SELECT 1 FROM non_gc_batches
-             WHERE batch_identifier = $2
-               AND aggregation_param = $3)",
+                "-- set_hpke_keypair_state()
+UPDATE hpke_keys
+    SET state = $1, last_state_change_at = $2, updated_at = $3, updated_by = $4
+    WHERE config_id = $5",
             )
             .await?;
-
-        let row = self
-            .query_one(
+        let now = self.clock.now().as_naive_date_time()?;
+        check_single_row_mutation(
+            self.execute(
                 &stmt,
                 &[
-                    /* task_id */ &task_info.pkey,
-                    /* batch_identifier */ &batch_identifier.get_encoded()?,
-                    /* aggregation_param */ &aggregation_parameter.get_encoded()?,
-                    /* threshold */
-                    &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
+                    /* state */ state,
+                    /* last_state_change_at */ &now,
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                    /* config_id */ &(u8::from(*config_id) as i16),
                 ],
             )
-            .await?;
-
-        let aggregation_jobs_created = row.get_nullable_bigint_and_convert("created")?;
-        let aggregation_jobs_terminated = row.get_nullable_bigint_and_convert("terminated")?;
-
-        Ok((
-            aggregation_jobs_created.unwrap_or(0),
-            aggregation_jobs_terminated.unwrap_or(0),
-        ))
+            .await?,
+        )
     }
 
-    #[cfg(feature = "test-util")]
-    pub async fn get_batch_aggregations_for_task<
-        const SEED_SIZE: usize,
-        B: BatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
-        &self,
-        vdaf: &A,
-        task_id: &TaskId,
-    ) -> Result<Vec<BatchAggregation<SEED_SIZE, B, A>>, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok(Vec::new()),
-        };
+    /// Inserts a new HPKE keypair and places it in the [`HpkeKeyState::Pending`] state.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn put_hpke_keypair(&self, hpke_keypair: &hpke::HpkeKeypair) -> Result<(), Error> {
+        let hpke_config_id = u8::from(*hpke_keypair.config().id()) as i16;
+        let hpke_config = hpke_keypair.config().get_encoded()?;
+        let encrypted_hpke_private_key = self.crypter.encrypt(
+            "hpke_keys",
+            &u8::from(*hpke_keypair.config().id()).to_be_bytes(),
+            "private_key",
+            hpke_keypair.private_key().as_ref(),
+        )?;
 
-        // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param) which
-        // are _not_ garbage collected. This is used to evaluate whether given batch_aggregations
-        // rows are GC'ed.
         let stmt = self
             .prepare_cached(
-                "-- get_batch_aggregations_for_task()
-WITH non_gc_batches AS (
-    SELECT batch_identifier, aggregation_param
-    FROM batch_aggregations
-    WHERE task_id = $1
-    GROUP BY batch_identifier, aggregation_param
-    HAVING MAX(UPPER(COALESCE(batch_interval, client_timestamp_interval))) >= $2
-)
-SELECT
-    client_timestamp_interval, batch_aggregations.batch_identifier,
-    batch_aggregations.aggregation_param, ord, batch_aggregations.state,
-    aggregate_share, report_count, checksum, aggregation_jobs_created,
-    aggregation_jobs_terminated
-FROM batch_aggregations
-WHERE task_id = $1
-  AND EXISTS(SELECT 1 FROM non_gc_batches
-             WHERE batch_identifier = batch_aggregations.batch_identifier
-               AND aggregation_param = batch_aggregations.aggregation_param)",
+                "-- put_hpke_keypair()
+INSERT INTO hpke_keys
+    (config_id, config, private_key, last_state_change_at, created_at, updated_at, updated_by)
+    VALUES ($1, $2, $3, $4, $5, $6, $7)",
             )
             .await?;
-
-        self.query(
-            &stmt,
-            &[
-                /* task_id */ &task_info.pkey,
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-            ],
-        )
-        .await?
-        .into_iter()
-        .map(|row| {
-            let batch_identifier = B::BatchIdentifier::get_decoded(row.get("batch_identifier"))?;
-            let aggregation_param = A::AggregationParam::get_decoded(row.get("aggregation_param"))?;
-            let ord = row.get_bigint_and_convert("ord")?;
-
-            Self::batch_aggregation_from_row(
-                vdaf,
-                *task_id,
-                batch_identifier,
-                aggregation_param,
-                ord,
-                row,
+        let now = self.clock.now().as_naive_date_time()?;
+        check_insert(
+            self.execute(
+                &stmt,
+                &[
+                    /* config_id */ &hpke_config_id,
+                    /* config */ &hpke_config,
+                    /* private_key */ &encrypted_hpke_private_key,
+                    /* last_state_change_at */ &now,
+                    /* created_at */ &now,
+                    /* updated_at */ &now,
+                    /* updated_by */ &self.name,
+                ],
             )
-        })
-        .collect()
+            .await?,
+        )
     }
 
-    fn batch_aggregation_from_row<
-        const SEED_SIZE: usize,
-        B: BatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
-        vdaf: &A,
-        task_id: TaskId,
-        batch_identifier: B::BatchIdentifier,
-        aggregation_param: A::AggregationParam,
-        ord: u64,
-        row: Row,
-    ) -> Result<BatchAggregation<SEED_SIZE, B, A>, Error> {
-        #[allow(clippy::type_complexity)]
-        fn parse_values_from_row<const SEED_SIZE: usize, A: AsyncAggregator<SEED_SIZE>>(
-            vdaf: &A,
-            aggregation_param: &A::AggregationParam,
-            row: &Row,
-        ) -> Result<(Option<A::AggregateShare>, u64, ReportIdChecksum, u64, u64), Error> {
-            let aggregate_share = row
-                .get::<_, Option<Vec<u8>>>("aggregate_share")
-                .map(|bytes| {
-                    A::AggregateShare::get_decoded_with_param(&(vdaf, aggregation_param), &bytes)
-                })
-                .transpose()
-                .map_err(|_| Error::DbState("aggregate_share couldn't be parsed".to_string()))?;
-            let report_count = row.get_bigint_and_convert("report_count")?;
-            let checksum = ReportIdChecksum::get_decoded(row.get("checksum"))?;
-            let aggregation_jobs_created =
-                row.get_bigint_and_convert("aggregation_jobs_created")?;
-            let aggregation_jobs_terminated =
-                row.get_bigint_and_convert("aggregation_jobs_terminated")?;
-
-            Ok((
-                aggregate_share,
-                report_count,
-                checksum,
-                aggregation_jobs_created,
-                aggregation_jobs_terminated,
-            ))
-        }
+    /// Take an ExclusiveLock on the ohttp_gateway_keys table.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn lock_ohttp_gateway_keypairs(&self) -> Result<(), Error> {
+        self.raw_tx
+            .batch_execute("LOCK TABLE ohttp_gateway_keys IN EXCLUSIVE MODE")
+            .await
+            .map_err(|err| err.into())
+    }
 
-        let client_timestamp_interval = row
-            .get::<_, SqlInterval>("client_timestamp_interval")
-            .as_interval();
-        let state: BatchAggregationStateCode = row.get("state");
-        let state = match state {
-            BatchAggregationStateCode::Aggregating => {
-                let (
-                    aggregate_share,
-                    report_count,
-                    checksum,
-                    aggregation_jobs_created,
-                    aggregation_jobs_terminated,
-                ) = parse_values_from_row(vdaf, &aggregation_param, &row)?;
-                BatchAggregationState::Aggregating {
-                    aggregate_share,
-                    report_count,
-                    checksum,
-                    aggregation_jobs_created,
-                    aggregation_jobs_terminated,
-                }
-            }
-            BatchAggregationStateCode::Collected => {
-                let (
-                    aggregate_share,
-                    report_count,
-                    checksum,
-                    aggregation_jobs_created,
-                    aggregation_jobs_terminated,
-                ) = parse_values_from_row(vdaf, &aggregation_param, &row)?;
-                BatchAggregationState::Collected {
-                    aggregate_share,
-                    report_count,
-                    checksum,
-                    aggregation_jobs_created,
-                    aggregation_jobs_terminated,
-                }
-            }
-            BatchAggregationStateCode::Scrubbed => BatchAggregationState::Scrubbed,
-        };
+    /// Retrieve all OHTTP gateway keypairs. The private key of each keypair is the seed from which
+    /// the gateway's OHTTP key config is derived.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_ohttp_gateway_keypairs(&self) -> Result<Vec<HpkeKeypair>, Error> {
+        let stmt = self
+            .prepare_cached(
+                "-- get_ohttp_gateway_keypairs()
+SELECT config_id, config, private_key, state, last_state_change_at FROM ohttp_gateway_keys",
+            )
+            .await?;
+        let key_rows = self.query(&stmt, &[]).await?;
 
-        Ok(BatchAggregation::new(
-            task_id,
-            batch_identifier,
-            aggregation_param,
-            ord,
-            client_timestamp_interval,
-            state,
-        ))
+        key_rows
+            .iter()
+            .map(|row| self.hpke_keypair_from_row("ohttp_gateway_keys", row))
+            .collect()
     }
 
-    /// Store a new `batch_aggregations` row in the datastore.
+    /// Retrieve an OHTTP gateway keypair by key ID.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn put_batch_aggregation<
-        const SEED_SIZE: usize,
-        B: AccumulableBatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    pub async fn get_ohttp_gateway_keypair(
         &self,
-        batch_aggregation: &BatchAggregation<SEED_SIZE, B, A>,
-    ) -> Result<(), Error> {
-        let task_info = match self.task_info_for(batch_aggregation.task_id()).await? {
-            Some(task_info) => task_info,
-            None => return Err(Error::MutationTargetNotFound),
-        };
-        let now = self.clock.now().as_naive_date_time()?;
-
-        let batch_interval =
-            B::to_batch_interval(batch_aggregation.batch_identifier()).map(SqlInterval::from);
-        let encoded_state_values = batch_aggregation.state().encoded_values_from_state()?;
+        config_id: &HpkeConfigId,
+    ) -> Result<Option<HpkeKeypair>, Error> {
+        let stmt = self
+            .prepare_cached(
+                "-- get_ohttp_gateway_keypair()
+SELECT config_id, config, private_key, state, last_state_change_at FROM ohttp_gateway_keys
+    WHERE config_id = $1",
+            )
+            .await?;
+        self.query_opt(&stmt, &[&(u8::from(*config_id) as i16)])
+            .await?
+            .map(|row| self.hpke_keypair_from_row("ohttp_gateway_keys", &row))
+            .transpose()
+    }
 
-        batch_aggregation
-            .client_timestamp_interval()
-            .validate_precision(&task_info.time_precision)
-            .map_err(|e| {
-                Self::unaligned_time_error(
-                    batch_aggregation.task_id(),
-                    &task_info.time_precision,
-                    e,
-                )
-            })?;
+    /// Unconditionally and fully drop an OHTTP gateway keypair. Requests encapsulated to this key
+    /// will no longer be decapsulable.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn delete_ohttp_gateway_keypair(
+        &self,
+        config_id: &HpkeConfigId,
+    ) -> Result<(), Error> {
+        let stmt = self
+            .prepare_cached(
+                "-- delete_ohttp_gateway_keypair()
+DELETE FROM ohttp_gateway_keys WHERE config_id = $1",
+            )
+            .await?;
+        check_single_row_mutation(
+            self.execute(&stmt, &[&(u8::from(*config_id) as i16)])
+                .await?,
+        )
+    }
 
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn set_ohttp_gateway_keypair_state(
+        &self,
+        config_id: &HpkeConfigId,
+        state: &HpkeKeyState,
+    ) -> Result<(), Error> {
         let stmt = self
             .prepare_cached(
-                "-- put_batch_aggregation()
-INSERT INTO batch_aggregations (
-    task_id, batch_identifier, batch_interval, aggregation_param, ord,
-    client_timestamp_interval, state, aggregate_share, report_count, checksum,
-    aggregation_jobs_created, aggregation_jobs_terminated, created_at, updated_at,
-    updated_by
-)
-VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
-ON CONFLICT(task_id, batch_identifier, aggregation_param, ord) DO UPDATE
-    SET (
-        client_timestamp_interval, state, aggregate_share, report_count, checksum,
-        aggregation_jobs_created, aggregation_jobs_terminated, created_at,
-        updated_at, updated_by
-    ) = (
-        excluded.client_timestamp_interval, excluded.state,
-        excluded.aggregate_share, excluded.report_count, excluded.checksum,
-        excluded.aggregation_jobs_created, excluded.aggregation_jobs_terminated,
-        excluded.created_at, excluded.updated_at, excluded.updated_by
-    )
-    WHERE GREATEST(
-              UPPER(COALESCE(batch_aggregations.batch_interval,
-                             batch_aggregations.client_timestamp_interval)),
-              (SELECT MAX(UPPER(COALESCE(ba.batch_interval,
-                                         ba.client_timestamp_interval)))
-               FROM batch_aggregations ba
-               WHERE ba.task_id = batch_aggregations.task_id
-                 AND ba.batch_identifier = batch_aggregations.batch_identifier
-                 AND ba.aggregation_param = batch_aggregations.aggregation_param)) < $16",
+                "-- set_ohttp_gateway_keypair_state()
+UPDATE ohttp_gateway_keys
+    SET state = $1, last_state_change_at = $2, updated_at = $3, updated_by = $4
+    WHERE config_id = $5",
             )
             .await?;
-        check_insert(
+        let now = self.clock.now().as_naive_date_time()?;
+        check_single_row_mutation(
             self.execute(
                 &stmt,
                 &[
-                    /* task_id */ &task_info.pkey,
-                    /* batch_identifier */
-                    &batch_aggregation.batch_identifier().get_encoded()?,
-                    /* batch_interval */ &batch_interval,
-                    /* aggregation_param */
-                    &batch_aggregation.aggregation_parameter().get_encoded()?,
-                    /* ord */ &i64::try_from(batch_aggregation.ord())?,
-                    /* client_timestamp_interval */
-                    &SqlInterval::from(batch_aggregation.client_timestamp_interval()),
-                    /* state */ &batch_aggregation.state().state_code(),
-                    /* aggregate_share */ &encoded_state_values.aggregate_share,
-                    /* report_count */ &encoded_state_values.report_count,
-                    /* checksum */ &encoded_state_values.checksum,
-                    /* aggregation_jobs_created */
-                    &encoded_state_values.aggregation_jobs_created,
-                    /* aggregation_jobs_terminated */
-                    &encoded_state_values.aggregation_jobs_terminated,
-                    /* created_at */ &now,
+                    /* state */ state,
+                    /* last_state_change_at */ &now,
                     /* updated_at */ &now,
                     /* updated_by */ &self.name,
-                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                    /* config_id */ &(u8::from(*config_id) as i16),
                 ],
             )
             .await?,
         )
     }
 
-    /// Update an existing `batch_aggregations` row with the values from the provided batch
-    /// aggregation.
+    /// Inserts a new OHTTP gateway keypair and places it in the [`HpkeKeyState::Pending`] state.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn update_batch_aggregation<
-        const SEED_SIZE: usize,
-        B: BatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    pub async fn put_ohttp_gateway_keypair(
         &self,
-        batch_aggregation: &BatchAggregation<SEED_SIZE, B, A>,
+        keypair: &hpke::HpkeKeypair,
     ) -> Result<(), Error> {
-        let task_info = match self.task_info_for(batch_aggregation.task_id()).await? {
-            Some(task_info) => task_info,
-            None => return Err(Error::MutationTargetNotFound),
-        };
-        let now = self.clock.now().as_naive_date_time()?;
-        let encoded_state_values = batch_aggregation.state().encoded_values_from_state()?;
-
-        batch_aggregation
-            .client_timestamp_interval()
-            .validate_precision(&task_info.time_precision)
-            .map_err(|e| {
-                Self::unaligned_time_error(
-                    batch_aggregation.task_id(),
-                    &task_info.time_precision,
-                    e,
-                )
-            })?;
+        let config_id = u8::from(*keypair.config().id()) as i16;
+        let config = keypair.config().get_encoded()?;
+        let encrypted_private_key = self.crypter.encrypt(
+            "ohttp_gateway_keys",
+            &u8::from(*keypair.config().id()).to_be_bytes(),
+            "private_key",
+            keypair.private_key().as_ref(),
+        )?;
 
         let stmt = self
             .prepare_cached(
-                "-- update_batch_aggregations()
-UPDATE batch_aggregations
-SET
-    client_timestamp_interval = $1,
-    state = $2,
-    aggregate_share = $3,
-    report_count = $4,
-    checksum = $5,
-    aggregation_jobs_created = $6,
-    aggregation_jobs_terminated = $7,
-    updated_at = $8,
-    updated_by = $9
-WHERE task_id = $10
-  AND batch_identifier = $11
-  AND aggregation_param = $12
-  AND ord = $13
-  AND GREATEST(
-          UPPER($1::TSRANGE),
-          (SELECT MAX(UPPER(COALESCE(batch_interval, client_timestamp_interval)))
-           FROM batch_aggregations ba
-           WHERE ba.task_id = batch_aggregations.task_id
-             AND ba.batch_identifier = batch_aggregations.batch_identifier
-             AND ba.aggregation_param = batch_aggregations.aggregation_param)) >= $14",
+                "-- put_ohttp_gateway_keypair()
+INSERT INTO ohttp_gateway_keys
+    (config_id, config, private_key, last_state_change_at, created_at, updated_at, updated_by)
+    VALUES ($1, $2, $3, $4, $5, $6, $7)",
             )
             .await?;
-        check_single_row_mutation(
+        let now = self.clock.now().as_naive_date_time()?;
+        check_insert(
             self.execute(
                 &stmt,
                 &[
-                    /* client_timestamp_interval */
-                    &SqlInterval::from(batch_aggregation.client_timestamp_interval()),
-                    /* state */ &batch_aggregation.state().state_code(),
-                    /* aggregate_share */ &encoded_state_values.aggregate_share,
-                    /* report_count */ &encoded_state_values.report_count,
-                    /* checksum */ &encoded_state_values.checksum,
-                    /* aggregation_jobs_created */
-                    &encoded_state_values.aggregation_jobs_created,
-                    /* aggregation_jobs_terminated */
-                    &encoded_state_values.aggregation_jobs_terminated,
+                    /* config_id */ &config_id,
+                    /* config */ &config,
+                    /* private_key */ &encrypted_private_key,
+                    /* last_state_change_at */ &now,
+                    /* created_at */ &now,
                     /* updated_at */ &now,
                     /* updated_by */ &self.name,
-                    /* task_id */ &task_info.pkey,
-                    /* batch_identifier */
-                    &batch_aggregation.batch_identifier().get_encoded()?,
-                    /* aggregation_param */
-                    &batch_aggregation.aggregation_parameter().get_encoded()?,
-                    /* ord */
-                    &TryInto::<i64>::try_into(batch_aggregation.ord())?,
-                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
                 ],
             )
             .await?,
         )
     }
 
-    /// Fetch an [`AggregateShareJob`] from the datastore corresponding to given parameters, or
-    /// `None` if no such job exists.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn get_aggregate_share_job<
-        const SEED_SIZE: usize,
-        B: BatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    pub async fn get_taskprov_peer_aggregators(&self) -> Result<Vec<PeerAggregator>, Error> {
+        let stmt = self
+            .prepare_cached(
+                "-- get_taskprov_peer_aggregators()
+SELECT id, endpoint, peer_role, aggregation_mode, verify_key_init, collector_hpke_config,
+        report_expiry_age
+    FROM taskprov_peer_aggregators",
+            )
+            .await?;
+        let peer_aggregator_rows = self.query(&stmt, &[]);
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_taskprov_peer_aggregators()
+SELECT (SELECT p.id FROM taskprov_peer_aggregators AS p
+    WHERE p.id = a.peer_aggregator_id) AS peer_id,
+ord, type, token FROM taskprov_aggregator_auth_tokens AS a
+    ORDER BY ord ASC",
+            )
+            .await?;
+        let aggregator_auth_token_rows = self.query(&stmt, &[]);
+
+        let stmt = self
+            .prepare_cached(
+                "-- get_taskprov_peer_aggregators()
+SELECT (SELECT p.id FROM taskprov_peer_aggregators AS p
+    WHERE p.id = a.peer_aggregator_id) AS peer_id,
+ord, type, token FROM taskprov_collector_auth_tokens AS a
+    ORDER BY ord ASC",
+            )
+            .await?;
+        let collector_auth_token_rows = self.query(&stmt, &[]);
+
+        let (peer_aggregator_rows, aggregator_auth_token_rows, collector_auth_token_rows) = try_join!(
+            peer_aggregator_rows,
+            aggregator_auth_token_rows,
+            collector_auth_token_rows,
+        )?;
+
+        let mut aggregator_auth_token_rows_by_peer_id: HashMap<i64, Vec<Row>> = HashMap::new();
+        for row in aggregator_auth_token_rows {
+            aggregator_auth_token_rows_by_peer_id
+                .entry(row.get("peer_id"))
+                .or_default()
+                .push(row);
+        }
+
+        let mut collector_auth_token_rows_by_peer_id: HashMap<i64, Vec<Row>> = HashMap::new();
+        for row in collector_auth_token_rows {
+            collector_auth_token_rows_by_peer_id
+                .entry(row.get("peer_id"))
+                .or_default()
+                .push(row);
+        }
+
+        peer_aggregator_rows
+            .into_iter()
+            .map(|row| (row.get("id"), row))
+            .map(|(peer_id, peer_aggregator_row)| {
+                self.taskprov_peer_aggregator_from_rows(
+                    &peer_aggregator_row,
+                    &aggregator_auth_token_rows_by_peer_id
+                        .remove(&peer_id)
+                        .unwrap_or_default(),
+                    &collector_auth_token_rows_by_peer_id
+                        .remove(&peer_id)
+                        .unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_taskprov_peer_aggregator(
         &self,
-        vdaf: &A,
-        task_id: &TaskId,
-        batch_identifier: &B::BatchIdentifier,
-        aggregation_parameter: &A::AggregationParam,
-    ) -> Result<Option<AggregateShareJob<SEED_SIZE, B, A>>, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok(None),
-        };
+        aggregator_url: &Url,
+        role: &Role,
+    ) -> Result<Option<PeerAggregator>, Error> {
+        let aggregator_url = aggregator_url.as_str();
+        let role = AggregatorRole::from_role(*role)?;
+        let params: &[&(dyn ToSql + Sync)] = &[&aggregator_url, &role];
 
         let stmt = self
             .prepare_cached(
-                "-- get_aggregate_share_job()
-SELECT helper_aggregate_share, report_count, checksum
-FROM aggregate_share_jobs
-WHERE task_id = $1
-  AND batch_identifier = $2
-  AND aggregation_param = $3
-  AND COALESCE(
-          LOWER(batch_interval),
-          (SELECT MAX(UPPER(client_timestamp_interval))
-           FROM batch_aggregations ba
-           WHERE ba.task_id = aggregate_share_jobs.task_id
-             AND ba.batch_identifier = aggregate_share_jobs.batch_identifier
-             AND ba.aggregation_param = aggregate_share_jobs.aggregation_param),
-          '-infinity'::TIMESTAMP) >= $4",
+                "-- get_taskprov_peer_aggregator()
+SELECT endpoint, peer_role, aggregation_mode, verify_key_init, collector_hpke_config,
+        report_expiry_age
+    FROM taskprov_peer_aggregators WHERE endpoint = $1 AND peer_role = $2",
             )
             .await?;
-        self.query_opt(
-            &stmt,
-            &[
-                /* task_id */ &task_info.pkey,
-                /* batch_identifier */ &batch_identifier.get_encoded()?,
-                /* aggregation_param */ &aggregation_parameter.get_encoded()?,
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-            ],
-        )
-        .await?
-        .map(|row| {
-            Self::aggregate_share_job_from_row(
-                vdaf,
-                task_id,
-                batch_identifier.clone(),
-                aggregation_parameter.clone(),
-                &row,
-            )
-        })
-        .transpose()
-    }
-
-    /// Returns all aggregate share jobs for the given task whose collect intervals intersect with
-    /// the given interval. Applies only to time-interval tasks.
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn get_aggregate_share_jobs_intersecting_interval<
-        const SEED_SIZE: usize,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
-        &self,
-        vdaf: &A,
-        task_id: &TaskId,
-        interval: &Interval,
-    ) -> Result<Vec<AggregateShareJob<SEED_SIZE, TimeInterval, A>>, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok(Vec::new()),
-        };
-
-        interval
-            .validate_precision(&task_info.time_precision)
-            .map_err(|e| Self::unaligned_time_error(task_id, &task_info.time_precision, e))?;
+        let peer_aggregator_row = self.query_opt(&stmt, params);
 
         let stmt = self
             .prepare_cached(
-                "-- get_aggregate_share_jobs_intersecting_interval()
-SELECT
-    batch_identifier, aggregation_param, helper_aggregate_share, report_count,
-    checksum
-FROM aggregate_share_jobs
-WHERE task_id = $1
-  AND batch_interval && $2
-  AND LOWER(aggregate_share_jobs.batch_interval) >= $3",
+                "-- get_taskprov_peer_aggregator()
+SELECT ord, type, token FROM taskprov_aggregator_auth_tokens
+    WHERE peer_aggregator_id = (SELECT id FROM taskprov_peer_aggregators
+        WHERE endpoint = $1 AND peer_role = $2)
+    ORDER BY ord ASC",
             )
             .await?;
-        self.query(
-            &stmt,
-            &[
-                /* task_id */ &task_info.pkey,
-                /* interval */ &SqlInterval::from(interval),
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-            ],
-        )
-        .await?
-        .into_iter()
-        .map(|row| {
-            let batch_identifier = Interval::get_decoded(row.get("batch_identifier"))?;
-            let aggregation_param = A::AggregationParam::get_decoded(row.get("aggregation_param"))?;
-            Self::aggregate_share_job_from_row(
-                vdaf,
-                task_id,
-                batch_identifier,
-                aggregation_param,
-                &row,
-            )
-        })
-        .collect()
-    }
-
-    /// Returns all aggregate share jobs for the given task with the given batch identifier.
-    /// Multiple aggregate share jobs may be returned with distinct aggregation parameters. Applies
-    /// only to leader-selected tasks.
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn get_aggregate_share_jobs_by_batch_id<
-        const SEED_SIZE: usize,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
-        &self,
-        vdaf: &A,
-        task_id: &TaskId,
-        batch_id: &BatchId,
-    ) -> Result<Vec<AggregateShareJob<SEED_SIZE, LeaderSelected, A>>, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok(Vec::new()),
-        };
+        let aggregator_auth_token_rows = self.query(&stmt, params);
 
         let stmt = self
             .prepare_cached(
-                "-- get_aggregate_share_jobs_by_batch_id()
-SELECT
-    aggregation_param, helper_aggregate_share, report_count, checksum
-FROM aggregate_share_jobs
-WHERE task_id = $1
-  AND batch_identifier = $2
-  AND COALESCE(
-          (SELECT MAX(UPPER(client_timestamp_interval))
-           FROM batch_aggregations ba
-           WHERE ba.task_id = aggregate_share_jobs.task_id
-             AND ba.batch_identifier = aggregate_share_jobs.batch_identifier
-             AND ba.aggregation_param = aggregate_share_jobs.aggregation_param),
-          '-infinity'::TIMESTAMP) >= $3",
+                "-- get_taskprov_peer_aggregator()
+SELECT ord, type, token FROM taskprov_collector_auth_tokens
+    WHERE peer_aggregator_id = (SELECT id FROM taskprov_peer_aggregators
+        WHERE endpoint = $1 AND peer_role = $2)
+    ORDER BY ord ASC",
             )
             .await?;
-        self.query(
-            &stmt,
-            &[
-                /* task_id */ &task_info.pkey,
-                /* batch_id */ &batch_id.get_encoded()?,
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-            ],
-        )
-        .await?
-        .into_iter()
-        .map(|row| {
-            let aggregation_param = A::AggregationParam::get_decoded(row.get("aggregation_param"))?;
-            Self::aggregate_share_job_from_row(vdaf, task_id, *batch_id, aggregation_param, &row)
-        })
-        .collect()
+        let collector_auth_token_rows = self.query(&stmt, params);
+
+        let (peer_aggregator_row, aggregator_auth_token_rows, collector_auth_token_rows) = try_join!(
+            peer_aggregator_row,
+            aggregator_auth_token_rows,
+            collector_auth_token_rows,
+        )?;
+        peer_aggregator_row
+            .map(|peer_aggregator_row| {
+                self.taskprov_peer_aggregator_from_rows(
+                    &peer_aggregator_row,
+                    &aggregator_auth_token_rows,
+                    &collector_auth_token_rows,
+                )
+            })
+            .transpose()
     }
 
-    #[cfg(feature = "test-util")]
-    pub async fn get_aggregate_share_jobs_for_task<
-        const SEED_SIZE: usize,
-        B: BatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    fn taskprov_peer_aggregator_from_rows(
         &self,
-        vdaf: &A,
-        task_id: &TaskId,
-    ) -> Result<Vec<AggregateShareJob<SEED_SIZE, B, A>>, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok(Vec::new()),
+        peer_aggregator_row: &Row,
+        aggregator_auth_token_rows: &[Row],
+        collector_auth_token_rows: &[Row],
+    ) -> Result<PeerAggregator, Error> {
+        let endpoint = Url::parse(peer_aggregator_row.get::<_, &str>("endpoint"))?;
+        let endpoint_bytes = endpoint.as_str().as_ref();
+        let peer_role: AggregatorRole = peer_aggregator_row.get("peer_role");
+        let aggregation_mode: Option<AggregationMode> = peer_aggregator_row.get("aggregation_mode");
+        let report_expiry_age = peer_aggregator_row
+            .get_nullable_bigint_and_convert("report_expiry_age")?
+            .map(Duration::from_seconds);
+        let collector_hpke_config =
+            HpkeConfig::get_decoded(peer_aggregator_row.get("collector_hpke_config"))?;
+
+        let encrypted_verify_key_init: Vec<u8> = peer_aggregator_row.get("verify_key_init");
+        let verify_key_init = self
+            .crypter
+            .decrypt(
+                "taskprov_peer_aggregator",
+                endpoint_bytes,
+                "verify_key_init",
+                &encrypted_verify_key_init,
+            )?
+            .as_slice()
+            .try_into()?;
+
+        let decrypt_tokens = |rows: &[Row], table| -> Result<Vec<_>, Error> {
+            rows.iter()
+                .map(|row| {
+                    let ord: i64 = row.get("ord");
+                    let auth_token_type: AuthenticationTokenType = row.get("type");
+                    let encrypted_token: Vec<u8> = row.get("token");
+
+                    let mut row_id = Vec::new();
+                    row_id.extend_from_slice(endpoint_bytes);
+                    row_id.extend_from_slice(&peer_role.as_role().get_encoded()?);
+                    row_id.extend_from_slice(&ord.to_be_bytes());
+
+                    auth_token_type.as_authentication(&self.crypter.decrypt(
+                        table,
+                        &row_id,
+                        "token",
+                        &encrypted_token,
+                    )?)
+                })
+                .collect()
         };
 
-        let stmt = self
-            .prepare_cached(
-                "-- get_aggregate_share_jobs_for_task()
-SELECT
-    batch_identifier, aggregation_param, helper_aggregate_share, report_count,
-    checksum
-FROM aggregate_share_jobs
-WHERE task_id = $1
-  AND COALESCE(
-          LOWER(aggregate_share_jobs.batch_interval),
-          (SELECT MAX(UPPER(client_timestamp_interval))
-           FROM batch_aggregations ba
-           WHERE ba.task_id = aggregate_share_jobs.task_id
-             AND ba.batch_identifier = aggregate_share_jobs.batch_identifier
-             AND ba.aggregation_param = aggregate_share_jobs.aggregation_param),
-          '-infinity'::TIMESTAMP) >= $2",
-            )
-            .await?;
-        self.query(
-            &stmt,
-            &[
-                /* task_id */ &task_info.pkey,
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-            ],
-        )
-        .await?
-        .into_iter()
-        .map(|row| {
-            let batch_identifier = B::BatchIdentifier::get_decoded(row.get("batch_identifier"))?;
-            let aggregation_param = A::AggregationParam::get_decoded(row.get("aggregation_param"))?;
-            Self::aggregate_share_job_from_row(
-                vdaf,
-                task_id,
-                batch_identifier,
-                aggregation_param,
-                &row,
-            )
-        })
-        .collect()
-    }
+        let aggregator_auth_tokens = decrypt_tokens(
+            aggregator_auth_token_rows,
+            "taskprov_aggregator_auth_tokens",
+        )?;
+        let collector_auth_tokens =
+            decrypt_tokens(collector_auth_token_rows, "taskprov_collector_auth_tokens")?;
 
-    fn aggregate_share_job_from_row<
-        const SEED_SIZE: usize,
-        B: BatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
-        vdaf: &A,
-        task_id: &TaskId,
-        batch_identifier: B::BatchIdentifier,
-        aggregation_param: A::AggregationParam,
-        row: &Row,
-    ) -> Result<AggregateShareJob<SEED_SIZE, B, A>, Error> {
-        let helper_aggregate_share =
-            row.get_bytea_and_decode("helper_aggregate_share", &(vdaf, &aggregation_param))?;
-        Ok(AggregateShareJob::new(
-            *task_id,
-            batch_identifier,
-            aggregation_param,
-            helper_aggregate_share,
-            row.get_bigint_and_convert("report_count")?,
-            ReportIdChecksum::get_decoded(row.get("checksum"))?,
-        ))
+        PeerAggregator::new(
+            endpoint,
+            peer_role.as_role(),
+            aggregation_mode,
+            verify_key_init,
+            collector_hpke_config,
+            report_expiry_age,
+            aggregator_auth_tokens,
+            collector_auth_tokens,
+        )
+        .map_err(|e| Error::User(e.into()))
     }
 
-    /// Put an `aggregate_share_job` row into the datastore.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn put_aggregate_share_job<
-        const SEED_SIZE: usize,
-        B: CollectableBatchMode,
-        A: AsyncAggregator<SEED_SIZE>,
-    >(
+    pub async fn put_taskprov_peer_aggregator(
         &self,
-        aggregate_share_job: &AggregateShareJob<SEED_SIZE, B, A>,
+        peer_aggregator: &PeerAggregator,
     ) -> Result<(), Error> {
-        let task_info = match self.task_info_for(aggregate_share_job.task_id()).await? {
-            Some(task_info) => task_info,
-            None => return Err(Error::MutationTargetNotFound),
-        };
-        let now = self.clock.now().as_naive_date_time()?;
-        let batch_interval =
-            B::to_batch_interval(aggregate_share_job.batch_identifier()).map(SqlInterval::from);
+        let endpoint = peer_aggregator.endpoint().as_str();
+        let peer_role = &AggregatorRole::from_role(*peer_aggregator.peer_role())?;
+        let encrypted_verify_key_init = self.crypter.encrypt(
+            "taskprov_peer_aggregator",
+            endpoint.as_ref(),
+            "verify_key_init",
+            peer_aggregator.verify_key_init().as_ref(),
+        )?;
 
         let stmt = self
             .prepare_cached(
-                "-- put_aggregate_share_job()
-INSERT INTO aggregate_share_jobs (
-    task_id, batch_identifier, batch_interval, aggregation_param,
-    helper_aggregate_share, report_count, checksum, created_at, updated_by
-)
-VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-ON CONFLICT(task_id, batch_identifier, aggregation_param) DO UPDATE
-    SET (
-        helper_aggregate_share, report_count, checksum, created_at, updated_by
-    ) = (
-        excluded.helper_aggregate_share, excluded.report_count, excluded.checksum,
-        excluded.created_at, excluded.updated_by
-    )
-    WHERE COALESCE(
-              LOWER(aggregate_share_jobs.batch_interval),
-              (SELECT MAX(UPPER(ba.client_timestamp_interval))
-               FROM batch_aggregations ba
-               WHERE ba.task_id = aggregate_share_jobs.task_id
-                 AND ba.batch_identifier = aggregate_share_jobs.batch_identifier
-                 AND ba.aggregation_param = aggregate_share_jobs.aggregation_param),
-              '-infinity'::TIMESTAMP) < $10",
+                "-- put_taskprov_peer_aggregator()
+INSERT INTO taskprov_peer_aggregators (
+    endpoint, peer_role, aggregation_mode, verify_key_init, report_expiry_age,
+    collector_hpke_config, created_at, updated_by
+) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+ON CONFLICT DO NOTHING",
             )
             .await?;
         check_insert(
             self.execute(
                 &stmt,
                 &[
-                    /* task_id */ &task_info.pkey,
-                    /* batch_identifier */
-                    &aggregate_share_job.batch_identifier().get_encoded()?,
-                    /* batch_interval */ &batch_interval,
-                    /* aggregation_param */
-                    &aggregate_share_job.aggregation_parameter().get_encoded()?,
-                    /* helper_aggregate_share */
-                    &aggregate_share_job.helper_aggregate_share().get_encoded()?,
-                    /* report_count */ &i64::try_from(aggregate_share_job.report_count())?,
-                    /* checksum */ &aggregate_share_job.checksum().get_encoded()?,
-                    /* created_at */ &now,
-                    /* updated_by */ &self.name,
-                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                    /* endpoint */ &endpoint,
+                    /* peer_role */ peer_role,
+                    /* aggregation_mode */ &peer_aggregator.aggregation_mode(),
+                    /* verify_key_init */ &encrypted_verify_key_init,
+                    /* report_expiry_age */
+                    &peer_aggregator
+                        .report_expiry_age()
+                        .map(Duration::as_seconds)
+                        .map(i64::try_from)
+                        .transpose()?,
+                    /* collector_hpke_config */
+                    &peer_aggregator.collector_hpke_config().get_encoded()?,
+                    /* created_at */ &self.clock.now().as_naive_date_time()?,
+                    /* updated_by */
+                    &self.name,
                 ],
             )
             .await?,
-        )
-    }
+        )?;
 
-    /// Writes an outstanding batch. (This method does not take an [`OutstandingBatch`] as several
-    /// of the included values are read implicitly.)
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn put_outstanding_batch(
-        &self,
-        task_id: &TaskId,
-        batch_id: &BatchId,
-        time_bucket_start: &Option<Time>,
-    ) -> Result<(), Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Err(Error::MutationTargetNotFound),
-        };
+        let encrypt_tokens = |tokens: &[AuthenticationToken], table| -> Result<_, Error> {
+            let mut ords = Vec::new();
+            let mut types = Vec::new();
+            let mut encrypted_tokens = Vec::new();
+            for (ord, token) in tokens.iter().enumerate() {
+                let ord = i64::try_from(ord)?;
 
-        if let Some(start) = time_bucket_start {
-            start
-                .validate_precision(&task_info.time_precision)
-                .map_err(|e| Self::unaligned_time_error(task_id, &task_info.time_precision, e))?;
-        }
+                let mut row_id = Vec::new();
+                row_id.extend_from_slice(endpoint.as_ref());
+                row_id.extend_from_slice(&peer_role.as_role().get_encoded()?);
+                row_id.extend_from_slice(&ord.to_be_bytes());
 
-        let now = self.clock.now().as_naive_date_time()?;
+                let encrypted_auth_token =
+                    self.crypter
+                        .encrypt(table, &row_id, "token", token.as_ref())?;
 
-        // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param) which
-        // are _not_ garbage collected. This is used to evaluate whether given batch_aggregations
-        // rows are GC'ed.
-        //
-        // Note that this ignores aggregation parameter, as `outstanding_batches` does not need to
-        // worry about aggregation parameters.
-        //
-        // TODO(#225): reevaluate whether we can ignore aggregation parameter here once we have
-        // experience with VDAFs requiring multiple aggregations per batch.
+                ords.push(ord);
+                types.push(AuthenticationTokenType::from(token));
+                encrypted_tokens.push(encrypted_auth_token);
+            }
+            Ok((ords, types, encrypted_tokens))
+        };
+
+        let (aggregator_auth_token_ords, aggregator_auth_token_types, aggregator_auth_tokens) =
+            encrypt_tokens(
+                peer_aggregator.aggregator_auth_tokens(),
+                "taskprov_aggregator_auth_tokens",
+            )?;
         let stmt = self
             .prepare_cached(
-                "-- put_outstanding_batch()
-WITH non_gc_batches AS (
-    SELECT batch_identifier
-    FROM batch_aggregations
-    WHERE task_id = $1
-      AND batch_identifier = $2
-    GROUP BY batch_identifier
-    HAVING MAX(UPPER(client_timestamp_interval)) >= $6
+                "-- put_taskprov_peer_aggregator()
+INSERT INTO taskprov_aggregator_auth_tokens (
+    peer_aggregator_id, created_at, updated_by, ord, type, token
 )
-INSERT INTO outstanding_batches (
-    task_id, batch_id, time_bucket_start, created_at, updated_by
+SELECT
+    (SELECT id FROM taskprov_peer_aggregators WHERE endpoint = $1 AND peer_role = $2),
+    $3, $4, * FROM UNNEST($5::BIGINT[], $6::AUTH_TOKEN_TYPE[], $7::BYTEA[])",
+            )
+            .await?;
+        let aggregator_auth_tokens_params: &[&(dyn ToSql + Sync)] = &[
+            /* endpoint */ &endpoint,
+            /* peer_role */ peer_role,
+            /* created_at */ &self.clock.now().as_naive_date_time()?,
+            /* updated_by */ &self.name,
+            /* ords */ &aggregator_auth_token_ords,
+            /* token_types */ &aggregator_auth_token_types,
+            /* tokens */ &aggregator_auth_tokens,
+        ];
+        let aggregator_auth_tokens_future = self.execute(&stmt, aggregator_auth_tokens_params);
+
+        let (collector_auth_token_ords, collector_auth_token_types, collector_auth_tokens) =
+            encrypt_tokens(
+                peer_aggregator.collector_auth_tokens(),
+                "taskprov_collector_auth_tokens",
+            )?;
+        let stmt = self
+            .prepare_cached(
+                "-- put_taskprov_peer_aggregator()
+INSERT INTO taskprov_collector_auth_tokens (
+    peer_aggregator_id, created_at, updated_by, ord,  type, token
 )
-VALUES ($1, $2, $3, $4, $5)
-ON CONFLICT(task_id, batch_id) DO UPDATE
-    SET (
-        time_bucket_start, created_at, updated_by
-    ) = (
-        excluded.time_bucket_start, excluded.created_at, excluded.updated_by
-    )
-    WHERE NOT EXISTS(SELECT 1 FROM non_gc_batches
-                     WHERE batch_identifier = outstanding_batches.batch_id)",
+SELECT
+    (SELECT id FROM taskprov_peer_aggregators WHERE endpoint = $1 AND peer_role = $2),
+    $3, $4, * FROM UNNEST($5::BIGINT[], $6::AUTH_TOKEN_TYPE[], $7::BYTEA[])",
             )
             .await?;
-        check_insert(
-            self.execute(
-                &stmt,
-                &[
-                    /* task_id */ &task_info.pkey,
-                    /* batch_id */ batch_id.as_ref(),
-                    /* time_bucket_start */
-                    &time_bucket_start
-                        .as_ref()
-                        .map(Time::as_naive_date_time)
-                        .transpose()?,
-                    /* created_at */ &now,
-                    /* updated_by */ &self.name,
-                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
-                ],
-            )
-            .await?,
-        )
+        let collector_auth_tokens_params: &[&(dyn ToSql + Sync)] = &[
+            /* endpoint */ &endpoint,
+            /* peer_role */ peer_role,
+            /* created_at */ &self.clock.now().as_naive_date_time()?,
+            /* updated_by */ &self.name,
+            /* ords */ &collector_auth_token_ords,
+            /* token_types */ &collector_auth_token_types,
+            /* tokens */ &collector_auth_tokens,
+        ];
+        let collector_auth_tokens_future = self.execute(&stmt, collector_auth_tokens_params);
+
+        try_join!(aggregator_auth_tokens_future, collector_auth_tokens_future)?;
+        Ok(())
     }
 
-    /// Retrieves all [`OutstandingBatch`]es for a given task and (if applicable) time bucket which
-    /// have not yet been marked filled.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn get_unfilled_outstanding_batches(
+    pub async fn delete_taskprov_peer_aggregator(
         &self,
-        task_id: &TaskId,
-        time_bucket_start: &Option<Time>,
-    ) -> Result<Vec<OutstandingBatch>, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok(Vec::new()),
-        };
+        aggregator_url: &Url,
+        peer_role: &Role,
+    ) -> Result<(), Error> {
+        let aggregator_url = aggregator_url.as_str();
+        let peer_role = AggregatorRole::from_role(*peer_role)?;
 
-        let rows = if let Some(time_bucket_start) = time_bucket_start {
-            // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param)
-            // which are _not_ garbage collected. This is used to evaluate whether given
-            // batch_aggregations rows are GC'ed.
-            let stmt = self
-                .prepare_cached(
-                    "-- get_unfilled_outstanding_batches()
-SELECT batch_id FROM outstanding_batches
-WHERE task_id = $1
-  AND time_bucket_start = $2
-  AND state = 'FILLING'
-  AND (SELECT MAX(UPPER(client_timestamp_interval)) FROM batch_aggregations
-          WHERE batch_identifier = outstanding_batches.batch_id
-            AND task_id = $1
-  ) >= $3",
-                )
-                .await?;
-            self.query(
-                &stmt,
-                &[
-                    /* task_id */ &task_info.pkey,
-                    /* time_bucket_start */ &time_bucket_start.as_naive_date_time()?,
-                    /* threshold */
-                    &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-                ],
-            )
-            .await?
-        } else {
-            // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param)
-            // which are _not_ garbage collected. This is used to evaluate whether given
-            // batch_aggregations rows are GC'ed.
-            let stmt = self
-                .prepare_cached(
-                    "-- get_unfilled_outstanding_batches()
-SELECT batch_id FROM outstanding_batches
-WHERE task_id = $1
-  AND time_bucket_start IS NULL
-  AND state = 'FILLING'
-  AND (SELECT MAX(UPPER(client_timestamp_interval)) FROM batch_aggregations
-          WHERE batch_identifier = outstanding_batches.batch_id
-            AND task_id = $1
-  ) >= $2",
-                )
-                .await?;
-            self.query(
-                &stmt,
-                &[
-                    /* task_id */ &task_info.pkey,
-                    /* threshold */
-                    &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-                ],
+        // Deletion of other data implemented via ON DELETE CASCADE.
+        let stmt = self
+            .prepare_cached(
+                "-- delete_taskprov_peer_aggregator()
+DELETE FROM taskprov_peer_aggregators WHERE endpoint = $1 AND peer_role = $2",
             )
-            .await?
-        };
-
-        try_join_all(rows.into_iter().map(|row| async move {
-            let batch_id = BatchId::get_decoded(row.get("batch_id"))?;
-            let size = self.read_batch_size(task_info.pkey, &batch_id).await?;
-            Ok(OutstandingBatch::new(*task_id, batch_id, size))
-        }))
-        .await
+            .await?;
+        check_single_row_mutation(self.execute(&stmt, &[&aggregator_url, &peer_role]).await?)
     }
 
-    // Return value is an inclusive range [min_size, max_size], where:
-    //  * min_size is the minimum possible number of reports included in the batch, i.e. all report
-    //    aggregations in the batch which have reached the FINISHED state.
-    //  * max_size is the maximum possible number of reports included in the batch, i.e. all report
-    //    aggregations in the batch which are in a non-failure state (INIT/CONTINUE/FINISHED).
-    async fn read_batch_size(
+    /// Get the [`TaskUploadCounter`] for a task. This is aggregated across all shards. Returns
+    /// `None` if the task doesn't exist.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn get_task_upload_counter(
         &self,
-        task_pkey: i64,
-        batch_id: &BatchId,
-    ) -> Result<RangeInclusive<usize>, Error> {
+        task_id: &TaskId,
+    ) -> Result<Option<TaskUploadCounter>, Error> {
         let stmt = self
             .prepare_cached(
-                "-- read_batch_size()
-WITH report_aggregations_count AS (
-    SELECT COUNT(*) AS count FROM report_aggregations
-    JOIN aggregation_jobs
-        ON report_aggregations.aggregation_job_id = aggregation_jobs.id
-    WHERE aggregation_jobs.task_id = $1
-    AND report_aggregations.task_id = aggregation_jobs.task_id
-    AND aggregation_jobs.batch_id = $2
-    AND report_aggregations.state in ('INIT', 'CONTINUE')
-),
-batch_aggregation_count AS (
-    SELECT SUM(report_count) AS count FROM batch_aggregations
-    WHERE batch_aggregations.task_id = $1
-    AND batch_aggregations.batch_identifier = $2
-)
+                "-- get_task_upload_counter()
 SELECT
-    (SELECT count FROM batch_aggregation_count)::BIGINT AS min_size,
-    (SELECT count FROM report_aggregations_count)::BIGINT
-        + (SELECT count FROM batch_aggregation_count)::BIGINT AS max_size",
+    tasks.id,
+    COALESCE(SUM(interval_collected)::BIGINT, 0) AS interval_collected,
+    COALESCE(SUM(report_decode_failure)::BIGINT, 0) AS report_decode_failure,
+    COALESCE(SUM(report_decrypt_failure)::BIGINT, 0) AS report_decrypt_failure,
+    COALESCE(SUM(report_expired)::BIGINT, 0) AS report_expired,
+    COALESCE(SUM(report_outdated_key)::BIGINT, 0) AS report_outdated_key,
+    COALESCE(SUM(report_success)::BIGINT, 0) AS report_success,
+    COALESCE(SUM(report_too_early)::BIGINT, 0) AS report_too_early,
+    COALESCE(SUM(task_not_started)::BIGINT, 0) AS task_not_started,
+    COALESCE(SUM(task_ended)::BIGINT, 0) AS task_ended,
+    COALESCE(SUM(task_not_accepting_reports)::BIGINT, 0) AS task_not_accepting_reports
+FROM task_upload_counters
+RIGHT JOIN tasks on tasks.id = task_upload_counters.task_id
+WHERE tasks.task_id = $1
+GROUP BY tasks.id",
             )
             .await?;
 
-        let row = self
-            .query_one(
+        self.query_opt(&stmt, &[task_id.as_ref()])
+            .await?
+            .map(|row| {
+                Ok(TaskUploadCounter {
+                    interval_collected: row.get_bigint_and_convert("interval_collected")?,
+                    report_decode_failure: row.get_bigint_and_convert("report_decode_failure")?,
+                    report_decrypt_failure: row.get_bigint_and_convert("report_decrypt_failure")?,
+                    report_expired: row.get_bigint_and_convert("report_expired")?,
+                    report_outdated_key: row.get_bigint_and_convert("report_outdated_key")?,
+                    report_success: row.get_bigint_and_convert("report_success")?,
+                    report_too_early: row.get_bigint_and_convert("report_too_early")?,
+                    task_not_started: row.get_bigint_and_convert("task_not_started")?,
+                    task_ended: row.get_bigint_and_convert("task_ended")?,
+                    task_not_accepting_reports: row
+                        .get_bigint_and_convert("task_not_accepting_reports")?,
+                })
+            })
+            .transpose()
+    }
+
+    /// Add a `TaskUploadCounter` to the counter associated with the given [`TaskId`]. This is sharded,
+    /// requiring an `ord` parameter to determine which shard to add to. `ord` should be randomly
+    /// generated by the caller.
+    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
+    pub async fn increment_task_upload_counter(
+        &self,
+        task_id: &TaskId,
+        ord: u64,
+        counter: &TaskUploadCounter,
+    ) -> Result<(), Error> {
+        let stmt = "-- increment_task_upload_counter()
+INSERT INTO task_upload_counters (
+    task_id, ord, interval_collected, report_decode_failure,
+    report_decrypt_failure, report_expired, report_outdated_key, report_success, report_too_early,
+    task_not_started, task_ended, task_not_accepting_reports
+)
+VALUES ((SELECT id FROM tasks WHERE task_id = $1), $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+ON CONFLICT (task_id, ord) DO UPDATE SET
+    interval_collected = task_upload_counters.interval_collected + $3,
+    report_decode_failure = task_upload_counters.report_decode_failure + $4,
+    report_decrypt_failure = task_upload_counters.report_decrypt_failure + $5,
+    report_expired = task_upload_counters.report_expired + $6,
+    report_outdated_key = task_upload_counters.report_outdated_key + $7,
+    report_success = task_upload_counters.report_success + $8,
+    report_too_early = task_upload_counters.report_too_early + $9,
+    task_not_started = task_upload_counters.task_not_started + $10,
+    task_ended = task_upload_counters.task_ended + $11,
+    task_not_accepting_reports = task_upload_counters.task_not_accepting_reports + $12";
+
+        let stmt = self.prepare_cached(stmt).await?;
+        check_single_row_mutation(
+            self.execute(
                 &stmt,
                 &[
-                    /* task_id */ &task_pkey,
-                    /* batch_id */ batch_id.as_ref(),
+                    task_id.as_ref(),
+                    &i64::try_from(ord)?,
+                    &i64::try_from(counter.interval_collected)?,
+                    &i64::try_from(counter.report_decode_failure)?,
+                    &i64::try_from(counter.report_decrypt_failure)?,
+                    &i64::try_from(counter.report_expired)?,
+                    &i64::try_from(counter.report_outdated_key)?,
+                    &i64::try_from(counter.report_success)?,
+                    &i64::try_from(counter.report_too_early)?,
+                    &i64::try_from(counter.task_not_started)?,
+                    &i64::try_from(counter.task_ended)?,
+                    &i64::try_from(counter.task_not_accepting_reports)?,
                 ],
             )
-            .await?;
-
-        Ok(RangeInclusive::new(
-            row.get::<_, Option<i64>>("min_size")
-                .unwrap_or_default()
-                .try_into()?,
-            row.get::<_, Option<i64>>("max_size")
-                .unwrap_or_default()
-                .try_into()?,
-        ))
+            .await?,
+        )
     }
 
-    /// Retrieves an outstanding batch for the given task with at least the given number of
-    /// successfully-aggregated reports, removing it from the datastore.
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn acquire_outstanding_batch_with_report_count(
+    /// Retrieves the task aggregation counters for a given task. This result reflects the overall
+    /// counter values, merged across all shards.
+    pub async fn get_task_aggregation_counter(
         &self,
-        task_id: &TaskId,
-        min_report_count: u64,
-    ) -> Result<Option<BatchId>, Error> {
+        task_id: &TaskId,
+    ) -> Result<Option<TaskAggregationCounter>, Error> {
         let task_info = match self.task_info_for(task_id).await? {
             Some(task_info) => task_info,
             None => return Ok(None),
         };
 
-        // non_gc_batches finds batches (by task ID, batch identifier, and aggregation param) which
-        // are _not_ garbage collected. This is used to evaluate whether given batch_aggregations
-        // rows are GC'ed.
         let stmt = self
             .prepare_cached(
-                "-- acquire_outstanding_batch_with_report_count()
-WITH non_gc_batches AS (
-    SELECT batch_identifier, SUM(report_count) AS report_count
-    FROM batch_aggregations
-    WHERE task_id = $1
-    GROUP BY batch_identifier
-    HAVING MAX(UPPER(client_timestamp_interval)) >= $3
-),
-selected_outstanding_batch AS (
-    SELECT outstanding_batches.id
-    FROM outstanding_batches
-    WHERE task_id = $1
-    AND (SELECT report_count FROM non_gc_batches
-        WHERE batch_identifier = outstanding_batches.batch_id) >= $2::BIGINT
-    LIMIT 1
-)
-DELETE FROM outstanding_batches WHERE id IN (SELECT id FROM selected_outstanding_batch)
-RETURNING batch_id",
+                "-- get_task_aggregation_counter()
+SELECT
+    COALESCE(SUM(success)::BIGINT, 0) AS success
+FROM task_aggregation_counters
+WHERE task_id = $1",
             )
             .await?;
 
-        self.query_opt(
-            &stmt,
-            &[
-                /* task_id */ &task_info.pkey,
-                /* min_report_count */ &i64::try_from(min_report_count)?,
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-            ],
-        )
-        .await?
-        .map(|row| Ok(BatchId::get_decoded(row.get("batch_id"))?))
-        .transpose()
+        self.query_opt(&stmt, &[/* task_id */ &task_info.pkey])
+            .await?
+            .map(|row| {
+                Ok(TaskAggregationCounter {
+                    success: row.get_bigint_and_convert("success")?,
+                })
+            })
+            .transpose()
     }
 
-    /// Marks a given outstanding batch as filled, such that it will no longer be considered when
-    /// assigning aggregation jobs to batches.
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn mark_outstanding_batch_filled(
+    /// Increments the task aggregation counters for a given task by the values in the provided
+    /// counters. The `ord` parameter determines which "shard" of the counters to increment;
+    /// generally this value would be randomly-generated.
+    pub async fn increment_task_aggregation_counter(
         &self,
         task_id: &TaskId,
-        batch_id: &BatchId,
+        ord: u64,
+        counter: &TaskAggregationCounter,
     ) -> Result<(), Error> {
         let task_info = match self.task_info_for(task_id).await? {
             Some(task_info) => task_info,
             None => return Err(Error::MutationTargetNotFound),
         };
-        let now = self.clock.now().as_naive_date_time()?;
 
         let stmt = self
             .prepare_cached(
-                "-- mark_outstanding_batch_filled()
-WITH non_gc_batches AS (
-    SELECT batch_identifier
-    FROM batch_aggregations
-    WHERE task_id = $1
-    AND batch_identifier = $2
-    GROUP BY batch_identifier
-    HAVING MAX(UPPER(client_timestamp_interval)) >= $3
-)
-UPDATE outstanding_batches
-SET state = 'FILLED'
-WHERE task_id = $1
-AND batch_id = $2
-AND EXISTS(SELECT 1 FROM non_gc_batches WHERE batch_identifier = $2)",
+                "-- increment_task_aggregation_counter()
+INSERT INTO task_aggregation_counters (task_id, ord, success)
+VALUES ($1, $2, $3)
+ON CONFLICT (task_id, ord) DO UPDATE SET
+    success = task_aggregation_counters.success + $3",
             )
             .await?;
+
         check_single_row_mutation(
             self.execute(
                 &stmt,
                 &[
                     /* task_id */ &task_info.pkey,
-                    /* batch_id */ batch_id.as_ref(),
-                    /* threshold */ &task_info.report_expiry_threshold(&now)?,
+                    /* ord */ &i64::try_from(ord)?,
+                    /* success */ &i64::try_from(counter.success)?,
                 ],
             )
             .await?,
         )
     }
 
-    /// Deletes old client reports for a given task, that is, client reports whose timestamp is
-    /// older than the task's report expiry age. Up to `limit` client reports will be deleted.
-    /// Returns the number of client reports deleted.
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn delete_expired_client_reports(
+    /// Records that a collection of the given batch of a task, with the given aggregation
+    /// parameter, incurred a privacy loss of `privacy_loss` against the task's differential privacy
+    /// budget. If recording the loss would take the task's spending past its budget, nothing is
+    /// recorded and `false` is returned.
+    #[tracing::instrument(skip(self, aggregation_param), err(level = Level::DEBUG))]
+    pub async fn spend_dp_budget<
+        const SEED_SIZE: usize,
+        B: BatchMode,
+        A: AsyncAggregator<SEED_SIZE>,
+    >(
         &self,
         task_id: &TaskId,
-        limit: u64,
-    ) -> Result<u64, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok(0),
-        };
+        batch_identifier: &B::BatchIdentifier,
+        aggregation_param: &A::AggregationParam,
+        privacy_loss: f64,
+    ) -> Result<bool, Error> {
+        if self.task_info_for(task_id).await?.is_none() {
+            return Err(Error::MutationTargetNotFound);
+        }
 
+        // Spending is accumulated on the task's row, rather than summed over the ledger, so that
+        // concurrent collections conflict with one another instead of both seeing enough budget
+        // remaining.
         let stmt = self
             .prepare_cached(
-                "-- delete_expired_client_reports()
-WITH client_reports_to_delete AS (
-    SELECT client_reports.id FROM client_reports
-    WHERE client_reports.task_id = $1
-        AND client_reports.client_timestamp < $2::TIMESTAMP
-    LIMIT $3
+                "-- spend_dp_budget()
+WITH updated_task AS (
+    UPDATE tasks SET dp_budget_spent = dp_budget_spent + $2
+    WHERE task_id = $1 AND (dp_budget IS NULL OR dp_budget_spent + $2 <= dp_budget)
+    RETURNING id
 )
-DELETE FROM client_reports
-USING client_reports_to_delete
-WHERE client_reports.id = client_reports_to_delete.id",
+INSERT INTO dp_budget_ledger
+    (task_id, batch_identifier, aggregation_param, privacy_loss, created_at, updated_by)
+SELECT id, $3, $4, $2, $5, $6 FROM updated_task",
             )
             .await?;
-        self.execute(
-            &stmt,
-            &[
-                /* id */ &task_info.pkey,
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-                /* limit */ &i64::try_from(limit)?,
-            ],
-        )
-        .await
-        .map_err(Into::into)
+        let rows_affected = self
+            .execute(
+                &stmt,
+                &[
+                    /* task_id */ &task_id.as_ref(),
+                    /* privacy_loss */ &privacy_loss,
+                    /* batch_identifier */ &batch_identifier.get_encoded()?,
+                    /* aggregation_param */ &aggregation_param.get_encoded()?,
+                    /* created_at */ &self.clock.now().as_naive_date_time()?,
+                    /* updated_by */ &self.name,
+                ],
+            )
+            .await?;
+        Ok(rows_affected == 1)
     }
 
-    /// Deletes old aggregation artifacts (aggregation jobs/report aggregations) for a given task,
-    /// that is, aggregation artifacts for which the aggregation job's maximum client timestamp is
-    /// older than the task's report expiry age. Up to `limit` aggregation jobs will be deleted,
-    /// along with all related aggregation artifacts. Returns the number of aggregation jobs
-    /// deleted.
+    /// Retrieves the differential privacy budget spent by a given task's collections.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn delete_expired_aggregation_artifacts(
+    pub async fn get_dp_budget_usage(
         &self,
         task_id: &TaskId,
-        limit: u64,
-    ) -> Result<u64, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok(0),
-        };
-
+    ) -> Result<Option<DpBudgetUsage>, Error> {
         let stmt = self
             .prepare_cached(
-                "-- delete_expired_aggregation_artifacts()
-WITH aggregation_jobs_to_delete AS (
-    SELECT aggregation_jobs.id FROM aggregation_jobs
-    WHERE task_id = $1
-      AND UPPER(aggregation_jobs.client_timestamp_interval) < $2
-    LIMIT $3
-),
-deleted_report_aggregations AS (
-    DELETE FROM report_aggregations
-    WHERE aggregation_job_id IN (SELECT id FROM aggregation_jobs_to_delete)
-)
-DELETE FROM aggregation_jobs
-WHERE id IN (SELECT id FROM aggregation_jobs_to_delete)",
+                "-- get_dp_budget_usage()
+SELECT
+    tasks.dp_budget_spent,
+    (SELECT COUNT(*) FROM dp_budget_ledger WHERE dp_budget_ledger.task_id = tasks.id)
+        AS collection_count
+FROM tasks WHERE tasks.task_id = $1",
             )
             .await?;
-        self.execute(
-            &stmt,
-            &[
-                /* task_id */ &task_info.pkey,
-                /* threshold */
-                &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-                /* limit */ &i64::try_from(limit)?,
-            ],
-        )
-        .await
-        .map_err(Into::into)
-    }
 
-    /// Deletes old collection artifacts (outstanding batches/batch aggregations/collection jobs/
-    /// aggregate share jobs) for a given task per the following policy:
-    ///
-    /// * batch_aggregations and outstanding_batches will be considered part of the same entity for
-    ///   purposes of GC, and will be considered eligible for GC once the maximum of the
-    ///   batch_interval (for time-interval) or merged client_timestamp_interval (for
-    ///   leader-selected) of the batch_aggregations rows is older than report_expiry_age.
-    /// * collection_jobs and aggregate_share_jobs use a batch mode-specific rule to determine
-    ///   GC-eligiblity.
-    ///   * For time-interval tasks, collection_jobs and aggregate_share_jobs are considered
-    ///     eligible for GC if the minimum of the collection interval is older than
-    ///     report_expiry_age. (The minimum is used instead of the maximum to ensure that collection
-    ///     jobs are not GC'ed after their underlying aggregation information from
-    ///     batch_aggregations.)
-    ///   * For leader-selected tasks, collection_jobs and aggregate_share_jobs are considered
-    ///     eligible for GC if the related batch is eligible for GC, based on the
-    ///     `batch_aggregations` rows.
-    ///
-    /// Up to `limit` batches will be deleted, along with all related collection artifacts.
-    ///
-    /// Returns the number of batches deleted.
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn delete_expired_collection_artifacts(
-        &self,
-        task_id: &TaskId,
-        limit: u64,
-    ) -> Result<u64, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok(0),
-        };
+        self.query_opt(&stmt, &[/* task_id */ &task_id.as_ref()])
+            .await?
+            .map(|row| {
+                Ok(DpBudgetUsage::new(
+                    row.get("dp_budget_spent"),
+                    row.get_bigint_and_convert("collection_count")?,
+                ))
+            })
+            .transpose()
+    }
 
+    /// put_audit_log_entry appends an entry to the audit log, recording an operator action.
+    #[tracing::instrument(skip(self, entry), fields(action = entry.action()), err(level = Level::DEBUG))]
+    pub async fn put_audit_log_entry(&self, entry: &AuditLogEntry) -> Result<(), Error> {
         let stmt = self
             .prepare_cached(
-                "-- delete_expired_collection_artifacts()
-WITH candidate_batches_to_delete AS (
-    SELECT DISTINCT batch_identifier, aggregation_param
-    FROM batch_aggregations
-    WHERE task_id = $1
-      AND UPPER(COALESCE(batch_interval, client_timestamp_interval)) < $2
-),
-batches_to_delete AS (
-    SELECT ba.batch_identifier, ba.aggregation_param
-    FROM batch_aggregations AS ba
-    JOIN candidate_batches_to_delete AS candidate
-      ON ba.batch_identifier = candidate.batch_identifier
-     AND ba.aggregation_param = candidate.aggregation_param
-    WHERE ba.task_id = $1
-    GROUP BY ba.batch_identifier, ba.aggregation_param
-    HAVING MAX(UPPER(COALESCE(ba.batch_interval, ba.client_timestamp_interval))) < $2
-    LIMIT $3
-),
-deleted_outstanding_batches AS (
-    DELETE FROM outstanding_batches
-    USING batches_to_delete
-    WHERE task_id = $1
-      AND outstanding_batches.batch_id = batches_to_delete.batch_identifier
-),
-deleted_collection_jobs AS (
-    DELETE FROM collection_jobs
-    USING batches_to_delete
-    WHERE task_id = $1
-      AND (LOWER(batch_interval) < $2
-        OR (collection_jobs.batch_identifier = batches_to_delete.batch_identifier
-          AND collection_jobs.aggregation_param = batches_to_delete.aggregation_param))
-),
-deleted_aggregate_share_jobs AS (
-    DELETE FROM aggregate_share_jobs
-    USING batches_to_delete
-    WHERE task_id = $1
-      AND (LOWER(batch_interval) < $2
-        OR (aggregate_share_jobs.batch_identifier = batches_to_delete.batch_identifier
-          AND aggregate_share_jobs.aggregation_param = batches_to_delete.aggregation_param))
-),
-deleted_batch_aggregations AS (
-    DELETE FROM batch_aggregations
-    USING batches_to_delete
-    WHERE task_id = $1
-      AND batch_aggregations.batch_identifier = batches_to_delete.batch_identifier
-      AND batch_aggregations.aggregation_param = batches_to_delete.aggregation_param
-)
-SELECT COUNT(1) AS batch_count FROM batches_to_delete",
+                "-- put_audit_log_entry()
+INSERT INTO audit_log
+    (actor, action, task_id, target, route, before_state, after_state, outcome, details,
+    created_at)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
             )
-            .await?;
-        let row = self
-            .query_one(
+            .await?;
+        check_insert(
+            self.execute(
                 &stmt,
                 &[
-                    /* task_id */ &task_info.pkey,
-                    /* threshold */
-                    &task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?,
-                    /* limit */ &i64::try_from(limit)?,
+                    /* actor */ &entry.actor(),
+                    /* action */ &entry.action(),
+                    /* task_id */ &entry.task_id().map(TaskId::get_encoded).transpose()?,
+                    /* target */ &entry.target(),
+                    /* route */ &entry.route(),
+                    /* before_state */ &entry.before(),
+                    /* after_state */ &entry.after(),
+                    /* outcome */ entry.outcome(),
+                    /* details */ entry.details(),
+                    /* created_at */ &entry.created_at().as_naive_date_time()?,
                 ],
             )
-            .await?;
-        row.get_bigint_and_convert("batch_count")
+            .await?,
+        )
     }
 
-    /// Take an ExclusiveLock on the hpke_keys table.
+    /// get_audit_log_entries retrieves the audit log entries matching the given filter, most
+    /// recent first.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn lock_hpke_keypairs(&self) -> Result<(), Error> {
-        self.raw_tx
-            .batch_execute("LOCK TABLE hpke_keys IN EXCLUSIVE MODE")
-            .await
-            .map_err(|err| err.into())
+    pub async fn get_audit_log_entries(
+        &self,
+        filter: &AuditLogFilter,
+    ) -> Result<Vec<AuditLogEntry>, Error> {
+        let stmt = self
+            .prepare_cached(
+                "-- get_audit_log_entries()
+SELECT actor, action, task_id, target, route, before_state, after_state, outcome, details,
+    created_at
+FROM audit_log
+WHERE ($1::BYTEA IS NULL OR task_id = $1)
+  AND ($2::TEXT IS NULL OR actor = $2)
+  AND ($3::TEXT IS NULL OR action = $3)
+  AND ($4::TIMESTAMP IS NULL OR created_at >= $4)
+  AND ($5::TIMESTAMP IS NULL OR created_at < $5)
+ORDER BY created_at DESC, id DESC
+LIMIT $6",
+            )
+            .await?;
+        self.query(
+            &stmt,
+            &[
+                /* task_id */ &filter.task_id().map(TaskId::get_encoded).transpose()?,
+                /* actor */ &filter.actor(),
+                /* action */ &filter.action(),
+                /* since */
+                &filter.since().map(Time::as_naive_date_time).transpose()?,
+                /* until */
+                &filter.until().map(Time::as_naive_date_time).transpose()?,
+                /* limit */
+                &filter.limit().map(i64::try_from).transpose()?,
+            ],
+        )
+        .await?
+        .into_iter()
+        .map(|row| {
+            Ok(AuditLogEntry::new(
+                row.get("actor"),
+                row.get("action"),
+                Time::from_naive_date_time(&row.get("created_at")),
+            )
+            .with_task_id(
+                row.get::<_, Option<&[u8]>>("task_id")
+                    .map(TaskId::get_decoded)
+                    .transpose()?,
+            )
+            .with_target(row.get("target"))
+            .with_route(row.get("route"))
+            .with_before(row.get("before_state"))
+            .with_after(row.get("after_state"))
+            .with_outcome(row.get("outcome"))
+            .with_details(
+                row.get::<_, Option<serde_json::Value>>("details")
+                    .unwrap_or_default(),
+            ))
+        })
+        .collect()
     }
 
-    /// Retrieve all HPKE keypairs.
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn get_hpke_keypairs(&self) -> Result<Vec<HpkeKeypair>, Error> {
+    /// put_api_token writes a new aggregator API token. Returns
+    /// [`Error::MutationTargetAlreadyExists`] if a token with the same name or hash exists, even if
+    /// it has been revoked.
+    #[tracing::instrument(skip(self, token), fields(name = token.name()), err(level = Level::DEBUG))]
+    pub async fn put_api_token(&self, token: &ApiToken) -> Result<(), Error> {
         let stmt = self
             .prepare_cached(
-                "-- get_hpke_keypairs()
-SELECT config_id, config, private_key, state, last_state_change_at FROM hpke_keys",
+                "-- put_api_token()
+INSERT INTO api_tokens (name, token_hash, scopes, task_ids, created_at, revoked_at)
+VALUES ($1, $2, $3, $4, $5, $6)
+ON CONFLICT DO NOTHING",
             )
             .await?;
-        let hpke_key_rows = self.query(&stmt, &[]).await?;
-
-        hpke_key_rows
-            .iter()
-            .map(|row| self.hpke_keypair_from_row(row))
-            .collect()
+        check_insert(
+            self.execute(
+                &stmt,
+                &[
+                    /* name */ &token.name(),
+                    /* token_hash */ &token.token_hash().as_ref(),
+                    /* scopes */ &token.scopes(),
+                    /* task_ids */
+                    &token
+                        .task_ids()
+                        .map(|task_ids| {
+                            task_ids
+                                .iter()
+                                .map(TaskId::get_encoded)
+                                .collect::<Result<Vec<_>, _>>()
+                        })
+                        .transpose()?,
+                    /* created_at */ &token.created_at().as_naive_date_time()?,
+                    /* revoked_at */
+                    &token
+                        .revoked_at()
+                        .map(Time::as_naive_date_time)
+                        .transpose()?,
+                ],
+            )
+            .await?,
+        )
     }
 
-    /// Retrieve an HPKE keypair by config ID.
+    /// get_api_token retrieves the aggregator API token with the given name, whether or not it has
+    /// been revoked.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn get_hpke_keypair(
-        &self,
-        config_id: &HpkeConfigId,
-    ) -> Result<Option<HpkeKeypair>, Error> {
+    pub async fn get_api_token(&self, name: &str) -> Result<Option<ApiToken>, Error> {
         let stmt = self
             .prepare_cached(
-                "-- get_hpke_keypair()
-SELECT config_id, config, private_key, state, last_state_change_at FROM hpke_keys
-    WHERE config_id = $1",
+                "-- get_api_token()
+SELECT name, token_hash, scopes, task_ids, created_at, revoked_at
+FROM api_tokens WHERE name = $1",
             )
             .await?;
-        self.query_opt(&stmt, &[&(u8::from(*config_id) as i16)])
+        self.query_opt(&stmt, &[/* name */ &name])
             .await?
-            .map(|row| self.hpke_keypair_from_row(&row))
+            .map(|row| Self::api_token_from_row(&row))
             .transpose()
     }
 
-    fn hpke_keypair_from_row(&self, row: &Row) -> Result<HpkeKeypair, Error> {
-        let config = HpkeConfig::get_decoded(row.get("config"))?;
-        let config_id = u8::try_from(row.get::<_, i16>("config_id"))?;
-
-        let encrypted_private_key: Vec<u8> = row.get("private_key");
-        let private_key = HpkePrivateKey::new(self.crypter.decrypt(
-            "hpke_keys",
-            &config_id.to_be_bytes(),
-            "private_key",
-            &encrypted_private_key,
-        )?);
-        Ok(HpkeKeypair::new(
-            hpke::HpkeKeypair::new(config, private_key),
-            row.get("state"),
-            Time::from_naive_date_time(&row.get("last_state_change_at")),
-        ))
+    /// get_api_token_by_hash retrieves the unrevoked aggregator API token with the given hash, if
+    /// any. This is used to authenticate requests to the aggregator API.
+    #[tracing::instrument(skip(self, token_hash), err(level = Level::DEBUG))]
+    pub async fn get_api_token_by_hash(
+        &self,
+        token_hash: &AuthenticationTokenHash,
+    ) -> Result<Option<ApiToken>, Error> {
+        let stmt = self
+            .prepare_cached(
+                "-- get_api_token_by_hash()
+SELECT name, token_hash, scopes, task_ids, created_at, revoked_at
+FROM api_tokens WHERE token_hash = $1 AND revoked_at IS NULL",
+            )
+            .await?;
+        self.query_opt(&stmt, &[/* token_hash */ &token_hash.as_ref()])
+            .await?
+            .map(|row| Self::api_token_from_row(&row))
+            .transpose()
     }
 
-    /// Unconditionally and fully drop a keypair. This is a dangerous operation, since report shares
-    /// encrypted with this key will no longer be decryptable.
+    /// get_api_tokens retrieves all aggregator API tokens, including revoked ones, ordered by name.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn delete_hpke_keypair(&self, config_id: &HpkeConfigId) -> Result<(), Error> {
+    pub async fn get_api_tokens(&self) -> Result<Vec<ApiToken>, Error> {
         let stmt = self
             .prepare_cached(
-                "-- delete_hpke_keypair()
-DELETE FROM hpke_keys WHERE config_id = $1",
+                "-- get_api_tokens()
+SELECT name, token_hash, scopes, task_ids, created_at, revoked_at
+FROM api_tokens ORDER BY name",
             )
             .await?;
-        check_single_row_mutation(
-            self.execute(&stmt, &[&(u8::from(*config_id) as i16)])
-                .await?,
+        self.query(&stmt, &[])
+            .await?
+            .iter()
+            .map(Self::api_token_from_row)
+            .collect()
+    }
+
+    fn api_token_from_row(row: &Row) -> Result<ApiToken, Error> {
+        Ok(ApiToken::new(
+            row.get("name"),
+            AuthenticationTokenType::AuthorizationBearerToken
+                .as_authentication_token_hash(row.get("token_hash"))?,
+            row.get("scopes"),
+            row.get::<_, Option<Vec<&[u8]>>>("task_ids")
+                .map(|task_ids| {
+                    task_ids
+                        .into_iter()
+                        .map(TaskId::get_decoded)
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()?,
+            Time::from_naive_date_time(&row.get("created_at")),
         )
+        .with_revoked_at(
+            row.get::<_, Option<NaiveDateTime>>("revoked_at")
+                .as_ref()
+                .map(Time::from_naive_date_time),
+        ))
     }
 
+    /// revoke_api_token revokes the unrevoked aggregator API token with the given name, so that it
+    /// can no longer be used. Returns [`Error::MutationTargetNotFound`] if there is no such token.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn set_hpke_keypair_state(
-        &self,
-        config_id: &HpkeConfigId,
-        state: &HpkeKeyState,
-    ) -> Result<(), Error> {
+    pub async fn revoke_api_token(&self, name: &str, revoked_at: &Time) -> Result<(), Error> {
         let stmt = self
             .prepare_cached(
-                "-- set_hpke_keypair_state()
-UPDATE hpke_keys
-    SET state = $1, last_state_change_at = $2, updated_at = $3, updated_by = $4
-    WHERE config_id = $5",
+                "-- revoke_api_token()
+UPDATE api_tokens SET revoked_at = $2 WHERE name = $1 AND revoked_at IS NULL",
             )
             .await?;
-        let now = self.clock.now().as_naive_date_time()?;
         check_single_row_mutation(
             self.execute(
                 &stmt,
                 &[
-                    /* state */ state,
-                    /* last_state_change_at */ &now,
-                    /* updated_at */ &now,
-                    /* updated_by */ &self.name,
-                    /* config_id */ &(u8::from(*config_id) as i16),
+                    /* name */ &name,
+                    /* revoked_at */ &revoked_at.as_naive_date_time()?,
                 ],
             )
             .await?,
         )
     }
 
-    /// Inserts a new HPKE keypair and places it in the [`HpkeKeyState::Pending`] state.
+    /// Exports the state of the given task, for import into another datastore via
+    /// [`Self::import_task`]. The archive includes all HPKE keypairs, along with the task's
+    /// outstanding batches, batch aggregations, collection jobs & aggregate share jobs.
+    ///
+    /// Every unexpired client report is included, so that its report ID remains protected against
+    /// replay once imported. The payloads of unaggregated reports are included only if
+    /// `include_unaggregated_reports` is set; otherwise, those reports will never be aggregated
+    /// after import.
+    ///
+    /// Aggregation jobs are not exported, so the export fails if the task has any unexpired
+    /// aggregation jobs which have not yet reached a terminal state. Returns `None` if the task
+    /// does not exist.
     #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn put_hpke_keypair(&self, hpke_keypair: &hpke::HpkeKeypair) -> Result<(), Error> {
-        let hpke_config_id = u8::from(*hpke_keypair.config().id()) as i16;
-        let hpke_config = hpke_keypair.config().get_encoded()?;
-        let encrypted_hpke_private_key = self.crypter.encrypt(
-            "hpke_keys",
-            &u8::from(*hpke_keypair.config().id()).to_be_bytes(),
-            "private_key",
-            hpke_keypair.private_key().as_ref(),
-        )?;
+    pub async fn export_task(
+        &self,
+        task_id: &TaskId,
+        include_unaggregated_reports: bool,
+    ) -> Result<Option<TaskArchive>, Error> {
+        let task = match self.get_aggregator_task(task_id).await? {
+            Some(task) => task,
+            None => return Ok(None),
+        };
+        if task.collector_hpke_config().is_none() {
+            return Err(Error::InvalidParameter(
+                "tasks provisioned via taskprov cannot be exported",
+            ));
+        }
+        let task_info = self
+            .task_info_for(task_id)
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?;
+        let threshold =
+            task_info.report_expiry_threshold(&self.clock.now().as_naive_date_time()?)?;
 
         let stmt = self
             .prepare_cached(
-                "-- put_hpke_keypair()
-INSERT INTO hpke_keys
-    (config_id, config, private_key, last_state_change_at, created_at, updated_at, updated_by)
-    VALUES ($1, $2, $3, $4, $5, $6, $7)",
+                "-- export_task()
+SELECT COUNT(*) AS count FROM aggregation_jobs
+WHERE task_id = $1
+  AND state IN ('ACTIVE', 'AWAITING_REQUEST')
+  AND UPPER(client_timestamp_interval) >= $2",
             )
             .await?;
-        let now = self.clock.now().as_naive_date_time()?;
-        check_insert(
-            self.execute(
+        let in_progress_aggregation_jobs: u64 = self
+            .query_one(
                 &stmt,
                 &[
-                    /* config_id */ &hpke_config_id,
-                    /* config */ &hpke_config,
-                    /* private_key */ &encrypted_hpke_private_key,
-                    /* last_state_change_at */ &now,
-                    /* created_at */ &now,
-                    /* updated_at */ &now,
-                    /* updated_by */ &self.name,
+                    /* task_id */ &task_info.pkey,
+                    /* threshold */ &threshold,
                 ],
             )
-            .await?,
-        )
-    }
+            .await?
+            .get_bigint_and_convert("count")?;
+        if in_progress_aggregation_jobs > 0 {
+            return Err(Error::User(
+                format!(
+                    "task has {in_progress_aggregation_jobs} aggregation jobs in progress, which \
+                     must finish before the task can be exported"
+                )
+                .into(),
+            ));
+        }
 
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn get_taskprov_peer_aggregators(&self) -> Result<Vec<PeerAggregator>, Error> {
-        let stmt = self
-            .prepare_cached(
-                "-- get_taskprov_peer_aggregators()
-SELECT id, endpoint, peer_role, aggregation_mode, verify_key_init, collector_hpke_config,
-        report_expiry_age
-    FROM taskprov_peer_aggregators",
-            )
-            .await?;
-        let peer_aggregator_rows = self.query(&stmt, &[]);
+        let hpke_keypairs = self
+            .get_hpke_keypairs()
+            .await?
+            .into_iter()
+            .map(|keypair| ArchivedHpkeKeypair {
+                keypair: keypair.hpke_keypair().clone(),
+                state: *keypair.state(),
+                last_state_change_at: *keypair.last_state_change_at(),
+            })
+            .collect();
 
         let stmt = self
             .prepare_cached(
-                "-- get_taskprov_peer_aggregators()
-SELECT (SELECT p.id FROM taskprov_peer_aggregators AS p
-    WHERE p.id = a.peer_aggregator_id) AS peer_id,
-ord, type, token FROM taskprov_aggregator_auth_tokens AS a
-    ORDER BY ord ASC",
+                "-- export_task()
+SELECT
+    report_id, client_timestamp, public_extensions, public_share,
+    leader_private_extensions, leader_input_share, helper_encrypted_input_share,
+    aggregation_started
+FROM client_reports
+WHERE task_id = $1
+  AND client_timestamp >= $2",
             )
             .await?;
-        let aggregator_auth_token_rows = self.query(&stmt, &[]);
+        let client_reports = self
+            .query(
+                &stmt,
+                &[
+                    /* task_id */ &task_info.pkey,
+                    /* threshold */ &threshold,
+                ],
+            )
+            .await?
+            .into_iter()
+            .map(|row| {
+                let payload =
+                    if include_unaggregated_reports && !row.get::<_, bool>("aggregation_started") {
+                        row.get::<_, Option<Vec<u8>>>("leader_input_share")
+                            .map(|leader_input_share| {
+                                Ok::<_, Error>(ArchivedClientReportPayload {
+                                    public_extensions: row.try_get("public_extensions")?,
+                                    public_share: row.try_get("public_share")?,
+                                    leader_private_extensions: row
+                                        .try_get("leader_private_extensions")?,
+                                    leader_input_share,
+                                    helper_encrypted_input_share: row
+                                        .try_get("helper_encrypted_input_share")?,
+                                })
+                            })
+                            .transpose()?
+                    } else {
+                        None
+                    };
+                Ok(ArchivedClientReport {
+                    report_id: row.get("report_id"),
+                    client_timestamp: Time::from_naive_date_time(&row.get("client_timestamp")),
+                    payload,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
 
         let stmt = self
             .prepare_cached(
-                "-- get_taskprov_peer_aggregators()
-SELECT (SELECT p.id FROM taskprov_peer_aggregators AS p
-    WHERE p.id = a.peer_aggregator_id) AS peer_id,
-ord, type, token FROM taskprov_collector_auth_tokens AS a
-    ORDER BY ord ASC",
+                "-- export_task()
+SELECT batch_id, time_bucket_start, state::TEXT AS state
+FROM outstanding_batches
+WHERE task_id = $1",
             )
             .await?;
-        let collector_auth_token_rows = self.query(&stmt, &[]);
-
-        let (peer_aggregator_rows, aggregator_auth_token_rows, collector_auth_token_rows) = try_join!(
-            peer_aggregator_rows,
-            aggregator_auth_token_rows,
-            collector_auth_token_rows,
-        )?;
-
-        let mut aggregator_auth_token_rows_by_peer_id: HashMap<i64, Vec<Row>> = HashMap::new();
-        for row in aggregator_auth_token_rows {
-            aggregator_auth_token_rows_by_peer_id
-                .entry(row.get("peer_id"))
-                .or_default()
-                .push(row);
-        }
-
-        let mut collector_auth_token_rows_by_peer_id: HashMap<i64, Vec<Row>> = HashMap::new();
-        for row in collector_auth_token_rows {
-            collector_auth_token_rows_by_peer_id
-                .entry(row.get("peer_id"))
-                .or_default()
-                .push(row);
-        }
-
-        peer_aggregator_rows
+        let outstanding_batches = self
+            .query(&stmt, &[/* task_id */ &task_info.pkey])
+            .await?
             .into_iter()
-            .map(|row| (row.get("id"), row))
-            .map(|(peer_id, peer_aggregator_row)| {
-                self.taskprov_peer_aggregator_from_rows(
-                    &peer_aggregator_row,
-                    &aggregator_auth_token_rows_by_peer_id
-                        .remove(&peer_id)
-                        .unwrap_or_default(),
-                    &collector_auth_token_rows_by_peer_id
-                        .remove(&peer_id)
-                        .unwrap_or_default(),
-                )
+            .map(|row| ArchivedOutstandingBatch {
+                batch_id: row.get("batch_id"),
+                time_bucket_start: row
+                    .get::<_, Option<NaiveDateTime>>("time_bucket_start")
+                    .as_ref()
+                    .map(Time::from_naive_date_time),
+                state: row.get("state"),
             })
-            .collect()
-    }
-
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn get_taskprov_peer_aggregator(
-        &self,
-        aggregator_url: &Url,
-        role: &Role,
-    ) -> Result<Option<PeerAggregator>, Error> {
-        let aggregator_url = aggregator_url.as_str();
-        let role = AggregatorRole::from_role(*role)?;
-        let params: &[&(dyn ToSql + Sync)] = &[&aggregator_url, &role];
+            .collect();
 
         let stmt = self
             .prepare_cached(
-                "-- get_taskprov_peer_aggregator()
-SELECT endpoint, peer_role, aggregation_mode, verify_key_init, collector_hpke_config,
-        report_expiry_age
-    FROM taskprov_peer_aggregators WHERE endpoint = $1 AND peer_role = $2",
+                "-- export_task()
+SELECT
+    batch_identifier, batch_interval, aggregation_param, ord,
+    client_timestamp_interval, state::TEXT AS state, aggregate_share, report_count,
+    checksum, aggregation_jobs_created, aggregation_jobs_terminated
+FROM batch_aggregations
+WHERE task_id = $1",
             )
             .await?;
-        let peer_aggregator_row = self.query_opt(&stmt, params);
+        let batch_aggregations = self
+            .query(&stmt, &[/* task_id */ &task_info.pkey])
+            .await?
+            .into_iter()
+            .map(|row| ArchivedBatchAggregation {
+                batch_identifier: row.get("batch_identifier"),
+                batch_interval: row
+                    .get::<_, Option<SqlInterval>>("batch_interval")
+                    .map(|interval| interval.as_interval().into()),
+                aggregation_param: row.get("aggregation_param"),
+                ord: row.get("ord"),
+                client_timestamp_interval: row
+                    .get::<_, SqlInterval>("client_timestamp_interval")
+                    .as_interval()
+                    .into(),
+                state: row.get("state"),
+                aggregate_share: row.get("aggregate_share"),
+                report_count: row.get("report_count"),
+                checksum: row.get("checksum"),
+                aggregation_jobs_created: row.get("aggregation_jobs_created"),
+                aggregation_jobs_terminated: row.get("aggregation_jobs_terminated"),
+            })
+            .collect();
 
         let stmt = self
             .prepare_cached(
-                "-- get_taskprov_peer_aggregator()
-SELECT ord, type, token FROM taskprov_aggregator_auth_tokens
-    WHERE peer_aggregator_id = (SELECT id FROM taskprov_peer_aggregators
-        WHERE endpoint = $1 AND peer_role = $2)
-    ORDER BY ord ASC",
+                "-- export_task()
+SELECT
+    collection_job_id, query, aggregation_param, batch_identifier, batch_interval,
+    state::TEXT AS state, report_count, client_timestamp_interval,
+    helper_aggregate_share, leader_aggregate_share, step_attempts
+FROM collection_jobs
+WHERE task_id = $1",
             )
             .await?;
-        let aggregator_auth_token_rows = self.query(&stmt, params);
+        let collection_jobs = self
+            .query(&stmt, &[/* task_id */ &task_info.pkey])
+            .await?
+            .into_iter()
+            .map(|row| ArchivedCollectionJob {
+                collection_job_id: row.get("collection_job_id"),
+                query: row.get("query"),
+                aggregation_param: row.get("aggregation_param"),
+                batch_identifier: row.get("batch_identifier"),
+                batch_interval: row
+                    .get::<_, Option<SqlInterval>>("batch_interval")
+                    .map(|interval| interval.as_interval().into()),
+                state: row.get("state"),
+                report_count: row.get("report_count"),
+                client_timestamp_interval: row
+                    .get::<_, Option<SqlInterval>>("client_timestamp_interval")
+                    .map(|interval| interval.as_interval().into()),
+                helper_aggregate_share: row.get("helper_aggregate_share"),
+                leader_aggregate_share: row.get("leader_aggregate_share"),
+                step_attempts: row.get("step_attempts"),
+            })
+            .collect();
 
         let stmt = self
             .prepare_cached(
-                "-- get_taskprov_peer_aggregator()
-SELECT ord, type, token FROM taskprov_collector_auth_tokens
-    WHERE peer_aggregator_id = (SELECT id FROM taskprov_peer_aggregators
-        WHERE endpoint = $1 AND peer_role = $2)
-    ORDER BY ord ASC",
+                "-- export_task()
+SELECT
+    batch_identifier, batch_interval, aggregation_param, helper_aggregate_share,
+    report_count, checksum
+FROM aggregate_share_jobs
+WHERE task_id = $1",
             )
             .await?;
-        let collector_auth_token_rows = self.query(&stmt, params);
+        let aggregate_share_jobs = self
+            .query(&stmt, &[/* task_id */ &task_info.pkey])
+            .await?
+            .into_iter()
+            .map(|row| ArchivedAggregateShareJob {
+                batch_identifier: row.get("batch_identifier"),
+                batch_interval: row
+                    .get::<_, Option<SqlInterval>>("batch_interval")
+                    .map(|interval| interval.as_interval().into()),
+                aggregation_param: row.get("aggregation_param"),
+                helper_aggregate_share: row.get("helper_aggregate_share"),
+                report_count: row.get("report_count"),
+                checksum: row.get("checksum"),
+            })
+            .collect();
 
-        let (peer_aggregator_row, aggregator_auth_token_rows, collector_auth_token_rows) = try_join!(
-            peer_aggregator_row,
-            aggregator_auth_token_rows,
-            collector_auth_token_rows,
-        )?;
-        peer_aggregator_row
-            .map(|peer_aggregator_row| {
-                self.taskprov_peer_aggregator_from_rows(
-                    &peer_aggregator_row,
-                    &aggregator_auth_token_rows,
-                    &collector_auth_token_rows,
-                )
+        let stmt = self
+            .prepare_cached(
+                "-- export_task()
+SELECT batch_identifier, aggregation_param, privacy_loss
+FROM dp_budget_ledger
+WHERE task_id = $1",
+            )
+            .await?;
+        let dp_budget_ledger = self
+            .query(&stmt, &[/* task_id */ &task_info.pkey])
+            .await?
+            .into_iter()
+            .map(|row| ArchivedDpBudgetLedgerEntry {
+                batch_identifier: row.get("batch_identifier"),
+                aggregation_param: row.get("aggregation_param"),
+                privacy_loss: row.get("privacy_loss"),
             })
-            .transpose()
+            .collect();
+
+        Ok(Some(TaskArchive {
+            task,
+            hpke_keypairs,
+            client_reports,
+            outstanding_batches,
+            batch_aggregations,
+            collection_jobs,
+            aggregate_share_jobs,
+            dp_budget_ledger,
+        }))
     }
 
-    fn taskprov_peer_aggregator_from_rows(
-        &self,
-        peer_aggregator_row: &Row,
-        aggregator_auth_token_rows: &[Row],
-        collector_auth_token_rows: &[Row],
-    ) -> Result<PeerAggregator, Error> {
-        let endpoint = Url::parse(peer_aggregator_row.get::<_, &str>("endpoint"))?;
-        let endpoint_bytes = endpoint.as_str().as_ref();
-        let peer_role: AggregatorRole = peer_aggregator_row.get("peer_role");
-        let aggregation_mode: Option<AggregationMode> = peer_aggregator_row.get("aggregation_mode");
-        let report_expiry_age = peer_aggregator_row
-            .get_nullable_bigint_and_convert("report_expiry_age")?
-            .map(Duration::from_seconds);
-        let collector_hpke_config =
-            HpkeConfig::get_decoded(peer_aggregator_row.get("collector_hpke_config"))?;
-
-        let encrypted_verify_key_init: Vec<u8> = peer_aggregator_row.get("verify_key_init");
-        let verify_key_init = self
-            .crypter
-            .decrypt(
-                "taskprov_peer_aggregator",
-                endpoint_bytes,
-                "verify_key_init",
-                &encrypted_verify_key_init,
-            )?
-            .as_slice()
-            .try_into()?;
-
-        let decrypt_tokens = |rows: &[Row], table| -> Result<Vec<_>, Error> {
-            rows.iter()
-                .map(|row| {
-                    let ord: i64 = row.get("ord");
-                    let auth_token_type: AuthenticationTokenType = row.get("type");
-                    let encrypted_token: Vec<u8> = row.get("token");
-
-                    let mut row_id = Vec::new();
-                    row_id.extend_from_slice(endpoint_bytes);
-                    row_id.extend_from_slice(&peer_role.as_role().get_encoded()?);
-                    row_id.extend_from_slice(&ord.to_be_bytes());
-
-                    auth_token_type.as_authentication(&self.crypter.decrypt(
-                        table,
-                        &row_id,
-                        "token",
-                        &encrypted_token,
-                    )?)
-                })
-                .collect()
-        };
-
-        let aggregator_auth_tokens = decrypt_tokens(
-            aggregator_auth_token_rows,
-            "taskprov_aggregator_auth_tokens",
-        )?;
-        let collector_auth_tokens =
-            decrypt_tokens(collector_auth_token_rows, "taskprov_collector_auth_tokens")?;
-
-        PeerAggregator::new(
-            endpoint,
-            peer_role.as_role(),
-            aggregation_mode,
-            verify_key_init,
-            collector_hpke_config,
-            report_expiry_age,
-            aggregator_auth_tokens,
-            collector_auth_tokens,
-        )
-        .map_err(|e| Error::User(e.into()))
-    }
+    /// Imports a task exported via [`Self::export_task`], preserving its task ID. Fails with
+    /// [`Error::MutationTargetAlreadyExists`] if the task already exists.
+    ///
+    /// HPKE keypairs which are not already present are inserted in their exported state; a
+    /// keypair whose config ID is in use by a different keypair causes the import to fail. Secret
+    /// values are encrypted under this datastore's crypter, and any leases held on collection jobs
+    /// are dropped.
+    #[tracing::instrument(skip(self, archive), fields(task_id = ?archive.task().id()), err(level = Level::DEBUG))]
+    pub async fn import_task(&self, archive: &TaskArchive) -> Result<(), Error> {
+        self.put_aggregator_task(&archive.task).await?;
+        // Any cached information about the task predates its deletion from this datastore, and is
+        // therefore stale.
+        {
+            // unwrap safety: mutex poisoning
+            self.task_infos.lock().unwrap().remove(archive.task.id());
+        }
+        let task_info = self
+            .task_info_for(archive.task.id())
+            .await?
+            .ok_or(Error::MutationTargetNotFound)?;
+        let now = self.clock.now().as_naive_date_time()?;
 
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn put_taskprov_peer_aggregator(
-        &self,
-        peer_aggregator: &PeerAggregator,
-    ) -> Result<(), Error> {
-        let endpoint = peer_aggregator.endpoint().as_str();
-        let peer_role = &AggregatorRole::from_role(*peer_aggregator.peer_role())?;
-        let encrypted_verify_key_init = self.crypter.encrypt(
-            "taskprov_peer_aggregator",
-            endpoint.as_ref(),
-            "verify_key_init",
-            peer_aggregator.verify_key_init().as_ref(),
-        )?;
+        let hpke_keypair_stmt = self
+            .prepare_cached(
+                "-- import_task()
+UPDATE hpke_keys SET state = $1, last_state_change_at = $2 WHERE config_id = $3",
+            )
+            .await?;
+        for keypair in &archive.hpke_keypairs {
+            let config_id = keypair.keypair.config().id();
+            match self.get_hpke_keypair(config_id).await? {
+                Some(existing) if existing.hpke_keypair() == &keypair.keypair => (),
+                Some(_) => {
+                    return Err(Error::DbState(format!(
+                        "HPKE config ID {config_id} is already in use by a different keypair"
+                    )));
+                }
+                None => {
+                    self.put_hpke_keypair(&keypair.keypair).await?;
+                    check_single_row_mutation(
+                        self.execute(
+                            &hpke_keypair_stmt,
+                            &[
+                                /* state */ &keypair.state,
+                                /* last_state_change_at */
+                                &keypair.last_state_change_at.as_naive_date_time()?,
+                                /* config_id */ &(u8::from(*config_id) as i16),
+                            ],
+                        )
+                        .await?,
+                    )?;
+                }
+            }
+        }
 
         let stmt = self
             .prepare_cached(
-                "-- put_taskprov_peer_aggregator()
-INSERT INTO taskprov_peer_aggregators (
-    endpoint, peer_role, aggregation_mode, verify_key_init, report_expiry_age,
-    collector_hpke_config, created_at, updated_by
-) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-ON CONFLICT DO NOTHING",
+                "-- import_task()
+WITH client_report_id AS (
+    INSERT INTO client_report_ids (task_id, report_id, client_timestamp)
+    VALUES ($1, $2, $3)
+)
+INSERT INTO client_reports (
+    task_id, report_id, client_timestamp, public_extensions, public_share,
+    leader_private_extensions, leader_input_share, helper_encrypted_input_share,
+    aggregation_started, created_at, updated_at, updated_by
+)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
             )
             .await?;
-        check_insert(
-            self.execute(
-                &stmt,
-                &[
-                    /* endpoint */ &endpoint,
-                    /* peer_role */ peer_role,
-                    /* aggregation_mode */ &peer_aggregator.aggregation_mode(),
-                    /* verify_key_init */ &encrypted_verify_key_init,
-                    /* report_expiry_age */
-                    &peer_aggregator
-                        .report_expiry_age()
-                        .map(Duration::as_seconds)
-                        .map(i64::try_from)
-                        .transpose()?,
-                    /* collector_hpke_config */
-                    &peer_aggregator.collector_hpke_config().get_encoded()?,
-                    /* created_at */ &self.clock.now().as_naive_date_time()?,
-                    /* updated_by */
-                    &self.name,
-                ],
-            )
-            .await?,
-        )?;
-
-        let encrypt_tokens = |tokens: &[AuthenticationToken], table| -> Result<_, Error> {
-            let mut ords = Vec::new();
-            let mut types = Vec::new();
-            let mut encrypted_tokens = Vec::new();
-            for (ord, token) in tokens.iter().enumerate() {
-                let ord = i64::try_from(ord)?;
-
-                let mut row_id = Vec::new();
-                row_id.extend_from_slice(endpoint.as_ref());
-                row_id.extend_from_slice(&peer_role.as_role().get_encoded()?);
-                row_id.extend_from_slice(&ord.to_be_bytes());
-
-                let encrypted_auth_token =
-                    self.crypter
-                        .encrypt(table, &row_id, "token", token.as_ref())?;
-
-                ords.push(ord);
-                types.push(AuthenticationTokenType::from(token));
-                encrypted_tokens.push(encrypted_auth_token);
+        try_join_all(archive.client_reports.iter().map(|report| {
+            let stmt = &stmt;
+            async move {
+                let payload = report.payload.as_ref();
+                check_insert(
+                    self.execute(
+                        stmt,
+                        &[
+                            /* task_id */ &task_info.pkey,
+                            /* report_id */ &report.report_id,
+                            /* client_timestamp */
+                            &report.client_timestamp.as_naive_date_time()?,
+                            /* public_extensions */
+                            &payload.map(|payload| &payload.public_extensions),
+                            /* public_share */ &payload.map(|payload| &payload.public_share),
+                            /* leader_private_extensions */
+                            &payload.map(|payload| &payload.leader_private_extensions),
+                            /* leader_input_share */
+                            &payload.map(|payload| &payload.leader_input_share),
+                            /* helper_encrypted_input_share */
+                            &payload.map(|payload| &payload.helper_encrypted_input_share),
+                            /* aggregation_started */ &payload.is_none(),
+                            /* created_at */ &now,
+                            /* updated_at */ &now,
+                            /* updated_by */ &self.name,
+                        ],
+                    )
+                    .await?,
+                )
             }
-            Ok((ords, types, encrypted_tokens))
-        };
+        }))
+        .await?;
 
-        let (aggregator_auth_token_ords, aggregator_auth_token_types, aggregator_auth_tokens) =
-            encrypt_tokens(
-                peer_aggregator.aggregator_auth_tokens(),
-                "taskprov_aggregator_auth_tokens",
-            )?;
         let stmt = self
             .prepare_cached(
-                "-- put_taskprov_peer_aggregator()
-INSERT INTO taskprov_aggregator_auth_tokens (
-    peer_aggregator_id, created_at, updated_by, ord, type, token
+                "-- import_task()
+INSERT INTO outstanding_batches (
+    task_id, batch_id, time_bucket_start, state, created_at, updated_by
 )
-SELECT
-    (SELECT id FROM taskprov_peer_aggregators WHERE endpoint = $1 AND peer_role = $2),
-    $3, $4, * FROM UNNEST($5::BIGINT[], $6::AUTH_TOKEN_TYPE[], $7::BYTEA[])",
+VALUES ($1, $2, $3, $4::TEXT::OUTSTANDING_BATCH_STATE, $5, $6)",
             )
             .await?;
-        let aggregator_auth_tokens_params: &[&(dyn ToSql + Sync)] = &[
-            /* endpoint */ &endpoint,
-            /* peer_role */ peer_role,
-            /* created_at */ &self.clock.now().as_naive_date_time()?,
-            /* updated_by */ &self.name,
-            /* ords */ &aggregator_auth_token_ords,
-            /* token_types */ &aggregator_auth_token_types,
-            /* tokens */ &aggregator_auth_tokens,
-        ];
-        let aggregator_auth_tokens_future = self.execute(&stmt, aggregator_auth_tokens_params);
+        try_join_all(archive.outstanding_batches.iter().map(|batch| {
+            let stmt = &stmt;
+            async move {
+                check_insert(
+                    self.execute(
+                        stmt,
+                        &[
+                            /* task_id */ &task_info.pkey,
+                            /* batch_id */ &batch.batch_id,
+                            /* time_bucket_start */
+                            &batch
+                                .time_bucket_start
+                                .map(|time| time.as_naive_date_time())
+                                .transpose()?,
+                            /* state */ &batch.state,
+                            /* created_at */ &now,
+                            /* updated_by */ &self.name,
+                        ],
+                    )
+                    .await?,
+                )
+            }
+        }))
+        .await?;
 
-        let (collector_auth_token_ords, collector_auth_token_types, collector_auth_tokens) =
-            encrypt_tokens(
-                peer_aggregator.collector_auth_tokens(),
-                "taskprov_collector_auth_tokens",
-            )?;
         let stmt = self
             .prepare_cached(
-                "-- put_taskprov_peer_aggregator()
-INSERT INTO taskprov_collector_auth_tokens (
-    peer_aggregator_id, created_at, updated_by, ord,  type, token
+                "-- import_task()
+INSERT INTO batch_aggregations (
+    task_id, batch_identifier, batch_interval, aggregation_param, ord,
+    client_timestamp_interval, state, aggregate_share, report_count, checksum,
+    aggregation_jobs_created, aggregation_jobs_terminated, created_at, updated_at,
+    updated_by
 )
-SELECT
-    (SELECT id FROM taskprov_peer_aggregators WHERE endpoint = $1 AND peer_role = $2),
-    $3, $4, * FROM UNNEST($5::BIGINT[], $6::AUTH_TOKEN_TYPE[], $7::BYTEA[])",
+VALUES (
+    $1, $2, $3, $4, $5, $6, $7::TEXT::BATCH_AGGREGATION_STATE, $8, $9, $10, $11, $12, $13,
+    $14, $15
+)",
             )
             .await?;
-        let collector_auth_tokens_params: &[&(dyn ToSql + Sync)] = &[
-            /* endpoint */ &endpoint,
-            /* peer_role */ peer_role,
-            /* created_at */ &self.clock.now().as_naive_date_time()?,
-            /* updated_by */ &self.name,
-            /* ords */ &collector_auth_token_ords,
-            /* token_types */ &collector_auth_token_types,
-            /* tokens */ &collector_auth_tokens,
-        ];
-        let collector_auth_tokens_future = self.execute(&stmt, collector_auth_tokens_params);
-
-        try_join!(aggregator_auth_tokens_future, collector_auth_tokens_future)?;
-        Ok(())
-    }
-
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn delete_taskprov_peer_aggregator(
-        &self,
-        aggregator_url: &Url,
-        peer_role: &Role,
-    ) -> Result<(), Error> {
-        let aggregator_url = aggregator_url.as_str();
-        let peer_role = AggregatorRole::from_role(*peer_role)?;
+        try_join_all(archive.batch_aggregations.iter().map(|batch_aggregation| {
+            let stmt = &stmt;
+            async move {
+                check_insert(
+                    self.execute(
+                        stmt,
+                        &[
+                            /* task_id */ &task_info.pkey,
+                            /* batch_identifier */ &batch_aggregation.batch_identifier,
+                            /* batch_interval */
+                            &batch_aggregation
+                                .batch_interval
+                                .map(|interval| Interval::try_from(interval).map(SqlInterval::from))
+                                .transpose()?,
+                            /* aggregation_param */ &batch_aggregation.aggregation_param,
+                            /* ord */ &batch_aggregation.ord,
+                            /* client_timestamp_interval */
+                            &SqlInterval::from(Interval::try_from(
+                                batch_aggregation.client_timestamp_interval,
+                            )?),
+                            /* state */ &batch_aggregation.state,
+                            /* aggregate_share */ &batch_aggregation.aggregate_share,
+                            /* report_count */ &batch_aggregation.report_count,
+                            /* checksum */ &batch_aggregation.checksum,
+                            /* aggregation_jobs_created */
+                            &batch_aggregation.aggregation_jobs_created,
+                            /* aggregation_jobs_terminated */
+                            &batch_aggregation.aggregation_jobs_terminated,
+                            /* created_at */ &now,
+                            /* updated_at */ &now,
+                            /* updated_by */ &self.name,
+                        ],
+                    )
+                    .await?,
+                )
+            }
+        }))
+        .await?;
 
-        // Deletion of other data implemented via ON DELETE CASCADE.
         let stmt = self
             .prepare_cached(
-                "-- delete_taskprov_peer_aggregator()
-DELETE FROM taskprov_peer_aggregators WHERE endpoint = $1 AND peer_role = $2",
+                "-- import_task()
+INSERT INTO collection_jobs (
+    task_id, collection_job_id, query, aggregation_param, batch_identifier,
+    batch_interval, state, report_count, client_timestamp_interval,
+    helper_aggregate_share, leader_aggregate_share, step_attempts, created_at,
+    updated_at, updated_by
+)
+VALUES (
+    $1, $2, $3, $4, $5, $6, $7::TEXT::COLLECTION_JOB_STATE, $8, $9, $10, $11, $12, $13,
+    $14, $15
+)",
             )
             .await?;
-        check_single_row_mutation(self.execute(&stmt, &[&aggregator_url, &peer_role]).await?)
-    }
+        try_join_all(archive.collection_jobs.iter().map(|collection_job| {
+            let stmt = &stmt;
+            async move {
+                check_insert(
+                    self.execute(
+                        stmt,
+                        &[
+                            /* task_id */ &task_info.pkey,
+                            /* collection_job_id */ &collection_job.collection_job_id,
+                            /* query */ &collection_job.query,
+                            /* aggregation_param */ &collection_job.aggregation_param,
+                            /* batch_identifier */ &collection_job.batch_identifier,
+                            /* batch_interval */
+                            &collection_job
+                                .batch_interval
+                                .map(|interval| Interval::try_from(interval).map(SqlInterval::from))
+                                .transpose()?,
+                            /* state */ &collection_job.state,
+                            /* report_count */ &collection_job.report_count,
+                            /* client_timestamp_interval */
+                            &collection_job
+                                .client_timestamp_interval
+                                .map(|interval| Interval::try_from(interval).map(SqlInterval::from))
+                                .transpose()?,
+                            /* helper_aggregate_share */
+                            &collection_job.helper_aggregate_share,
+                            /* leader_aggregate_share */
+                            &collection_job.leader_aggregate_share,
+                            /* step_attempts */ &collection_job.step_attempts,
+                            /* created_at */ &now,
+                            /* updated_at */ &now,
+                            /* updated_by */ &self.name,
+                        ],
+                    )
+                    .await?,
+                )
+            }
+        }))
+        .await?;
 
-    /// Get the [`TaskUploadCounter`] for a task. This is aggregated across all shards. Returns
-    /// `None` if the task doesn't exist.
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn get_task_upload_counter(
-        &self,
-        task_id: &TaskId,
-    ) -> Result<Option<TaskUploadCounter>, Error> {
         let stmt = self
             .prepare_cached(
-                "-- get_task_upload_counter()
-SELECT
-    tasks.id,
-    COALESCE(SUM(interval_collected)::BIGINT, 0) AS interval_collected,
-    COALESCE(SUM(report_decode_failure)::BIGINT, 0) AS report_decode_failure,
-    COALESCE(SUM(report_decrypt_failure)::BIGINT, 0) AS report_decrypt_failure,
-    COALESCE(SUM(report_expired)::BIGINT, 0) AS report_expired,
-    COALESCE(SUM(report_outdated_key)::BIGINT, 0) AS report_outdated_key,
-    COALESCE(SUM(report_success)::BIGINT, 0) AS report_success,
-    COALESCE(SUM(report_too_early)::BIGINT, 0) AS report_too_early,
-    COALESCE(SUM(task_not_started)::BIGINT, 0) AS task_not_started,
-    COALESCE(SUM(task_ended)::BIGINT, 0) AS task_ended
-FROM task_upload_counters
-RIGHT JOIN tasks on tasks.id = task_upload_counters.task_id
-WHERE tasks.task_id = $1
-GROUP BY tasks.id",
-            )
-            .await?;
-
-        self.query_opt(&stmt, &[task_id.as_ref()])
-            .await?
-            .map(|row| {
-                Ok(TaskUploadCounter {
-                    interval_collected: row.get_bigint_and_convert("interval_collected")?,
-                    report_decode_failure: row.get_bigint_and_convert("report_decode_failure")?,
-                    report_decrypt_failure: row.get_bigint_and_convert("report_decrypt_failure")?,
-                    report_expired: row.get_bigint_and_convert("report_expired")?,
-                    report_outdated_key: row.get_bigint_and_convert("report_outdated_key")?,
-                    report_success: row.get_bigint_and_convert("report_success")?,
-                    report_too_early: row.get_bigint_and_convert("report_too_early")?,
-                    task_not_started: row.get_bigint_and_convert("task_not_started")?,
-                    task_ended: row.get_bigint_and_convert("task_ended")?,
-                })
-            })
-            .transpose()
-    }
-
-    /// Add a `TaskUploadCounter` to the counter associated with the given [`TaskId`]. This is sharded,
-    /// requiring an `ord` parameter to determine which shard to add to. `ord` should be randomly
-    /// generated by the caller.
-    #[tracing::instrument(skip(self), err(level = Level::DEBUG))]
-    pub async fn increment_task_upload_counter(
-        &self,
-        task_id: &TaskId,
-        ord: u64,
-        counter: &TaskUploadCounter,
-    ) -> Result<(), Error> {
-        let stmt = "-- increment_task_upload_counter()
-INSERT INTO task_upload_counters (
-    task_id, ord, interval_collected, report_decode_failure,
-    report_decrypt_failure, report_expired, report_outdated_key, report_success, report_too_early,
-    task_not_started, task_ended
+                "-- import_task()
+INSERT INTO aggregate_share_jobs (
+    task_id, batch_identifier, batch_interval, aggregation_param,
+    helper_aggregate_share, report_count, checksum, created_at, updated_by
 )
-VALUES ((SELECT id FROM tasks WHERE task_id = $1), $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
-ON CONFLICT (task_id, ord) DO UPDATE SET
-    interval_collected = task_upload_counters.interval_collected + $3,
-    report_decode_failure = task_upload_counters.report_decode_failure + $4,
-    report_decrypt_failure = task_upload_counters.report_decrypt_failure + $5,
-    report_expired = task_upload_counters.report_expired + $6,
-    report_outdated_key = task_upload_counters.report_outdated_key + $7,
-    report_success = task_upload_counters.report_success + $8,
-    report_too_early = task_upload_counters.report_too_early + $9,
-    task_not_started = task_upload_counters.task_not_started + $10,
-    task_ended = task_upload_counters.task_ended + $11";
-
-        let stmt = self.prepare_cached(stmt).await?;
-        check_single_row_mutation(
-            self.execute(
-                &stmt,
-                &[
-                    task_id.as_ref(),
-                    &i64::try_from(ord)?,
-                    &i64::try_from(counter.interval_collected)?,
-                    &i64::try_from(counter.report_decode_failure)?,
-                    &i64::try_from(counter.report_decrypt_failure)?,
-                    &i64::try_from(counter.report_expired)?,
-                    &i64::try_from(counter.report_outdated_key)?,
-                    &i64::try_from(counter.report_success)?,
-                    &i64::try_from(counter.report_too_early)?,
-                    &i64::try_from(counter.task_not_started)?,
-                    &i64::try_from(counter.task_ended)?,
-                ],
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
             )
-            .await?,
+            .await?;
+        try_join_all(
+            archive
+                .aggregate_share_jobs
+                .iter()
+                .map(|aggregate_share_job| {
+                    let stmt = &stmt;
+                    async move {
+                        check_insert(
+                            self.execute(
+                                stmt,
+                                &[
+                                    /* task_id */ &task_info.pkey,
+                                    /* batch_identifier */
+                                    &aggregate_share_job.batch_identifier,
+                                    /* batch_interval */
+                                    &aggregate_share_job
+                                        .batch_interval
+                                        .map(|interval| {
+                                            Interval::try_from(interval).map(SqlInterval::from)
+                                        })
+                                        .transpose()?,
+                                    /* aggregation_param */
+                                    &aggregate_share_job.aggregation_param,
+                                    /* helper_aggregate_share */
+                                    &aggregate_share_job.helper_aggregate_share,
+                                    /* report_count */ &aggregate_share_job.report_count,
+                                    /* checksum */ &aggregate_share_job.checksum,
+                                    /* created_at */ &now,
+                                    /* updated_by */ &self.name,
+                                ],
+                            )
+                            .await?,
+                        )
+                    }
+                }),
         )
-    }
-
-    /// Retrieves the task aggregation counters for a given task. This result reflects the overall
-    /// counter values, merged across all shards.
-    pub async fn get_task_aggregation_counter(
-        &self,
-        task_id: &TaskId,
-    ) -> Result<Option<TaskAggregationCounter>, Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Ok(None),
-        };
+        .await?;
 
         let stmt = self
             .prepare_cached(
-                "-- get_task_aggregation_counter()
-SELECT
-    COALESCE(SUM(success)::BIGINT, 0) AS success
-FROM task_aggregation_counters
-WHERE task_id = $1",
+                "-- import_task()
+INSERT INTO dp_budget_ledger (
+    task_id, batch_identifier, aggregation_param, privacy_loss, created_at, updated_by
+)
+VALUES ($1, $2, $3, $4, $5, $6)",
             )
             .await?;
+        try_join_all(archive.dp_budget_ledger.iter().map(|entry| {
+            let stmt = &stmt;
+            async move {
+                check_insert(
+                    self.execute(
+                        stmt,
+                        &[
+                            /* task_id */ &task_info.pkey,
+                            /* batch_identifier */ &entry.batch_identifier,
+                            /* aggregation_param */ &entry.aggregation_param,
+                            /* privacy_loss */ &entry.privacy_loss,
+                            /* created_at */ &now,
+                            /* updated_by */ &self.name,
+                        ],
+                    )
+                    .await?,
+                )
+            }
+        }))
+        .await?;
 
-        self.query_opt(&stmt, &[/* task_id */ &task_info.pkey])
-            .await?
-            .map(|row| {
-                Ok(TaskAggregationCounter {
-                    success: row.get_bigint_and_convert("success")?,
-                })
-            })
-            .transpose()
-    }
-
-    /// Increments the task aggregation counters for a given task by the values in the provided
-    /// counters. The `ord` parameter determines which "shard" of the counters to increment;
-    /// generally this value would be randomly-generated.
-    pub async fn increment_task_aggregation_counter(
-        &self,
-        task_id: &TaskId,
-        ord: u64,
-        counter: &TaskAggregationCounter,
-    ) -> Result<(), Error> {
-        let task_info = match self.task_info_for(task_id).await? {
-            Some(task_info) => task_info,
-            None => return Err(Error::MutationTargetNotFound),
-        };
-
+        // The task's running total of spent budget must agree with its ledger.
         let stmt = self
             .prepare_cached(
-                "-- increment_task_aggregation_counter()
-INSERT INTO task_aggregation_counters (task_id, ord, success)
-VALUES ($1, $2, $3)
-ON CONFLICT (task_id, ord) DO UPDATE SET
-    success = task_aggregation_counters.success + $3",
+                "-- import_task()
+UPDATE tasks SET dp_budget_spent = (
+    SELECT COALESCE(SUM(privacy_loss), 0) FROM dp_budget_ledger WHERE task_id = $1
+)
+WHERE id = $1",
             )
             .await?;
-
         check_single_row_mutation(
-            self.execute(
-                &stmt,
-                &[
-                    /* task_id */ &task_info.pkey,
-                    /* ord */ &i64::try_from(ord)?,
-                    /* success */ &i64::try_from(counter.success)?,
-                ],
-            )
-            .await?,
-        )
+            self.execute(&stmt, &[/* task_id */ &task_info.pkey])
+                .await?,
+        )?;
+
+        Ok(())
     }
 
     /// Helper function to look up (cached) information about a given task. The cache is retained
@@ -5852,6 +8839,41 @@ fn add_naive_date_time_duration(
     .ok_or(Error::TimeOverflow("overflow adding duration to time"))
 }
 
+/// Converts the rows returned by a job history query, which left-joins the job's history against
+/// the job itself, into a list of [`JobHistoryEntry`]. Returns `None` if there are no rows, i.e.
+/// the job does not exist.
+fn job_history_from_rows(rows: Vec<Row>) -> Result<Option<Vec<JobHistoryEntry>>, Error> {
+    if rows.is_empty() {
+        return Ok(None);
+    }
+    rows.into_iter()
+        // A job without any history is represented by a single row with a NULL history ID.
+        .filter(|row| row.get::<_, Option<i64>>("id").is_some())
+        .map(|row| {
+            let mut record = JobStepRecord::new(row.get("outcome"));
+            if let Some(error) = row.get("error") {
+                record = record.with_error(error);
+            }
+            if let Some(http_status) = row.get::<_, Option<i32>>("http_status") {
+                record = record.with_http_status(u16::try_from(http_status)?);
+            }
+            if let Some(problem_type) = row.get("problem_type") {
+                record = record.with_problem_type(problem_type);
+            }
+            if let Some(retry_delay_ms) = row.get_nullable_bigint_and_convert("retry_delay_ms")? {
+                record = record.with_retry_delay(StdDuration::from_millis(retry_delay_ms));
+            }
+            Ok(JobHistoryEntry::new(
+                row.get_bigint_and_convert("step")?,
+                row.get_bigint_and_convert("lease_attempts")?,
+                record,
+                Time::from_naive_date_time(&row.get("created_at")),
+            ))
+        })
+        .collect::<Result<Vec<_>, Error>>()
+        .map(Some)
+}
+
 /// Extensions for [`tokio_postgres::row::Row`]
 trait RowExt {
     /// Get an integer of type `P` from the row, then attempt to convert it to the desired integer
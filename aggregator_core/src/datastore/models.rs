@@ -33,6 +33,7 @@ use std::{
     fmt::{Debug, Display, Formatter},
     hash::Hash,
     ops::RangeInclusive,
+    time::Duration as StdDuration,
 };
 
 // We have to manually implement [Partial]Eq for a number of types because the derived
@@ -623,6 +624,7 @@ pub struct AcquiredAggregationJob {
     aggregation_job_id: AggregationJobId,
     batch_mode: task::BatchMode,
     vdaf: VdafInstance,
+    created_at: Time,
 }
 
 impl AcquiredAggregationJob {
@@ -632,12 +634,14 @@ impl AcquiredAggregationJob {
         aggregation_job_id: AggregationJobId,
         batch_mode: task::BatchMode,
         vdaf: VdafInstance,
+        created_at: Time,
     ) -> Self {
         Self {
             task_id,
             aggregation_job_id,
             batch_mode,
             vdaf,
+            created_at,
         }
     }
 
@@ -660,6 +664,11 @@ impl AcquiredAggregationJob {
     pub fn vdaf(&self) -> &VdafInstance {
         &self.vdaf
     }
+
+    /// Returns the time at which this aggregation job was created.
+    pub fn created_at(&self) -> &Time {
+        &self.created_at
+    }
 }
 
 /// AcquiredCollectionJob represents an incomplete collection job whose lease has been acquired.
@@ -673,6 +682,7 @@ pub struct AcquiredCollectionJob {
     encoded_batch_identifier: Vec<u8>,
     encoded_aggregation_param: Vec<u8>,
     step_attempts: u64,
+    created_at: Time,
 }
 
 impl AcquiredCollectionJob {
@@ -687,6 +697,7 @@ impl AcquiredCollectionJob {
         encoded_batch_identifier: Vec<u8>,
         encoded_aggregation_param: Vec<u8>,
         step_attempts: u64,
+        created_at: Time,
     ) -> Self {
         Self {
             task_id,
@@ -697,6 +708,7 @@ impl AcquiredCollectionJob {
             encoded_batch_identifier,
             encoded_aggregation_param,
             step_attempts,
+            created_at,
         }
     }
 
@@ -741,6 +753,11 @@ impl AcquiredCollectionJob {
         self.step_attempts
     }
 
+    /// Returns the time at which this collection job was created.
+    pub fn created_at(&self) -> &Time {
+        &self.created_at
+    }
+
     #[cfg(feature = "test-util")]
     pub fn with_step_attempts(self, step_attempts: u64) -> Self {
         Self {
@@ -750,6 +767,593 @@ impl AcquiredCollectionJob {
     }
 }
 
+/// AbandonedAggregationJob summarizes an aggregation job in the abandoned state, for use by
+/// operator tooling. Unlike [`AggregationJob`], it is not generic over the batch mode or VDAF.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AbandonedAggregationJob {
+    task_id: TaskId,
+    aggregation_job_id: AggregationJobId,
+    client_timestamp_interval: Interval,
+    step: AggregationJobStep,
+    last_error: Option<String>,
+    updated_at: Time,
+}
+
+impl AbandonedAggregationJob {
+    /// Creates a new [`AbandonedAggregationJob`].
+    pub fn new(
+        task_id: TaskId,
+        aggregation_job_id: AggregationJobId,
+        client_timestamp_interval: Interval,
+        step: AggregationJobStep,
+        last_error: Option<String>,
+        updated_at: Time,
+    ) -> Self {
+        Self {
+            task_id,
+            aggregation_job_id,
+            client_timestamp_interval,
+            step,
+            last_error,
+            updated_at,
+        }
+    }
+
+    /// Returns the task ID associated with this aggregation job.
+    pub fn task_id(&self) -> &TaskId {
+        &self.task_id
+    }
+
+    /// Returns the aggregation job ID of this aggregation job.
+    pub fn aggregation_job_id(&self) -> &AggregationJobId {
+        &self.aggregation_job_id
+    }
+
+    /// Returns the minimal interval containing all of the client timestamps included in this
+    /// aggregation job.
+    pub fn client_timestamp_interval(&self) -> &Interval {
+        &self.client_timestamp_interval
+    }
+
+    /// Returns the step this aggregation job had reached when it was abandoned.
+    pub fn step(&self) -> AggregationJobStep {
+        self.step
+    }
+
+    /// Returns the most recent error recorded while stepping this aggregation job, if any.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    /// Returns the time at which this aggregation job was last updated, which is typically the
+    /// time at which it was abandoned.
+    pub fn updated_at(&self) -> &Time {
+        &self.updated_at
+    }
+}
+
+/// AbandonedCollectionJob summarizes a collection job in the abandoned state, for use by operator
+/// tooling. Unlike [`CollectionJob`], it is not generic over the batch mode or VDAF.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AbandonedCollectionJob {
+    task_id: TaskId,
+    collection_job_id: CollectionJobId,
+    step_attempts: u64,
+    last_error: Option<String>,
+    updated_at: Time,
+}
+
+impl AbandonedCollectionJob {
+    /// Creates a new [`AbandonedCollectionJob`].
+    pub fn new(
+        task_id: TaskId,
+        collection_job_id: CollectionJobId,
+        step_attempts: u64,
+        last_error: Option<String>,
+        updated_at: Time,
+    ) -> Self {
+        Self {
+            task_id,
+            collection_job_id,
+            step_attempts,
+            last_error,
+            updated_at,
+        }
+    }
+
+    /// Returns the task ID associated with this collection job.
+    pub fn task_id(&self) -> &TaskId {
+        &self.task_id
+    }
+
+    /// Returns the collection job ID of this collection job.
+    pub fn collection_job_id(&self) -> &CollectionJobId {
+        &self.collection_job_id
+    }
+
+    /// Returns the number of times this collection job was stepped without making progress.
+    pub fn step_attempts(&self) -> u64 {
+        self.step_attempts
+    }
+
+    /// Returns the most recent error recorded while stepping this collection job, if any.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    /// Returns the time at which this collection job was last updated, which is typically the
+    /// time at which it was abandoned.
+    pub fn updated_at(&self) -> &Time {
+        &self.updated_at
+    }
+}
+
+/// JobStepOutcome represents the outcome of an unsuccessful attempt to step an aggregation or
+/// collection job. It corresponds to the JOB_STEP_OUTCOME enum in the schema.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, ToSql, FromSql, Serialize, Deserialize)]
+#[postgres(name = "job_step_outcome")]
+#[serde(rename_all = "snake_case")]
+pub enum JobStepOutcome {
+    /// The step failed with an error that is expected to be transient, so the job will be retried.
+    #[postgres(name = "RETRYABLE_ERROR")]
+    RetryableError,
+    /// The step failed with an error that is not expected to be transient, so the job was
+    /// abandoned.
+    #[postgres(name = "FATAL_ERROR")]
+    FatalError,
+    /// The job could not yet make progress, e.g. because a collection job is waiting on
+    /// aggregation to complete.
+    #[postgres(name = "NOT_READY")]
+    NotReady,
+}
+
+/// JobStepRecord describes an unsuccessful attempt to step an aggregation or collection job, as
+/// reported by a job driver.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobStepRecord {
+    outcome: JobStepOutcome,
+    error: Option<String>,
+    http_status: Option<u16>,
+    problem_type: Option<String>,
+    retry_delay: Option<StdDuration>,
+}
+
+impl JobStepRecord {
+    /// Creates a new [`JobStepRecord`] with the given outcome and no further details.
+    pub fn new(outcome: JobStepOutcome) -> Self {
+        Self {
+            outcome,
+            error: None,
+            http_status: None,
+            problem_type: None,
+            retry_delay: None,
+        }
+    }
+
+    /// Returns a new [`JobStepRecord`] which records the given error.
+    pub fn with_error(self, error: String) -> Self {
+        Self {
+            error: Some(error),
+            ..self
+        }
+    }
+
+    /// Returns a new [`JobStepRecord`] which records the given HTTP status returned by the peer
+    /// aggregator.
+    pub fn with_http_status(self, http_status: u16) -> Self {
+        Self {
+            http_status: Some(http_status),
+            ..self
+        }
+    }
+
+    /// Returns a new [`JobStepRecord`] which records the given problem type URI returned by the
+    /// peer aggregator.
+    pub fn with_problem_type(self, problem_type: String) -> Self {
+        Self {
+            problem_type: Some(problem_type),
+            ..self
+        }
+    }
+
+    /// Returns a new [`JobStepRecord`] which records the delay chosen before the job is retried.
+    pub fn with_retry_delay(self, retry_delay: StdDuration) -> Self {
+        Self {
+            retry_delay: Some(retry_delay),
+            ..self
+        }
+    }
+
+    /// Returns the outcome of the attempt.
+    pub fn outcome(&self) -> &JobStepOutcome {
+        &self.outcome
+    }
+
+    /// Returns the error encountered during the attempt, if any.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Returns the HTTP status returned by the peer aggregator, if any.
+    pub fn http_status(&self) -> Option<u16> {
+        self.http_status
+    }
+
+    /// Returns the problem type URI returned by the peer aggregator, if any.
+    pub fn problem_type(&self) -> Option<&str> {
+        self.problem_type.as_deref()
+    }
+
+    /// Returns the delay chosen before the job is retried, if one was chosen. Jobs which fail
+    /// without a chosen delay are retried once their lease expires.
+    pub fn retry_delay(&self) -> Option<&StdDuration> {
+        self.retry_delay.as_ref()
+    }
+}
+
+/// JobHistoryEntry represents a row in the `aggregation_job_history` or `collection_job_history`
+/// tables, recording a single unsuccessful attempt to step a job.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobHistoryEntry {
+    step: u64,
+    lease_attempts: u64,
+    record: JobStepRecord,
+    created_at: Time,
+}
+
+impl JobHistoryEntry {
+    /// Creates a new [`JobHistoryEntry`].
+    pub fn new(step: u64, lease_attempts: u64, record: JobStepRecord, created_at: Time) -> Self {
+        Self {
+            step,
+            lease_attempts,
+            record,
+            created_at,
+        }
+    }
+
+    /// Returns the step of the job at the time of the attempt. For aggregation jobs, this is the
+    /// aggregation job step; for collection jobs, this is the number of step attempts made without
+    /// making progress.
+    pub fn step(&self) -> u64 {
+        self.step
+    }
+
+    /// Returns the number of times the job's lease had been acquired at the time of the attempt.
+    pub fn lease_attempts(&self) -> u64 {
+        self.lease_attempts
+    }
+
+    /// Returns the details of the attempt.
+    pub fn record(&self) -> &JobStepRecord {
+        &self.record
+    }
+
+    /// Returns the time at which the attempt was made.
+    pub fn created_at(&self) -> &Time {
+        &self.created_at
+    }
+}
+
+/// AuditLogOutcome represents whether an audited action succeeded. It corresponds to the
+/// AUDIT_LOG_OUTCOME enum in the schema.
+#[derive(
+    Copy, Clone, Debug, Default, Hash, PartialEq, Eq, ToSql, FromSql, Serialize, Deserialize,
+)]
+#[postgres(name = "audit_log_outcome")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditLogOutcome {
+    /// The action was carried out.
+    #[default]
+    #[postgres(name = "SUCCESS")]
+    Success,
+    /// The action was attempted, but failed.
+    #[postgres(name = "FAILURE")]
+    Failure,
+}
+
+/// AuditLogEntry represents a row in the `audit_log` table, recording a single operator action.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    actor: String,
+    action: String,
+    task_id: Option<TaskId>,
+    target: Option<String>,
+    route: Option<String>,
+    before: Option<serde_json::Value>,
+    after: Option<serde_json::Value>,
+    outcome: AuditLogOutcome,
+    details: serde_json::Value,
+    created_at: Time,
+}
+
+impl AuditLogEntry {
+    /// Creates a new, successful [`AuditLogEntry`] with no further context. Use the `with_`
+    /// methods to describe the action in more detail.
+    pub fn new(actor: String, action: String, created_at: Time) -> Self {
+        Self {
+            actor,
+            action,
+            task_id: None,
+            target: None,
+            route: None,
+            before: None,
+            after: None,
+            outcome: AuditLogOutcome::Success,
+            details: serde_json::Value::Null,
+            created_at,
+        }
+    }
+
+    /// Sets the ID of the task affected by the action.
+    pub fn with_task_id(self, task_id: Option<TaskId>) -> Self {
+        Self { task_id, ..self }
+    }
+
+    /// Sets a description of the specific resource affected by the action.
+    pub fn with_target(self, target: Option<String>) -> Self {
+        Self { target, ..self }
+    }
+
+    /// Sets the API route or CLI command through which the action was taken.
+    pub fn with_route(self, route: Option<String>) -> Self {
+        Self { route, ..self }
+    }
+
+    /// Sets the state of the affected resource before the action. Secrets must already have been
+    /// redacted.
+    pub fn with_before(self, before: Option<serde_json::Value>) -> Self {
+        Self { before, ..self }
+    }
+
+    /// Sets the state of the affected resource after the action. Secrets must already have been
+    /// redacted.
+    pub fn with_after(self, after: Option<serde_json::Value>) -> Self {
+        Self { after, ..self }
+    }
+
+    /// Sets whether the action succeeded.
+    pub fn with_outcome(self, outcome: AuditLogOutcome) -> Self {
+        Self { outcome, ..self }
+    }
+
+    /// Sets additional, action-specific details.
+    pub fn with_details(self, details: serde_json::Value) -> Self {
+        Self { details, ..self }
+    }
+
+    /// Returns the identity of the operator or component which took the action.
+    pub fn actor(&self) -> &str {
+        &self.actor
+    }
+
+    /// Returns the name of the action taken.
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    /// Returns the ID of the task affected by the action, if any.
+    pub fn task_id(&self) -> Option<&TaskId> {
+        self.task_id.as_ref()
+    }
+
+    /// Returns a description of the specific resource affected by the action, if any.
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    /// Returns the API route or CLI command through which the action was taken, if known.
+    pub fn route(&self) -> Option<&str> {
+        self.route.as_deref()
+    }
+
+    /// Returns the redacted state of the affected resource before the action, if any.
+    pub fn before(&self) -> Option<&serde_json::Value> {
+        self.before.as_ref()
+    }
+
+    /// Returns the redacted state of the affected resource after the action, if any.
+    pub fn after(&self) -> Option<&serde_json::Value> {
+        self.after.as_ref()
+    }
+
+    /// Returns whether the action succeeded.
+    pub fn outcome(&self) -> &AuditLogOutcome {
+        &self.outcome
+    }
+
+    /// Returns additional, action-specific details.
+    pub fn details(&self) -> &serde_json::Value {
+        &self.details
+    }
+
+    /// Returns the time at which the action was taken.
+    pub fn created_at(&self) -> &Time {
+        &self.created_at
+    }
+}
+
+/// AuditLogFilter restricts the entries returned by
+/// [`crate::datastore::Transaction::get_audit_log_entries`]. The default filter matches every
+/// entry.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuditLogFilter {
+    task_id: Option<TaskId>,
+    actor: Option<String>,
+    action: Option<String>,
+    since: Option<Time>,
+    until: Option<Time>,
+    limit: Option<u64>,
+}
+
+impl AuditLogFilter {
+    /// Matches only entries affecting the given task.
+    pub fn with_task_id(self, task_id: Option<TaskId>) -> Self {
+        Self { task_id, ..self }
+    }
+
+    /// Matches only entries recorded for the given actor.
+    pub fn with_actor(self, actor: Option<String>) -> Self {
+        Self { actor, ..self }
+    }
+
+    /// Matches only entries recording the given action.
+    pub fn with_action(self, action: Option<String>) -> Self {
+        Self { action, ..self }
+    }
+
+    /// Matches only entries recorded at or after the given time.
+    pub fn with_since(self, since: Option<Time>) -> Self {
+        Self { since, ..self }
+    }
+
+    /// Matches only entries recorded strictly before the given time.
+    pub fn with_until(self, until: Option<Time>) -> Self {
+        Self { until, ..self }
+    }
+
+    /// Returns at most this many entries, most recent first.
+    pub fn with_limit(self, limit: Option<u64>) -> Self {
+        Self { limit, ..self }
+    }
+
+    pub fn task_id(&self) -> Option<&TaskId> {
+        self.task_id.as_ref()
+    }
+
+    pub fn actor(&self) -> Option<&str> {
+        self.actor.as_deref()
+    }
+
+    pub fn action(&self) -> Option<&str> {
+        self.action.as_deref()
+    }
+
+    pub fn since(&self) -> Option<&Time> {
+        self.since.as_ref()
+    }
+
+    pub fn until(&self) -> Option<&Time> {
+        self.until.as_ref()
+    }
+
+    pub fn limit(&self) -> Option<u64> {
+        self.limit
+    }
+}
+
+/// ApiTokenScope represents a permission granted to an aggregator API token. It corresponds to the
+/// API_TOKEN_SCOPE enum in the schema.
+#[derive(
+    Copy, Clone, Debug, Hash, PartialEq, Eq, ToSql, FromSql, Serialize, Deserialize, ValueEnum,
+)]
+#[postgres(name = "api_token_scope")]
+#[serde(rename_all = "snake_case")]
+pub enum ApiTokenScope {
+    /// The token may read any resource, but may not modify anything.
+    #[postgres(name = "READ_ONLY")]
+    ReadOnly,
+    /// The token may create, modify and delete tasks, and requeue or fail their jobs.
+    #[postgres(name = "TASK_ADMIN")]
+    TaskAdmin,
+    /// The token may create, modify and delete HPKE keypairs.
+    #[postgres(name = "KEY_ADMIN")]
+    KeyAdmin,
+    /// The token may create and delete taskprov peer aggregators.
+    #[postgres(name = "TASKPROV_ADMIN")]
+    TaskprovAdmin,
+}
+
+impl ApiTokenScope {
+    /// Returns true if holding this scope permits actions requiring the `required` scope. Every
+    /// scope permits reading.
+    pub fn permits(&self, required: &ApiTokenScope) -> bool {
+        self == required || required == &ApiTokenScope::ReadOnly
+    }
+}
+
+/// ApiToken represents a row in the `api_tokens` table: a named, scoped credential for the
+/// aggregator API. Only the hash of the token is stored.
+#[derive(Clone, Educe, PartialEq, Eq)]
+#[educe(Debug)]
+pub struct ApiToken {
+    name: String,
+    #[educe(Debug(ignore))]
+    token_hash: AuthenticationTokenHash,
+    scopes: Vec<ApiTokenScope>,
+    task_ids: Option<Vec<TaskId>>,
+    created_at: Time,
+    revoked_at: Option<Time>,
+}
+
+impl ApiToken {
+    /// Creates a new, unrevoked [`ApiToken`]. If `task_ids` is set, the token may only be used to
+    /// access those tasks.
+    pub fn new(
+        name: String,
+        token_hash: AuthenticationTokenHash,
+        scopes: Vec<ApiTokenScope>,
+        task_ids: Option<Vec<TaskId>>,
+        created_at: Time,
+    ) -> Self {
+        Self {
+            name,
+            token_hash,
+            scopes,
+            task_ids,
+            created_at,
+            revoked_at: None,
+        }
+    }
+
+    /// Sets the time at which the token was revoked.
+    pub fn with_revoked_at(self, revoked_at: Option<Time>) -> Self {
+        Self { revoked_at, ..self }
+    }
+
+    /// Returns the unique name of the token.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the hash of the token.
+    pub fn token_hash(&self) -> &AuthenticationTokenHash {
+        &self.token_hash
+    }
+
+    /// Returns the scopes granted to the token.
+    pub fn scopes(&self) -> &[ApiTokenScope] {
+        &self.scopes
+    }
+
+    /// Returns the tasks to which the token is restricted, or `None` if it may access any task.
+    pub fn task_ids(&self) -> Option<&[TaskId]> {
+        self.task_ids.as_deref()
+    }
+
+    /// Returns the time at which the token was created.
+    pub fn created_at(&self) -> &Time {
+        &self.created_at
+    }
+
+    /// Returns the time at which the token was revoked, if it has been.
+    pub fn revoked_at(&self) -> Option<&Time> {
+        self.revoked_at.as_ref()
+    }
+
+    /// Returns true if the token grants a scope permitting actions which require `required`.
+    pub fn permits(&self, required: &ApiTokenScope) -> bool {
+        self.scopes.iter().any(|scope| scope.permits(required))
+    }
+
+    /// Returns true if the token may access the given task.
+    pub fn permits_task(&self, task_id: &TaskId) -> bool {
+        self.task_ids
+            .as_ref()
+            .is_none_or(|task_ids| task_ids.contains(task_id))
+    }
+}
+
 /// ReportAggregation represents a the state of a single client report's ongoing aggregation.
 #[derive(Clone, Debug)]
 // PartialEq and Eq are gated on the `test-util` feature  as we do not wish to compare preparation
@@ -1599,7 +2203,7 @@ impl<const SEED_SIZE: usize, A: AsyncAggregator<SEED_SIZE>> BatchAggregationStat
     }
 }
 
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 pub(super) struct EncodedBatchAggregationStateValues {
     // State for Aggregating & Collected states.
     pub(super) aggregate_share: Option<Vec<u8>>,
@@ -2316,6 +2920,8 @@ pub struct TaskUploadCounter {
     pub(crate) task_not_started: u64,
     /// Reports that were submitted to the task after the task's end time.
     pub(crate) task_ended: u64,
+    /// Reports that were submitted to the task while it was paused, draining or archived.
+    pub(crate) task_not_accepting_reports: u64,
 }
 
 impl TaskUploadCounter {
@@ -2331,6 +2937,7 @@ impl TaskUploadCounter {
         report_too_early: u64,
         task_not_started: u64,
         task_ended: u64,
+        task_not_accepting_reports: u64,
     ) -> Self {
         Self {
             interval_collected,
@@ -2342,6 +2949,7 @@ impl TaskUploadCounter {
             report_too_early,
             task_not_started,
             task_ended,
+            task_not_accepting_reports,
         }
     }
 
@@ -2381,6 +2989,10 @@ impl TaskUploadCounter {
         self.task_ended += 1
     }
 
+    pub fn increment_task_not_accepting_reports(&mut self) {
+        self.task_not_accepting_reports += 1
+    }
+
     pub fn interval_collected(&self) -> u64 {
         self.interval_collected
     }
@@ -2416,6 +3028,10 @@ impl TaskUploadCounter {
     pub fn task_ended(&self) -> u64 {
         self.task_ended
     }
+
+    pub fn task_not_accepting_reports(&self) -> u64 {
+        self.task_not_accepting_reports
+    }
 }
 
 /// Per-task counts of aggregated reports.
@@ -2431,6 +3047,11 @@ impl TaskAggregationCounter {
         Self { success }
     }
 
+    /// Returns the number of successfully-aggregated reports.
+    pub fn success(&self) -> u64 {
+        self.success
+    }
+
     /// Increments the counter of successfully-aggregated reports.
     pub fn increment_success(&mut self) {
         self.success += 1
@@ -2442,3 +3063,32 @@ impl TaskAggregationCounter {
         self == &TaskAggregationCounter::default()
     }
 }
+
+/// The differential privacy budget a task has spent on collections of its batches.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DpBudgetUsage {
+    /// The total privacy loss incurred by the task's collections.
+    spent: f64,
+    /// The number of collections which have spent the task's budget.
+    collection_count: u64,
+}
+
+impl DpBudgetUsage {
+    /// Creates a new [`DpBudgetUsage`].
+    pub fn new(spent: f64, collection_count: u64) -> Self {
+        Self {
+            spent,
+            collection_count,
+        }
+    }
+
+    /// Returns the total privacy loss incurred by the task's collections.
+    pub fn spent(&self) -> f64 {
+        self.spent
+    }
+
+    /// Returns the number of collections which have spent the task's budget.
+    pub fn collection_count(&self) -> u64 {
+        self.collection_count
+    }
+}
@@ -0,0 +1,1910 @@
+//! Abstraction over the backend in which the datastore keeps its state.
+//!
+//! [`Storage`] runs transactions, and [`StorageTransaction`] provides the operations available
+//! within them. [`Datastore`] implements these traits on top of PostgreSQL, while
+//! [`InMemoryStorage`] keeps all state in memory, which suits tests that don't need durability.
+//!
+//! # Scope
+//!
+//! [`StorageTransaction`] covers the tasks, the HPKE & OHTTP gateway keys managed by the key
+//! rotator, the garbage collector's deletions, and the acquisition & release of aggregation and
+//! collection job leases. Both the key rotator and the garbage collector run against any
+//! [`Storage`] backend.
+//!
+//! Jobs & batch aggregations are written in a type-erased form, with their VDAF- and batch
+//! mode-specific fields encoded: see [`AggregationJobRecord`], [`BatchAggregationRecord`] and
+//! [`CollectionJobRecord`]. [`StorageTransaction`] must be dyn-compatible, so the operations that
+//! decode these fields, which the aggregator and the job drivers use to step jobs, remain methods
+//! of [`Transaction`] only. For the same reason, client reports can only be written scrubbed, via
+//! [`StorageTransaction::put_scrubbed_report`], and report aggregations, aggregate share jobs and
+//! outstanding batches can't be written at all.
+
+use crate::{
+    AsyncAggregator,
+    batch_mode::{AccumulableBatchMode, CollectableBatchMode},
+    datastore::{
+        Datastore, Error, Transaction, add_naive_date_time_duration,
+        models::{
+            AcquiredAggregationJob, AcquiredCollectionJob, AggregationJob, AggregationJobState,
+            BatchAggregation, BatchAggregationStateCode, CollectionJob, CollectionJobStateCode,
+            EncodedBatchAggregationStateValues, HpkeKeyState, HpkeKeypair, Lease, LeaseToken,
+        },
+    },
+    task::AggregatorTask,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use janus_core::{
+    hpke,
+    time::{Clock, DurationExt, IntervalExt, TimeExt},
+};
+use janus_messages::{
+    AggregationJobId, AggregationJobStep, CollectionJobId, HpkeConfigId, Interval, ReportId, Role,
+    TaskId, Time, batch_mode::BatchMode,
+};
+use postgres_types::Timestamp;
+use prio::codec::Encode;
+use rand::random;
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    fmt::Debug,
+    future::Future,
+    hash::Hash,
+    pin::Pin,
+    sync::Mutex,
+    time::Duration as StdDuration,
+};
+
+/// The future returned by the body of a transaction run via [`Storage::run_tx`].
+pub type StorageTxFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>;
+
+/// A backend in which the datastore keeps its state.
+#[async_trait]
+pub trait Storage<C: Clock>: Debug + Send + Sync {
+    /// Runs a transaction, whose body is determined by the given function. The transaction is
+    /// committed if the body returns a successful value, and rolled back if the body returns an
+    /// error value. Backends may retry the transaction, so the given function should support being
+    /// called multiple times. See [`Datastore::run_tx`].
+    async fn run_tx<F, T>(&self, name: &'static str, f: F) -> Result<T, Error>
+    where
+        for<'a> F: Fn(&'a dyn StorageTransaction<C>) -> StorageTxFuture<'a, T> + Send + Sync,
+        T: Send;
+}
+
+/// The operations available within a transaction run via [`Storage::run_tx`]. Each behaves as the
+/// [`Transaction`] method of the same name.
+#[async_trait]
+pub trait StorageTransaction<C: Clock>: Send + Sync {
+    /// Returns the clock used by this transaction.
+    fn clock(&self) -> &C;
+
+    /// Writes a task.
+    async fn put_aggregator_task(&self, task: &AggregatorTask) -> Result<(), Error>;
+
+    /// Fetch the task parameters corresponding to the provided `task_id`.
+    async fn get_aggregator_task(&self, task_id: &TaskId) -> Result<Option<AggregatorTask>, Error>;
+
+    /// Fetch all the tasks.
+    async fn get_aggregator_tasks(&self) -> Result<Vec<AggregatorTask>, Error>;
+
+    /// Stores a scrubbed report, given its associated task ID & identifiers.
+    async fn put_scrubbed_report(
+        &self,
+        task_id: &TaskId,
+        report_id: &ReportId,
+        client_timestamp: &Time,
+    ) -> Result<(), Error>;
+
+    /// Return the number of reports in the provided task whose timestamp falls within the provided
+    /// interval.
+    async fn count_client_reports_for_interval(
+        &self,
+        task_id: &TaskId,
+        batch_interval: &Interval,
+    ) -> Result<u64, Error>;
+
+    /// Deletes up to `limit` client reports older than the task's report expiry age.
+    async fn delete_expired_client_reports(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error>;
+
+    /// Deletes up to `limit` client reports, regardless of their timestamps.
+    async fn delete_all_client_reports(&self, task_id: &TaskId, limit: u64) -> Result<u64, Error>;
+
+    /// Scrubs up to `limit` client reports aggregated longer ago than the task's report retention
+    /// window.
+    async fn scrub_retained_client_reports(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error>;
+
+    /// Stores an aggregation job given in its type-erased form.
+    async fn put_aggregation_job_record(
+        &self,
+        aggregation_job: &AggregationJobRecord,
+    ) -> Result<(), Error>;
+
+    /// Acquires leases on up to `maximum_acquire_count` unclaimed incomplete aggregation jobs.
+    async fn acquire_incomplete_aggregation_jobs(
+        &self,
+        lease_duration: &StdDuration,
+        maximum_acquire_count: usize,
+    ) -> Result<Vec<Lease<AcquiredAggregationJob>>, Error>;
+
+    /// Releases a lease acquired via [`Self::acquire_incomplete_aggregation_jobs`].
+    async fn release_aggregation_job(
+        &self,
+        lease: &Lease<AcquiredAggregationJob>,
+        reacquire_delay: Option<&StdDuration>,
+    ) -> Result<(), Error>;
+
+    /// Deletes up to `limit` aggregation jobs older than the task's report expiry age, along with
+    /// their report aggregations.
+    async fn delete_expired_aggregation_artifacts(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error>;
+
+    /// Deletes up to `limit` aggregation jobs regardless of their client timestamps, along with
+    /// their report aggregations.
+    async fn delete_all_aggregation_artifacts(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error>;
+
+    /// Stores a batch aggregation given in its type-erased form.
+    async fn put_batch_aggregation_record(
+        &self,
+        batch_aggregation: &BatchAggregationRecord,
+    ) -> Result<(), Error>;
+
+    /// Stores a collection job given in its type-erased form.
+    async fn put_collection_job_record(
+        &self,
+        collection_job: &CollectionJobRecord,
+    ) -> Result<(), Error>;
+
+    /// Acquires leases on up to `maximum_acquire_count` unclaimed incomplete collection jobs.
+    async fn acquire_incomplete_collection_jobs(
+        &self,
+        lease_duration: &StdDuration,
+        maximum_acquire_count: usize,
+    ) -> Result<Vec<Lease<AcquiredCollectionJob>>, Error>;
+
+    /// Releases a lease acquired via [`Self::acquire_incomplete_collection_jobs`].
+    async fn release_collection_job(
+        &self,
+        lease: &Lease<AcquiredCollectionJob>,
+        reacquire_delay: Option<&StdDuration>,
+    ) -> Result<(), Error>;
+
+    /// Deletes up to `limit` expired batches, along with their collection artifacts.
+    async fn delete_expired_collection_artifacts(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error>;
+
+    /// Prevents concurrent transactions from writing HPKE keypairs until this one completes.
+    async fn lock_hpke_keypairs(&self) -> Result<(), Error>;
+
+    /// Retrieve all HPKE keypairs.
+    async fn get_hpke_keypairs(&self) -> Result<Vec<HpkeKeypair>, Error>;
+
+    /// Retrieve an HPKE keypair by config ID.
+    async fn get_hpke_keypair(
+        &self,
+        config_id: &HpkeConfigId,
+    ) -> Result<Option<HpkeKeypair>, Error>;
+
+    /// Inserts a new HPKE keypair and places it in the [`HpkeKeyState::Pending`] state.
+    async fn put_hpke_keypair(&self, hpke_keypair: &hpke::HpkeKeypair) -> Result<(), Error>;
+
+    async fn set_hpke_keypair_state(
+        &self,
+        config_id: &HpkeConfigId,
+        state: &HpkeKeyState,
+    ) -> Result<(), Error>;
+
+    /// Unconditionally and fully drop a keypair.
+    async fn delete_hpke_keypair(&self, config_id: &HpkeConfigId) -> Result<(), Error>;
+
+    /// Prevents concurrent transactions from writing OHTTP gateway keypairs until this one
+    /// completes.
+    async fn lock_ohttp_gateway_keypairs(&self) -> Result<(), Error>;
+
+    /// Retrieve all OHTTP gateway keypairs.
+    async fn get_ohttp_gateway_keypairs(&self) -> Result<Vec<HpkeKeypair>, Error>;
+
+    /// Retrieve an OHTTP gateway keypair by key ID.
+    async fn get_ohttp_gateway_keypair(
+        &self,
+        config_id: &HpkeConfigId,
+    ) -> Result<Option<HpkeKeypair>, Error>;
+
+    /// Inserts a new OHTTP gateway keypair and places it in the [`HpkeKeyState::Pending`] state.
+    async fn put_ohttp_gateway_keypair(&self, keypair: &hpke::HpkeKeypair) -> Result<(), Error>;
+
+    async fn set_ohttp_gateway_keypair_state(
+        &self,
+        config_id: &HpkeConfigId,
+        state: &HpkeKeyState,
+    ) -> Result<(), Error>;
+
+    /// Unconditionally and fully drop an OHTTP gateway keypair.
+    async fn delete_ohttp_gateway_keypair(&self, config_id: &HpkeConfigId) -> Result<(), Error>;
+}
+
+/// An [`AggregationJob`] with its aggregation parameter & batch ID encoded, as written by
+/// [`StorageTransaction::put_aggregation_job_record`].
+#[derive(Clone, Debug)]
+pub struct AggregationJobRecord {
+    task_id: TaskId,
+    aggregation_job_id: AggregationJobId,
+    encoded_aggregation_param: Vec<u8>,
+    encoded_batch_id: Vec<u8>,
+    client_timestamp_interval: Interval,
+    state: AggregationJobState,
+    step: AggregationJobStep,
+    last_request_hash: Option<[u8; 32]>,
+}
+
+impl AggregationJobRecord {
+    /// Returns the task ID associated with this aggregation job.
+    pub fn task_id(&self) -> &TaskId {
+        &self.task_id
+    }
+
+    /// Returns the aggregation job ID associated with this aggregation job.
+    pub fn aggregation_job_id(&self) -> &AggregationJobId {
+        &self.aggregation_job_id
+    }
+
+    /// Returns the encoded aggregation parameter associated with this aggregation job.
+    pub fn encoded_aggregation_param(&self) -> &[u8] {
+        &self.encoded_aggregation_param
+    }
+
+    /// Returns the encoded partial batch identifier associated with this aggregation job.
+    pub fn encoded_batch_id(&self) -> &[u8] {
+        &self.encoded_batch_id
+    }
+
+    /// Returns the minimal interval containing all of the client timestamps associated with this
+    /// aggregation job.
+    pub fn client_timestamp_interval(&self) -> &Interval {
+        &self.client_timestamp_interval
+    }
+
+    /// Returns the state of the aggregation job.
+    pub fn state(&self) -> &AggregationJobState {
+        &self.state
+    }
+
+    /// Returns the step the aggregation job is on.
+    pub fn step(&self) -> AggregationJobStep {
+        self.step
+    }
+
+    /// Returns the SHA-256 digest of the most recent request for the job, if any.
+    pub fn last_request_hash(&self) -> Option<[u8; 32]> {
+        self.last_request_hash
+    }
+}
+
+impl<const SEED_SIZE: usize, B: BatchMode, A: AsyncAggregator<SEED_SIZE>>
+    TryFrom<&AggregationJob<SEED_SIZE, B, A>> for AggregationJobRecord
+{
+    type Error = Error;
+
+    fn try_from(aggregation_job: &AggregationJob<SEED_SIZE, B, A>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            task_id: *aggregation_job.task_id(),
+            aggregation_job_id: *aggregation_job.id(),
+            encoded_aggregation_param: aggregation_job.aggregation_parameter().get_encoded()?,
+            encoded_batch_id: aggregation_job.partial_batch_identifier().get_encoded()?,
+            client_timestamp_interval: *aggregation_job.client_timestamp_interval(),
+            state: *aggregation_job.state(),
+            step: aggregation_job.step(),
+            last_request_hash: aggregation_job.last_request_hash(),
+        })
+    }
+}
+
+/// A [`BatchAggregation`] with its batch identifier, aggregation parameter & state encoded, as
+/// written by [`StorageTransaction::put_batch_aggregation_record`].
+#[derive(Clone, Debug)]
+pub struct BatchAggregationRecord {
+    task_id: TaskId,
+    encoded_batch_identifier: Vec<u8>,
+    batch_interval: Option<Interval>,
+    encoded_aggregation_param: Vec<u8>,
+    ord: u64,
+    client_timestamp_interval: Interval,
+    state: BatchAggregationStateCode,
+    encoded_state_values: EncodedBatchAggregationStateValues,
+}
+
+impl BatchAggregationRecord {
+    /// Returns the task ID associated with this batch aggregation.
+    pub fn task_id(&self) -> &TaskId {
+        &self.task_id
+    }
+
+    /// Returns the encoded batch identifier of this batch aggregation.
+    pub fn encoded_batch_identifier(&self) -> &[u8] {
+        &self.encoded_batch_identifier
+    }
+
+    /// Returns the batch interval of this batch aggregation, if its batch mode identifies batches
+    /// by interval.
+    pub fn batch_interval(&self) -> Option<&Interval> {
+        self.batch_interval.as_ref()
+    }
+
+    /// Returns the encoded aggregation parameter associated with this batch aggregation.
+    pub fn encoded_aggregation_param(&self) -> &[u8] {
+        &self.encoded_aggregation_param
+    }
+
+    /// Returns the index of this batch aggregation among all batch aggregations for this (task_id,
+    /// batch_identifier, aggregation_parameter).
+    pub fn ord(&self) -> u64 {
+        self.ord
+    }
+
+    /// Returns the minimal interval of time spanned by the reports included in this batch
+    /// aggregation shard.
+    pub fn client_timestamp_interval(&self) -> &Interval {
+        &self.client_timestamp_interval
+    }
+
+    pub(super) fn state(&self) -> &BatchAggregationStateCode {
+        &self.state
+    }
+
+    pub(super) fn encoded_state_values(&self) -> &EncodedBatchAggregationStateValues {
+        &self.encoded_state_values
+    }
+
+    /// Returns the end of the time spanned by this batch aggregation, which determines when it
+    /// expires.
+    fn end(&self) -> Time {
+        self.batch_interval
+            .as_ref()
+            .unwrap_or(&self.client_timestamp_interval)
+            .end()
+    }
+
+    fn is_for_batch(&self, task_id: &TaskId, batch: &(Vec<u8>, Vec<u8>)) -> bool {
+        &self.task_id == task_id
+            && self.encoded_batch_identifier == batch.0
+            && self.encoded_aggregation_param == batch.1
+    }
+}
+
+impl<const SEED_SIZE: usize, B: AccumulableBatchMode, A: AsyncAggregator<SEED_SIZE>>
+    TryFrom<&BatchAggregation<SEED_SIZE, B, A>> for BatchAggregationRecord
+{
+    type Error = Error;
+
+    fn try_from(
+        batch_aggregation: &BatchAggregation<SEED_SIZE, B, A>,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            task_id: *batch_aggregation.task_id(),
+            encoded_batch_identifier: batch_aggregation.batch_identifier().get_encoded()?,
+            batch_interval: B::to_batch_interval(batch_aggregation.batch_identifier()).copied(),
+            encoded_aggregation_param: batch_aggregation.aggregation_parameter().get_encoded()?,
+            ord: batch_aggregation.ord(),
+            client_timestamp_interval: *batch_aggregation.client_timestamp_interval(),
+            state: batch_aggregation.state().state_code(),
+            encoded_state_values: batch_aggregation.state().encoded_values_from_state()?,
+        })
+    }
+}
+
+/// A [`CollectionJob`] with its query, aggregation parameter & batch identifier encoded, as
+/// written by [`StorageTransaction::put_collection_job_record`].
+#[derive(Clone, Debug)]
+pub struct CollectionJobRecord {
+    task_id: TaskId,
+    collection_job_id: CollectionJobId,
+    encoded_query: Vec<u8>,
+    encoded_aggregation_param: Vec<u8>,
+    encoded_batch_identifier: Vec<u8>,
+    batch_interval: Option<Interval>,
+    state: CollectionJobStateCode,
+}
+
+impl CollectionJobRecord {
+    /// Returns the task ID associated with this collection job.
+    pub fn task_id(&self) -> &TaskId {
+        &self.task_id
+    }
+
+    /// Returns the collection job ID associated with this collection job.
+    pub fn collection_job_id(&self) -> &CollectionJobId {
+        &self.collection_job_id
+    }
+
+    /// Returns the encoded query associated with this collection job.
+    pub fn encoded_query(&self) -> &[u8] {
+        &self.encoded_query
+    }
+
+    /// Returns the encoded aggregation parameter associated with this collection job.
+    pub fn encoded_aggregation_param(&self) -> &[u8] {
+        &self.encoded_aggregation_param
+    }
+
+    /// Returns the encoded batch identifier associated with this collection job.
+    pub fn encoded_batch_identifier(&self) -> &[u8] {
+        &self.encoded_batch_identifier
+    }
+
+    /// Returns the batch interval of this collection job, if its batch mode identifies batches by
+    /// interval.
+    pub fn batch_interval(&self) -> Option<&Interval> {
+        self.batch_interval.as_ref()
+    }
+
+    /// Returns the state of this collection job.
+    pub fn state(&self) -> &CollectionJobStateCode {
+        &self.state
+    }
+
+    fn is_for_batch(&self, batch: &(Vec<u8>, Vec<u8>)) -> bool {
+        self.encoded_batch_identifier == batch.0 && self.encoded_aggregation_param == batch.1
+    }
+}
+
+impl<const SEED_SIZE: usize, B: CollectableBatchMode, A: AsyncAggregator<SEED_SIZE>>
+    TryFrom<&CollectionJob<SEED_SIZE, B, A>> for CollectionJobRecord
+{
+    type Error = Error;
+
+    fn try_from(collection_job: &CollectionJob<SEED_SIZE, B, A>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            task_id: *collection_job.task_id(),
+            collection_job_id: *collection_job.id(),
+            encoded_query: collection_job.query().get_encoded()?,
+            encoded_aggregation_param: collection_job.aggregation_parameter().get_encoded()?,
+            encoded_batch_identifier: collection_job.batch_identifier().get_encoded()?,
+            batch_interval: B::to_batch_interval(collection_job.batch_identifier()).copied(),
+            state: collection_job.state().collection_job_state_code(),
+        })
+    }
+}
+
+#[async_trait]
+impl<C: Clock> Storage<C> for Datastore<C> {
+    async fn run_tx<F, T>(&self, name: &'static str, f: F) -> Result<T, Error>
+    where
+        for<'a> F: Fn(&'a dyn StorageTransaction<C>) -> StorageTxFuture<'a, T> + Send + Sync,
+        T: Send,
+    {
+        Datastore::run_tx(self, name, |tx| f(tx as &dyn StorageTransaction<C>)).await
+    }
+}
+
+#[async_trait]
+impl<C: Clock> StorageTransaction<C> for Transaction<'_, C> {
+    fn clock(&self) -> &C {
+        Transaction::clock(self)
+    }
+
+    async fn put_aggregator_task(&self, task: &AggregatorTask) -> Result<(), Error> {
+        Transaction::put_aggregator_task(self, task).await
+    }
+
+    async fn get_aggregator_task(&self, task_id: &TaskId) -> Result<Option<AggregatorTask>, Error> {
+        Transaction::get_aggregator_task(self, task_id).await
+    }
+
+    async fn get_aggregator_tasks(&self) -> Result<Vec<AggregatorTask>, Error> {
+        Transaction::get_aggregator_tasks(self).await
+    }
+
+    async fn put_scrubbed_report(
+        &self,
+        task_id: &TaskId,
+        report_id: &ReportId,
+        client_timestamp: &Time,
+    ) -> Result<(), Error> {
+        Transaction::put_scrubbed_report(self, task_id, report_id, client_timestamp).await
+    }
+
+    async fn count_client_reports_for_interval(
+        &self,
+        task_id: &TaskId,
+        batch_interval: &Interval,
+    ) -> Result<u64, Error> {
+        Transaction::count_client_reports_for_interval(self, task_id, batch_interval).await
+    }
+
+    async fn delete_expired_client_reports(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        Transaction::delete_expired_client_reports(self, task_id, limit).await
+    }
+
+    async fn delete_all_client_reports(&self, task_id: &TaskId, limit: u64) -> Result<u64, Error> {
+        Transaction::delete_all_client_reports(self, task_id, limit).await
+    }
+
+    async fn scrub_retained_client_reports(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        Transaction::scrub_retained_client_reports(self, task_id, limit).await
+    }
+
+    async fn put_aggregation_job_record(
+        &self,
+        aggregation_job: &AggregationJobRecord,
+    ) -> Result<(), Error> {
+        Transaction::put_aggregation_job_record(self, aggregation_job).await
+    }
+
+    async fn acquire_incomplete_aggregation_jobs(
+        &self,
+        lease_duration: &StdDuration,
+        maximum_acquire_count: usize,
+    ) -> Result<Vec<Lease<AcquiredAggregationJob>>, Error> {
+        Transaction::acquire_incomplete_aggregation_jobs(
+            self,
+            lease_duration,
+            maximum_acquire_count,
+        )
+        .await
+    }
+
+    async fn release_aggregation_job(
+        &self,
+        lease: &Lease<AcquiredAggregationJob>,
+        reacquire_delay: Option<&StdDuration>,
+    ) -> Result<(), Error> {
+        Transaction::release_aggregation_job(self, lease, reacquire_delay).await
+    }
+
+    async fn delete_expired_aggregation_artifacts(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        Transaction::delete_expired_aggregation_artifacts(self, task_id, limit).await
+    }
+
+    async fn delete_all_aggregation_artifacts(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        Transaction::delete_all_aggregation_artifacts(self, task_id, limit).await
+    }
+
+    async fn put_batch_aggregation_record(
+        &self,
+        batch_aggregation: &BatchAggregationRecord,
+    ) -> Result<(), Error> {
+        Transaction::put_batch_aggregation_record(self, batch_aggregation).await
+    }
+
+    async fn put_collection_job_record(
+        &self,
+        collection_job: &CollectionJobRecord,
+    ) -> Result<(), Error> {
+        Transaction::put_collection_job_record(self, collection_job).await
+    }
+
+    async fn acquire_incomplete_collection_jobs(
+        &self,
+        lease_duration: &StdDuration,
+        maximum_acquire_count: usize,
+    ) -> Result<Vec<Lease<AcquiredCollectionJob>>, Error> {
+        Transaction::acquire_incomplete_collection_jobs(self, lease_duration, maximum_acquire_count)
+            .await
+    }
+
+    async fn release_collection_job(
+        &self,
+        lease: &Lease<AcquiredCollectionJob>,
+        reacquire_delay: Option<&StdDuration>,
+    ) -> Result<(), Error> {
+        Transaction::release_collection_job(self, lease, reacquire_delay).await
+    }
+
+    async fn delete_expired_collection_artifacts(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        Transaction::delete_expired_collection_artifacts(self, task_id, limit).await
+    }
+
+    async fn lock_hpke_keypairs(&self) -> Result<(), Error> {
+        Transaction::lock_hpke_keypairs(self).await
+    }
+
+    async fn get_hpke_keypairs(&self) -> Result<Vec<HpkeKeypair>, Error> {
+        Transaction::get_hpke_keypairs(self).await
+    }
+
+    async fn get_hpke_keypair(
+        &self,
+        config_id: &HpkeConfigId,
+    ) -> Result<Option<HpkeKeypair>, Error> {
+        Transaction::get_hpke_keypair(self, config_id).await
+    }
+
+    async fn put_hpke_keypair(&self, hpke_keypair: &hpke::HpkeKeypair) -> Result<(), Error> {
+        Transaction::put_hpke_keypair(self, hpke_keypair).await
+    }
+
+    async fn set_hpke_keypair_state(
+        &self,
+        config_id: &HpkeConfigId,
+        state: &HpkeKeyState,
+    ) -> Result<(), Error> {
+        Transaction::set_hpke_keypair_state(self, config_id, state).await
+    }
+
+    async fn delete_hpke_keypair(&self, config_id: &HpkeConfigId) -> Result<(), Error> {
+        Transaction::delete_hpke_keypair(self, config_id).await
+    }
+
+    async fn lock_ohttp_gateway_keypairs(&self) -> Result<(), Error> {
+        Transaction::lock_ohttp_gateway_keypairs(self).await
+    }
+
+    async fn get_ohttp_gateway_keypairs(&self) -> Result<Vec<HpkeKeypair>, Error> {
+        Transaction::get_ohttp_gateway_keypairs(self).await
+    }
+
+    async fn get_ohttp_gateway_keypair(
+        &self,
+        config_id: &HpkeConfigId,
+    ) -> Result<Option<HpkeKeypair>, Error> {
+        Transaction::get_ohttp_gateway_keypair(self, config_id).await
+    }
+
+    async fn put_ohttp_gateway_keypair(&self, keypair: &hpke::HpkeKeypair) -> Result<(), Error> {
+        Transaction::put_ohttp_gateway_keypair(self, keypair).await
+    }
+
+    async fn set_ohttp_gateway_keypair_state(
+        &self,
+        config_id: &HpkeConfigId,
+        state: &HpkeKeyState,
+    ) -> Result<(), Error> {
+        Transaction::set_ohttp_gateway_keypair_state(self, config_id, state).await
+    }
+
+    async fn delete_ohttp_gateway_keypair(&self, config_id: &HpkeConfigId) -> Result<(), Error> {
+        Transaction::delete_ohttp_gateway_keypair(self, config_id).await
+    }
+}
+
+/// A [`Storage`] backend which keeps all state in memory, so that it is lost when the backend is
+/// dropped.
+///
+/// Transactions run one at a time, each against its own copy of the state, which replaces the
+/// shared state only if the transaction commits. Transactions are therefore serializable, never
+/// need to be retried, and table locks are no-ops.
+///
+/// Job leases, report expiry and garbage collection follow the same rules as in PostgreSQL: a
+/// lease is held until it expires or is released, and release requires the lease's expiry time &
+/// token to match.
+pub struct InMemoryStorage<C: Clock> {
+    clock: C,
+    state: tokio::sync::Mutex<InMemoryState>,
+}
+
+impl<C: Clock> InMemoryStorage<C> {
+    /// Creates a new, empty in-memory backend.
+    pub fn new(clock: C) -> Self {
+        Self {
+            clock,
+            state: Default::default(),
+        }
+    }
+
+    /// Returns the clock in use by this backend.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+}
+
+impl<C: Clock> Debug for InMemoryStorage<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InMemoryStorage")
+    }
+}
+
+#[async_trait]
+impl<C: Clock> Storage<C> for InMemoryStorage<C> {
+    async fn run_tx<F, T>(&self, _name: &'static str, f: F) -> Result<T, Error>
+    where
+        for<'a> F: Fn(&'a dyn StorageTransaction<C>) -> StorageTxFuture<'a, T> + Send + Sync,
+        T: Send,
+    {
+        let mut state = self.state.lock().await;
+        let tx = InMemoryTransaction {
+            clock: &self.clock,
+            state: Mutex::new(state.clone()),
+        };
+
+        let rslt = f(&tx).await;
+        if rslt.is_ok() {
+            *state = tx.state.into_inner().unwrap();
+        }
+        rslt
+    }
+}
+
+/// Keypairs of either the HPKE or the OHTTP gateway key table, by config ID.
+type Keypairs = HashMap<HpkeConfigId, HpkeKeypair>;
+
+/// A batch, by encoded batch identifier & aggregation parameter.
+type Batch = (Vec<u8>, Vec<u8>);
+
+#[derive(Clone, Default)]
+struct InMemoryState {
+    hpke_keypairs: Keypairs,
+    ohttp_gateway_keypairs: Keypairs,
+    tasks: HashMap<TaskId, AggregatorTask>,
+    /// The client timestamps of scrubbed client reports.
+    client_reports: HashMap<(TaskId, ReportId), Time>,
+    aggregation_jobs: HashMap<(TaskId, AggregationJobId), InMemoryJob<AggregationJobRecord>>,
+    batch_aggregations: Vec<BatchAggregationRecord>,
+    collection_jobs: HashMap<(TaskId, CollectionJobId), InMemoryJob<CollectionJobRecord>>,
+}
+
+/// A job, along with the state of its lease.
+#[derive(Clone)]
+struct InMemoryJob<R> {
+    record: R,
+    /// When the current lease expires. `None` stands for `-infinity`, i.e. the job has never been
+    /// leased, or its last lease was released without a reacquire delay.
+    lease_expiry: Option<NaiveDateTime>,
+    lease_token: Option<LeaseToken>,
+    lease_attempts: usize,
+    step_attempts: u64,
+    created_at: Time,
+}
+
+impl<R> InMemoryJob<R> {
+    fn new(record: R, created_at: Time) -> Self {
+        Self {
+            record,
+            lease_expiry: None,
+            lease_token: None,
+            lease_attempts: 0,
+            step_attempts: 0,
+            created_at,
+        }
+    }
+
+    fn is_leased(&self, now: &NaiveDateTime) -> bool {
+        self.lease_expiry
+            .is_some_and(|lease_expiry| &lease_expiry > now)
+    }
+
+    fn acquire(&mut self, lease_expiry: NaiveDateTime) -> LeaseToken {
+        let lease_token = random();
+        self.lease_expiry = Some(lease_expiry);
+        self.lease_token = Some(lease_token);
+        self.lease_attempts += 1;
+        lease_token
+    }
+
+    fn holds<T>(&self, lease: &Lease<T>) -> bool {
+        self.lease_expiry.as_ref() == Some(lease.lease_expiry_time())
+            && self.lease_token.as_ref() == Some(lease.lease_token())
+    }
+
+    fn release(&mut self, lease_expiry: Option<NaiveDateTime>) {
+        self.lease_expiry = lease_expiry;
+        self.lease_token = None;
+        self.lease_attempts = 0;
+    }
+}
+
+struct InMemoryTransaction<'a, C: Clock> {
+    clock: &'a C,
+    state: Mutex<InMemoryState>,
+}
+
+impl<C: Clock> InMemoryTransaction<'_, C> {
+    fn get_keypairs(&self, table: fn(&mut InMemoryState) -> &mut Keypairs) -> Vec<HpkeKeypair> {
+        table(&mut self.state.lock().unwrap())
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    fn get_keypair(
+        &self,
+        table: fn(&mut InMemoryState) -> &mut Keypairs,
+        config_id: &HpkeConfigId,
+    ) -> Option<HpkeKeypair> {
+        table(&mut self.state.lock().unwrap())
+            .get(config_id)
+            .cloned()
+    }
+
+    fn put_keypair(
+        &self,
+        table: fn(&mut InMemoryState) -> &mut Keypairs,
+        keypair: &hpke::HpkeKeypair,
+    ) -> Result<(), Error> {
+        let now = self.clock.now();
+        match table(&mut self.state.lock().unwrap()).entry(*keypair.config().id()) {
+            Entry::Occupied(_) => Err(Error::MutationTargetAlreadyExists),
+            Entry::Vacant(entry) => {
+                entry.insert(HpkeKeypair::new(
+                    keypair.clone(),
+                    HpkeKeyState::Pending,
+                    now,
+                ));
+                Ok(())
+            }
+        }
+    }
+
+    fn set_keypair_state(
+        &self,
+        table: fn(&mut InMemoryState) -> &mut Keypairs,
+        config_id: &HpkeConfigId,
+        state: &HpkeKeyState,
+        now: Time,
+    ) -> Result<(), Error> {
+        table(&mut self.state.lock().unwrap())
+            .get_mut(config_id)
+            .ok_or(Error::MutationTargetNotFound)?
+            .set_state(*state, now);
+        Ok(())
+    }
+
+    fn delete_keypair(
+        &self,
+        table: fn(&mut InMemoryState) -> &mut Keypairs,
+        config_id: &HpkeConfigId,
+    ) -> Result<(), Error> {
+        table(&mut self.state.lock().unwrap())
+            .remove(config_id)
+            .map(|_| ())
+            .ok_or(Error::MutationTargetNotFound)
+    }
+
+    /// Returns the report expiry threshold of the given task, if it exists.
+    fn report_expiry_threshold(
+        &self,
+        state: &InMemoryState,
+        task_id: &TaskId,
+    ) -> Result<Option<Timestamp<Time>>, Error> {
+        state
+            .tasks
+            .get(task_id)
+            .map(|task| report_expiry_threshold(task, &self.clock.now()))
+            .transpose()
+    }
+
+    fn delete_client_reports_before(
+        &self,
+        task_id: &TaskId,
+        threshold: impl FnOnce(&AggregatorTask) -> Result<Timestamp<Time>, Error>,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        let mut state = self.state.lock().unwrap();
+        let threshold = match state.tasks.get(task_id) {
+            Some(task) => threshold(task)?,
+            None => return Ok(0),
+        };
+        Ok(delete_up_to(
+            &mut state.client_reports,
+            limit,
+            |(report_task_id, _), client_timestamp| {
+                report_task_id == task_id && is_before(client_timestamp, &threshold)
+            },
+        ))
+    }
+
+    fn delete_aggregation_artifacts_before(
+        &self,
+        task_id: &TaskId,
+        threshold: impl FnOnce(&AggregatorTask) -> Result<Timestamp<Time>, Error>,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        let mut state = self.state.lock().unwrap();
+        let threshold = match state.tasks.get(task_id) {
+            Some(task) => threshold(task)?,
+            None => return Ok(0),
+        };
+        // Report aggregations can't be written via `StorageTransaction`, so there are none to
+        // delete along with the jobs.
+        Ok(delete_up_to(
+            &mut state.aggregation_jobs,
+            limit,
+            |(job_task_id, _), job| {
+                job_task_id == task_id
+                    && is_before(&job.record.client_timestamp_interval().end(), &threshold)
+            },
+        ))
+    }
+}
+
+fn hpke_keypairs(state: &mut InMemoryState) -> &mut Keypairs {
+    &mut state.hpke_keypairs
+}
+
+fn ohttp_gateway_keypairs(state: &mut InMemoryState) -> &mut Keypairs {
+    &mut state.ohttp_gateway_keypairs
+}
+
+/// Computes the minimum timestamp at which the given task's reports are not GC'ed, as
+/// `TaskInfo::report_expiry_threshold` does.
+fn report_expiry_threshold(task: &AggregatorTask, now: &Time) -> Result<Timestamp<Time>, Error> {
+    match task.report_expiry_age() {
+        Some(report_expiry_age) => Ok(Timestamp::Value(
+            now.sub(report_expiry_age)
+                .map_err(|_| Error::TimeOverflow("overflow computing report expiry threshold"))?,
+        )),
+        None => Ok(Timestamp::NegInfinity),
+    }
+}
+
+/// Returns whether `time` is before `threshold`, i.e. whatever it timestamps has expired.
+fn is_before(time: &Time, threshold: &Timestamp<Time>) -> bool {
+    match threshold {
+        Timestamp::PosInfinity => true,
+        Timestamp::NegInfinity => false,
+        Timestamp::Value(threshold) => time < threshold,
+    }
+}
+
+/// Returns the time which determines whether a collection job has expired: the start of its batch
+/// interval if any, or else the latest end of the client timestamp intervals of its batch's
+/// aggregations. `None` stands for `-infinity`, i.e. the collection job has no batch aggregations.
+fn collection_job_time(
+    batch_aggregations: &[BatchAggregationRecord],
+    collection_job: &CollectionJobRecord,
+) -> Option<Time> {
+    match collection_job.batch_interval() {
+        Some(batch_interval) => Some(*batch_interval.start()),
+        None => batch_aggregations
+            .iter()
+            .filter(|batch_aggregation| {
+                batch_aggregation.task_id() == collection_job.task_id()
+                    && batch_aggregation.encoded_batch_identifier()
+                        == collection_job.encoded_batch_identifier()
+                    && batch_aggregation.encoded_aggregation_param()
+                        == collection_job.encoded_aggregation_param()
+            })
+            .map(|batch_aggregation| batch_aggregation.client_timestamp_interval().end())
+            .max(),
+    }
+}
+
+/// Returns whether a collection job with the given [`collection_job_time`] has expired.
+fn is_collection_job_expired(time: Option<Time>, threshold: &Timestamp<Time>) -> bool {
+    match time {
+        Some(time) => is_before(&time, threshold),
+        None => !matches!(threshold, Timestamp::NegInfinity),
+    }
+}
+
+/// Removes up to `limit` entries matching `predicate` from `map`, returning how many were removed.
+fn delete_up_to<K: Eq + Hash, V>(
+    map: &mut HashMap<K, V>,
+    limit: u64,
+    mut predicate: impl FnMut(&K, &V) -> bool,
+) -> u64 {
+    let mut deleted = 0;
+    map.retain(|key, value| {
+        if deleted < limit && predicate(key, value) {
+            deleted += 1;
+            false
+        } else {
+            true
+        }
+    });
+    deleted
+}
+
+fn unaligned_time_error(task: &AggregatorTask, inner_error: janus_messages::Error) -> Error {
+    Error::TimeUnaligned {
+        task_id: *task.id(),
+        time_precision: *task.time_precision(),
+        inner_error,
+    }
+}
+
+#[async_trait]
+impl<C: Clock> StorageTransaction<C> for InMemoryTransaction<'_, C> {
+    fn clock(&self) -> &C {
+        self.clock
+    }
+
+    async fn put_aggregator_task(&self, task: &AggregatorTask) -> Result<(), Error> {
+        if let Some(start) = task.task_start() {
+            start
+                .validate_precision(task.time_precision())
+                .map_err(|e| unaligned_time_error(task, e))?;
+        }
+        if let Some(end) = task.task_end() {
+            end.validate_precision(task.time_precision())
+                .map_err(|e| unaligned_time_error(task, e))?;
+        }
+        task.tolerable_clock_skew()
+            .validate_precision(task.time_precision())
+            .map_err(|e| unaligned_time_error(task, e))?;
+
+        match self.state.lock().unwrap().tasks.entry(*task.id()) {
+            Entry::Occupied(_) => Err(Error::MutationTargetAlreadyExists),
+            Entry::Vacant(entry) => {
+                entry.insert(task.clone());
+                Ok(())
+            }
+        }
+    }
+
+    async fn get_aggregator_task(&self, task_id: &TaskId) -> Result<Option<AggregatorTask>, Error> {
+        Ok(self.state.lock().unwrap().tasks.get(task_id).cloned())
+    }
+
+    async fn get_aggregator_tasks(&self) -> Result<Vec<AggregatorTask>, Error> {
+        Ok(self.state.lock().unwrap().tasks.values().cloned().collect())
+    }
+
+    async fn put_scrubbed_report(
+        &self,
+        task_id: &TaskId,
+        report_id: &ReportId,
+        client_timestamp: &Time,
+    ) -> Result<(), Error> {
+        let now = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        let InMemoryState {
+            tasks,
+            client_reports,
+            ..
+        } = &mut *state;
+        let task = tasks.get(task_id).ok_or(Error::MutationTargetNotFound)?;
+
+        client_timestamp
+            .validate_precision(task.time_precision())
+            .map_err(|e| unaligned_time_error(task, e))?;
+        let threshold = report_expiry_threshold(task, &now)?;
+
+        match client_reports.entry((*task_id, *report_id)) {
+            // As in PostgreSQL, an expired report with the same ID is replaced.
+            Entry::Occupied(mut entry) if is_before(entry.get(), &threshold) => {
+                entry.insert(*client_timestamp);
+                Ok(())
+            }
+            Entry::Occupied(_) => Err(Error::MutationTargetAlreadyExists),
+            Entry::Vacant(entry) => {
+                entry.insert(*client_timestamp);
+                Ok(())
+            }
+        }
+    }
+
+    async fn count_client_reports_for_interval(
+        &self,
+        task_id: &TaskId,
+        batch_interval: &Interval,
+    ) -> Result<u64, Error> {
+        let now = self.clock.now();
+        let state = self.state.lock().unwrap();
+        let task = match state.tasks.get(task_id) {
+            Some(task) => task,
+            None => return Ok(0),
+        };
+
+        batch_interval
+            .validate_precision(task.time_precision())
+            .map_err(|e| unaligned_time_error(task, e))?;
+        let threshold = report_expiry_threshold(task, &now)?;
+
+        Ok(state
+            .client_reports
+            .iter()
+            .filter(|((report_task_id, _), client_timestamp)| {
+                report_task_id == task_id
+                    && *client_timestamp >= batch_interval.start()
+                    && **client_timestamp < batch_interval.end()
+                    && !is_before(client_timestamp, &threshold)
+            })
+            .count()
+            .try_into()?)
+    }
+
+    async fn delete_expired_client_reports(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        let now = self.clock.now();
+        self.delete_client_reports_before(
+            task_id,
+            |task| report_expiry_threshold(task, &now),
+            limit,
+        )
+    }
+
+    async fn delete_all_client_reports(&self, task_id: &TaskId, limit: u64) -> Result<u64, Error> {
+        self.delete_client_reports_before(task_id, |_| Ok(Timestamp::PosInfinity), limit)
+    }
+
+    async fn scrub_retained_client_reports(
+        &self,
+        _task_id: &TaskId,
+        _limit: u64,
+    ) -> Result<u64, Error> {
+        // Reports can only be written scrubbed via `StorageTransaction`, so there is never anything
+        // to scrub.
+        Ok(0)
+    }
+
+    async fn put_aggregation_job_record(
+        &self,
+        aggregation_job: &AggregationJobRecord,
+    ) -> Result<(), Error> {
+        let now = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        let InMemoryState {
+            tasks,
+            aggregation_jobs,
+            ..
+        } = &mut *state;
+        let task = tasks
+            .get(aggregation_job.task_id())
+            .ok_or(Error::MutationTargetNotFound)?;
+
+        aggregation_job
+            .client_timestamp_interval()
+            .validate_precision(task.time_precision())
+            .map_err(|e| unaligned_time_error(task, e))?;
+        let threshold = report_expiry_threshold(task, &now)?;
+
+        match aggregation_jobs.entry((
+            *aggregation_job.task_id(),
+            *aggregation_job.aggregation_job_id(),
+        )) {
+            // As in PostgreSQL, an expired job with the same ID is overwritten, though its lease
+            // is kept.
+            Entry::Occupied(mut entry)
+                if is_before(
+                    &entry.get().record.client_timestamp_interval().end(),
+                    &threshold,
+                ) =>
+            {
+                let job = entry.get_mut();
+                job.record = aggregation_job.clone();
+                job.created_at = now;
+                Ok(())
+            }
+            Entry::Occupied(_) => Err(Error::MutationTargetAlreadyExists),
+            Entry::Vacant(entry) => {
+                entry.insert(InMemoryJob::new(aggregation_job.clone(), now));
+                Ok(())
+            }
+        }
+    }
+
+    async fn acquire_incomplete_aggregation_jobs(
+        &self,
+        lease_duration: &StdDuration,
+        maximum_acquire_count: usize,
+    ) -> Result<Vec<Lease<AcquiredAggregationJob>>, Error> {
+        let now = self.clock.now();
+        let lease_expiry_time =
+            add_naive_date_time_duration(&now.as_naive_date_time()?, lease_duration)?;
+        let mut state = self.state.lock().unwrap();
+        let InMemoryState {
+            tasks,
+            aggregation_jobs,
+            ..
+        } = &mut *state;
+
+        let mut leases = Vec::new();
+        for ((task_id, aggregation_job_id), job) in aggregation_jobs.iter_mut() {
+            if leases.len() >= maximum_acquire_count {
+                break;
+            }
+            let task = tasks.get(task_id).ok_or(Error::MutationTargetNotFound)?;
+            if job.record.state() != &AggregationJobState::Active
+                || job.is_leased(&now.as_naive_date_time()?)
+                || is_before(
+                    &job.record.client_timestamp_interval().end(),
+                    &report_expiry_threshold(task, &now)?,
+                )
+            {
+                continue;
+            }
+
+            let lease_token = job.acquire(lease_expiry_time);
+            leases.push(Lease::new(
+                AcquiredAggregationJob::new(
+                    *task_id,
+                    *aggregation_job_id,
+                    *task.batch_mode(),
+                    task.vdaf().clone(),
+                    job.created_at,
+                ),
+                lease_expiry_time,
+                lease_token,
+                job.lease_attempts,
+            ));
+        }
+        Ok(leases)
+    }
+
+    async fn release_aggregation_job(
+        &self,
+        lease: &Lease<AcquiredAggregationJob>,
+        reacquire_delay: Option<&StdDuration>,
+    ) -> Result<(), Error> {
+        let now = self.clock.now();
+        let lease_expiry = reacquire_delay
+            .map(|rd| add_naive_date_time_duration(&now.as_naive_date_time()?, rd))
+            .transpose()?;
+        let mut state = self.state.lock().unwrap();
+        let threshold = self
+            .report_expiry_threshold(&state, lease.leased().task_id())?
+            .ok_or(Error::MutationTargetNotFound)?;
+
+        let job = state
+            .aggregation_jobs
+            .get_mut(&(
+                *lease.leased().task_id(),
+                *lease.leased().aggregation_job_id(),
+            ))
+            .filter(|job| {
+                job.holds(lease)
+                    && !is_before(&job.record.client_timestamp_interval().end(), &threshold)
+            })
+            .ok_or(Error::MutationTargetNotFound)?;
+        job.release(lease_expiry);
+        Ok(())
+    }
+
+    async fn delete_expired_aggregation_artifacts(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        let now = self.clock.now();
+        self.delete_aggregation_artifacts_before(
+            task_id,
+            |task| report_expiry_threshold(task, &now),
+            limit,
+        )
+    }
+
+    async fn delete_all_aggregation_artifacts(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        self.delete_aggregation_artifacts_before(task_id, |_| Ok(Timestamp::PosInfinity), limit)
+    }
+
+    async fn put_batch_aggregation_record(
+        &self,
+        batch_aggregation: &BatchAggregationRecord,
+    ) -> Result<(), Error> {
+        let now = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        let InMemoryState {
+            tasks,
+            batch_aggregations,
+            ..
+        } = &mut *state;
+        let task = tasks
+            .get(batch_aggregation.task_id())
+            .ok_or(Error::MutationTargetNotFound)?;
+
+        batch_aggregation
+            .client_timestamp_interval()
+            .validate_precision(task.time_precision())
+            .map_err(|e| unaligned_time_error(task, e))?;
+        let threshold = report_expiry_threshold(task, &now)?;
+
+        let batch = (
+            batch_aggregation.encoded_batch_identifier().to_vec(),
+            batch_aggregation.encoded_aggregation_param().to_vec(),
+        );
+        let existing = batch_aggregations.iter().position(|existing| {
+            existing.is_for_batch(batch_aggregation.task_id(), &batch)
+                && existing.ord() == batch_aggregation.ord()
+        });
+        match existing {
+            // As in PostgreSQL, a batch aggregation is overwritten only if its whole batch has
+            // expired.
+            Some(existing) => {
+                let batch_end = batch_aggregations
+                    .iter()
+                    .filter(|other| other.is_for_batch(batch_aggregation.task_id(), &batch))
+                    .map(BatchAggregationRecord::end)
+                    .max()
+                    .unwrap(); // unwrap safety: the existing batch aggregation matches
+                if !is_before(&batch_end, &threshold) {
+                    return Err(Error::MutationTargetAlreadyExists);
+                }
+                batch_aggregations[existing] = batch_aggregation.clone();
+            }
+            None => batch_aggregations.push(batch_aggregation.clone()),
+        }
+        Ok(())
+    }
+
+    async fn put_collection_job_record(
+        &self,
+        collection_job: &CollectionJobRecord,
+    ) -> Result<(), Error> {
+        let now = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        let InMemoryState {
+            tasks,
+            batch_aggregations,
+            collection_jobs,
+            ..
+        } = &mut *state;
+        let task = tasks
+            .get(collection_job.task_id())
+            .ok_or(Error::MutationTargetNotFound)?;
+        let threshold = report_expiry_threshold(task, &now)?;
+
+        match collection_jobs.entry((
+            *collection_job.task_id(),
+            *collection_job.collection_job_id(),
+        )) {
+            // As in PostgreSQL, an expired job with the same ID is overwritten, though its lease
+            // is kept.
+            Entry::Occupied(mut entry)
+                if is_collection_job_expired(
+                    collection_job_time(batch_aggregations, &entry.get().record),
+                    &threshold,
+                ) =>
+            {
+                let job = entry.get_mut();
+                job.record = collection_job.clone();
+                job.created_at = now;
+                Ok(())
+            }
+            Entry::Occupied(_) => Err(Error::MutationTargetAlreadyExists),
+            Entry::Vacant(entry) => {
+                entry.insert(InMemoryJob::new(collection_job.clone(), now));
+                Ok(())
+            }
+        }
+    }
+
+    async fn acquire_incomplete_collection_jobs(
+        &self,
+        lease_duration: &StdDuration,
+        maximum_acquire_count: usize,
+    ) -> Result<Vec<Lease<AcquiredCollectionJob>>, Error> {
+        let now = self.clock.now();
+        let lease_expiry_time =
+            add_naive_date_time_duration(&now.as_naive_date_time()?, lease_duration)?;
+        let mut state = self.state.lock().unwrap();
+        let InMemoryState {
+            tasks,
+            batch_aggregations,
+            collection_jobs,
+            ..
+        } = &mut *state;
+
+        let mut leases = Vec::new();
+        for ((task_id, collection_job_id), job) in collection_jobs.iter_mut() {
+            if leases.len() >= maximum_acquire_count {
+                break;
+            }
+            let task = tasks.get(task_id).ok_or(Error::MutationTargetNotFound)?;
+            if task.role() != &Role::Leader
+                || !matches!(job.record.state(), CollectionJobStateCode::Start)
+                || job.is_leased(&now.as_naive_date_time()?)
+                || is_collection_job_expired(
+                    collection_job_time(batch_aggregations, &job.record),
+                    &report_expiry_threshold(task, &now)?,
+                )
+            {
+                continue;
+            }
+
+            let lease_token = job.acquire(lease_expiry_time);
+            leases.push(Lease::new(
+                AcquiredCollectionJob::new(
+                    *task_id,
+                    *collection_job_id,
+                    *task.batch_mode(),
+                    task.vdaf().clone(),
+                    *task.time_precision(),
+                    job.record.encoded_batch_identifier().to_vec(),
+                    job.record.encoded_aggregation_param().to_vec(),
+                    job.step_attempts,
+                    job.created_at,
+                ),
+                lease_expiry_time,
+                lease_token,
+                job.lease_attempts,
+            ));
+        }
+        Ok(leases)
+    }
+
+    async fn release_collection_job(
+        &self,
+        lease: &Lease<AcquiredCollectionJob>,
+        reacquire_delay: Option<&StdDuration>,
+    ) -> Result<(), Error> {
+        let now = self.clock.now();
+        let lease_expiry = reacquire_delay
+            .map(|rd| add_naive_date_time_duration(&now.as_naive_date_time()?, rd))
+            .transpose()?;
+        let mut state = self.state.lock().unwrap();
+        let threshold = self
+            .report_expiry_threshold(&state, lease.leased().task_id())?
+            .ok_or(Error::MutationTargetNotFound)?;
+        let InMemoryState {
+            batch_aggregations,
+            collection_jobs,
+            ..
+        } = &mut *state;
+
+        let job = collection_jobs
+            .get_mut(&(
+                *lease.leased().task_id(),
+                *lease.leased().collection_job_id(),
+            ))
+            .filter(|job| {
+                job.holds(lease)
+                    && !is_collection_job_expired(
+                        collection_job_time(batch_aggregations, &job.record),
+                        &threshold,
+                    )
+            })
+            .ok_or(Error::MutationTargetNotFound)?;
+        job.release(lease_expiry);
+        // PostgreSQL counts every release as a step attempt, since it compares the released lease's
+        // expiry, which is never `-infinity`, against `-infinity`.
+        job.step_attempts += 1;
+        Ok(())
+    }
+
+    async fn delete_expired_collection_artifacts(
+        &self,
+        task_id: &TaskId,
+        limit: u64,
+    ) -> Result<u64, Error> {
+        let mut state = self.state.lock().unwrap();
+        let threshold = match self.report_expiry_threshold(&state, task_id)? {
+            Some(threshold) => threshold,
+            None => return Ok(0),
+        };
+        let InMemoryState {
+            batch_aggregations,
+            collection_jobs,
+            ..
+        } = &mut *state;
+
+        // A batch is deleted once all of its batch aggregations have expired.
+        let mut batch_ends: HashMap<Batch, Time> = HashMap::new();
+        for batch_aggregation in batch_aggregations
+            .iter()
+            .filter(|batch_aggregation| batch_aggregation.task_id() == task_id)
+        {
+            let batch_end = batch_ends
+                .entry((
+                    batch_aggregation.encoded_batch_identifier().to_vec(),
+                    batch_aggregation.encoded_aggregation_param().to_vec(),
+                ))
+                .or_insert(batch_aggregation.end());
+            *batch_end = (*batch_end).max(batch_aggregation.end());
+        }
+        let batches_to_delete: Vec<Batch> = batch_ends
+            .into_iter()
+            .filter(|(_, batch_end)| is_before(batch_end, &threshold))
+            .map(|(batch, _)| batch)
+            .take(usize::try_from(limit)?)
+            .collect();
+        if batches_to_delete.is_empty() {
+            return Ok(0);
+        }
+
+        // As in PostgreSQL, collection jobs with an expired batch interval are deleted along with
+        // any deleted batch. Aggregate share jobs & outstanding batches can't be written via
+        // `StorageTransaction`, so there are none to delete.
+        collection_jobs.retain(|(job_task_id, _), job| {
+            job_task_id != task_id
+                || !(job
+                    .record
+                    .batch_interval()
+                    .is_some_and(|batch_interval| is_before(batch_interval.start(), &threshold))
+                    || batches_to_delete
+                        .iter()
+                        .any(|batch| job.record.is_for_batch(batch)))
+        });
+        batch_aggregations.retain(|batch_aggregation| {
+            !batches_to_delete
+                .iter()
+                .any(|batch| batch_aggregation.is_for_batch(task_id, batch))
+        });
+
+        Ok(batches_to_delete.len().try_into()?)
+    }
+
+    async fn lock_hpke_keypairs(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn get_hpke_keypairs(&self) -> Result<Vec<HpkeKeypair>, Error> {
+        Ok(self.get_keypairs(hpke_keypairs))
+    }
+
+    async fn get_hpke_keypair(
+        &self,
+        config_id: &HpkeConfigId,
+    ) -> Result<Option<HpkeKeypair>, Error> {
+        Ok(self.get_keypair(hpke_keypairs, config_id))
+    }
+
+    async fn put_hpke_keypair(&self, hpke_keypair: &hpke::HpkeKeypair) -> Result<(), Error> {
+        self.put_keypair(hpke_keypairs, hpke_keypair)
+    }
+
+    async fn set_hpke_keypair_state(
+        &self,
+        config_id: &HpkeConfigId,
+        state: &HpkeKeyState,
+    ) -> Result<(), Error> {
+        self.set_keypair_state(hpke_keypairs, config_id, state, self.clock.now())
+    }
+
+    async fn delete_hpke_keypair(&self, config_id: &HpkeConfigId) -> Result<(), Error> {
+        self.delete_keypair(hpke_keypairs, config_id)
+    }
+
+    async fn lock_ohttp_gateway_keypairs(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn get_ohttp_gateway_keypairs(&self) -> Result<Vec<HpkeKeypair>, Error> {
+        Ok(self.get_keypairs(ohttp_gateway_keypairs))
+    }
+
+    async fn get_ohttp_gateway_keypair(
+        &self,
+        config_id: &HpkeConfigId,
+    ) -> Result<Option<HpkeKeypair>, Error> {
+        Ok(self.get_keypair(ohttp_gateway_keypairs, config_id))
+    }
+
+    async fn put_ohttp_gateway_keypair(&self, keypair: &hpke::HpkeKeypair) -> Result<(), Error> {
+        self.put_keypair(ohttp_gateway_keypairs, keypair)
+    }
+
+    async fn set_ohttp_gateway_keypair_state(
+        &self,
+        config_id: &HpkeConfigId,
+        state: &HpkeKeyState,
+    ) -> Result<(), Error> {
+        self.set_keypair_state(ohttp_gateway_keypairs, config_id, state, self.clock.now())
+    }
+
+    async fn delete_ohttp_gateway_keypair(&self, config_id: &HpkeConfigId) -> Result<(), Error> {
+        self.delete_keypair(ohttp_gateway_keypairs, config_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        datastore::{
+            Error,
+            models::{
+                AcquiredCollectionJob, AggregationJob, AggregationJobState, CollectionJob,
+                CollectionJobState, HpkeKeyState, Lease,
+            },
+            storage::{AggregationJobRecord, CollectionJobRecord, InMemoryStorage, Storage},
+        },
+        task::{self, AggregationMode, AggregatorTask, test_util::TaskBuilder},
+    };
+    use assert_matches::assert_matches;
+    use janus_core::{
+        hpke::HpkeKeypair,
+        time::{Clock, MockClock, TimeExt},
+        vdaf::VdafInstance,
+    };
+    use janus_messages::{
+        AggregationJobStep, Duration, HpkeConfigId, Interval, Query, Time, batch_mode::TimeInterval,
+    };
+    use prio::vdaf::dummy;
+    use rand::random;
+    use std::time::Duration as StdDuration;
+
+    const OLDEST_ALLOWED_REPORT_TIMESTAMP: Time = Time::from_seconds_since_epoch(1000);
+    const REPORT_EXPIRY_AGE: Duration = Duration::from_seconds(1000);
+    const TIME_PRECISION: Duration = Duration::from_seconds(100);
+    const LEASE_DURATION: StdDuration = StdDuration::from_secs(300);
+
+    fn leader_task() -> AggregatorTask {
+        TaskBuilder::new(
+            task::BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Fake { rounds: 1 },
+        )
+        .with_report_expiry_age(Some(REPORT_EXPIRY_AGE))
+        .with_time_precision(TIME_PRECISION)
+        .build()
+        .leader_view()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn in_memory_storage_commit_and_rollback() {
+        let clock = MockClock::default();
+        let storage = InMemoryStorage::new(clock.clone());
+        let keypair = HpkeKeypair::test_with_id(HpkeConfigId::from(1));
+
+        // A transaction which returns an error is rolled back.
+        let result = storage
+            .run_tx("test", |tx| {
+                let keypair = keypair.clone();
+                Box::pin(async move {
+                    tx.put_hpke_keypair(&keypair).await?;
+                    Err::<(), _>(Error::MutationTargetNotFound)
+                })
+            })
+            .await;
+        assert_matches!(result, Err(Error::MutationTargetNotFound));
+        let keypairs = storage
+            .run_tx("test", |tx| {
+                Box::pin(async move { tx.get_hpke_keypairs().await })
+            })
+            .await
+            .unwrap();
+        assert!(keypairs.is_empty());
+
+        // A successful transaction is committed, and later transactions observe its writes.
+        storage
+            .run_tx("test", |tx| {
+                let keypair = keypair.clone();
+                Box::pin(async move {
+                    tx.put_hpke_keypair(&keypair).await?;
+                    tx.set_hpke_keypair_state(keypair.config().id(), &HpkeKeyState::Active)
+                        .await
+                })
+            })
+            .await
+            .unwrap();
+        let got_keypair = storage
+            .run_tx("test", |tx| {
+                Box::pin(async move { tx.get_hpke_keypair(&HpkeConfigId::from(1)).await })
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(got_keypair.hpke_keypair(), &keypair);
+        assert_eq!(got_keypair.state(), &HpkeKeyState::Active);
+        assert_eq!(got_keypair.last_state_change_at(), &clock.now());
+
+        // Mutations report the same errors as the PostgreSQL backend.
+        storage
+            .run_tx("test", |tx| {
+                let keypair = keypair.clone();
+                Box::pin(async move {
+                    assert_matches!(
+                        tx.put_hpke_keypair(&keypair).await,
+                        Err(Error::MutationTargetAlreadyExists)
+                    );
+                    assert_matches!(
+                        tx.delete_ohttp_gateway_keypair(keypair.config().id()).await,
+                        Err(Error::MutationTargetNotFound)
+                    );
+                    Ok(())
+                })
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn in_memory_storage_aggregation_job_leases() {
+        let clock = MockClock::new(OLDEST_ALLOWED_REPORT_TIMESTAMP);
+        let storage = InMemoryStorage::new(clock.clone());
+        let task = leader_task();
+        let aggregation_job =
+            AggregationJobRecord::try_from(&AggregationJob::<0, TimeInterval, dummy::Vdaf>::new(
+                *task.id(),
+                random(),
+                dummy::AggregationParam(0),
+                (),
+                Interval::new(OLDEST_ALLOWED_REPORT_TIMESTAMP, TIME_PRECISION).unwrap(),
+                AggregationJobState::Active,
+                AggregationJobStep::from(0),
+            ))
+            .unwrap();
+
+        storage
+            .run_tx("test", |tx| {
+                let (task, aggregation_job) = (task.clone(), aggregation_job.clone());
+                Box::pin(async move {
+                    tx.put_aggregator_task(&task).await?;
+                    tx.put_aggregation_job_record(&aggregation_job).await?;
+                    assert_matches!(
+                        tx.put_aggregation_job_record(&aggregation_job).await,
+                        Err(Error::MutationTargetAlreadyExists)
+                    );
+                    Ok(())
+                })
+            })
+            .await
+            .unwrap();
+
+        let acquire = || {
+            storage.run_tx("test", |tx| {
+                Box::pin(async move {
+                    tx.acquire_incomplete_aggregation_jobs(&LEASE_DURATION, 10)
+                        .await
+                })
+            })
+        };
+
+        // A job can be acquired, and can't be acquired again while its lease is held.
+        let leases = acquire().await.unwrap();
+        assert_eq!(leases.len(), 1);
+        let lease = leases.into_iter().next().unwrap();
+        assert_eq!(lease.leased().task_id(), task.id());
+        assert_eq!(
+            lease.leased().aggregation_job_id(),
+            aggregation_job.aggregation_job_id()
+        );
+        assert_eq!(
+            lease.leased().created_at(),
+            &OLDEST_ALLOWED_REPORT_TIMESTAMP
+        );
+        assert_eq!(
+            lease.lease_expiry_time(),
+            &clock
+                .now()
+                .as_naive_date_time()
+                .unwrap()
+                .checked_add_signed(chrono::Duration::from_std(LEASE_DURATION).unwrap())
+                .unwrap()
+        );
+        assert_eq!(lease.lease_attempts(), 1);
+        assert!(acquire().await.unwrap().is_empty());
+
+        // Once the lease expires, the job can be reacquired, and the expired lease can no longer
+        // be released.
+        clock.advance(&Duration::from_seconds(LEASE_DURATION.as_secs()));
+        let leases = acquire().await.unwrap();
+        assert_eq!(leases.len(), 1);
+        let reacquired_lease = leases.into_iter().next().unwrap();
+        assert_eq!(reacquired_lease.lease_attempts(), 2);
+        assert_ne!(reacquired_lease.lease_token(), lease.lease_token());
+        storage
+            .run_tx("test", |tx| {
+                let lease = lease.clone();
+                Box::pin(async move {
+                    assert_matches!(
+                        tx.release_aggregation_job(&lease, None).await,
+                        Err(Error::MutationTargetNotFound)
+                    );
+                    Ok(())
+                })
+            })
+            .await
+            .unwrap();
+
+        // Releasing a job with a reacquire delay prevents it from being acquired until the delay
+        // elapses, and resets its lease attempts.
+        storage
+            .run_tx("test", |tx| {
+                let reacquired_lease = reacquired_lease.clone();
+                Box::pin(async move {
+                    tx.release_aggregation_job(&reacquired_lease, Some(&LEASE_DURATION))
+                        .await
+                })
+            })
+            .await
+            .unwrap();
+        assert!(acquire().await.unwrap().is_empty());
+        clock.advance(&Duration::from_seconds(LEASE_DURATION.as_secs()));
+        let leases = acquire().await.unwrap();
+        assert_eq!(leases.len(), 1);
+        assert_eq!(leases[0].lease_attempts(), 1);
+
+        // Jobs whose client timestamps have expired are no longer acquired.
+        storage
+            .run_tx("test", |tx| {
+                let lease = leases[0].clone();
+                Box::pin(async move { tx.release_aggregation_job(&lease, None).await })
+            })
+            .await
+            .unwrap();
+        clock.advance(&REPORT_EXPIRY_AGE);
+        assert!(acquire().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn in_memory_storage_collection_job_leases() {
+        let clock = MockClock::new(OLDEST_ALLOWED_REPORT_TIMESTAMP);
+        let storage = InMemoryStorage::new(clock.clone());
+        let task = leader_task();
+        let batch_interval =
+            Interval::new(OLDEST_ALLOWED_REPORT_TIMESTAMP, TIME_PRECISION).unwrap();
+        let collection_job =
+            CollectionJobRecord::try_from(&CollectionJob::<0, TimeInterval, dummy::Vdaf>::new(
+                *task.id(),
+                random(),
+                Query::<TimeInterval>::new(batch_interval),
+                dummy::AggregationParam(0),
+                batch_interval,
+                CollectionJobState::<0, dummy::Vdaf>::Start,
+            ))
+            .unwrap();
+
+        storage
+            .run_tx("test", |tx| {
+                let (task, collection_job) = (task.clone(), collection_job.clone());
+                Box::pin(async move {
+                    tx.put_aggregator_task(&task).await?;
+                    tx.put_collection_job_record(&collection_job).await
+                })
+            })
+            .await
+            .unwrap();
+
+        let acquire = || {
+            storage.run_tx("test", |tx| {
+                Box::pin(async move {
+                    tx.acquire_incomplete_collection_jobs(&LEASE_DURATION, 10)
+                        .await
+                })
+            })
+        };
+        let release = |lease: Lease<AcquiredCollectionJob>| {
+            storage.run_tx("test", move |tx| {
+                let lease = lease.clone();
+                Box::pin(async move { tx.release_collection_job(&lease, None).await })
+            })
+        };
+
+        let leases = acquire().await.unwrap();
+        assert_eq!(leases.len(), 1);
+        assert_eq!(
+            leases[0].leased().collection_job_id(),
+            collection_job.collection_job_id()
+        );
+        assert_eq!(leases[0].leased().step_attempts(), 0);
+        assert!(acquire().await.unwrap().is_empty());
+
+        // Each release counts as a step attempt.
+        release(leases[0].clone()).await.unwrap();
+        let leases = acquire().await.unwrap();
+        assert_eq!(leases.len(), 1);
+        assert_eq!(leases[0].leased().step_attempts(), 1);
+        release(leases[0].clone()).await.unwrap();
+        assert_matches!(
+            release(leases[0].clone()).await,
+            Err(Error::MutationTargetNotFound)
+        );
+        let leases = acquire().await.unwrap();
+        assert_eq!(leases[0].leased().step_attempts(), 2);
+    }
+}
@@ -0,0 +1,334 @@
+//! Archives of the state of a single task, used to move a task between Janus deployments or to
+//! restore a task after loss of the datastore.
+//!
+//! An archive is produced by [`Transaction::export_task`](super::Transaction::export_task) and
+//! consumed by [`Transaction::import_task`](super::Transaction::import_task). Values stored
+//! encrypted in the datastore (e.g. the VDAF verify key and HPKE private keys) are held decrypted
+//! in a [`TaskArchive`], and are re-encrypted under the destination datastore's [`Crypter`] on
+//! import. A [`TaskArchive`] must therefore only leave the process once sealed into a
+//! [`SealedTaskArchive`], which is encrypted under a separate archive key.
+
+use super::{Crypter, Error, models::HpkeKeyState};
+use crate::task::AggregatorTask;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use janus_core::hpke::HpkeKeypair;
+use janus_messages::{Duration, Interval, TaskId, Time};
+use serde::{Deserialize, Serialize};
+
+/// The version of the task archive format written by this version of Janus. Archives of any other
+/// version are rejected.
+pub const TASK_ARCHIVE_VERSION: u32 = 1;
+
+/// The state of a single task, as exported from a datastore.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TaskArchive {
+    pub(super) task: AggregatorTask,
+    pub(super) hpke_keypairs: Vec<ArchivedHpkeKeypair>,
+    pub(super) client_reports: Vec<ArchivedClientReport>,
+    pub(super) outstanding_batches: Vec<ArchivedOutstandingBatch>,
+    pub(super) batch_aggregations: Vec<ArchivedBatchAggregation>,
+    pub(super) collection_jobs: Vec<ArchivedCollectionJob>,
+    pub(super) aggregate_share_jobs: Vec<ArchivedAggregateShareJob>,
+    #[serde(default)]
+    pub(super) dp_budget_ledger: Vec<ArchivedDpBudgetLedgerEntry>,
+}
+
+impl TaskArchive {
+    /// Returns the task stored in this archive.
+    pub fn task(&self) -> &AggregatorTask {
+        &self.task
+    }
+
+    /// Returns the number of HPKE keypairs stored in this archive.
+    pub fn hpke_keypair_count(&self) -> usize {
+        self.hpke_keypairs.len()
+    }
+
+    /// Returns the number of client reports stored in this archive, including reports which are
+    /// retained only for replay protection.
+    pub fn client_report_count(&self) -> usize {
+        self.client_reports.len()
+    }
+
+    /// Returns the number of unaggregated client reports stored in this archive, i.e. those whose
+    /// payloads were exported.
+    pub fn unaggregated_client_report_count(&self) -> usize {
+        self.client_reports
+            .iter()
+            .filter(|report| report.payload.is_some())
+            .count()
+    }
+
+    /// Returns the number of outstanding batches stored in this archive.
+    pub fn outstanding_batch_count(&self) -> usize {
+        self.outstanding_batches.len()
+    }
+
+    /// Returns the number of batch aggregation shards stored in this archive.
+    pub fn batch_aggregation_count(&self) -> usize {
+        self.batch_aggregations.len()
+    }
+
+    /// Returns the number of collection jobs stored in this archive.
+    pub fn collection_job_count(&self) -> usize {
+        self.collection_jobs.len()
+    }
+
+    /// Returns the number of aggregate share jobs stored in this archive.
+    pub fn aggregate_share_job_count(&self) -> usize {
+        self.aggregate_share_jobs.len()
+    }
+
+    /// Returns the number of differential privacy budget ledger entries stored in this archive.
+    pub fn dp_budget_ledger_entry_count(&self) -> usize {
+        self.dp_budget_ledger.len()
+    }
+
+    /// Encrypts this archive under the primary key of the given crypter.
+    pub fn seal(&self, crypter: &Crypter) -> Result<SealedTaskArchive, Error> {
+        let plaintext = serde_json::to_vec(self).map_err(|err| Error::User(err.into()))?;
+        let ciphertext = crypter.encrypt(
+            SealedTaskArchive::AAD_TABLE,
+            self.task.id().as_ref(),
+            &SealedTaskArchive::aad_column(TASK_ARCHIVE_VERSION),
+            &plaintext,
+        )?;
+        Ok(SealedTaskArchive {
+            version: TASK_ARCHIVE_VERSION,
+            task_id: *self.task.id(),
+            ciphertext: URL_SAFE_NO_PAD.encode(ciphertext),
+        })
+    }
+}
+
+/// An encrypted [`TaskArchive`], suitable for writing to storage outside of the datastore.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedTaskArchive {
+    version: u32,
+    task_id: TaskId,
+    /// The serialized [`TaskArchive`], encrypted under the archive key, in unpadded base64url.
+    ciphertext: String,
+}
+
+impl SealedTaskArchive {
+    const AAD_TABLE: &'static str = "task_archive";
+
+    fn aad_column(version: u32) -> String {
+        format!("archive_v{version}")
+    }
+
+    /// Returns the version of the archive format.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the ID of the task stored in the archive.
+    pub fn task_id(&self) -> &TaskId {
+        &self.task_id
+    }
+
+    /// Decrypts the archive using the given crypter, which must hold the key the archive was sealed
+    /// under.
+    pub fn open(&self, crypter: &Crypter) -> Result<TaskArchive, Error> {
+        if self.version != TASK_ARCHIVE_VERSION {
+            return Err(Error::User(
+                format!(
+                    "unsupported task archive version {} (expected {TASK_ARCHIVE_VERSION})",
+                    self.version
+                )
+                .into(),
+            ));
+        }
+        let plaintext = crypter.decrypt(
+            Self::AAD_TABLE,
+            self.task_id.as_ref(),
+            &Self::aad_column(self.version),
+            &URL_SAFE_NO_PAD.decode(&self.ciphertext)?,
+        )?;
+        let archive: TaskArchive =
+            serde_json::from_slice(&plaintext).map_err(|err| Error::User(err.into()))?;
+        if archive.task.id() != &self.task_id {
+            return Err(Error::DbState(format!(
+                "task archive for task {} contains task {}",
+                self.task_id,
+                archive.task.id()
+            )));
+        }
+        Ok(archive)
+    }
+}
+
+/// An HPKE keypair, along with its state in the datastore.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct ArchivedHpkeKeypair {
+    pub(super) keypair: HpkeKeypair,
+    pub(super) state: HpkeKeyState,
+    pub(super) last_state_change_at: Time,
+}
+
+/// A client report. Reports which had been aggregated or scrubbed, or whose payloads were not
+/// requested, are archived without a payload, and are imported only to provide replay protection.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct ArchivedClientReport {
+    #[serde(with = "base64_bytes")]
+    pub(super) report_id: Vec<u8>,
+    pub(super) client_timestamp: Time,
+    pub(super) payload: Option<ArchivedClientReportPayload>,
+}
+
+/// The payload of an unaggregated client report.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct ArchivedClientReportPayload {
+    #[serde(with = "base64_bytes")]
+    pub(super) public_extensions: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub(super) public_share: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub(super) leader_private_extensions: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub(super) leader_input_share: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub(super) helper_encrypted_input_share: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct ArchivedOutstandingBatch {
+    #[serde(with = "base64_bytes")]
+    pub(super) batch_id: Vec<u8>,
+    pub(super) time_bucket_start: Option<Time>,
+    /// The name of the batch's `OUTSTANDING_BATCH_STATE`.
+    pub(super) state: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct ArchivedBatchAggregation {
+    #[serde(with = "base64_bytes")]
+    pub(super) batch_identifier: Vec<u8>,
+    pub(super) batch_interval: Option<ArchivedInterval>,
+    #[serde(with = "base64_bytes")]
+    pub(super) aggregation_param: Vec<u8>,
+    pub(super) ord: i64,
+    pub(super) client_timestamp_interval: ArchivedInterval,
+    /// The name of the batch aggregation's `BATCH_AGGREGATION_STATE`.
+    pub(super) state: String,
+    #[serde(with = "base64_bytes_option")]
+    pub(super) aggregate_share: Option<Vec<u8>>,
+    pub(super) report_count: Option<i64>,
+    #[serde(with = "base64_bytes_option")]
+    pub(super) checksum: Option<Vec<u8>>,
+    pub(super) aggregation_jobs_created: Option<i64>,
+    pub(super) aggregation_jobs_terminated: Option<i64>,
+}
+
+/// A collection job. Leases are not archived; any in-flight lease is dropped on import.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct ArchivedCollectionJob {
+    #[serde(with = "base64_bytes")]
+    pub(super) collection_job_id: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub(super) query: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub(super) aggregation_param: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub(super) batch_identifier: Vec<u8>,
+    pub(super) batch_interval: Option<ArchivedInterval>,
+    /// The name of the collection job's `COLLECTION_JOB_STATE`.
+    pub(super) state: String,
+    pub(super) report_count: Option<i64>,
+    pub(super) client_timestamp_interval: Option<ArchivedInterval>,
+    #[serde(with = "base64_bytes_option")]
+    pub(super) helper_aggregate_share: Option<Vec<u8>>,
+    #[serde(with = "base64_bytes_option")]
+    pub(super) leader_aggregate_share: Option<Vec<u8>>,
+    pub(super) step_attempts: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct ArchivedAggregateShareJob {
+    #[serde(with = "base64_bytes")]
+    pub(super) batch_identifier: Vec<u8>,
+    pub(super) batch_interval: Option<ArchivedInterval>,
+    #[serde(with = "base64_bytes")]
+    pub(super) aggregation_param: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub(super) helper_aggregate_share: Vec<u8>,
+    pub(super) report_count: i64,
+    #[serde(with = "base64_bytes")]
+    pub(super) checksum: Vec<u8>,
+}
+
+/// A record of the differential privacy budget spent by one collection.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct ArchivedDpBudgetLedgerEntry {
+    #[serde(with = "base64_bytes")]
+    pub(super) batch_identifier: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub(super) aggregation_param: Vec<u8>,
+    pub(super) privacy_loss: f64,
+}
+
+/// A time interval, as stored in a `TSRANGE` column.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(super) struct ArchivedInterval {
+    start: Time,
+    duration: Duration,
+}
+
+impl From<Interval> for ArchivedInterval {
+    fn from(interval: Interval) -> Self {
+        Self {
+            start: *interval.start(),
+            duration: *interval.duration(),
+        }
+    }
+}
+
+impl TryFrom<ArchivedInterval> for Interval {
+    type Error = janus_messages::Error;
+
+    fn try_from(interval: ArchivedInterval) -> Result<Self, Self::Error> {
+        Interval::new(interval.start, interval.duration)
+    }
+}
+
+/// Serializes byte strings in unpadded base64url.
+mod base64_bytes {
+    use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub(super) fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        URL_SAFE_NO_PAD
+            .decode(String::deserialize(deserializer)?)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Like [`base64_bytes`], for optional byte strings.
+mod base64_bytes_option {
+    use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub(super) fn serialize<S: Serializer>(
+        bytes: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => serializer.serialize_some(&URL_SAFE_NO_PAD.encode(bytes)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|encoded| URL_SAFE_NO_PAD.decode(encoded).map_err(D::Error::custom))
+            .transpose()
+    }
+}
@@ -3,30 +3,37 @@
 use crate::{
     batch_mode::CollectableBatchMode,
     datastore::{
-        Crypter, Datastore, Error, RowExt, SUPPORTED_SCHEMA_VERSIONS, Transaction,
+        Crypter, Datastore, Error, MAX_JOB_HISTORY_ENTRIES, ReplicaStatus, RowExt,
+        SUPPORTED_SCHEMA_VERSIONS, Transaction,
         models::{
             AcquiredAggregationJob, AcquiredCollectionJob, AggregateShareJob, AggregationJob,
-            AggregationJobState, BatchAggregation, BatchAggregationState, CollectionJob,
-            CollectionJobState, CollectionJobStateCode, HpkeKeyState, HpkeKeypair,
-            LeaderStoredReport, Lease, OutstandingBatch, ReportAggregation,
-            ReportAggregationMetadata, ReportAggregationMetadataState, ReportAggregationState,
-            SqlInterval, TaskAggregationCounter, TaskUploadCounter,
+            AggregationJobState, ApiToken, ApiTokenScope, AuditLogEntry, AuditLogFilter,
+            AuditLogOutcome, BatchAggregation, BatchAggregationState, CollectionJob,
+            CollectionJobState, CollectionJobStateCode, DpBudgetUsage, HpkeKeyState, HpkeKeypair,
+            JobHistoryEntry, JobStepOutcome, JobStepRecord, LeaderStoredReport, Lease,
+            OutstandingBatch, ReportAggregation, ReportAggregationMetadata,
+            ReportAggregationMetadataState, ReportAggregationState, SqlInterval,
+            TaskAggregationCounter, TaskUploadCounter,
         },
         schema_versions_template,
+        task_archive::{SealedTaskArchive, TASK_ARCHIVE_VERSION},
         test_util::{
             EphemeralDatastore, EphemeralDatastoreBuilder, TEST_DATASTORE_MAX_TRANSACTION_RETRIES,
             ephemeral_datastore_schema_version, generate_aead_key,
         },
     },
-    task::{self, AggregationMode, AggregatorTask, test_util::TaskBuilder},
+    task::{self, AggregationMode, AggregatorTask, DpBudget, TaskState, test_util::TaskBuilder},
     taskprov::test_util::PeerAggregatorBuilder,
     test_util::noop_meter,
 };
 use assert_matches::assert_matches;
 use async_trait::async_trait;
 use chrono::NaiveDate;
-use futures::future::try_join_all;
+use deadpool_postgres::{Manager, Pool, Timeouts};
+use futures::future::{join_all, try_join_all};
 use janus_core::{
+    auth_tokens::{AuthenticationToken, AuthenticationTokenHash},
+    dp::DpStrategyConfig,
     hpke::{self, HpkeApplicationInfo, Label},
     test_util::{install_test_trace_subscriber, run_vdaf},
     time::{Clock, DurationExt, IntervalExt, MockClock, TimeExt},
@@ -41,9 +48,6 @@ use janus_messages::{
 };
 use prio::{
     codec::{Decode, Encode},
-    dp::{
-        DifferentialPrivacyStrategy, PureDpBudget, Rational, distributions::PureDpDiscreteLaplace,
-    },
     topology::ping_pong::PingPongMessage,
     vdaf::{dummy, prio3::Prio3Count},
 };
@@ -54,11 +58,12 @@ use std::{
     ops::RangeInclusive,
     sync::{
         Arc,
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
     },
     time::Duration as StdDuration,
 };
-use tokio::{time::timeout, try_join};
+use tokio::{sync::Barrier, time::timeout, try_join};
+use tokio_postgres::{NoTls, error::SqlState};
 use url::Url;
 
 // This function is only used when there are multiple supported versions.
@@ -138,6 +143,123 @@ async fn retry_limit() {
     }
 }
 
+#[tokio::test]
+async fn read_only_tx() {
+    install_test_trace_subscriber();
+    let ephemeral_datastore = EphemeralDatastoreBuilder::new().build().await;
+
+    // The ephemeral database serves as its own replica. It isn't in recovery, so it never lags.
+    let ds = ephemeral_datastore
+        .datastore(MockClock::default())
+        .await
+        .with_replica(ephemeral_datastore.pool(), StdDuration::ZERO);
+
+    let task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .build()
+    .leader_view()
+    .unwrap();
+    ds.put_aggregator_task(&task).await.unwrap();
+
+    let task_ids = ds
+        .run_read_only_tx("test-read-only", |tx| {
+            Box::pin(async move { tx.get_task_ids(None).await })
+        })
+        .await
+        .unwrap();
+    assert_eq!(task_ids, Vec::from([*task.id()]));
+
+    // Writes are rejected.
+    let result = ds
+        .run_read_only_tx("test-read-only", |tx| {
+            let task_id = *task.id();
+            Box::pin(async move { tx.delete_task(&task_id).await })
+        })
+        .await;
+    assert_matches!(result, Err(Error::Db(err)) => {
+        assert_eq!(err.code(), Some(&SqlState::READ_ONLY_SQL_TRANSACTION));
+    });
+
+    // If the replica can't be reached, read-only transactions fall back to the primary.
+    let unreachable_pool = Pool::builder(Manager::new(
+        "postgres://postgres@127.0.0.1:1/postgres".parse().unwrap(),
+        NoTls,
+    ))
+    .runtime(deadpool::Runtime::Tokio1)
+    .timeouts(Timeouts {
+        wait: Some(StdDuration::from_secs(1)),
+        create: Some(StdDuration::from_secs(1)),
+        recycle: Some(StdDuration::from_secs(1)),
+    })
+    .build()
+    .unwrap();
+    let ds = ephemeral_datastore
+        .datastore(MockClock::default())
+        .await
+        .with_replica(unreachable_pool, StdDuration::from_secs(60));
+
+    for _ in 0..2 {
+        let task_ids = ds
+            .run_read_only_tx("test-read-only", |tx| {
+                Box::pin(async move { tx.get_task_ids(None).await })
+            })
+            .await
+            .unwrap();
+        assert_eq!(task_ids, Vec::from([*task.id()]));
+    }
+}
+
+#[test]
+fn replica_status_check() {
+    let max_staleness = StdDuration::from_secs(5);
+
+    // A database which isn't a replica is always healthy.
+    assert_matches!(
+        ReplicaStatus {
+            in_recovery: false,
+            streaming: false,
+            lag: StdDuration::ZERO,
+        }
+        .check(max_staleness),
+        Ok(())
+    );
+
+    // A replica streaming from the primary is healthy while its lag is within the bound.
+    assert_matches!(
+        ReplicaStatus {
+            in_recovery: true,
+            streaming: true,
+            lag: StdDuration::from_secs(5),
+        }
+        .check(max_staleness),
+        Ok(())
+    );
+    assert_matches!(
+        ReplicaStatus {
+            in_recovery: true,
+            streaming: true,
+            lag: StdDuration::from_secs(6),
+        }
+        .check(max_staleness),
+        Err(("stale", _))
+    );
+
+    // A replica which has lost its connection to the primary is unhealthy, even though it has
+    // replayed all of the WAL it received.
+    assert_matches!(
+        ReplicaStatus {
+            in_recovery: true,
+            streaming: false,
+            lag: StdDuration::ZERO,
+        }
+        .check(max_staleness),
+        Err(("disconnected", _))
+    );
+}
+
 #[rstest_reuse::apply(schema_versions_template)]
 #[tokio::test]
 async fn roundtrip_task(ephemeral_datastore: EphemeralDatastore) {
@@ -147,7 +269,12 @@ async fn roundtrip_task(ephemeral_datastore: EphemeralDatastore) {
     // Insert tasks, check that they can be retrieved by ID.
     let mut want_tasks = HashMap::new();
     for (vdaf, role) in [
-        (VdafInstance::Prio3Count, Role::Leader),
+        (
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
+            Role::Leader,
+        ),
         (
             VdafInstance::Prio3SumVec {
                 bits: 1,
@@ -163,9 +290,7 @@ async fn roundtrip_task(ephemeral_datastore: EphemeralDatastore) {
                 length: 8,
                 chunk_length: 3,
                 dp_strategy: vdaf_dp_strategies::Prio3SumVec::PureDpDiscreteLaplace(
-                    PureDpDiscreteLaplace::from_budget(
-                        PureDpBudget::new(Rational::from_unsigned(1u128, 4u128).unwrap()).unwrap(),
-                    ),
+                    DpStrategyConfig::from_epsilon(1, 4).unwrap(),
                 ),
             },
             Role::Leader,
@@ -182,12 +307,14 @@ async fn roundtrip_task(ephemeral_datastore: EphemeralDatastore) {
         (
             VdafInstance::Prio3Sum {
                 max_measurement: 4096,
+                dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
             },
             Role::Helper,
         ),
         (
             VdafInstance::Prio3Sum {
                 max_measurement: 4096,
+                dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
             },
             Role::Helper,
         ),
@@ -217,6 +344,8 @@ async fn roundtrip_task(ephemeral_datastore: EphemeralDatastore) {
         .with_task_end(Some(Time::from_seconds_since_epoch(4000)))
         .with_time_precision(TIME_PRECISION)
         .with_report_expiry_age(Some(Duration::from_seconds(3600)))
+        .with_report_retention_window(Some(Duration::from_seconds(600)))
+        .with_dp_budget(Some(DpBudget::new(2.5).unwrap()))
         .build()
         .view_for_role(role)
         .unwrap();
@@ -316,7 +445,9 @@ async fn update_task_end(ephemeral_datastore: EphemeralDatastore) {
     let task = TaskBuilder::new(
         task::BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_time_precision(TIME_PRECISION)
     .with_task_end(Some(Time::from_seconds_since_epoch(1000)))
@@ -361,6 +492,109 @@ async fn update_task_end(ephemeral_datastore: EphemeralDatastore) {
     .unwrap();
 }
 
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn update_task_state(ephemeral_datastore: EphemeralDatastore) {
+    install_test_trace_subscriber();
+    let ds = ephemeral_datastore.datastore(MockClock::default()).await;
+
+    let task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
+    )
+    .build()
+    .leader_view()
+    .unwrap();
+    ds.put_aggregator_task(&task).await.unwrap();
+
+    ds.run_unnamed_tx(|tx| {
+        let task_id = *task.id();
+        Box::pin(async move {
+            let task = tx.get_aggregator_task(&task_id).await.unwrap().unwrap();
+            assert_eq!(task.state(), &TaskState::Active);
+
+            for state in [TaskState::Paused, TaskState::Draining, TaskState::Archived] {
+                tx.update_task_state(&task_id, &state).await.unwrap();
+
+                let task = tx.get_aggregator_task(&task_id).await.unwrap().unwrap();
+                assert_eq!(task.state(), &state);
+            }
+
+            let result = tx.update_task_state(&random(), &TaskState::Paused).await;
+            assert_matches!(result, Err(Error::MutationTargetNotFound));
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+}
+
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn update_task_auth_tokens(ephemeral_datastore: EphemeralDatastore) {
+    install_test_trace_subscriber();
+    let ds = ephemeral_datastore.datastore(MockClock::default()).await;
+
+    for role in [Role::Leader, Role::Helper] {
+        let task_builder = TaskBuilder::new(
+            task::BatchMode::TimeInterval,
+            AggregationMode::Synchronous,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
+        )
+        .with_time_precision(TIME_PRECISION);
+        let task = task_builder.clone().build().view_for_role(role).unwrap();
+        let rotated_task = task_builder
+            .with_aggregator_auth_token(random())
+            .with_collector_auth_token(random())
+            .build()
+            .view_for_role(role)
+            .unwrap();
+        ds.put_aggregator_task(&task).await.unwrap();
+
+        ds.run_unnamed_tx(|tx| {
+            let rotated_task = rotated_task.clone();
+            Box::pin(async move {
+                tx.update_task_auth_tokens(&rotated_task).await.unwrap();
+
+                let got_task = tx
+                    .get_aggregator_task(rotated_task.id())
+                    .await
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(got_task, rotated_task);
+
+                Ok(())
+            })
+        })
+        .await
+        .unwrap();
+    }
+
+    let task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
+    )
+    .build()
+    .leader_view()
+    .unwrap();
+    let result = ds
+        .run_unnamed_tx(|tx| {
+            let task = task.clone();
+            Box::pin(async move { tx.update_task_auth_tokens(&task).await })
+        })
+        .await;
+    assert_matches!(result, Err(Error::MutationTargetNotFound));
+}
+
 #[rstest_reuse::apply(schema_versions_template)]
 #[tokio::test]
 async fn put_task_invalid_aggregator_auth_tokens(ephemeral_datastore: EphemeralDatastore) {
@@ -370,7 +604,9 @@ async fn put_task_invalid_aggregator_auth_tokens(ephemeral_datastore: EphemeralD
     let task = TaskBuilder::new(
         task::BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .build()
     .leader_view()
@@ -414,7 +650,9 @@ async fn put_task_invalid_collector_auth_tokens(ephemeral_datastore: EphemeralDa
     let task = TaskBuilder::new(
         task::BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .build()
     .leader_view()
@@ -661,6 +899,101 @@ async fn roundtrip_report(ephemeral_datastore: EphemeralDatastore) {
     assert_eq!(None, retrieved_report);
 }
 
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn put_client_report_concurrent_duplicates(ephemeral_datastore: EphemeralDatastore) {
+    install_test_trace_subscriber();
+    let clock = MockClock::default();
+    let ds = ephemeral_datastore.datastore(clock.clone()).await;
+
+    let task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .with_report_expiry_age(Some(REPORT_EXPIRY_AGE))
+    .with_time_precision(Duration::from_seconds(1))
+    .build()
+    .leader_view()
+    .unwrap();
+    let task_id = *task.id();
+    ds.put_aggregator_task(&task).await.unwrap();
+
+    // Two uploads of the same report ID with different client timestamps, which may be stored in
+    // different partitions of client_reports.
+    let report_id = random();
+    let reports = [
+        clock.now().sub(&Duration::from_seconds(1)).unwrap(),
+        clock.now().sub(&Duration::from_seconds(2)).unwrap(),
+    ]
+    .map(|time| {
+        LeaderStoredReport::<0, dummy::Vdaf>::new(
+            task_id,
+            ReportMetadata::new(report_id, time, Vec::new()),
+            (), // public share
+            Vec::new(),
+            dummy::InputShare::default(), // leader input share
+            HpkeCiphertext::new(
+                HpkeConfigId::from(13),
+                Vec::from("encapsulated_context"),
+                Vec::from("payload"),
+            ),
+        )
+    });
+
+    // Both transactions take their snapshots before either writes the report, so neither can see
+    // the other's write.
+    let barrier = Arc::new(Barrier::new(reports.len()));
+    let results = join_all(reports.iter().map(|report| {
+        let ds = &ds;
+        let barrier = Arc::clone(&barrier);
+        let first_attempt = Arc::new(AtomicBool::new(true));
+        async move {
+            ds.run_unnamed_tx(|tx| {
+                let report = report.clone();
+                let barrier = Arc::clone(&barrier);
+                let first_attempt = Arc::clone(&first_attempt);
+                Box::pin(async move {
+                    tx.get_aggregator_task(&task_id).await?;
+                    if first_attempt.swap(false, Ordering::SeqCst) {
+                        barrier.wait().await;
+                    }
+                    tx.put_client_report(&report).await
+                })
+            })
+            .await
+        }
+    }))
+    .await;
+
+    // Exactly one upload succeeds; the other is rejected as a duplicate.
+    assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+    assert_eq!(
+        results
+            .iter()
+            .filter(|result| matches!(result, Err(Error::MutationTargetAlreadyExists)))
+            .count(),
+        1
+    );
+
+    ds.run_unnamed_tx(|tx| {
+        Box::pin(async move {
+            let row = tx
+                .query_one(
+                    "--
+SELECT COUNT(*) AS count FROM client_reports WHERE report_id = $1",
+                    &[/* report_id */ &report_id.as_ref()],
+                )
+                .await
+                .unwrap();
+            assert_eq!(row.get::<_, i64>("count"), 1);
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+}
+
 #[rstest_reuse::apply(schema_versions_template)]
 #[tokio::test]
 async fn report_not_found(ephemeral_datastore: EphemeralDatastore) {
@@ -912,18 +1245,18 @@ async fn get_unaggregated_client_reports_for_task(ephemeral_datastore: Ephemeral
 
 #[rstest_reuse::apply(schema_versions_template)]
 #[tokio::test]
-async fn get_unaggregated_client_report_ids_with_agg_param_for_task(
-    ephemeral_datastore: EphemeralDatastore,
-) {
+async fn get_oldest_unaggregated_client_report_time(ephemeral_datastore: EphemeralDatastore) {
     install_test_trace_subscriber();
-    let ds = ephemeral_datastore.datastore(MockClock::default()).await;
 
+    let clock = MockClock::new(OLDEST_ALLOWED_REPORT_TIMESTAMP);
+    let ds = ephemeral_datastore.datastore(clock.clone()).await;
     let task = TaskBuilder::new(
         task::BatchMode::TimeInterval,
         AggregationMode::Synchronous,
         VdafInstance::Fake { rounds: 1 },
     )
-    .with_time_precision(Duration::from_seconds(100))
+    .with_report_expiry_age(Some(REPORT_EXPIRY_AGE))
+    .with_time_precision(TIME_PRECISION)
     .build()
     .leader_view()
     .unwrap();
@@ -932,59 +1265,169 @@ async fn get_unaggregated_client_report_ids_with_agg_param_for_task(
         AggregationMode::Synchronous,
         VdafInstance::Fake { rounds: 1 },
     )
-    .with_time_precision(Duration::from_seconds(100))
+    .with_time_precision(TIME_PRECISION)
     .build()
     .leader_view()
     .unwrap();
 
-    let first_unaggregated_report =
-        LeaderStoredReport::new_dummy(*task.id(), Time::from_seconds_since_epoch(12300));
-    let second_unaggregated_report =
-        LeaderStoredReport::new_dummy(*task.id(), Time::from_seconds_since_epoch(12400));
     let aggregated_report =
-        LeaderStoredReport::new_dummy(*task.id(), Time::from_seconds_since_epoch(12500));
+        LeaderStoredReport::new_dummy(*task.id(), OLDEST_ALLOWED_REPORT_TIMESTAMP);
     let unrelated_report =
-        LeaderStoredReport::new_dummy(*unrelated_task.id(), Time::from_seconds_since_epoch(12600));
+        LeaderStoredReport::new_dummy(*unrelated_task.id(), OLDEST_ALLOWED_REPORT_TIMESTAMP);
+    let first_unaggregated_report =
+        LeaderStoredReport::new_dummy(*task.id(), OLDEST_ALLOWED_REPORT_TIMESTAMP);
+    let second_unaggregated_report =
+        LeaderStoredReport::new_dummy(*task.id(), OLDEST_ALLOWED_REPORT_TIMESTAMP);
 
-    // Set up state.
+    // Store each report at a different time, with the unaggregated reports stored last.
     ds.run_unnamed_tx(|tx| {
-        let (
-            task,
-            unrelated_task,
-            first_unaggregated_report,
-            second_unaggregated_report,
-            aggregated_report,
-            unrelated_report,
-        ) = (
-            task.clone(),
-            unrelated_task.clone(),
-            first_unaggregated_report.clone(),
-            second_unaggregated_report.clone(),
-            aggregated_report.clone(),
-            unrelated_report.clone(),
-        );
+        let task = task.clone();
+        let unrelated_task = unrelated_task.clone();
+        let aggregated_report = aggregated_report.clone();
+        let unrelated_report = unrelated_report.clone();
 
         Box::pin(async move {
-            tx.put_aggregator_task(&task).await?;
-            tx.put_aggregator_task(&unrelated_task).await?;
-
-            tx.put_client_report(&first_unaggregated_report).await?;
-            tx.put_client_report(&second_unaggregated_report).await?;
-            tx.put_client_report(&aggregated_report).await?;
-            tx.put_client_report(&unrelated_report).await?;
+            tx.put_aggregator_task(&task).await.unwrap();
+            tx.put_aggregator_task(&unrelated_task).await.unwrap();
+            tx.put_client_report(&aggregated_report).await.unwrap();
+            tx.put_client_report(&unrelated_report).await.unwrap();
+            tx.mark_report_aggregated(task.id(), aggregated_report.metadata().id())
+                .await
+                .unwrap();
 
-            // There are no client reports submitted under this task, so we shouldn't see
-            // this aggregation parameter at all.
-            tx.put_collection_job(&CollectionJob::<0, TimeInterval, dummy::Vdaf>::new(
-                *unrelated_task.id(),
-                random(),
-                Query::<TimeInterval>::new(
-                    Interval::new(
-                        Time::from_seconds_since_epoch(0),
-                        Duration::from_hours(8).unwrap(),
-                    )
+            assert_eq!(
+                tx.get_oldest_unaggregated_client_report_time(task.id())
+                    .await
                     .unwrap(),
-                ),
+                None
+            );
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+
+    clock.advance(&Duration::from_seconds(10));
+    let first_stored_at = clock.now();
+    ds.run_unnamed_tx(|tx| {
+        let report = first_unaggregated_report.clone();
+        Box::pin(async move { tx.put_client_report(&report).await })
+    })
+    .await
+    .unwrap();
+
+    clock.advance(&Duration::from_seconds(10));
+    let second_stored_at = clock.now();
+    ds.run_unnamed_tx(|tx| {
+        let report = second_unaggregated_report.clone();
+        Box::pin(async move { tx.put_client_report(&report).await })
+    })
+    .await
+    .unwrap();
+
+    ds.run_unnamed_tx(|tx| {
+        let task = task.clone();
+        let first_unaggregated_report = first_unaggregated_report.clone();
+
+        Box::pin(async move {
+            assert_eq!(
+                tx.get_oldest_unaggregated_client_report_time(task.id())
+                    .await
+                    .unwrap(),
+                Some(first_stored_at)
+            );
+
+            tx.mark_report_aggregated(task.id(), first_unaggregated_report.metadata().id())
+                .await
+                .unwrap();
+            assert_eq!(
+                tx.get_oldest_unaggregated_client_report_time(task.id())
+                    .await
+                    .unwrap(),
+                Some(second_stored_at)
+            );
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+}
+
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn get_unaggregated_client_report_ids_with_agg_param_for_task(
+    ephemeral_datastore: EphemeralDatastore,
+) {
+    install_test_trace_subscriber();
+    let ds = ephemeral_datastore.datastore(MockClock::default()).await;
+
+    let task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .with_time_precision(Duration::from_seconds(100))
+    .build()
+    .leader_view()
+    .unwrap();
+    let unrelated_task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .with_time_precision(Duration::from_seconds(100))
+    .build()
+    .leader_view()
+    .unwrap();
+
+    let first_unaggregated_report =
+        LeaderStoredReport::new_dummy(*task.id(), Time::from_seconds_since_epoch(12300));
+    let second_unaggregated_report =
+        LeaderStoredReport::new_dummy(*task.id(), Time::from_seconds_since_epoch(12400));
+    let aggregated_report =
+        LeaderStoredReport::new_dummy(*task.id(), Time::from_seconds_since_epoch(12500));
+    let unrelated_report =
+        LeaderStoredReport::new_dummy(*unrelated_task.id(), Time::from_seconds_since_epoch(12600));
+
+    // Set up state.
+    ds.run_unnamed_tx(|tx| {
+        let (
+            task,
+            unrelated_task,
+            first_unaggregated_report,
+            second_unaggregated_report,
+            aggregated_report,
+            unrelated_report,
+        ) = (
+            task.clone(),
+            unrelated_task.clone(),
+            first_unaggregated_report.clone(),
+            second_unaggregated_report.clone(),
+            aggregated_report.clone(),
+            unrelated_report.clone(),
+        );
+
+        Box::pin(async move {
+            tx.put_aggregator_task(&task).await?;
+            tx.put_aggregator_task(&unrelated_task).await?;
+
+            tx.put_client_report(&first_unaggregated_report).await?;
+            tx.put_client_report(&second_unaggregated_report).await?;
+            tx.put_client_report(&aggregated_report).await?;
+            tx.put_client_report(&unrelated_report).await?;
+
+            // There are no client reports submitted under this task, so we shouldn't see
+            // this aggregation parameter at all.
+            tx.put_collection_job(&CollectionJob::<0, TimeInterval, dummy::Vdaf>::new(
+                *unrelated_task.id(),
+                random(),
+                Query::<TimeInterval>::new(
+                    Interval::new(
+                        Time::from_seconds_since_epoch(0),
+                        Duration::from_hours(8).unwrap(),
+                    )
+                    .unwrap(),
+                ),
                 dummy::AggregationParam(255),
                 Interval::new(
                     Time::from_seconds_since_epoch(0),
@@ -1215,6 +1658,91 @@ async fn get_unaggregated_client_report_ids_with_agg_param_for_task(
     assert_eq!(got_reports, want_reports);
 }
 
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn check_report_aggregated_with_aggregation_param(ephemeral_datastore: EphemeralDatastore) {
+    install_test_trace_subscriber();
+    let ds = ephemeral_datastore.datastore(MockClock::default()).await;
+
+    let task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .with_time_precision(Duration::from_seconds(100))
+    .build()
+    .helper_view()
+    .unwrap();
+    let report_id = random();
+    let report_time = Time::from_seconds_since_epoch(12300);
+
+    ds.run_unnamed_tx(|tx| {
+        let task = task.clone();
+        Box::pin(async move {
+            tx.put_aggregator_task(&task).await.unwrap();
+            tx.put_scrubbed_report(task.id(), &report_id, &report_time)
+                .await
+                .unwrap();
+
+            let aggregation_job_id = random();
+            tx.put_aggregation_job(&AggregationJob::<0, TimeInterval, dummy::Vdaf>::new(
+                *task.id(),
+                aggregation_job_id,
+                dummy::AggregationParam(0),
+                (),
+                Interval::new(report_time, *task.time_precision()).unwrap(),
+                AggregationJobState::Finished,
+                AggregationJobStep::from(1),
+            ))
+            .await
+            .unwrap();
+            tx.put_report_aggregation::<0, dummy::Vdaf>(&ReportAggregation::new(
+                *task.id(),
+                aggregation_job_id,
+                report_id,
+                report_time,
+                0,
+                None,
+                ReportAggregationState::Finished,
+            ))
+            .await
+            .unwrap();
+
+            assert!(
+                tx.check_report_aggregated_with_aggregation_param::<0, dummy::Vdaf>(
+                    task.id(),
+                    &report_id,
+                    &dummy::AggregationParam(0),
+                )
+                .await
+                .unwrap()
+            );
+            assert!(
+                !tx.check_report_aggregated_with_aggregation_param::<0, dummy::Vdaf>(
+                    task.id(),
+                    &report_id,
+                    &dummy::AggregationParam(1),
+                )
+                .await
+                .unwrap()
+            );
+            assert!(
+                !tx.check_report_aggregated_with_aggregation_param::<0, dummy::Vdaf>(
+                    task.id(),
+                    &random(),
+                    &dummy::AggregationParam(0),
+                )
+                .await
+                .unwrap()
+            );
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+}
+
 #[rstest_reuse::apply(schema_versions_template)]
 #[tokio::test]
 async fn count_client_reports_for_interval(ephemeral_datastore: EphemeralDatastore) {
@@ -1522,7 +2050,9 @@ async fn roundtrip_scrubbed_report(ephemeral_datastore: EphemeralDatastore) {
     let task = TaskBuilder::new(
         task::BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_time_precision(TIME_PRECISION)
     .with_report_expiry_age(Some(report_expiry_age))
@@ -1936,13 +2466,16 @@ async fn aggregation_job_acquire_release(ephemeral_datastore: EphemeralDatastore
     const LEASE_DURATION: StdDuration = StdDuration::from_secs(300);
     let clock = MockClock::new(OLDEST_ALLOWED_REPORT_TIMESTAMP);
     let ds = Arc::new(ephemeral_datastore.datastore(clock.clone()).await);
+    let aggregation_job_created_at = clock.now();
 
     const AGGREGATION_JOB_COUNT: usize = 10;
     const TIME_PRECISION: Duration = Duration::from_seconds(200);
     let leader_task = TaskBuilder::new(
         task::BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_report_expiry_age(Some(REPORT_EXPIRY_AGE))
     .with_time_precision(TIME_PRECISION)
@@ -1952,7 +2485,9 @@ async fn aggregation_job_acquire_release(ephemeral_datastore: EphemeralDatastore
     let helper_task = TaskBuilder::new(
         task::BatchMode::TimeInterval,
         AggregationMode::Asynchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .with_time_precision(TIME_PRECISION)
     .build()
@@ -2104,7 +2639,10 @@ async fn aggregation_job_acquire_release(ephemeral_datastore: EphemeralDatastore
                     *task_id,
                     *aggregation_job_id,
                     task::BatchMode::TimeInterval,
-                    VdafInstance::Prio3Count,
+                    VdafInstance::Prio3Count {
+                        dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                    },
+                    aggregation_job_created_at,
                 ),
                 want_expiry_time,
             )
@@ -2255,7 +2793,10 @@ async fn aggregation_job_acquire_release(ephemeral_datastore: EphemeralDatastore
                     *task_id,
                     *aggregation_job_id,
                     task::BatchMode::TimeInterval,
-                    VdafInstance::Prio3Count,
+                    VdafInstance::Prio3Count {
+                        dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                    },
+                    aggregation_job_created_at,
                 ),
                 want_expiry_time,
             )
@@ -2424,55 +2965,495 @@ async fn get_aggregation_jobs_for_task(ephemeral_datastore: EphemeralDatastore)
     ]);
 
     ds.run_unnamed_tx(|tx| {
-        let (task, want_agg_jobs) = (task.clone(), want_agg_jobs.clone());
+        let (task, want_agg_jobs) = (task.clone(), want_agg_jobs.clone());
+        Box::pin(async move {
+            tx.put_aggregator_task(&task).await.unwrap();
+
+            for agg_job in want_agg_jobs {
+                tx.put_aggregation_job(&agg_job).await.unwrap();
+            }
+
+            // Also write an unrelated aggregation job with a different task ID to check that it
+            // is not returned.
+            let unrelated_task = TaskBuilder::new(
+                task::BatchMode::LeaderSelected {
+                    batch_time_window_size: None,
+                },
+                AggregationMode::Synchronous,
+                VdafInstance::Fake { rounds: 1 },
+            )
+            .build()
+            .leader_view()
+            .unwrap();
+            tx.put_aggregator_task(&unrelated_task).await.unwrap();
+            tx.put_aggregation_job(&AggregationJob::<0, LeaderSelected, dummy::Vdaf>::new(
+                *unrelated_task.id(),
+                random(),
+                dummy::AggregationParam(82),
+                random(),
+                Interval::new(Time::from_seconds_since_epoch(0), *task.time_precision()).unwrap(),
+                AggregationJobState::Active,
+                AggregationJobStep::from(0),
+            ))
+            .await
+        })
+    })
+    .await
+    .unwrap();
+
+    // Run.
+    want_agg_jobs.sort_by_key(|agg_job| *agg_job.id());
+    let mut got_agg_jobs = ds
+        .run_unnamed_tx(|tx| {
+            let task = task.clone();
+            Box::pin(async move { tx.get_aggregation_jobs_for_task(task.id()).await })
+        })
+        .await
+        .unwrap();
+    got_agg_jobs.sort_by_key(|agg_job| *agg_job.id());
+
+    // Verify.
+    assert_eq!(want_agg_jobs, got_agg_jobs);
+}
+
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn abandoned_aggregation_job_recovery(ephemeral_datastore: EphemeralDatastore) {
+    install_test_trace_subscriber();
+
+    let clock = MockClock::new(OLDEST_ALLOWED_REPORT_TIMESTAMP);
+    let ds = ephemeral_datastore.datastore(clock.clone()).await;
+
+    let task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .with_report_expiry_age(Some(REPORT_EXPIRY_AGE))
+    .with_time_precision(TIME_PRECISION)
+    .build()
+    .leader_view()
+    .unwrap();
+    let batch_interval = Interval::new(OLDEST_ALLOWED_REPORT_TIMESTAMP, TIME_PRECISION).unwrap();
+    let aggregation_param = dummy::AggregationParam(0);
+
+    // One report has not yet been sent to the helper, and one has finished aggregation.
+    let init_report = LeaderStoredReport::new_dummy(*task.id(), OLDEST_ALLOWED_REPORT_TIMESTAMP);
+    let finished_report =
+        LeaderStoredReport::new_dummy(*task.id(), OLDEST_ALLOWED_REPORT_TIMESTAMP);
+    let aggregation_job = AggregationJob::<0, TimeInterval, dummy::Vdaf>::new(
+        *task.id(),
+        random(),
+        aggregation_param,
+        (),
+        batch_interval,
+        AggregationJobState::Abandoned,
+        AggregationJobStep::from(0),
+    );
+    let init_report_aggregation =
+        init_report.as_leader_init_report_aggregation(*aggregation_job.id(), 0);
+    let finished_report_aggregation = finished_report
+        .as_leader_init_report_aggregation(*aggregation_job.id(), 1)
+        .with_state(ReportAggregationState::Finished);
+
+    ds.run_unnamed_tx(|tx| {
+        let task = task.clone();
+        let (init_report, finished_report) = (init_report.clone(), finished_report.clone());
+        let aggregation_job = aggregation_job.clone();
+        let init_report_aggregation = init_report_aggregation.clone();
+        let finished_report_aggregation = finished_report_aggregation.clone();
+        Box::pin(async move {
+            tx.put_aggregator_task(&task).await.unwrap();
+            for report in [&init_report, &finished_report] {
+                tx.put_client_report(report).await.unwrap();
+                tx.mark_report_aggregated(task.id(), report.metadata().id())
+                    .await
+                    .unwrap();
+                tx.scrub_client_report(task.id(), report.metadata().id())
+                    .await
+                    .unwrap();
+            }
+            tx.put_aggregation_job(&aggregation_job).await.unwrap();
+            tx.put_report_aggregation(&init_report_aggregation)
+                .await
+                .unwrap();
+            tx.put_report_aggregation(&finished_report_aggregation)
+                .await
+                .unwrap();
+            tx.put_batch_aggregation(&BatchAggregation::<0, TimeInterval, dummy::Vdaf>::new(
+                *task.id(),
+                batch_interval,
+                aggregation_param,
+                0,
+                Interval::EMPTY,
+                BatchAggregationState::Aggregating {
+                    aggregate_share: None,
+                    report_count: 0,
+                    checksum: ReportIdChecksum::default(),
+                    aggregation_jobs_created: 1,
+                    aggregation_jobs_terminated: 1,
+                },
+            ))
+            .await
+            .unwrap();
+            tx.set_aggregation_job_last_error(task.id(), aggregation_job.id(), "helper went away")
+                .await
+                .unwrap();
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+
+    ds.run_unnamed_tx(|tx| {
+        let task = task.clone();
+        let aggregation_job = aggregation_job.clone();
+        Box::pin(async move {
+            let abandoned_jobs = tx
+                .get_abandoned_aggregation_jobs(Some(task.id()))
+                .await
+                .unwrap();
+            assert_eq!(abandoned_jobs.len(), 1);
+            assert_eq!(abandoned_jobs[0].task_id(), task.id());
+            assert_eq!(abandoned_jobs[0].aggregation_job_id(), aggregation_job.id());
+            assert_eq!(abandoned_jobs[0].last_error(), Some("helper went away"));
+
+            // Requeueing the job reactivates it, and it is once again counted as outstanding in its
+            // batch.
+            tx.requeue_abandoned_aggregation_job(task.id(), aggregation_job.id())
+                .await
+                .unwrap();
+            assert_matches!(
+                tx.requeue_abandoned_aggregation_job(task.id(), aggregation_job.id())
+                    .await,
+                Err(Error::MutationTargetNotFound)
+            );
+            assert!(
+                tx.get_abandoned_aggregation_jobs(None)
+                    .await
+                    .unwrap()
+                    .is_empty()
+            );
+            assert_eq!(
+                tx.get_aggregation_job::<0, TimeInterval, dummy::Vdaf>(
+                    task.id(),
+                    aggregation_job.id()
+                )
+                .await
+                .unwrap()
+                .unwrap()
+                .state(),
+                &AggregationJobState::Active
+            );
+            assert_eq!(
+                tx.get_batch_aggregation_job_count_for_batch::<0, TimeInterval, dummy::Vdaf>(
+                    task.id(),
+                    &batch_interval,
+                    &aggregation_param,
+                )
+                .await
+                .unwrap(),
+                (1, 0)
+            );
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+
+    ds.run_unnamed_tx(|tx| {
+        let task = task.clone();
+        let init_report = init_report.clone();
+        let aggregation_job = aggregation_job.clone();
+        let finished_report_aggregation = finished_report_aggregation.clone();
+        Box::pin(async move {
+            let vdaf = dummy::Vdaf::default();
+
+            // Failing the job returns the report which had not yet been sent to the helper to the
+            // pool of unaggregated reports.
+            let report_ids = tx
+                .fail_aggregation_job(task.id(), aggregation_job.id())
+                .await
+                .unwrap();
+            assert_eq!(report_ids, Vec::from([*init_report.metadata().id()]));
+
+            assert_eq!(
+                tx.get_aggregation_job::<0, TimeInterval, dummy::Vdaf>(
+                    task.id(),
+                    aggregation_job.id()
+                )
+                .await
+                .unwrap()
+                .unwrap()
+                .state(),
+                &AggregationJobState::Abandoned
+            );
+            assert_eq!(
+                tx.get_batch_aggregation_job_count_for_batch::<0, TimeInterval, dummy::Vdaf>(
+                    task.id(),
+                    &batch_interval,
+                    &aggregation_param,
+                )
+                .await
+                .unwrap(),
+                (1, 1)
+            );
+
+            let unaggregated_reports = tx
+                .get_unaggregated_client_reports_for_task(task.id(), 10)
+                .await
+                .unwrap();
+            assert_eq!(unaggregated_reports.len(), 1);
+            assert_eq!(
+                unaggregated_reports[0].report_id(),
+                init_report.metadata().id()
+            );
+            assert_eq!(
+                tx.get_client_report(&vdaf, task.id(), init_report.metadata().id())
+                    .await
+                    .unwrap()
+                    .unwrap(),
+                init_report
+            );
+
+            let mut report_aggregations = tx
+                .get_report_aggregations_for_aggregation_job(
+                    &vdaf,
+                    &Role::Leader,
+                    task.id(),
+                    aggregation_job.id(),
+                )
+                .await
+                .unwrap();
+            report_aggregations.sort_by_key(|report_aggregation| report_aggregation.ord());
+            assert_eq!(
+                report_aggregations[0].state(),
+                &ReportAggregationState::Failed {
+                    report_error: ReportError::ReportDropped
+                }
+            );
+            assert_eq!(report_aggregations[1], finished_report_aggregation);
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+}
+
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn roundtrip_audit_log_entry(ephemeral_datastore: EphemeralDatastore) {
+    install_test_trace_subscriber();
+
+    let clock = MockClock::new(OLDEST_ALLOWED_REPORT_TIMESTAMP);
+    let ds = ephemeral_datastore.datastore(clock.clone()).await;
+
+    let task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .build()
+    .leader_view()
+    .unwrap();
+
+    ds.run_unnamed_tx(|tx| {
+        let task = task.clone();
+        let clock = clock.clone();
+        Box::pin(async move {
+            tx.put_aggregator_task(&task).await.unwrap();
+
+            tx.put_audit_log_entry(&AuditLogEntry::new(
+                "operator".to_string(),
+                "first".to_string(),
+                clock.now(),
+            ))
+            .await
+            .unwrap();
+            tx.put_audit_log_entry(
+                &AuditLogEntry::new("token:0123".to_string(), "second".to_string(), clock.now())
+                    .with_task_id(Some(*task.id()))
+                    .with_target(Some("target".to_string()))
+                    .with_route(Some("PATCH /tasks/:task_id".to_string()))
+                    .with_before(Some(serde_json::json!({"task_end": null})))
+                    .with_after(Some(serde_json::json!({"task_end": 1000})))
+                    .with_outcome(AuditLogOutcome::Failure)
+                    .with_details(serde_json::json!({"reports_unaggregated": 3})),
+            )
+            .await
+            .unwrap();
+
+            let entries = tx
+                .get_audit_log_entries(&AuditLogFilter::default())
+                .await
+                .unwrap();
+            assert_eq!(entries.len(), 2);
+            assert_eq!(entries[0].action(), "second");
+            assert_eq!(entries[0].task_id(), Some(task.id()));
+            assert_eq!(entries[0].target(), Some("target"));
+            assert_eq!(entries[0].route(), Some("PATCH /tasks/:task_id"));
+            assert_eq!(
+                entries[0].before(),
+                Some(&serde_json::json!({"task_end": null}))
+            );
+            assert_eq!(
+                entries[0].after(),
+                Some(&serde_json::json!({"task_end": 1000}))
+            );
+            assert_eq!(entries[0].outcome(), &AuditLogOutcome::Failure);
+            assert_eq!(
+                entries[0].details(),
+                &serde_json::json!({"reports_unaggregated": 3})
+            );
+            assert_eq!(entries[0].created_at(), &clock.now());
+            assert_eq!(entries[1].action(), "first");
+            assert_eq!(entries[1].task_id(), None);
+            assert_eq!(entries[1].route(), None);
+            assert_eq!(entries[1].before(), None);
+            assert_eq!(entries[1].outcome(), &AuditLogOutcome::Success);
+
+            let entries = tx
+                .get_audit_log_entries(&AuditLogFilter::default().with_task_id(Some(*task.id())))
+                .await
+                .unwrap();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].actor(), "token:0123");
+
+            let entries = tx
+                .get_audit_log_entries(
+                    &AuditLogFilter::default().with_actor(Some("operator".to_string())),
+                )
+                .await
+                .unwrap();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].action(), "first");
+
+            let entries = tx
+                .get_audit_log_entries(&AuditLogFilter::default().with_limit(Some(1)))
+                .await
+                .unwrap();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].action(), "second");
+
+            let entries = tx
+                .get_audit_log_entries(
+                    &AuditLogFilter::default()
+                        .with_since(Some(clock.now().add(&Duration::from_seconds(1)).unwrap())),
+                )
+                .await
+                .unwrap();
+            assert!(entries.is_empty());
+
+            let entries = tx
+                .get_audit_log_entries(&AuditLogFilter::default().with_until(Some(clock.now())))
+                .await
+                .unwrap();
+            assert!(entries.is_empty());
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+}
+
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn roundtrip_api_token(ephemeral_datastore: EphemeralDatastore) {
+    install_test_trace_subscriber();
+
+    let clock = MockClock::new(OLDEST_ALLOWED_REPORT_TIMESTAMP);
+    let ds = ephemeral_datastore.datastore(clock.clone()).await;
+
+    let admin_token = AuthenticationToken::Bearer(random());
+    let dashboard_token = AuthenticationToken::Bearer(random());
+    let admin = ApiToken::new(
+        "admin".to_string(),
+        AuthenticationTokenHash::from(&admin_token),
+        Vec::from([ApiTokenScope::TaskAdmin, ApiTokenScope::KeyAdmin]),
+        None,
+        clock.now(),
+    );
+    let dashboard = ApiToken::new(
+        "dashboard".to_string(),
+        AuthenticationTokenHash::from(&dashboard_token),
+        Vec::from([ApiTokenScope::ReadOnly]),
+        Some(Vec::from([random(), random()])),
+        clock.now(),
+    );
+
+    ds.run_unnamed_tx(|tx| {
+        let (admin, dashboard) = (admin.clone(), dashboard.clone());
         Box::pin(async move {
-            tx.put_aggregator_task(&task).await.unwrap();
-
-            for agg_job in want_agg_jobs {
-                tx.put_aggregation_job(&agg_job).await.unwrap();
-            }
+            tx.put_api_token(&admin).await.unwrap();
+            tx.put_api_token(&dashboard).await.unwrap();
 
-            // Also write an unrelated aggregation job with a different task ID to check that it
-            // is not returned.
-            let unrelated_task = TaskBuilder::new(
-                task::BatchMode::LeaderSelected {
-                    batch_time_window_size: None,
-                },
-                AggregationMode::Synchronous,
-                VdafInstance::Fake { rounds: 1 },
-            )
-            .build()
-            .leader_view()
-            .unwrap();
-            tx.put_aggregator_task(&unrelated_task).await.unwrap();
-            tx.put_aggregation_job(&AggregationJob::<0, LeaderSelected, dummy::Vdaf>::new(
-                *unrelated_task.id(),
-                random(),
-                dummy::AggregationParam(82),
-                random(),
-                Interval::new(Time::from_seconds_since_epoch(0), *task.time_precision()).unwrap(),
-                AggregationJobState::Active,
-                AggregationJobStep::from(0),
-            ))
-            .await
+            // Names must be unique.
+            assert_matches!(
+                tx.put_api_token(&ApiToken::new(
+                    "admin".to_string(),
+                    AuthenticationTokenHash::from(&AuthenticationToken::Bearer(random())),
+                    Vec::new(),
+                    None,
+                    *admin.created_at(),
+                ))
+                .await,
+                Err(Error::MutationTargetAlreadyExists)
+            );
+            Ok(())
         })
     })
     .await
     .unwrap();
 
-    // Run.
-    want_agg_jobs.sort_by_key(|agg_job| *agg_job.id());
-    let mut got_agg_jobs = ds
-        .run_unnamed_tx(|tx| {
-            let task = task.clone();
-            Box::pin(async move { tx.get_aggregation_jobs_for_task(task.id()).await })
-        })
-        .await
-        .unwrap();
-    got_agg_jobs.sort_by_key(|agg_job| *agg_job.id());
+    clock.advance(&Duration::from_seconds(60));
 
-    // Verify.
-    assert_eq!(want_agg_jobs, got_agg_jobs);
+    ds.run_unnamed_tx(|tx| {
+        let (admin, dashboard) = (admin.clone(), dashboard.clone());
+        let (admin_token, dashboard_token) = (admin_token.clone(), dashboard_token.clone());
+        let clock = clock.clone();
+        Box::pin(async move {
+            assert_eq!(
+                tx.get_api_token("admin").await.unwrap(),
+                Some(admin.clone())
+            );
+            assert_eq!(tx.get_api_token("nonexistent").await.unwrap(), None);
+            assert_eq!(
+                tx.get_api_token_by_hash(&AuthenticationTokenHash::from(&dashboard_token))
+                    .await
+                    .unwrap(),
+                Some(dashboard.clone())
+            );
+
+            tx.revoke_api_token("admin", &clock.now()).await.unwrap();
+            assert_matches!(
+                tx.revoke_api_token("admin", &clock.now()).await,
+                Err(Error::MutationTargetNotFound)
+            );
+            assert_matches!(
+                tx.revoke_api_token("nonexistent", &clock.now()).await,
+                Err(Error::MutationTargetNotFound)
+            );
+
+            // Revoked tokens can no longer be used, but are still listed.
+            assert_eq!(
+                tx.get_api_token_by_hash(&AuthenticationTokenHash::from(&admin_token))
+                    .await
+                    .unwrap(),
+                None
+            );
+            assert_eq!(
+                tx.get_api_tokens().await.unwrap(),
+                Vec::from([admin.with_revoked_at(Some(clock.now())), dashboard])
+            );
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
 }
 
 #[rstest_reuse::apply(schema_versions_template)]
@@ -3471,25 +4452,274 @@ async fn get_collection_job(ephemeral_datastore: EphemeralDatastore) {
         Box::pin(async move {
             let vdaf = dummy::Vdaf::default();
 
-            let first_collection_job = tx
-                .get_collection_job::<0, TimeInterval, dummy::Vdaf>(
-                    &vdaf,
-                    task.id(),
-                    first_collection_job.id(),
-                )
+            let first_collection_job = tx
+                .get_collection_job::<0, TimeInterval, dummy::Vdaf>(
+                    &vdaf,
+                    task.id(),
+                    first_collection_job.id(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(first_collection_job, None);
+
+            let second_collection_job = tx
+                .get_collection_job::<0, TimeInterval, dummy::Vdaf>(
+                    &vdaf,
+                    task.id(),
+                    second_collection_job.id(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(second_collection_job, None);
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+}
+
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn abandoned_collection_job_recovery(ephemeral_datastore: EphemeralDatastore) {
+    install_test_trace_subscriber();
+
+    let clock = MockClock::new(OLDEST_ALLOWED_REPORT_TIMESTAMP);
+    let ds = ephemeral_datastore.datastore(clock.clone()).await;
+
+    let task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .with_report_expiry_age(Some(REPORT_EXPIRY_AGE))
+    .with_time_precision(TIME_PRECISION)
+    .build()
+    .leader_view()
+    .unwrap();
+    let batch_interval = Interval::new(OLDEST_ALLOWED_REPORT_TIMESTAMP, TIME_PRECISION).unwrap();
+    let collection_job = CollectionJob::<0, TimeInterval, dummy::Vdaf>::new(
+        *task.id(),
+        random(),
+        Query::new_time_interval(batch_interval),
+        dummy::AggregationParam(0),
+        batch_interval,
+        CollectionJobState::Start,
+    );
+
+    ds.run_unnamed_tx(|tx| {
+        let task = task.clone();
+        let collection_job = collection_job.clone();
+        Box::pin(async move {
+            tx.put_aggregator_task(&task).await.unwrap();
+            tx.put_collection_job(&collection_job).await.unwrap();
+
+            // A job which is not abandoned can't be requeued, and isn't listed.
+            assert_matches!(
+                tx.requeue_abandoned_collection_job(task.id(), collection_job.id())
+                    .await,
+                Err(Error::MutationTargetNotFound)
+            );
+            assert!(
+                tx.get_abandoned_collection_jobs(None)
+                    .await
+                    .unwrap()
+                    .is_empty()
+            );
+
+            tx.set_collection_job_last_error(task.id(), collection_job.id(), "helper went away")
+                .await
+                .unwrap();
+            tx.fail_collection_job(task.id(), collection_job.id())
+                .await
+                .unwrap();
+            assert_matches!(
+                tx.fail_collection_job(task.id(), collection_job.id()).await,
+                Err(Error::MutationTargetNotFound)
+            );
+
+            let abandoned_jobs = tx.get_abandoned_collection_jobs(None).await.unwrap();
+            assert_eq!(abandoned_jobs.len(), 1);
+            assert_eq!(abandoned_jobs[0].task_id(), task.id());
+            assert_eq!(abandoned_jobs[0].collection_job_id(), collection_job.id());
+            assert_eq!(abandoned_jobs[0].last_error(), Some("helper went away"));
+            assert_eq!(
+                tx.get_abandoned_collection_jobs(Some(&random()))
+                    .await
+                    .unwrap(),
+                Vec::new()
+            );
+
+            tx.requeue_abandoned_collection_job(task.id(), collection_job.id())
+                .await
+                .unwrap();
+            assert!(
+                tx.get_abandoned_collection_jobs(Some(task.id()))
+                    .await
+                    .unwrap()
+                    .is_empty()
+            );
+            assert_eq!(
+                tx.get_collection_job::<0, TimeInterval, dummy::Vdaf>(
+                    &dummy::Vdaf::default(),
+                    task.id(),
+                    collection_job.id(),
+                )
+                .await
+                .unwrap()
+                .unwrap(),
+                collection_job
+            );
+
+            tx.fail_collection_job(task.id(), collection_job.id())
+                .await
+                .unwrap();
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+
+    // Advance the clock to expire the collection job's batch.
+    clock.advance(&REPORT_EXPIRY_AGE);
+    clock.advance(&TIME_PRECISION);
+
+    ds.run_unnamed_tx(|tx| {
+        let task = task.clone();
+        let collection_job = collection_job.clone();
+        Box::pin(async move {
+            // A job whose batch has expired can't be requeued.
+            assert_matches!(
+                tx.requeue_abandoned_collection_job(task.id(), collection_job.id())
+                    .await,
+                Err(Error::MutationTargetNotFound)
+            );
+
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+}
+
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn roundtrip_job_history(ephemeral_datastore: EphemeralDatastore) {
+    install_test_trace_subscriber();
+
+    let clock = MockClock::new(OLDEST_ALLOWED_REPORT_TIMESTAMP);
+    let ds = ephemeral_datastore.datastore(clock.clone()).await;
+
+    let task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .with_report_expiry_age(Some(REPORT_EXPIRY_AGE))
+    .with_time_precision(TIME_PRECISION)
+    .build()
+    .leader_view()
+    .unwrap();
+    let batch_interval = Interval::new(OLDEST_ALLOWED_REPORT_TIMESTAMP, TIME_PRECISION).unwrap();
+    let aggregation_job = AggregationJob::<0, TimeInterval, dummy::Vdaf>::new(
+        *task.id(),
+        random(),
+        dummy::AggregationParam(0),
+        (),
+        batch_interval,
+        AggregationJobState::Active,
+        AggregationJobStep::from(1),
+    );
+    let collection_job = CollectionJob::<0, TimeInterval, dummy::Vdaf>::new(
+        *task.id(),
+        random(),
+        Query::new_time_interval(batch_interval),
+        dummy::AggregationParam(0),
+        batch_interval,
+        CollectionJobState::Start,
+    );
+
+    ds.run_unnamed_tx(|tx| {
+        let task = task.clone();
+        let aggregation_job = aggregation_job.clone();
+        let collection_job = collection_job.clone();
+        let clock = clock.clone();
+        Box::pin(async move {
+            tx.put_aggregator_task(&task).await.unwrap();
+            tx.put_aggregation_job(&aggregation_job).await.unwrap();
+            tx.put_collection_job(&collection_job).await.unwrap();
+
+            // Jobs without history have an empty history, while unknown jobs have none at all.
+            assert_eq!(
+                tx.get_aggregation_job_history(task.id(), aggregation_job.id())
+                    .await
+                    .unwrap(),
+                Some(Vec::new())
+            );
+            assert_eq!(
+                tx.get_aggregation_job_history(task.id(), &random())
+                    .await
+                    .unwrap(),
+                None
+            );
+            assert_eq!(
+                tx.get_collection_job_history(task.id(), &random())
+                    .await
+                    .unwrap(),
+                None
+            );
+
+            // Only the most recent entries are retained.
+            let records: Vec<_> = (0..MAX_JOB_HISTORY_ENTRIES + 1)
+                .map(|i| {
+                    JobStepRecord::new(JobStepOutcome::RetryableError)
+                        .with_error(format!("error {i}"))
+                        .with_http_status(503)
+                        .with_problem_type("urn:ietf:params:ppm:dap:error:invalidMessage".into())
+                })
+                .collect();
+            for record in &records {
+                tx.put_aggregation_job_history_entry(task.id(), aggregation_job.id(), record)
+                    .await
+                    .unwrap();
+            }
+            let history = tx
+                .get_aggregation_job_history(task.id(), aggregation_job.id())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                history,
+                records
+                    .iter()
+                    .skip(1)
+                    .rev()
+                    .map(|record| JobHistoryEntry::new(1, 0, record.clone(), clock.now()))
+                    .collect::<Vec<_>>()
+            );
+
+            let record = JobStepRecord::new(JobStepOutcome::NotReady)
+                .with_retry_delay(StdDuration::from_millis(1500));
+            tx.put_collection_job_history_entry(task.id(), collection_job.id(), &record)
                 .await
                 .unwrap();
-            assert_eq!(first_collection_job, None);
+            assert_eq!(
+                tx.get_collection_job_history(task.id(), collection_job.id())
+                    .await
+                    .unwrap(),
+                Some(Vec::from([JobHistoryEntry::new(0, 0, record, clock.now())]))
+            );
 
-            let second_collection_job = tx
-                .get_collection_job::<0, TimeInterval, dummy::Vdaf>(
-                    &vdaf,
+            assert_matches!(
+                tx.put_collection_job_history_entry(
                     task.id(),
-                    second_collection_job.id(),
+                    &random(),
+                    &JobStepRecord::new(JobStepOutcome::FatalError)
                 )
-                .await
-                .unwrap();
-            assert_eq!(second_collection_job, None);
+                .await,
+                Err(Error::MutationTargetNotFound)
+            );
 
             Ok(())
         })
@@ -3835,6 +5065,7 @@ async fn run_collection_job_acquire_test_case<B: TestBatchModeExt>(
                             c.batch_identifier.get_encoded().unwrap(),
                             c.agg_param.get_encoded().unwrap(),
                             0,
+                            clock.now(),
                         ),
                         clock.now().as_naive_date_time().unwrap()
                             + chrono::Duration::try_seconds(100).unwrap(),
@@ -4427,6 +5658,7 @@ async fn collection_job_acquire_job_max(ephemeral_datastore: EphemeralDatastore)
                             c.batch_identifier.get_encoded().unwrap(),
                             c.agg_param.get_encoded().unwrap(),
                             0,
+                            clock.now(),
                         ),
                         clock.now().as_naive_date_time().unwrap()
                             + chrono::Duration::try_seconds(100).unwrap(),
@@ -6043,9 +7275,371 @@ async fn delete_expired_client_reports_noop(ephemeral_datastore: EphemeralDatast
                 ))
             })
         })
-        .await
-        .unwrap();
-    assert_eq!(want_report_ids, got_report_ids);
+        .await
+        .unwrap();
+    assert_eq!(want_report_ids, got_report_ids);
+}
+
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn scrub_retained_client_reports(ephemeral_datastore: EphemeralDatastore) {
+    install_test_trace_subscriber();
+
+    let clock = MockClock::new(OLDEST_ALLOWED_REPORT_TIMESTAMP);
+    let ds = ephemeral_datastore.datastore(clock.clone()).await;
+    let vdaf = dummy::Vdaf::default();
+    let report_retention_window = Duration::from_seconds(100);
+
+    // Setup.
+    let (
+        task_id,
+        old_report_id,
+        new_report_id,
+        unaggregated_report_id,
+        other_task_id,
+        other_task_report_id,
+    ) = ds
+        .run_unnamed_tx(|tx| {
+            let clock = clock.clone();
+            Box::pin(async move {
+                let task = TaskBuilder::new(
+                    task::BatchMode::TimeInterval,
+                    AggregationMode::Synchronous,
+                    VdafInstance::Fake { rounds: 1 },
+                )
+                .with_time_precision(Duration::from_seconds(1))
+                .with_report_retention_window(Some(report_retention_window))
+                .build()
+                .leader_view()
+                .unwrap();
+                let other_task = TaskBuilder::new(
+                    task::BatchMode::TimeInterval,
+                    AggregationMode::Synchronous,
+                    VdafInstance::Fake { rounds: 1 },
+                )
+                .with_time_precision(Duration::from_seconds(1))
+                .build()
+                .leader_view()
+                .unwrap();
+                tx.put_aggregator_task(&task).await.unwrap();
+                tx.put_aggregator_task(&other_task).await.unwrap();
+
+                let old_report = LeaderStoredReport::new_dummy(*task.id(), clock.now());
+                let new_report = LeaderStoredReport::new_dummy(*task.id(), clock.now());
+                let unaggregated_report = LeaderStoredReport::new_dummy(*task.id(), clock.now());
+                let other_task_report =
+                    LeaderStoredReport::new_dummy(*other_task.id(), clock.now());
+                for report in [
+                    &old_report,
+                    &new_report,
+                    &unaggregated_report,
+                    &other_task_report,
+                ] {
+                    tx.put_client_report::<0, dummy::Vdaf>(report)
+                        .await
+                        .unwrap();
+                }
+                tx.mark_report_aggregated(task.id(), old_report.metadata().id())
+                    .await
+                    .unwrap();
+                tx.mark_report_aggregated(other_task.id(), other_task_report.metadata().id())
+                    .await
+                    .unwrap();
+
+                Ok((
+                    *task.id(),
+                    *old_report.metadata().id(),
+                    *new_report.metadata().id(),
+                    *unaggregated_report.metadata().id(),
+                    *other_task.id(),
+                    *other_task_report.metadata().id(),
+                ))
+            })
+        })
+        .await
+        .unwrap();
+
+    // Aggregate the new report partway through the old report's retention window.
+    clock.advance(&Duration::from_seconds(50));
+    ds.run_unnamed_tx(|tx| {
+        Box::pin(async move {
+            tx.mark_report_aggregated(&task_id, &new_report_id)
+                .await
+                .unwrap();
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+
+    // Run, once the old report's retention window has elapsed but the new report's has not.
+    clock.advance(&Duration::from_seconds(51));
+    let scrubbed_report_count = ds
+        .run_unnamed_tx(|tx| {
+            Box::pin(async move {
+                let scrubbed_report_count = tx
+                    .scrub_retained_client_reports(&task_id, u64::try_from(i64::MAX).unwrap())
+                    .await
+                    .unwrap();
+                let other_task_scrubbed_report_count = tx
+                    .scrub_retained_client_reports(&other_task_id, u64::try_from(i64::MAX).unwrap())
+                    .await
+                    .unwrap();
+                Ok(scrubbed_report_count + other_task_scrubbed_report_count)
+            })
+        })
+        .await
+        .unwrap();
+
+    // Verify.
+    assert_eq!(1, scrubbed_report_count);
+    ds.run_unnamed_tx(|tx| {
+        let vdaf = vdaf.clone();
+        Box::pin(async move {
+            tx.verify_client_report_scrubbed(&task_id, &old_report_id)
+                .await;
+            for (task_id, report_id) in [
+                (task_id, new_report_id),
+                (task_id, unaggregated_report_id),
+                (other_task_id, other_task_report_id),
+            ] {
+                assert!(
+                    tx.get_client_report(&vdaf, &task_id, &report_id)
+                        .await
+                        .unwrap()
+                        .is_some()
+                );
+            }
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+}
+
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn export_import_task(ephemeral_datastore: EphemeralDatastore) {
+    install_test_trace_subscriber();
+
+    let clock = MockClock::new(OLDEST_ALLOWED_REPORT_TIMESTAMP);
+    let ds = ephemeral_datastore.datastore(clock.clone()).await;
+    let vdaf = dummy::Vdaf::default();
+    let archive_crypter = Crypter::new(Vec::from([generate_aead_key()]));
+
+    let task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .with_time_precision(Duration::from_seconds(1))
+    .with_report_expiry_age(Some(REPORT_EXPIRY_AGE))
+    .build()
+    .leader_view()
+    .unwrap();
+    let hpke_keypair = hpke::HpkeKeypair::test();
+    let batch_interval =
+        Interval::new(OLDEST_ALLOWED_REPORT_TIMESTAMP, Duration::from_seconds(100)).unwrap();
+    let aggregated_report = LeaderStoredReport::new_dummy(*task.id(), clock.now());
+    let unaggregated_report = LeaderStoredReport::new_dummy(*task.id(), clock.now());
+    let batch_aggregation = BatchAggregation::<0, TimeInterval, dummy::Vdaf>::new(
+        *task.id(),
+        batch_interval,
+        dummy::AggregationParam(11),
+        0,
+        batch_interval,
+        BatchAggregationState::Aggregating {
+            aggregate_share: Some(dummy::AggregateShare(23)),
+            report_count: 1,
+            checksum: ReportIdChecksum::get_decoded(&[3; 32]).unwrap(),
+            aggregation_jobs_created: 1,
+            aggregation_jobs_terminated: 1,
+        },
+    );
+    let collection_job = CollectionJob::<0, TimeInterval, dummy::Vdaf>::new(
+        *task.id(),
+        random(),
+        Query::new_time_interval(batch_interval),
+        dummy::AggregationParam(11),
+        batch_interval,
+        CollectionJobState::Start,
+    );
+
+    ds.run_unnamed_tx(|tx| {
+        let task = task.clone();
+        let hpke_keypair = hpke_keypair.clone();
+        let aggregated_report = aggregated_report.clone();
+        let unaggregated_report = unaggregated_report.clone();
+        let batch_aggregation = batch_aggregation.clone();
+        let collection_job = collection_job.clone();
+        Box::pin(async move {
+            tx.put_aggregator_task(&task).await.unwrap();
+            tx.put_hpke_keypair(&hpke_keypair).await.unwrap();
+            tx.set_hpke_keypair_state(hpke_keypair.config().id(), &HpkeKeyState::Active)
+                .await
+                .unwrap();
+            for report in [&aggregated_report, &unaggregated_report] {
+                tx.put_client_report::<0, dummy::Vdaf>(report)
+                    .await
+                    .unwrap();
+            }
+            tx.mark_report_aggregated(task.id(), aggregated_report.metadata().id())
+                .await
+                .unwrap();
+            tx.put_batch_aggregation(&batch_aggregation).await.unwrap();
+            tx.put_collection_job(&collection_job).await.unwrap();
+            assert!(
+                tx.spend_dp_budget::<0, TimeInterval, dummy::Vdaf>(
+                    task.id(),
+                    &batch_interval,
+                    &dummy::AggregationParam(11),
+                    0.5,
+                )
+                .await
+                .unwrap()
+            );
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+
+    // Export the task, seal the archive & round-trip it through its serialized form.
+    let sealed_archive = ds
+        .run_unnamed_tx(|tx| {
+            let task_id = *task.id();
+            Box::pin(async move { Ok(tx.export_task(&task_id, true).await.unwrap().unwrap()) })
+        })
+        .await
+        .unwrap()
+        .seal(&archive_crypter)
+        .unwrap();
+    let sealed_archive: SealedTaskArchive =
+        serde_json::from_str(&serde_json::to_string(&sealed_archive).unwrap()).unwrap();
+    assert_eq!(sealed_archive.task_id(), task.id());
+
+    // Opening the archive requires the archive key & a supported archive version.
+    assert_matches!(
+        sealed_archive.open(&Crypter::new(Vec::from([generate_aead_key()]))),
+        Err(Error::Crypt)
+    );
+    let mut unsupported_archive = serde_json::to_value(&sealed_archive).unwrap();
+    unsupported_archive["version"] = serde_json::json!(TASK_ARCHIVE_VERSION + 1);
+    assert_matches!(
+        serde_json::from_value::<SealedTaskArchive>(unsupported_archive)
+            .unwrap()
+            .open(&archive_crypter),
+        Err(Error::User(_))
+    );
+
+    let archive = sealed_archive.open(&archive_crypter).unwrap();
+    assert_eq!(archive.task(), &task);
+    assert_eq!(archive.hpke_keypair_count(), 1);
+    assert_eq!(archive.client_report_count(), 2);
+    assert_eq!(archive.unaggregated_client_report_count(), 1);
+    assert_eq!(archive.batch_aggregation_count(), 1);
+    assert_eq!(archive.collection_job_count(), 1);
+    assert_eq!(archive.dp_budget_ledger_entry_count(), 1);
+
+    // Importing a task which already exists fails.
+    ds.run_unnamed_tx(|tx| {
+        let archive = archive.clone();
+        Box::pin(async move {
+            assert_matches!(
+                tx.import_task(&archive).await,
+                Err(Error::MutationTargetAlreadyExists)
+            );
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+
+    // Delete the task & its HPKE keypair, then import the archive.
+    ds.run_unnamed_tx(|tx| {
+        let task_id = *task.id();
+        let hpke_config_id = *hpke_keypair.config().id();
+        let archive = archive.clone();
+        Box::pin(async move {
+            tx.delete_task(&task_id).await.unwrap();
+            tx.delete_hpke_keypair(&hpke_config_id).await.unwrap();
+            tx.import_task(&archive).await.unwrap();
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+
+    // Verify.
+    ds.run_unnamed_tx(|tx| {
+        let vdaf = vdaf.clone();
+        let task = task.clone();
+        let hpke_keypair = hpke_keypair.clone();
+        let aggregated_report = aggregated_report.clone();
+        let unaggregated_report = unaggregated_report.clone();
+        let batch_aggregation = batch_aggregation.clone();
+        let collection_job = collection_job.clone();
+        Box::pin(async move {
+            assert_eq!(
+                tx.get_aggregator_task(task.id()).await.unwrap().unwrap(),
+                task
+            );
+
+            let got_hpke_keypair = tx
+                .get_hpke_keypair(hpke_keypair.config().id())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(got_hpke_keypair.hpke_keypair(), &hpke_keypair);
+            assert_eq!(got_hpke_keypair.state(), &HpkeKeyState::Active);
+
+            // The unaggregated report's payload is imported, so it can still be aggregated.
+            assert_eq!(
+                tx.get_client_report(&vdaf, task.id(), unaggregated_report.metadata().id())
+                    .await
+                    .unwrap()
+                    .unwrap(),
+                unaggregated_report
+            );
+
+            // The aggregated report is imported only for replay protection.
+            tx.verify_client_report_scrubbed(task.id(), aggregated_report.metadata().id())
+                .await;
+            assert_matches!(
+                tx.put_client_report::<0, dummy::Vdaf>(&aggregated_report)
+                    .await,
+                Err(Error::MutationTargetAlreadyExists)
+            );
+
+            assert_eq!(
+                tx.get_batch_aggregations_for_task::<0, TimeInterval, dummy::Vdaf>(
+                    &vdaf,
+                    task.id()
+                )
+                .await
+                .unwrap(),
+                Vec::from([batch_aggregation])
+            );
+            assert_eq!(
+                tx.get_collection_job::<0, TimeInterval, dummy::Vdaf>(
+                    &vdaf,
+                    task.id(),
+                    collection_job.id()
+                )
+                .await
+                .unwrap()
+                .unwrap(),
+                collection_job
+            );
+            assert_eq!(
+                tx.get_dp_budget_usage(task.id()).await.unwrap(),
+                Some(DpBudgetUsage::new(0.5, 1))
+            );
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
 }
 
 #[rstest_reuse::apply(schema_versions_template)]
@@ -7537,6 +9131,155 @@ async fn delete_expired_collection_artifacts(ephemeral_datastore: EphemeralDatas
     assert_eq!(want_batch_aggregation_ids, got_batch_aggregation_ids);
 }
 
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn report_partitions(ephemeral_datastore: EphemeralDatastore) {
+    install_test_trace_subscriber();
+
+    let clock = MockClock::default();
+    let ds = ephemeral_datastore.datastore(clock.clone()).await;
+    let vdaf = dummy::Vdaf::default();
+    let now = clock.now();
+    let partition_width = Duration::from_seconds(100);
+
+    // Setup: write a report before any partitions exist, so it is stored in the default partition.
+    let task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .with_report_expiry_age(Some(REPORT_EXPIRY_AGE))
+    .with_time_precision(Duration::from_seconds(1))
+    .build()
+    .leader_view()
+    .unwrap();
+    let task_id = *task.id();
+    let report =
+        LeaderStoredReport::new_dummy(task_id, now.sub(&Duration::from_seconds(250)).unwrap());
+    let report_id = *report.metadata().id();
+    ds.run_unnamed_tx(|tx| {
+        let task = task.clone();
+        let report = report.clone();
+        Box::pin(async move {
+            tx.put_aggregator_task(&task).await.unwrap();
+            tx.put_client_report(&report).await.unwrap();
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+
+    // Create partitions for [now - 300s, now + 200s): a task partition & five ranges per table.
+    let interval = Interval::new(
+        now.sub(&Duration::from_seconds(300)).unwrap(),
+        Duration::from_seconds(500),
+    )
+    .unwrap();
+    let created = ds
+        .run_unnamed_tx(|tx| {
+            Box::pin(async move {
+                tx.create_report_partitions(&task_id, &interval, &partition_width)
+                    .await
+            })
+        })
+        .await
+        .unwrap();
+    assert_eq!(created, 12);
+
+    // The existing report was moved into its range partition, and remains readable.
+    ds.run_unnamed_tx(|tx| {
+        let vdaf = vdaf.clone();
+        Box::pin(async move {
+            let row = tx
+                .query_one(
+                    "--
+SELECT tableoid::regclass::TEXT AS partition_name FROM client_reports WHERE report_id = $1",
+                    &[/* report_id */ &report_id.as_ref()],
+                )
+                .await
+                .unwrap();
+            let partition_name: String = row.get("partition_name");
+            assert!(partition_name.starts_with("client_reports_t"));
+            assert!(partition_name.ends_with(&format!(
+                "_{}",
+                now.sub(&Duration::from_seconds(300))
+                    .unwrap()
+                    .as_seconds_since_epoch()
+            )));
+
+            assert!(
+                tx.get_client_report(&vdaf, &task_id, &report_id)
+                    .await
+                    .unwrap()
+                    .is_some()
+            );
+
+            // Creating partitions is idempotent.
+            assert_eq!(
+                tx.create_report_partitions(&task_id, &interval, &partition_width)
+                    .await
+                    .unwrap(),
+                0
+            );
+
+            // An unexpired aggregation job overlapping [now - 100s, now + 100s) keeps the
+            // report_aggregations partitions of that range from being dropped.
+            tx.put_aggregation_job(&AggregationJob::<0, TimeInterval, dummy::Vdaf>::new(
+                task_id,
+                random(),
+                dummy::AggregationParam(0),
+                (),
+                Interval::new(
+                    now.sub(&Duration::from_seconds(50)).unwrap(),
+                    Duration::from_seconds(250),
+                )
+                .unwrap(),
+                AggregationJobState::Active,
+                AggregationJobStep::from(0),
+            ))
+            .await
+            .unwrap();
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+
+    // Advance the clock so that ranges ending at or before now + 150s are expired.
+    clock.advance(&REPORT_EXPIRY_AGE.add(&Duration::from_seconds(150)).unwrap());
+    let dropped = ds
+        .run_unnamed_tx(|tx| {
+            Box::pin(async move { tx.drop_expired_report_partitions(&task_id).await })
+        })
+        .await
+        .unwrap();
+    assert_eq!(dropped, 4 + 2);
+
+    ds.run_unnamed_tx(|tx| {
+        Box::pin(async move {
+            let row = tx
+                .query_one(
+                    "--
+SELECT COUNT(*) AS count FROM client_reports WHERE report_id = $1",
+                    &[/* report_id */ &report_id.as_ref()],
+                )
+                .await
+                .unwrap();
+            assert_eq!(row.get::<_, i64>("count"), 0);
+
+            // Once the task is deleted, its remaining partitions are dropped.
+            tx.delete_task(&task_id).await.unwrap();
+            assert_eq!(
+                tx.drop_report_partitions_for_deleted_tasks().await.unwrap(),
+                2 + 4
+            );
+            Ok(())
+        })
+    })
+    .await
+    .unwrap();
+}
+
 #[rstest_reuse::apply(schema_versions_template)]
 #[tokio::test]
 async fn roundtrip_interval_sql(ephemeral_datastore: EphemeralDatastore) {
@@ -7748,6 +9491,97 @@ async fn roundtrip_hpke_keypair(ephemeral_datastore: EphemeralDatastore) {
         .unwrap();
 }
 
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn roundtrip_ohttp_gateway_keypair(ephemeral_datastore: EphemeralDatastore) {
+    install_test_trace_subscriber();
+    let datastore = ephemeral_datastore.datastore(MockClock::default()).await;
+    let clock = datastore.clock.clone();
+    let keypair = hpke::HpkeKeypair::test();
+
+    datastore
+        .run_tx("test-put-keys", |tx| {
+            let keypair = keypair.clone();
+            let clock = clock.clone();
+            Box::pin(async move {
+                assert_eq!(tx.get_ohttp_gateway_keypairs().await.unwrap(), Vec::new());
+                tx.put_ohttp_gateway_keypair(&keypair).await.unwrap();
+
+                // Gateway keys are independent of HPKE keys, even if their IDs collide.
+                assert_eq!(tx.get_hpke_keypairs().await.unwrap(), Vec::new());
+                tx.put_hpke_keypair(&keypair).await.unwrap();
+
+                let expected_keypair =
+                    HpkeKeypair::new(keypair.clone(), HpkeKeyState::Pending, clock.now());
+                assert_eq!(
+                    tx.get_ohttp_gateway_keypairs().await.unwrap(),
+                    Vec::from([expected_keypair.clone()])
+                );
+                assert_eq!(
+                    tx.get_ohttp_gateway_keypair(keypair.config().id())
+                        .await
+                        .unwrap()
+                        .unwrap(),
+                    expected_keypair
+                );
+
+                clock.advance(&Duration::from_seconds(100));
+                tx.set_ohttp_gateway_keypair_state(keypair.config().id(), &HpkeKeyState::Active)
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    tx.get_ohttp_gateway_keypair(keypair.config().id())
+                        .await
+                        .unwrap()
+                        .unwrap(),
+                    HpkeKeypair::new(keypair.clone(), HpkeKeyState::Active, clock.now())
+                );
+                assert_eq!(
+                    tx.get_hpke_keypair(keypair.config().id())
+                        .await
+                        .unwrap()
+                        .unwrap()
+                        .state(),
+                    &HpkeKeyState::Pending
+                );
+
+                Ok(())
+            })
+        })
+        .await
+        .unwrap();
+
+    // Should not be able to set keypair with the same id.
+    assert_matches!(
+        datastore
+            .run_unnamed_tx(|tx| {
+                let keypair = keypair.clone();
+                Box::pin(async move { tx.put_ohttp_gateway_keypair(&keypair).await })
+            })
+            .await,
+        Err(Error::Db(_))
+    );
+
+    datastore
+        .run_unnamed_tx(|tx| {
+            let keypair = keypair.clone();
+            Box::pin(async move {
+                tx.delete_ohttp_gateway_keypair(keypair.config().id())
+                    .await
+                    .unwrap();
+                assert_eq!(tx.get_ohttp_gateway_keypairs().await.unwrap(), Vec::new());
+                assert_eq!(tx.get_hpke_keypairs().await.unwrap().len(), 1);
+
+                tx.check_timestamp_columns("ohttp_gateway_keys", "test-put-keys", true)
+                    .await;
+
+                Ok(())
+            })
+        })
+        .await
+        .unwrap();
+}
+
 #[rstest_reuse::apply(schema_versions_template)]
 #[tokio::test]
 async fn roundtrip_taskprov_peer_aggregator(ephemeral_datastore: EphemeralDatastore) {
@@ -8032,7 +9866,7 @@ async fn roundtrip_task_upload_counter(ephemeral_datastore: EphemeralDatastore)
                 tx.increment_task_upload_counter(
                     &task_id,
                     ord,
-                    &TaskUploadCounter::new_with_values(2, 4, 6, 8, 10, 100, 25, 22, 12),
+                    &TaskUploadCounter::new_with_values(2, 4, 6, 8, 10, 100, 25, 22, 12, 0),
                 )
                 .await
                 .unwrap();
@@ -8041,7 +9875,7 @@ async fn roundtrip_task_upload_counter(ephemeral_datastore: EphemeralDatastore)
                 tx.increment_task_upload_counter(
                     &task_id,
                     ord,
-                    &TaskUploadCounter::new_with_values(0, 0, 0, 0, 0, 0, 0, 0, 8),
+                    &TaskUploadCounter::new_with_values(0, 0, 0, 0, 0, 0, 0, 0, 8, 3),
                 )
                 .await
                 .unwrap();
@@ -8064,6 +9898,7 @@ async fn roundtrip_task_upload_counter(ephemeral_datastore: EphemeralDatastore)
                         report_too_early: 25,
                         task_not_started: 22,
                         task_ended: 20,
+                        task_not_accepting_reports: 3,
                     })
                 );
 
@@ -8142,3 +9977,108 @@ async fn roundtrip_task_aggregation_counter(ephemeral_datastore: EphemeralDatast
         .await
         .unwrap();
 }
+
+#[rstest_reuse::apply(schema_versions_template)]
+#[tokio::test]
+async fn spend_dp_budget(ephemeral_datastore: EphemeralDatastore) {
+    install_test_trace_subscriber();
+    let clock = MockClock::default();
+    let datastore = ephemeral_datastore.datastore(clock.clone()).await;
+
+    let limited_task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .with_dp_budget(Some(DpBudget::new(1.0).unwrap()))
+    .build()
+    .leader_view()
+    .unwrap();
+    let unlimited_task = TaskBuilder::new(
+        task::BatchMode::TimeInterval,
+        AggregationMode::Synchronous,
+        VdafInstance::Fake { rounds: 1 },
+    )
+    .build()
+    .leader_view()
+    .unwrap();
+    let batch_interval = Interval::new(
+        Time::from_seconds_since_epoch(1000),
+        Duration::from_seconds(100),
+    )
+    .unwrap();
+
+    datastore
+        .run_tx("test-spend-dp-budget", |tx| {
+            let (limited_task, unlimited_task) = (limited_task.clone(), unlimited_task.clone());
+            Box::pin(async move {
+                // Returns None for non-existent task, and refuses to spend its budget.
+                assert_eq!(tx.get_dp_budget_usage(&random()).await.unwrap(), None);
+                assert_matches!(
+                    tx.spend_dp_budget::<0, TimeInterval, dummy::Vdaf>(
+                        &random(),
+                        &batch_interval,
+                        &dummy::AggregationParam(0),
+                        0.5,
+                    )
+                    .await,
+                    Err(Error::MutationTargetNotFound)
+                );
+
+                tx.put_aggregator_task(&limited_task).await.unwrap();
+                tx.put_aggregator_task(&unlimited_task).await.unwrap();
+
+                // Nothing has been spent by a task that has just been created.
+                assert_eq!(
+                    tx.get_dp_budget_usage(limited_task.id()).await.unwrap(),
+                    Some(DpBudgetUsage::default())
+                );
+
+                // Collections may spend the budget up to, but not past, its total.
+                for (aggregation_param, privacy_loss, want_spent) in
+                    [(0, 0.5, true), (1, 0.5, true), (2, 0.5, false)]
+                {
+                    assert_eq!(
+                        tx.spend_dp_budget::<0, TimeInterval, dummy::Vdaf>(
+                            limited_task.id(),
+                            &batch_interval,
+                            &dummy::AggregationParam(aggregation_param),
+                            privacy_loss,
+                        )
+                        .await
+                        .unwrap(),
+                        want_spent
+                    );
+                }
+                assert_eq!(
+                    tx.get_dp_budget_usage(limited_task.id()).await.unwrap(),
+                    Some(DpBudgetUsage::new(1.0, 2))
+                );
+
+                // Spending is recorded, but not limited, for tasks without a budget.
+                for aggregation_param in 0..3 {
+                    assert!(
+                        tx.spend_dp_budget::<0, TimeInterval, dummy::Vdaf>(
+                            unlimited_task.id(),
+                            &batch_interval,
+                            &dummy::AggregationParam(aggregation_param),
+                            0.5,
+                        )
+                        .await
+                        .unwrap()
+                    );
+                }
+                assert_eq!(
+                    tx.get_dp_budget_usage(unlimited_task.id()).await.unwrap(),
+                    Some(DpBudgetUsage::new(1.5, 3))
+                );
+
+                tx.check_timestamp_columns("dp_budget_ledger", "test-spend-dp-budget", false)
+                    .await;
+
+                Ok(())
+            })
+        })
+        .await
+        .unwrap();
+}
@@ -13,10 +13,11 @@ use prio::{
 };
 use std::hash::Hash;
 use tracing::{Instrument, Span, debug, info_span};
-use trillium::{Conn, Handler, Status};
+use trillium::{Conn, Handler, Headers, Status};
 use trillium_macros::Handler;
 use trillium_router::RouterConnExt;
 
+pub mod audit;
 pub mod batch_mode;
 pub mod datastore;
 pub mod task;
@@ -111,26 +112,51 @@ pub trait VdafHasAggregationParameter {}
 impl VdafHasAggregationParameter for prio::vdaf::dummy::Vdaf {}
 
 pub fn instrumented<H: Handler>(handler: H) -> impl Handler {
-    InstrumentedHandler(handler)
+    instrumented_with_trace_context(handler, false)
+}
+
+/// Like [`instrumented`], but if `extract_trace_context` is set, each request's span is made a
+/// child of the W3C trace context in the request's `traceparent` and `tracestate` headers, so that
+/// it joins the trace of the sender. Trace context is only extracted when built with the `otlp`
+/// feature, and when an OpenTelemetry propagator has been installed.
+pub fn instrumented_with_trace_context<H: Handler>(
+    handler: H,
+    extract_trace_context: bool,
+) -> impl Handler {
+    InstrumentedHandler {
+        handler,
+        extract_trace_context,
+    }
 }
 
 struct InstrumentedHandlerSpan(Span);
 
 #[derive(Handler)]
-struct InstrumentedHandler<H>(#[handler(except = [run, before_send])] H);
+struct InstrumentedHandler<H> {
+    #[handler(except = [run, before_send])]
+    handler: H,
+    extract_trace_context: bool,
+}
 
 impl<H: Handler> InstrumentedHandler<H> {
     async fn run(&self, mut conn: Conn) -> Conn {
         let route = conn.route().expect("no route in conn").to_string();
         let method = conn.method();
         let span = info_span!("endpoint", route, %method);
+        if self.extract_trace_context {
+            set_parent_from_trace_context(&span, conn.request_headers());
+        }
         conn.insert_state(InstrumentedHandlerSpan(span.clone()));
-        self.0.run(conn).instrument(span).await
+        self.handler.run(conn).instrument(span).await
     }
 
     async fn before_send(&self, mut conn: Conn) -> Conn {
         if let Some(span) = conn.take_state::<InstrumentedHandlerSpan>() {
-            let conn = self.0.before_send(conn).instrument(span.0.clone()).await;
+            let conn = self
+                .handler
+                .before_send(conn)
+                .instrument(span.0.clone())
+                .await;
             span.0.in_scope(|| {
                 let status = conn
                     .status()
@@ -140,11 +166,31 @@ impl<H: Handler> InstrumentedHandler<H> {
             });
             conn
         } else {
-            self.0.before_send(conn).await
+            self.handler.before_send(conn).await
         }
     }
 }
 
+/// Sets the parent of `span` to the trace context propagated in `headers`, if any.
+#[cfg(feature = "otlp")]
+fn set_parent_from_trace_context(span: &Span, headers: &Headers) {
+    use opentelemetry::global::get_text_map_propagator;
+    use std::collections::HashMap;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let context = get_text_map_propagator(|propagator| {
+        let carrier: HashMap<String, String> = propagator
+            .fields()
+            .filter_map(|field| Some((field.to_owned(), headers.get_str(field)?.to_owned())))
+            .collect();
+        propagator.extract(&carrier)
+    });
+    span.set_parent(context);
+}
+
+#[cfg(not(feature = "otlp"))]
+fn set_parent_from_trace_context(_: &Span, _: &Headers) {}
+
 /// These boundaries are intended to be able to capture the length of short-lived operations
 /// (e.g. HTTP requests) as well as longer-running operations.
 pub const TIME_HISTOGRAM_BOUNDARIES: &[f64] = &[
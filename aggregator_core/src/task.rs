@@ -66,6 +66,46 @@ impl TryFrom<batch_mode::Code> for BatchMode {
     }
 }
 
+/// A task's total differential privacy budget, measured in the units of its VDAF's differential
+/// privacy strategy, as described by [`PrivacyLoss`][janus_core::dp::PrivacyLoss].
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct DpBudget(f64);
+
+impl DpBudget {
+    /// Create a new [`DpBudget`], which must be finite and non-negative.
+    pub fn new(budget: f64) -> Result<Self, Error> {
+        if !budget.is_finite() || budget < 0.0 {
+            return Err(Error::InvalidParameter(
+                "dp_budget must be finite and non-negative",
+            ));
+        }
+        Ok(Self(budget))
+    }
+
+    /// Returns the amount of the budget.
+    pub fn get(&self) -> f64 {
+        self.0
+    }
+}
+
+// NaN is rejected on construction, so equality is reflexive.
+impl Eq for DpBudget {}
+
+impl TryFrom<f64> for DpBudget {
+    type Error = Error;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<DpBudget> for f64 {
+    fn from(value: DpBudget) -> Self {
+        value.0
+    }
+}
+
 /// A verification key for a VDAF, with a fixed length. It must be kept secret from clients to
 /// maintain robustness, and it must be shared between aggregators.
 #[derive(Educe, Clone, Copy)]
@@ -110,6 +150,15 @@ struct CommonTaskParameters {
     /// candidate for garbage collection. A value of `None` indicates that garbage collection is
     /// disabled.
     report_expiry_age: Option<Duration>,
+    /// How long the payloads of client reports are retained after the reports are aggregated, so
+    /// that they may be aggregated again. A value of `None` indicates that report payloads are
+    /// scrubbed as soon as the reports are aggregated.
+    report_retention_window: Option<Duration>,
+    /// The total differential privacy budget which collections of this task's batches may spend.
+    /// A value of `None` indicates that spending is recorded, but not limited.
+    dp_budget: Option<DpBudget>,
+    /// The lifecycle state of the task.
+    state: TaskState,
     /// The minimum number of reports in a batch to allow it to be collected.
     min_batch_size: u64,
     /// The duration to which clients should round their reported timestamps to. For time-interval
@@ -189,6 +238,9 @@ impl CommonTaskParameters {
             task_start,
             task_end,
             report_expiry_age,
+            report_retention_window: None,
+            dp_budget: None,
+            state: TaskState::Active,
             min_batch_size,
             time_precision,
             tolerable_clock_skew,
@@ -339,6 +391,21 @@ impl AggregatorTask {
         self.common_parameters.report_expiry_age.as_ref()
     }
 
+    /// Retrieves the report retention window associated with this task.
+    pub fn report_retention_window(&self) -> Option<&Duration> {
+        self.common_parameters.report_retention_window.as_ref()
+    }
+
+    /// Retrieves the differential privacy budget associated with this task.
+    pub fn dp_budget(&self) -> Option<&DpBudget> {
+        self.common_parameters.dp_budget.as_ref()
+    }
+
+    /// Retrieves the lifecycle state of this task.
+    pub fn state(&self) -> &TaskState {
+        &self.common_parameters.state
+    }
+
     /// Retrieves the min batch size parameter associated with this task.
     pub fn min_batch_size(&self) -> u64 {
         self.common_parameters.min_batch_size
@@ -479,6 +546,33 @@ impl AggregatorTask {
         self
     }
 
+    /// Set the report retention window for this task.
+    pub fn with_report_retention_window(
+        mut self,
+        report_retention_window: Option<Duration>,
+    ) -> Result<Self, Error> {
+        // This is stored as a 64-bit signed integer in the database.
+        if let Some(report_retention_window) = report_retention_window {
+            if report_retention_window > Duration::from_seconds(i64::MAX as u64) {
+                return Err(Error::InvalidParameter("report_retention_window too large"));
+            }
+        }
+        self.common_parameters.report_retention_window = report_retention_window;
+        Ok(self)
+    }
+
+    /// Set the differential privacy budget for this task.
+    pub fn with_dp_budget(mut self, dp_budget: Option<DpBudget>) -> Self {
+        self.common_parameters.dp_budget = dp_budget;
+        self
+    }
+
+    /// Set the lifecycle state of this task.
+    pub fn with_state(mut self, state: TaskState) -> Self {
+        self.common_parameters.state = state;
+        self
+    }
+
     /// Return the Taskprov `task_info` field for this task.
     pub fn taskprov_task_info(&self) -> Option<&[u8]> {
         self.common_parameters.taskprov_task_info.as_deref()
@@ -543,6 +637,49 @@ impl FromStr for AggregationMode {
     }
 }
 
+/// The lifecycle state of a task, corresponding to the TASK_STATE enum in the schema.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSql, FromSql,
+)]
+#[postgres(name = "task_state")]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    /// Reports are accepted, aggregated and collected.
+    #[default]
+    #[postgres(name = "ACTIVE")]
+    Active,
+    /// Reports are not accepted, but those already uploaded are still aggregated and collected.
+    /// The task may be made active again.
+    #[postgres(name = "PAUSED")]
+    Paused,
+    /// As for [`Self::Paused`], but the task is finishing its remaining work before it is
+    /// archived.
+    #[postgres(name = "DRAINING")]
+    Draining,
+    /// Reports are no longer accepted, aggregated or collected, and all report data is garbage
+    /// collected. The task's parameters and existing collection results are kept. Since the
+    /// reports needed to detect replays are deleted, an archived task can't leave this state.
+    #[postgres(name = "ARCHIVED")]
+    Archived,
+}
+
+impl TaskState {
+    /// Returns true if this is the active state, in which reports may be uploaded.
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Active)
+    }
+
+    /// Returns true if new aggregation jobs may be created for the task's unaggregated reports.
+    pub fn creates_aggregation_jobs(&self) -> bool {
+        !matches!(self, Self::Archived)
+    }
+
+    /// Returns true if a task may move from this state to the given state.
+    pub fn can_transition_to(&self, state: &Self) -> bool {
+        !matches!(self, Self::Archived) || matches!(state, Self::Archived)
+    }
+}
+
 impl AggregatorTaskParameters {
     /// Returns the [`Role`] that this aggregator plays.
     pub fn role(&self) -> &Role {
@@ -633,6 +770,12 @@ pub struct SerializedAggregatorTask {
     task_start: Option<Time>,
     task_end: Option<Time>,
     report_expiry_age: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    report_retention_window: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dp_budget: Option<DpBudget>,
+    #[serde(default, skip_serializing_if = "TaskState::is_active")]
+    state: TaskState,
     min_batch_size: u64,
     time_precision: Duration,
     tolerable_clock_skew: Duration,
@@ -691,6 +834,9 @@ impl Serialize for AggregatorTask {
             task_start: self.task_start().copied(),
             task_end: self.task_end().copied(),
             report_expiry_age: self.report_expiry_age().copied(),
+            report_retention_window: self.report_retention_window().copied(),
+            dp_budget: self.dp_budget().copied(),
+            state: *self.state(),
             min_batch_size: self.min_batch_size(),
             time_precision: *self.time_precision(),
             tolerable_clock_skew: *self.tolerable_clock_skew(),
@@ -762,7 +908,12 @@ impl TryFrom<SerializedAggregatorTask> for AggregatorTask {
             serialized_task.time_precision,
             serialized_task.tolerable_clock_skew,
             aggregator_parameters,
-        )
+        )?
+        .with_report_retention_window(serialized_task.report_retention_window)
+        .map(|task| {
+            task.with_dp_budget(serialized_task.dp_budget)
+                .with_state(serialized_task.state)
+        })
     }
 }
 
@@ -781,7 +932,7 @@ pub mod test_util {
         SecretBytes,
         task::{
             AggregationMode, AggregatorTask, AggregatorTaskParameters, BatchMode,
-            CommonTaskParameters, Error, VerifyKey,
+            CommonTaskParameters, DpBudget, Error, TaskState, VerifyKey,
         },
     };
     use educe::Educe;
@@ -871,6 +1022,9 @@ pub mod test_util {
                     task_start,
                     task_end,
                     report_expiry_age,
+                    report_retention_window: None,
+                    dp_budget: None,
+                    state: TaskState::Active,
                     min_batch_size,
                     time_precision,
                     tolerable_clock_skew,
@@ -939,6 +1093,21 @@ pub mod test_util {
             self.common_parameters.report_expiry_age.as_ref()
         }
 
+        /// Retrieves the report retention window associated with this task.
+        pub fn report_retention_window(&self) -> Option<&Duration> {
+            self.common_parameters.report_retention_window.as_ref()
+        }
+
+        /// Retrieves the differential privacy budget associated with this task.
+        pub fn dp_budget(&self) -> Option<&DpBudget> {
+            self.common_parameters.dp_budget.as_ref()
+        }
+
+        /// Retrieves the lifecycle state of this task.
+        pub fn state(&self) -> &TaskState {
+            &self.common_parameters.state
+        }
+
         /// Retrieves the min batch size parameter associated with this task.
         pub fn min_batch_size(&self) -> u64 {
             self.common_parameters.min_batch_size
@@ -1328,6 +1497,42 @@ pub mod test_util {
             })
         }
 
+        /// Sets the report retention window.
+        pub fn with_report_retention_window(
+            self,
+            report_retention_window: Option<Duration>,
+        ) -> Self {
+            Self(Task {
+                common_parameters: CommonTaskParameters {
+                    report_retention_window,
+                    ..self.0.common_parameters
+                },
+                ..self.0
+            })
+        }
+
+        /// Sets the differential privacy budget.
+        pub fn with_dp_budget(self, dp_budget: Option<DpBudget>) -> Self {
+            Self(Task {
+                common_parameters: CommonTaskParameters {
+                    dp_budget,
+                    ..self.0.common_parameters
+                },
+                ..self.0
+            })
+        }
+
+        /// Sets the lifecycle state.
+        pub fn with_state(self, state: TaskState) -> Self {
+            Self(Task {
+                common_parameters: CommonTaskParameters {
+                    state,
+                    ..self.0.common_parameters
+                },
+                ..self.0
+            })
+        }
+
         /// Set the Taskprov `task_info` field for this task.
         pub fn with_taskprov_task_info(mut self, taskprov_task_info: Vec<u8>) -> Self {
             self.0.common_parameters.taskprov_task_info = Some(taskprov_task_info);
@@ -1362,8 +1567,8 @@ mod tests {
     use crate::{
         SecretBytes,
         task::{
-            AggregationMode, AggregatorTask, AggregatorTaskParameters, BatchMode, VdafInstance,
-            test_util::TaskBuilder,
+            AggregationMode, AggregatorTask, AggregatorTaskParameters, BatchMode, DpBudget,
+            TaskState, VdafInstance, test_util::TaskBuilder,
         },
     };
     use assert_matches::assert_matches;
@@ -1387,7 +1592,9 @@ mod tests {
             TaskBuilder::new(
                 BatchMode::TimeInterval,
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
             )
             .build()
             .leader_view()
@@ -1401,7 +1608,9 @@ mod tests {
             TaskBuilder::new(
                 BatchMode::TimeInterval,
                 AggregationMode::Synchronous,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
             )
             .build()
             .helper_view()
@@ -1409,6 +1618,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dp_budget() {
+        roundtrip_encoding(
+            TaskBuilder::new(
+                BatchMode::TimeInterval,
+                AggregationMode::Synchronous,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
+            )
+            .with_dp_budget(Some(DpBudget::new(1.5).unwrap()))
+            .build()
+            .leader_view()
+            .unwrap(),
+        );
+
+        assert_matches!(DpBudget::new(0.0), Ok(_));
+        assert_matches!(DpBudget::new(-1.0), Err(_));
+        assert_matches!(DpBudget::new(f64::NAN), Err(_));
+        assert_matches!(DpBudget::new(f64::INFINITY), Err(_));
+        assert_matches!(serde_json::from_str::<DpBudget>("-0.5"), Err(_));
+    }
+
+    #[test]
+    fn task_state() {
+        for state in [
+            TaskState::Active,
+            TaskState::Paused,
+            TaskState::Draining,
+            TaskState::Archived,
+        ] {
+            roundtrip_encoding(
+                TaskBuilder::new(
+                    BatchMode::TimeInterval,
+                    AggregationMode::Synchronous,
+                    VdafInstance::Prio3Count {
+                        dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                    },
+                )
+                .with_state(state)
+                .build()
+                .helper_view()
+                .unwrap(),
+            );
+        }
+
+        assert!(TaskState::Active.creates_aggregation_jobs());
+        assert!(TaskState::Paused.creates_aggregation_jobs());
+        assert!(TaskState::Draining.creates_aggregation_jobs());
+        assert!(!TaskState::Archived.creates_aggregation_jobs());
+
+        assert!(TaskState::Paused.can_transition_to(&TaskState::Active));
+        assert!(TaskState::Draining.can_transition_to(&TaskState::Archived));
+        assert!(TaskState::Archived.can_transition_to(&TaskState::Archived));
+        assert!(!TaskState::Archived.can_transition_to(&TaskState::Active));
+        assert!(!TaskState::Archived.can_transition_to(&TaskState::Draining));
+    }
+
     #[test]
     fn deserialize_docs_sample_tasks() {
         serde_yaml::from_str::<Vec<AggregatorTask>>(include_str!("../../docs/samples/tasks.yaml"))
@@ -1420,7 +1687,9 @@ mod tests {
         let task = TaskBuilder::new(
             BatchMode::TimeInterval,
             AggregationMode::Synchronous,
-            VdafInstance::Prio3Count,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
         )
         .with_leader_aggregator_endpoint("http://leader_endpoint/foo/bar".parse().unwrap())
         .with_helper_aggregator_endpoint("http://helper_endpoint".parse().unwrap())
@@ -1444,7 +1713,9 @@ mod tests {
                 TaskBuilder::new(
                     BatchMode::TimeInterval,
                     AggregationMode::Synchronous,
-                    VdafInstance::Prio3Count,
+                    VdafInstance::Prio3Count {
+                        dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                    },
                 )
                 .build(),
             ),
@@ -1453,7 +1724,9 @@ mod tests {
                 TaskBuilder::new(
                     BatchMode::TimeInterval,
                     AggregationMode::Synchronous,
-                    VdafInstance::Prio3Count,
+                    VdafInstance::Prio3Count {
+                        dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                    },
                 )
                 .with_leader_aggregator_endpoint("https://leader.com/prefix/".parse().unwrap())
                 .with_helper_aggregator_endpoint("https://helper.com/prefix/".parse().unwrap())
@@ -1484,7 +1757,9 @@ mod tests {
         let task = TaskBuilder::new(
             BatchMode::TimeInterval,
             AggregationMode::Synchronous,
-            VdafInstance::Prio3Count,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
         )
         .build();
 
@@ -1519,7 +1794,9 @@ mod tests {
                 TaskId::from([0; 32]),
                 "https://example.net/".parse().unwrap(),
                 BatchMode::TimeInterval,
-                VdafInstance::Prio3Count,
+                VdafInstance::Prio3Count {
+                    dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+                },
                 SecretBytes::new(b"1234567812345678".to_vec()),
                 None,
                 None,
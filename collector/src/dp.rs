@@ -0,0 +1,322 @@
+//! Post-processing of aggregate results which were noised for differential privacy.
+//!
+//! When a task's VDAF is configured with a differential privacy strategy, each aggregator adds
+//! noise to its aggregate share. The aggregate result may then lie outside the range of values an
+//! exact aggregate could take, for example a negative count, or histogram buckets which do not sum
+//! to the number of reports. [`DpPostProcessor`] maps noisy results back into that range, and
+//! estimates how far they may be from the exact aggregate.
+//!
+//! Post-processing only uses the published aggregate result and public task parameters, so it
+//! does not weaken the differential privacy guarantee.
+
+use janus_core::vdaf::VdafInstance;
+use prio::field::{Field64, Field128, FieldElementWithInteger};
+
+/// The number of aggregators which each add independent noise to their aggregate share.
+const NOISING_AGGREGATORS: f64 = 2.0;
+
+/// The two-sided critical value of the standard normal distribution at 95% confidence.
+const Z_95: f64 = 1.959_963_984_540_054;
+
+/// The set of values an exact aggregate result can take.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Range {
+    /// Each entry lies between zero and `max_measurement` times the number of reports.
+    Bounded { max_measurement: u128 },
+    /// Entries are non-negative, and sum to the number of reports.
+    Simplex,
+}
+
+/// Post-processes aggregate results of a VDAF which is configured with a differential privacy
+/// strategy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DpPostProcessor {
+    range: Range,
+    modulus: u128,
+    std_dev: f64,
+}
+
+impl DpPostProcessor {
+    /// Creates a post-processor for aggregate results of the given VDAF, driven by its differential
+    /// privacy strategy. Returns `None` if the VDAF adds no noise, or if its aggregate results
+    /// cannot be post-processed.
+    pub fn new(vdaf: &VdafInstance) -> Option<Self> {
+        let (range, modulus) = match vdaf {
+            VdafInstance::Prio3Count { .. } => (
+                Range::Bounded { max_measurement: 1 },
+                u128::from(Field64::modulus()),
+            ),
+            VdafInstance::Prio3Sum {
+                max_measurement, ..
+            } => (
+                Range::Bounded {
+                    max_measurement: u128::from(*max_measurement),
+                },
+                u128::from(Field64::modulus()),
+            ),
+            VdafInstance::Prio3SumVec { bits, .. } => (
+                Range::Bounded {
+                    max_measurement: max_sum_vec_entry(*bits),
+                },
+                Field128::modulus(),
+            ),
+            VdafInstance::Prio3SumVecField64MultiproofHmacSha256Aes128 { bits, .. } => (
+                Range::Bounded {
+                    max_measurement: max_sum_vec_entry(*bits),
+                },
+                u128::from(Field64::modulus()),
+            ),
+            VdafInstance::Prio3Histogram { .. } => (Range::Simplex, Field128::modulus()),
+            _ => return None,
+        };
+        Some(Self {
+            range,
+            modulus,
+            std_dev: NOISING_AGGREGATORS.sqrt() * vdaf.noise_std_dev()?,
+        })
+    }
+
+    /// Returns the standard deviation of the noise in each entry of an aggregate result, combining
+    /// the noise added by both aggregators.
+    pub fn std_dev(&self) -> f64 {
+        self.std_dev
+    }
+
+    /// Post-processes the entries of an aggregate result over `report_count` reports. Entries are
+    /// the integer representatives of field elements, as returned by the VDAF's unsharding.
+    ///
+    /// Counts and sums are clamped to the range of possible values, and histograms are projected
+    /// onto the set of non-negative vectors summing to `report_count`. Confidence intervals are
+    /// computed from the raw entries using a normal approximation of the noise.
+    pub fn process(&self, entries: &[u128], report_count: u64) -> Vec<DpEstimate> {
+        let raw: Vec<f64> = entries.iter().map(|entry| self.signed(*entry)).collect();
+        let report_count = report_count as f64;
+        let (values, upper_bound) = match self.range {
+            Range::Bounded { max_measurement } => {
+                let upper_bound = report_count * max_measurement as f64;
+                (
+                    raw.iter()
+                        .map(|raw| raw.clamp(0.0, upper_bound))
+                        .collect::<Vec<_>>(),
+                    upper_bound,
+                )
+            }
+            Range::Simplex => (project_onto_simplex(&raw, report_count), report_count),
+        };
+
+        let margin = Z_95 * self.std_dev;
+        raw.into_iter()
+            .zip(values)
+            .map(|(raw, value)| DpEstimate {
+                raw,
+                value,
+                std_dev: self.std_dev,
+                confidence_interval: (
+                    (raw - margin).clamp(0.0, upper_bound),
+                    (raw + margin).clamp(0.0, upper_bound),
+                ),
+            })
+            .collect()
+    }
+
+    /// Interprets the integer representative of a field element as a signed value. Noise may push
+    /// an entry below zero, in which case it wraps around the field modulus.
+    fn signed(&self, entry: u128) -> f64 {
+        if entry > self.modulus / 2 {
+            -((self.modulus - entry) as f64)
+        } else {
+            entry as f64
+        }
+    }
+}
+
+/// Returns the largest value of an entry of a `Prio3SumVec` measurement with the given bit length.
+fn max_sum_vec_entry(bits: usize) -> u128 {
+    u32::try_from(bits)
+        .ok()
+        .and_then(|bits| 1u128.checked_shl(bits))
+        .map_or(u128::MAX, |bound| bound - 1)
+}
+
+/// Returns the Euclidean projection of `values` onto the set of non-negative vectors summing to
+/// `total`, following Duchi et al., "Efficient Projections onto the l1-Ball for Learning in High
+/// Dimensions" (ICML 2008).
+fn project_onto_simplex(values: &[f64], total: f64) -> Vec<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| b.total_cmp(a));
+
+    // Find the threshold which, subtracted from every value and clamping at zero, leaves values
+    // summing to `total`. The values which stay positive form a prefix of the sorted values.
+    let mut threshold = sorted.first().map_or(0.0, |largest| largest - total);
+    let mut prefix_sum = 0.0;
+    for (i, value) in sorted.iter().enumerate() {
+        prefix_sum += value;
+        let candidate = (prefix_sum - total) / (i + 1) as f64;
+        if *value <= candidate {
+            break;
+        }
+        threshold = candidate;
+    }
+
+    values
+        .iter()
+        .map(|value| (value - threshold).max(0.0))
+        .collect()
+}
+
+/// A post-processed estimate of one entry of an aggregate result.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DpEstimate {
+    raw: f64,
+    value: f64,
+    std_dev: f64,
+    confidence_interval: (f64, f64),
+}
+
+impl DpEstimate {
+    /// Retrieves the noisy entry, as released by the aggregators.
+    pub fn raw(&self) -> f64 {
+        self.raw
+    }
+
+    /// Retrieves the post-processed entry, which lies in the range of possible values.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Retrieves the standard deviation of the noise in the raw entry.
+    pub fn std_dev(&self) -> f64 {
+        self.std_dev
+    }
+
+    /// Retrieves a 95% confidence interval for the exact entry.
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        self.confidence_interval
+    }
+}
+
+/// Aggregate results whose entries are integer representatives of field elements, and which can
+/// therefore be post-processed by a [`DpPostProcessor`].
+pub trait NoisyAggregateResult {
+    /// Returns the entries of this aggregate result.
+    fn entries(&self) -> Vec<u128>;
+}
+
+impl NoisyAggregateResult for u64 {
+    fn entries(&self) -> Vec<u128> {
+        Vec::from([u128::from(*self)])
+    }
+}
+
+impl NoisyAggregateResult for u128 {
+    fn entries(&self) -> Vec<u128> {
+        Vec::from([*self])
+    }
+}
+
+impl NoisyAggregateResult for Vec<u64> {
+    fn entries(&self) -> Vec<u128> {
+        self.iter().copied().map(u128::from).collect()
+    }
+}
+
+impl NoisyAggregateResult for Vec<u128> {
+    fn entries(&self) -> Vec<u128> {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dp::{DpPostProcessor, NoisyAggregateResult, Z_95, project_onto_simplex};
+    use janus_core::{
+        dp::DpStrategyConfig,
+        vdaf::{VdafInstance, vdaf_dp_strategies},
+    };
+    use prio::field::{Field64, Field128, FieldElementWithInteger};
+
+    #[test]
+    fn no_differential_privacy() {
+        assert_eq!(
+            DpPostProcessor::new(&VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn count_is_clamped() {
+        let processor = DpPostProcessor::new(&VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::PureDpDiscreteLaplace(
+                DpStrategyConfig::from_epsilon(1, 1).unwrap(),
+            ),
+        })
+        .unwrap();
+
+        // A count of -2 wraps around the field modulus.
+        let negative = Field64::modulus() - 2;
+        let estimates = processor.process(&negative.entries(), 10);
+        assert_eq!(estimates.len(), 1);
+        assert_eq!(estimates[0].raw(), -2.0);
+        assert_eq!(estimates[0].value(), 0.0);
+        assert_eq!(estimates[0].confidence_interval().0, 0.0);
+
+        let estimates = processor.process(&13u64.entries(), 10);
+        assert_eq!(estimates[0].raw(), 13.0);
+        assert_eq!(estimates[0].value(), 10.0);
+        assert_eq!(estimates[0].confidence_interval().1, 10.0);
+
+        let estimates = processor.process(&50u64.entries(), 100);
+        assert_eq!(estimates[0].value(), 50.0);
+        assert_eq!(
+            estimates[0].confidence_interval(),
+            (
+                50.0 - Z_95 * processor.std_dev(),
+                50.0 + Z_95 * processor.std_dev()
+            )
+        );
+    }
+
+    #[test]
+    fn histogram_is_projected() {
+        let processor = DpPostProcessor::new(&VdafInstance::Prio3Histogram {
+            length: 4,
+            chunk_length: 2,
+            dp_strategy: vdaf_dp_strategies::Prio3Histogram::ZCdpDiscreteGaussian(
+                DpStrategyConfig::from_epsilon(1, 1).unwrap(),
+            ),
+        })
+        .unwrap();
+        // Noise from both aggregators: sqrt(2) * sqrt(2) / 1.
+        assert!((processor.std_dev() - 2.0).abs() < 1e-9);
+
+        let estimates = processor.process(&[7, 5, Field128::modulus() - 1, 1], 10);
+        let values: Vec<f64> = estimates.iter().map(|estimate| estimate.value()).collect();
+        assert_eq!(values, Vec::from([6.0, 4.0, 0.0, 0.0]));
+    }
+
+    #[test]
+    fn simplex_projection() {
+        // Values already on the simplex are unchanged.
+        assert_eq!(
+            project_onto_simplex(&[1.0, 2.0, 3.0], 6.0),
+            Vec::from([1.0, 2.0, 3.0])
+        );
+        // Excess is removed evenly from positive entries.
+        assert_eq!(
+            project_onto_simplex(&[3.0, 4.0, 5.0], 6.0),
+            Vec::from([1.0, 2.0, 3.0])
+        );
+        // Shortfall is added evenly to all entries.
+        assert_eq!(
+            project_onto_simplex(&[0.0, 1.0, 2.0], 6.0),
+            Vec::from([1.0, 2.0, 3.0])
+        );
+        assert_eq!(
+            project_onto_simplex(&[4.0, 4.0], 0.0),
+            Vec::from([0.0, 0.0])
+        );
+        assert_eq!(project_onto_simplex(&[], 6.0), Vec::<f64>::new());
+    }
+}
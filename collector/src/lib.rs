@@ -62,11 +62,13 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 mod credential;
+mod dp;
 
 use anyhow::Context;
 pub use backon::{BackoffBuilder, ExponentialBackoff, ExponentialBuilder};
 use chrono::{DateTime, Duration, TimeZone, Utc};
 pub use credential::PrivateCollectorCredential;
+pub use dp::{DpEstimate, DpPostProcessor, NoisyAggregateResult};
 use educe::Educe;
 pub use janus_core::auth_tokens::AuthenticationToken;
 use janus_core::{
@@ -261,6 +263,18 @@ where
     }
 }
 
+impl<T, B> Collection<T, B>
+where
+    T: NoisyAggregateResult,
+    B: BatchMode,
+{
+    /// Post-processes the aggregated result of this collection, which was noised for differential
+    /// privacy, returning an estimate for each of its entries. See [`DpPostProcessor::process`].
+    pub fn dp_estimates(&self, processor: &DpPostProcessor) -> Vec<DpEstimate> {
+        processor.process(&self.aggregate_result.entries(), self.report_count)
+    }
+}
+
 #[cfg(feature = "test-util")]
 #[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
 impl<T, B> Collection<T, B>
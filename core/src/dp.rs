@@ -1,5 +1,9 @@
 #[cfg(feature = "fpvec_bounded_l2")]
 use fixed::traits::Fixed;
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_rational::Ratio;
+use num_traits::ToPrimitive;
 #[cfg(feature = "fpvec_bounded_l2")]
 use prio::flp::{
     gadgets::PolyEval,
@@ -10,15 +14,19 @@ use prio::vdaf::{AggregatorWithNoise, dummy};
 use prio::{
     dp::{
         DifferentialPrivacyBudget, DifferentialPrivacyDistribution, DifferentialPrivacyStrategy,
-        DpError,
+        DpError, PureDpBudget, Rational, ZCdpBudget,
+        distributions::{PureDpDiscreteLaplace, ZCdpDiscreteGaussian},
     },
-    field::{Field64, Field128},
+    field::{Field64, Field128, FieldElementWithInteger},
     flp::{
-        TypeWithNoise,
+        FlpError, Type, TypeWithNoise,
         gadgets::{Mul, ParallelSumGadget},
+        types::{Count, Histogram, Sum, SumVec},
     },
 };
-use serde::{Deserialize, Serialize};
+use rand::{distr::Distribution, rng};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as _};
+use std::fmt::Debug;
 
 /// An "empty" differential privacy budget type. Tasks which don't require differential privacy
 /// should use this type as their `DifferentialPrivacyBudget`.
@@ -46,6 +54,183 @@ impl DifferentialPrivacyStrategy for NoDifferentialPrivacy {
     }
 }
 
+/// A differential privacy strategy from libprio, applied by Janus to a VDAF for which libprio does
+/// not implement it.
+///
+/// Both libprio's strategies and its FLP types are foreign to this crate, so Janus' noise
+/// implementations are keyed on this wrapper instead of on the wrapped strategy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JanusStrategy<S>(pub S);
+
+impl<S: DifferentialPrivacyStrategy> DifferentialPrivacyStrategy for JanusStrategy<S> {
+    type Budget = S::Budget;
+    type Distribution = S::Distribution;
+    type Sensitivity = S::Sensitivity;
+    fn from_budget(b: Self::Budget) -> Self {
+        Self(S::from_budget(b))
+    }
+    fn create_distribution(&self, s: Self::Sensitivity) -> Result<Self::Distribution, DpError> {
+        self.0.create_distribution(s)
+    }
+}
+
+/// Differential privacy strategies whose application to an aggregate share spends privacy budget.
+///
+/// The privacy loss is measured in the units natural to the strategy's notion of privacy: epsilon
+/// for pure differential privacy, and rho for zero-concentrated differential privacy. In either
+/// case, the losses of successive releases compose by addition.
+pub trait PrivacyLoss {
+    /// Returns the privacy loss incurred each time an aggregate share is noised with this
+    /// strategy.
+    fn privacy_loss(&self) -> f64;
+}
+
+impl PrivacyLoss for NoDifferentialPrivacy {
+    fn privacy_loss(&self) -> f64 {
+        0.0
+    }
+}
+
+impl PrivacyLoss for DpStrategyConfig<PureDpDiscreteLaplace> {
+    fn privacy_loss(&self) -> f64 {
+        self.epsilon_f64()
+    }
+}
+
+impl PrivacyLoss for DpStrategyConfig<ZCdpDiscreteGaussian> {
+    fn privacy_loss(&self) -> f64 {
+        // `ZCdpBudget` is parameterized by epsilon, from which rho = epsilon^2 / 2.
+        let epsilon = self.epsilon_f64();
+        epsilon * epsilon / 2.0
+    }
+}
+
+/// Differential privacy strategies whose noise has a known spread.
+pub trait NoiseStdDev {
+    /// Returns the standard deviation of the noise this strategy adds to each entry of an
+    /// aggregate share, given the sensitivity of the aggregation in the norm the strategy is
+    /// calibrated to: the l1-sensitivity for pure differential privacy, and the l2-sensitivity for
+    /// zero-concentrated differential privacy.
+    fn noise_std_dev(&self, sensitivity: f64) -> f64;
+}
+
+impl NoiseStdDev for DpStrategyConfig<PureDpDiscreteLaplace> {
+    fn noise_std_dev(&self, sensitivity: f64) -> f64 {
+        // The noise follows a discrete Laplace distribution with scale t = sensitivity / epsilon,
+        // whose variance is 2p / (1 - p)^2 for p = e^(-1/t).
+        let inverse_scale = self.epsilon_f64() / sensitivity;
+        let p = (-inverse_scale).exp();
+        let one_minus_p = -(-inverse_scale).exp_m1();
+        (2.0 * p).sqrt() / one_minus_p
+    }
+}
+
+impl NoiseStdDev for DpStrategyConfig<ZCdpDiscreteGaussian> {
+    fn noise_std_dev(&self, sensitivity: f64) -> f64 {
+        // The noise follows a discrete Gaussian distribution with sigma = sensitivity / epsilon,
+        // whose variance is at most sigma^2.
+        sensitivity / self.epsilon_f64()
+    }
+}
+
+/// Differential privacy strategies from libprio whose budget is parameterized by a single epsilon.
+pub trait EpsilonStrategy: DifferentialPrivacyStrategy + Sized {
+    /// Builds the strategy from the epsilon parameter of its budget.
+    fn from_epsilon(epsilon: Rational) -> Result<Self, DpError>;
+}
+
+impl EpsilonStrategy for PureDpDiscreteLaplace {
+    fn from_epsilon(epsilon: Rational) -> Result<Self, DpError> {
+        Ok(Self::from_budget(PureDpBudget::new(epsilon)?))
+    }
+}
+
+impl EpsilonStrategy for ZCdpDiscreteGaussian {
+    fn from_epsilon(epsilon: Rational) -> Result<Self, DpError> {
+        Ok(Self::from_budget(ZCdpBudget::new(epsilon)))
+    }
+}
+
+/// A differential privacy strategy from libprio, as configured for a task.
+///
+/// libprio does not expose the budgets of its strategies, so this keeps the epsilon the strategy
+/// was built from, which privacy budget accounting and noise estimates are computed from. It is
+/// serialized in the same form as libprio's strategies, `{"budget": {"epsilon": [[n], [d]]}}`, but
+/// independently of libprio's serde implementations.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DpStrategyConfig<S> {
+    epsilon: Ratio<u128>,
+    strategy: S,
+}
+
+impl<S: EpsilonStrategy> DpStrategyConfig<S> {
+    /// Builds a strategy whose budget has epsilon `numerator / denominator`.
+    pub fn from_epsilon(numerator: u128, denominator: u128) -> Result<Self, DpError> {
+        let strategy = S::from_epsilon(Rational::from_unsigned(numerator, denominator)?)?;
+        Ok(Self {
+            epsilon: Ratio::new(numerator, denominator),
+            strategy,
+        })
+    }
+}
+
+impl<S> DpStrategyConfig<S> {
+    /// Returns the libprio strategy.
+    pub fn strategy(&self) -> &S {
+        &self.strategy
+    }
+
+    /// Consumes this configuration, returning the libprio strategy.
+    pub fn into_strategy(self) -> S {
+        self.strategy
+    }
+
+    fn epsilon_f64(&self) -> f64 {
+        *self.epsilon.numer() as f64 / *self.epsilon.denom() as f64
+    }
+}
+
+impl<S> Serialize for DpStrategyConfig<S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        SerializedStrategy {
+            budget: SerializedBudget {
+                epsilon: Ratio::new_raw(
+                    BigUint::from(*self.epsilon.numer()),
+                    BigUint::from(*self.epsilon.denom()),
+                ),
+            },
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, S: EpsilonStrategy> Deserialize<'de> for DpStrategyConfig<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let epsilon = SerializedStrategy::deserialize(deserializer)?
+            .budget
+            .epsilon;
+        let (Some(numerator), Some(denominator)) =
+            (epsilon.numer().to_u128(), epsilon.denom().to_u128())
+        else {
+            return Err(D::Error::custom("epsilon is out of range"));
+        };
+        Self::from_epsilon(numerator, denominator).map_err(D::Error::custom)
+    }
+}
+
+/// The serialized form of a differential privacy strategy parameterized by epsilon.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "DpStrategyConfig")]
+struct SerializedStrategy {
+    budget: SerializedBudget,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "DpBudget")]
+struct SerializedBudget {
+    epsilon: Ratio<BigUint>,
+}
+
 // identity strategy implementations for vdafs from janus
 #[cfg(feature = "test-util")]
 impl AggregatorWithNoise<0, 16, NoDifferentialPrivacy> for dummy::Vdaf {
@@ -61,29 +246,205 @@ impl AggregatorWithNoise<0, 16, NoDifferentialPrivacy> for dummy::Vdaf {
 }
 
 // identity strategy implementations for vdafs from libprio
-impl TypeWithNoise<NoDifferentialPrivacy> for prio::flp::types::Sum<Field64> {
+impl TypeWithNoise<NoDifferentialPrivacy> for Sum<Field64> {
     fn add_noise_to_result(
         &self,
         _dp_strategy: &NoDifferentialPrivacy,
         _agg_result: &mut [Self::Field],
         _num_measurements: usize,
-    ) -> Result<(), prio::flp::FlpError> {
+    ) -> Result<(), FlpError> {
         Ok(())
     }
 }
 
-impl TypeWithNoise<NoDifferentialPrivacy> for prio::flp::types::Count<Field64> {
+impl TypeWithNoise<NoDifferentialPrivacy> for Count<Field64> {
     fn add_noise_to_result(
         &self,
         _dp_strategy: &NoDifferentialPrivacy,
         _agg_result: &mut [Self::Field],
         _num_measurements: usize,
-    ) -> Result<(), prio::flp::FlpError> {
+    ) -> Result<(), FlpError> {
         Ok(())
     }
 }
 
-impl<PS> TypeWithNoise<NoDifferentialPrivacy> for prio::flp::types::Histogram<Field128, PS>
+// noise implementations for vdafs from libprio which libprio does not provide itself
+
+/// Adds noise drawn independently from `distribution` to each element of an aggregate result.
+fn add_iid_noise<F, D>(agg_result: &mut [F], distribution: &D) -> Result<(), FlpError>
+where
+    F: FieldElementWithInteger,
+    F::Integer: Into<u128> + TryFrom<u128>,
+    <F::Integer as TryFrom<u128>>::Error: Debug,
+    D: Distribution<BigInt>,
+{
+    let mut rng = rng();
+    let modulus: u128 = F::modulus().into();
+    let modulus = BigInt::from(modulus);
+    for entry in agg_result.iter_mut() {
+        // Reducing modulo the field modulus leaves a non-negative value below the modulus, which
+        // fits in the field's integer type.
+        let noise = distribution
+            .sample(&mut rng)
+            .mod_floor(&modulus)
+            .to_u128()
+            .expect("reduced noise is non-negative and below 2^128");
+        *entry += <F as From<F::Integer>>::from(
+            F::Integer::try_from(noise).expect("reduced noise is below the field modulus"),
+        );
+    }
+    Ok(())
+}
+
+/// The sensitivity of a `Count` aggregation. Replacing one measurement changes the count by at most
+/// one, so this is both the l1- and l2-sensitivity.
+fn count_sensitivity() -> Ratio<BigUint> {
+    Ratio::from_integer(BigUint::from(1u8))
+}
+
+/// An upper bound on the sensitivity of a `Sum` aggregation, which is both the l1- and
+/// l2-sensitivity since the aggregate is a scalar.
+///
+/// Replacing one measurement changes the sum by at most `max_measurement`, which `Sum` does not
+/// expose. Each measurement is encoded as two bit decompositions of `bits` bits each, so we use the
+/// largest value representable in `bits` bits instead. This is at most twice `max_measurement`, and
+/// is exact when `max_measurement` is one less than a power of two.
+fn sum_sensitivity(sum: &Sum<Field64>) -> Ratio<BigUint> {
+    let bits = sum.input_len() / 2;
+    Ratio::from_integer((BigUint::from(1u8) << bits) - 1u8)
+}
+
+/// Returns a rational upper bound on `multiplier * sqrt(n)`, which exceeds it by less than
+/// `multiplier / 2^32`.
+///
+/// The l2-sensitivities of vector-valued aggregations are generally irrational, while libprio's
+/// distributions take rational sensitivities. Overestimating the sensitivity adds slightly more
+/// noise than necessary, but never weakens the privacy guarantee.
+fn sqrt_upper_bound(multiplier: BigUint, n: usize) -> Ratio<BigUint> {
+    let denominator = BigUint::from(1u8) << 32;
+    let scaled = BigUint::from(n) * &denominator * &denominator;
+    let mut root = scaled.sqrt();
+    if &root * &root < scaled {
+        root += 1u8;
+    }
+    Ratio::new(multiplier * root, denominator)
+}
+
+/// An upper bound on the l2-sensitivity of a `Histogram` aggregation.
+///
+/// Replacing one measurement moves a single count from one bucket to another, changing two entries
+/// of the aggregate by one each, so the l2-sensitivity is `sqrt(2)`.
+fn histogram_l2_sensitivity() -> Ratio<BigUint> {
+    sqrt_upper_bound(BigUint::from(1u8), 2)
+}
+
+/// An upper bound on the l2-sensitivity of a `SumVec` aggregation.
+///
+/// Each of the `length` entries of a measurement lies between zero and `2^bits - 1`, so replacing
+/// one measurement changes each entry of the aggregate by at most `2^bits - 1`, and the
+/// l2-sensitivity is `(2^bits - 1) * sqrt(length)`.
+fn sum_vec_l2_sensitivity<T: Type>(sum_vec: &T) -> Ratio<BigUint> {
+    // `SumVec` does not expose its parameters, but encodes each of its `length` output entries as
+    // `bits` input entries.
+    let length = sum_vec.output_len();
+    let bits = sum_vec.input_len().checked_div(length).unwrap_or(0);
+    sqrt_upper_bound((BigUint::from(1u8) << bits) - 1u8, length)
+}
+
+impl TypeWithNoise<JanusStrategy<PureDpDiscreteLaplace>> for Count<Field64> {
+    fn add_noise_to_result(
+        &self,
+        dp_strategy: &JanusStrategy<PureDpDiscreteLaplace>,
+        agg_result: &mut [Self::Field],
+        _num_measurements: usize,
+    ) -> Result<(), FlpError> {
+        let distribution = dp_strategy.create_distribution(count_sensitivity())?;
+        add_iid_noise(agg_result, &distribution)
+    }
+}
+
+impl TypeWithNoise<JanusStrategy<ZCdpDiscreteGaussian>> for Count<Field64> {
+    fn add_noise_to_result(
+        &self,
+        dp_strategy: &JanusStrategy<ZCdpDiscreteGaussian>,
+        agg_result: &mut [Self::Field],
+        _num_measurements: usize,
+    ) -> Result<(), FlpError> {
+        let distribution = dp_strategy.create_distribution(count_sensitivity())?;
+        add_iid_noise(agg_result, &distribution)
+    }
+}
+
+impl TypeWithNoise<JanusStrategy<PureDpDiscreteLaplace>> for Sum<Field64> {
+    fn add_noise_to_result(
+        &self,
+        dp_strategy: &JanusStrategy<PureDpDiscreteLaplace>,
+        agg_result: &mut [Self::Field],
+        _num_measurements: usize,
+    ) -> Result<(), FlpError> {
+        let distribution = dp_strategy.create_distribution(sum_sensitivity(self))?;
+        add_iid_noise(agg_result, &distribution)
+    }
+}
+
+impl TypeWithNoise<JanusStrategy<ZCdpDiscreteGaussian>> for Sum<Field64> {
+    fn add_noise_to_result(
+        &self,
+        dp_strategy: &JanusStrategy<ZCdpDiscreteGaussian>,
+        agg_result: &mut [Self::Field],
+        _num_measurements: usize,
+    ) -> Result<(), FlpError> {
+        let distribution = dp_strategy.create_distribution(sum_sensitivity(self))?;
+        add_iid_noise(agg_result, &distribution)
+    }
+}
+
+impl<PS> TypeWithNoise<JanusStrategy<ZCdpDiscreteGaussian>> for Histogram<Field128, PS>
+where
+    PS: ParallelSumGadget<Field128, Mul<Field128>> + Eq + 'static,
+{
+    fn add_noise_to_result(
+        &self,
+        dp_strategy: &JanusStrategy<ZCdpDiscreteGaussian>,
+        agg_result: &mut [Self::Field],
+        _num_measurements: usize,
+    ) -> Result<(), FlpError> {
+        let distribution = dp_strategy.create_distribution(histogram_l2_sensitivity())?;
+        add_iid_noise(agg_result, &distribution)
+    }
+}
+
+impl<PS> TypeWithNoise<JanusStrategy<ZCdpDiscreteGaussian>> for SumVec<Field128, PS>
+where
+    PS: ParallelSumGadget<Field128, Mul<Field128>> + Eq + 'static,
+{
+    fn add_noise_to_result(
+        &self,
+        dp_strategy: &JanusStrategy<ZCdpDiscreteGaussian>,
+        agg_result: &mut [Self::Field],
+        _num_measurements: usize,
+    ) -> Result<(), FlpError> {
+        let distribution = dp_strategy.create_distribution(sum_vec_l2_sensitivity(self))?;
+        add_iid_noise(agg_result, &distribution)
+    }
+}
+
+impl<PS> TypeWithNoise<JanusStrategy<ZCdpDiscreteGaussian>> for SumVec<Field64, PS>
+where
+    PS: ParallelSumGadget<Field64, Mul<Field64>> + Eq + 'static,
+{
+    fn add_noise_to_result(
+        &self,
+        dp_strategy: &JanusStrategy<ZCdpDiscreteGaussian>,
+        agg_result: &mut [Self::Field],
+        _num_measurements: usize,
+    ) -> Result<(), FlpError> {
+        let distribution = dp_strategy.create_distribution(sum_vec_l2_sensitivity(self))?;
+        add_iid_noise(agg_result, &distribution)
+    }
+}
+
+impl<PS> TypeWithNoise<NoDifferentialPrivacy> for Histogram<Field128, PS>
 where
     PS: ParallelSumGadget<Field128, Mul<Field128>> + Eq + 'static,
 {
@@ -92,12 +453,12 @@ where
         _dp_strategy: &NoDifferentialPrivacy,
         _agg_result: &mut [Self::Field],
         _num_measurements: usize,
-    ) -> Result<(), prio::flp::FlpError> {
+    ) -> Result<(), FlpError> {
         Ok(())
     }
 }
 
-impl<PS> TypeWithNoise<NoDifferentialPrivacy> for prio::flp::types::SumVec<Field128, PS>
+impl<PS> TypeWithNoise<NoDifferentialPrivacy> for SumVec<Field128, PS>
 where
     PS: ParallelSumGadget<Field128, Mul<Field128>> + Eq + 'static,
 {
@@ -106,12 +467,12 @@ where
         _dp_strategy: &NoDifferentialPrivacy,
         _agg_result: &mut [Self::Field],
         _num_measurements: usize,
-    ) -> Result<(), prio::flp::FlpError> {
+    ) -> Result<(), FlpError> {
         Ok(())
     }
 }
 
-impl<PS> TypeWithNoise<NoDifferentialPrivacy> for prio::flp::types::SumVec<Field64, PS>
+impl<PS> TypeWithNoise<NoDifferentialPrivacy> for SumVec<Field64, PS>
 where
     PS: ParallelSumGadget<Field64, Mul<Field64>> + Eq + 'static,
 {
@@ -120,7 +481,7 @@ where
         _dp_strategy: &NoDifferentialPrivacy,
         _agg_result: &mut [Self::Field],
         _num_measurements: usize,
-    ) -> Result<(), prio::flp::FlpError> {
+    ) -> Result<(), FlpError> {
         Ok(())
     }
 }
@@ -138,7 +499,81 @@ where
         _dp_strategy: &NoDifferentialPrivacy,
         _agg_result: &mut [Self::Field],
         _num_measurements: usize,
-    ) -> Result<(), prio::flp::FlpError> {
+    ) -> Result<(), FlpError> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::dp::{
+        DpStrategyConfig, PrivacyLoss, histogram_l2_sensitivity, sqrt_upper_bound,
+        sum_vec_l2_sensitivity,
+    };
+    use num_bigint::BigUint;
+    use num_rational::Ratio;
+    use prio::{
+        dp::distributions::{PureDpDiscreteLaplace, ZCdpDiscreteGaussian},
+        field::{Field64, Field128},
+        flp::{
+            gadgets::{Mul, ParallelSum},
+            types::SumVec,
+        },
+    };
+
+    #[test]
+    fn sqrt_upper_bound_is_tight() {
+        // Perfect squares are bounded exactly.
+        assert_eq!(
+            sqrt_upper_bound(BigUint::from(3u8), 16),
+            Ratio::from_integer(BigUint::from(12u8))
+        );
+
+        // Otherwise, the bound exceeds the root by less than 2^-32.
+        let bound = histogram_l2_sensitivity();
+        let two = Ratio::from_integer(BigUint::from(2u8));
+        let epsilon = Ratio::new(BigUint::from(1u8), BigUint::from(1u8) << 32);
+        assert!(&bound * &bound > two);
+        assert!((&bound - &epsilon) * (&bound - &epsilon) < two);
+    }
+
+    #[test]
+    fn sum_vec_sensitivity() {
+        // (2^2 - 1) * sqrt(9)
+        assert_eq!(
+            sum_vec_l2_sensitivity(
+                &SumVec::<Field128, ParallelSum<Field128, Mul<Field128>>>::new(2, 9, 3).unwrap()
+            ),
+            Ratio::from_integer(BigUint::from(9u8))
+        );
+        // (2^8 - 1) * sqrt(4)
+        assert_eq!(
+            sum_vec_l2_sensitivity(
+                &SumVec::<Field64, ParallelSum<Field64, Mul<Field64>>>::new(8, 4, 2).unwrap()
+            ),
+            Ratio::from_integer(BigUint::from(510u16))
+        );
+    }
+
+    #[test]
+    fn dp_strategy_config_serialization() {
+        let strategy: DpStrategyConfig<ZCdpDiscreteGaussian> =
+            serde_yaml::from_str("budget: {epsilon: [[2], [4]]}").unwrap();
+        assert_eq!(strategy, DpStrategyConfig::from_epsilon(1, 2).unwrap());
+        assert_eq!(strategy.privacy_loss(), 0.125);
+        assert_eq!(
+            serde_yaml::from_str::<serde_yaml::Value>(&serde_yaml::to_string(&strategy).unwrap())
+                .unwrap(),
+            serde_yaml::from_str::<serde_yaml::Value>("budget: {epsilon: [[1], [2]]}").unwrap()
+        );
+
+        // Malformed budgets, and epsilons which don't fit in a u128, fail to deserialize.
+        for invalid in [
+            "budget: {epsilon: [[1], [0]]}",
+            "budget: {epsilon: [[0, 0, 0, 0, 1], [1]]}",
+            "budget: {}",
+        ] {
+            serde_yaml::from_str::<DpStrategyConfig<PureDpDiscreteLaplace>>(invalid).unwrap_err();
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use crate::DAP_VERSION_IDENTIFIER;
+use crate::{
+    DAP_VERSION_IDENTIFIER,
+    dp::{NoiseStdDev, PrivacyLoss},
+};
 use janus_messages::{TaskId, taskprov};
 use prio::{
     field::Field64,
@@ -8,8 +11,14 @@ use prio::{
     },
     vdaf::{VdafError, prio3::Prio3, xof::XofHmacSha256Aes128},
 };
-use serde::{Deserialize, Serialize};
-use std::str;
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{
+        self, EnumAccess, MapAccess, Visitor,
+        value::{EnumAccessDeserializer, MapAccessDeserializer, StrDeserializer},
+    },
+};
+use std::{fmt, str};
 
 /// The length of the verify key parameter for Prio3 VDAF instantiations using
 /// [`XofTurboShake128`][prio::vdaf::xof::XofTurboShake128].
@@ -49,17 +58,67 @@ pub enum Prio3FixedPointBoundedL2VecSumBitSize {
 /// If a VDAF only supports a single strategy, such as for example `NoDifferentialPrivacy`, then no
 /// enum is required.
 pub mod vdaf_dp_strategies {
-    use prio::dp::distributions::PureDpDiscreteLaplace;
-    #[cfg(feature = "fpvec_bounded_l2")]
-    use prio::dp::distributions::ZCdpDiscreteGaussian;
+    use crate::dp::{DpStrategyConfig, PrivacyLoss};
+    use prio::dp::distributions::{PureDpDiscreteLaplace, ZCdpDiscreteGaussian};
     use serde::{Deserialize, Serialize};
 
+    /// Differential privacy strategies supported by `Prio3Count`.
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+    #[serde(tag = "dp_strategy")]
+    pub enum Prio3Count {
+        NoDifferentialPrivacy,
+        PureDpDiscreteLaplace(DpStrategyConfig<PureDpDiscreteLaplace>),
+        ZCdpDiscreteGaussian(DpStrategyConfig<ZCdpDiscreteGaussian>),
+    }
+
+    impl Default for Prio3Count {
+        fn default() -> Self {
+            Self::NoDifferentialPrivacy
+        }
+    }
+
+    impl PrivacyLoss for Prio3Count {
+        fn privacy_loss(&self) -> f64 {
+            match self {
+                Self::NoDifferentialPrivacy => 0.0,
+                Self::PureDpDiscreteLaplace(dp_strategy) => dp_strategy.privacy_loss(),
+                Self::ZCdpDiscreteGaussian(dp_strategy) => dp_strategy.privacy_loss(),
+            }
+        }
+    }
+
+    /// Differential privacy strategies supported by `Prio3Sum`.
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+    #[serde(tag = "dp_strategy")]
+    pub enum Prio3Sum {
+        NoDifferentialPrivacy,
+        PureDpDiscreteLaplace(DpStrategyConfig<PureDpDiscreteLaplace>),
+        ZCdpDiscreteGaussian(DpStrategyConfig<ZCdpDiscreteGaussian>),
+    }
+
+    impl Default for Prio3Sum {
+        fn default() -> Self {
+            Self::NoDifferentialPrivacy
+        }
+    }
+
+    impl PrivacyLoss for Prio3Sum {
+        fn privacy_loss(&self) -> f64 {
+            match self {
+                Self::NoDifferentialPrivacy => 0.0,
+                Self::PureDpDiscreteLaplace(dp_strategy) => dp_strategy.privacy_loss(),
+                Self::ZCdpDiscreteGaussian(dp_strategy) => dp_strategy.privacy_loss(),
+            }
+        }
+    }
+
     /// Differential privacy strategies supported by `Prio3Histogram`.
     #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
     #[serde(tag = "dp_strategy")]
     pub enum Prio3Histogram {
         NoDifferentialPrivacy,
-        PureDpDiscreteLaplace(PureDpDiscreteLaplace),
+        PureDpDiscreteLaplace(DpStrategyConfig<PureDpDiscreteLaplace>),
+        ZCdpDiscreteGaussian(DpStrategyConfig<ZCdpDiscreteGaussian>),
     }
 
     impl Default for Prio3Histogram {
@@ -68,12 +127,23 @@ pub mod vdaf_dp_strategies {
         }
     }
 
+    impl PrivacyLoss for Prio3Histogram {
+        fn privacy_loss(&self) -> f64 {
+            match self {
+                Self::NoDifferentialPrivacy => 0.0,
+                Self::PureDpDiscreteLaplace(dp_strategy) => dp_strategy.privacy_loss(),
+                Self::ZCdpDiscreteGaussian(dp_strategy) => dp_strategy.privacy_loss(),
+            }
+        }
+    }
+
     /// Differential privacy strategies supported by `Prio3SumVec`.
     #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
     #[serde(tag = "dp_strategy")]
     pub enum Prio3SumVec {
         NoDifferentialPrivacy,
-        PureDpDiscreteLaplace(PureDpDiscreteLaplace),
+        PureDpDiscreteLaplace(DpStrategyConfig<PureDpDiscreteLaplace>),
+        ZCdpDiscreteGaussian(DpStrategyConfig<ZCdpDiscreteGaussian>),
     }
 
     impl Default for Prio3SumVec {
@@ -82,13 +152,23 @@ pub mod vdaf_dp_strategies {
         }
     }
 
+    impl PrivacyLoss for Prio3SumVec {
+        fn privacy_loss(&self) -> f64 {
+            match self {
+                Self::NoDifferentialPrivacy => 0.0,
+                Self::PureDpDiscreteLaplace(dp_strategy) => dp_strategy.privacy_loss(),
+                Self::ZCdpDiscreteGaussian(dp_strategy) => dp_strategy.privacy_loss(),
+            }
+        }
+    }
+
     /// Differential privacy strategies supported by `Prio3FixedPointBoundedL2VecSum`.
     #[cfg(feature = "fpvec_bounded_l2")]
     #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
     #[serde(tag = "dp_strategy")]
     pub enum Prio3FixedPointBoundedL2VecSum {
         NoDifferentialPrivacy,
-        ZCdpDiscreteGaussian(ZCdpDiscreteGaussian),
+        ZCdpDiscreteGaussian(DpStrategyConfig<ZCdpDiscreteGaussian>),
     }
 
     #[cfg(feature = "fpvec_bounded_l2")]
@@ -97,19 +177,46 @@ pub mod vdaf_dp_strategies {
             Self::NoDifferentialPrivacy
         }
     }
+
+    #[cfg(feature = "fpvec_bounded_l2")]
+    impl PrivacyLoss for Prio3FixedPointBoundedL2VecSum {
+        fn privacy_loss(&self) -> f64 {
+            match self {
+                Self::NoDifferentialPrivacy => 0.0,
+                Self::ZCdpDiscreteGaussian(dp_strategy) => dp_strategy.privacy_loss(),
+            }
+        }
+    }
 }
 
 /// Identifiers for supported VDAFs, corresponding to definitions in
 /// [draft-irtf-cfrg-vdaf-03][1] and implementations in [`prio::vdaf::prio3`].
 ///
 /// [1]: https://datatracker.ietf.org/doc/draft-irtf-cfrg-vdaf/03/
+///
+/// `VdafInstance` can only be deserialized from self-describing formats, such as JSON or YAML: the
+/// legacy unit variant form of `Prio3Count` is told apart from its struct variant form by
+/// inspecting the input, and the differential privacy strategies are internally tagged. Formats
+/// like bincode or postcard are not supported.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+// The derived implementations are wrapped by the `Serialize` and `Deserialize` impls below, which
+// keep the legacy unit variant form of `Prio3Count`.
+#[serde(remote = "Self")]
 #[non_exhaustive]
 pub enum VdafInstance {
     /// A `Prio3` counter.
-    Prio3Count,
+    Prio3Count {
+        #[serde(default)]
+        dp_strategy: vdaf_dp_strategies::Prio3Count,
+    },
     /// A `Prio3` sum.
-    Prio3Sum { max_measurement: u64 },
+    Prio3Sum {
+        max_measurement: u64,
+        // Omitted without differential privacy, so that `Prio3Sum` keeps the form it had before it
+        // carried a DP strategy.
+        #[serde(default, skip_serializing_if = "is_prio3_sum_without_dp")]
+        dp_strategy: vdaf_dp_strategies::Prio3Sum,
+    },
     /// A vector of `Prio3` sums.
     Prio3SumVec {
         bits: usize,
@@ -174,6 +281,166 @@ impl VdafInstance {
             _ => VERIFY_KEY_LENGTH_PRIO3,
         }
     }
+
+    /// Returns the privacy loss incurred each time an aggregator adds differential privacy noise to
+    /// an aggregate share of this VDAF, measured as described by [`PrivacyLoss`]. VDAFs without
+    /// differential privacy incur no loss.
+    pub fn privacy_loss(&self) -> f64 {
+        match self {
+            VdafInstance::Prio3Count { dp_strategy } => dp_strategy.privacy_loss(),
+            VdafInstance::Prio3Sum { dp_strategy, .. } => dp_strategy.privacy_loss(),
+            VdafInstance::Prio3SumVec { dp_strategy, .. }
+            | VdafInstance::Prio3SumVecField64MultiproofHmacSha256Aes128 { dp_strategy, .. } => {
+                dp_strategy.privacy_loss()
+            }
+            VdafInstance::Prio3Histogram { dp_strategy, .. } => dp_strategy.privacy_loss(),
+            #[cfg(feature = "fpvec_bounded_l2")]
+            VdafInstance::Prio3FixedPointBoundedL2VecSum { dp_strategy, .. } => {
+                dp_strategy.privacy_loss()
+            }
+            #[cfg(feature = "test-util")]
+            VdafInstance::Fake { .. }
+            | VdafInstance::FakeFailsPrepInit
+            | VdafInstance::FakeFailsPrepStep => 0.0,
+        }
+    }
+
+    /// Returns the standard deviation of the differential privacy noise which each aggregator adds
+    /// to each entry of an aggregate share of this VDAF, as described by [`NoiseStdDev`], or `None`
+    /// if the VDAF adds no noise or its noise is not modeled here.
+    ///
+    /// The sensitivities used here match those Janus and libprio use when noising aggregate shares,
+    /// treating two measurements as neighboring if one replaces the other.
+    pub fn noise_std_dev(&self) -> Option<f64> {
+        match self {
+            VdafInstance::Prio3Count { dp_strategy } => match dp_strategy {
+                vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy => None,
+                vdaf_dp_strategies::Prio3Count::PureDpDiscreteLaplace(dp_strategy) => {
+                    Some(dp_strategy.noise_std_dev(1.0))
+                }
+                vdaf_dp_strategies::Prio3Count::ZCdpDiscreteGaussian(dp_strategy) => {
+                    Some(dp_strategy.noise_std_dev(1.0))
+                }
+            },
+            VdafInstance::Prio3Sum {
+                max_measurement,
+                dp_strategy,
+            } => {
+                // Janus bounds the sensitivity by the largest value representable in as many bits
+                // as `max_measurement`.
+                let bits = u64::BITS - max_measurement.leading_zeros();
+                let sensitivity = 2f64.powi(bits as i32) - 1.0;
+                match dp_strategy {
+                    vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy => None,
+                    vdaf_dp_strategies::Prio3Sum::PureDpDiscreteLaplace(dp_strategy) => {
+                        Some(dp_strategy.noise_std_dev(sensitivity))
+                    }
+                    vdaf_dp_strategies::Prio3Sum::ZCdpDiscreteGaussian(dp_strategy) => {
+                        Some(dp_strategy.noise_std_dev(sensitivity))
+                    }
+                }
+            }
+            VdafInstance::Prio3SumVec {
+                bits,
+                length,
+                dp_strategy,
+                ..
+            }
+            | VdafInstance::Prio3SumVecField64MultiproofHmacSha256Aes128 {
+                bits,
+                length,
+                dp_strategy,
+                ..
+            } => {
+                // Each entry of a measurement lies between zero and 2^bits - 1.
+                let max_entry = 2f64.powi(*bits as i32) - 1.0;
+                match dp_strategy {
+                    vdaf_dp_strategies::Prio3SumVec::NoDifferentialPrivacy => None,
+                    vdaf_dp_strategies::Prio3SumVec::PureDpDiscreteLaplace(dp_strategy) => {
+                        Some(dp_strategy.noise_std_dev(max_entry * *length as f64))
+                    }
+                    vdaf_dp_strategies::Prio3SumVec::ZCdpDiscreteGaussian(dp_strategy) => {
+                        Some(dp_strategy.noise_std_dev(max_entry * (*length as f64).sqrt()))
+                    }
+                }
+            }
+            VdafInstance::Prio3Histogram { dp_strategy, .. } => match dp_strategy {
+                vdaf_dp_strategies::Prio3Histogram::NoDifferentialPrivacy => None,
+                // Replacing a measurement changes two buckets by one each.
+                vdaf_dp_strategies::Prio3Histogram::PureDpDiscreteLaplace(dp_strategy) => {
+                    Some(dp_strategy.noise_std_dev(2.0))
+                }
+                vdaf_dp_strategies::Prio3Histogram::ZCdpDiscreteGaussian(dp_strategy) => {
+                    Some(dp_strategy.noise_std_dev(2f64.sqrt()))
+                }
+            },
+            // libprio calibrates this VDAF's noise to its fixed point encoding, which is not
+            // modeled here.
+            #[cfg(feature = "fpvec_bounded_l2")]
+            VdafInstance::Prio3FixedPointBoundedL2VecSum { .. } => None,
+            #[cfg(feature = "test-util")]
+            VdafInstance::Fake { .. }
+            | VdafInstance::FakeFailsPrepInit
+            | VdafInstance::FakeFailsPrepStep => None,
+        }
+    }
+}
+
+fn is_prio3_sum_without_dp(dp_strategy: &vdaf_dp_strategies::Prio3Sum) -> bool {
+    matches!(
+        dp_strategy,
+        vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy
+    )
+}
+
+impl Serialize for VdafInstance {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            // Without differential privacy, `Prio3Count` keeps the unit variant form it had before
+            // it carried a DP strategy, so that previously-written readers can still parse it.
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            } => serializer.serialize_unit_variant("VdafInstance", 0, "Prio3Count"),
+            _ => VdafInstance::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for VdafInstance {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // `deserialize_any` is needed to tell the unit variant form of `Prio3Count` apart from the
+        // struct variant form, which restricts this to self-describing formats.
+        deserializer.deserialize_any(VdafInstanceVisitor)
+    }
+}
+
+/// Deserializes a [`VdafInstance`], accepting the unit variant form of `Prio3Count` in addition to
+/// the forms accepted by the derived implementation.
+struct VdafInstanceVisitor;
+
+impl<'de> Visitor<'de> for VdafInstanceVisitor {
+    type Value = VdafInstance;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("enum VdafInstance")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        match value {
+            "Prio3Count" => Ok(VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            }),
+            _ => VdafInstance::deserialize(StrDeserializer::new(value)),
+        }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        VdafInstance::deserialize(MapAccessDeserializer::new(map))
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        VdafInstance::deserialize(EnumAccessDeserializer::new(data))
+    }
 }
 
 impl TryFrom<&taskprov::VdafConfig> for VdafInstance {
@@ -181,9 +448,12 @@ impl TryFrom<&taskprov::VdafConfig> for VdafInstance {
 
     fn try_from(value: &taskprov::VdafConfig) -> Result<Self, Self::Error> {
         match value {
-            taskprov::VdafConfig::Prio3Count => Ok(Self::Prio3Count),
+            taskprov::VdafConfig::Prio3Count => Ok(Self::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            }),
             taskprov::VdafConfig::Prio3Sum { max_measurement } => Ok(Self::Prio3Sum {
                 max_measurement: u64::from(*max_measurement),
+                dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
             }),
             taskprov::VdafConfig::Prio3SumVec {
                 bits,
@@ -255,22 +525,65 @@ pub fn new_prio3_sum_vec_field64_multiproof_hmacsha256_aes128<
 macro_rules! vdaf_dispatch_impl_base {
     (impl match base $vdaf_instance:expr, ($vdaf:ident, $Vdaf:ident, $VERIFY_KEY_LEN:ident, $dp_strategy:ident, $DpStrategy:ident) => $body:tt) => {
         match $vdaf_instance {
-            ::janus_core::vdaf::VdafInstance::Prio3Count => {
+            ::janus_core::vdaf::VdafInstance::Prio3Count { dp_strategy } => {
                 let $vdaf = ::prio::vdaf::prio3::Prio3::new_count(2)?;
                 type $Vdaf = ::prio::vdaf::prio3::Prio3Count;
                 const $VERIFY_KEY_LEN: usize = ::janus_core::vdaf::VERIFY_KEY_LENGTH_PRIO3;
-                type $DpStrategy = janus_core::dp::NoDifferentialPrivacy;
-                let $dp_strategy = janus_core::dp::NoDifferentialPrivacy;
-                $body
+                match dp_strategy.clone() {
+                    ::janus_core::vdaf::vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy => {
+                        type $DpStrategy = janus_core::dp::NoDifferentialPrivacy;
+                        let $dp_strategy = janus_core::dp::NoDifferentialPrivacy;
+                        $body
+                    }
+                    ::janus_core::vdaf::vdaf_dp_strategies::Prio3Count::PureDpDiscreteLaplace(
+                        _strategy,
+                    ) => {
+                        type $DpStrategy =
+                            janus_core::dp::JanusStrategy<::prio::dp::distributions::PureDpDiscreteLaplace>;
+                        let $dp_strategy = janus_core::dp::JanusStrategy(_strategy.into_strategy());
+                        $body
+                    }
+                    ::janus_core::vdaf::vdaf_dp_strategies::Prio3Count::ZCdpDiscreteGaussian(
+                        _strategy,
+                    ) => {
+                        type $DpStrategy =
+                            janus_core::dp::JanusStrategy<::prio::dp::distributions::ZCdpDiscreteGaussian>;
+                        let $dp_strategy = janus_core::dp::JanusStrategy(_strategy.into_strategy());
+                        $body
+                    }
+                }
             }
 
-            ::janus_core::vdaf::VdafInstance::Prio3Sum { max_measurement } => {
+            ::janus_core::vdaf::VdafInstance::Prio3Sum {
+                max_measurement,
+                dp_strategy,
+            } => {
                 let $vdaf = ::prio::vdaf::prio3::Prio3::new_sum(2, *max_measurement)?;
                 type $Vdaf = ::prio::vdaf::prio3::Prio3Sum;
                 const $VERIFY_KEY_LEN: usize = ::janus_core::vdaf::VERIFY_KEY_LENGTH_PRIO3;
-                type $DpStrategy = janus_core::dp::NoDifferentialPrivacy;
-                let $dp_strategy = janus_core::dp::NoDifferentialPrivacy;
-                $body
+                match dp_strategy.clone() {
+                    ::janus_core::vdaf::vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy => {
+                        type $DpStrategy = janus_core::dp::NoDifferentialPrivacy;
+                        let $dp_strategy = janus_core::dp::NoDifferentialPrivacy;
+                        $body
+                    }
+                    ::janus_core::vdaf::vdaf_dp_strategies::Prio3Sum::PureDpDiscreteLaplace(
+                        _strategy,
+                    ) => {
+                        type $DpStrategy =
+                            janus_core::dp::JanusStrategy<::prio::dp::distributions::PureDpDiscreteLaplace>;
+                        let $dp_strategy = janus_core::dp::JanusStrategy(_strategy.into_strategy());
+                        $body
+                    }
+                    ::janus_core::vdaf::vdaf_dp_strategies::Prio3Sum::ZCdpDiscreteGaussian(
+                        _strategy,
+                    ) => {
+                        type $DpStrategy =
+                            janus_core::dp::JanusStrategy<::prio::dp::distributions::ZCdpDiscreteGaussian>;
+                        let $dp_strategy = janus_core::dp::JanusStrategy(_strategy.into_strategy());
+                        $body
+                    }
+                }
             }
 
             ::janus_core::vdaf::VdafInstance::Prio3SumVec {
@@ -293,7 +606,15 @@ macro_rules! vdaf_dispatch_impl_base {
                         _strategy,
                     ) => {
                         type $DpStrategy = ::prio::dp::distributions::PureDpDiscreteLaplace;
-                        let $dp_strategy = _strategy;
+                        let $dp_strategy = _strategy.into_strategy();
+                        $body
+                    }
+                    ::janus_core::vdaf::vdaf_dp_strategies::Prio3SumVec::ZCdpDiscreteGaussian(
+                        _strategy,
+                    ) => {
+                        type $DpStrategy =
+                            janus_core::dp::JanusStrategy<::prio::dp::distributions::ZCdpDiscreteGaussian>;
+                        let $dp_strategy = janus_core::dp::JanusStrategy(_strategy.into_strategy());
                         $body
                     }
                 }
@@ -331,7 +652,15 @@ macro_rules! vdaf_dispatch_impl_base {
                         _strategy,
                     ) => {
                         type $DpStrategy = ::prio::dp::distributions::PureDpDiscreteLaplace;
-                        let $dp_strategy = _strategy;
+                        let $dp_strategy = _strategy.into_strategy();
+                        $body
+                    }
+                    ::janus_core::vdaf::vdaf_dp_strategies::Prio3SumVec::ZCdpDiscreteGaussian(
+                        _strategy,
+                    ) => {
+                        type $DpStrategy =
+                            janus_core::dp::JanusStrategy<::prio::dp::distributions::ZCdpDiscreteGaussian>;
+                        let $dp_strategy = janus_core::dp::JanusStrategy(_strategy.into_strategy());
                         $body
                     }
                 }
@@ -353,7 +682,13 @@ macro_rules! vdaf_dispatch_impl_base {
                     }
                     ::janus_core::vdaf::vdaf_dp_strategies::Prio3Histogram::PureDpDiscreteLaplace(_strategy) => {
                         type $DpStrategy = ::prio::dp::distributions::PureDpDiscreteLaplace;
-                        let $dp_strategy = _strategy;
+                        let $dp_strategy = _strategy.into_strategy();
+                        $body
+                    }
+                    ::janus_core::vdaf::vdaf_dp_strategies::Prio3Histogram::ZCdpDiscreteGaussian(_strategy) => {
+                        type $DpStrategy =
+                            janus_core::dp::JanusStrategy<::prio::dp::distributions::ZCdpDiscreteGaussian>;
+                        let $dp_strategy = janus_core::dp::JanusStrategy(_strategy.into_strategy());
                         $body
                     }
                 }
@@ -376,7 +711,7 @@ macro_rules! vdaf_dispatch_impl_fpvec_bounded_l2 {
                 match dp_strategy.clone() {
                     janus_core::vdaf::vdaf_dp_strategies::Prio3FixedPointBoundedL2VecSum::ZCdpDiscreteGaussian(_strategy) => {
                         type $DpStrategy = ::prio::dp::distributions::ZCdpDiscreteGaussian;
-                        let $dp_strategy = _strategy;
+                        let $dp_strategy = _strategy.into_strategy();
                         janus_core::vdaf_dispatch_impl_fpvec_bounded_l2!(@dispatch_bitsize bitsize, $Vdaf, $vdaf, length => $body)
                     },
                     janus_core::vdaf::vdaf_dp_strategies::Prio3FixedPointBoundedL2VecSum::NoDifferentialPrivacy => {
@@ -476,7 +811,7 @@ macro_rules! vdaf_dispatch_impl_test_util {
 macro_rules! vdaf_dispatch_impl {
     (impl match all $vdaf_instance:expr, ($vdaf:ident, $Vdaf:ident, $VERIFY_KEY_LEN:ident, $dp_strategy:ident, $DpStrategy:ident) => $body:tt) => {
         match $vdaf_instance {
-            ::janus_core::vdaf::VdafInstance::Prio3Count
+            ::janus_core::vdaf::VdafInstance::Prio3Count { .. }
             | ::janus_core::vdaf::VdafInstance::Prio3Sum { .. }
             | ::janus_core::vdaf::VdafInstance::Prio3SumVec { .. }
             | ::janus_core::vdaf::VdafInstance::Prio3SumVecField64MultiproofHmacSha256Aes128 { .. }
@@ -505,7 +840,7 @@ macro_rules! vdaf_dispatch_impl {
 macro_rules! vdaf_dispatch_impl {
     (impl match all $vdaf_instance:expr, ($vdaf:ident, $Vdaf:ident, $VERIFY_KEY_LEN:ident, $dp_strategy:ident, $DpStrategy:ident) => $body:tt) => {
         match $vdaf_instance {
-            ::janus_core::vdaf::VdafInstance::Prio3Count
+            ::janus_core::vdaf::VdafInstance::Prio3Count { .. }
             | ::janus_core::vdaf::VdafInstance::Prio3Sum { .. }
             | ::janus_core::vdaf::VdafInstance::Prio3SumVec { .. }
             | ::janus_core::vdaf::VdafInstance::Prio3SumVecField64MultiproofHmacSha256Aes128 { .. }
@@ -528,7 +863,7 @@ macro_rules! vdaf_dispatch_impl {
 macro_rules! vdaf_dispatch_impl {
     (impl match all $vdaf_instance:expr, ($vdaf:ident, $Vdaf:ident, $VERIFY_KEY_LEN:ident, $dp_strategy:ident, $DpStrategy:ident) => $body:tt) => {
         match $vdaf_instance {
-            ::janus_core::vdaf::VdafInstance::Prio3Count
+            ::janus_core::vdaf::VdafInstance::Prio3Count { .. }
             | ::janus_core::vdaf::VdafInstance::Prio3Sum { .. }
             | ::janus_core::vdaf::VdafInstance::Prio3SumVec { .. }
             | ::janus_core::vdaf::VdafInstance::Prio3SumVecField64MultiproofHmacSha256Aes128 { .. }
@@ -553,7 +888,7 @@ macro_rules! vdaf_dispatch_impl {
 macro_rules! vdaf_dispatch_impl {
     (impl match all $vdaf_instance:expr, ($vdaf:ident, $Vdaf:ident, $VERIFY_KEY_LEN:ident, $dp_strategy:ident, $DpStrategy:ident) => $body:tt) => {
         match $vdaf_instance {
-            ::janus_core::vdaf::VdafInstance::Prio3Count
+            ::janus_core::vdaf::VdafInstance::Prio3Count { .. }
             | ::janus_core::vdaf::VdafInstance::Prio3Sum { .. }
             | ::janus_core::vdaf::VdafInstance::Prio3SumVec { .. }
             | ::janus_core::vdaf::VdafInstance::Prio3SumVecField64MultiproofHmacSha256Aes128 { .. }
@@ -586,7 +921,9 @@ macro_rules! vdaf_dispatch_impl {
 /// #     Ok(())
 /// # }
 /// # fn test() -> Result<(), prio::vdaf::VdafError> {
-/// #     let vdaf = janus_core::vdaf::VdafInstance::Prio3Count;
+/// #     let vdaf = janus_core::vdaf::VdafInstance::Prio3Count {
+/// #         dp_strategy: janus_core::vdaf::vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+/// #     };
 /// vdaf_dispatch!(&vdaf, (vdaf, VdafType, VERIFY_KEY_LEN) => {
 ///     handle_request_generic::<VdafType, VERIFY_KEY_LEN>(&vdaf)
 /// })
@@ -614,14 +951,15 @@ macro_rules! vdaf_dispatch {
 mod tests {
     #[cfg(feature = "fpvec_bounded_l2")]
     use crate::vdaf::Prio3FixedPointBoundedL2VecSumBitSize;
-    use crate::vdaf::{VdafInstance, vdaf_dp_strategies};
+    use crate::{
+        dp::DpStrategyConfig,
+        vdaf::{VdafInstance, vdaf_dp_strategies},
+    };
     use assert_matches::assert_matches;
-    use prio::dp::{
-        DifferentialPrivacyStrategy, PureDpBudget, Rational,
-        distributions::{DiscreteLaplaceDpStrategy, PureDpDiscreteLaplace},
+    use serde::{
+        Deserialize, Deserializer,
+        de::{self, Visitor},
     };
-    #[cfg(feature = "fpvec_bounded_l2")]
-    use prio::dp::{ZCdpBudget, distributions::ZCdpDiscreteGaussian};
     use serde_test::{Token, assert_tokens};
 
     #[test]
@@ -629,15 +967,56 @@ mod tests {
         // The `Vdaf` type must have a stable serialization, as it gets stored in a JSON database
         // column.
         assert_tokens(
-            &VdafInstance::Prio3Count,
+            &VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
             &[Token::UnitVariant {
                 name: "VdafInstance",
                 variant: "Prio3Count",
             }],
         );
+        assert_tokens(
+            &VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::PureDpDiscreteLaplace(
+                    DpStrategyConfig::from_epsilon(2, 1).unwrap(),
+                ),
+            },
+            &[
+                Token::StructVariant {
+                    name: "VdafInstance",
+                    variant: "Prio3Count",
+                    len: 1,
+                },
+                Token::Str("dp_strategy"),
+                Token::Struct {
+                    name: "DpStrategyConfig",
+                    len: 2,
+                },
+                Token::Str("dp_strategy"),
+                Token::Str("PureDpDiscreteLaplace"),
+                Token::Str("budget"),
+                Token::Struct {
+                    name: "DpBudget",
+                    len: 1,
+                },
+                Token::Str("epsilon"),
+                Token::Tuple { len: 2 },
+                Token::Seq { len: Some(1) },
+                Token::U32(2),
+                Token::SeqEnd,
+                Token::Seq { len: Some(1) },
+                Token::U32(1),
+                Token::SeqEnd,
+                Token::TupleEnd,
+                Token::StructEnd,
+                Token::StructEnd,
+                Token::StructVariantEnd,
+            ],
+        );
         assert_tokens(
             &VdafInstance::Prio3Sum {
                 max_measurement: 4096,
+                dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
             },
             &[
                 Token::StructVariant {
@@ -650,6 +1029,47 @@ mod tests {
                 Token::StructVariantEnd,
             ],
         );
+        assert_tokens(
+            &VdafInstance::Prio3Sum {
+                max_measurement: 4096,
+                dp_strategy: vdaf_dp_strategies::Prio3Sum::ZCdpDiscreteGaussian(
+                    DpStrategyConfig::from_epsilon(1, 2).unwrap(),
+                ),
+            },
+            &[
+                Token::StructVariant {
+                    name: "VdafInstance",
+                    variant: "Prio3Sum",
+                    len: 2,
+                },
+                Token::Str("max_measurement"),
+                Token::U64(4096),
+                Token::Str("dp_strategy"),
+                Token::Struct {
+                    name: "DpStrategyConfig",
+                    len: 2,
+                },
+                Token::Str("dp_strategy"),
+                Token::Str("ZCdpDiscreteGaussian"),
+                Token::Str("budget"),
+                Token::Struct {
+                    name: "DpBudget",
+                    len: 1,
+                },
+                Token::Str("epsilon"),
+                Token::Tuple { len: 2 },
+                Token::Seq { len: Some(1) },
+                Token::U32(1),
+                Token::SeqEnd,
+                Token::Seq { len: Some(1) },
+                Token::U32(2),
+                Token::SeqEnd,
+                Token::TupleEnd,
+                Token::StructEnd,
+                Token::StructEnd,
+                Token::StructVariantEnd,
+            ],
+        );
         assert_tokens(
             &VdafInstance::Prio3SumVec {
                 bits: 1,
@@ -686,9 +1106,7 @@ mod tests {
                 length: 8,
                 chunk_length: 3,
                 dp_strategy: vdaf_dp_strategies::Prio3SumVec::PureDpDiscreteLaplace(
-                    PureDpDiscreteLaplace::from_budget(
-                        PureDpBudget::new(Rational::from_unsigned(2u128, 1u128).unwrap()).unwrap(),
-                    ),
+                    DpStrategyConfig::from_epsilon(2, 1).unwrap(),
                 ),
             },
             &[
@@ -705,14 +1123,14 @@ mod tests {
                 Token::U64(3),
                 Token::Str("dp_strategy"),
                 Token::Struct {
-                    name: "DiscreteLaplaceDpStrategy",
+                    name: "DpStrategyConfig",
                     len: 2,
                 },
                 Token::Str("dp_strategy"),
                 Token::Str("PureDpDiscreteLaplace"),
                 Token::Str("budget"),
                 Token::Struct {
-                    name: "PureDpBudget",
+                    name: "DpBudget",
                     len: 1,
                 },
                 Token::Str("epsilon"),
@@ -839,9 +1257,7 @@ length: 10"
                 bitsize: Prio3FixedPointBoundedL2VecSumBitSize::BitSize16,
                 dp_strategy:
                     vdaf_dp_strategies::Prio3FixedPointBoundedL2VecSum::ZCdpDiscreteGaussian(
-                        ZCdpDiscreteGaussian::from_budget(ZCdpBudget::new(
-                            Rational::from_unsigned(1u128, 2u128).unwrap(),
-                        )),
+                        DpStrategyConfig::from_epsilon(1, 2).unwrap(),
                     ),
                 length: 10,
             }
@@ -857,9 +1273,51 @@ length: 10"
 max_measurement: 4096"
             ),
             Ok(VdafInstance::Prio3Sum {
-                max_measurement: 4096
+                max_measurement: 4096,
+                dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
             })
         );
+        assert_matches!(
+            serde_yaml::from_str("Prio3Count"),
+            Ok(VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            })
+        );
+        assert_matches!(
+            serde_json::from_str(r#""Prio3Count""#),
+            Ok(VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            })
+        );
+        assert_eq!(
+            serde_yaml::from_str::<VdafInstance>(
+                "---
+!Prio3Sum
+max_measurement: 255
+dp_strategy:
+    dp_strategy: ZCdpDiscreteGaussian
+    budget:
+        epsilon: [[1], [2]]"
+            )
+            .unwrap(),
+            VdafInstance::Prio3Sum {
+                max_measurement: 255,
+                dp_strategy: vdaf_dp_strategies::Prio3Sum::ZCdpDiscreteGaussian(
+                    DpStrategyConfig::from_epsilon(1, 2).unwrap(),
+                ),
+            }
+        );
+        assert_eq!(
+            serde_json::from_str::<VdafInstance>(
+                r#"{"Prio3Count":{"dp_strategy":{"dp_strategy":"PureDpDiscreteLaplace","budget":{"epsilon":[[1],[1]]}}}}"#
+            )
+            .unwrap(),
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::PureDpDiscreteLaplace(
+                    DpStrategyConfig::from_epsilon(1, 1).unwrap(),
+                ),
+            }
+        );
         assert_matches!(
             serde_yaml::from_str(
                 "---
@@ -891,11 +1349,174 @@ dp_strategy:
                 length: 2,
                 chunk_length: 2,
                 dp_strategy: vdaf_dp_strategies::Prio3SumVec::PureDpDiscreteLaplace(
-                    DiscreteLaplaceDpStrategy::from_budget(
-                        PureDpBudget::new(Rational::from_unsigned(1u128, 1u128).unwrap()).unwrap()
-                    ),
+                    DpStrategyConfig::from_epsilon(1, 1).unwrap(),
+                ),
+            }
+        );
+    }
+
+    #[test]
+    fn vdaf_deserialization_requires_self_describing_format() {
+        /// Stands in for a non-self-describing format, such as bincode, which cannot implement
+        /// `deserialize_any`.
+        struct NotSelfDescribing;
+
+        impl<'de> Deserializer<'de> for NotSelfDescribing {
+            type Error = de::value::Error;
+
+            fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+                Err(de::Error::custom("deserialize_any is not supported"))
+            }
+
+            serde::forward_to_deserialize_any! {
+                bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+                byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map struct
+                enum identifier ignored_any
+            }
+        }
+
+        assert_eq!(
+            VdafInstance::deserialize(NotSelfDescribing)
+                .unwrap_err()
+                .to_string(),
+            "deserialize_any is not supported"
+        );
+    }
+
+    #[test]
+    fn vdaf_privacy_loss() {
+        assert_eq!(
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            }
+            .privacy_loss(),
+            0.0
+        );
+        assert_eq!(
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::PureDpDiscreteLaplace(
+                    DpStrategyConfig::from_epsilon(1, 2).unwrap(),
+                ),
+            }
+            .privacy_loss(),
+            0.5
+        );
+        // For zCDP, the loss is rho = epsilon^2 / 2.
+        assert_eq!(
+            VdafInstance::Prio3Sum {
+                max_measurement: 255,
+                dp_strategy: vdaf_dp_strategies::Prio3Sum::ZCdpDiscreteGaussian(
+                    DpStrategyConfig::from_epsilon(1, 2).unwrap(),
+                ),
+            }
+            .privacy_loss(),
+            0.125
+        );
+        assert_eq!(
+            VdafInstance::Prio3Histogram {
+                length: 4,
+                chunk_length: 2,
+                dp_strategy: vdaf_dp_strategies::Prio3Histogram::ZCdpDiscreteGaussian(
+                    DpStrategyConfig::from_epsilon(1, 1).unwrap(),
+                ),
+            }
+            .privacy_loss(),
+            0.5
+        );
+    }
+
+    #[test]
+    fn vdaf_zcdp_histogram_and_sum_vec() {
+        assert_eq!(
+            serde_yaml::from_str::<VdafInstance>(
+                "---
+!Prio3Histogram
+length: 100
+chunk_length: 10
+dp_strategy:
+    dp_strategy: ZCdpDiscreteGaussian
+    budget:
+        epsilon: [[1], [4]]"
+            )
+            .unwrap(),
+            VdafInstance::Prio3Histogram {
+                length: 100,
+                chunk_length: 10,
+                dp_strategy: vdaf_dp_strategies::Prio3Histogram::ZCdpDiscreteGaussian(
+                    DpStrategyConfig::from_epsilon(1, 4).unwrap(),
+                ),
+            }
+        );
+        assert_eq!(
+            serde_yaml::from_str::<VdafInstance>(
+                "---
+!Prio3SumVecField64MultiproofHmacSha256Aes128
+proofs: 2
+bits: 1
+length: 12
+chunk_length: 4
+dp_strategy:
+    dp_strategy: ZCdpDiscreteGaussian
+    budget:
+        epsilon: [[1], [1]]"
+            )
+            .unwrap(),
+            VdafInstance::Prio3SumVecField64MultiproofHmacSha256Aes128 {
+                proofs: 2,
+                bits: 1,
+                length: 12,
+                chunk_length: 4,
+                dp_strategy: vdaf_dp_strategies::Prio3SumVec::ZCdpDiscreteGaussian(
+                    DpStrategyConfig::from_epsilon(1, 1).unwrap(),
                 ),
             }
         );
     }
+
+    #[test]
+    fn vdaf_noise_std_dev() {
+        assert_eq!(
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            }
+            .noise_std_dev(),
+            None
+        );
+
+        // Discrete Laplace noise with scale 1 has variance 2e / (e - 1)^2.
+        let std_dev = VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::PureDpDiscreteLaplace(
+                DpStrategyConfig::from_epsilon(1, 1).unwrap(),
+            ),
+        }
+        .noise_std_dev()
+        .unwrap();
+        let e = std::f64::consts::E;
+        assert!((std_dev - (2.0 * e).sqrt() / (e - 1.0)).abs() < 1e-9);
+
+        // Discrete Gaussian noise has sigma = l2-sensitivity / epsilon.
+        let std_dev = VdafInstance::Prio3Histogram {
+            length: 4,
+            chunk_length: 2,
+            dp_strategy: vdaf_dp_strategies::Prio3Histogram::ZCdpDiscreteGaussian(
+                DpStrategyConfig::from_epsilon(1, 2).unwrap(),
+            ),
+        }
+        .noise_std_dev()
+        .unwrap();
+        assert!((std_dev - 2.0 * 2f64.sqrt()).abs() < 1e-9);
+
+        // (2^2 - 1) * sqrt(9)
+        let std_dev = VdafInstance::Prio3SumVec {
+            bits: 2,
+            length: 9,
+            chunk_length: 3,
+            dp_strategy: vdaf_dp_strategies::Prio3SumVec::ZCdpDiscreteGaussian(
+                DpStrategyConfig::from_epsilon(1, 1).unwrap(),
+            ),
+        }
+        .noise_std_dev()
+        .unwrap();
+        assert!((std_dev - 9.0).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,7 @@
+DROP INDEX audit_log_task_id CASCADE;
+DROP INDEX audit_log_created_at CASCADE;
+DROP TABLE audit_log CASCADE;
+DROP INDEX collection_jobs_abandoned CASCADE;
+DROP INDEX aggregation_jobs_abandoned CASCADE;
+ALTER TABLE collection_jobs DROP COLUMN last_error;
+ALTER TABLE aggregation_jobs DROP COLUMN last_error;
@@ -0,0 +1,23 @@
+-- Record the most recent error encountered while stepping each job, so that operators can tell
+-- why a job was abandoned.
+ALTER TABLE aggregation_jobs ADD COLUMN last_error TEXT;  -- the most recent error encountered while stepping this job, if any (leader only)
+ALTER TABLE collection_jobs ADD COLUMN last_error TEXT;   -- the most recent error encountered while stepping this job, if any
+
+CREATE INDEX aggregation_jobs_abandoned ON aggregation_jobs(task_id) WHERE state = 'ABANDONED';
+CREATE INDEX collection_jobs_abandoned ON collection_jobs(task_id) WHERE state = 'ABANDONED';
+
+-- An append-only record of operator actions taken against the datastore, e.g. via the aggregator
+-- API or janus_cli.
+CREATE TABLE audit_log(
+    id          BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,  -- artificial ID, internal-only
+    actor       TEXT NOT NULL,    -- the identity of the operator or component which took the action
+    action      TEXT NOT NULL,    -- a short, machine-readable name for the action taken
+    task_id     BYTEA,            -- the DAP task ID affected by the action, if any (not a foreign key, so entries outlive their task)
+    target      TEXT,             -- a description of the specific resource affected by the action, if any
+    details     JSONB,            -- additional, action-specific details
+
+    -- creation records
+    created_at  TIMESTAMP NOT NULL  -- when the action was taken
+);
+CREATE INDEX audit_log_created_at ON audit_log(created_at);
+CREATE INDEX audit_log_task_id ON audit_log(task_id, created_at);
@@ -0,0 +1,5 @@
+DROP INDEX collection_job_history_collection_job_id CASCADE;
+DROP TABLE collection_job_history CASCADE;
+DROP INDEX aggregation_job_history_aggregation_job_id CASCADE;
+DROP TABLE aggregation_job_history CASCADE;
+DROP TYPE JOB_STEP_OUTCOME CASCADE;
@@ -0,0 +1,44 @@
+-- Specifies the possible outcomes of an unsuccessful attempt to step a job.
+CREATE TYPE JOB_STEP_OUTCOME AS ENUM(
+    'RETRYABLE_ERROR',  -- the step failed with an error that is expected to be transient; the job will be retried
+    'FATAL_ERROR',      -- the step failed with an error that is not expected to be transient; the job was abandoned
+    'NOT_READY'         -- the job could not yet make progress (e.g. a collection job waiting on aggregation)
+);
+
+-- A bounded history of unsuccessful attempts to step each leader aggregation job, most recent last.
+CREATE TABLE aggregation_job_history(
+    id                  BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,  -- artificial ID, internal-only
+    aggregation_job_id  BIGINT NOT NULL,            -- the aggregation job this entry pertains to
+    step                INTEGER NOT NULL,           -- the step of the aggregation job at the time of the attempt
+    lease_attempts      BIGINT NOT NULL,            -- the number of lease acquisitions of the job at the time of the attempt
+    outcome             JOB_STEP_OUTCOME NOT NULL,  -- the outcome of the attempt
+    error               TEXT,                       -- the error encountered, if any
+    http_status         INTEGER,                    -- the HTTP status returned by the peer aggregator, if any
+    problem_type        TEXT,                       -- the problem type URI returned by the peer aggregator, if any
+    retry_delay_ms      BIGINT,                     -- the delay before the job will be retried, in milliseconds, if one was chosen
+
+    -- creation records
+    created_at  TIMESTAMP NOT NULL,  -- when the attempt was made
+
+    CONSTRAINT fk_aggregation_job_id FOREIGN KEY(aggregation_job_id) REFERENCES aggregation_jobs(id) ON DELETE CASCADE
+);
+CREATE INDEX aggregation_job_history_aggregation_job_id ON aggregation_job_history(aggregation_job_id, id);
+
+-- A bounded history of unsuccessful attempts to step each collection job, most recent last.
+CREATE TABLE collection_job_history(
+    id                  BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,  -- artificial ID, internal-only
+    collection_job_id   BIGINT NOT NULL,            -- the collection job this entry pertains to
+    step                BIGINT NOT NULL,            -- the number of step attempts of the collection job at the time of the attempt
+    lease_attempts      BIGINT NOT NULL,            -- the number of lease acquisitions of the job at the time of the attempt
+    outcome             JOB_STEP_OUTCOME NOT NULL,  -- the outcome of the attempt
+    error               TEXT,                       -- the error encountered, if any
+    http_status         INTEGER,                    -- the HTTP status returned by the peer aggregator, if any
+    problem_type        TEXT,                       -- the problem type URI returned by the peer aggregator, if any
+    retry_delay_ms      BIGINT,                     -- the delay before the job will be retried, in milliseconds, if one was chosen
+
+    -- creation records
+    created_at  TIMESTAMP NOT NULL,  -- when the attempt was made
+
+    CONSTRAINT fk_collection_job_id FOREIGN KEY(collection_job_id) REFERENCES collection_jobs(id) ON DELETE CASCADE
+);
+CREATE INDEX collection_job_history_collection_job_id ON collection_job_history(collection_job_id, id);
@@ -0,0 +1,2 @@
+DROP INDEX client_reports_task_and_updated_at_retained_index CASCADE;
+ALTER TABLE tasks DROP COLUMN report_retention_window;
@@ -0,0 +1,8 @@
+-- The number of seconds the payloads of client reports are retained after the reports are
+-- aggregated. If NULL, report payloads are scrubbed as soon as the reports are aggregated.
+ALTER TABLE tasks ADD COLUMN report_retention_window BIGINT;
+
+-- Supports garbage collection of the payloads of aggregated client reports which have been
+-- retained past their task's report retention window.
+CREATE INDEX client_reports_task_and_updated_at_retained_index ON client_reports(task_id, updated_at)
+    WHERE aggregation_started = TRUE AND leader_input_share IS NOT NULL;
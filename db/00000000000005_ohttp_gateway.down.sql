@@ -0,0 +1 @@
+DROP TABLE ohttp_gateway_keys;
@@ -0,0 +1,17 @@
+-- Keys used by the aggregator's OHTTP gateway to decapsulate requests relayed on behalf of clients.
+-- These are rotated through the same lifecycle as the keys in hpke_keys.
+CREATE TABLE ohttp_gateway_keys(
+    -- These columns should be treated as immutable.
+    config_id SMALLINT PRIMARY KEY,  -- OHTTP key ID
+    config BYTEA NOT NULL,           -- key config, including public key (encoded HpkeConfig message)
+    private_key BYTEA NOT NULL,      -- seed from which the OHTTP key config is derived (encrypted)
+
+    -- These columns are mutable.
+    state HPKE_KEY_STATE NOT NULL DEFAULT 'PENDING',  -- state of the key
+    last_state_change_at TIMESTAMP NOT NULL,          -- when the key state was last changed. Used for key rotation logic.
+
+    -- creation/update records
+    created_at TIMESTAMP NOT NULL,  -- when the row was created
+    updated_at TIMESTAMP NOT NULL,  -- when the row was last changed
+    updated_by TEXT NOT NULL        -- the name of the transaction that last updated the row
+);
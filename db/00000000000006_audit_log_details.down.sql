@@ -0,0 +1,6 @@
+DROP INDEX audit_log_actor CASCADE;
+ALTER TABLE audit_log DROP COLUMN outcome;
+ALTER TABLE audit_log DROP COLUMN after_state;
+ALTER TABLE audit_log DROP COLUMN before_state;
+ALTER TABLE audit_log DROP COLUMN route;
+DROP TYPE AUDIT_LOG_OUTCOME;
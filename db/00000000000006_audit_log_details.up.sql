@@ -0,0 +1,12 @@
+-- Record enough about each audited action to reconstruct who changed what, and whether it worked.
+CREATE TYPE AUDIT_LOG_OUTCOME AS ENUM(
+    'SUCCESS',  -- the action was carried out
+    'FAILURE'   -- the action was attempted, but failed; the error is recorded in details
+);
+
+ALTER TABLE audit_log ADD COLUMN route TEXT;                   -- the API route or CLI command through which the action was taken, if any
+ALTER TABLE audit_log ADD COLUMN before_state JSONB;           -- the state of the affected resource before the action, with secrets redacted, if any
+ALTER TABLE audit_log ADD COLUMN after_state JSONB;            -- the state of the affected resource after the action, with secrets redacted, if any
+ALTER TABLE audit_log ADD COLUMN outcome AUDIT_LOG_OUTCOME NOT NULL DEFAULT 'SUCCESS';  -- whether the action succeeded
+
+CREATE INDEX audit_log_actor ON audit_log(actor, created_at);
@@ -0,0 +1,2 @@
+DROP TABLE api_tokens CASCADE;
+DROP TYPE API_TOKEN_SCOPE;
@@ -0,0 +1,19 @@
+-- Named credentials for the aggregator API, each granting a set of scopes.
+CREATE TYPE API_TOKEN_SCOPE AS ENUM(
+    'READ_ONLY',       -- may read tasks, metrics, jobs, HPKE configs, taskprov peers & the audit log
+    'TASK_ADMIN',      -- may also create, modify & delete tasks, and requeue or fail their jobs
+    'KEY_ADMIN',       -- may also create, modify & delete HPKE keypairs
+    'TASKPROV_ADMIN'   -- may also create & delete taskprov peer aggregators
+);
+
+CREATE TABLE api_tokens(
+    id          BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,  -- artificial ID, internal-only
+    name        TEXT NOT NULL UNIQUE,          -- a unique, human-readable name for the token, recorded as the actor in the audit log
+    token_hash  BYTEA NOT NULL UNIQUE,         -- SHA-256 hash of the bearer token; the token itself is never stored
+    scopes      API_TOKEN_SCOPE[] NOT NULL,    -- the scopes granted to the token
+    task_ids    BYTEA[],                       -- if set, the DAP task IDs to which the token is restricted
+
+    -- creation/revocation records
+    created_at  TIMESTAMP NOT NULL,  -- when the token was created
+    revoked_at  TIMESTAMP            -- when the token was revoked, if it has been
+);
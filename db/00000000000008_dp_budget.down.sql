@@ -0,0 +1,4 @@
+DROP INDEX dp_budget_ledger_task_id_index CASCADE;
+DROP TABLE dp_budget_ledger CASCADE;
+ALTER TABLE tasks DROP COLUMN dp_budget_spent;
+ALTER TABLE tasks DROP COLUMN dp_budget;
@@ -0,0 +1,20 @@
+-- Per-task differential privacy budget accounting. Budgets and losses are measured in the units of
+-- the task's DP strategy: epsilon for pure DP, or rho for zero-concentrated DP.
+ALTER TABLE tasks ADD COLUMN dp_budget DOUBLE PRECISION;                      -- the total privacy budget collections may spend; if NULL, spending is recorded but not limited
+ALTER TABLE tasks ADD COLUMN dp_budget_spent DOUBLE PRECISION NOT NULL DEFAULT 0;  -- the privacy budget spent so far, i.e. the sum of privacy_loss over the task's ledger entries
+
+-- Records the privacy budget spent by each collection of a task's batches.
+CREATE TABLE dp_budget_ledger(
+    id                 BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,  -- artificial ID, internal-only
+    task_id            BIGINT NOT NULL,            -- the task whose budget was spent
+    batch_identifier   BYTEA NOT NULL,             -- encoded batch mode-specific batch identifier of the collected batch
+    aggregation_param  BYTEA NOT NULL,             -- the aggregation parameter the batch was collected with (opaque VDAF message)
+    privacy_loss       DOUBLE PRECISION NOT NULL,  -- the privacy budget spent by the collection
+
+    -- creation/update records
+    created_at TIMESTAMP NOT NULL,  -- when the row was created
+    updated_by TEXT NOT NULL,       -- the name of the transaction that last updated the row
+
+    CONSTRAINT fk_task_id FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+CREATE INDEX dp_budget_ledger_task_id_index ON dp_budget_ledger(task_id);
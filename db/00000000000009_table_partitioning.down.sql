@@ -0,0 +1,40 @@
+DROP INDEX report_partitions_task_id_index CASCADE;
+DROP TABLE report_partitions CASCADE;
+
+-- report_aggregations: move rows from task partitions back into the default partition, which
+-- becomes a standalone table again.
+ALTER TABLE report_aggregations DETACH PARTITION report_aggregations_default;
+INSERT INTO report_aggregations_default SELECT * FROM report_aggregations;
+DROP TABLE report_aggregations CASCADE;
+ALTER TABLE report_aggregations_default RENAME TO report_aggregations;
+ALTER TABLE report_aggregations DROP CONSTRAINT report_aggregations_default_unique_ord;
+DROP INDEX report_aggregations_default_id_index CASCADE;
+ALTER INDEX report_aggregations_default_aggregation_job_id_index
+    RENAME TO report_aggregations_aggregation_job_id_index;
+ALTER INDEX report_aggregations_default_client_report_id_index
+    RENAME TO report_aggregations_client_report_id_index;
+ALTER TABLE report_aggregations ADD CONSTRAINT report_aggregations_pkey PRIMARY KEY (id);
+ALTER TABLE report_aggregations ADD CONSTRAINT report_aggregations_unique_ord UNIQUE(task_id, aggregation_job_id, ord);
+ALTER TABLE report_aggregations ALTER COLUMN id ADD GENERATED ALWAYS AS IDENTITY;
+SELECT setval(pg_get_serial_sequence('report_aggregations', 'id'), COALESCE(MAX(id), 0) + 1, false) FROM report_aggregations;
+
+DROP INDEX client_report_ids_task_and_timestamp_index CASCADE;
+DROP TABLE client_report_ids CASCADE;
+
+-- client_reports: as for report_aggregations.
+ALTER TABLE client_reports DETACH PARTITION client_reports_default;
+INSERT INTO client_reports_default SELECT * FROM client_reports;
+DROP TABLE client_reports CASCADE;
+ALTER TABLE client_reports_default RENAME TO client_reports;
+ALTER TABLE client_reports DROP CONSTRAINT client_reports_default_unique_task_id_and_report_id;
+DROP INDEX client_reports_default_id_index CASCADE;
+ALTER INDEX client_reports_default_task_and_timestamp_unaggregated_index
+    RENAME TO client_reports_task_and_timestamp_unaggregated_index;
+ALTER INDEX client_reports_default_task_and_timestamp_index
+    RENAME TO client_reports_task_and_timestamp_index;
+ALTER INDEX client_reports_default_task_and_updated_at_retained_index
+    RENAME TO client_reports_task_and_updated_at_retained_index;
+ALTER TABLE client_reports ADD CONSTRAINT client_reports_pkey PRIMARY KEY (id);
+ALTER TABLE client_reports ADD CONSTRAINT client_reports_unique_task_id_and_report_id UNIQUE(task_id, report_id);
+ALTER TABLE client_reports ALTER COLUMN id ADD GENERATED ALWAYS AS IDENTITY;
+SELECT setval(pg_get_serial_sequence('client_reports', 'id'), COALESCE(MAX(id), 0) + 1, false) FROM client_reports;
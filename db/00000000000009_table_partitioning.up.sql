@@ -0,0 +1,160 @@
+-- Allows client_reports and report_aggregations to be partitioned by task and by client timestamp
+-- range. Each table is replaced by a table partitioned by task, and the existing table becomes its
+-- default partition. Partitions are created by the partition manager, which moves any matching rows
+-- out of the default partitions as it does so; while no partitions exist, all rows are stored in the
+-- default partitions, as before.
+--
+-- Unique constraints on partitioned tables must include the partition key, so report IDs are
+-- instead enforced unique by client_report_ids, an unpartitioned table holding only the key of each
+-- stored report. The positions of report aggregations within aggregation jobs are only enforced
+-- unique by the database per client timestamp; Janus writes the report aggregations of an
+-- aggregation job in the transaction which writes the job, so this is checked when writing rows.
+
+-- client_reports: detach the existing table's identity & unique constraints, which can't be shared
+-- with the partitioned table, and rename its indexes so the partitioned table can use their names.
+ALTER TABLE client_reports RENAME TO client_reports_default;
+ALTER TABLE client_reports_default ALTER COLUMN id DROP IDENTITY;
+ALTER TABLE client_reports_default DROP CONSTRAINT client_reports_pkey;
+ALTER TABLE client_reports_default DROP CONSTRAINT client_reports_unique_task_id_and_report_id;
+ALTER TABLE client_reports_default ADD CONSTRAINT client_reports_default_unique_task_id_and_report_id
+    UNIQUE(task_id, report_id, client_timestamp);
+CREATE INDEX client_reports_default_id_index ON client_reports_default(id);
+ALTER INDEX client_reports_task_and_timestamp_unaggregated_index
+    RENAME TO client_reports_default_task_and_timestamp_unaggregated_index;
+ALTER INDEX client_reports_task_and_timestamp_index
+    RENAME TO client_reports_default_task_and_timestamp_index;
+ALTER INDEX client_reports_task_and_updated_at_retained_index
+    RENAME TO client_reports_default_task_and_updated_at_retained_index;
+
+CREATE SEQUENCE client_reports_id_seq;
+SELECT setval('client_reports_id_seq', COALESCE(MAX(id), 0) + 1, false) FROM client_reports_default;
+
+-- Individual reports received from clients, partitioned by task.
+CREATE TABLE client_reports(
+    id                              BIGINT NOT NULL DEFAULT nextval('client_reports_id_seq'),  -- artificial ID, internal-only
+    task_id                         BIGINT NOT NULL,                 -- task ID the report is associated with
+    report_id                       BYTEA NOT NULL,                  -- 16-byte ReportID as defined by the DAP specification
+    client_timestamp                TIMESTAMP NOT NULL,              -- report timestamp, from client
+
+    public_extensions               BYTEA,                           -- encoded sequence of public Extension messages (opaque DAP messages, populated for unscrubbed reports only)
+    public_share                    BYTEA,                           -- encoded public share (opaque VDAF message, populated for unscrubbed reports only)
+    leader_private_extensions       BYTEA,                           -- encoded sequence of leader's private Extension messages (opaque DAP messages, populated for unscrubbed reports only)
+    leader_input_share              BYTEA,                           -- encoded, decrypted leader input share (opaque VDAF message, populated for unscrubbed reports only)
+    helper_encrypted_input_share    BYTEA,                           -- encoded HpkeCiphertext message containing the helper's input share (opaque DAP message, populated for unscrubbed reports only)
+
+    aggregation_started             BOOLEAN NOT NULL DEFAULT FALSE,  -- has this client report been associated with an aggregation job?
+
+    -- creation/update records
+    created_at TIMESTAMP NOT NULL,  -- when the row was created
+    updated_at TIMESTAMP NOT NULL,  -- when the row was last changed
+    updated_by TEXT NOT NULL,       -- the name of the transaction that last updated the row
+
+    CONSTRAINT client_reports_unique_task_id_and_report_id UNIQUE(task_id, report_id, client_timestamp),
+    CONSTRAINT fk_task_id FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+) PARTITION BY LIST (task_id);
+ALTER SEQUENCE client_reports_id_seq OWNED BY client_reports.id;
+CREATE INDEX client_reports_id_index ON client_reports(id);
+CREATE INDEX client_reports_task_and_timestamp_unaggregated_index ON client_reports (task_id, client_timestamp) WHERE aggregation_started = FALSE;
+CREATE INDEX client_reports_task_and_timestamp_index ON client_reports(task_id, client_timestamp);
+CREATE INDEX client_reports_task_and_updated_at_retained_index ON client_reports(task_id, updated_at)
+    WHERE aggregation_started = TRUE AND leader_input_share IS NOT NULL;
+ALTER TABLE client_reports ATTACH PARTITION client_reports_default DEFAULT;
+
+-- The IDs of the reports stored in client_reports. This table is not partitioned, so that the
+-- database enforces uniqueness of report IDs within a task regardless of client timestamp; each row
+-- is written & deleted along with the corresponding row of client_reports.
+CREATE TABLE client_report_ids(
+    task_id           BIGINT NOT NULL,     -- task ID the report is associated with
+    report_id         BYTEA NOT NULL,      -- 16-byte ReportID as defined by the DAP specification
+    client_timestamp  TIMESTAMP NOT NULL,  -- report timestamp, from client
+
+    CONSTRAINT client_report_ids_pkey PRIMARY KEY(task_id, report_id),
+    CONSTRAINT fk_task_id FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+CREATE INDEX client_report_ids_task_and_timestamp_index ON client_report_ids(task_id, client_timestamp);
+INSERT INTO client_report_ids (task_id, report_id, client_timestamp)
+    SELECT task_id, report_id, client_timestamp FROM client_reports_default;
+
+-- report_aggregations: as for client_reports.
+ALTER TABLE report_aggregations RENAME TO report_aggregations_default;
+ALTER TABLE report_aggregations_default ALTER COLUMN id DROP IDENTITY;
+ALTER TABLE report_aggregations_default DROP CONSTRAINT report_aggregations_pkey;
+ALTER TABLE report_aggregations_default DROP CONSTRAINT report_aggregations_unique_ord;
+ALTER TABLE report_aggregations_default ADD CONSTRAINT report_aggregations_default_unique_ord
+    UNIQUE(task_id, aggregation_job_id, ord, client_timestamp);
+CREATE INDEX report_aggregations_default_id_index ON report_aggregations_default(id);
+ALTER INDEX report_aggregations_aggregation_job_id_index
+    RENAME TO report_aggregations_default_aggregation_job_id_index;
+ALTER INDEX report_aggregations_client_report_id_index
+    RENAME TO report_aggregations_default_client_report_id_index;
+
+CREATE SEQUENCE report_aggregations_id_seq;
+SELECT setval('report_aggregations_id_seq', COALESCE(MAX(id), 0) + 1, false) FROM report_aggregations_default;
+
+-- An aggregation attempt for a single client report, partitioned by task.
+CREATE TABLE report_aggregations(
+    id                  BIGINT NOT NULL DEFAULT nextval('report_aggregations_id_seq'),  -- artificial ID, internal-only
+    task_id             BIGINT NOT NULL,                    -- ID of related task
+    aggregation_job_id  BIGINT NOT NULL,                    -- the aggregation job ID this report aggregation is associated with
+    ord                 BIGINT NOT NULL,                    -- a value used to specify the ordering of client reports in the aggregation job
+    client_report_id    BYTEA NOT NULL,                     -- the client report ID this report aggregation is associated with
+    client_timestamp    TIMESTAMP NOT NULL,                 -- the client timestamp this report aggregation is associated with
+    last_prep_resp      BYTEA,                              -- the last PrepareResp message sent to the Leader, to assist in replay (opaque DAP message, populated for Helper only)
+    state               REPORT_AGGREGATION_STATE NOT NULL,  -- the current state of this report aggregation
+
+    -- Additional data for state LeaderInit.
+    public_extensions             BYTEA,  -- encoded sequence of public Extension messages (opaque DAP messages)
+    public_share                  BYTEA,  -- the public share for the report (opaque VDAF message)
+    leader_private_extensions     BYTEA,  -- encoded sequence of leader's private Extension messages (opaque DAP messages)
+    leader_input_share            BYTEA,  -- encoded leader input share (opaque VDAF message)
+    helper_encrypted_input_share  BYTEA,  -- encoded HPKE ciphertext of helper input share (opaque DAP message)
+
+    -- Additional data for state LeaderContinue or LeaderPollContinue
+    leader_prep_transition  BYTEA,  -- the current VDAF prepare transition (opaque VDAF message)
+
+    -- Additional data for state LeaderPollInit.
+    leader_prep_state    BYTEA,  -- the current prepare state (opaque VDAF message)
+
+    -- Additional data for state HelperInitProcessing.
+    prepare_init  BYTEA,                  -- the preparation initialization message received from the Leader (opaque DAP message)
+    require_taskbind_extension  BOOLEAN,  -- is the taskprov extension required?
+
+    -- Additional data for state HelperContinue & HelperContinueProcessing.
+    helper_prep_state  BYTEA,  -- the current VDAF prepare state (opaque VDAF message)
+
+    -- Additional data for state HelperContinueProcessing.
+    prepare_continue  BYTEA,  -- the preparation continuation message received from the Leader (opaque VDAF message)
+
+    -- Additional data for state Failed.
+    error_code  SMALLINT,  -- error code corresponding to a DAP ReportShareError value
+
+    -- creation/update records
+    created_at TIMESTAMP NOT NULL,  -- when the row was created
+    updated_at TIMESTAMP NOT NULL,  -- when the row was last changed
+    updated_by TEXT NOT NULL,       -- the name of the transaction that last updated the row
+
+    CONSTRAINT report_aggregations_unique_ord UNIQUE(task_id, aggregation_job_id, ord, client_timestamp),
+    CONSTRAINT fk_task_id FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE,
+    CONSTRAINT fk_aggregation_job_id FOREIGN KEY(aggregation_job_id) REFERENCES aggregation_jobs(id) ON DELETE CASCADE
+) PARTITION BY LIST (task_id);
+ALTER SEQUENCE report_aggregations_id_seq OWNED BY report_aggregations.id;
+CREATE INDEX report_aggregations_id_index ON report_aggregations(id);
+CREATE INDEX report_aggregations_aggregation_job_id_index ON report_aggregations(aggregation_job_id);
+CREATE INDEX report_aggregations_client_report_id_index ON report_aggregations(client_report_id);
+ALTER TABLE report_aggregations ATTACH PARTITION report_aggregations_default DEFAULT;
+
+-- Tracks the partitions created by the partition manager.
+CREATE TABLE report_partitions(
+    id                         BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,  -- artificial ID, internal-only
+    task_id                    BIGINT NOT NULL,  -- ID of the task whose rows the partition stores; not a foreign key, so that the partitions of deleted tasks can be found & dropped
+    partitioned_table          TEXT NOT NULL,    -- the table the partition belongs to, either client_reports or report_aggregations
+    client_timestamp_interval  TSRANGE,          -- the range of client timestamps stored in the partition; NULL for the task's own partition, which is subpartitioned by client timestamp
+    partition_name             TEXT NOT NULL,    -- the name of the partition table
+
+    -- creation/update records
+    created_at TIMESTAMP NOT NULL,  -- when the row was created
+    updated_by TEXT NOT NULL,       -- the name of the transaction that last updated the row
+
+    CONSTRAINT report_partitions_unique_partition_name UNIQUE(partition_name)
+);
+CREATE INDEX report_partitions_task_id_index ON report_partitions(task_id);
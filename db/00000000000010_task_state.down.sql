@@ -0,0 +1,3 @@
+ALTER TABLE task_upload_counters DROP COLUMN task_not_accepting_reports;
+ALTER TABLE tasks DROP COLUMN state;
+DROP TYPE TASK_STATE;
@@ -0,0 +1,12 @@
+-- The lifecycle state of a task.
+CREATE TYPE TASK_STATE AS ENUM(
+    'ACTIVE',    -- reports are accepted, aggregated & collected
+    'PAUSED',    -- reports are not accepted, but those already uploaded are aggregated & collected
+    'DRAINING',  -- as for PAUSED; the task is finishing its remaining work before being archived
+    'ARCHIVED'   -- reports are not accepted, aggregated or collected; report data is deleted by GC
+);
+
+ALTER TABLE tasks ADD COLUMN state TASK_STATE NOT NULL DEFAULT 'ACTIVE';  -- the lifecycle state of the task
+
+ALTER TABLE task_upload_counters
+    ADD COLUMN task_not_accepting_reports BIGINT NOT NULL DEFAULT 0;  -- reports sent to the task while it was paused, draining or archived.
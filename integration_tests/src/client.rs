@@ -85,10 +85,12 @@ impl InteropClientEncoding for dummy::Vdaf {
 
 fn json_encode_vdaf(vdaf: &VdafInstance) -> Value {
     match vdaf {
-        VdafInstance::Prio3Count => json!({
+        VdafInstance::Prio3Count { .. } => json!({
             "type": "Prio3Count"
         }),
-        VdafInstance::Prio3Sum { max_measurement } => json!({
+        VdafInstance::Prio3Sum {
+            max_measurement, ..
+        } => json!({
             "type": "Prio3Sum",
             "max_measurement": format!("{max_measurement}"),
         }),
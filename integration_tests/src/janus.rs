@@ -6,18 +6,21 @@ use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use janus_aggregator::{
     binaries::{
         aggregation_job_creator::{
-            self, Config as AggregationJobCreatorConfig, Options as AggregationJobCreatorOptions,
+            self, AggregationJobCreatorConfig, Config as AggregationJobCreatorBinaryConfig,
+            Options as AggregationJobCreatorOptions,
         },
         aggregation_job_driver::{
-            self, Config as AggregationJobDriverConfig, Options as AggregationJobDriverOptions,
+            self, AggregationJobDriverConfig, Config as AggregationJobDriverBinaryConfig,
+            Options as AggregationJobDriverOptions,
         },
         aggregator::{self, Config as AggregatorConfig, Options as AggregatorOptions},
         collection_job_driver::{
-            self, Config as CollectionJobDriverConfig, Options as CollectionJobDriverOptions,
+            self, CollectionJobDriverConfig, Config as CollectionJobDriverBinaryConfig,
+            Options as CollectionJobDriverOptions,
         },
         key_rotator::{self, Config as KeyRotatorConfig, Options as KeyRotatorOptions},
     },
-    binary_utils::{BinaryContext, CommonBinaryOptions},
+    binary_utils::{BinaryContext, CommonBinaryOptions, readiness::ReadinessChecks},
     config::{
         CommonConfig, DbConfig, JobDriverConfig, TaskprovConfig, default_max_transaction_retries,
     },
@@ -39,6 +42,7 @@ use janus_messages::Role;
 use std::net::{Ipv4Addr, SocketAddr};
 #[cfg(feature = "testcontainer")]
 use testcontainers::{ContainerRequest, ImageExt, runners::AsyncRunner};
+use tokio::sync::watch;
 use trillium_tokio::Stopper;
 
 /// Represents a running Janus test instance in a container.
@@ -145,11 +149,13 @@ impl JanusInProcess {
                 connection_pool_max_size: None,
                 check_schema_version: false,
                 tls_trust_store_path: None,
+                replica: None,
             },
             logging_config,
             metrics_config: MetricsConfiguration {
                 exporter: None,
                 tokio: None,
+                task_labels: None,
             },
             health_check_listen_address: (Ipv4Addr::LOCALHOST, 0).into(),
             max_transaction_retries: default_max_transaction_retries(),
@@ -158,6 +164,7 @@ impl JanusInProcess {
         let aggregator_options = AggregatorOptions {
             common: common_binary_options.clone(),
             aggregator_api_auth_tokens: Vec::new(),
+            aggregator_api_report_count_noise_key: None,
             hpke_config_signing_key: None,
         };
         let aggregator_config = AggregatorConfig {
@@ -177,24 +184,28 @@ impl JanusInProcess {
             task_cache_capacity: None,
             log_forbidden_mutations: None,
             helper_aggregation_request_queue: None,
+            ohttp_gateway: None,
+            honor_untrusted_trace_context: false,
         };
         let aggregation_job_creator_options = AggregationJobCreatorOptions {
             common: common_binary_options.clone(),
         };
-        let aggregation_job_creator_config = AggregationJobCreatorConfig {
+        let aggregation_job_creator_config = AggregationJobCreatorBinaryConfig {
             common_config: common_config.clone(),
-            batch_aggregation_shard_count: 32,
-            tasks_update_frequency_s: 2,
-            aggregation_job_creation_interval_s: 1,
-            min_aggregation_job_size: 1,
-            max_aggregation_job_size: 100,
-            aggregation_job_creation_report_window: 5000,
-            late_report_grace_period_s: 3600,
+            aggregation_job_creator: AggregationJobCreatorConfig {
+                batch_aggregation_shard_count: 32,
+                tasks_update_frequency_s: 2,
+                aggregation_job_creation_interval_s: 1,
+                min_aggregation_job_size: 1,
+                max_aggregation_job_size: 100,
+                aggregation_job_creation_report_window: 5000,
+                late_report_grace_period_s: 3600,
+            },
         };
         let aggregation_job_driver_options = AggregationJobDriverOptions {
             common: common_binary_options.clone(),
         };
-        let aggregation_job_driver_config = AggregationJobDriverConfig {
+        let aggregation_job_driver_config = AggregationJobDriverBinaryConfig {
             common_config: common_config.clone(),
             job_driver_config: JobDriverConfig {
                 job_discovery_interval_s: 1,
@@ -208,16 +219,18 @@ impl JanusInProcess {
                 retry_max_interval_ms: 30_000,
                 retry_max_elapsed_time_ms: 300_000,
             },
-            taskprov_config: TaskprovConfig::default(),
-            batch_aggregation_shard_count: 32,
-            task_counter_shard_count: 32,
-            hpke_configs_refresh_interval: None,
-            default_async_poll_interval: 1000,
+            aggregation_job_driver: AggregationJobDriverConfig {
+                taskprov_config: TaskprovConfig::default(),
+                batch_aggregation_shard_count: 32,
+                task_counter_shard_count: 32,
+                hpke_configs_refresh_interval: None,
+                default_async_poll_interval: 1000,
+            },
         };
         let collection_job_driver_options = CollectionJobDriverOptions {
             common: common_binary_options.clone(),
         };
-        let collection_job_driver_config = CollectionJobDriverConfig {
+        let collection_job_driver_config = CollectionJobDriverBinaryConfig {
             common_config: common_config.clone(),
             job_driver_config: JobDriverConfig {
                 job_discovery_interval_s: 1,
@@ -231,11 +244,13 @@ impl JanusInProcess {
                 retry_max_interval_ms: 30_000,
                 retry_max_elapsed_time_ms: 300_000,
             },
-            batch_aggregation_shard_count: 32,
-            min_collection_job_retry_delay_s: 1,
-            max_collection_job_retry_delay_s: 1,
-            collection_job_retry_delay_exponential_factor: 1.0,
-            max_future_concurrency: 1000,
+            collection_job_driver: CollectionJobDriverConfig {
+                batch_aggregation_shard_count: 32,
+                min_collection_job_retry_delay_s: 1,
+                max_collection_job_retry_delay_s: 1,
+                collection_job_retry_delay_exponential_factor: 1.0,
+                max_future_concurrency: 1000,
+            },
         };
         let key_rotator_config = KeyRotatorConfig {
             common_config,
@@ -245,6 +260,16 @@ impl JanusInProcess {
             common: common_binary_options,
         };
 
+        // Configuration is never reloaded in tests.
+        let (_, aggregator_config_updates) = watch::channel(aggregator_config.clone());
+        let (_, aggregation_job_creator_config_updates) =
+            watch::channel(aggregation_job_creator_config.clone());
+        let (_, aggregation_job_driver_config_updates) =
+            watch::channel(aggregation_job_driver_config.clone());
+        let (_, collection_job_driver_config_updates) =
+            watch::channel(collection_job_driver_config.clone());
+        let (_, key_rotator_config_updates) = watch::channel(key_rotator_config.clone());
+
         // Spawn each component.
         let (aggregator_future, mut socket_address_receiver) =
             aggregator::make_callback_ephemeral_address(BinaryContext {
@@ -254,6 +279,8 @@ impl JanusInProcess {
                 datastore: ephemeral_datastore.datastore(clock).await,
                 meter: noop_meter(),
                 stopper: stopper.clone(),
+                readiness_checks: ReadinessChecks::default(),
+                config_updates: aggregator_config_updates,
             });
         tokio::spawn(async {
             aggregator_future.await.unwrap();
@@ -266,6 +293,8 @@ impl JanusInProcess {
                 datastore: ephemeral_datastore.datastore(clock).await,
                 meter: noop_meter(),
                 stopper: stopper.clone(),
+                readiness_checks: ReadinessChecks::default(),
+                config_updates: aggregation_job_creator_config_updates,
             });
             async {
                 future.await.unwrap();
@@ -279,6 +308,8 @@ impl JanusInProcess {
                 datastore: ephemeral_datastore.datastore(clock).await,
                 meter: noop_meter(),
                 stopper: stopper.clone(),
+                readiness_checks: ReadinessChecks::default(),
+                config_updates: aggregation_job_driver_config_updates,
             });
             async {
                 future.await.unwrap();
@@ -292,6 +323,8 @@ impl JanusInProcess {
                 datastore: ephemeral_datastore.datastore(clock).await,
                 meter: noop_meter(),
                 stopper: stopper.clone(),
+                readiness_checks: ReadinessChecks::default(),
+                config_updates: collection_job_driver_config_updates,
             });
             async {
                 future.await.unwrap();
@@ -305,6 +338,8 @@ impl JanusInProcess {
                 datastore,
                 meter: noop_meter(),
                 stopper: stopper.clone(),
+                readiness_checks: ReadinessChecks::default(),
+                config_updates: key_rotator_config_updates,
             });
             async {
                 future.await.unwrap();
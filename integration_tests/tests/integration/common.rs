@@ -357,7 +357,7 @@ pub async fn submit_measurements_and_verify_aggregate(
     let total_measurements: usize = task_parameters.min_batch_size.try_into().unwrap();
 
     match &task_parameters.vdaf {
-        VdafInstance::Prio3Count => {
+        VdafInstance::Prio3Count { .. } => {
             let vdaf = Prio3::new_count(2).unwrap();
 
             let num_true_measurements = total_measurements / 2;
@@ -391,7 +391,9 @@ pub async fn submit_measurements_and_verify_aggregate(
             )
             .await;
         }
-        VdafInstance::Prio3Sum { max_measurement } => {
+        VdafInstance::Prio3Sum {
+            max_measurement, ..
+        } => {
             let max_measurement = *max_measurement;
             let vdaf = Prio3::new_sum(2, max_measurement).unwrap();
 
@@ -3,7 +3,10 @@ use crate::{
     initialize_rustls,
 };
 use janus_aggregator_core::task::{AggregationMode, BatchMode, test_util::TaskBuilder};
-use janus_core::{test_util::install_test_trace_subscriber, vdaf::VdafInstance};
+use janus_core::{
+    test_util::install_test_trace_subscriber,
+    vdaf::{VdafInstance, vdaf_dp_strategies},
+};
 #[cfg(feature = "testcontainer")]
 use janus_integration_tests::janus::JanusContainer;
 use janus_integration_tests::{
@@ -30,7 +33,9 @@ async fn daphne_janus() {
         TaskBuilder::new(
             BatchMode::TimeInterval,
             AggregationMode::Synchronous,
-            VdafInstance::Prio3Count,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
         ),
         TestContext::VirtualNetwork,
         Duration::from_millis(500),
@@ -76,7 +81,9 @@ async fn janus_daphne() {
         TaskBuilder::new(
             BatchMode::TimeInterval,
             AggregationMode::Synchronous,
-            VdafInstance::Prio3Count,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
         ),
         TestContext::VirtualNetwork,
         Duration::from_millis(500),
@@ -122,7 +129,9 @@ async fn janus_in_process_daphne() {
         TaskBuilder::new(
             BatchMode::TimeInterval,
             AggregationMode::Synchronous,
-            VdafInstance::Prio3Count,
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
         ),
         TestContext::VirtualNetwork,
         Duration::from_millis(500),
@@ -49,7 +49,13 @@ async fn janus_divviup_ts_count() {
     install_test_trace_subscriber();
     initialize_rustls();
 
-    run_divviup_ts_integration_test("janus_divviup_ts_count", VdafInstance::Prio3Count).await;
+    run_divviup_ts_integration_test(
+        "janus_divviup_ts_count",
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
+    )
+    .await;
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -62,6 +68,7 @@ async fn janus_divviup_ts_sum() {
         "janus_divviup_ts_sum",
         VdafInstance::Prio3Sum {
             max_measurement: 255,
+            dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
         },
     )
     .await;
@@ -19,6 +19,7 @@ use janus_client::OhttpConfig;
 use janus_collector::PrivateCollectorCredential;
 use janus_core::{
     auth_tokens::AuthenticationToken,
+    dp::DpStrategyConfig,
     hpke::HpkeKeypair,
     test_util::{
         install_test_trace_subscriber,
@@ -30,9 +31,6 @@ use janus_core::{
 use janus_integration_tests::{TaskParameters, client::ClientBackend};
 use janus_messages::{Duration as JanusDuration, TaskId};
 use prio::{
-    dp::{
-        DifferentialPrivacyStrategy, PureDpBudget, Rational, distributions::PureDpDiscreteLaplace,
-    },
     field::{Field128, FieldElementWithInteger},
     vdaf::prio3::Prio3,
 };
@@ -413,9 +411,11 @@ impl InClusterJanusPair {
             leader_aggregator_id,
             helper_aggregator_id,
             vdaf: match task.vdaf().to_owned() {
-                VdafInstance::Prio3Count => Vdaf::Count,
+                VdafInstance::Prio3Count { .. } => Vdaf::Count,
                 VdafInstance::Prio3Sum {
                     max_measurement: _max_measurement,
+                    dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
+                    ..
                 } => Vdaf::Sum {
                     // TODO(#3436): once divviup_client is updated for DAP-13, plumb max_measurement through
                     bits: 64,
@@ -513,8 +513,13 @@ async fn in_cluster_count() {
     initialize_rustls();
 
     // Start port forwards and set up task.
-    let janus_pair =
-        InClusterJanusPair::new(VdafInstance::Prio3Count, BatchMode::TimeInterval).await;
+    let janus_pair = InClusterJanusPair::new(
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
+        BatchMode::TimeInterval,
+    )
+    .await;
 
     // Run the behavioral test.
     submit_measurements_and_verify_aggregate(
@@ -533,8 +538,13 @@ async fn in_cluster_count_ohttp() {
     initialize_rustls();
 
     // Start port forwards and set up task.
-    let mut janus_pair =
-        InClusterJanusPair::new(VdafInstance::Prio3Count, BatchMode::TimeInterval).await;
+    let mut janus_pair = InClusterJanusPair::new(
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
+        BatchMode::TimeInterval,
+    )
+    .await;
 
     // Set up the client to use OHTTP. The keys and relay are assumed to be deployed adjacent to the
     // leader.
@@ -572,6 +582,7 @@ async fn in_cluster_sum() {
     let janus_pair = InClusterJanusPair::new(
         VdafInstance::Prio3Sum {
             max_measurement: 65535,
+            dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
         },
         BatchMode::TimeInterval,
     )
@@ -620,7 +631,9 @@ async fn in_cluster_leader_selected() {
 
     // Start port forwards and set up task.
     let janus_pair = InClusterJanusPair::new(
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
         BatchMode::LeaderSelected {
             batch_time_window_size: None,
         },
@@ -644,7 +657,9 @@ async fn in_cluster_time_bucketed_leader_selected() {
 
     // Start port forwards and set up task.
     let janus_pair = InClusterJanusPair::new(
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
         BatchMode::LeaderSelected {
             batch_time_window_size: Some(JanusDuration::from_hours(8).unwrap()),
         },
@@ -723,8 +738,13 @@ mod rate_limits {
         initialize_rustls();
         let test_config = TestConfig::load();
 
-        let janus_pair =
-            InClusterJanusPair::new(VdafInstance::Prio3Count, BatchMode::TimeInterval).await;
+        let janus_pair = InClusterJanusPair::new(
+            VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
+            BatchMode::TimeInterval,
+        )
+        .await;
 
         let (leader_url, helper_url) = janus_pair
             .task_parameters
@@ -934,13 +954,12 @@ async fn in_cluster_histogram_dp_noise() {
     initialize_rustls();
 
     // Start port forwards and set up task.
-    let epsilon = Rational::from_unsigned(1u128, 10u128).unwrap();
     let janus_pair = InClusterJanusPair::new(
         VdafInstance::Prio3Histogram {
             length: HISTOGRAM_LENGTH,
             chunk_length: CHUNK_LENGTH,
             dp_strategy: vdaf_dp_strategies::Prio3Histogram::PureDpDiscreteLaplace(
-                PureDpDiscreteLaplace::from_budget(PureDpBudget::new(epsilon).unwrap()),
+                DpStrategyConfig::from_epsilon(1, 10).unwrap(),
             ),
         },
         BatchMode::LeaderSelected {
@@ -1006,14 +1025,13 @@ async fn in_cluster_sumvec_dp_noise() {
     initialize_rustls();
 
     // Start port forwards and set up task.
-    let epsilon = Rational::from_unsigned(1u128, 10u128).unwrap();
     let janus_pair = InClusterJanusPair::new(
         VdafInstance::Prio3SumVec {
             bits: BITS,
             length: VECTOR_LENGTH,
             chunk_length: CHUNK_LENGTH,
             dp_strategy: vdaf_dp_strategies::Prio3SumVec::PureDpDiscreteLaplace(
-                PureDpDiscreteLaplace::from_budget(PureDpBudget::new(epsilon).unwrap()),
+                DpStrategyConfig::from_epsilon(1, 10).unwrap(),
             ),
         },
         BatchMode::LeaderSelected {
@@ -7,6 +7,7 @@ use crate::{
 };
 use janus_aggregator_core::task::{AggregationMode, BatchMode, test_util::TaskBuilder};
 use janus_core::{
+    dp::DpStrategyConfig,
     test_util::install_test_trace_subscriber,
     vdaf::{VdafInstance, vdaf_dp_strategies},
 };
@@ -17,9 +18,6 @@ use janus_integration_tests::{TaskParameters, client::ClientBackend, janus::Janu
 use janus_interop_binaries::test_util::generate_network_name;
 use janus_messages::Role;
 use prio::{
-    dp::{
-        DifferentialPrivacyStrategy, PureDpBudget, Rational, distributions::PureDpDiscreteLaplace,
-    },
     field::{Field128, FieldElementWithInteger},
     vdaf::prio3::Prio3,
 };
@@ -162,7 +160,9 @@ async fn janus_janus_sync_count() {
         "janus_janus_sync_count",
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .await
 }
@@ -177,7 +177,9 @@ async fn janus_janus_async_count() {
         "janus_janus_async_count",
         BatchMode::TimeInterval,
         AggregationMode::Asynchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .await
 }
@@ -190,7 +192,9 @@ async fn janus_in_process_sync_count() {
         "janus_in_process_sync_count",
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .await
 }
@@ -203,7 +207,9 @@ async fn janus_in_process_async_count() {
         "janus_in_process_async_count",
         BatchMode::TimeInterval,
         AggregationMode::Asynchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .await
 }
@@ -219,6 +225,7 @@ async fn janus_janus_sync_sum_16() {
         AggregationMode::Synchronous,
         VdafInstance::Prio3Sum {
             max_measurement: 65535,
+            dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
         },
     )
     .await
@@ -235,6 +242,7 @@ async fn janus_janus_async_sum_16() {
         AggregationMode::Asynchronous,
         VdafInstance::Prio3Sum {
             max_measurement: 65535,
+            dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
         },
     )
     .await
@@ -250,6 +258,7 @@ async fn janus_in_process_sync_sum_16() {
         AggregationMode::Synchronous,
         VdafInstance::Prio3Sum {
             max_measurement: 4096,
+            dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
         },
     )
     .await
@@ -265,6 +274,7 @@ async fn janus_in_process_async_sum_16() {
         AggregationMode::Synchronous,
         VdafInstance::Prio3Sum {
             max_measurement: 4096,
+            dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
         },
     )
     .await
@@ -351,7 +361,9 @@ async fn janus_janus_leader_selected_sync() {
             batch_time_window_size: None,
         },
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .await
 }
@@ -367,7 +379,9 @@ async fn janus_janus_leader_selected_async() {
             batch_time_window_size: None,
         },
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .await
 }
@@ -382,7 +396,9 @@ async fn janus_in_process_leader_selected_sync() {
             batch_time_window_size: None,
         },
         AggregationMode::Synchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .await
 }
@@ -397,7 +413,9 @@ async fn janus_in_process_leader_selected_async() {
             batch_time_window_size: None,
         },
         AggregationMode::Asynchronous,
-        VdafInstance::Prio3Count,
+        VdafInstance::Prio3Count {
+            dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+        },
     )
     .await
 }
@@ -514,7 +532,6 @@ async fn janus_in_process_histogram_dp_noise() {
     install_test_trace_subscriber();
     initialize_rustls();
 
-    let epsilon = Rational::from_unsigned(1u128, 10u128).unwrap();
     let janus_pair = JanusInProcessPair::new(TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
@@ -522,7 +539,7 @@ async fn janus_in_process_histogram_dp_noise() {
             length: HISTOGRAM_LENGTH,
             chunk_length: CHUNK_LENGTH,
             dp_strategy: vdaf_dp_strategies::Prio3Histogram::PureDpDiscreteLaplace(
-                PureDpDiscreteLaplace::from_budget(PureDpBudget::new(epsilon).unwrap()),
+                DpStrategyConfig::from_epsilon(1, 10).unwrap(),
             ),
         },
     ))
@@ -584,7 +601,6 @@ async fn janus_in_process_sumvec_dp_noise() {
     install_test_trace_subscriber();
     initialize_rustls();
 
-    let epsilon = Rational::from_unsigned(1u128, 10u128).unwrap();
     let janus_pair = JanusInProcessPair::new(TaskBuilder::new(
         BatchMode::TimeInterval,
         AggregationMode::Synchronous,
@@ -593,7 +609,7 @@ async fn janus_in_process_sumvec_dp_noise() {
             length: VECTOR_LENGTH,
             chunk_length: CHUNK_LENGTH,
             dp_strategy: vdaf_dp_strategies::Prio3SumVec::PureDpDiscreteLaplace(
-                PureDpDiscreteLaplace::from_budget(PureDpBudget::new(epsilon).unwrap()),
+                DpStrategyConfig::from_epsilon(1, 10).unwrap(),
             ),
         },
     ))
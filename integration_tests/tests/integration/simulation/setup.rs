@@ -93,6 +93,9 @@ impl SimulationAggregator {
                 task_cache_ttl: TASK_AGGREGATOR_CACHE_DEFAULT_TTL,
                 task_cache_capacity: TASK_AGGREGATOR_CACHE_DEFAULT_CAPACITY,
                 log_forbidden_mutations: None,
+                ohttp_gateway_keys_refresh_interval: None,
+                task_metric_labels: Default::default(),
+                honor_untrusted_trace_context: false,
             },
         )
         .await
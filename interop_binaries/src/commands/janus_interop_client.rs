@@ -116,13 +116,15 @@ async fn handle_upload(
 ) -> anyhow::Result<()> {
     let vdaf_instance = request.vdaf.clone().into();
     match vdaf_instance {
-        VdafInstance::Prio3Count => {
+        VdafInstance::Prio3Count { .. } => {
             let measurement = parse_primitive_measurement::<u64>(request.measurement.clone())?;
             let vdaf = Prio3::new_count(2).context("failed to construct Prio3Count VDAF")?;
             handle_upload_generic(http_client, vdaf, request, measurement != 0).await?;
         }
 
-        VdafInstance::Prio3Sum { max_measurement } => {
+        VdafInstance::Prio3Sum {
+            max_measurement, ..
+        } => {
             let measurement = parse_primitive_measurement::<u64>(request.measurement.clone())?;
             let vdaf =
                 Prio3::new_sum(2, max_measurement).context("failed to construct Prio3Sum VDAF")?;
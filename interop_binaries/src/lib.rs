@@ -140,9 +140,11 @@ pub enum VdafObject {
 impl From<VdafInstance> for VdafObject {
     fn from(vdaf: VdafInstance) -> Self {
         match vdaf {
-            VdafInstance::Prio3Count => VdafObject::Prio3Count,
+            VdafInstance::Prio3Count { .. } => VdafObject::Prio3Count,
 
-            VdafInstance::Prio3Sum { max_measurement } => VdafObject::Prio3Sum {
+            VdafInstance::Prio3Sum {
+                max_measurement, ..
+            } => VdafObject::Prio3Sum {
                 max_measurement: NumberAsString(max_measurement),
             },
 
@@ -198,10 +200,13 @@ impl From<VdafInstance> for VdafObject {
 impl From<VdafObject> for VdafInstance {
     fn from(vdaf: VdafObject) -> Self {
         match vdaf {
-            VdafObject::Prio3Count => VdafInstance::Prio3Count,
+            VdafObject::Prio3Count => VdafInstance::Prio3Count {
+                dp_strategy: vdaf_dp_strategies::Prio3Count::NoDifferentialPrivacy,
+            },
 
             VdafObject::Prio3Sum { max_measurement } => VdafInstance::Prio3Sum {
                 max_measurement: max_measurement.0,
+                dp_strategy: vdaf_dp_strategies::Prio3Sum::NoDifferentialPrivacy,
             },
 
             VdafObject::Prio3SumVec {
@@ -10,12 +10,14 @@ use fixed::types::extra::{U15, U31};
 #[cfg(feature = "fpvec_bounded_l2")]
 use fixed::{FixedI16, FixedI32};
 use janus_collector::{
-    AuthenticationToken, Collection, CollectionJob, Collector, PollResult,
-    PrivateCollectorCredential, default_http_client,
+    AuthenticationToken, Collection, CollectionJob, Collector, DpEstimate, DpPostProcessor,
+    NoisyAggregateResult, PollResult, PrivateCollectorCredential, default_http_client,
 };
 use janus_core::{
+    dp::{DpStrategyConfig, EpsilonStrategy},
     hpke::{HpkeKeypair, HpkePrivateKey},
     retries::ExponentialWithTotalDelayBuilder,
+    vdaf::{VdafInstance, vdaf_dp_strategies},
 };
 use janus_messages::{
     CollectionJobId, Duration, HpkeConfig, Interval, PartialBatchSelector, Query, TaskId, Time,
@@ -84,6 +86,47 @@ enum VdafType {
     FixedPoint64BitBoundedL2VecSum,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+#[clap(rename_all = "lower")]
+enum DpStrategyType {
+    /// PureDpDiscreteLaplace
+    PureDpDiscreteLaplace,
+    /// ZCdpDiscreteGaussian
+    ZCdpDiscreteGaussian,
+}
+
+/// The epsilon parameter of a differential privacy budget, as a fraction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Epsilon {
+    numerator: u128,
+    denominator: u128,
+}
+
+impl Epsilon {
+    fn to_dp_strategy<S: EpsilonStrategy>(self) -> Result<DpStrategyConfig<S>, Error> {
+        DpStrategyConfig::from_epsilon(self.numerator, self.denominator)
+            .map_err(|err| Error::Anyhow(err.into()))
+    }
+}
+
+fn epsilon_parser(s: &str) -> Result<Epsilon, String> {
+    let (numerator, denominator) = s.split_once('/').unwrap_or((s, "1"));
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<u128>()
+            .map_err(|err| format!("invalid epsilon {s:?}: {err}"))
+    };
+    let (numerator, denominator) = (parse(numerator)?, parse(denominator)?);
+    if numerator == 0 || denominator == 0 {
+        return Err(format!("invalid epsilon {s:?}: must be positive"));
+    }
+    Ok(Epsilon {
+        numerator,
+        denominator,
+    })
+}
+
 #[derive(Clone)]
 struct HpkeConfigValueParser {
     inner: NonEmptyStringValueParser,
@@ -197,6 +240,29 @@ struct QueryOptions {
     batch_interval_duration: Option<u64>,
 }
 
+#[derive(Debug, Args, PartialEq, Eq)]
+struct DpOptions {
+    /// Differential privacy strategy with which the aggregators noise aggregate shares
+    ///
+    /// If present, the aggregation result is post-processed into the range of possible values, and
+    /// printed along with the standard deviation of the noise and 95% confidence intervals.
+    #[clap(
+        long,
+        value_enum,
+        requires = "dp_epsilon",
+        help_heading = "Differential Privacy"
+    )]
+    dp_strategy: Option<DpStrategyType>,
+    /// Epsilon parameter of the task's differential privacy budget, as a fraction such as "1/2"
+    #[clap(
+        long,
+        value_parser = epsilon_parser,
+        requires = "dp_strategy",
+        help_heading = "Differential Privacy"
+    )]
+    dp_epsilon: Option<Epsilon>,
+}
+
 #[derive(Debug, Args, PartialEq, Eq)]
 #[group(required = true, multiple = true)]
 struct HpkeConfigOptions {
@@ -324,6 +390,9 @@ struct Options {
     #[clap(long, help_heading = "VDAF Algorithm and Parameters")]
     max_measurement: Option<u64>,
 
+    #[clap(flatten)]
+    dp: DpOptions,
+
     #[clap(flatten)]
     query: QueryOptions,
 }
@@ -389,6 +458,64 @@ impl Options {
         }
     }
 
+    /// Construct a post-processor for aggregation results from the VDAF and differential privacy
+    /// options, if a differential privacy strategy was given.
+    fn dp_post_processor(&self) -> Result<Option<DpPostProcessor>, Error> {
+        let (Some(strategy), Some(epsilon)) = (self.dp.dp_strategy, self.dp.dp_epsilon) else {
+            return Ok(None);
+        };
+
+        macro_rules! dp_strategy {
+            ($Strategies:ident) => {
+                match strategy {
+                    DpStrategyType::PureDpDiscreteLaplace => {
+                        vdaf_dp_strategies::$Strategies::PureDpDiscreteLaplace(
+                            epsilon.to_dp_strategy()?,
+                        )
+                    }
+                    DpStrategyType::ZCdpDiscreteGaussian => {
+                        vdaf_dp_strategies::$Strategies::ZCdpDiscreteGaussian(
+                            epsilon.to_dp_strategy()?,
+                        )
+                    }
+                }
+            };
+        }
+
+        // Chunk lengths do not affect post-processing.
+        let vdaf_instance = match (self.vdaf, self.length, self.bits, self.max_measurement) {
+            (VdafType::Count, None, None, None) => VdafInstance::Prio3Count {
+                dp_strategy: dp_strategy!(Prio3Count),
+            },
+            (VdafType::Sum, None, None, Some(max_measurement)) => VdafInstance::Prio3Sum {
+                max_measurement,
+                dp_strategy: dp_strategy!(Prio3Sum),
+            },
+            (VdafType::SumVec, Some(length), Some(bits), None) => VdafInstance::Prio3SumVec {
+                bits,
+                length,
+                chunk_length: 1,
+                dp_strategy: dp_strategy!(Prio3SumVec),
+            },
+            (VdafType::Histogram, Some(length), None, None) => VdafInstance::Prio3Histogram {
+                length,
+                chunk_length: 1,
+                dp_strategy: dp_strategy!(Prio3Histogram),
+            },
+            _ => {
+                return Err(clap::Error::raw(
+                    ErrorKind::ArgumentConflict,
+                    format!(
+                        "differential privacy post-processing is not supported for {}",
+                        self.vdaf.to_possible_value().unwrap().get_help().unwrap(),
+                    ),
+                )
+                .into());
+            }
+        };
+        Ok(DpPostProcessor::new(&vdaf_instance))
+    }
+
     /// Extract all collector-related credentials from the given options.
     fn credential(&self) -> Result<(AuthenticationToken, HpkeKeypair), Error> {
         let collector_credential = self.collector_credential()?;
@@ -536,15 +663,25 @@ macro_rules! options_dispatch {
 async fn run(options: Options) -> Result<(), Error> {
     let http_client = default_http_client().map_err(|err| Error::Anyhow(err.into()))?;
     options_dispatch!(options, (query, vdaf) => {
+        let dp_post_processor = options.dp_post_processor()?;
         match options.subcommand {
             Some(Subcommands::NewJob { collection_job_id }) => {
                 let collection_job_id = collection_job_id.unwrap_or_else(random);
                 run_new_job(options, vdaf, http_client, query, &(), collection_job_id).await
             }
             Some(Subcommands::PollJob { collection_job_id }) => {
-                run_poll_job(options, vdaf, http_client, query, &(), collection_job_id).await
+                run_poll_job(
+                    options,
+                    vdaf,
+                    http_client,
+                    query,
+                    &(),
+                    collection_job_id,
+                    dp_post_processor,
+                )
+                .await
             }
-            _ => run_collection(options, vdaf, http_client, query, &()).await,
+            _ => run_collection(options, vdaf, http_client, query, &(), dp_post_processor).await,
         }
     })
 }
@@ -555,15 +692,16 @@ async fn run_collection<V: vdaf::Collector, B: BatchModeExt>(
     http_client: reqwest::Client,
     query: Query<B>,
     agg_param: &V::AggregationParam,
+    dp_post_processor: Option<DpPostProcessor>,
 ) -> Result<(), Error>
 where
-    V::AggregateResult: Debug,
+    V::AggregateResult: AggregateResultExt,
 {
     let collection = new_collector(options, vdaf, http_client)?
         .collect(query, agg_param)
         .await
         .map_err(|err| Error::Anyhow(err.into()))?;
-    print_collection::<V, B>(collection)?;
+    print_collection::<V, B>(collection, dp_post_processor.as_ref())?;
     Ok(())
 }
 
@@ -593,9 +731,10 @@ async fn run_poll_job<V: vdaf::Collector, B: BatchModeExt>(
     query: Query<B>,
     agg_param: &V::AggregationParam,
     collection_job_id: CollectionJobId,
+    dp_post_processor: Option<DpPostProcessor>,
 ) -> Result<(), Error>
 where
-    V::AggregateResult: Debug,
+    V::AggregateResult: AggregateResultExt,
 {
     let collection_job = CollectionJob::new(collection_job_id, query, agg_param.clone());
     let poll_result = new_collector(options, vdaf, http_client)?
@@ -605,7 +744,7 @@ where
     match poll_result {
         PollResult::CollectionResult(collection) => {
             println!("State: Ready");
-            print_collection::<V, B>(collection)?;
+            print_collection::<V, B>(collection, dp_post_processor.as_ref())?;
             Ok(())
         }
         PollResult::NotReady(retry_after) => {
@@ -645,7 +784,11 @@ fn new_collector<V: vdaf::Collector>(
 
 fn print_collection<V: vdaf::Collector, B: BatchModeExt>(
     collection: Collection<<V as Vdaf>::AggregateResult, B>,
-) -> Result<(), Error> {
+    dp_post_processor: Option<&DpPostProcessor>,
+) -> Result<(), Error>
+where
+    V::AggregateResult: AggregateResultExt,
+{
     if !B::IS_PARTIAL_BATCH_SELECTOR_TRIVIAL {
         println!(
             "Batch: {}",
@@ -663,6 +806,48 @@ fn print_collection<V: vdaf::Collector, B: BatchModeExt>(
         duration.to_std().map_err(|err| Error::Anyhow(err.into()))?
     );
     println!("Aggregation result: {:?}", collection.aggregate_result());
+
+    let Some(dp_post_processor) = dp_post_processor else {
+        return Ok(());
+    };
+    let Some(estimates) = collection
+        .aggregate_result()
+        .dp_estimates(dp_post_processor, collection.report_count())
+    else {
+        return Ok(());
+    };
+    let format = |values: Vec<String>| {
+        if V::AggregateResult::IS_VECTOR {
+            format!("[{}]", values.join(", "))
+        } else {
+            values.join(", ")
+        }
+    };
+    println!(
+        "Post-processed result: {}",
+        format(
+            estimates
+                .iter()
+                .map(|estimate| format!("{:.2}", estimate.value()))
+                .collect()
+        )
+    );
+    println!(
+        "Noise standard deviation: {:.2}",
+        dp_post_processor.std_dev()
+    );
+    println!(
+        "95% confidence intervals: {}",
+        format(
+            estimates
+                .iter()
+                .map(|estimate| {
+                    let (lower, upper) = estimate.confidence_interval();
+                    format!("({lower:.2}, {upper:.2})")
+                })
+                .collect()
+        )
+    );
     Ok(())
 }
 
@@ -705,11 +890,52 @@ impl BatchModeExt for LeaderSelected {
     }
 }
 
+trait AggregateResultExt: Debug {
+    const IS_VECTOR: bool;
+
+    /// Post-processes this aggregate result for differential privacy, if its VDAF supports it.
+    fn dp_estimates(
+        &self,
+        dp_post_processor: &DpPostProcessor,
+        report_count: u64,
+    ) -> Option<Vec<DpEstimate>>;
+}
+
+macro_rules! impl_aggregate_result_ext {
+    ($AggregateResult:ty, $is_vector:expr) => {
+        impl AggregateResultExt for $AggregateResult {
+            const IS_VECTOR: bool = $is_vector;
+
+            fn dp_estimates(
+                &self,
+                dp_post_processor: &DpPostProcessor,
+                report_count: u64,
+            ) -> Option<Vec<DpEstimate>> {
+                Some(dp_post_processor.process(&self.entries(), report_count))
+            }
+        }
+    };
+}
+
+impl_aggregate_result_ext!(u64, false);
+impl_aggregate_result_ext!(u128, false);
+impl_aggregate_result_ext!(Vec<u64>, true);
+impl_aggregate_result_ext!(Vec<u128>, true);
+
+#[cfg(feature = "fpvec_bounded_l2")]
+impl AggregateResultExt for Vec<f64> {
+    const IS_VECTOR: bool = true;
+
+    fn dp_estimates(&self, _: &DpPostProcessor, _: u64) -> Option<Vec<DpEstimate>> {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        AuthenticationOptions, AuthenticationToken, Error, HpkeConfigOptions, Options,
-        QueryOptions, Subcommands, VdafType, run,
+        AuthenticationOptions, AuthenticationToken, DpOptions, DpStrategyType, Epsilon, Error,
+        HpkeConfigOptions, Options, QueryOptions, Subcommands, VdafType, run,
     };
     use assert_matches::assert_matches;
     use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
@@ -776,6 +1002,10 @@ mod tests {
             length: None,
             bits: None,
             max_measurement: None,
+            dp: DpOptions {
+                dp_strategy: None,
+                dp_epsilon: None,
+            },
             query: QueryOptions {
                 batch_interval_start: Some(1_000_000),
                 batch_interval_duration: Some(1_000),
@@ -1010,6 +1240,10 @@ mod tests {
             length: None,
             bits: None,
             max_measurement: None,
+            dp: DpOptions {
+                dp_strategy: None,
+                dp_epsilon: None,
+            },
             query: QueryOptions {
                 batch_interval_start: None,
                 batch_interval_duration: None,
@@ -1062,6 +1296,109 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dp_arguments() {
+        let task_id: TaskId = random();
+        let task_id_encoded = URL_SAFE_NO_PAD.encode(task_id.get_encoded().unwrap());
+
+        let hpke_keypair = HpkeKeypair::test();
+        let encoded_hpke_config =
+            URL_SAFE_NO_PAD.encode(hpke_keypair.config().get_encoded().unwrap());
+        let encoded_private_key = URL_SAFE_NO_PAD.encode(hpke_keypair.private_key().as_ref());
+        let auth_token = AuthenticationToken::DapAuth(random());
+
+        let base_arguments = Vec::from([
+            "collect".to_string(),
+            format!("--task-id={task_id_encoded}"),
+            "--leader".to_string(),
+            "https://example.com/dap/".to_string(),
+            format!("--dap-auth-token={}", auth_token.as_str()),
+            format!("--hpke-config={encoded_hpke_config}"),
+            format!("--hpke-private-key={encoded_private_key}"),
+            "--vdaf=histogram".to_string(),
+            "--length=4".to_string(),
+        ]);
+
+        // Without a strategy, results are not post-processed.
+        let options = Options::try_parse_from(base_arguments.clone()).unwrap();
+        assert_matches!(options.dp_post_processor(), Ok(None));
+
+        let mut good_arguments = base_arguments.clone();
+        good_arguments.extend([
+            "--dp-strategy=zcdpdiscretegaussian".to_string(),
+            "--dp-epsilon=1/2".to_string(),
+        ]);
+        let options = Options::try_parse_from(good_arguments).unwrap();
+        assert_eq!(
+            options.dp,
+            DpOptions {
+                dp_strategy: Some(DpStrategyType::ZCdpDiscreteGaussian),
+                dp_epsilon: Some(Epsilon {
+                    numerator: 1,
+                    denominator: 2,
+                }),
+            }
+        );
+        assert_matches!(options.dp_post_processor(), Ok(Some(_)));
+
+        let mut good_arguments = base_arguments.clone();
+        good_arguments.extend([
+            "--dp-strategy=puredpdiscretelaplace".to_string(),
+            "--dp-epsilon=3".to_string(),
+        ]);
+        let options = Options::try_parse_from(good_arguments).unwrap();
+        assert_eq!(
+            options.dp.dp_epsilon,
+            Some(Epsilon {
+                numerator: 3,
+                denominator: 1,
+            })
+        );
+        assert_matches!(options.dp_post_processor(), Ok(Some(_)));
+
+        let mut bad_arguments = base_arguments.clone();
+        bad_arguments.push("--dp-strategy=puredpdiscretelaplace".to_string());
+        assert_eq!(
+            Options::try_parse_from(bad_arguments).unwrap_err().kind(),
+            ErrorKind::MissingRequiredArgument
+        );
+
+        let mut bad_arguments = base_arguments.clone();
+        bad_arguments.push("--dp-epsilon=1".to_string());
+        assert_eq!(
+            Options::try_parse_from(bad_arguments).unwrap_err().kind(),
+            ErrorKind::MissingRequiredArgument
+        );
+
+        for epsilon in ["0", "1/0", "-1", "one half"] {
+            let mut bad_arguments = base_arguments.clone();
+            bad_arguments.extend([
+                "--dp-strategy=puredpdiscretelaplace".to_string(),
+                format!("--dp-epsilon={epsilon}"),
+            ]);
+            assert_eq!(
+                Options::try_parse_from(bad_arguments).unwrap_err().kind(),
+                ErrorKind::ValueValidation
+            );
+        }
+
+        #[cfg(feature = "fpvec_bounded_l2")]
+        {
+            let mut bad_arguments = Vec::from(&base_arguments[..7]);
+            bad_arguments.extend([
+                "--vdaf=fixedpoint16bitboundedl2vecsum".to_string(),
+                "--length=4".to_string(),
+                "--dp-strategy=zcdpdiscretegaussian".to_string(),
+                "--dp-epsilon=1".to_string(),
+            ]);
+            let options = Options::try_parse_from(bad_arguments).unwrap();
+            assert_matches!(
+                options.dp_post_processor(),
+                Err(Error::Clap(err)) => assert_eq!(err.kind(), ErrorKind::ArgumentConflict)
+            );
+        }
+    }
+
     #[test]
     fn auth_arguments() {
         let task_id: TaskId = random();
@@ -1351,6 +1688,10 @@ mod tests {
             length: None,
             bits: None,
             max_measurement: None,
+            dp: DpOptions {
+                dp_strategy: None,
+                dp_epsilon: None,
+            },
             query: QueryOptions {
                 batch_interval_start: Some(1_000_000),
                 batch_interval_duration: Some(1_000),
@@ -1435,6 +1776,10 @@ mod tests {
             length: None,
             bits: None,
             max_measurement: None,
+            dp: DpOptions {
+                dp_strategy: None,
+                dp_epsilon: None,
+            },
             query: QueryOptions {
                 batch_interval_start: Some(1_000_000),
                 batch_interval_duration: Some(1_000),